@@ -0,0 +1,6 @@
+"""Allow running the CLI as ``python -m chi_llm``."""
+
+from .cli import main
+
+if __name__ == "__main__":  # pragma: no cover
+    main()
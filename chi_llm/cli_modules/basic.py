@@ -17,6 +17,12 @@ def cmd_generate(args):
             prompt = f.read()
     else:
         prompt = args.prompt
+    if getattr(args, "stream", False):
+        # Print chunks as they arrive so callers (e.g. chi-tui) can show progress
+        for chunk in llm.generate_stream(prompt):
+            print(chunk, end="", flush=True)
+        print()
+        return
     response = llm.generate(prompt)
     print(response)
 
@@ -105,6 +111,9 @@ def register(subparsers: _SubParsersAction):
     gen_parser.add_argument(
         "-m", "--max-tokens", type=int, default=4096, help="Max tokens"
     )
+    gen_parser.add_argument(
+        "--stream", action="store_true", help="Print tokens as they are generated"
+    )
     gen_parser.set_defaults(func=cmd_generate)
 
     # chat
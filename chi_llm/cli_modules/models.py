@@ -2,12 +2,15 @@
 Model management and setup commands.
 """
 
+import os
+import sys
 from argparse import _SubParsersAction
 from importlib import resources
+from pathlib import Path
 from typing import Any, Dict, List, Tuple
 
 try:
-    from ..models import ModelManager, MODELS
+    from ..models import ModelManager, MODELS, add_custom_models, remove_custom_model
     from ..model_utils import format_model_info
     from ..setup import SetupWizard
 
@@ -170,6 +173,7 @@ def cmd_models(args):
             current = manager.get_current_model().id
             out = []
             for m in MODELS.values():
+                path = manager.get_model_path(m.id)
                 out.append(
                     {
                         "id": m.id,
@@ -181,6 +185,8 @@ def cmd_models(args):
                         "tags": m.tags,
                         "downloaded": manager.is_downloaded(m.id),
                         "current": m.id == current,
+                        "path": str(path) if path else None,
+                        "custom": bool(getattr(m, "path", None)),
                     }
                 )
             _print_json(out)
@@ -330,6 +336,33 @@ def cmd_models(args):
             )
         else:
             print(format_model_info(model, is_downloaded, is_current))
+    elif args.models_command in ("add", "remove"):
+        target = getattr(args, "yaml", None) or os.environ.get("CHI_LLM_MODELS_YAML")
+        as_json = getattr(args, "json", False)
+        if not target:
+            msg = "Set CHI_LLM_MODELS_YAML (or pass --yaml) to the catalog to update"
+            _print_json({"error": msg}) if as_json else print(f"❌ {msg}")
+            sys.exit(1)
+        try:
+            if args.models_command == "add":
+                files = [Path(p) for p in args.paths]
+                result = {"yaml": target, "added": add_custom_models(Path(target), files)}
+            else:
+                removed = remove_custom_model(Path(target), args.model_id)
+                result = {"yaml": target, "removed": removed}
+        except Exception as e:
+            _print_json({"error": str(e)}) if as_json else print(f"❌ {e}")
+            sys.exit(1)
+        if as_json:
+            _print_json(result)
+        elif args.models_command == "add":
+            for m in result["added"]:
+                print(f"✅ Registered {m['id']} ({m['path']}) in {target}")
+            if not result["added"]:
+                print("Those models are already registered")
+        else:
+            state = "Removed" if result["removed"] else "No custom model"
+            print(f"{state} {args.model_id} in {target}")
     elif args.models_command == "validate-yaml":
         # Determine path: user-given or package default
         path = getattr(args, "path", None)
@@ -408,6 +441,22 @@ def register(subparsers: _SubParsersAction):
     models_info = models_sub.add_parser("info", help="Show model details")
     models_info.add_argument("model_id", help="Model ID")
     models_info.add_argument("--json", action="store_true", help="Output JSON")
+    models_add = models_sub.add_parser(
+        "add", help="Register local GGUF files in the models catalog"
+    )
+    models_add.add_argument("paths", nargs="+", help="GGUF files to register")
+    models_add.add_argument(
+        "--yaml", help="Catalog to update (default: $CHI_LLM_MODELS_YAML)"
+    )
+    models_add.add_argument("--json", action="store_true", help="Output JSON")
+    models_remove = models_sub.add_parser(
+        "remove", help="Forget a model registered with 'models add'"
+    )
+    models_remove.add_argument("model_id", help="Model ID (e.g., custom/my-model)")
+    models_remove.add_argument(
+        "--yaml", help="Catalog to update (default: $CHI_LLM_MODELS_YAML)"
+    )
+    models_remove.add_argument("--json", action="store_true", help="Output JSON")
     models_parser.set_defaults(func=cmd_models)
 
     v_yaml = models_sub.add_parser("validate-yaml", help="Validate a models YAML file")
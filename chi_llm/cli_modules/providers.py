@@ -180,7 +180,7 @@ PROVIDER_SCHEMAS = {
             },
             {
                 "name": "base_url",
-                "type": "string",
+                "type": "url",
                 "required": False,
                 "help": "API base URL",
             },
@@ -521,6 +521,13 @@ def register(subparsers: _SubParsersAction):
     disc.add_argument(
         "--org-id", dest="org_id", default=None, help="Organization ID (OpenAI)"
     )
+    disc.add_argument(
+        "--header",
+        dest="headers",
+        action="append",
+        default=None,
+        help="Extra HTTP header 'Name: value' (OpenAI; repeatable)",
+    )
     disc.set_defaults(func=cmd_discover_models)
 
 
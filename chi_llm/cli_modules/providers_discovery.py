@@ -81,6 +81,10 @@ def cmd_discover_models(args):
             req.add_header("Authorization", f"Bearer {api_key}")
             if org_id:
                 req.add_header("OpenAI-Organization", org_id)
+            for header in getattr(args, "headers", None) or []:
+                name, _, value = header.partition(":")
+                if name.strip():
+                    req.add_header(name.strip(), value.strip())
             with _request.urlopen(req, timeout=5) as resp:
                 if resp.status != 200:
                     raise HTTPError(
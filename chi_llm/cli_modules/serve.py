@@ -0,0 +1,99 @@
+"""
+Long-lived JSON-RPC 2.0 session on stdin/stdout (`chi-llm serve --stdio`).
+
+Front ends such as the Rust TUI send one request per line and read one
+response per line, so commands run inside this process instead of paying the
+interpreter startup on every call. Methods:
+
+- ``version``: ``{"protocol": 1}``
+- ``run``: ``{"args": [...]}`` runs a CLI command in-process and returns
+  ``{"exit_code": int, "stdout": str, "stderr": str}``
+"""
+
+from argparse import _SubParsersAction
+import contextlib
+import io
+import json
+import sys
+
+PROTOCOL_VERSION = 1
+
+# Commands that read from the terminal or never return
+_BLOCKED = {"serve", "chat", "interactive", "ui", "setup"}
+
+
+def _error(rid, code: int, message: str) -> dict:
+    return {"jsonrpc": "2.0", "id": rid, "error": {"code": code, "message": message}}
+
+
+def _run(args: list) -> dict:
+    from ..cli_main import main
+
+    out, err = io.StringIO(), io.StringIO()
+    code = 0
+    with contextlib.redirect_stdout(out), contextlib.redirect_stderr(err):
+        try:
+            main(list(args))
+        except SystemExit as e:
+            if isinstance(e.code, int):
+                code = e.code
+            elif e.code is not None:
+                print(e.code, file=sys.stderr)
+                code = 1
+    return {"exit_code": code, "stdout": out.getvalue(), "stderr": err.getvalue()}
+
+
+def handle_request(request) -> dict:
+    if not isinstance(request, dict):
+        return _error(None, -32600, "invalid request")
+    rid = request.get("id")
+    method = request.get("method")
+    params = request.get("params") or {}
+    if method == "version":
+        return {"jsonrpc": "2.0", "id": rid, "result": {"protocol": PROTOCOL_VERSION}}
+    if method != "run":
+        return _error(rid, -32601, f"unknown method: {method}")
+    args = params.get("args") if isinstance(params, dict) else None
+    if not isinstance(args, list) or not all(isinstance(a, str) for a in args):
+        return _error(rid, -32602, "params.args must be a list of strings")
+    if not args or args[0] in _BLOCKED:
+        message = f"command not available over serve: {' '.join(args)}"
+        return _error(rid, -32602, message)
+    try:
+        result = _run(args)
+    except Exception as e:  # pragma: no cover - main() reports its own errors
+        return _error(rid, -32603, str(e))
+    return {"jsonrpc": "2.0", "id": rid, "result": result}
+
+
+def serve(stdin=None, stdout=None):
+    """Answer requests from `stdin` until it is closed."""
+    stdin = stdin or sys.stdin
+    stdout = stdout or sys.stdout
+    for line in stdin:
+        line = line.strip()
+        if not line:
+            continue
+        try:
+            response = handle_request(json.loads(line))
+        except json.JSONDecodeError as e:
+            response = _error(None, -32700, f"parse error: {e}")
+        stdout.write(json.dumps(response) + "\n")
+        stdout.flush()
+
+
+def cmd_serve(args):
+    if not getattr(args, "stdio", False):
+        print("Only --stdio is supported: chi-llm serve --stdio")
+        sys.exit(2)
+    serve()
+
+
+def register(subparsers: _SubParsersAction):
+    sub = subparsers.add_parser(
+        "serve", help="Answer JSON-RPC requests (one per line) for front ends"
+    )
+    sub.add_argument(
+        "--stdio", action="store_true", help="Read requests from stdin, reply on stdout"
+    )
+    sub.set_defaults(func=cmd_serve)
@@ -3,7 +3,7 @@
 import os
 import warnings
 from pathlib import Path
-from typing import Optional, Dict, List
+from typing import Iterator, Optional, Dict, List
 from threading import Lock
 from llama_cpp import Llama
 from .utils import load_config
@@ -92,6 +92,8 @@ class MicroLLM:
                             port=provider.get("port", 1234),
                             model=provider.get("model"),
                             timeout=float(provider.get("timeout", 30.0)),
+                            proxy_url=provider.get("proxy_url") or None,
+                            ca_cert_path=provider.get("ca_cert_path") or None,
                         )
                         self._provider_type = "lmstudio"
                     except Exception as e:
@@ -109,6 +111,8 @@ class MicroLLM:
                             port=provider.get("port", 11434),
                             model=provider.get("model"),
                             timeout=float(provider.get("timeout", 30.0)),
+                            proxy_url=provider.get("proxy_url") or None,
+                            ca_cert_path=provider.get("ca_cert_path") or None,
                         )
                         self._provider_type = "ollama"
                     except Exception as e:
@@ -135,6 +139,11 @@ class MicroLLM:
                             else None,
                             port=provider.get("port"),
                             timeout=float(provider.get("timeout", 30.0)),
+                            headers=provider.get("headers")
+                            if isinstance(provider.get("headers"), dict)
+                            else None,
+                            proxy_url=provider.get("proxy_url") or None,
+                            ca_cert_path=provider.get("ca_cert_path") or None,
                         )
                         self._provider_type = "openai"
                     except Exception as e:
@@ -150,6 +159,8 @@ class MicroLLM:
                             api_key=str(provider.get("api_key", "")),
                             model=provider.get("model"),
                             timeout=float(provider.get("timeout", 30.0)),
+                            proxy_url=provider.get("proxy_url") or None,
+                            ca_cert_path=provider.get("ca_cert_path") or None,
                         )
                         self._provider_type = "anthropic"
                     except Exception as e:
@@ -283,6 +294,10 @@ class MicroLLM:
                 if self.verbose:
                     print(f"✅ Using {model_info.name} from: {model_path}")
                 return str(model_path)
+            if getattr(model_info, "path", None):
+                raise FileNotFoundError(
+                    f"Model file for {model_info.id} not found: {model_info.path}"
+                )
 
             # Download selected model
             print(f"📥 Downloading {model_info.name} ({model_info.file_size_mb}MB)...")
@@ -319,9 +334,10 @@ class MicroLLM:
             if self.verbose:
                 print("🤖 Loading model...")
 
-            # Get context window and n_gpu_layers
+            # Get context window, n_gpu_layers and n_threads
             context_window = 32768  # Default
             n_gpu_layers = 0
+            n_threads = min(4, os.cpu_count() or 4)
             if HAS_MODEL_MANAGER and self.model_id:
                 if self.model_id in MODELS:
                     context_window = MODELS[self.model_id].context_window
@@ -347,11 +363,17 @@ class MicroLLM:
                         n_gpu_layers = ngl
                 except Exception:
                     pass
+                try:
+                    nth = self.provider_config.get("n_threads")
+                    if isinstance(nth, int) and nth > 0:
+                        n_threads = nth
+                except Exception:
+                    pass
 
             model = Llama(
                 model_path=model_path,
                 n_ctx=context_window,
-                n_threads=min(4, os.cpu_count() or 4),
+                n_threads=n_threads,
                 n_gpu_layers=n_gpu_layers,  # default 0 (CPU) unless overridden
                 verbose=False,
             )
@@ -384,6 +406,55 @@ class MicroLLM:
             ).strip()
             raise RuntimeError(hint)
 
+        formatted_prompt, params = self._local_request(prompt, kwargs)
+        try:
+            # Use lock to ensure thread-safe model access
+            with _generation_lock:
+                output = self.llm(formatted_prompt, echo=False, **params)
+            return output["choices"][0]["text"].strip()
+        except Exception as e:
+            raise RuntimeError(f"Generation failed: {e}")
+
+    def generate_stream(self, prompt: str, **kwargs) -> Iterator[str]:
+        """Yield generated text in chunks as it is produced.
+
+        The local model and providers with a ``generate_stream`` method stream
+        token by token; other providers and the router yield the whole reply
+        once.
+        """
+        if self._router is not None or self._provider is not None:
+            stream = getattr(self._provider, "generate_stream", None)
+            try:
+                if self._router is None and stream is not None:
+                    yield from stream(prompt, **kwargs)
+                else:
+                    yield self.generate(prompt, **kwargs)
+            except Exception as e:
+                raise RuntimeError(str(e))
+            return
+        if self._provider_type is not None:
+            # External provider configured but unavailable; generate() explains
+            yield self.generate(prompt, **kwargs)
+            return
+
+        formatted_prompt, params = self._local_request(prompt, kwargs)
+        started = False
+        try:
+            with _generation_lock:
+                chunks = self.llm(formatted_prompt, echo=False, stream=True, **params)
+                for chunk in chunks:
+                    text = chunk["choices"][0]["text"]
+                    # Match generate(), which strips leading whitespace
+                    if not started:
+                        text = text.lstrip()
+                    if text:
+                        started = True
+                        yield text
+        except Exception as e:
+            raise RuntimeError(f"Generation failed: {e}")
+
+    def _local_request(self, prompt: str, kwargs: Dict) -> tuple:
+        """Prompt and sampling parameters for the local llama.cpp model."""
         # Check if we should use raw prompt (no formatting)
         use_raw = kwargs.pop("use_raw", False)
 
@@ -414,14 +485,7 @@ class MicroLLM:
             formatted_prompt = (
                 f"<start_of_turn>user\n{prompt}<end_of_turn>\n<start_of_turn>model\n"
             )
-
-        try:
-            # Use lock to ensure thread-safe model access
-            with _generation_lock:
-                output = self.llm(formatted_prompt, echo=False, **params)
-            return output["choices"][0]["text"].strip()
-        except Exception as e:
-            raise RuntimeError(f"Generation failed: {e}")
+        return formatted_prompt, params
 
     def chat(self, message: str, history: Optional[List[Dict[str, str]]] = None) -> str:
         """Chat with optional history (provider/router aware)."""
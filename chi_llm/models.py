@@ -38,6 +38,8 @@ class ModelInfo:
     # Optional tuning defaults
     n_gpu_layers: int = 0
     output_tokens: int = 4096
+    # Local GGUF file, for models registered with `chi-llm models add`
+    path: Optional[str] = None
 
 
 def _load_yaml_registry() -> Tuple[Dict[str, ModelInfo], Optional[str]]:
@@ -80,6 +82,7 @@ def _load_yaml_registry() -> Tuple[Dict[str, ModelInfo], Optional[str]]:
                     tags=list(item.get("tags", []) or []),
                     n_gpu_layers=int(item.get("n_gpu_layers", 0) or 0),
                     output_tokens=int(item.get("output_tokens", 4096) or 4096),
+                    path=str(item["path"]) if item.get("path") else None,
                 )
                 models[mi.id] = mi
             except Exception:
@@ -153,6 +156,85 @@ MODELS: Dict[str, ModelInfo] = {
     ),
 }
 
+CUSTOM_TAG = "custom"
+
+
+def _read_catalog(yaml_path: Path) -> dict:
+    """The catalog document at `yaml_path`, or the packaged one if it is new."""
+    import yaml  # type: ignore
+
+    if yaml_path.exists():
+        with open(yaml_path, "r", encoding="utf-8") as f:
+            doc = yaml.safe_load(f) or {}
+    else:
+        with resources.files(__package__).joinpath("models.yaml").open(
+            "r", encoding="utf-8"
+        ) as f:
+            doc = yaml.safe_load(f) or {}
+    if not isinstance(doc, dict):
+        raise ValueError(f"{yaml_path} is not a models catalog")
+    doc["models"] = list(doc.get("models") or [])
+    return doc
+
+
+def _write_catalog(yaml_path: Path, doc: dict) -> None:
+    import yaml  # type: ignore
+
+    yaml_path.parent.mkdir(parents=True, exist_ok=True)
+    with open(yaml_path, "w", encoding="utf-8") as f:
+        yaml.safe_dump(doc, f, sort_keys=False, allow_unicode=True)
+
+
+def add_custom_models(yaml_path: Path, files: List[Path]) -> List[dict]:
+    """Register local GGUF `files` in the models catalog at `yaml_path`.
+
+    A new catalog starts as a copy of the packaged one, so pointing
+    CHI_LLM_MODELS_YAML at it keeps the curated models. Ids are
+    `custom/<file stem>`; files already registered (same id or path) are
+    skipped. Returns the added entries.
+    """
+    doc = _read_catalog(yaml_path)
+    added: List[dict] = []
+    for file in files:
+        path = Path(file).expanduser().resolve()
+        if not path.is_file():
+            raise FileNotFoundError(f"{path} does not exist")
+        entry = {
+            "id": f"{CUSTOM_TAG}/{path.stem.lower()}",
+            "name": path.stem,
+            "path": str(path),
+            "filename": path.name,
+            "file_size_mb": path.stat().st_size // (1024 * 1024),
+            "description": "Custom model",
+            "tags": [CUSTOM_TAG],
+        }
+        taken = any(
+            m.get("id") == entry["id"] or m.get("path") == entry["path"]
+            for m in doc["models"]
+        )
+        if not taken:
+            doc["models"].append(entry)
+            added.append(entry)
+    if added:
+        _write_catalog(yaml_path, doc)
+    return added
+
+
+def remove_custom_model(yaml_path: Path, model_id: str) -> bool:
+    """Forget the custom model `model_id` (the file stays). False if not one."""
+    if not yaml_path.exists():
+        return False
+    doc = _read_catalog(yaml_path)
+    kept = [
+        m for m in doc["models"] if not (m.get("id") == model_id and m.get("path"))
+    ]
+    if len(kept) == len(doc["models"]):
+        return False
+    doc["models"] = kept
+    _write_catalog(yaml_path, doc)
+    return True
+
+
 # Attempt to override from YAML registry
 _YAML_MODELS, _YAML_ZERO_DEFAULT = _load_yaml_registry()
 if _YAML_MODELS:
@@ -367,6 +449,8 @@ class ModelManager:
             return False
 
         model = MODELS[model_id]
+        if model.path:
+            return Path(model.path).expanduser().is_file()
         model_path = MODEL_DIR / model.filename
         return model_path.exists()
 
@@ -376,6 +460,8 @@ class ModelManager:
             return None
 
         model = MODELS[model_id]
+        if model.path:
+            return Path(model.path).expanduser()
         return MODEL_DIR / model.filename
 
     def set_default_model(self, model_id: str, save_target: str = "global"):
@@ -8,12 +8,15 @@ Configuration:
 - api_key: str (required)
 - model: str (required), e.g., "claude-3-haiku-20240307"
 - timeout: float (optional)
+- proxy_url, ca_cert_path: str (optional; see providers.network)
 """
 
 from __future__ import annotations
 
 from typing import Dict, List, Optional
 
+from .network import httpx_client
+
 
 class AnthropicProvider:
     def __init__(
@@ -21,10 +24,14 @@ class AnthropicProvider:
         api_key: Optional[str] = None,
         model: Optional[str] = None,
         timeout: float = 30.0,
+        proxy_url: Optional[str] = None,
+        ca_cert_path: Optional[str] = None,
     ) -> None:
         self.api_key = (api_key or "").strip()
         self.model = (model or "").strip()
         self.timeout = timeout
+        self.proxy_url = proxy_url or None
+        self.ca_cert_path = ca_cert_path or None
         if not self.api_key:
             raise RuntimeError(
                 "Anthropic provider requires an API key. Set CHI_LLM_PROVIDER_API_KEY "
@@ -43,7 +50,11 @@ class AnthropicProvider:
             import anthropic  # type: ignore
 
             # SDK v0.30+: instantiate client with api_key
-            self._client = anthropic.Anthropic(api_key=self.api_key)
+            kwargs = {}
+            http_client = httpx_client(self.proxy_url, self.ca_cert_path, self.timeout)
+            if http_client is not None:
+                kwargs["http_client"] = http_client
+            self._client = anthropic.Anthropic(api_key=self.api_key, **kwargs)
             return self._client
         except Exception as e:  # pragma: no cover - depends on env
             raise RuntimeError(
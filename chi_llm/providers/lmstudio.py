@@ -12,6 +12,8 @@ from typing import List, Dict, Optional, Any
 
 import json
 
+from .network import requests_kwargs, urlopen
+
 
 class LmStudioProvider:
     """
@@ -22,6 +24,7 @@ class LmStudioProvider:
     - port: int (default: 1234)
     - model: str (required by LM Studio for most requests)
     - timeout: float (seconds, default: 30)
+    - proxy_url, ca_cert_path: str (optional; see providers.network)
     """
 
     def __init__(
@@ -30,11 +33,15 @@ class LmStudioProvider:
         port: int | str = 1234,
         model: Optional[str] = None,
         timeout: float = 30.0,
+        proxy_url: Optional[str] = None,
+        ca_cert_path: Optional[str] = None,
     ) -> None:
         self.host = host or "127.0.0.1"
         self.port = port or 1234
         self.model = model
         self.timeout = timeout
+        self.proxy_url = proxy_url or None
+        self.ca_cert_path = ca_cert_path or None
         self.base_url = f"http://{self.host}:{self.port}/v1"
 
     # --- Basic HTTP helpers (requests optional) ---
@@ -44,7 +51,12 @@ class LmStudioProvider:
             try:
                 import requests  # type: ignore
 
-                r = requests.post(url, json=payload, timeout=self.timeout)
+                r = requests.post(
+                    url,
+                    json=payload,
+                    timeout=self.timeout,
+                    **requests_kwargs(self.proxy_url, self.ca_cert_path),
+                )
                 if r.status_code >= 400:
                     raise RuntimeError(
                         f"LM Studio error {r.status_code}: {r.text[:200]}"
@@ -59,7 +71,9 @@ class LmStudioProvider:
                     url, data=data, headers={"Content-Type": "application/json"}
                 )
                 try:
-                    with request.urlopen(req, timeout=self.timeout) as resp:
+                    with urlopen(
+                        req, self.timeout, self.proxy_url, self.ca_cert_path
+                    ) as resp:
                         body = resp.read().decode("utf-8")
                         return json.loads(body)
                 except error.HTTPError as he:  # pragma: no cover - network dependent
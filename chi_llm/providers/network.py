@@ -0,0 +1,64 @@
+"""
+Per-provider proxy and CA bundle for the HTTP providers.
+
+Configuration (lmstudio, ollama, openai, anthropic):
+- proxy_url: str (optional; e.g. http://proxy.corp:3128)
+- ca_cert_path: str (optional; PEM bundle trusted for TLS, e.g. a corporate root CA)
+
+When neither is set the providers behave as before and honour the standard
+HTTP(S)_PROXY / SSL_CERT_FILE environment variables.
+"""
+
+from __future__ import annotations
+
+from typing import Any, Dict, Optional
+
+
+def requests_kwargs(
+    proxy_url: Optional[str] = None, ca_cert_path: Optional[str] = None
+) -> Dict[str, Any]:
+    """Extra keyword arguments for `requests.post`; empty when unset."""
+    kwargs: Dict[str, Any] = {}
+    if proxy_url:
+        kwargs["proxies"] = {"http": proxy_url, "https": proxy_url}
+    if ca_cert_path:
+        kwargs["verify"] = ca_cert_path
+    return kwargs
+
+
+def urlopen(
+    req: Any,
+    timeout: float,
+    proxy_url: Optional[str] = None,
+    ca_cert_path: Optional[str] = None,
+):
+    """`urllib.request.urlopen` honouring the proxy and CA bundle."""
+    from urllib import request
+
+    if not proxy_url and not ca_cert_path:
+        return request.urlopen(req, timeout=timeout)
+    handlers = []
+    if proxy_url:
+        handlers.append(request.ProxyHandler({"http": proxy_url, "https": proxy_url}))
+    if ca_cert_path:
+        import ssl
+
+        context = ssl.create_default_context(cafile=ca_cert_path)
+        handlers.append(request.HTTPSHandler(context=context))
+    return request.build_opener(*handlers).open(req, timeout=timeout)
+
+
+def httpx_client(
+    proxy_url: Optional[str] = None,
+    ca_cert_path: Optional[str] = None,
+    timeout: float = 30.0,
+):
+    """httpx client for the SDK-based providers, or None when neither is set."""
+    if not proxy_url and not ca_cert_path:
+        return None
+    import httpx  # type: ignore
+
+    kwargs: Dict[str, Any] = {"timeout": timeout, "verify": ca_cert_path or True}
+    if proxy_url:
+        kwargs["proxy"] = proxy_url
+    return httpx.Client(**kwargs)
@@ -5,16 +5,19 @@ Minimal synchronous client for Ollama's local HTTP API.
 Endpoints:
 - POST /api/generate {model, prompt, stream=False}
 - POST /api/chat {model, messages, stream=False}
+- POST /api/generate {model, prompt, stream=True} for generate_stream()
 
 No hard dependency on requests; falls back to urllib when missing.
 """
 
 from __future__ import annotations
 
-from typing import List, Dict, Optional, Any
+from typing import Any, Dict, Iterator, List, Optional
 
 import json
 
+from .network import requests_kwargs, urlopen
+
 
 class OllamaProvider:
     """
@@ -25,6 +28,7 @@ class OllamaProvider:
     - port: int (default: 11434)
     - model: str (required)
     - timeout: float (seconds, default: 30)
+    - proxy_url, ca_cert_path: str (optional; see providers.network)
     """
 
     def __init__(
@@ -33,11 +37,15 @@ class OllamaProvider:
         port: int | str = 11434,
         model: Optional[str] = None,
         timeout: float = 30.0,
+        proxy_url: Optional[str] = None,
+        ca_cert_path: Optional[str] = None,
     ) -> None:
         self.host = host or "127.0.0.1"
         self.port = port or 11434
         self.model = model
         self.timeout = timeout
+        self.proxy_url = proxy_url or None
+        self.ca_cert_path = ca_cert_path or None
         self.base_url = f"http://{self.host}:{self.port}"
 
     # --- Basic HTTP helpers (requests optional) ---
@@ -47,7 +55,12 @@ class OllamaProvider:
             try:
                 import requests  # type: ignore
 
-                r = requests.post(url, json=payload, timeout=self.timeout)
+                r = requests.post(
+                    url,
+                    json=payload,
+                    timeout=self.timeout,
+                    **requests_kwargs(self.proxy_url, self.ca_cert_path),
+                )
                 if r.status_code >= 400:
                     raise RuntimeError(f"Ollama error {r.status_code}: {r.text[:200]}")
                 return r.json()
@@ -60,7 +73,9 @@ class OllamaProvider:
                     url, data=data, headers={"Content-Type": "application/json"}
                 )
                 try:
-                    with request.urlopen(req, timeout=self.timeout) as resp:
+                    with urlopen(
+                        req, self.timeout, self.proxy_url, self.ca_cert_path
+                    ) as resp:
                         body = resp.read().decode("utf-8")
                         return json.loads(body)
                 except error.HTTPError as he:  # pragma: no cover - network dependent
@@ -78,6 +93,39 @@ class OllamaProvider:
                 )
             )
 
+    def _post_stream(self, path: str, payload: Dict[str, Any]) -> Iterator[Dict[str, Any]]:
+        """POST and yield each JSON object of the newline-delimited reply."""
+        url = f"{self.base_url}{path}"
+        try:
+            try:
+                import requests  # type: ignore
+
+                r = requests.post(
+                    url,
+                    json=payload,
+                    timeout=self.timeout,
+                    stream=True,
+                    **requests_kwargs(self.proxy_url, self.ca_cert_path),
+                )
+                if r.status_code >= 400:
+                    raise RuntimeError(f"Ollama error {r.status_code}: {r.text[:200]}")
+                lines = r.iter_lines()
+            except ModuleNotFoundError:
+                from urllib import request
+
+                data = json.dumps(payload).encode("utf-8")
+                req = request.Request(
+                    url, data=data, headers={"Content-Type": "application/json"}
+                )
+                lines = urlopen(req, self.timeout, self.proxy_url, self.ca_cert_path)
+            for line in lines:
+                if line and line.strip():
+                    yield json.loads(line)
+        except Exception as e:
+            raise RuntimeError(
+                f"Could not stream from Ollama at {self.base_url}. Error: {e}"
+            )
+
     # --- Provider protocol methods ---
     def generate(self, prompt: str, **kwargs) -> str:
         if not self.model:
@@ -105,6 +153,27 @@ class OllamaProvider:
         except Exception as e:
             raise RuntimeError(f"Unexpected Ollama response: {e}")
 
+    def generate_stream(self, prompt: str, **kwargs) -> Iterator[str]:
+        """Yield response chunks as Ollama produces them."""
+        if not self.model:
+            raise RuntimeError("Ollama provider requires 'model' in configuration.")
+        payload = {
+            "model": self.model,
+            "prompt": prompt,
+            "stream": True,
+            "options": {
+                "temperature": kwargs.get("temperature", 0.7),
+            },
+        }
+        for data in self._post_stream("/api/generate", payload):
+            if data.get("error"):
+                raise RuntimeError(f"Ollama error: {data['error']}")
+            chunk = data.get("response")
+            if chunk:
+                yield chunk
+            if data.get("done"):
+                break
+
     def chat(self, message: str, history: Optional[List[Dict[str, str]]] = None) -> str:
         if not self.model:
             raise RuntimeError("Ollama provider requires 'model' in configuration.")
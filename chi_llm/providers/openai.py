@@ -10,12 +10,16 @@ Configuration:
 - base_url: str (optional; for custom endpoints). If not provided,
   tries to derive from `host` if it looks like a URL.
 - timeout: float (optional, default 30s)
+- headers: dict (optional; extra HTTP headers, e.g. for API gateways)
+- proxy_url, ca_cert_path: str (optional; see providers.network)
 """
 
 from __future__ import annotations
 
 from typing import Dict, List, Optional
 
+from .network import httpx_client
+
 
 class OpenAIProvider:
     def __init__(
@@ -26,10 +30,16 @@ class OpenAIProvider:
         host: Optional[str] = None,
         port: Optional[int | str] = None,
         timeout: float = 30.0,
+        headers: Optional[Dict[str, str]] = None,
+        proxy_url: Optional[str] = None,
+        ca_cert_path: Optional[str] = None,
     ) -> None:
         self.api_key = (api_key or "").strip()
+        self.headers = {str(k): str(v) for k, v in (headers or {}).items()}
         self.model = (model or "").strip()
         self.timeout = timeout
+        self.proxy_url = proxy_url or None
+        self.ca_cert_path = ca_cert_path or None
         # Prefer explicit base_url; otherwise accept full URL passed in host
         if base_url:
             self.base_url = base_url
@@ -58,7 +68,16 @@ class OpenAIProvider:
             # New-style SDK (>=1.0)
             from openai import OpenAI  # type: ignore
 
-            self._client = OpenAI(api_key=self.api_key, base_url=self.base_url)
+            kwargs = {}
+            http_client = httpx_client(self.proxy_url, self.ca_cert_path, self.timeout)
+            if http_client is not None:
+                kwargs["http_client"] = http_client
+            self._client = OpenAI(
+                api_key=self.api_key,
+                base_url=self.base_url,
+                default_headers=self.headers or None,
+                **kwargs,
+            )
             return self._client
         except Exception:
             # Old SDK fallback
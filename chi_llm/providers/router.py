@@ -98,6 +98,8 @@ def default_registry() -> Dict[str, Callable[[Dict[str, Any]], Any]]:
             port=prof.get("port", 1234),
             model=prof.get("model"),
             timeout=float(prof.get("timeout", 30.0)),
+            proxy_url=prof.get("proxy_url") or None,
+            ca_cert_path=prof.get("ca_cert_path") or None,
         )
     except Exception:
         pass
@@ -109,6 +111,8 @@ def default_registry() -> Dict[str, Callable[[Dict[str, Any]], Any]]:
             port=prof.get("port", 11434),
             model=prof.get("model"),
             timeout=float(prof.get("timeout", 30.0)),
+            proxy_url=prof.get("proxy_url") or None,
+            ca_cert_path=prof.get("ca_cert_path") or None,
         )
     except Exception:
         pass
@@ -131,6 +135,11 @@ def default_registry() -> Dict[str, Callable[[Dict[str, Any]], Any]]:
             else None,
             port=prof.get("port"),
             timeout=float(prof.get("timeout", 30.0)),
+            headers=prof.get("headers")
+            if isinstance(prof.get("headers"), dict)
+            else None,
+            proxy_url=prof.get("proxy_url") or None,
+            ca_cert_path=prof.get("ca_cert_path") or None,
         )
     except Exception:
         pass
@@ -141,6 +150,8 @@ def default_registry() -> Dict[str, Callable[[Dict[str, Any]], Any]]:
             api_key=str(prof.get("api_key", "")),
             model=prof.get("model"),
             timeout=float(prof.get("timeout", 30.0)),
+            proxy_url=prof.get("proxy_url") or None,
+            ca_cert_path=prof.get("ca_cert_path") or None,
         )
     except Exception:
         pass
@@ -3,6 +3,7 @@ Utility functions for chi_llm.
 """
 
 import os
+import re
 import json
 import yaml
 from pathlib import Path
@@ -19,6 +20,46 @@ def _deep_merge(base: Dict[str, Any], overrides: Dict[str, Any]) -> Dict[str, An
     return base
 
 
+_ENV_REF = re.compile(r"\$\{([A-Za-z_][A-Za-z0-9_]*)\}")
+
+
+def _expand_env_refs(value: Any) -> Any:
+    """Replace ``${VAR}`` in string values (recursively) with the variable's
+    value; references to unset variables are left as written."""
+    if isinstance(value, str):
+        return _ENV_REF.sub(lambda m: os.environ.get(m.group(1), m.group(0)), value)
+    if isinstance(value, dict):
+        return {k: _expand_env_refs(v) for k, v in value.items()}
+    if isinstance(value, list):
+        return [_expand_env_refs(v) for v in value]
+    return value
+
+
+KEYRING_SERVICE = "chi-llm"
+KEYRING_PREFIX = "keyring:"
+
+
+def _resolve_keyring_refs(value: Any) -> Any:
+    """Replace ``keyring:<account>`` string values (recursively) with the secret
+    stored under the ``chi-llm`` service of the system keychain (written by the
+    TUI's ``k``). Needs the optional ``keyring`` package; without it, or without
+    an entry, the reference is left as written."""
+    if isinstance(value, dict):
+        return {k: _resolve_keyring_refs(v) for k, v in value.items()}
+    if isinstance(value, list):
+        return [_resolve_keyring_refs(v) for v in value]
+    if not isinstance(value, str) or not value.strip().startswith(KEYRING_PREFIX):
+        return value
+    account = value.strip()[len(KEYRING_PREFIX) :]
+    try:
+        import keyring  # type: ignore
+
+        secret = keyring.get_password(KEYRING_SERVICE, account)
+    except Exception:
+        return value
+    return secret if secret is not None else value
+
+
 def load_config(config_path: Optional[str] = None) -> Dict[str, Any]:
     """
     Load configuration with sensible precedence and deep-merge.
@@ -29,6 +70,10 @@ def load_config(config_path: Optional[str] = None) -> Dict[str, Any]:
     3. Provided config_path (if any) — explicit path should override project
     4. CHI_LLM_CONFIG (file path or inline JSON) — always overrides
 
+    ``${VAR}`` references in string values are expanded from the environment
+    and ``keyring:<account>`` values are looked up in the system keychain, so
+    config files can point at secrets instead of holding them.
+
     Returns:
         Configuration dictionary
     """
@@ -130,7 +175,7 @@ def load_config(config_path: Optional[str] = None) -> Dict[str, Any]:
         except Exception:
             pass
 
-    return config
+    return _resolve_keyring_refs(_expand_env_refs(config))
 
 
 def _load_file_config(filepath: str, defaults: Dict) -> Dict[str, Any]:
@@ -80,6 +80,10 @@ setup(
             "numpy>=1.21.0",
             "tqdm>=4.65.0",
         ],
+        # Secrets stored in the system keychain (keyring:<account> values)
+        "keyring": [
+            "keyring>=24.0",
+        ],
         # GPU support
         "gpu": [
             "torch>=2.0.0",  # For GPU detection
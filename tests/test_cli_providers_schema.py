@@ -28,3 +28,7 @@ def test_providers_schema_json_shape():
 
     openai_fields = {f.get("name") for f in pmap["openai"].get("fields", [])}
     assert "api_key" in openai_fields
+
+    # Typed fields the TUI validates on edit
+    openai_types = {f.get("name"): f.get("type") for f in pmap["openai"]["fields"]}
+    assert openai_types["base_url"] == "url"
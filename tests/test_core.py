@@ -417,3 +417,19 @@ class TestModelManagement:
         args, kwargs = mock_download.call_args
         assert kwargs["repo_id"] == MODELS["qwen3-1.7b"].repo
         assert kwargs["filename"] == MODELS["qwen3-1.7b"].filename
+
+
+def test_local_provider_n_threads_overrides_the_default():
+    """provider.n_threads from the config reaches llama.cpp."""
+    llm = MicroLLM.__new__(MicroLLM)
+    llm.verbose = False
+    llm.model_id = None
+    llm.provider_config = {"type": "local", "n_threads": 2}
+    with patch("chi_llm.core.Llama") as mock_llama:
+        llm._load_model("/fake/path/model.gguf")
+    assert mock_llama.call_args.kwargs["n_threads"] == 2
+
+    llm.provider_config = {"type": "local"}
+    with patch("chi_llm.core.Llama") as mock_llama:
+        llm._load_model("/fake/path/model.gguf")
+    assert mock_llama.call_args.kwargs["n_threads"] >= 1
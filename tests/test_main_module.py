@@ -0,0 +1,20 @@
+"""
+Tests for running the CLI as ``python -m chi_llm``.
+"""
+
+import subprocess
+import sys
+from pathlib import Path
+
+
+def test_python_m_chi_llm_prints_version():
+    repo = Path(__file__).resolve().parents[1]
+    out = subprocess.run(
+        [sys.executable, "-m", "chi_llm", "--version"],
+        cwd=repo,
+        capture_output=True,
+        text=True,
+        timeout=60,
+    )
+    assert out.returncode == 0, out.stderr
+    assert out.stdout.startswith("chi_llm ")
@@ -44,3 +44,99 @@ def test_setup_recommend_json(capsys):
     out = capsys.readouterr().out
     data = json.loads(out)
     assert data["id"] == "gemma-270m"
+
+
+def test_models_list_json_includes_path_of_downloaded(capsys):
+    model_id = "gemma-270m"
+
+    class FakeMgr:
+        def is_downloaded(self, mid):
+            return mid == model_id
+
+        def get_model_path(self, mid):
+            return f"/models/{mid}.gguf" if mid == model_id else None
+
+        def get_current_model(self):
+            return MODELS[model_id]
+
+    with patch.object(models_cli, "ModelManager", return_value=FakeMgr()):
+        args = SimpleNamespace(models_command="list", json=True)
+        models_cli.cmd_models(args)
+    data = json.loads(capsys.readouterr().out)
+    by_id = {m["id"]: m for m in data}
+    assert by_id[model_id]["path"] == f"/models/{model_id}.gguf"
+    assert all(m["path"] is None for m in data if m["id"] != model_id)
+
+
+def test_models_add_and_remove_update_the_yaml_catalog(tmp_path, capsys):
+    gguf = tmp_path / "My-Model.Q4.gguf"
+    gguf.write_bytes(b"gguf")
+    catalog = tmp_path / "models.yaml"
+
+    args = SimpleNamespace(
+        models_command="add", paths=[str(gguf)], yaml=str(catalog), json=True
+    )
+    models_cli.cmd_models(args)
+    added = json.loads(capsys.readouterr().out)["added"]
+    assert [m["id"] for m in added] == ["custom/my-model.q4"]
+
+    import yaml
+
+    doc = yaml.safe_load(catalog.read_text())
+    ids = [m["id"] for m in doc["models"]]
+    # A new catalog keeps the packaged models
+    assert "gemma-270m" in ids and "custom/my-model.q4" in ids
+    entry = next(m for m in doc["models"] if m["id"] == "custom/my-model.q4")
+    assert entry["path"] == str(gguf.resolve())
+
+    # Adding the same file again is a no-op
+    models_cli.cmd_models(args)
+    assert json.loads(capsys.readouterr().out)["added"] == []
+
+    args = SimpleNamespace(
+        models_command="remove",
+        model_id="custom/my-model.q4",
+        yaml=str(catalog),
+        json=True,
+    )
+    models_cli.cmd_models(args)
+    assert json.loads(capsys.readouterr().out)["removed"] is True
+    doc = yaml.safe_load(catalog.read_text())
+    assert "custom/my-model.q4" not in [m["id"] for m in doc["models"]]
+    assert gguf.exists()
+
+
+def test_models_add_needs_a_catalog_path(tmp_path, monkeypatch, capsys):
+    monkeypatch.delenv("CHI_LLM_MODELS_YAML", raising=False)
+    args = SimpleNamespace(
+        models_command="add", paths=[str(tmp_path / "x.gguf")], yaml=None, json=True
+    )
+    try:
+        models_cli.cmd_models(args)
+    except SystemExit as e:
+        assert e.code == 1
+    assert "CHI_LLM_MODELS_YAML" in json.loads(capsys.readouterr().out)["error"]
+
+
+def test_registered_path_is_the_model_file(tmp_path):
+    from chi_llm.models import ModelInfo, ModelManager
+
+    gguf = tmp_path / "local.gguf"
+    gguf.write_bytes(b"gguf")
+    info = ModelInfo(
+        id="custom/local",
+        name="local",
+        size="?",
+        file_size_mb=0,
+        repo="",
+        filename="local.gguf",
+        context_window=4096,
+        description="",
+        recommended_ram_gb=0,
+        tags=["custom"],
+        path=str(gguf),
+    )
+    mgr = ModelManager.__new__(ModelManager)
+    with patch.dict(MODELS, {info.id: info}):
+        assert mgr.is_downloaded(info.id)
+        assert mgr.get_model_path(info.id) == gguf
@@ -0,0 +1,52 @@
+import json
+from unittest.mock import patch
+
+
+def test_ollama_sends_through_the_configured_proxy_and_ca(monkeypatch):
+    cfg = {
+        "provider": {
+            "type": "ollama",
+            "model": "llama3",
+            "proxy_url": "http://proxy.corp:3128",
+            "ca_cert_path": "/etc/corp-ca.pem",
+        }
+    }
+    monkeypatch.setenv("CHI_LLM_CONFIG", json.dumps(cfg))
+    seen = {}
+
+    class Resp:
+        status_code = 200
+
+        def json(self):
+            return {"response": "Pong"}
+
+    def fake_post(url, json=None, timeout=30, **kwargs):  # noqa: A002
+        seen.update(kwargs)
+        return Resp()
+
+    with patch("requests.post", side_effect=fake_post):
+        from chi_llm.core import MicroLLM
+
+        assert MicroLLM().generate("Ping") == "Pong"
+    assert seen["proxies"] == {
+        "http": "http://proxy.corp:3128",
+        "https": "http://proxy.corp:3128",
+    }
+    assert seen["verify"] == "/etc/corp-ca.pem"
+
+
+def test_unset_proxy_and_ca_add_no_request_arguments():
+    from chi_llm.providers.network import httpx_client, requests_kwargs
+
+    assert requests_kwargs(None, "") == {}
+    assert httpx_client(None, None) is None
+
+
+def test_router_profiles_pass_proxy_and_ca_to_providers():
+    from chi_llm.providers.router import default_registry
+
+    prov = default_registry()["lmstudio"](
+        {"type": "lmstudio", "proxy_url": "http://p:1", "ca_cert_path": "/ca.pem"}
+    )
+    assert prov.proxy_url == "http://p:1"
+    assert prov.ca_cert_path == "/ca.pem"
@@ -72,3 +72,27 @@ def test_ollama_connection_error_surface(monkeypatch):
             msg = str(e)
             assert "Ollama" in msg
             assert "http://127.0.0.1:11434" in msg
+
+
+def test_ollama_generate_stream_yields_chunks(monkeypatch):
+    _env_provider(monkeypatch)
+
+    class Resp:
+        status_code = 200
+
+        def iter_lines(self):
+            yield b'{"response": "Hel", "done": false}'
+            yield b""
+            yield b'{"response": "lo", "done": false}'
+            yield b'{"response": "", "done": true}'
+
+    def fake_post(url, json=None, timeout=30, stream=False):  # noqa: A002
+        assert url.endswith("/api/generate")
+        assert json["stream"] is True and stream is True
+        return Resp()
+
+    with patch("requests.post", side_effect=fake_post):
+        from chi_llm.core import MicroLLM
+
+        llm = MicroLLM()
+        assert list(llm.generate_stream("Ping")) == ["Hel", "lo"]
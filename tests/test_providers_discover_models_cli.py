@@ -84,3 +84,28 @@ def test_discover_models_openai_headers_and_json(monkeypatch, capsys):
     assert seen["auth"] == "Bearer test-key"
     assert seen["org"] == "org-123"
     assert "/v1/models" in (seen["url"] or "")
+
+
+def test_discover_models_openai_custom_headers(monkeypatch, capsys):
+    seen = {}
+
+    def _stub_urlopen(req, timeout=5):
+        seen.update({k.lower(): v for k, v in req.header_items()})
+        return _StubResp({"data": [{"id": "gpt-4o-mini"}]}, status=200)
+
+    monkeypatch.setattr(disc._request, "urlopen", _stub_urlopen)
+    args = SimpleNamespace(
+        ptype="openai",
+        base_url="https://gateway.example",
+        api_key="test-key",
+        org_id=None,
+        headers=["X-Api-Key: gw-1", "X-Tenant:acme"],
+        json=True,
+    )
+
+    disc.cmd_discover_models(args)
+    data = json.loads(capsys.readouterr().out)
+
+    assert len(data["models"]) == 1
+    assert seen["x-api-key"] == "gw-1"
+    assert seen["x-tenant"] == "acme"
@@ -0,0 +1,47 @@
+"""
+Tests for the `serve --stdio` JSON-RPC session.
+"""
+
+import io
+import json
+from unittest.mock import patch
+
+from chi_llm.cli_modules import serve as srv
+
+
+def test_run_captures_output_and_exit_code():
+    def fake_main(argv):
+        print(json.dumps({"args": argv}))
+        raise SystemExit(3)
+
+    with patch("chi_llm.cli_main.main", side_effect=fake_main):
+        resp = srv.handle_request(
+            {
+                "jsonrpc": "2.0",
+                "id": 7,
+                "method": "run",
+                "params": {"args": ["models", "list", "--json"]},
+            }
+        )
+    assert resp["id"] == 7
+    assert resp["result"]["exit_code"] == 3
+    out = json.loads(resp["result"]["stdout"])
+    assert out == {"args": ["models", "list", "--json"]}
+
+
+def test_errors_for_bad_requests():
+    def code(request):
+        return srv.handle_request(request)["error"]["code"]
+
+    assert code({"id": 1, "method": "nope"}) == -32601
+    assert code({"id": 2, "method": "run", "params": {"args": "x"}}) == -32602
+    assert code({"id": 3, "method": "run", "params": {"args": ["chat"]}}) == -32602
+
+
+def test_serve_answers_one_line_per_request():
+    stdin = io.StringIO('{"jsonrpc": "2.0", "id": 1, "method": "version"}\nnot json\n')
+    stdout = io.StringIO()
+    srv.serve(stdin, stdout)
+    lines = [json.loads(line) for line in stdout.getvalue().splitlines()]
+    assert lines[0]["result"] == {"protocol": srv.PROTOCOL_VERSION}
+    assert lines[1]["error"]["code"] == -32700
@@ -6,6 +6,7 @@ import json
 import yaml
 import tempfile
 from pathlib import Path
+from types import SimpleNamespace
 from unittest.mock import patch
 
 from chi_llm.utils import (
@@ -108,6 +109,49 @@ class TestConfigLoading:
         assert config["provider"]["port"] == 11434
         assert config["provider"]["api_key"] == "secret"
 
+    @patch.dict("os.environ", {"CHI_TEST_API_KEY": "sk-from-env"})
+    def test_env_references_are_expanded(self):
+        """Test ${VAR} references in config files resolve from the environment."""
+        cfg = {
+            "provider": {"type": "openai", "api_key": "${CHI_TEST_API_KEY}"},
+            "provider_profiles": [
+                {"name": "a", "api_key": "Bearer ${CHI_TEST_API_KEY}"},
+                {"name": "b", "api_key": "${CHI_TEST_UNSET_KEY}"},
+            ],
+        }
+        with tempfile.NamedTemporaryFile(mode="w", suffix=".json", delete=False) as f:
+            json.dump(cfg, f)
+            f.flush()
+
+            config = load_config(f.name)
+
+            assert config["provider"]["api_key"] == "sk-from-env"
+            profiles = config["provider_profiles"]
+            assert profiles[0]["api_key"] == "Bearer sk-from-env"
+            assert profiles[1]["api_key"] == "${CHI_TEST_UNSET_KEY}"
+
+            Path(f.name).unlink()
+
+    def test_keyring_references_are_resolved(self):
+        """Test keyring:<account> values come from the chi-llm keychain service."""
+        stored = {("chi-llm", "p1/api_key"): "sk-from-keychain"}
+        fake_keyring = SimpleNamespace(get_password=lambda s, a: stored.get((s, a)))
+        cfg = {
+            "provider": {"type": "openai", "api_key": "keyring:p1/api_key"},
+            "provider_profiles": [{"name": "b", "api_key": "keyring:missing"}],
+        }
+        with tempfile.NamedTemporaryFile(mode="w", suffix=".json", delete=False) as f:
+            json.dump(cfg, f)
+            f.flush()
+
+            with patch.dict("sys.modules", {"keyring": fake_keyring}):
+                config = load_config(f.name)
+
+            assert config["provider"]["api_key"] == "sk-from-keychain"
+            assert config["provider_profiles"][0]["api_key"] == "keyring:missing"
+
+            Path(f.name).unlink()
+
 
 class TestTextUtilities:
     """Test text manipulation utilities."""
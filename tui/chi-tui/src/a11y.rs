@@ -0,0 +1,98 @@
+//! Screen-reader-friendly mode (`--a11y` or `CHI_TUI_A11Y`): pages are rendered as
+//! plain linear text and state changes are announced on a status line and with the
+//! terminal bell.
+
+use ratatui::buffer::Buffer;
+
+use crate::app::{App, Page};
+
+/// Tracks what was last announced so only changes are reported.
+#[derive(Clone, Debug, Default)]
+pub struct Announcer {
+    /// Latest announcement, shown on the status line.
+    pub status: Option<String>,
+    /// Ring the bell on the next frame.
+    pub bell: bool,
+    seen: Option<(Page, Vec<String>, Option<String>)>,
+}
+
+impl Announcer {
+    /// Compare the page, open popups and toast with the last call and announce
+    /// what changed. Background results (toasts) also ring the bell.
+    pub fn observe(&mut self, page: Page, modals: Vec<String>, toast: Option<String>) {
+        let Some((old_page, old_modals, old_toast)) = self.seen.replace((page, modals.clone(), toast.clone())) else {
+            self.status = Some(format!("{} page", page_name(page)));
+            return;
+        };
+        if toast.is_some() && toast != old_toast {
+            self.status = toast;
+            self.bell = true;
+        } else if modals.len() > old_modals.len() {
+            self.status = modals.last().map(|m| format!("{} opened; Esc closes", m));
+        } else if modals.len() < old_modals.len() && page == old_page {
+            self.status = old_modals.last().map(|m| format!("{} closed", m));
+        } else if page != old_page {
+            self.status = Some(format!("{} page", page_name(page)));
+        }
+    }
+
+    pub fn take_bell(&mut self) -> bool {
+        std::mem::take(&mut self.bell)
+    }
+}
+
+/// Feed the current app state to its announcer.
+pub fn announce_changes(app: &mut App) {
+    let modals = app.modals.iter().map(|m| m.label()).collect();
+    let toast = app.toasts.latest().map(|t| format!("{}: {}", if t.ok { "Done" } else { "Failed" }, t.message));
+    app.announcer.observe(app.page, modals, toast);
+}
+
+pub fn page_name(page: Page) -> &'static str {
+    match page {
+        Page::Welcome => "Welcome",
+        Page::Readme => "Docs",
+        Page::Configure => "Configure Providers",
+        Page::SelectDefault => "Select Default",
+        Page::ModelBrowser => "Model Browser",
+        Page::Diagnostics => "Diagnostics",
+        Page::Build => "Build Configuration",
+        Page::Routing => "Routing",
+        Page::Settings => "Settings",
+        Page::WhatsNew => "What's New",
+        Page::Benchmarks => "Benchmarks",
+        Page::CliLog => "CLI Log",
+    }
+}
+
+/// Blank out box-drawing characters (borders, separators) so a screen reader
+/// only meets text.
+pub fn linearize(buf: &mut Buffer) {
+    for cell in buf.content.iter_mut() {
+        if cell.symbol().chars().next().is_some_and(|c| ('\u{2500}'..='\u{257F}').contains(&c)) {
+            cell.set_symbol(" ");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn announces_only_changes() {
+        let mut a = Announcer::default();
+        a.observe(Page::Welcome, Vec::new(), None);
+        assert_eq!(a.status.as_deref(), Some("Welcome page"));
+        a.observe(Page::Build, Vec::new(), None);
+        assert_eq!(a.status.as_deref(), Some("Build Configuration page"));
+        a.observe(Page::Build, vec!["Help".to_string()], None);
+        assert_eq!(a.status.as_deref(), Some("Help opened; Esc closes"));
+        assert!(!a.take_bell());
+        a.observe(Page::Build, Vec::new(), Some("Failed: boom".to_string()));
+        assert_eq!(a.status.as_deref(), Some("Failed: boom"));
+        assert!(a.take_bell());
+        a.observe(Page::Build, Vec::new(), Some("Failed: boom".to_string()));
+        assert!(!a.take_bell());
+    }
+}
@@ -1,11 +1,43 @@
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, Sender};
 use std::time::Instant;
 
-use crate::build::BuildState;
-use crate::diagnostics::DiagState;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+
+use crate::a11y::{announce_changes, Announcer};
+use crate::bench::{poll_bench, BenchState};
+use crate::build::{poll_build_git, BuildState, GitJobs};
+use crate::clipboard::{copy_message, copy_text, paste_text};
+use crate::compat::Compat;
+use crate::controller::controller;
+use crate::diagnostics::{DiagState, DiagWatch};
+use crate::events::{handle_event, AppEvent};
+use crate::hf::{poll_hf, HfStep};
+use crate::hooks::{run_hooks, HookEvent};
+use crate::jobs::{Job, Jobs};
+use crate::log::{LogLine, LOG_CAPACITY};
+use crate::macros::{load_macros, macro_keys, save_macro, MacroState};
+use crate::modal::{ConfirmModal, DropdownModal, HelpModal, Modal, ModalResult, PromptModal};
+use crate::model_meta::{now_rfc3339, ModelMetaStore};
 use crate::models::ModelBrowser;
-use crate::providers::{DefaultProviderState, ProvidersState};
-use crate::readme::ReadmeState;
+use crate::mouse::{HitMap, HitTarget, Scroll};
+use crate::msg::{chord_msg, global_msg, macro_msg, Msg};
+use crate::page_data::PageData;
+use crate::providers::{
+    current_month, estimate_tokens, is_cloud_type, open_quick_default, spawn_default_health_check, tick_monitor,
+    ActiveStatus, DefaultHealth, DefaultProviderState, Generation, HealthMonitor, HealthStatus, LatencyBook,
+    ProvidersState, Reachability, RoutingState, TestMode, UsageBook,
+};
+use crate::pyenv::PyEnv;
+use crate::readme::{remember_docs, ReadmeState};
+use crate::recent::RecentPicks;
+use crate::session::{apply_resume, Session};
+use crate::settings::{poll_pyenvs, save_display, UserSettings};
 use crate::theme::Theme;
+use crate::toast::Toasts;
+use crate::tutorial::{self, Milestone, Tutorial};
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum Page {
@@ -13,64 +45,503 @@ pub enum Page {
     Readme,
     Configure,
     SelectDefault,
+    Routing,
     ModelBrowser,
     Diagnostics,
     Build,
     Settings,
+    WhatsNew,
+    Benchmarks,
+    CliLog,
+}
+
+/// Completion notice for a background task, delivered regardless of the active page.
+#[derive(Clone, Debug)]
+pub struct Notification {
+    pub message: String,
+    pub ok: bool,
+    pub origin: Page,
+    pub created: Instant,
+}
+
+impl Notification {
+    pub fn new(message: impl Into<String>, ok: bool, origin: Page) -> Self {
+        Self { message: message.into(), ok, origin, created: Instant::now() }
+    }
 }
 
 pub struct App {
     pub page: Page,
     pub menu_idx: usize,
-    pub show_help: bool,
     pub anim: bool,
     pub tick: u64,
     pub last_tick: Instant,
+    /// Last key, paste, mouse or resize event; see `is_idle`.
+    pub last_input: Instant,
     pub theme: Theme,
-    pub use_alt: bool,
     pub should_quit: bool,
-    pub diag: Option<DiagState>,
-    pub last_error: Option<String>,
-    pub model: Option<ModelBrowser>,
-    pub selected_model_id: Option<String>,
-    pub readme: Option<ReadmeState>,
+    pub diag: PageData<DiagState>,
+    /// Diagnostics: endpoint check of every configured provider.
+    pub reach: PageData<Vec<Reachability>>,
+    /// Diagnostics watch mode, while on.
+    pub diag_watch: Option<DiagWatch>,
+    /// Model Browser: running Hugging Face search, listing or download.
+    pub hf_job: Option<Job<HfStep>>,
+    pub bench: BenchState,
+    /// Settings: Python environments with chi_llm, found with `e`.
+    pub pyenvs: PageData<Vec<PyEnv>>,
+    /// Parts of a resumed session waiting for their page's data (see `session`).
+    pub resume: Session,
+    /// CLI Log: selected call, newest first.
+    pub cli_log_selected: usize,
+    /// Background health checks of every provider, when turned on.
+    pub monitor: Option<HealthMonitor>,
+    pub model: PageData<ModelBrowser>,
+    pub readme: PageData<ReadmeState>,
+    /// What's new page: bundled changelog or fetched release notes.
+    pub changelog: PageData<ReadmeState>,
     pub defaultp: Option<DefaultProviderState>,
-    pub providers: Option<ProvidersState>,
+    pub routing: Option<RoutingState>,
+    /// Prompt run from the Configure page (`g`), shown in a `GenerationModal`.
+    pub generation: Option<Generation>,
+    pub providers: PageData<ProvidersState>,
     pub build: Option<BuildState>,
+    /// Build's git status and ignore-check jobs.
+    pub build_git: GitJobs,
+    /// Running background jobs; cloned into page state that starts them.
+    pub jobs: Jobs,
+    /// Cloned into background threads so their results reach the UI.
+    pub notify_tx: Sender<Notification>,
+    pub notify_rx: Receiver<Notification>,
+    pub toasts: Toasts,
+    /// Open overlays, topmost last. The top modal receives all keys.
+    pub modals: Vec<Box<dyn Modal>>,
+    /// Cross-page events waiting for `process_events`.
+    pub events: VecDeque<AppEvent>,
+    /// Terminal quirk handling (see `compat`).
+    pub compat: Compat,
+    pub macros: MacroState,
+    /// User settings from `settings.toml`.
+    pub settings: UserSettings,
+    /// Where Settings changes are saved; `None` keeps them for this session only.
+    pub settings_path: Option<PathBuf>,
+    /// Cloned into background threads (e.g. hooks) to stream lines to the log panel.
+    pub log_tx: Sender<LogLine>,
+    pub log_rx: Receiver<LogLine>,
+    pub log: VecDeque<LogLine>,
+    pub show_log: bool,
+    /// Startup check of the default provider, shown in the header.
+    pub health: Option<DefaultHealth>,
+    /// Status strip: default provider and the config chi-llm reads.
+    pub active: ActiveStatus,
+    pub health_job: Option<Job<DefaultHealth>>,
+    /// Download and last-use times of catalog models (`model_meta.json`).
+    pub model_meta: ModelMetaStore,
+    /// Duration of each provider's last passed test (`latency.json`).
+    pub latency: LatencyBook,
+    /// Recently chosen dropdown values (`recent.json`).
+    pub recent: RecentPicks,
+    /// Cloud provider budgets and token counters (`usage.json`).
+    pub usage: UsageBook,
+    /// Linear, border-free rendering with announced state changes (`--a11y`).
+    pub a11y: bool,
+    pub announcer: Announcer,
+    /// Clickable areas of the last frame; drawing code fills it (see `mouse`).
+    pub hits: RefCell<HitMap>,
+    /// Running guided tutorial (see `tutorial`).
+    pub tutorial: Option<Tutorial>,
 }
 
 impl App {
-    pub fn new(use_alt: bool) -> Self {
+    pub fn new() -> Self {
+        let (notify_tx, notify_rx) = mpsc::channel();
+        let (log_tx, log_rx) = mpsc::channel();
         Self {
             page: Page::Welcome,
             menu_idx: 0,
-            show_help: false,
             anim: true,
             tick: 0,
             last_tick: Instant::now(),
+            last_input: Instant::now(),
             theme: Theme::synthwave_dark(),
-            use_alt,
             should_quit: false,
-            diag: None,
-            last_error: None,
-            model: None,
-            selected_model_id: None,
-            readme: None,
+            diag: PageData::NotLoaded,
+            reach: PageData::NotLoaded,
+            diag_watch: None,
+            hf_job: None,
+            bench: BenchState::default(),
+            pyenvs: PageData::NotLoaded,
+            resume: Session::default(),
+            cli_log_selected: 0,
+            monitor: None,
+            model: PageData::NotLoaded,
+            readme: PageData::NotLoaded,
+            changelog: PageData::NotLoaded,
             defaultp: None,
-            providers: None,
+            routing: None,
+            generation: None,
+            providers: PageData::NotLoaded,
             build: None,
+            build_git: GitJobs::default(),
+            jobs: Jobs::default(),
+            notify_tx,
+            notify_rx,
+            toasts: Toasts::default(),
+            modals: Vec::new(),
+            events: VecDeque::new(),
+            compat: Compat::default(),
+            macros: MacroState::default(),
+            settings: UserSettings::default(),
+            settings_path: None,
+            log_tx,
+            log_rx,
+            log: VecDeque::new(),
+            show_log: false,
+            health: None,
+            active: ActiveStatus::default(),
+            health_job: None,
+            model_meta: ModelMetaStore::load(),
+            latency: LatencyBook::load(),
+            recent: RecentPicks::load(),
+            usage: UsageBook::load(),
+            a11y: false,
+            announcer: Announcer::default(),
+            hits: RefCell::new(HitMap::default()),
+            tutorial: None,
+        }
+    }
+
+    /// True once there has been no input for `idle_after` and nothing is running
+    /// or replaying, so the loop can stop animating and poll slowly.
+    pub fn is_idle(&self) -> bool {
+        let Some(after) = self.settings.idle_after() else { return false };
+        self.last_input.elapsed() >= after && !self.is_busy() && !self.macros.is_replaying()
+    }
+
+    /// Drain pending notifications into the toast queue and expire stale toasts.
+    pub fn poll_notifications(&mut self) {
+        while let Ok(n) = self.notify_rx.try_recv() {
+            self.toasts.push(n);
+        }
+        self.toasts.expire();
+    }
+
+    /// Navigate to the page that produced the newest toast and dismiss it.
+    pub fn jump_to_toast(&mut self) {
+        if let Some(t) = self.toasts.dismiss_latest() {
+            self.page = t.origin;
+        }
+    }
+
+    /// Show a failure as a toast.
+    pub fn report_error(&mut self, message: impl Into<String>) {
+        let _ = self.notify_tx.send(Notification::new(message, false, self.page));
+    }
+
+    /// Queue a cross-page event; it is applied on the next `process_events`.
+    pub fn emit(&mut self, event: AppEvent) {
+        self.events.push_back(event);
+    }
+
+    /// Apply queued events, keeping those that cannot be handled yet.
+    pub fn process_events(&mut self) {
+        for event in std::mem::take(&mut self.events) {
+            if let Some(deferred) = handle_event(self, event) { self.events.push_back(deferred); }
+        }
+    }
+
+    /// Collect results of background tasks; runs every loop iteration regardless of page.
+    pub fn poll_background(&mut self) {
+        controller(self.page).tick(self);
+        self.readme.poll();
+        if self.page != Page::Readme { remember_docs(self); }
+        self.providers.poll();
+        self.model.poll();
+        self.diag.poll();
+        self.reach.poll();
+        poll_hf(self);
+        poll_bench(self);
+        tick_monitor(self);
+        poll_pyenvs(self);
+        poll_build_git(self);
+        apply_resume(self);
+        self.active.refresh();
+        if let Some(outcome) = self.providers.ready_mut().and_then(|st| st.poll_test()) {
+            self.record_usage(&outcome.entry_id, outcome.tokens);
+            if let Some(reply) = &outcome.reply {
+                let source = format!("test {}", outcome.entry_id);
+                for line in reply.trim().lines() { let _ = self.log_tx.send(LogLine::new(source.clone(), line, false)); }
+            }
+            if outcome.ok {
+                self.latency.record(&outcome.entry_id, outcome.elapsed, outcome.mode == TestMode::E2e);
+                if let Err(e) = self.latency.save() { self.report_error(format!("Saving test latency failed: {e}")); }
+                self.note_model_used(&outcome.entry_id);
+                self.run_hooks(HookEvent::Test { provider_id: outcome.entry_id });
+                tutorial::reached(self, Milestone::TestPassed);
+            }
+        }
+        tutorial::tick(self);
+        self.poll_generation();
+        if let Some(res) = self.health_job.as_ref().and_then(|job| job.poll()) {
+            self.health_job = None;
+            if let Ok(done) = res {
+                if done.status == HealthStatus::Failed {
+                    let _ = self.notify_tx.send(Notification::new(format!("Default provider {}: {}", done.name, done.detail), false, Page::Configure));
+                }
+                self.health = Some(done);
+            }
+        }
+        self.poll_log();
+        self.process_events();
+        self.poll_notifications();
+        self.replay_next_key();
+        if self.a11y { announce_changes(self); }
+    }
+
+    /// Pick up streamed prompt output; a finished run is billed like a test and
+    /// reported with a toast unless it was cancelled.
+    fn poll_generation(&mut self) {
+        let Some(g) = self.generation.as_mut() else { return };
+        if !g.poll() { return; }
+        let tokens = (estimate_tokens(&g.prompt), estimate_tokens(&g.output));
+        let (entry_id, ok) = (g.entry_id.clone(), matches!(g.result, Some(Ok(()))));
+        let message = match &g.result {
+            Some(Err(e)) => format!("Prompt to {} failed: {}", g.provider, e),
+            _ => format!("Prompt to {} finished ({} chars)", g.provider, g.output.chars().count()),
+        };
+        if !g.was_cancelled() { let _ = self.notify_tx.send(Notification::new(message, ok, Page::Configure)); }
+        if !g.output.is_empty() { self.record_usage(&entry_id, Some(tokens)); }
+        if ok { self.note_model_used(&entry_id); }
+    }
+
+    /// Kill the running provider test, if any, and say so in a toast.
+    pub fn cancel_test(&mut self) {
+        let cancelled = self.providers.ready_mut().is_some_and(|st| st.cancel_test());
+        let msg = if cancelled { "Test cancelled" } else { "No test running" };
+        let _ = self.notify_tx.send(Notification::new(msg, cancelled, Page::Configure));
+    }
+
+    /// Test the default provider in the background (run once at startup).
+    pub fn start_health_check(&mut self) {
+        if let Some((health, job)) = spawn_default_health_check(&self.jobs) {
+            self.health = Some(health);
+            self.health_job = job;
+        }
+    }
+
+    /// Stamp the model of provider `entry_id` as used now.
+    fn note_model_used(&mut self, entry_id: &str) {
+        let Some(entry) = self.providers.ready().and_then(|st| st.entries.iter().find(|e| e.id == entry_id)) else { return };
+        let Some(model) = entry.config.get("model").and_then(|m| m.as_str()).filter(|m| !m.is_empty()) else { return };
+        self.model_meta.note_used(model, &entry.name, &now_rfc3339());
+        if let Err(e) = self.model_meta.save() { self.report_error(format!("Saving model metadata failed: {e}")); }
+    }
+
+    /// Add tokens billed to a cloud provider to this month's counters.
+    fn record_usage(&mut self, entry_id: &str, tokens: Option<(u64, u64)>) {
+        let Some((input, output)) = tokens else { return };
+        let cloud = self.providers.ready().and_then(|st| st.entries.iter().find(|e| e.id == entry_id)).is_some_and(|e| is_cloud_type(&e.ptype));
+        if !cloud { return; }
+        self.usage.record(entry_id, &current_month(), input, output);
+        if let Err(e) = self.usage.save() { self.report_error(format!("Saving usage failed: {e}")); }
+    }
+
+    /// Start the configured hooks for `event`; output goes to the log panel.
+    pub fn run_hooks(&self, event: HookEvent) {
+        run_hooks(&self.settings.hooks, event, &self.jobs, self.log_tx.clone(), self.notify_tx.clone(), self.page);
+    }
+
+    /// Move streamed log lines into the panel buffer.
+    pub fn poll_log(&mut self) {
+        while let Ok(line) = self.log_rx.try_recv() {
+            if self.log.len() == LOG_CAPACITY { self.log.pop_front(); }
+            self.log.push_back(line);
+        }
+    }
+
+    /// True while any background job (page load, provider test, ...) runs.
+    pub fn is_busy(&self) -> bool {
+        self.jobs.is_busy()
+    }
+
+    /// Feed the next replayed macro key unless background work is pending.
+    fn replay_next_key(&mut self) {
+        if self.is_busy() { return; }
+        if let Some(key) = self.macros.replay.pop_front() {
+            self.dispatch_key(key);
+            if !self.macros.is_replaying() {
+                let _ = self.notify_tx.send(Notification::new("Macro finished", true, self.page));
+            }
+        }
+    }
+
+    fn toggle_recording(&mut self) {
+        let Some(keys) = self.macros.recording.take() else {
+            self.macros.recording = Some(Vec::new());
+            return;
+        };
+        if keys.is_empty() { return; }
+        self.modals.push(Box::new(PromptModal::new(format!("Save macro ({} keys) as", keys.len()), move |app, name| {
+            if !load_macros().contains_key(&name) { return app.store_macro(&name, &keys); }
+            let msg = format!("A macro named '{}' is already saved. Replace it?", name);
+            app.modals.push(Box::new(ConfirmModal::new("Replace macro", msg, move |app| app.store_macro(&name, &keys))));
+        })));
+    }
+
+    fn store_macro(&mut self, name: &str, keys: &[KeyEvent]) {
+        let (msg, ok) = match save_macro(name, keys) {
+            Ok(()) => (format!("Saved macro '{}'", name), true),
+            Err(e) => (format!("Saving macro failed: {}", e), false),
+        };
+        let _ = self.notify_tx.send(Notification::new(msg, ok, self.page));
+    }
+
+    /// Whether keys go to a popup or a field being typed in rather than to hotkeys.
+    fn input_captured(&self) -> bool {
+        !self.modals.is_empty() || controller(self.page).captures_input(self)
+    }
+
+    fn open_macro_picker(&mut self) {
+        let book = load_macros();
+        if book.is_empty() {
+            let _ = self.notify_tx.send(Notification::new("No saved macros; Ctrl+R records one", false, self.page));
+            return;
+        }
+        let names: Vec<String> = book.keys().cloned().collect();
+        self.modals.push(Box::new(DropdownModal::new("Replay macro", names, "", move |app, name| {
+            match macro_keys(&book, &name) {
+                Ok(keys) => app.macros.replay = keys.into(),
+                Err(e) => { let _ = app.notify_tx.send(Notification::new(e.to_string(), false, app.page)); }
+            }
+        })));
+    }
+
+    /// Handle a key typed by the user: macro hotkeys and recording, then `dispatch_key`.
+    pub fn handle_key(&mut self, key: KeyEvent) {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        // Typing during a replay stops it (Ctrl+C still quits)
+        if self.macros.is_replaying() && !(ctrl && key.code == KeyCode::Char('c')) {
+            self.macros.replay.clear();
+            let _ = self.notify_tx.send(Notification::new("Macro stopped", false, self.page));
+            return;
+        }
+        if let Some(msg) = macro_msg(key).filter(|_| !self.input_captured()) { self.update(msg); return; }
+        self.macros.record(key);
+        self.dispatch_key(key);
+    }
+
+    /// Route a bracketed paste to the topmost popup, or the active page when
+    /// none is open. Pastes are not recorded in macros.
+    pub fn handle_paste(&mut self, text: &str) {
+        if let Some(mut modal) = self.modals.pop() {
+            let depth = self.modals.len();
+            modal.handle_paste(self, text);
+            self.modals.insert(depth, modal);
+            return;
+        }
+        controller(self.page).handle_paste(self, text);
+    }
+
+    /// `Y`: the log panel's lines (test output, hooks, pulls) to the clipboard.
+    fn copy_log(&mut self) {
+        let (msg, ok) = if self.log.is_empty() {
+            ("The log is empty".to_string(), false)
+        } else {
+            let text: String = self.log.iter().map(|l| format!("[{}] {}\n", l.source, l.text)).collect();
+            copy_message(&format!("{} log line(s)", self.log.len()), copy_text(&text))
+        };
+        let _ = self.notify_tx.send(Notification::new(msg, ok, self.page));
+    }
+
+    /// Route a mouse event to the active page. Like pastes, mouse events are not
+    /// recorded in macros and are ignored while a popup is open.
+    pub fn handle_mouse(&mut self, ev: MouseEvent) {
+        if !self.modals.is_empty() { return; }
+        let page = controller(self.page);
+        match ev.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                let target = self.hits.borrow().at(ev.column, ev.row);
+                match target {
+                    Some(HitTarget::Toast(i)) => { self.toasts.dismiss(i); }
+                    Some(target) => { page.handle_click(self, target); }
+                    None => {}
+                }
+            }
+            MouseEventKind::ScrollUp => { page.handle_scroll(self, Scroll::Up); }
+            MouseEventKind::ScrollDown => { page.handle_scroll(self, Scroll::Down); }
+            _ => {}
+        }
+    }
+
+    /// Route a key to the active page, falling back to the global keymap.
+    pub fn dispatch_key(&mut self, key: KeyEvent) {
+        if let Some(msg) = chord_msg(key, self.tutorial.is_some()) { self.update(msg); return; }
+        if let Some(mut modal) = self.modals.pop() {
+            if key.code == KeyCode::Esc { return; }
+            // Re-insert below anything the modal opened while handling the key
+            let depth = self.modals.len();
+            if modal.handle_key(self, key) == ModalResult::Keep { self.modals.insert(depth, modal); }
+            return;
+        }
+
+        // The active page sees the key first; globals only apply to keys it leaves unhandled
+        let page = controller(self.page);
+        let captured = page.captures_input(self);
+        if let Some(msg) = page.handle_key(self, key) { self.update(msg); return; }
+        if captured { return; }
+        if let Some(msg) = global_msg(key) { self.update(msg); }
+    }
+
+    /// Carry out an app-level message (see `msg`).
+    pub fn update(&mut self, msg: Msg) {
+        match msg {
+            Msg::Handled => {}
+            Msg::Quit => self.should_quit = true,
+            Msg::Back => {
+                if self.page != Page::Welcome { self.page = Page::Welcome; }
+                else { self.should_quit = true; }
+            }
+            Msg::Goto(page) => self.page = page,
+            Msg::ToggleTheme => { self.theme.toggle(); save_display(self); }
+            Msg::ToggleAnimation => { self.anim = !self.anim; save_display(self); }
+            Msg::ToggleLog => self.show_log = !self.show_log,
+            Msg::CopyLog => self.copy_log(),
+            Msg::PasteClipboard => match paste_text() {
+                Ok(text) => self.handle_paste(&text),
+                Err(e) => { let _ = self.notify_tx.send(Notification::new(format!("Paste failed: {e}"), false, self.page)); }
+            },
+            Msg::Help => self.modals.push(Box::new(HelpModal)),
+            Msg::JumpToToast => self.jump_to_toast(),
+            Msg::DismissToast => { self.toasts.dismiss_latest(); }
+            Msg::QuickDefault => open_quick_default(self),
+            Msg::CancelTest => self.cancel_test(),
+            Msg::ToggleRecording => self.toggle_recording(),
+            Msg::OpenMacros => self.open_macro_picker(),
+            Msg::TutorialStart => tutorial::start(self),
+            Msg::TutorialSkip => tutorial::advance(self),
+            Msg::TutorialEnd => self.tutorial = None,
         }
     }
 }
 
+impl Default for App {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 pub const WELCOME_ITEMS: &[(&str, Page)] = &[
-    ("README", Page::Readme),
+    ("Tutorial", Page::Configure),
+    ("Docs", Page::Readme),
     ("Configure Providers", Page::Configure),
     ("Select Default", Page::SelectDefault),
+    ("Routing", Page::Routing),
     ("Diagnostics", Page::Diagnostics),
     ("Build Configuration", Page::Build),
     ("Settings", Page::Settings),
     ("Model Browser", Page::ModelBrowser),
+    ("Benchmarks", Page::Benchmarks),
+    ("CLI Log", Page::CliLog),
+    ("What's New", Page::WhatsNew),
     ("EXIT", Page::Welcome),
 ];
-
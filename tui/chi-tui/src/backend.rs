@@ -0,0 +1,196 @@
+//! Long-lived `chi-llm serve --stdio` session. The JSON data calls
+//! (`run_cli_json`: schema, model list, diagnostics) are sent to it as
+//! JSON-RPC 2.0 requests, one per line, instead of starting Python each time.
+//! A chi-llm without `serve`, a session that dies, or one busy with another
+//! call makes the caller spawn the CLI as before.
+
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde_json::{json, Value};
+
+use crate::util::{chi_llm_command, ChiLlmCommand, Failure, RunResult};
+
+/// How long a starting session may take to answer `version` (Python imports).
+const START_TIMEOUT: Duration = Duration::from_secs(15);
+
+struct Session {
+    child: Child,
+    stdin: ChildStdin,
+    lines: Receiver<String>,
+    next_id: u64,
+}
+
+impl Session {
+    fn start(cmd: &ChiLlmCommand) -> Option<Session> {
+        let mut child = Command::new(&cmd.program)
+            .args(&cmd.prefix)
+            .args(["serve", "--stdio"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .ok()?;
+        let stdin = child.stdin.take()?;
+        let stdout = child.stdout.take()?;
+        let (tx, lines) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let Ok(line) = line else { break };
+                if tx.send(line).is_err() { break; }
+            }
+        });
+        let mut session = Session { child, stdin, lines, next_id: 0 };
+        match session.request("version", json!({}), START_TIMEOUT, &AtomicBool::new(false)) {
+            Ok(v) if v.get("protocol").and_then(Value::as_u64) == Some(1) => Some(session),
+            _ => {
+                session.kill();
+                None
+            }
+        }
+    }
+
+    /// Send one request and wait for its result. `Failure::Io` means the
+    /// session is gone; a JSON-RPC error comes back as `Failure::Exit`.
+    fn request(&mut self, method: &str, params: Value, timeout: Duration, cancel: &AtomicBool) -> Result<Value, Failure> {
+        self.next_id += 1;
+        let id = self.next_id;
+        let line = json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params });
+        writeln!(self.stdin, "{}", line).and_then(|_| self.stdin.flush()).map_err(Failure::Io)?;
+        let started = Instant::now();
+        loop {
+            if cancel.load(Ordering::Relaxed) { return Err(Failure::Cancelled); }
+            let left = timeout.saturating_sub(started.elapsed());
+            if left.is_zero() { return Err(Failure::TimedOut); }
+            let line = match self.lines.recv_timeout(left.min(Duration::from_millis(100))) {
+                Ok(line) => line,
+                Err(RecvTimeoutError::Timeout) => continue,
+                Err(RecvTimeoutError::Disconnected) => return Err(Failure::Io(std::io::Error::other("chi-llm serve exited"))),
+            };
+            // Skip anything that is not the answer to this request
+            let Ok(reply) = serde_json::from_str::<Value>(&line) else { continue };
+            if reply.get("id").and_then(Value::as_u64) != Some(id) { continue; }
+            if let Some(err) = reply.get("error") {
+                let msg = err.get("message").and_then(Value::as_str).unwrap_or("request failed");
+                return Err(Failure::Exit(None, msg.to_string()));
+            }
+            return Ok(reply.get("result").cloned().unwrap_or(Value::Null));
+        }
+    }
+
+    fn kill(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+struct State {
+    enabled: bool,
+    session: Option<Session>,
+    /// Starting failed (no `serve` in this chi-llm); not retried until `reset_backend`.
+    unavailable: bool,
+}
+
+static STATE: Mutex<State> = Mutex::new(State { enabled: true, session: None, unavailable: false });
+
+/// Whether the session may be used (`[chi_llm] backend` in settings.toml).
+pub fn set_backend_enabled(enabled: bool) {
+    let mut state = STATE.lock().unwrap_or_else(|e| e.into_inner());
+    state.enabled = enabled;
+    if !enabled { stop(&mut state); }
+}
+
+/// Stop the session and try `serve` again on the next call; used when the
+/// `chi-llm` command changes.
+pub fn reset_backend() {
+    let mut state = STATE.lock().unwrap_or_else(|e| e.into_inner());
+    stop(&mut state);
+    state.unavailable = false;
+}
+
+fn stop(state: &mut State) {
+    if let Some(mut session) = state.session.take() { session.kill(); }
+}
+
+/// The session's state in words, for the Settings page.
+pub fn backend_status() -> &'static str {
+    let Ok(state) = STATE.try_lock() else { return "busy" };
+    match (state.enabled, state.unavailable, state.session.is_some()) {
+        (false, _, _) => "off",
+        (_, true, _) => "unavailable (chi-llm has no serve --stdio)",
+        (_, _, true) => "running",
+        _ => "not started",
+    }
+}
+
+/// Run `chi-llm <args>` in the session: exit code and stdout, or why it
+/// failed. `None` when the caller should spawn the CLI instead: the session
+/// is off, unavailable, busy with another call or exited mid-call.
+pub(crate) fn run_in_backend(args: &[&str], timeout: Duration, cancel: &AtomicBool) -> Option<RunResult<Vec<u8>>> {
+    let mut state = STATE.try_lock().ok()?;
+    if !state.enabled || state.unavailable { return None; }
+    if state.session.is_none() {
+        state.session = Session::start(&chi_llm_command());
+        if state.session.is_none() {
+            state.unavailable = true;
+            return None;
+        }
+    }
+    let session = state.session.as_mut()?;
+    let result = session.request("run", json!({ "args": args }), timeout, cancel);
+    let reply = match result {
+        Ok(reply) => reply,
+        // The call may still be running in there; start a fresh session next time
+        Err(failure @ (Failure::Cancelled | Failure::TimedOut)) => {
+            stop(&mut state);
+            return Some(Err(failure));
+        }
+        Err(Failure::Io(_)) => {
+            stop(&mut state);
+            return None;
+        }
+        Err(failure) => return Some(Err(failure)),
+    };
+    let code = reply.get("exit_code").and_then(Value::as_i64).map(|c| c as i32);
+    let text = |key: &str| reply.get(key).and_then(Value::as_str).unwrap_or_default().to_string();
+    Some(match code {
+        Some(0) => Ok((Some(0), text("stdout").into_bytes())),
+        code => {
+            let stderr = text("stderr");
+            Err(Failure::Exit(code, if stderr.trim().is_empty() { text("stdout") } else { stderr }))
+        }
+    })
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    /// A stand-in for `chi-llm serve --stdio` that answers every request with
+    /// the same result.
+    fn fake_server(result: &str) -> ChiLlmCommand {
+        let script = format!(
+            r#"while read -r line; do id=$(echo "$line" | sed 's/.*"id":\([0-9]*\).*/\1/'); echo '{{"jsonrpc":"2.0","id":'"$id"',"result":{}}}'; done"#,
+            result
+        );
+        ChiLlmCommand { program: "sh".to_string(), prefix: vec!["-c".to_string(), script, "sh".to_string()] }
+    }
+
+    #[test]
+    fn session_answers_requests_by_id() {
+        let cmd = fake_server(r#"{"protocol":1,"exit_code":0,"stdout":"[]","stderr":""}"#);
+        let mut session = Session::start(&cmd).expect("fake server starts");
+        let reply = session.request("run", json!({ "args": ["models", "list", "--json"] }), Duration::from_secs(5), &AtomicBool::new(false));
+        assert_eq!(reply.ok().and_then(|r| r.get("stdout").cloned()), Some(json!("[]")));
+        session.kill();
+
+        // A CLI without `serve` exits at once
+        let old = ChiLlmCommand { program: "sh".to_string(), prefix: vec!["-c".to_string(), "exit 2".to_string()] };
+        assert!(Session::start(&old).is_none());
+    }
+}
@@ -0,0 +1,135 @@
+//! Batch writes (`p` on the Build page): the default provider's config written
+//! as `.chi_llm.json` (or `.chi_llm.yaml`) into many project directories at once, with one result per
+//! directory, for standardising chi-llm config across repositories.
+
+use std::fs;
+use std::path::PathBuf;
+
+use crate::app::{App, Notification, Page};
+use crate::build::{active_provider, secret_check, BuildState, ConfigBlocks, ConfigFormat};
+use crate::hooks::HookEvent;
+use crate::modal::{ConfirmModal, TextEditorModal};
+
+/// Outcome for one directory: the written file or why it failed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BatchResult {
+    pub dir: PathBuf,
+    pub result: Result<PathBuf, String>,
+}
+
+/// Directories from the batch editor: one per line, `@file` reads a list from
+/// a file (same format, without nesting). Blank lines and `#` comments are
+/// skipped, as are repeated entries.
+pub fn parse_project_list(text: &str) -> Result<Vec<PathBuf>, String> {
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    for line in text.lines() {
+        match line.trim().strip_prefix('@') {
+            Some(file) => {
+                let list = fs::read_to_string(file.trim()).map_err(|e| format!("{}: {}", file.trim(), e))?;
+                for l in list.lines() { add_dir(l, &mut dirs); }
+            }
+            None => add_dir(line, &mut dirs),
+        }
+    }
+    Ok(dirs)
+}
+
+fn add_dir(line: &str, dirs: &mut Vec<PathBuf>) {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') { return; }
+    let dir = PathBuf::from(line);
+    if !dirs.contains(&dir) { dirs.push(dir); }
+}
+
+/// Write `cfg` as the project config in `format` into each of `dirs`, which
+/// must exist.
+pub fn write_to_projects(dirs: &[PathBuf], format: ConfigFormat, cfg: &ConfigBlocks) -> Vec<BatchResult> {
+    let json = format.render(&cfg.to_json()).unwrap_or_default();
+    dirs.iter().map(|dir| {
+        let result = if dir.is_dir() {
+            let path = dir.join(format.project_file());
+            fs::write(&path, &json).map(|_| path).map_err(|e| e.to_string())
+        } else {
+            Err("not a directory".to_string())
+        };
+        BatchResult { dir: dir.clone(), result }
+    }).collect()
+}
+
+/// Open the directory list editor, pre-filled with the last list.
+pub fn open_batch_editor(app: &mut App) {
+    let last = app.build.as_ref().map(|b| b.batch_dirs.clone()).unwrap_or_default();
+    let text = if last.is_empty() { "# One project directory per line; @file reads a list\n".to_string() } else { last };
+    app.modals.push(Box::new(TextEditorModal::new("Write default provider to projects", &text, |app, text| {
+        let dirs = parse_project_list(text)?;
+        if dirs.is_empty() { return Err("no directories listed".to_string()); }
+        app.build.get_or_insert_with(BuildState::default).batch_dirs = text.to_string();
+        start_batch(app, dirs);
+        Ok(())
+    })));
+}
+
+/// Write to `dirs`, honouring the page's `${ENV}` setting; plaintext secrets
+/// ask first, since they would land in every repository.
+fn start_batch(app: &mut App, dirs: Vec<PathBuf>) {
+    let st = app.build.get_or_insert_with(BuildState::default);
+    let env_refs = st.env_refs;
+    let mut cfg = match active_provider() {
+        Ok((_, cfg)) => cfg,
+        Err(e) => { st.status = Some(format!("Error: {}", e)); return; }
+    };
+    if env_refs {
+        cfg.secrets_to_env_refs(secret_check(app));
+    } else {
+        let secrets = cfg.plaintext_secrets(secret_check(app));
+        if !secrets.is_empty() {
+            let msg = format!("Write plaintext secrets ({}) into {} projects? ('e' on Build uses ${{ENV}} references instead)", secrets.join(", "), dirs.len());
+            app.modals.push(Box::new(ConfirmModal::new("Secrets in project configs", msg, move |app| finish_batch(app, &dirs, &cfg))));
+            return;
+        }
+    }
+    finish_batch(app, &dirs, &cfg);
+}
+
+fn finish_batch(app: &mut App, dirs: &[PathBuf], cfg: &ConfigBlocks) {
+    let format = app.build.as_ref().map(|b| b.format).unwrap_or_default();
+    let results = write_to_projects(dirs, format, cfg);
+    let failed = results.iter().filter(|r| r.result.is_err()).count();
+    let msg = format!("Batch: {} written, {} failed", results.len() - failed, failed);
+    for path in results.iter().filter_map(|r| r.result.as_ref().ok()) {
+        app.run_hooks(HookEvent::Write { path: path.display().to_string() });
+    }
+    let st = app.build.get_or_insert_with(BuildState::default);
+    st.status = Some(msg.clone());
+    st.batch = results;
+    let _ = app.notify_tx.send(Notification::new(msg, failed == 0, Page::Build));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::paths::PROJECT_CONFIG;
+
+    #[test]
+    fn lists_are_read_and_every_directory_gets_a_result() {
+        let tmp = std::env::temp_dir().join(format!("chi-tui-batch-{}", std::process::id()));
+        let (a, b) = (tmp.join("a"), tmp.join("b"));
+        fs::create_dir_all(&a).unwrap();
+        fs::create_dir_all(&b).unwrap();
+        let list = tmp.join("list.txt");
+        fs::write(&list, format!("{}\n# skipped\n{}\n", b.display(), a.display())).unwrap();
+        let text = format!("{}\n\n@{}\n{}\n", a.display(), list.display(), tmp.join("missing").display());
+        let dirs = parse_project_list(&text).unwrap();
+        assert_eq!(dirs, [a.clone(), b.clone(), tmp.join("missing")]);
+        assert!(parse_project_list("@/no/such/list").is_err());
+
+        let cfg = ConfigBlocks { provider: serde_json::from_value(serde_json::json!({"type": "ollama"})).unwrap(), profiles: Vec::new() };
+        let results = write_to_projects(&dirs, ConfigFormat::Json, &cfg);
+        assert_eq!(results[0].result, Ok(a.join(PROJECT_CONFIG)));
+        assert!(fs::read_to_string(b.join(PROJECT_CONFIG)).unwrap().contains("\"ollama\""));
+        assert_eq!(results[2].result, Err("not a directory".to_string()));
+        write_to_projects(&dirs[..1], ConfigFormat::Yaml, &cfg);
+        assert_eq!(fs::read_to_string(a.join(".chi_llm.yaml")).unwrap(), "provider:\n  type: ollama\n");
+        fs::remove_dir_all(&tmp).unwrap();
+    }
+}
@@ -0,0 +1,360 @@
+//! Benchmarks page: a fixed prompt run N times against every enabled provider
+//! through `chi-llm generate --stream`, one provider after another so runs do
+//! not compete for the same machine. Each run records the wall time, the time
+//! to the first streamed chunk and an estimated token count, shown as a table
+//! with a latency sparkline per provider.
+//!
+//! Cloud providers cost money: a run asks first, skips those already at their
+//! monthly budget and adds the tokens it used to their usage counters.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::layout::Rect;
+use ratatui::prelude::Frame;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph};
+
+use crate::app::{App, Notification, Page};
+use crate::controller::PageController;
+use crate::jobs::{Job, JobCtx};
+use crate::keymap::KeyAction;
+use crate::modal::{ConfirmModal, PromptModal};
+use crate::msg::Msg;
+use crate::providers::{
+    budget_status, build_generate_args, current_month, estimate_tokens, is_cloud_type, load_scratch_entries, plugin_for,
+    ProviderScratchEntry,
+};
+use crate::text::{pad, truncate};
+
+/// Prompt every run sends; long enough an answer to measure throughput.
+pub const BENCH_PROMPT: &str = "Explain in three short paragraphs what a large language model is.";
+/// `--max-tokens` for benchmark runs.
+pub const BENCH_MAX_TOKENS: &str = "128";
+/// Runs per provider unless changed with `n`.
+pub const BENCH_RUNS: usize = 3;
+const MAX_RUNS: usize = 20;
+const SPARK: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// One generate call.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BenchRun {
+    pub elapsed: Duration,
+    /// Time to the first streamed chunk.
+    pub first_chunk: Option<Duration>,
+    /// Estimated tokens in the reply.
+    pub tokens: u64,
+}
+
+impl BenchRun {
+    /// Tokens per second after the first chunk (the whole run when the reply
+    /// came in one piece).
+    pub fn tokens_per_sec(&self) -> f64 {
+        let streaming = self.elapsed.saturating_sub(self.first_chunk.unwrap_or_default());
+        let secs = if streaming.as_millis() > 0 { streaming } else { self.elapsed }.as_secs_f64();
+        if secs > 0.0 { self.tokens as f64 / secs } else { 0.0 }
+    }
+}
+
+/// All runs of one provider; `error` is why the rest were skipped.
+#[derive(Clone, Debug, Default)]
+pub struct BenchResult {
+    /// Provider id, for the usage counters.
+    pub id: String,
+    pub name: String,
+    pub ptype: String,
+    pub runs: Vec<BenchRun>,
+    pub error: Option<String>,
+}
+
+impl BenchResult {
+    /// Median wall time.
+    pub fn median(&self) -> Option<Duration> {
+        let mut all: Vec<Duration> = self.runs.iter().map(|r| r.elapsed).collect();
+        all.sort();
+        all.get(all.len() / 2).copied()
+    }
+
+    pub fn min_max(&self) -> Option<(Duration, Duration)> {
+        let all = self.runs.iter().map(|r| r.elapsed);
+        Some((all.clone().min()?, all.max()?))
+    }
+
+    /// Mean time to the first chunk.
+    pub fn first_chunk(&self) -> Option<Duration> {
+        let all: Vec<Duration> = self.runs.iter().filter_map(|r| r.first_chunk).collect();
+        (!all.is_empty()).then(|| all.iter().sum::<Duration>() / all.len() as u32)
+    }
+
+    pub fn tokens_per_sec(&self) -> Option<f64> {
+        (!self.runs.is_empty()).then(|| self.runs.iter().map(BenchRun::tokens_per_sec).sum::<f64>() / self.runs.len() as f64)
+    }
+
+    /// Estimated input and output tokens of the completed runs.
+    pub fn usage(&self) -> (u64, u64) {
+        let input = estimate_tokens(BENCH_PROMPT) * self.runs.len() as u64;
+        (input, self.runs.iter().map(|r| r.tokens).sum())
+    }
+}
+
+/// Run latencies as block characters, scaled to `max`.
+pub fn sparkline(runs: &[BenchRun], max: Duration) -> String {
+    let max = max.as_secs_f64();
+    runs.iter().map(|r| {
+        let level = if max > 0.0 { (r.elapsed.as_secs_f64() / max * (SPARK.len() - 1) as f64).round() as usize } else { 0 };
+        SPARK[level.min(SPARK.len() - 1)]
+    }).collect()
+}
+
+pub struct BenchState {
+    pub runs: usize,
+    pub results: Vec<BenchResult>,
+    /// Providers still to benchmark, in list order.
+    pub queue: VecDeque<ProviderScratchEntry>,
+    /// The provider being benchmarked and its job.
+    pub running: Option<(String, Job<BenchResult>)>,
+}
+
+impl Default for BenchState {
+    fn default() -> Self {
+        Self { runs: BENCH_RUNS, results: Vec::new(), queue: VecDeque::new(), running: None }
+    }
+}
+
+impl BenchState {
+    pub fn is_running(&self) -> bool {
+        self.running.is_some()
+    }
+
+    /// The result with the lowest median latency.
+    pub fn fastest(&self) -> Option<&BenchResult> {
+        self.results.iter().filter(|r| r.error.is_none()).filter_map(|r| r.median().map(|m| (m, r))).min_by_key(|(m, _)| *m).map(|(_, r)| r)
+    }
+}
+
+/// Time `runs` generate calls against `entry`, stopping at the first failure.
+fn bench_provider(entry: &ProviderScratchEntry, runs: usize, ctx: &JobCtx) -> BenchResult {
+    let mut result = BenchResult { id: entry.id.clone(), name: entry.name.clone(), ptype: entry.ptype.clone(), ..Default::default() };
+    let cmd = match build_generate_args(entry, BENCH_PROMPT, BENCH_MAX_TOKENS) {
+        Ok(cmd) => cmd,
+        Err(e) => {
+            result.error = Some(e);
+            return result;
+        }
+    };
+    for i in 0..runs {
+        ctx.progress(format!("run {}/{}", i + 1, runs));
+        let started = Instant::now();
+        let mut first_chunk = None;
+        let mut reply = String::new();
+        let done = cmd.stream(ctx.cancel_flag(), |chunk| {
+            first_chunk.get_or_insert_with(|| started.elapsed());
+            reply.push_str(chunk);
+        });
+        if let Err(e) = done {
+            result.error = Some(e.to_string());
+            break;
+        }
+        result.runs.push(BenchRun { elapsed: started.elapsed(), first_chunk, tokens: estimate_tokens(&reply) });
+    }
+    result
+}
+
+/// Start benchmarking the next queued provider, if any.
+fn start_next(app: &mut App) {
+    let Some(entry) = app.bench.queue.pop_front() else { return };
+    let runs = app.bench.runs;
+    let name = entry.name.clone();
+    let job = app.jobs.spawn(format!("Benchmarking {}", name), move |ctx| bench_provider(&entry, runs, ctx));
+    app.bench.running = Some((name, job));
+}
+
+/// `r`: benchmark every enabled provider that can run prompts, after asking
+/// when some of them are billed.
+pub fn start_bench(app: &mut App) {
+    let entries = match load_scratch_entries() {
+        Ok(entries) => entries,
+        Err(e) => {
+            let _ = app.notify_tx.send(Notification::new(format!("Cannot read providers: {}", e), false, Page::Benchmarks));
+            return;
+        }
+    };
+    let mut skipped = Vec::new();
+    let mut queue = VecDeque::new();
+    for e in entries.into_iter().filter(|e| !e.disabled && plugin_for(&e.ptype).is_none()) {
+        let model = e.config.get("model").and_then(|m| m.as_str()).unwrap_or_default();
+        let spent = budget_status(&app.usage, &e.id, model, &app.settings.pricing).filter(|_| is_cloud_type(&e.ptype));
+        match spent {
+            Some(b) if b.ratio() >= 1.0 => skipped.push(BenchResult {
+                id: e.id.clone(), name: e.name.clone(), ptype: e.ptype.clone(),
+                error: Some(format!("skipped: ${:.2} of its ${:.2} monthly budget spent", b.spent, b.budget)),
+                ..Default::default()
+            }),
+            _ => queue.push_back(e),
+        }
+    }
+    if queue.is_empty() && skipped.is_empty() {
+        let _ = app.notify_tx.send(Notification::new("No enabled providers to benchmark", false, Page::Benchmarks));
+        return;
+    }
+    let cloud: Vec<&str> = queue.iter().filter(|e| is_cloud_type(&e.ptype)).map(|e| e.name.as_str()).collect();
+    if cloud.is_empty() {
+        begin(app, queue, skipped);
+        return;
+    }
+    let msg = format!(
+        "{} run(s) of up to {} tokens each go to paid providers: {}. Start the benchmark?",
+        app.bench.runs, BENCH_MAX_TOKENS, cloud.join(", "),
+    );
+    app.modals.push(Box::new(ConfirmModal::new("Benchmark paid providers", msg, move |app| begin(app, queue, skipped))));
+}
+
+fn begin(app: &mut App, queue: VecDeque<ProviderScratchEntry>, skipped: Vec<BenchResult>) {
+    stop_bench(app);
+    app.bench.results = skipped;
+    app.bench.queue = queue;
+    start_next(app);
+}
+
+/// `x`: stop the running provider and drop the rest of the queue.
+pub fn stop_bench(app: &mut App) {
+    if let Some((_, job)) = app.bench.running.take() { job.cancel(); }
+    app.bench.queue.clear();
+}
+
+/// Collect a finished provider and start the next; runs on every page.
+pub fn poll_bench(app: &mut App) {
+    let Some(done) = app.bench.running.as_ref().and_then(|(_, job)| job.poll()) else { return };
+    let name = app.bench.running.take().map(|(name, _)| name).unwrap_or_default();
+    let result = done.unwrap_or_else(|e| BenchResult { name, error: Some(e), ..Default::default() });
+    let (input, output) = result.usage();
+    if is_cloud_type(&result.ptype) && !result.runs.is_empty() {
+        app.usage.record(&result.id, &current_month(), input, output);
+        if let Err(e) = app.usage.save() { app.report_error(format!("Saving usage failed: {e}")); }
+    }
+    app.bench.results.push(result);
+    start_next(app);
+    if app.bench.is_running() { return; }
+    let msg = match app.bench.fastest() {
+        Some(best) => format!("Benchmark finished; fastest: {} ({:.1}s median)", best.name, best.median().unwrap_or_default().as_secs_f64()),
+        None => "Benchmark finished; no provider completed a run".to_string(),
+    };
+    let ok = app.bench.fastest().is_some();
+    let _ = app.notify_tx.send(Notification::new(msg, ok, Page::Benchmarks));
+}
+
+fn prompt_runs(app: &mut App) {
+    let title = format!("Runs per provider, 1-{} (now {})", MAX_RUNS, app.bench.runs);
+    app.modals.push(Box::new(PromptModal::new(title, |app, text| {
+        match text.trim().parse::<usize>() {
+            Ok(n) if (1..=MAX_RUNS).contains(&n) => app.bench.runs = n,
+            _ => { let _ = app.notify_tx.send(Notification::new(format!("Invalid number of runs: {}", text), false, Page::Benchmarks)); }
+        }
+    })));
+}
+
+fn secs(d: Option<Duration>) -> String {
+    d.map(|d| format!("{:.2}s", d.as_secs_f64())).unwrap_or_else(|| "-".to_string())
+}
+
+fn result_line(r: &BenchResult, max: Duration, fastest: bool, app: &App) -> Line<'static> {
+    let range = r.min_max().map(|(lo, hi)| format!("{:.2}–{:.2}s", lo.as_secs_f64(), hi.as_secs_f64())).unwrap_or_else(|| "-".to_string());
+    let tps = r.tokens_per_sec().map(|t| format!("{:.1}", t)).unwrap_or_else(|| "-".to_string());
+    let mut text = format!(
+        "{} {} {} {} {} {} {} {}",
+        pad(&truncate(&r.name, 20), 20), pad(&r.ptype, 10), pad(&r.runs.len().to_string(), 4),
+        pad(&secs(r.median()), 9), pad(&range, 13), pad(&secs(r.first_chunk()), 11), pad(&tps, 7), sparkline(&r.runs, max),
+    );
+    if let Some(e) = &r.error { text.push_str(&format!("  ✗ {}", e.lines().next().unwrap_or(e))); }
+    let style = if r.error.is_some() { app.theme.error() } else if fastest { app.theme.success() } else { app.theme.text() };
+    Line::from(Span::styled(text, style))
+}
+
+pub fn draw_bench(f: &mut Frame, area: Rect, app: &App) {
+    let b = &app.bench;
+    let mut lines = vec![
+        Line::from(Span::styled(format!("Prompt: \"{}\" • --max-tokens {} • {} runs per provider", BENCH_PROMPT, BENCH_MAX_TOKENS, b.runs), app.theme.hint())),
+        Line::from(""),
+        Line::from(Span::styled(
+            format!("{} {} {} {} {} {} {} {}", pad("Provider", 20), pad("Type", 10), pad("Runs", 4), pad("Median", 9), pad("Min–max", 13), pad("First chunk", 11), pad("tok/s", 7), "Runs"),
+            app.theme.title(),
+        )),
+    ];
+    let max = b.results.iter().flat_map(|r| r.runs.iter().map(|run| run.elapsed)).max().unwrap_or_default();
+    let fastest = b.fastest().map(|r| r.name.clone());
+    lines.extend(b.results.iter().map(|r| result_line(r, max, fastest.as_deref() == Some(r.name.as_str()), app)));
+    if let Some((name, _)) = &b.running {
+        lines.push(Line::from(Span::styled(format!("{} running…", pad(&truncate(name, 20), 20)), app.theme.hint())));
+    }
+    lines.extend(b.queue.iter().map(|e| Line::from(Span::styled(format!("{} queued", pad(&truncate(&e.name, 20), 20)), app.theme.disabled()))));
+    if b.results.is_empty() && !b.is_running() {
+        lines.push(Line::from(Span::styled("Press r to benchmark every enabled provider.", app.theme.hint())));
+    }
+    if let Some(best) = b.fastest().filter(|_| !b.is_running()) {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(format!("Fastest: {} • make it the default on Select Default (3)", best.name), app.theme.success())));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled("tok/s counts estimated reply tokens after the first chunk.", app.theme.hint())));
+    let p = Paragraph::new(lines)
+        .style(app.theme.base())
+        .block(Block::default().borders(Borders::ALL).border_style(app.theme.border()).title("Benchmarks"));
+    f.render_widget(p, area);
+}
+
+pub struct BenchmarksPage;
+
+impl PageController for BenchmarksPage {
+    fn handle_key(&self, app: &mut App, key: KeyEvent) -> Option<Msg> {
+        match key.code {
+            KeyCode::Char('r') | KeyCode::Char('R') => start_bench(app),
+            KeyCode::Char('x') | KeyCode::Char('X') if app.bench.is_running() => stop_bench(app),
+            KeyCode::Char('n') | KeyCode::Char('N') => prompt_runs(app),
+            _ => return None,
+        }
+        Some(Msg::Handled)
+    }
+
+    fn draw(&self, f: &mut Frame, area: Rect, app: &App) {
+        draw_bench(f, area, app);
+    }
+
+    fn actions(&self) -> &'static [KeyAction] {
+        const ACTIONS: &[KeyAction] = &[
+            KeyAction::new("r", "run").when(|app| !app.bench.is_running()),
+            KeyAction::new("r", "restart").when(|app| app.bench.is_running()),
+            KeyAction::new("x", "stop").when(|app| app.bench.is_running()),
+            KeyAction::new("n", "runs per provider"),
+            KeyAction::new("Esc", "back"),
+            KeyAction::new("?", "help"),
+        ];
+        ACTIONS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(ms: u64, first_ms: Option<u64>, tokens: u64) -> BenchRun {
+        BenchRun { elapsed: Duration::from_millis(ms), first_chunk: first_ms.map(Duration::from_millis), tokens }
+    }
+
+    #[test]
+    fn results_summarize_runs_and_pick_the_fastest() {
+        let slow = BenchResult { name: "slow".into(), runs: vec![run(3000, Some(1000), 100), run(2000, Some(1000), 100), run(4000, None, 100)], ..Default::default() };
+        assert_eq!(slow.median(), Some(Duration::from_millis(3000)));
+        assert_eq!(slow.min_max(), Some((Duration::from_millis(2000), Duration::from_millis(4000))));
+        assert_eq!(slow.first_chunk(), Some(Duration::from_millis(1000)));
+        assert_eq!(run(3000, Some(1000), 100).tokens_per_sec(), 50.0);
+        assert_eq!(sparkline(&slow.runs, Duration::from_millis(4000)), "▆▅█");
+        // Billed as the prompt once per run plus the replies
+        assert_eq!(slow.usage(), (3 * estimate_tokens(BENCH_PROMPT), 300));
+
+        let fast = BenchResult { name: "fast".into(), runs: vec![run(500, Some(100), 10)], ..Default::default() };
+        let broken = BenchResult { name: "broken".into(), error: Some("connection refused".into()), ..Default::default() };
+        let state = BenchState { results: vec![slow, broken, fast], ..Default::default() };
+        assert_eq!(state.fastest().map(|r| r.name.as_str()), Some("fast"));
+    }
+}
@@ -1,27 +1,137 @@
+use std::path::{Path, PathBuf};
+
 use anyhow::{anyhow, Result};
+use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::layout::Rect;
 use ratatui::prelude::Frame;
-use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Paragraph, Wrap};
 use serde_json::Value;
 
-use crate::app::App;
+use crate::app::{App, Notification, Page};
+use crate::batch::{open_batch_editor, BatchResult};
+use crate::clipboard::{copy_message, copy_text};
+use crate::diff::{diff_values, Change, FieldChange};
+use crate::controller::PageController;
+use crate::git::{add_to_gitignore, commit_file, file_diff, file_status, FileStatus};
+use crate::highlight::{highlight_line, Lang};
+use crate::hooks::HookEvent;
+use crate::jobs::Job;
+use crate::keychain::keyring_account;
+use crate::keymap::KeyAction;
+use crate::modal::{ActionModal, ConfirmModal, TextViewModal};
+use crate::model_meta::now_rfc3339;
+use crate::msg::Msg;
+use crate::paths::{paths, PROJECT_CONFIG, PROJECT_CONFIG_LOOKUP, PROJECT_CONFIG_YAML, SCRATCH_FILE};
+use crate::providers::{is_secret_key, load_providers_state, read_config_file, write_json_schemas, ProviderScratchEntry};
+use crate::tutorial::{self, Milestone};
+use crate::util::invalidate_cli_cache;
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default, clap::ValueEnum)]
 pub enum BuildTarget {
     #[default]
     Project,
     Global,
 }
 
+/// File format of the project config. chi-llm reads JSON and YAML project
+/// files; the global config is always JSON.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum ConfigFormat {
+    #[default]
+    Json,
+    Yaml,
+}
+
+impl ConfigFormat {
+    pub fn project_file(self) -> &'static str {
+        match self {
+            ConfigFormat::Json => PROJECT_CONFIG,
+            ConfigFormat::Yaml => PROJECT_CONFIG_YAML,
+        }
+    }
+
+    pub fn render(self, config: &Value) -> Result<Vec<u8>> {
+        Ok(match self {
+            ConfigFormat::Json => serde_json::to_vec_pretty(config)?,
+            ConfigFormat::Yaml => serde_yaml::to_string(config)?.into_bytes(),
+        })
+    }
+}
+
+/// A project config file that chi-llm reads instead of `written`, because it
+/// comes first in its lookup order.
+pub fn shadowing_config(written: &str) -> Option<&'static str> {
+    PROJECT_CONFIG_LOOKUP.iter().take_while(|f| **f != written).find(|f| Path::new(f).exists()).copied()
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct BuildState {
     pub target: BuildTarget,
+    /// Project config format (`f` toggles); Global always writes JSON.
+    pub format: ConfigFormat,
     pub status: Option<String>,
+    /// Git status of `.chi_llm.json`; refreshed on entry and after writes/commits.
+    pub git: Option<FileStatus>,
+    /// Diff of the last project write.
+    pub diff: Option<String>,
+    /// Write secrets as `${VAR}` references instead of literal values.
+    pub env_refs: bool,
+    /// Shell `export` lines for the secrets replaced by the last write.
+    pub exports: Vec<String>,
+    /// Directory list last entered for a batch write (`p`).
+    pub batch_dirs: String,
+    /// Per-directory results of the last batch write.
+    pub batch: Vec<BatchResult>,
+    /// What a write would change, for the status line; see `PendingWrite`.
+    pub pending: Option<PendingWrite>,
+}
+
+/// Number of fields a write would change in `path` (`None`: not written yet),
+/// computed in `tick` for the target, format, `${ENV}` setting and file
+/// generation (`ActiveStatus::generation`) it was made for, so drawing does not
+/// read the files on every frame.
+#[derive(Clone, Debug)]
+pub struct PendingWrite {
+    made_for: (BuildTarget, ConfigFormat, bool, u64),
+    pub path: PathBuf,
+    pub changes: Result<Option<usize>, String>,
+}
+
+impl PendingWrite {
+    fn key(app: &App, st: &BuildState) -> (BuildTarget, ConfigFormat, bool, u64) {
+        (st.target, st.format, st.env_refs, app.active.generation())
+    }
+
+    /// Recompute when what it was made for changed.
+    pub fn refresh(app: &mut App) {
+        let Some(st) = app.build.as_ref() else { return };
+        let key = Self::key(app, st);
+        if st.pending.as_ref().is_some_and(|p| p.made_for == key) { return; }
+        let pending = config_path(st.target, st.format).ok().map(|path| {
+            let changes = pending_changes(app, &path).map(|c| c.map(|c| c.len())).map_err(|e| e.to_string());
+            PendingWrite { made_for: key, path, changes }
+        });
+        if let Some(st) = app.build.as_mut() { st.pending = pending; }
+    }
 }
 
 impl BuildState {
+    /// The project config file Build writes and tracks in git.
+    pub fn project_file(&self) -> &'static str {
+        self.format.project_file()
+    }
+
+    pub fn toggle_format(&mut self) {
+        self.format = match self.format {
+            ConfigFormat::Json => ConfigFormat::Yaml,
+            ConfigFormat::Yaml => ConfigFormat::Json,
+        };
+        // Git status and diff were for the other file
+        self.git = None;
+        self.diff = None;
+    }
+
     pub fn toggle_target(&mut self) {
         self.target = match self.target {
             BuildTarget::Project => BuildTarget::Global,
@@ -37,15 +147,18 @@ pub fn draw_build_config(f: &mut Frame, area: Rect, app: &App) {
         .as_ref()
         .map(|b| b.target)
         .unwrap_or(BuildTarget::Project);
+    let project_file = app.build.as_ref().map_or(PROJECT_CONFIG, |b| b.project_file());
     lines.push(Line::from(Span::styled(
         "Build/Write Configuration",
-        Style::default()
-            .fg(app.theme.primary)
-            .add_modifier(Modifier::BOLD),
+        app.theme.title(),
     )));
+    // Native separators, so Windows shows the real path
     lines.push(Line::from(match target {
-        BuildTarget::Project => "Target: Project (.chi_llm.json)",
-        BuildTarget::Global => "Target: Global (~/.cache/chi_llm/model_config.json)",
+        BuildTarget::Project => format!("Target: Project ({}; 'f' switches JSON/YAML)", project_file),
+        BuildTarget::Global => format!(
+            "Target: Global ({}, always JSON)",
+            paths().global_config().map(|p| p.display().to_string()).unwrap_or_else(|| "home dir not found".to_string())
+        ),
     }));
     // Show default provider summary
     match get_default_provider_summary() {
@@ -55,26 +168,87 @@ pub fn draw_build_config(f: &mut Frame, area: Rect, app: &App) {
         ))),
         Err(e) => lines.push(Line::from(Span::styled(
             format!("Default provider not set: {}", e),
-            Style::default().fg(Color::Red),
+            app.theme.error(),
         ))),
     }
+    if let Ok((_, cfg)) = active_provider() {
+        let mut tags: Vec<&str> = cfg.profiles.iter()
+            .flat_map(|p| p.get("tags").and_then(|t| t.as_array()).into_iter().flatten())
+            .filter_map(|t| t.as_str())
+            .collect();
+        tags.sort_unstable();
+        tags.dedup();
+        if !cfg.profiles.is_empty() {
+            lines.push(Line::from(format!("Routing: {} provider_profiles for tags {} (5 edits)", cfg.profiles.len(), tags.join(", "))));
+        }
+    }
+    if let Some(PendingWrite { path, changes, .. }) = app.build.as_ref().and_then(|b| b.pending.as_ref()) {
+        lines.push(match changes {
+            Ok(None) => Line::from(format!("{} is not written yet", path.display())),
+            Ok(Some(0)) => Line::from(Span::styled(format!("{} is up to date", path.display()), app.theme.success())),
+            Ok(Some(n)) => Line::from(format!("Writing changes {} field(s) in {} ('d' lists them)", n, path.display())),
+            Err(_) => Line::from(""),
+        });
+    }
+    if target == BuildTarget::Project {
+        if let Some(first) = shadowing_config(project_file) {
+            lines.push(Line::from(Span::styled(format!("{} exists and chi-llm reads it instead of {}", first, project_file), app.theme.error())));
+        }
+    }
+    let git = app.build.as_ref().and_then(|b| b.git.as_ref());
+    if let Some(git) = git.filter(|g| **g != FileStatus::NotARepo) {
+        let style = if *git == FileStatus::Ignored { app.theme.hint() } else { app.theme.text() };
+        lines.push(Line::from(Span::styled(format!("Git: {} {}", project_file, git.label()), style)));
+    }
+    let env_refs = app.build.as_ref().is_some_and(|b| b.env_refs);
+    lines.push(Line::from(if env_refs {
+        "Secrets: written as ${ENV} references ('e' to write literal values)"
+    } else {
+        "Secrets: written as literal values ('e' to use ${ENV} references)"
+    }));
     if let Some(st) = &app.build {
         if let Some(msg) = &st.status {
             lines.push(Line::from(Span::styled(
                 msg.clone(),
-                Style::default().fg(app.theme.secondary),
+                app.theme.hint(),
             )));
         }
+        if !st.exports.is_empty() {
+            lines.push(Line::from(Span::styled("Add to your shell profile ('y' copies):", app.theme.title())));
+            lines.extend(st.exports.iter().map(|l| Line::from(l.clone())));
+        }
+        for r in &st.batch {
+            lines.push(match &r.result {
+                Ok(path) => Line::from(Span::styled(format!("✓ {}", path.display()), app.theme.success())),
+                Err(e) => Line::from(Span::styled(format!("✗ {}: {}", r.dir.display(), e), app.theme.error())),
+            });
+        }
+    }
+    let can_commit = git.is_some_and(|g| g.can_commit());
+    lines.push(Line::from(if can_commit {
+        "Press Enter to preview, Enter again to write; 'g' toggles target; 'p' writes to several projects; 'c' commits the config change."
+    } else {
+        "Press Enter to preview, Enter again to write; 'g' toggles target; 'p' writes to several projects."
+    }));
+    if let Some(diff) = app.build.as_ref().and_then(|b| b.diff.as_ref()).filter(|d| !d.is_empty()) {
+        lines.push(Line::from(""));
+        for l in diff.lines() {
+            let style = if l.starts_with('+') && !l.starts_with("+++") {
+                app.theme.accent()
+            } else if l.starts_with('-') && !l.starts_with("---") {
+                app.theme.error()
+            } else {
+                app.theme.hint()
+            };
+            lines.push(Line::from(Span::styled(l.to_string(), style)));
+        }
     }
-    lines.push(Line::from(
-        "Press Enter to write; 'g' toggles target.",
-    ));
     let p = Paragraph::new(lines)
-        .style(Style::default().bg(app.theme.bg).fg(app.theme.fg))
+        .style(app.theme.base())
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .border_style(Style::default().fg(app.theme.frame))
+                .border_style(app.theme.border())
                 .title("Build"),
         )
         .alignment(ratatui::layout::Alignment::Left)
@@ -83,7 +257,7 @@ pub fn draw_build_config(f: &mut Frame, area: Rect, app: &App) {
 }
 
 pub fn get_default_provider_summary() -> Result<(String, String)> {
-    let path = "chi.tmp.json";
+    let path = SCRATCH_FILE;
     let text = std::fs::read_to_string(path).map_err(|e| anyhow!("{}", e))?;
     let v: Value = serde_json::from_str(&text)?;
     let def = v
@@ -106,10 +280,138 @@ pub fn get_default_provider_summary() -> Result<(String, String)> {
     Err(anyhow!("default provider entry not found"))
 }
 
-pub fn write_active_config(target: BuildTarget) -> Result<String> {
-    let path = "chi.tmp.json";
+/// Commit message for the project config, naming the default provider.
+pub fn commit_message(project_file: &str) -> String {
+    match get_default_provider_summary() {
+        Ok((id, ptype)) => format!("Set chi-llm default provider to {} [{}]", id, ptype),
+        Err(_) => format!("Update {}", project_file),
+    }
+}
+
+/// Where `target` is written: the project file in `format` or the global config.
+pub fn config_path(target: BuildTarget, format: ConfigFormat) -> Result<PathBuf> {
+    match target {
+        BuildTarget::Project => Ok(PathBuf::from(format.project_file())),
+        BuildTarget::Global => paths().global_config().ok_or_else(|| anyhow!("home dir not found")),
+    }
+}
+
+/// What Enter on the Build page would write, with the page's `${ENV}` setting applied.
+pub fn pending_config(app: &App) -> Result<ConfigBlocks> {
+    let (_, mut cfg) = active_provider()?;
+    if app.build.as_ref().is_some_and(|b| b.env_refs) { cfg.secrets_to_env_refs(secret_check(app)); }
+    Ok(cfg)
+}
+
+/// Field changes a Build write would make to the config at `path`; `None`
+/// when nothing is written there yet.
+pub fn pending_changes(app: &App, path: &Path) -> Result<Option<Vec<FieldChange>>> {
+    let cfg = pending_config(app)?;
+    if !path.exists() { return Ok(None); }
+    let written = read_config_file(path)?;
+    Ok(Some(diff_values(&written, &cfg.to_json())))
+}
+
+pub fn write_active_config(target: BuildTarget, format: ConfigFormat) -> Result<String> {
+    let (_, cfg) = active_provider()?;
+    write_config(target, format, cfg)
+}
+
+/// What Build writes: the default provider block plus, when providers have
+/// routing tags, chi-llm `provider_profiles` for its tag router.
+#[derive(Clone, Debug, Default)]
+pub struct ConfigBlocks {
+    pub provider: serde_json::Map<String, Value>,
+    pub profiles: Vec<serde_json::Map<String, Value>>,
+}
+
+impl ConfigBlocks {
+    /// Plaintext secrets: `key` for the provider block, `name.key` for profiles.
+    /// `is_secret` gets the block's type and key.
+    pub fn plaintext_secrets(&self, is_secret: impl Fn(&str, &str) -> bool) -> Vec<String> {
+        let mut out = plaintext_secrets(&self.provider, |k| is_secret(block_type(&self.provider), k));
+        for p in &self.profiles {
+            let name = p.get("name").and_then(|n| n.as_str()).unwrap_or("");
+            out.extend(plaintext_secrets(p, |k| is_secret(block_type(p), k)).into_iter().map(|k| format!("{}.{}", name, k)));
+        }
+        out
+    }
+
+    /// Replace every plaintext secret with a `${VAR}` reference; returns the
+    /// `(VAR, value)` pairs, once per variable.
+    pub fn secrets_to_env_refs(&mut self, is_secret: impl Fn(&str, &str) -> bool) -> Vec<(String, String)> {
+        let mut refs: Vec<(String, String)> = Vec::new();
+        for block in std::iter::once(&mut self.provider).chain(self.profiles.iter_mut()) {
+            let keys = plaintext_secrets(block, |k| is_secret(block_type(block), k));
+            for r in secrets_to_env_refs(block, &keys) {
+                if !refs.iter().any(|(var, _)| *var == r.0) { refs.push(r); }
+            }
+        }
+        refs
+    }
+
+    /// A copy with plaintext secrets masked, for showing on screen.
+    pub fn masked(&self, is_secret: impl Fn(&str, &str) -> bool) -> ConfigBlocks {
+        let mut out = self.clone();
+        for block in std::iter::once(&mut out.provider).chain(out.profiles.iter_mut()) {
+            for k in plaintext_secrets(block, |k| is_secret(block_type(block), k)) {
+                block.insert(k, Value::String("••••••".to_string()));
+            }
+        }
+        out
+    }
+
+    pub fn to_json(&self) -> Value {
+        let mut out = serde_json::Map::new();
+        out.insert("provider".to_string(), Value::Object(self.provider.clone()));
+        if !self.profiles.is_empty() {
+            out.insert("provider_profiles".to_string(), Value::Array(self.profiles.iter().cloned().map(Value::Object).collect()));
+        }
+        Value::Object(out)
+    }
+}
+
+fn block_type(block: &serde_json::Map<String, Value>) -> &str {
+    block.get("type").and_then(|t| t.as_str()).unwrap_or("")
+}
+
+/// `provider_profiles` for chi-llm's tag router: every tagged provider, in list
+/// order, skipping disabled ones. The default provider is always included with priority 10 (the others
+/// keep chi-llm's default of 100) so untagged calls still go to it. Empty when
+/// no provider has tags. Session-only secrets are written as `${VAR}`
+/// references, as in the provider block, never with the value from the
+/// environment.
+pub fn routing_profiles(scratch: &Value, default_id: &str) -> Vec<serde_json::Map<String, Value>> {
+    let entries: Vec<ProviderScratchEntry> = scratch.get("providers").and_then(|p| p.as_array())
+        .map(|arr| arr.iter().map(ProviderScratchEntry::from_json).collect())
+        .unwrap_or_default();
+    if entries.iter().all(|e| e.disabled || e.tags.is_empty()) { return Vec::new(); }
+    let mut profiles = Vec::new();
+    for e in entries.iter().filter(|e| !e.ptype.is_empty() && !e.disabled && (e.id == default_id || !e.tags.is_empty())) {
+        let mut profile = serde_json::Map::new();
+        profile.insert("name".to_string(), Value::String(e.name.clone()));
+        profile.extend(provider_block(&e.ptype, &e.stored_config()));
+        profile.insert("tags".to_string(), serde_json::json!(e.tags));
+        if e.id == default_id {
+            profile.insert("priority".to_string(), serde_json::json!(10));
+            profiles.insert(0, profile);
+        } else {
+            profiles.push(profile);
+        }
+    }
+    profiles
+}
+
+/// The default provider in `chi.tmp.json`: its scratch type and the blocks to
+/// write.
+pub fn active_provider() -> Result<(String, ConfigBlocks)> {
+    let path = SCRATCH_FILE;
     let text = std::fs::read_to_string(path).map_err(|e| anyhow!("{}", e))?;
-    let v: Value = serde_json::from_str(&text)?;
+    active_provider_in(&serde_json::from_str(&text)?)
+}
+
+/// Like `active_provider`, for scratch JSON `v` read from elsewhere (`--apply`).
+pub fn active_provider_in(v: &Value) -> Result<(String, ConfigBlocks)> {
     let def = v
         .get("default_provider_id")
         .and_then(|x| x.as_str())
@@ -118,65 +420,458 @@ pub fn write_active_config(target: BuildTarget) -> Result<String> {
         .get("providers")
         .and_then(|x| x.as_array())
         .ok_or_else(|| anyhow!("no providers array in chi.tmp.json"))?;
-    let mut ptype = String::new();
-    let mut cfg = serde_json::Map::new();
+    let mut found = None;
     for p in arr {
         let id = p.get("id").and_then(|x| x.as_str()).unwrap_or("");
         if id == def {
-            ptype = p
-                .get("type")
-                .and_then(|x| x.as_str())
-                .unwrap_or("")
-                .to_string();
-            if let Some(c) = p.get("config").and_then(|x| x.as_object()) {
-                for (k, val) in c {
-                    if k == "type" {
-                        continue;
-                    }
-                    // include only non-empty fields
-                    let include = match val {
-                        Value::Null => false,
-                        Value::String(s) => !s.is_empty(),
-                        _ => true,
-                    };
-                    if include {
-                        cfg.insert(k.clone(), val.clone());
-                    }
-                }
+            if p.get("disabled").and_then(|x| x.as_bool()) == Some(true) {
+                return Err(anyhow!("default provider '{}' is disabled", def));
+            }
+            let ptype = p.get("type").and_then(|x| x.as_str()).unwrap_or("");
+            if !ptype.is_empty() {
+                // Session-only secrets stay `${VAR}` references, even if the file has a value
+                let config = ProviderScratchEntry::from_json(p).stored_config();
+                let provider = provider_block(ptype, &config);
+                found = Some((ptype.to_string(), ConfigBlocks { provider, profiles: routing_profiles(v, def) }));
             }
             break;
         }
     }
-    if ptype.is_empty() {
-        return Err(anyhow!("default provider type missing"));
-    }
-    // Map UI-specific local variants to canonical type for config
-    let ptype_out = match ptype.as_str() {
-        "local-zeroconfig" | "local-custom" => "local".to_string(),
-        other => other.to_string(),
-    };
-    let mut out = serde_json::Map::new();
-    let mut pmap = serde_json::Map::new();
-    pmap.insert("type".to_string(), Value::String(ptype_out));
-    for (k, v) in cfg {
-        pmap.insert(k, v);
-    }
-    out.insert("provider".to_string(), Value::Object(pmap));
-    let json = Value::Object(out);
+    found.ok_or_else(|| anyhow!("default provider type missing"))
+}
+
+/// Write `cfg` as the config for `target` (the project file in `format`);
+/// returns the path.
+pub fn write_config(target: BuildTarget, format: ConfigFormat, cfg: ConfigBlocks) -> Result<String> {
+    let json = cfg.to_json();
     let written = match target {
         BuildTarget::Project => {
-            let p = ".chi_llm.json";
-            std::fs::write(p, serde_json::to_vec_pretty(&json)?)?;
-            p.to_string()
+            std::fs::write(format.project_file(), format.render(&json)?)?;
+            format.project_file().to_string()
         }
         BuildTarget::Global => {
-            let home = dirs::home_dir().ok_or_else(|| anyhow!("home dir not found"))?;
-            let dir = home.join(".cache").join("chi_llm");
-            std::fs::create_dir_all(&dir)?;
-            let p = dir.join("model_config.json");
+            let p = paths().global_config().ok_or_else(|| anyhow!("home dir not found"))?;
+            if let Some(dir) = p.parent() { std::fs::create_dir_all(dir)?; }
             std::fs::write(&p, serde_json::to_vec_pretty(&json)?)?;
             p.to_string_lossy().to_string()
         }
     };
+    // The current model (also flagged in the model list) and diagnostics now
+    // answer differently
+    invalidate_cli_cache(&["models"]);
+    invalidate_cli_cache(&["diagnostics"]);
     Ok(written)
 }
+
+/// Provider object as written to `.chi_llm.json`: UI-only local variants map to the
+/// canonical `local` type, and empty/null config values are dropped.
+pub fn provider_block(ptype: &str, config: &Value) -> serde_json::Map<String, Value> {
+    let ptype_out = match ptype {
+        "local-zeroconfig" | "local-custom" => "local",
+        other => other,
+    };
+    let mut pmap = serde_json::Map::new();
+    pmap.insert("type".to_string(), Value::String(ptype_out.to_string()));
+    if let Some(c) = config.as_object() {
+        for (k, val) in c {
+            if k == "type" {
+                continue;
+            }
+            // include only non-empty fields
+            let include = match val {
+                Value::Null => false,
+                Value::String(s) => !s.is_empty(),
+                _ => true,
+            };
+            if include {
+                pmap.insert(k.clone(), val.clone());
+            }
+        }
+    }
+    pmap
+}
+
+/// Environment variable for a secret config key, e.g. `OPENAI_API_KEY` for
+/// `api_key` of an `openai` provider.
+pub fn env_var_name(ptype: &str, key: &str) -> String {
+    format!("{}_{}", ptype, key).chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_uppercase() } else { '_' })
+        .collect()
+}
+
+/// Keys of `pmap` whose values are literal secrets (not `${VAR}` or keychain references).
+pub fn plaintext_secrets(pmap: &serde_json::Map<String, Value>, is_secret: impl Fn(&str) -> bool) -> Vec<String> {
+    pmap.iter()
+        .filter(|(k, v)| is_secret(k) && v.as_str().is_some_and(|s| !s.starts_with("${") && keyring_account(s).is_none()))
+        .map(|(k, _)| k.clone())
+        .collect()
+}
+
+/// Replace the values of `keys` with `${VAR}` references; returns `(VAR, value)`
+/// pairs for the replaced secrets.
+pub fn secrets_to_env_refs(pmap: &mut serde_json::Map<String, Value>, keys: &[String]) -> Vec<(String, String)> {
+    let ptype = pmap.get("type").and_then(|t| t.as_str()).unwrap_or("").to_string();
+    let mut refs = Vec::new();
+    for k in keys {
+        let Some(Value::String(secret)) = pmap.get(k).cloned() else { continue };
+        let var = env_var_name(&ptype, k);
+        pmap.insert(k.clone(), Value::String(format!("${{{}}}", var)));
+        refs.push((var, secret));
+    }
+    refs
+}
+
+/// `export VAR='value'` for a POSIX shell profile.
+pub fn export_line(var: &str, value: &str) -> String {
+    format!("export {}='{}'", var, value.replace('\'', "'\\''"))
+}
+
+/// Secret check for Build: the provider schema when Configure has loaded it,
+/// else the key-name heuristic.
+pub(crate) fn secret_check(app: &App) -> impl Fn(&str, &str) -> bool + '_ {
+    move |ptype, k| match app.providers.ready() {
+        Some(p) => p.is_secret(ptype, k),
+        None => is_secret_key(k),
+    }
+}
+
+/// Write the default provider as `spec` says (target, format, `${ENV}`
+/// references). A project file that would hold plaintext secrets without being
+/// gitignored asks first. Returns the status line of the write, `None` while
+/// the question is open; the Build page's state records the outcome either way.
+pub fn write_with_checks(app: &mut App, spec: BuildState) -> Result<Option<String>> {
+    let (_, cfg) = active_provider()?;
+    if spec.env_refs { return write_with_env_refs(app, &spec, cfg).map(Some); }
+    let secrets = cfg.plaintext_secrets(secret_check(app));
+    if spec.target != BuildTarget::Project || secrets.is_empty() { return finish_write(app, &spec, cfg).map(Some); }
+    // Whether the file is gitignored comes from git, off the UI thread
+    let file = spec.project_file();
+    let job = app.jobs.spawn(format!("Checking git status of {}", file), move |_| file_status(file));
+    if let Some(old) = app.build_git.check.replace(SecretsCheck { job, spec, cfg, secrets }) { old.job.cancel(); }
+    Ok(None)
+}
+
+/// A write with plaintext secrets waiting for `file_status` of its project file.
+pub struct SecretsCheck {
+    job: Job<FileStatus>,
+    spec: BuildState,
+    cfg: ConfigBlocks,
+    secrets: Vec<String>,
+}
+
+/// Build's git calls, which run as jobs: the page's status line (with the
+/// diff after a write), `c`'s commit and the ignore check before writing
+/// plaintext secrets.
+#[derive(Default)]
+pub struct GitJobs {
+    /// File, its status and (when asked for) its diff.
+    status: Option<Job<(&'static str, FileStatus, Option<String>)>>,
+    /// `c`: the committed file and its new short hash.
+    commit: Option<Job<(&'static str, Result<String>)>>,
+    pub check: Option<SecretsCheck>,
+}
+
+/// Look up `file`'s git status (and diff) for the Build page.
+fn refresh_git(app: &mut App, file: &'static str, with_diff: bool) {
+    let job = app.jobs.spawn(format!("Reading git status of {}", file), move |_| {
+        let git = file_status(file);
+        let diff = with_diff.then(|| file_diff(file, &git));
+        (file, git, diff)
+    });
+    if let Some(old) = app.build_git.status.replace(job) { old.cancel(); }
+}
+
+/// Collect finished git jobs; a finished ignore check asks about or goes on
+/// with its write. Runs on every page, since Ctrl+D writes from anywhere.
+pub fn poll_build_git(app: &mut App) {
+    if let Some(done) = app.build_git.status.as_ref().and_then(|job| job.poll()) {
+        app.build_git.status = None;
+        let st = app.build.get_or_insert_with(BuildState::default);
+        if let Ok((file, git, diff)) = done {
+            // Dropped when the page moved to the other format meanwhile
+            if file == st.project_file() {
+                st.git = Some(git);
+                if diff.is_some() { st.diff = diff; }
+            }
+        }
+    }
+    if let Some(done) = app.build_git.commit.as_ref().and_then(|job| job.poll()) {
+        app.build_git.commit = None;
+        let file = app.build.get_or_insert_with(BuildState::default).project_file();
+        let (file, (msg, ok)) = match done {
+            Ok((file, Ok(hash))) => (file, (format!("Committed {} ({})", file, hash), true)),
+            Ok((file, Err(e))) => (file, (format!("Commit failed: {}", e), false)),
+            Err(e) => (file, (format!("Commit failed: {}", e), false)),
+        };
+        let st = app.build.get_or_insert_with(BuildState::default);
+        st.status = Some(msg.clone());
+        if ok { st.diff = None; }
+        let _ = app.notify_tx.send(Notification::new(msg, ok, Page::Build));
+        refresh_git(app, file, false);
+    }
+    let Some(done) = app.build_git.check.as_ref().and_then(|c| c.job.poll()) else { return };
+    let Some(SecretsCheck { spec, cfg, secrets, .. }) = app.build_git.check.take() else { return };
+    let done = match done {
+        Ok(FileStatus::Ignored) => finish_write(app, &spec, cfg).map(|_| ()),
+        Ok(_) => { ask_about_secrets(app, spec, cfg, &secrets); Ok(()) }
+        Err(e) => Err(anyhow!(e)),
+    };
+    record_failure(app, done);
+}
+
+/// The project file would hold `secrets` in plaintext without being
+/// gitignored: gitignore it, convert them or write anyway.
+pub fn ask_about_secrets(app: &mut App, spec: BuildState, cfg: ConfigBlocks, secrets: &[String]) {
+    let file = spec.project_file();
+    let msg = format!("{} would contain plaintext secrets ({}) and is not gitignored.", file, secrets.join(", "));
+    let (ignored, converted) = (cfg.clone(), cfg.clone());
+    let (spec_g, spec_e) = (spec.clone(), spec.clone());
+    let modal = ActionModal::new("Secrets in project config", msg)
+        .action('g', format!("add {} to .gitignore, then write", file), move |app| {
+            let done = add_to_gitignore(file).map_err(|e| anyhow!(".gitignore: {}", e)).and_then(|()| finish_write(app, &spec_g, ignored));
+            record_failure(app, done);
+        })
+        .action('e', "convert secrets to ${ENV} references, then write", move |app| {
+            let done = write_with_env_refs(app, &spec_e, converted);
+            record_failure(app, done);
+        })
+        .action('w', "write anyway", move |app| {
+            let done = finish_write(app, &spec, cfg);
+            record_failure(app, done);
+        });
+    app.modals.push(Box::new(modal));
+}
+
+/// Enter in the preview: write with the page's own settings.
+fn write_from_page(app: &mut App) {
+    let spec = app.build.get_or_insert_with(BuildState::default).clone();
+    let done = write_with_checks(app, spec);
+    record_failure(app, done);
+}
+
+/// Show a failed write on the Build page.
+fn record_failure<T>(app: &mut App, done: Result<T>) {
+    if let Err(e) = done { app.build.get_or_insert_with(BuildState::default).status = Some(format!("Error: {}", e)); }
+}
+
+/// Write with secrets replaced by `${VAR}` references and list the matching
+/// `export` lines on the page.
+fn write_with_env_refs(app: &mut App, spec: &BuildState, mut cfg: ConfigBlocks) -> Result<String> {
+    let refs = cfg.secrets_to_env_refs(secret_check(app));
+    let status = finish_write(app, spec, cfg)?;
+    let st = app.build.get_or_insert_with(BuildState::default);
+    st.exports = refs.iter().map(|(var, value)| export_line(var, value)).collect();
+    Ok(status)
+}
+
+/// Write, record the outcome in the Build page's state and run write hooks.
+/// Returns the status line.
+fn finish_write(app: &mut App, spec: &BuildState, cfg: ConfigBlocks) -> Result<String> {
+    let model = cfg.provider.get("model").and_then(|m| m.as_str()).filter(|m| !m.is_empty()).map(String::from);
+    let by = format!("Build ({})", cfg.provider.get("type").and_then(|t| t.as_str()).unwrap_or("provider"));
+    let page = app.build.get_or_insert_with(BuildState::default);
+    page.exports.clear();
+    page.batch.clear();
+    // The file is about to change
+    page.pending = None;
+    let path = write_config(spec.target, spec.format, cfg)?;
+    let file = spec.project_file();
+    let mut status = format!("Written: {}", path);
+    if spec.target == BuildTarget::Project {
+        if let Some(first) = shadowing_config(file) {
+            status = format!("Written: {} (but chi-llm reads {} first; remove it)", path, first);
+        }
+    }
+    page.status = Some(status.clone());
+    // Git status and diff are for the page's project file
+    if spec.target == BuildTarget::Project && page.format == spec.format { refresh_git(app, file, true); }
+    if let Some(model) = model {
+        app.model_meta.note_used(&model, &by, &now_rfc3339());
+        let _ = app.model_meta.save();
+    }
+    app.run_hooks(HookEvent::Write { path });
+    tutorial::reached(app, Milestone::ConfigWritten);
+    Ok(status)
+}
+
+/// Enter: the file Build would write, highlighted and with secrets masked;
+/// Enter in the preview writes it.
+fn open_preview(app: &mut App) {
+    let st = app.build.get_or_insert_with(BuildState::default);
+    let (target, format) = (st.target, if st.target == BuildTarget::Project { st.format } else { ConfigFormat::Json });
+    let rendered = config_path(target, format).and_then(|path| {
+        let cfg = pending_config(app)?.masked(secret_check(app));
+        Ok((path, String::from_utf8_lossy(&format.render(&cfg.to_json())?).into_owned()))
+    });
+    let (path, text) = match rendered {
+        Ok(r) => r,
+        Err(e) => {
+            app.build.get_or_insert_with(BuildState::default).status = Some(format!("Error: {}", e));
+            return;
+        }
+    };
+    let lang = match format { ConfigFormat::Json => Lang::Json, ConfigFormat::Yaml => Lang::Yaml };
+    let lines = text.lines().map(|l| highlight_line(lang, l, &app.theme)).collect();
+    let modal = TextViewModal::new(format!("Write {}?", path.display()), lines).on_enter("write", write_from_page);
+    app.modals.push(Box::new(modal));
+}
+
+/// `d`: field-level diff between what Build would write and the written config.
+fn open_diff(app: &mut App) {
+    let st = app.build.get_or_insert_with(BuildState::default);
+    let path = match config_path(st.target, st.format) {
+        Ok(path) => path,
+        Err(e) => { st.status = Some(format!("Error: {}", e)); return; }
+    };
+    let mut lines: Vec<Line<'static>> = Vec::new();
+    match pending_changes(app, &path) {
+        Err(e) => lines.push(Line::from(Span::styled(format!("Error: {}", e), app.theme.error()))),
+        Ok(None) => lines.push(Line::from(format!("{} does not exist yet; Enter writes it.", path.display()))),
+        Ok(Some(changes)) if changes.is_empty() => lines.push(Line::from(Span::styled("No differences: the written config matches chi.tmp.json.", app.theme.success()))),
+        Ok(Some(changes)) => {
+            lines.push(Line::from(Span::styled(format!("{} → default provider in chi.tmp.json", path.display()), app.theme.title())));
+            for c in &changes {
+                let style = match c.change {
+                    Change::Added(_) => app.theme.accent(),
+                    Change::Removed(_) => app.theme.error(),
+                    Change::Changed { .. } => app.theme.warning(),
+                };
+                lines.push(Line::from(Span::styled(c.line(is_secret_key), style)));
+            }
+        }
+    }
+    app.modals.push(Box::new(TextViewModal::new("Diff: written config vs chi.tmp.json", lines)));
+}
+
+/// `j`: JSON Schemas for `chi.tmp.json` and `.chi_llm.json` into the export
+/// directory, from the loaded provider schema (loading it first if needed).
+fn export_schemas(app: &mut App) {
+    let Some(st) = app.providers.ready() else {
+        app.providers.ensure(&app.jobs, "Loading providers", |_| load_providers_state());
+        let _ = app.notify_tx.send(Notification::new("Loading the provider schema; press j again", true, Page::Build));
+        return;
+    };
+    let (msg, ok) = match write_json_schemas(&paths().export_dir(), &st.schema_types, &st.schema_map) {
+        Ok(written) => (format!("Schemas written: {}", written.iter().map(|p| p.display().to_string()).collect::<Vec<_>>().join(", ")), true),
+        Err(e) => (format!("Schema export failed: {}", e), false),
+    };
+    app.build.get_or_insert_with(BuildState::default).status = Some(msg.clone());
+    let _ = app.notify_tx.send(Notification::new(msg, ok, Page::Build));
+}
+
+pub struct BuildPage;
+
+impl PageController for BuildPage {
+    fn handle_key(&self, app: &mut App, key: KeyEvent) -> Option<Msg> {
+        let st = app.build.get_or_insert_with(BuildState::default);
+        match key.code {
+            KeyCode::Char('g') | KeyCode::Char('G') => { st.toggle_target(); }
+            KeyCode::Enter => open_preview(app),
+            KeyCode::Char('p') | KeyCode::Char('P') => open_batch_editor(app),
+            KeyCode::Char('e') | KeyCode::Char('E') => { st.env_refs = !st.env_refs; }
+            KeyCode::Char('f') | KeyCode::Char('F') => { st.toggle_format(); }
+            KeyCode::Char('j') | KeyCode::Char('J') => export_schemas(app),
+            KeyCode::Char('d') | KeyCode::Char('D') => open_diff(app),
+            KeyCode::Char('y') | KeyCode::Char('Y') if !st.exports.is_empty() => {
+                let (msg, ok) = copy_message(&format!("{} export line(s)", st.exports.len()), copy_text(&st.exports.join("\n")));
+                let _ = app.notify_tx.send(Notification::new(msg, ok, Page::Build));
+            }
+            KeyCode::Char('c') | KeyCode::Char('C') if st.git.as_ref().is_some_and(|g| g.can_commit()) => {
+                let file = st.project_file();
+                let message = commit_message(file);
+                app.modals.push(Box::new(ConfirmModal::new("Commit config change", format!("git commit {}:\n\n{}", file, message), move |app| {
+                    let job = app.jobs.spawn(format!("Committing {}", file), move |_| (file, commit_file(file, &message)));
+                    app.build_git.commit = Some(job);
+                })));
+            }
+            _ => return None,
+        }
+        Some(Msg::Handled)
+    }
+
+    fn tick(&self, app: &mut App) {
+        let st = app.build.get_or_insert_with(BuildState::default);
+        if st.git.is_none() && app.build_git.status.is_none() {
+            let file = st.project_file();
+            refresh_git(app, file, false);
+        }
+        PendingWrite::refresh(app);
+    }
+
+    fn draw(&self, f: &mut Frame, area: Rect, app: &App) {
+        draw_build_config(f, area, app);
+    }
+
+    fn actions(&self) -> &'static [KeyAction] {
+        const ACTIONS: &[KeyAction] = &[
+            KeyAction::new("g", "toggle target"),
+            KeyAction::new("e", "env refs"),
+            KeyAction::new("f", "JSON/YAML").when(|app| app.build.as_ref().is_none_or(|b| b.target == BuildTarget::Project)),
+            KeyAction::new("Enter", "preview & write"),
+            KeyAction::new("p", "write to projects"),
+            KeyAction::new("d", "diff"),
+            KeyAction::new("j", "schemas"),
+            KeyAction::new("y", "copy exports").when(|app| app.build.as_ref().is_some_and(|b| !b.exports.is_empty())),
+            KeyAction::new("c", "commit").when(|app| app.build.as_ref().and_then(|b| b.git.as_ref()).is_some_and(|g| g.can_commit())),
+            KeyAction::new("Esc", "back"),
+        ];
+        ACTIONS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn secrets_become_env_references() {
+        let mut pmap = provider_block("openai", &serde_json::json!({"api_key": "sk-1", "org_key": "${ORG}", "model": "gpt-4o"}));
+        let keys = plaintext_secrets(&pmap, is_secret_key);
+        assert_eq!(keys, ["api_key"]);
+        let refs = secrets_to_env_refs(&mut pmap, &keys);
+        assert_eq!(refs, [("OPENAI_API_KEY".to_string(), "sk-1".to_string())]);
+        assert_eq!(pmap["api_key"], "${OPENAI_API_KEY}");
+        assert_eq!(pmap["model"], "gpt-4o");
+        assert_eq!(export_line("K", "it's"), "export K='it'\\''s'");
+    }
+
+    #[test]
+    fn tagged_providers_become_routing_profiles() {
+        let scratch = serde_json::json!({"providers": [
+            {"id": "a", "name": "cheap", "type": "openai", "tags": ["cheap"], "config": {"api_key": "sk-1", "model": "gpt-4o-mini"}},
+            {"id": "b", "name": "untagged", "type": "ollama", "config": {}},
+            {"id": "c", "name": "main", "type": "local-custom", "config": {"model": "phi"}},
+        ]});
+        let profiles = routing_profiles(&scratch, "c");
+        assert_eq!(profiles.len(), 2);
+        assert_eq!(Value::Object(profiles[0].clone()), serde_json::json!({"name": "main", "type": "local", "model": "phi", "tags": [], "priority": 10}));
+        assert_eq!(profiles[1]["tags"], serde_json::json!(["cheap"]));
+
+        let mut cfg = ConfigBlocks { provider: profiles[1].clone(), profiles };
+        assert_eq!(cfg.plaintext_secrets(|_, k| is_secret_key(k)), ["api_key", "cheap.api_key"]);
+        let masked = cfg.masked(|_, k| is_secret_key(k));
+        assert_eq!((masked.provider["api_key"].as_str(), masked.profiles[1]["api_key"].as_str()), (Some("••••••"), Some("••••••")));
+        assert_eq!(cfg.secrets_to_env_refs(|_, k| is_secret_key(k)).len(), 1);
+        assert_eq!(cfg.to_json()["provider_profiles"][1]["api_key"], "${OPENAI_API_KEY}");
+        assert!(routing_profiles(&serde_json::json!({"providers": [{"id": "b", "type": "ollama"}]}), "b").is_empty());
+        let disabled = serde_json::json!({"providers": [
+            {"id": "a", "type": "openai", "tags": ["cheap"], "disabled": true},
+            {"id": "b", "type": "ollama", "tags": ["chat"]},
+        ]});
+        assert_eq!(routing_profiles(&disabled, "a").len(), 1);
+    }
+
+    #[test]
+    fn session_only_secrets_are_written_as_references() {
+        std::env::set_var("GROQ_SESSION_TEST_API_KEY", "gsk-live");
+        let scratch = serde_json::json!({"default_provider_id": "a", "providers": [
+            {"id": "a", "type": "openai", "session_only": ["api_key"], "config": {"api_key": "sk-left-in-file"}},
+            {"id": "b", "type": "groq-session-test", "tags": ["fast"], "session_only": ["api_key"], "config": {"api_key": "${GROQ_SESSION_TEST_API_KEY}"}},
+        ]});
+        let (_, cfg) = active_provider_in(&scratch).unwrap();
+        assert_eq!(cfg.provider["api_key"], "${OPENAI_API_KEY}");
+        assert_eq!(cfg.profiles[1]["api_key"], "${GROQ_SESSION_TEST_API_KEY}");
+        assert!(cfg.plaintext_secrets(|_, k| is_secret_key(k)).is_empty());
+        std::env::remove_var("GROQ_SESSION_TEST_API_KEY");
+    }
+}
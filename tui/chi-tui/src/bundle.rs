@@ -0,0 +1,386 @@
+//! State bundles (`x`/`i` on the Settings page): scratch providers, settings
+//! (theme included), provider plugins, macros, dropdown history, test latency
+//! and usage counters in one JSON file, for moving a setup to another machine.
+//! Plaintext secrets in `chi.tmp.json` are written as `${VAR}` references
+//! unless the export is asked to include them (`X`).
+//!
+//! Import merges instead of overwriting: entries missing here are added, and
+//! entries present on both sides with different values are conflicts, resolved
+//! all at once by keeping the local value or taking the bundle's. Providers are
+//! matched by id. Every file that changes is backed up first.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::app::{App, Notification, Page};
+use crate::build::{env_var_name, plaintext_secrets};
+use crate::modal::{ActionModal, ConfirmModal, PromptModal};
+use crate::model_meta::ModelMetaStore;
+use crate::page_data::PageData;
+use crate::paths::{file_in, paths, SCRATCH_FILE};
+use crate::providers::{is_secret_key, LatencyBook, UsageBook};
+use crate::recent::RecentPicks;
+use crate::settings::{apply_settings, load_settings};
+
+/// Format version written to `chi_tui_bundle`.
+pub const BUNDLE_VERSION: u32 = 1;
+/// Bundle names of provider plugin files start with this.
+const PLUGIN_PREFIX: &str = "providers.d/";
+/// Conflicts listed in the import popup before "and N more".
+const MAX_LISTED: usize = 8;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Bundle {
+    pub chi_tui_bundle: u32,
+    pub created: String,
+    /// Bundle name -> contents: parsed JSON for `.json` files, text otherwise.
+    pub files: BTreeMap<String, Value>,
+}
+
+/// Which side wins where both have a different value.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Resolution {
+    KeepMine,
+    TakeBundle,
+}
+
+/// What an import would do.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ImportPlan {
+    /// Files to write: bundle name, local path, new contents.
+    pub writes: Vec<(String, PathBuf, String)>,
+    /// Values that differ on both sides, e.g. `chi.tmp.json: providers["work"]`.
+    pub conflicts: Vec<String>,
+    /// Bundle entries this version does not know where to put.
+    pub skipped: Vec<String>,
+}
+
+/// Where a bundle entry lives on this machine.
+pub fn local_path(name: &str) -> Option<PathBuf> {
+    let p = paths();
+    let path = match name {
+        SCRATCH_FILE => PathBuf::from(SCRATCH_FILE),
+        "settings.toml" => p.settings_file(),
+        "macros.json" => p.config_dir.join("macros.json"),
+        "usage.json" => p.usage_file(),
+        "latency.json" | "recent.json" | "model_meta.json" => p.state_dir.join(name),
+        _ => {
+            // Plain file names only, so a bundle cannot write outside providers.d
+            let file = name.strip_prefix(PLUGIN_PREFIX)?;
+            let plain = !file.is_empty() && !file.contains(['/', '\\']) && !file.starts_with('.');
+            if !plain || !file.ends_with(".toml") { return None; }
+            p.plugin_dir().join(file)
+        }
+    };
+    Some(path)
+}
+
+/// Files that go into a bundle, with their bundle names.
+pub fn bundle_files() -> Vec<(String, PathBuf)> {
+    let mut names: Vec<String> = [SCRATCH_FILE, "settings.toml", "macros.json", "latency.json", "usage.json", "recent.json", "model_meta.json"]
+        .iter().map(|n| n.to_string()).collect();
+    if let Ok(dir) = fs::read_dir(paths().plugin_dir()) {
+        let mut plugins: Vec<String> = dir.flatten().map(|e| format!("{}{}", PLUGIN_PREFIX, e.file_name().to_string_lossy())).collect();
+        plugins.sort();
+        names.extend(plugins);
+    }
+    names.into_iter().filter_map(|n| local_path(&n).map(|p| (n, p))).collect()
+}
+
+/// Read the files that exist; unparsable JSON is kept as text.
+pub fn build_bundle(files: &[(String, PathBuf)]) -> Bundle {
+    let mut out = BTreeMap::new();
+    for (name, path) in files {
+        let Ok(text) = fs::read_to_string(path) else { continue };
+        let value = match name.ends_with(".json") {
+            true => serde_json::from_str(&text).unwrap_or(Value::String(text)),
+            false => Value::String(text),
+        };
+        out.insert(name.clone(), value);
+    }
+    Bundle { chi_tui_bundle: BUNDLE_VERSION, created: chrono::Utc::now().to_rfc3339(), files: out }
+}
+
+/// Replace plaintext provider secrets in the bundled `chi.tmp.json` with
+/// `${VAR}` references. Returns the variables, for the user to set on the
+/// other machine.
+pub fn redact_secrets(bundle: &mut Bundle) -> Vec<String> {
+    let mut vars = Vec::new();
+    let providers = bundle.files.get_mut(SCRATCH_FILE).and_then(|s| s.get_mut("providers")).and_then(Value::as_array_mut);
+    for p in providers.into_iter().flatten() {
+        let ptype = p.get("type").and_then(Value::as_str).unwrap_or_default().to_string();
+        let Some(cfg) = p.get_mut("config").and_then(Value::as_object_mut) else { continue };
+        for key in plaintext_secrets(cfg, is_secret_key) {
+            let var = env_var_name(&ptype, &key);
+            cfg.insert(key, Value::String(format!("${{{}}}", var)));
+            vars.push(var);
+        }
+    }
+    vars
+}
+
+/// Write a bundle of the current state to the exports directory; secrets stay
+/// out of it unless `include_secrets`. Returns the path, the file count and the
+/// variables that replaced secrets.
+pub fn export_bundle(include_secrets: bool) -> Result<(PathBuf, usize, Vec<String>)> {
+    let mut bundle = build_bundle(&bundle_files());
+    let vars = if include_secrets { Vec::new() } else { redact_secrets(&mut bundle) };
+    let name = format!("chi-tui-bundle-{}.json", chrono::Local::now().format("%Y%m%d-%H%M%S"));
+    let path = file_in(&paths().export_dir(), &name)?;
+    fs::write(&path, serde_json::to_vec_pretty(&bundle)?)?;
+    Ok((path, bundle.files.len(), vars))
+}
+
+pub fn read_bundle(path: &Path) -> Result<Bundle> {
+    let text = fs::read_to_string(path).with_context(|| path.display().to_string())?;
+    let bundle: Bundle = serde_json::from_str(&text).with_context(|| format!("{} is not a chi-tui bundle", path.display()))?;
+    if bundle.chi_tui_bundle > BUNDLE_VERSION {
+        bail!("bundle format {} is newer than this chi-tui supports ({})", bundle.chi_tui_bundle, BUNDLE_VERSION);
+    }
+    Ok(bundle)
+}
+
+/// Work out the files an import writes, locating each entry with `locate`.
+pub fn plan_import(bundle: &Bundle, resolution: Resolution, locate: impl Fn(&str) -> Option<PathBuf>) -> ImportPlan {
+    let mut plan = ImportPlan::default();
+    for (name, incoming) in &bundle.files {
+        let Some(path) = locate(name) else { plan.skipped.push(name.clone()); continue };
+        let current = fs::read_to_string(&path).ok();
+        let merged = match (&current, incoming) {
+            (None, Value::String(text)) => text.clone(),
+            (None, value) => to_pretty(value),
+            (Some(mine), Value::String(text)) => {
+                if mine == text { continue; }
+                plan.conflicts.push(name.clone());
+                if resolution == Resolution::KeepMine { continue; }
+                text.clone()
+            }
+            (Some(mine), value) => {
+                let Ok(mine) = serde_json::from_str::<Value>(mine) else {
+                    // An unreadable local file has nothing to keep
+                    plan.writes.push((name.clone(), path, to_pretty(value)));
+                    continue;
+                };
+                let merged = merge_json(&mine, value, name, resolution, &mut plan.conflicts);
+                if merged == mine { continue; }
+                to_pretty(&merged)
+            }
+        };
+        plan.writes.push((name.clone(), path, merged));
+    }
+    plan
+}
+
+fn to_pretty(value: &Value) -> String {
+    serde_json::to_string_pretty(value).unwrap_or_default()
+}
+
+/// Merge `incoming` into `mine`: objects key by key, arrays of objects with an
+/// `id` item by item (a differing item is one conflict), anything else whole.
+pub fn merge_json(mine: &Value, incoming: &Value, at: &str, resolution: Resolution, conflicts: &mut Vec<String>) -> Value {
+    match (mine, incoming) {
+        (Value::Object(a), Value::Object(b)) => {
+            let mut out = a.clone();
+            for (k, v) in b {
+                let merged = match a.get(k) {
+                    Some(m) => merge_json(m, v, &format!("{}{}{}", at, if at.contains(": ") { "." } else { ": " }, k), resolution, conflicts),
+                    None => v.clone(),
+                };
+                out.insert(k.clone(), merged);
+            }
+            Value::Object(out)
+        }
+        (Value::Array(a), Value::Array(b)) if keyed_by_id(a) && keyed_by_id(b) => {
+            let mut out = a.clone();
+            for item in b {
+                match out.iter().position(|m| m["id"] == item["id"]) {
+                    None => out.push(item.clone()),
+                    Some(i) if out[i] != *item => {
+                        let label = item.get("name").and_then(Value::as_str).or_else(|| item["id"].as_str()).unwrap_or_default();
+                        conflicts.push(format!("{}[{:?}]", at, label));
+                        if resolution == Resolution::TakeBundle { out[i] = item.clone(); }
+                    }
+                    Some(_) => {}
+                }
+            }
+            Value::Array(out)
+        }
+        _ if mine == incoming => mine.clone(),
+        _ => {
+            conflicts.push(at.to_string());
+            if resolution == Resolution::TakeBundle { incoming.clone() } else { mine.clone() }
+        }
+    }
+}
+
+fn keyed_by_id(items: &[Value]) -> bool {
+    items.iter().all(|v| v.get("id").is_some_and(Value::is_string))
+}
+
+/// Back up the files `plan` replaces, then write them. Returns the backup directory.
+pub fn apply_import(plan: &ImportPlan) -> Result<PathBuf> {
+    let backup = paths().backup_dir().join(format!("import-{}", chrono::Local::now().format("%Y%m%d-%H%M%S")));
+    for (name, path, _) in &plan.writes {
+        if path.exists() {
+            let copy = file_in(&backup, &name.replace('/', "_"))?;
+            fs::copy(path, copy).with_context(|| format!("backing up {}", path.display()))?;
+        }
+    }
+    for (_, path, contents) in &plan.writes {
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) { fs::create_dir_all(dir)?; }
+        fs::write(path, contents).with_context(|| path.display().to_string())?;
+    }
+    Ok(backup)
+}
+
+/// `x` on Settings: export with secrets as `${VAR}` references.
+pub fn export_to_file(app: &mut App) {
+    finish_export(app, false);
+}
+
+/// `X` on Settings: export with plaintext secrets, after a confirmation.
+pub fn export_with_secrets(app: &mut App) {
+    let msg = "Write provider API keys and other secrets into the bundle as plain text? Anyone with the file can use them.";
+    app.modals.push(Box::new(ConfirmModal::new("Export bundle with secrets", msg, |app| finish_export(app, true))));
+}
+
+fn finish_export(app: &mut App, include_secrets: bool) {
+    let (msg, ok) = match export_bundle(include_secrets) {
+        Ok((path, n, vars)) if vars.is_empty() => (format!("Bundle of {} files written to {}", n, path.display()), true),
+        Ok((path, n, vars)) => {
+            let msg = format!("Bundle of {} files written to {}; secrets left out, set {} there (X includes them)", n, path.display(), vars.join(", "));
+            (msg, true)
+        }
+        Err(e) => (format!("Bundle export failed: {:#}", e), false),
+    };
+    let _ = app.notify_tx.send(Notification::new(msg, ok, Page::Settings));
+}
+
+/// `i` on Settings: ask for a bundle, then for a resolution if anything conflicts.
+pub fn open_import(app: &mut App) {
+    app.modals.push(Box::new(PromptModal::new("Import bundle from (path)", |app, path| {
+        let bundle = match read_bundle(Path::new(&path)) {
+            Ok(b) => b,
+            Err(e) => { notify(app, format!("Import failed: {:#}", e), false); return; }
+        };
+        let plan = plan_import(&bundle, Resolution::KeepMine, local_path);
+        let mut msg = String::new();
+        if !plan.skipped.is_empty() { msg.push_str(&format!("Not recognised, skipped: {}\n", plan.skipped.join(", "))); }
+        if plan.conflicts.is_empty() {
+            if plan.writes.is_empty() { notify(app, "Nothing to import; everything matches".to_string(), true); return; }
+            let names: Vec<&str> = plan.writes.iter().map(|(n, _, _)| n.as_str()).collect();
+            msg.push_str(&format!("Update {}? Changed files are backed up first.", names.join(", ")));
+            app.modals.push(Box::new(ConfirmModal::new("Import bundle", msg, move |app| finish_import(app, &plan))));
+            return;
+        }
+        msg.push_str(&format!("{} entries differ from yours:\n", plan.conflicts.len()));
+        for c in plan.conflicts.iter().take(MAX_LISTED) { msg.push_str(&format!("- {}\n", c)); }
+        if plan.conflicts.len() > MAX_LISTED { msg.push_str(&format!("- and {} more\n", plan.conflicts.len() - MAX_LISTED)); }
+        msg.push_str("Everything else is merged in. Changed files are backed up first.");
+        let theirs = plan_import(&bundle, Resolution::TakeBundle, local_path);
+        app.modals.push(Box::new(
+            ActionModal::new("Import bundle: conflicts", msg)
+                .action('m', "keep mine where they differ", move |app| finish_import(app, &plan))
+                .action('b', "take the bundle's where they differ", move |app| finish_import(app, &theirs)),
+        ));
+    })));
+}
+
+fn finish_import(app: &mut App, plan: &ImportPlan) {
+    if plan.writes.is_empty() { notify(app, "Nothing to import".to_string(), true); return; }
+    match apply_import(plan) {
+        Ok(backup) => {
+            reload(app);
+            let plugins = plan.writes.iter().any(|(n, _, _)| n.starts_with(PLUGIN_PREFIX));
+            let note = if plugins { "; restart to load the imported plugins" } else { "" };
+            notify(app, format!("Imported {} files (backups in {}){}", plan.writes.len(), backup.display(), note), true);
+        }
+        Err(e) => notify(app, format!("Import failed: {:#}", e), false),
+    }
+}
+
+/// Drop state read from the imported files so it is loaded again.
+fn reload(app: &mut App) {
+    app.providers = PageData::NotLoaded;
+    app.defaultp = None;
+    app.routing = None;
+    app.latency = LatencyBook::load();
+    app.recent = RecentPicks::load();
+    app.usage = UsageBook::load();
+    app.model_meta = ModelMetaStore::load();
+    if let Ok(settings) = load_settings() {
+        app.settings = settings;
+        apply_settings(app);
+    }
+}
+
+fn notify(app: &App, msg: String, ok: bool) {
+    let _ = app.notify_tx.send(Notification::new(msg, ok, Page::Settings));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn import_merges_and_reports_conflicts() {
+        let tmp = std::env::temp_dir().join(format!("chi-tui-bundle-{}", std::process::id()));
+        fs::create_dir_all(&tmp).unwrap();
+        let scratch = json!({"default_provider_id": "a", "providers": [
+            {"id": "a", "name": "work", "type": "openai", "config": {"model": "gpt-4o"}},
+            {"id": "b", "name": "local", "type": "ollama", "config": {}},
+        ]});
+        fs::write(tmp.join("chi.tmp.json"), scratch.to_string()).unwrap();
+        fs::write(tmp.join("settings.toml"), "idle_after_secs = 30\n").unwrap();
+        let locate = |name: &str| (name != "unknown.bin").then(|| tmp.join(name));
+
+        let mut bundle = build_bundle(&[("chi.tmp.json".into(), tmp.join("chi.tmp.json")), ("settings.toml".into(), tmp.join("settings.toml"))]);
+        assert_eq!(bundle.files["chi.tmp.json"], scratch);
+        assert!(plan_import(&bundle, Resolution::TakeBundle, locate).writes.is_empty());
+
+        bundle.files.insert("chi.tmp.json".into(), json!({"default_provider_id": "c", "providers": [
+            {"id": "a", "name": "work", "type": "openai", "config": {"model": "gpt-4o-mini"}},
+            {"id": "c", "name": "new", "type": "groq", "config": {}},
+        ]}));
+        bundle.files.insert("latency.json".into(), json!({"a": {"millis": 900}}));
+        bundle.files.insert("unknown.bin".into(), json!("x"));
+        let mine = plan_import(&bundle, Resolution::KeepMine, locate);
+        assert_eq!(mine.conflicts, ["chi.tmp.json: default_provider_id", "chi.tmp.json: providers[\"work\"]"]);
+        assert_eq!(mine.skipped, ["unknown.bin"]);
+        let (_, _, merged) = &mine.writes[0];
+        let merged: Value = serde_json::from_str(merged).unwrap();
+        assert_eq!(merged["default_provider_id"], "a");
+        assert_eq!(merged["providers"][0]["config"]["model"], "gpt-4o");
+        assert_eq!(merged["providers"].as_array().unwrap().len(), 3);
+        assert_eq!(mine.writes[1].0, "latency.json");
+
+        let theirs = plan_import(&bundle, Resolution::TakeBundle, locate);
+        let merged: Value = serde_json::from_str(&theirs.writes[0].2).unwrap();
+        assert_eq!(merged["providers"][0]["config"]["model"], "gpt-4o-mini");
+        assert_eq!(merged["providers"][1]["name"], "local");
+
+        assert_eq!(local_path("providers.d/../../x.toml"), None);
+        assert!(local_path("providers.d/mine.toml").unwrap().ends_with("providers.d/mine.toml"));
+        fs::remove_dir_all(&tmp).unwrap();
+    }
+
+    #[test]
+    fn plaintext_secrets_become_env_references() {
+        let mut bundle = Bundle { chi_tui_bundle: BUNDLE_VERSION, created: String::new(), files: BTreeMap::new() };
+        bundle.files.insert(SCRATCH_FILE.into(), json!({"providers": [
+            {"id": "a", "type": "openai", "config": {"model": "gpt-4o", "api_key": "sk-live"}},
+            {"id": "b", "type": "groq", "config": {"type": "groq", "api_key": "${GROQ_API_KEY}"}},
+        ]}));
+        assert_eq!(redact_secrets(&mut bundle), ["OPENAI_API_KEY"]);
+        let providers = &bundle.files[SCRATCH_FILE]["providers"];
+        assert_eq!(providers[0]["config"]["api_key"], "${OPENAI_API_KEY}");
+        assert_eq!(providers[0]["config"]["model"], "gpt-4o");
+        assert_eq!(providers[1]["config"]["api_key"], "${GROQ_API_KEY}");
+    }
+}
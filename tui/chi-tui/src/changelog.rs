@@ -0,0 +1,122 @@
+//! What's new page: the bundled `CHANGELOG.md`, or on `f` the latest GitHub
+//! release notes, rendered with the README page's markdown view. Opened once
+//! automatically after chi-tui is upgraded (see `take_upgrade`).
+
+use std::fs;
+
+use anyhow::{anyhow, Result};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::layout::Rect;
+use ratatui::prelude::Frame;
+use serde_json::Value;
+
+use crate::app::{App, Page};
+use crate::controller::PageController;
+use crate::env::timeouts;
+use crate::keymap::KeyAction;
+use crate::mouse::Scroll;
+use crate::msg::Msg;
+use crate::page_data::draw_page_status;
+use crate::paths::{file_in, paths};
+use crate::readme::{draw_markdown, handle_markdown_key, open_link_list, parse_markdown, scroll_markdown, ReadmeState};
+use crate::util::run_program_text;
+
+pub const CHANGELOG: &str = include_str!("../CHANGELOG.md");
+const RELEASES_URL: &str = "https://api.github.com/repos/jacekjursza/chi_llm/releases/latest";
+/// Last chi-tui version that ran, in the state dir.
+const VERSION_FILE: &str = "last_version";
+
+pub fn bundled_changelog() -> ReadmeState {
+    parse_markdown(CHANGELOG)
+}
+
+/// Latest release notes from GitHub (needs `curl`).
+pub fn fetch_release_notes() -> Result<ReadmeState> {
+    let text = run_program_text("curl", &["-fsSL", "-H", "Accept: application/vnd.github+json", RELEASES_URL], &[], timeouts().cli)?;
+    Ok(parse_markdown(&release_markdown(&serde_json::from_str(&text)?)?))
+}
+
+/// Markdown for a GitHub release object: its name as the heading, then the body.
+pub fn release_markdown(release: &Value) -> Result<String> {
+    let str_of = |k: &str| release.get(k).and_then(|v| v.as_str()).filter(|s| !s.trim().is_empty());
+    let name = str_of("name").or_else(|| str_of("tag_name")).ok_or_else(|| anyhow!("no release found"))?;
+    Ok(format!("# {}\n\n{}", name, str_of("body").unwrap_or("(no release notes)")))
+}
+
+/// Record this version as seen; true when a different version ran before (a
+/// first start is not an upgrade).
+pub fn take_upgrade() -> bool {
+    let current = env!("CARGO_PKG_VERSION");
+    let path = paths().state_dir.join(VERSION_FILE);
+    let previous = fs::read_to_string(&path).ok();
+    if previous.as_deref().map(str::trim) == Some(current) { return false; }
+    if let Ok(path) = file_in(&paths().state_dir, VERSION_FILE) { let _ = fs::write(path, current); }
+    previous.is_some()
+}
+
+pub struct WhatsNewPage;
+
+impl PageController for WhatsNewPage {
+    fn handle_key(&self, app: &mut App, key: KeyEvent) -> Option<Msg> {
+        match key.code {
+            KeyCode::Char('f') | KeyCode::Char('F') if !app.changelog.is_loading() => {
+                app.changelog.load(&app.jobs, "Fetching release notes", |_| fetch_release_notes());
+                return Some(Msg::Handled);
+            }
+            // Back to the bundled changelog (also the retry after a failed fetch)
+            KeyCode::Char('r') | KeyCode::Char('R') => {
+                app.changelog.load(&app.jobs, "Loading changelog", |_| Ok(bundled_changelog()));
+                return Some(Msg::Handled);
+            }
+            KeyCode::Char('l') | KeyCode::Char('L') if app.changelog.ready().is_some() => {
+                open_link_list(app, Page::WhatsNew, |app| app.changelog.ready_mut());
+                return Some(Msg::Handled);
+            }
+            _ => {}
+        }
+        let rm = app.changelog.ready_mut()?;
+        handle_markdown_key(rm, key).then_some(Msg::Handled)
+    }
+
+    fn handle_scroll(&self, app: &mut App, dir: Scroll) -> bool {
+        app.changelog.ready_mut().is_some_and(|rm| scroll_markdown(rm, dir))
+    }
+
+    fn tick(&self, app: &mut App) {
+        app.changelog.ensure(&app.jobs, "Loading changelog", |_| Ok(bundled_changelog()));
+    }
+
+    fn draw(&self, f: &mut Frame, area: Rect, app: &App) {
+        match app.changelog.ready() {
+            Some(rm) => draw_markdown(f, area, app, rm, "What's new"),
+            None => draw_page_status(f, area, app, "What's new", &app.changelog),
+        }
+    }
+
+    fn actions(&self) -> &'static [KeyAction] {
+        const ACTIONS: &[KeyAction] = &[
+            KeyAction::new("Up/Down", "scroll"),
+            KeyAction::new("PgUp/PgDn", "page"),
+            KeyAction::new("h", "TOC"),
+            KeyAction::new("l", "links").when(|app| app.changelog.ready().is_some_and(|rm| !rm.links.is_empty())),
+            KeyAction::new("Tab", "switch TOC/Content").when(|app| app.changelog.ready().is_some_and(|rm| rm.show_toc)),
+            KeyAction::new("f", "fetch latest release notes").when(|app| !app.changelog.is_loading()),
+            KeyAction::new("r", "bundled changelog"),
+            KeyAction::new("Esc", "back"),
+        ];
+        ACTIONS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn release_object_becomes_markdown() {
+        let release = serde_json::json!({"tag_name": "v0.2.0", "name": "", "body": "- faster"});
+        assert_eq!(release_markdown(&release).unwrap(), "# v0.2.0\n\n- faster");
+        assert!(release_markdown(&serde_json::json!({"message": "Not Found"})).is_err());
+        assert!(bundled_changelog().toc.iter().any(|e| e.title == env!("CARGO_PKG_VERSION")));
+    }
+}
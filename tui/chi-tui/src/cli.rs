@@ -0,0 +1,282 @@
+//! Non-interactive subcommands and `--apply`. They reuse the page state and
+//! save/write code and print one JSON object to stdout, so scripts can drive the
+//! configuration.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use clap::Subcommand;
+use serde_json::{json, Value};
+
+use crate::app::App;
+use crate::build::{
+    active_provider_in, config_path, poll_build_git, write_config, write_with_checks, BuildState, BuildTarget,
+    ConfigFormat,
+};
+use crate::cli_log::mask_values;
+use crate::forms::{FieldSchema, FormField};
+use crate::providers::{
+    build_test_args, load_providers_scratch, load_providers_state, load_scratch_entries, network_errors,
+    probe_streaming, save_default_provider, scratch_json_schema, config_json_schema, tuning_errors, write_json_schemas,
+    ProviderScratchEntry, TestMode,
+};
+use crate::settings::load_settings;
+use crate::util::ensure_chi_llm;
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Set the default provider in chi.tmp.json
+    SetDefault {
+        /// Provider id, as listed in chi.tmp.json
+        id: String,
+    },
+    /// Add a provider to chi.tmp.json
+    AddProvider {
+        /// Provider type (see `chi-llm providers schema`)
+        #[arg(long = "type")]
+        ptype: String,
+        /// Display name (defaults to the type)
+        #[arg(long)]
+        name: Option<String>,
+        /// Model id
+        #[arg(long)]
+        model: Option<String>,
+        /// Extra config value, KEY=VALUE (repeatable)
+        #[arg(long = "config", value_name = "KEY=VALUE")]
+        config: Vec<String>,
+    },
+    /// Test a provider from chi.tmp.json the way the UI does; exits non-zero on failure
+    Test {
+        /// Provider id, as listed in chi.tmp.json
+        id: String,
+        /// Run an end-to-end generate call instead of the default check
+        #[arg(long)]
+        e2e: bool,
+    },
+    /// Write the default provider to the active config
+    Build {
+        #[arg(long, value_enum, default_value_t = BuildTarget::Project)]
+        target: BuildTarget,
+        /// Write secrets as ${VAR} references and return the matching export lines
+        #[arg(long)]
+        env_refs: bool,
+        /// Project config format (.chi_llm.json or .chi_llm.yaml); the global config is always JSON
+        #[arg(long, value_enum, default_value_t = ConfigFormat::Json)]
+        format: ConfigFormat,
+    },
+    /// Print JSON Schemas for chi.tmp.json and .chi_llm.json, from the provider schema
+    Schema {
+        /// Write chi.tmp.schema.json and chi_llm.schema.json into this directory instead
+        #[arg(long, value_name = "DIR")]
+        out: Option<PathBuf>,
+    },
+}
+
+/// Run `cmd` and return its JSON result.
+pub fn run_command(cmd: Command) -> Result<Value> {
+    match cmd {
+        Command::SetDefault { id } => {
+            let st = load_providers_scratch()?;
+            match st.providers.iter().find(|p| p.id == id) {
+                None => return Err(anyhow!("no provider with id '{}' in chi.tmp.json", id)),
+                Some(p) if p.disabled => return Err(anyhow!("provider '{}' is disabled", id)),
+                Some(_) => {}
+            }
+            save_default_provider(&id)?;
+            Ok(json!({ "ok": true, "default_provider_id": id }))
+        }
+        Command::AddProvider { ptype, name, model, config } => {
+            ensure_chi_llm()?;
+            let mut st = load_providers_state()?;
+            if !st.schema_types.is_empty() && !st.schema_types.contains(&ptype) {
+                return Err(anyhow!("unknown provider type '{}' (known: {})", ptype, st.schema_types.join(", ")));
+            }
+            let schema = st.schema_map.get(&ptype).cloned().unwrap_or_default();
+            let mut values: Vec<(String, String)> = Vec::new();
+            if let Some(m) = model { values.push(("model".to_string(), m)); }
+            for kv in config {
+                let (k, v) = kv.split_once('=').ok_or_else(|| anyhow!("expected KEY=VALUE, got '{}'", kv))?;
+                values.push((k.to_string(), v.to_string()));
+            }
+            let entry = st.add_provider(&ptype);
+            if let Some(n) = name { entry.name = n; }
+            if let Some(obj) = entry.config.as_object_mut() {
+                for (k, v) in values {
+                    // Type the value the same way the form does (e.g. int fields)
+                    let value = match schema.iter().find(|sc| sc.name == k) {
+                        Some(sc) => FormField::from_value(sc, Some(&Value::String(v))).value(),
+                        None => Value::String(v),
+                    };
+                    obj.insert(k, value);
+                }
+            }
+            let missing: Vec<&str> = schema.iter()
+                .filter(|sc| sc.required && entry.config.get(&sc.name).is_none_or(|v| v.is_null() || v.as_str() == Some("")))
+                .map(|sc| sc.name.as_str())
+                .collect();
+            let out = json!({
+                "ok": true,
+                "id": entry.id,
+                "name": entry.name,
+                "type": entry.ptype,
+                "config": entry.config,
+                "missing_required": missing,
+            });
+            st.save()?;
+            Ok(out)
+        }
+        Command::Test { id, e2e } => {
+            ensure_chi_llm()?;
+            let entry = load_scratch_entries()?.into_iter().find(|e| e.id == id)
+                .ok_or_else(|| anyhow!("no provider with id '{}' in chi.tmp.json", id))?;
+            let mode = if e2e { TestMode::E2e } else { TestMode::default_for(&entry.ptype) };
+            let cmd = build_test_args(&entry, mode).map_err(|e| anyhow!(e))?;
+            eprintln!("testing {} [{}] ({:?})", entry.id, entry.ptype, mode);
+            eprintln!("$ {}", cmd.redacted());
+            let (status, _) = probe_streaming(&entry, mode, &cmd, &AtomicBool::new(false), |_, chunk| eprint!("{}", mask_values(chunk, &cmd.secrets)))?;
+            eprintln!("{}", status);
+            Ok(json!({ "ok": true, "id": entry.id, "mode": format!("{:?}", mode).to_lowercase(), "status": status }))
+        }
+        Command::Build { target, env_refs, format } => build(target, env_refs, format),
+        Command::Schema { out } => {
+            ensure_chi_llm()?;
+            let st = load_providers_state()?;
+            match out {
+                Some(dir) => {
+                    let written = write_json_schemas(&dir, &st.schema_types, &st.schema_map)?;
+                    Ok(json!({ "ok": true, "written": written }))
+                }
+                None => Ok(json!({
+                    "ok": true,
+                    "chi.tmp.json": scratch_json_schema(&st.schema_types, &st.schema_map),
+                    ".chi_llm.json": config_json_schema(&st.schema_types, &st.schema_map),
+                })),
+            }
+        }
+    }
+}
+
+/// `build`: the Build page's write (plaintext-secret check, then the
+/// `after_write` hooks) without the page. Where the page would ask about
+/// secrets in a file git tracks, this fails instead.
+fn build(target: BuildTarget, env_refs: bool, format: ConfigFormat) -> Result<Value> {
+    let mut app = App::new();
+    app.settings = load_settings().map_err(|e| anyhow!(e))?;
+    let spec = BuildState { target, format, env_refs, ..Default::default() };
+    let file = spec.project_file();
+    write_with_checks(&mut app, spec)?;
+    while app.build_git.check.is_some() {
+        thread::sleep(Duration::from_millis(20));
+        poll_build_git(&mut app);
+    }
+    if !app.modals.is_empty() {
+        return Err(anyhow!("{} would contain plaintext secrets and is not gitignored; use --env-refs or add it to .gitignore", file));
+    }
+    let page = app.build.take().unwrap_or_default();
+    if let Some(e) = page.status.as_deref().and_then(|s| s.strip_prefix("Error: ")) { return Err(anyhow!("{}", e)); }
+    // Hooks report through the log and notifications
+    while app.jobs.is_busy() { thread::sleep(Duration::from_millis(20)); }
+    while let Ok(line) = app.log_rx.try_recv() { eprintln!("[{}] {}", line.source, line.text); }
+    let failed: Vec<String> = app.notify_rx.try_iter().filter(|n| !n.ok).map(|n| n.message).collect();
+    if !failed.is_empty() { return Err(anyhow!("config written, but {}", failed.join("; "))); }
+    let written = config_path(target, format)?.to_string_lossy().to_string();
+    let mut out = json!({ "ok": true, "target": format!("{:?}", target).to_lowercase(), "written": written });
+    if env_refs { out["exports"] = json!(page.exports); }
+    Ok(out)
+}
+
+/// `--apply`: validate a providers file (the `chi.tmp.json` format) against the
+/// CLI schema and write its default provider to `target`. The project's own
+/// `chi.tmp.json` is left alone.
+pub fn apply_providers_file(path: &Path, target: BuildTarget) -> Result<Value> {
+    let text = fs::read_to_string(path).map_err(|e| anyhow!("{}: {}", path.display(), e))?;
+    let scratch: Value = serde_json::from_str(&text).map_err(|e| anyhow!("{}: {}", path.display(), e))?;
+    ensure_chi_llm()?;
+    let st = load_providers_state()?;
+    let errors = validate_providers(&scratch, &st.schema_types, &st.schema_map);
+    if !errors.is_empty() { return Err(anyhow!("{}", errors.join("; "))); }
+    let (_, cfg) = active_provider_in(&scratch)?;
+    let written = write_config(target, ConfigFormat::Json, cfg)?;
+    let count = scratch["providers"].as_array().map_or(0, |a| a.len());
+    Ok(json!({ "ok": true, "target": format!("{:?}", target).to_lowercase(), "written": written, "providers": count }))
+}
+
+/// Problems with a providers file, checked the way the Configure form checks
+/// them (known type, required fields, ranges), plus a usable default provider.
+pub fn validate_providers(scratch: &Value, types: &[String], schema_map: &HashMap<String, Vec<FieldSchema>>) -> Vec<String> {
+    let Some(arr) = scratch.get("providers").and_then(|p| p.as_array()) else {
+        return vec!["no providers array".to_string()];
+    };
+    let mut errors = Vec::new();
+    let mut ids = HashSet::new();
+    for (i, p) in arr.iter().enumerate() {
+        let entry = ProviderScratchEntry::from_json(p);
+        let at = format!("providers[{}] ({})", i, if entry.name.is_empty() { &entry.id } else { &entry.name });
+        if entry.id.is_empty() { errors.push(format!("{}: missing id", at)); }
+        else if !ids.insert(entry.id.clone()) { errors.push(format!("{}: duplicate id '{}'", at, entry.id)); }
+        if entry.ptype.is_empty() { errors.push(format!("{}: missing type", at)); continue; }
+        if !types.is_empty() && !types.contains(&entry.ptype) {
+            errors.push(format!("{}: unknown provider type '{}'", at, entry.ptype));
+            continue;
+        }
+        let fields: Vec<FormField> = schema_map.get(&entry.ptype)
+            .map(|s| s.iter().map(|sc| FormField::from_value(sc, entry.config.get(&sc.name))).collect())
+            .unwrap_or_default();
+        let missing: Vec<&str> = fields.iter().filter(|ff| ff.is_missing()).map(|ff| ff.schema.name.as_str()).collect();
+        if !missing.is_empty() { errors.push(format!("{}: missing required {}", at, missing.join(", "))); }
+        for ff in &fields {
+            if let Some(e) = ff.type_error() { errors.push(format!("{}: {}: {}", at, ff.schema.name, e)); }
+        }
+        errors.extend(tuning_errors(&fields).into_iter().chain(network_errors(&fields)).map(|e| format!("{}: {}", at, e)));
+    }
+    match scratch.get("default_provider_id").and_then(|d| d.as_str()) {
+        None => errors.push("no default_provider_id".to_string()),
+        Some(def) => match arr.iter().find(|p| p.get("id").and_then(|x| x.as_str()) == Some(def)) {
+            None => errors.push(format!("default_provider_id '{}' is not in providers", def)),
+            Some(p) if p.get("disabled").and_then(|x| x.as_bool()) == Some(true) => errors.push(format!("default provider '{}' is disabled", def)),
+            Some(_) => {}
+        },
+    }
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn providers_files_are_checked_like_the_form() {
+        let key = FieldSchema { name: "api_key".into(), ftype: "secret".into(), required: true, default: None, help: None, options: None };
+        let port = FieldSchema { name: "port".into(), ftype: "int".into(), required: false, default: None, help: None, options: None };
+        let types = vec!["openai".to_string(), "ollama".to_string()];
+        let schema_map = HashMap::from([("openai".to_string(), vec![key]), ("ollama".to_string(), vec![port])]);
+        let good = json!({
+            "default_provider_id": "a",
+            "providers": [
+                {"id": "a", "name": "work", "type": "openai", "config": {"api_key": "sk-1"}},
+                {"id": "b", "type": "ollama", "config": {"port": 11434}},
+            ],
+        });
+        assert!(validate_providers(&good, &types, &schema_map).is_empty());
+        let bad = json!({
+            "default_provider_id": "x",
+            "providers": [
+                {"id": "a", "name": "work", "type": "openai", "config": {}},
+                {"id": "a", "type": "ollama", "config": {"port": "big"}},
+                {"id": "c", "type": "nope"},
+            ],
+        });
+        assert_eq!(validate_providers(&bad, &types, &schema_map), [
+            "providers[0] (work): missing required api_key",
+            "providers[1] (a): duplicate id 'a'",
+            "providers[1] (a): port: must be a whole number",
+            "providers[2] (c): unknown provider type 'nope'",
+            "default_provider_id 'x' is not in providers",
+        ]);
+    }
+}
@@ -0,0 +1,243 @@
+//! Record of every program run through `util` (`chi-llm` calls, plugin test
+//! commands, `curl`): arguments with secrets masked, duration, exit code and
+//! the start of the output, kept in memory for the CLI Log page (`7`). Set
+//! `CHI_TUI_CLI_LOG` to a file path to also append each call there as a JSON
+//! line.
+
+use std::collections::VecDeque;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::{DateTime, Local};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::prelude::Frame;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, Paragraph, Wrap};
+use serde::Serialize;
+
+use crate::app::App;
+use crate::controller::PageController;
+use crate::keymap::KeyAction;
+use crate::msg::Msg;
+use crate::text::{pad, truncate};
+
+/// Calls kept in memory.
+pub const CLI_LOG_CAPACITY: usize = 200;
+/// Output kept per call, in characters.
+const OUTPUT_MAX: usize = 2000;
+const MASK: &str = "••••••";
+
+static CALLS: Mutex<VecDeque<CliCall>> = Mutex::new(VecDeque::new());
+
+#[derive(Clone, Debug, Serialize)]
+pub struct CliCall {
+    #[serde(serialize_with = "as_rfc3339")]
+    pub at: DateTime<Local>,
+    pub program: String,
+    /// Arguments, with the values of secret-looking flags and known secrets masked.
+    pub args: Vec<String>,
+    /// Names of the extra environment variables (values may hold secrets).
+    pub env: Vec<String>,
+    #[serde(rename = "duration_ms", serialize_with = "as_millis")]
+    pub duration: Duration,
+    /// `None` when the program did not run to completion (not found,
+    /// cancelled, timed out).
+    pub exit_code: Option<i32>,
+    /// Why the call failed, if it did.
+    pub error: Option<String>,
+    /// Start of stdout (stderr for a failed call).
+    pub output: String,
+}
+
+fn as_rfc3339<S: serde::Serializer>(at: &DateTime<Local>, s: S) -> Result<S::Ok, S::Error> {
+    s.serialize_str(&at.to_rfc3339())
+}
+
+fn as_millis<S: serde::Serializer>(d: &Duration, s: S) -> Result<S::Ok, S::Error> {
+    s.serialize_u64(d.as_millis() as u64)
+}
+
+impl CliCall {
+    pub fn ok(&self) -> bool {
+        self.error.is_none()
+    }
+
+    /// `chi-llm models list --json`.
+    pub fn command_line(&self) -> String {
+        std::iter::once(self.program.as_str()).chain(self.args.iter().map(String::as_str)).collect::<Vec<_>>().join(" ")
+    }
+}
+
+/// Flags whose value is masked in the log.
+fn is_secret_flag(flag: &str) -> bool {
+    let f = flag.to_ascii_lowercase();
+    f.starts_with("--") && ["key", "token", "secret", "password", "header"].iter().any(|s| f.contains(s))
+}
+
+/// `text` with every occurrence of a `secrets` value masked, also in its
+/// JSON-escaped form (plugin commands pass `{config_json}`).
+pub(crate) fn mask_values(text: &str, secrets: &[String]) -> String {
+    secrets.iter().filter(|s| !s.is_empty()).fold(text.to_string(), |acc, s| {
+        let escaped = serde_json::to_string(s).unwrap_or_default();
+        acc.replace(s.as_str(), MASK).replace(escaped.trim_matches('"'), MASK)
+    })
+}
+
+/// `args` with the value after each secret flag (or after `=`) masked, and
+/// every occurrence of a `secrets` value (e.g. a plugin's `{api_key}`).
+pub fn redact_args(args: &[&str], secrets: &[String]) -> Vec<String> {
+    let mut out = Vec::with_capacity(args.len());
+    let mut mask_next = false;
+    for a in args {
+        if mask_next {
+            out.push(MASK.to_string());
+            mask_next = false;
+            continue;
+        }
+        match a.split_once('=') {
+            Some((flag, _)) if is_secret_flag(flag) => out.push(format!("{}={}", flag, MASK)),
+            _ => {
+                mask_next = is_secret_flag(a);
+                out.push(mask_values(a, secrets));
+            }
+        }
+    }
+    out
+}
+
+/// Add a finished call to the log (and to `CHI_TUI_CLI_LOG`). `output` is
+/// stdout, or stderr when `error` is set; `secrets` are masked wherever they
+/// appear in the arguments or the output.
+#[allow(clippy::too_many_arguments)]
+pub fn record_call(program: &str, args: &[&str], secrets: &[String], env: &[(String, String)], duration: Duration, exit_code: Option<i32>, error: Option<String>, output: &str) {
+    let call = CliCall {
+        at: Local::now(),
+        program: program.to_string(),
+        args: redact_args(args, secrets),
+        env: env.iter().map(|(k, _)| k.clone()).collect(),
+        duration,
+        exit_code,
+        error,
+        output: truncate(&mask_values(output.trim(), secrets), OUTPUT_MAX),
+    };
+    if let Some(path) = std::env::var_os("CHI_TUI_CLI_LOG") {
+        if let (Ok(mut file), Ok(line)) = (OpenOptions::new().create(true).append(true).open(path), serde_json::to_string(&call)) {
+            let _ = writeln!(file, "{}", line);
+        }
+    }
+    let mut calls = CALLS.lock().unwrap_or_else(|e| e.into_inner());
+    if calls.len() == CLI_LOG_CAPACITY { calls.pop_front(); }
+    calls.push_back(call);
+}
+
+/// Recorded calls, newest first.
+pub fn cli_calls() -> Vec<CliCall> {
+    with_cli_calls(|calls| calls.iter().rev().cloned().collect())
+}
+
+/// Run `f` on the recorded calls (oldest first) under the log's lock, so
+/// drawing them needs no copy.
+pub fn with_cli_calls<R>(f: impl FnOnce(&VecDeque<CliCall>) -> R) -> R {
+    f(&CALLS.lock().unwrap_or_else(|e| e.into_inner()))
+}
+
+pub fn clear_cli_calls() {
+    CALLS.lock().unwrap_or_else(|e| e.into_inner()).clear();
+}
+
+fn call_row(c: &CliCall) -> String {
+    let exit = c.exit_code.map(|code| format!("exit {}", code)).unwrap_or_else(|| "no exit".to_string());
+    format!("{} {} {} {}", c.at.format("%H:%M:%S"), pad(&format!("{:.2}s", c.duration.as_secs_f64()), 7), pad(&exit, 8), c.command_line())
+}
+
+pub fn draw_cli_log(f: &mut Frame, area: Rect, app: &App) {
+    with_cli_calls(|calls| draw_calls(f, area, app, calls.iter().rev().collect()));
+}
+
+/// `calls` newest first.
+fn draw_calls(f: &mut Frame, area: Rect, app: &App, calls: Vec<&CliCall>) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
+        .split(area);
+    let selected = app.cli_log_selected.min(calls.len().saturating_sub(1));
+    let mut items: Vec<ListItem> = calls.iter().enumerate().map(|(i, c)| {
+        let style = if i == selected { app.theme.selected_row() } else if c.ok() { app.theme.text() } else { app.theme.error() };
+        ListItem::new(Line::from(Span::styled(call_row(c), style)))
+    }).collect();
+    if items.is_empty() {
+        items.push(ListItem::new(Line::from(Span::styled("No CLI calls yet.", app.theme.hint()))));
+    }
+    // Keep the selection in view
+    let visible = chunks[0].height.saturating_sub(2) as usize;
+    let items: Vec<ListItem> = items.into_iter().skip((selected + 1).saturating_sub(visible.max(1))).collect();
+    let title = format!("CLI calls ({}, newest first)", calls.len());
+    f.render_widget(List::new(items).block(Block::default().borders(Borders::ALL).border_style(app.theme.border()).title(title)), chunks[0]);
+
+    let mut lines = Vec::new();
+    if let Some(c) = calls.get(selected) {
+        lines.push(Line::from(Span::styled(format!("$ {}", c.command_line()), app.theme.title())));
+        if !c.env.is_empty() { lines.push(Line::from(Span::styled(format!("env: {}", c.env.join(", ")), app.theme.hint()))); }
+        let status = match (&c.error, c.exit_code) {
+            (None, _) => format!("ok in {:.2}s", c.duration.as_secs_f64()),
+            (Some(e), Some(code)) => format!("{} (exit {}) after {:.2}s", e, code, c.duration.as_secs_f64()),
+            (Some(e), None) => format!("{} after {:.2}s", e, c.duration.as_secs_f64()),
+        };
+        lines.push(Line::from(Span::styled(status, if c.ok() { app.theme.success() } else { app.theme.error() })));
+        lines.push(Line::from(""));
+        lines.extend(c.output.lines().map(|l| Line::from(l.to_string())));
+    }
+    let p = Paragraph::new(lines)
+        .style(app.theme.base())
+        .wrap(Wrap { trim: false })
+        .block(Block::default().borders(Borders::ALL).border_style(app.theme.border()).title("Call"));
+    f.render_widget(p, chunks[1]);
+}
+
+pub struct CliLogPage;
+
+impl PageController for CliLogPage {
+    fn handle_key(&self, app: &mut App, key: KeyEvent) -> Option<Msg> {
+        let last = with_cli_calls(|calls| calls.len()).saturating_sub(1);
+        match key.code {
+            KeyCode::Up => app.cli_log_selected = app.cli_log_selected.min(last).saturating_sub(1),
+            KeyCode::Down => app.cli_log_selected = (app.cli_log_selected + 1).min(last),
+            KeyCode::Home => app.cli_log_selected = 0,
+            KeyCode::End => app.cli_log_selected = last,
+            KeyCode::Char('c') | KeyCode::Char('C') => {
+                clear_cli_calls();
+                app.cli_log_selected = 0;
+            }
+            _ => return None,
+        }
+        Some(Msg::Handled)
+    }
+
+    fn draw(&self, f: &mut Frame, area: Rect, app: &App) {
+        draw_cli_log(f, area, app);
+    }
+
+    fn actions(&self) -> &'static [KeyAction] {
+        const ACTIONS: &[KeyAction] = &[
+            KeyAction::new("Up/Down", "select call"),
+            KeyAction::new("c", "clear"),
+            KeyAction::new("Esc", "back"),
+            KeyAction::new("?", "help"),
+        ];
+        ACTIONS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn secret_flag_values_are_masked() {
+        let args = redact_args(&["providers", "discover-models", "--api-key", "sk-123", "--header", "Authorization: Bearer x", "--token=abc", "--host", "h"], &[]);
+        assert_eq!(args, ["providers", "discover-models", "--api-key", MASK, "--header", MASK, "--token=••••••", "--host", "h"]);
+    }
+}
@@ -0,0 +1,63 @@
+//! System clipboard access. Failures (e.g. no display server over SSH) are
+//! returned as errors for the caller to show, never panics. Copying falls back
+//! to the terminal's OSC 52 sequence when there is no system clipboard, so it
+//! also works over SSH in terminals that support it; pasting has no such
+//! fallback (terminals do not answer clipboard reads by default), but the
+//! terminal's own paste arrives as a bracketed paste anyway.
+
+use std::io::Write;
+
+use anyhow::{anyhow, Result};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+
+/// Where copied text went.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CopiedTo {
+    System,
+    /// Sent to the terminal with OSC 52; whether it arrived is up to the terminal.
+    Terminal,
+}
+
+pub fn copy_text(text: &str) -> Result<CopiedTo> {
+    let system = arboard::Clipboard::new().and_then(|mut cb| cb.set_text(text.to_string()));
+    match system {
+        Ok(()) => Ok(CopiedTo::System),
+        Err(e) => {
+            let mut out = std::io::stdout();
+            out.write_all(osc52(text).as_bytes()).and_then(|_| out.flush())
+                .map_err(|io| anyhow!("clipboard unavailable: {} ({})", e, io))?;
+            Ok(CopiedTo::Terminal)
+        }
+    }
+}
+
+pub fn paste_text() -> Result<String> {
+    let mut cb = arboard::Clipboard::new().map_err(|e| anyhow!("clipboard unavailable: {}", e))?;
+    cb.get_text().map_err(|e| anyhow!("clipboard has no text: {}", e))
+}
+
+/// Toast text for a copy: `Copied <what>`, or why it failed.
+pub fn copy_message(what: &str, res: Result<CopiedTo>) -> (String, bool) {
+    match res {
+        Ok(CopiedTo::System) => (format!("Copied {}", what), true),
+        Ok(CopiedTo::Terminal) => (format!("Copied {} (via the terminal)", what), true),
+        Err(e) => (format!("Copy failed: {}", e), false),
+    }
+}
+
+/// OSC 52 "set clipboard" sequence for `text`.
+fn osc52(text: &str) -> String {
+    format!("\x1b]52;c;{}\x07", STANDARD.encode(text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn osc52_encodes_text() {
+        assert_eq!(osc52("sk-ü"), "\x1b]52;c;c2stw7w=\x07");
+        assert_eq!(copy_message("2 lines", Ok(CopiedTo::Terminal)), ("Copied 2 lines (via the terminal)".to_string(), true));
+    }
+}
@@ -0,0 +1,82 @@
+//! Windows terminal quirks, gated behind platform detection with a manual
+//! override (`--compat` or `CHI_TUI_COMPAT`):
+//!
+//! - crossterm reports key release (and repeat) events on Windows, which would
+//!   otherwise be handled as a second key press;
+//! - legacy conhost has no usable alternate screen, so the UI draws in place.
+
+use std::env;
+
+use crossterm::event::{KeyEvent, KeyEventKind};
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum CompatMode {
+    /// Enable on Windows only.
+    #[default]
+    Auto,
+    On,
+    Off,
+}
+
+impl CompatMode {
+    /// `CHI_TUI_COMPAT` value (`auto` | `on` | `off`), if set and valid.
+    pub fn from_env() -> Option<Self> {
+        match env::var("CHI_TUI_COMPAT").ok()?.trim().to_ascii_lowercase().as_str() {
+            "auto" => Some(CompatMode::Auto),
+            "on" | "1" | "true" => Some(CompatMode::On),
+            "off" | "0" | "false" => Some(CompatMode::Off),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Compat {
+    /// Only key presses are handled (release/repeat events are dropped).
+    pub press_only: bool,
+    /// Whether the alternate screen may be used.
+    pub alt_screen: bool,
+}
+
+impl Default for Compat {
+    fn default() -> Self {
+        Self { press_only: false, alt_screen: true }
+    }
+}
+
+impl Compat {
+    pub fn detect(mode: CompatMode) -> Self {
+        let enabled = match mode {
+            CompatMode::Auto => cfg!(windows),
+            CompatMode::On => true,
+            CompatMode::Off => false,
+        };
+        Self { press_only: enabled, alt_screen: !(enabled && is_legacy_console()) }
+    }
+
+    pub fn accepts(&self, key: &KeyEvent) -> bool {
+        !self.press_only || key.kind == KeyEventKind::Press
+    }
+}
+
+/// Classic conhost: Windows without Windows Terminal, ConEmu or a terminal
+/// that announces itself through `TERM_PROGRAM`.
+fn is_legacy_console() -> bool {
+    cfg!(windows) && ["WT_SESSION", "ConEmuANSI", "TERM_PROGRAM"].iter().all(|v| env::var_os(v).is_none())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::{KeyCode, KeyEventState, KeyModifiers};
+
+    #[test]
+    fn press_only_drops_release_events() {
+        let release = KeyEvent { code: KeyCode::Char('j'), modifiers: KeyModifiers::NONE, kind: KeyEventKind::Release, state: KeyEventState::NONE };
+        let press = KeyEvent::new(KeyCode::Char('j'), KeyModifiers::NONE);
+        let on = Compat::detect(CompatMode::On);
+        assert!(on.accepts(&press) && !on.accepts(&release));
+        let off = Compat::detect(CompatMode::Off);
+        assert!(off.accepts(&release) && off.alt_screen);
+    }
+}
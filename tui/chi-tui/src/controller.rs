@@ -0,0 +1,83 @@
+use crossterm::event::KeyEvent;
+use ratatui::layout::Rect;
+use ratatui::prelude::Frame;
+
+use crate::app::{App, Page};
+use crate::bench::BenchmarksPage;
+use crate::build::BuildPage;
+use crate::cli_log::CliLogPage;
+use crate::changelog::WhatsNewPage;
+use crate::diagnostics::DiagnosticsPage;
+use crate::keymap::{footer_hints, KeyAction, GLOBAL_ACTIONS};
+use crate::models::ModelBrowserPage;
+use crate::mouse::{HitTarget, Scroll};
+use crate::msg::Msg;
+use crate::providers::{ConfigurePage, RoutingPage, SelectDefaultPage};
+use crate::readme::ReadmePage;
+use crate::settings::SettingsPage;
+use crate::welcome::WelcomePage;
+
+/// Behaviour of a single page. The main loop routes keys, ticks and drawing to the
+/// controller of the active page; global shortcuts apply only to keys it leaves unhandled.
+pub trait PageController {
+    /// Handle a key. Page state changes happen here; anything app-level (leaving
+    /// the page, quitting) comes back as a message for `App::update`.
+    /// `Some(Msg::Handled)` when the page took the key, `None` to leave it to the
+    /// global keys.
+    fn handle_key(&self, app: &mut App, key: KeyEvent) -> Option<Msg>;
+
+    /// Handle a bracketed paste (the whole pasted text at once); return true when
+    /// the page took it. Unhandled pastes are dropped rather than replayed as keys.
+    fn handle_paste(&self, _app: &mut App, _text: &str) -> bool {
+        false
+    }
+
+    /// Handle a left click on a target the page registered in `App::hits` while
+    /// drawing; return true when it did something.
+    fn handle_click(&self, _app: &mut App, _target: HitTarget) -> bool {
+        false
+    }
+
+    /// Handle a mouse wheel notch; return true when the page scrolled.
+    fn handle_scroll(&self, _app: &mut App, _dir: Scroll) -> bool {
+        false
+    }
+
+    /// Called every loop iteration while the page is active (e.g. to start loading data).
+    fn tick(&self, _app: &mut App) {}
+
+    fn draw(&self, f: &mut Frame, area: Rect, app: &App);
+
+    /// Hints for the keys `handle_key` binds (see `keymap`); the global ones by default.
+    fn actions(&self) -> &'static [KeyAction] {
+        GLOBAL_ACTIONS
+    }
+
+    /// Key hints shown in the footer: the actions valid right now.
+    fn footer(&self, app: &App) -> String {
+        footer_hints(self.actions(), app)
+    }
+
+    /// True while the page needs every key (e.g. text entry), so global
+    /// shortcuts such as `q` must not fire.
+    fn captures_input(&self, _app: &App) -> bool {
+        false
+    }
+}
+
+pub fn controller(page: Page) -> &'static dyn PageController {
+    match page {
+        Page::Welcome => &WelcomePage,
+        Page::Readme => &ReadmePage,
+        Page::Configure => &ConfigurePage,
+        Page::SelectDefault => &SelectDefaultPage,
+        Page::Routing => &RoutingPage,
+        Page::ModelBrowser => &ModelBrowserPage,
+        Page::Diagnostics => &DiagnosticsPage,
+        Page::Build => &BuildPage,
+        Page::Settings => &SettingsPage,
+        Page::WhatsNew => &WhatsNewPage,
+        Page::Benchmarks => &BenchmarksPage,
+        Page::CliLog => &CliLogPage,
+    }
+}
@@ -0,0 +1,183 @@
+//! GGUF files added by hand in the Model Browser (`a`), listed next to the
+//! chi-llm catalog. With `CHI_LLM_MODELS_YAML` set they are registered in that
+//! catalog through `chi-llm models add`, so chi-llm lists them too; otherwise
+//! they are kept in `custom_models.json` in the state dir. Choosing one sets
+//! `model_path` on the selected provider, which chi-llm's local provider loads
+//! directly.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::backend::reset_backend;
+use crate::env::timeouts;
+use crate::paths::{file_in, paths};
+use crate::util::{invalidate_cli_cache, run_cli_json};
+
+/// Tag every custom model carries, so the tag filter can show only them.
+pub const CUSTOM_TAG: &str = "custom";
+/// Config key of chi-llm's local provider naming a GGUF file to load.
+pub const MODEL_PATH: &str = "model_path";
+/// How deep a directory is scanned for `.gguf` files.
+const SCAN_DEPTH: usize = 3;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CustomModel {
+    pub id: String,
+    pub name: String,
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_size_mb: Option<u64>,
+}
+
+impl CustomModel {
+    /// Entry for the GGUF file at `path`; the id is `custom/<file stem>`.
+    pub fn from_file(path: &Path) -> Self {
+        let stem = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+        let file_size_mb = fs::metadata(path).ok().map(|m| m.len() / (1024 * 1024));
+        Self { id: format!("{}/{}", CUSTOM_TAG, stem.to_lowercase()), name: stem, path: path.display().to_string(), file_size_mb }
+    }
+
+    /// The model as a `models list --json` item, so the browser parses it like
+    /// catalog models.
+    pub fn to_list_item(&self) -> Value {
+        let mut v = serde_json::json!({
+            "id": self.id,
+            "name": self.name,
+            "tags": [CUSTOM_TAG],
+            "downloaded": Path::new(&self.path).is_file(),
+            "path": self.path,
+            "custom": true,
+        });
+        if let Some(mb) = self.file_size_mb { v["file_size_mb"] = mb.into(); }
+        v
+    }
+}
+
+/// The models catalog chi-llm reads instead of its packaged one, if set.
+fn catalog_yaml() -> Option<String> {
+    std::env::var("CHI_LLM_MODELS_YAML").ok().filter(|v| !v.trim().is_empty())
+}
+
+/// Run a `chi-llm models` command that changes the catalog. chi-llm reads the
+/// catalog once per process, so the `serve` session is restarted afterwards
+/// to list the change.
+fn change_catalog(args: &[&str]) -> Result<Value> {
+    let out = run_cli_json(args, timeouts().cli);
+    reset_backend();
+    out
+}
+
+fn store_path() -> PathBuf {
+    paths().state_dir.join("custom_models.json")
+}
+
+/// Registered custom models; a missing or unreadable file gives none.
+pub fn load_custom_models() -> Vec<CustomModel> {
+    fs::read_to_string(store_path()).ok().and_then(|t| serde_json::from_str(&t).ok()).unwrap_or_default()
+}
+
+pub fn save_custom_models(models: &[CustomModel]) -> Result<()> {
+    let path = file_in(&paths().state_dir, "custom_models.json")?;
+    fs::write(path, serde_json::to_vec_pretty(models)?)?;
+    Ok(())
+}
+
+/// `.gguf` files at `path`: the file itself, or those under a directory
+/// (`SCAN_DEPTH` levels), sorted.
+pub fn find_gguf(path: &Path) -> Result<Vec<PathBuf>> {
+    let is_gguf = |p: &Path| p.extension().is_some_and(|e| e.eq_ignore_ascii_case("gguf"));
+    if path.is_file() {
+        return if is_gguf(path) { Ok(vec![path.to_path_buf()]) } else { Err(anyhow!("{} is not a .gguf file", path.display())) };
+    }
+    if !path.is_dir() { return Err(anyhow!("{} does not exist", path.display())); }
+    let mut found = Vec::new();
+    let mut dirs = vec![(path.to_path_buf(), 0)];
+    while let Some((dir, depth)) = dirs.pop() {
+        let Ok(read) = fs::read_dir(&dir) else { continue };
+        for p in read.flatten().map(|e| e.path()) {
+            if p.is_dir() && depth + 1 < SCAN_DEPTH { dirs.push((p, depth + 1)); }
+            else if p.is_file() && is_gguf(&p) { found.push(p); }
+        }
+    }
+    found.sort();
+    Ok(found)
+}
+
+/// Register the GGUF files at `path` (a file or a directory to scan), skipping
+/// ones already registered. Returns the new models.
+pub fn add_custom_models(path: &Path) -> Result<Vec<CustomModel>> {
+    let files = find_gguf(path)?;
+    if files.is_empty() { return Err(anyhow!("no .gguf files under {}", path.display())); }
+    let files: Vec<PathBuf> = files.iter().map(|f| fs::canonicalize(f).unwrap_or_else(|_| f.clone())).collect();
+    // The model list changes either way (`downloaded` too, for files in chi-llm's cache)
+    invalidate_cli_cache(&["models", "list"]);
+    if catalog_yaml().is_some() {
+        let paths: Vec<String> = files.iter().map(|f| f.display().to_string()).collect();
+        let mut args = vec!["models", "add"];
+        args.extend(paths.iter().map(String::as_str));
+        args.push("--json");
+        let out = change_catalog(&args)?;
+        let added = out.get("added").cloned().unwrap_or_default();
+        return Ok(serde_json::from_value(added)?);
+    }
+    let mut models = load_custom_models();
+    let added: Vec<CustomModel> = files.iter()
+        .map(|f| CustomModel::from_file(f))
+        .filter(|m| !models.iter().any(|old| old.path == m.path || old.id == m.id))
+        .collect();
+    models.extend(added.iter().cloned());
+    save_custom_models(&models)?;
+    Ok(added)
+}
+
+/// Forget the custom model `id` (the file stays). False when it is not one.
+pub fn remove_custom_model(id: &str) -> Result<bool> {
+    invalidate_cli_cache(&["models", "list"]);
+    if catalog_yaml().is_some() {
+        let out = change_catalog(&["models", "remove", id, "--json"])?;
+        // Models added before the catalog was set are still in the local store
+        if out.get("removed").and_then(Value::as_bool) == Some(true) { return Ok(true); }
+    }
+    let mut models = load_custom_models();
+    let before = models.len();
+    models.retain(|m| m.id != id);
+    if models.len() == before { return Ok(false); }
+    save_custom_models(&models)?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn directories_are_scanned_for_gguf_files() {
+        let dir = std::env::temp_dir().join(format!("chi-tui-gguf-{}", std::process::id()));
+        fs::create_dir_all(dir.join("sub")).unwrap();
+        for f in ["a.gguf", "sub/Qwen3-4B-Q4_K_M.GGUF", "notes.txt"] { fs::write(dir.join(f), b"x").unwrap(); }
+        let found = find_gguf(&dir).unwrap();
+        assert_eq!(found, [dir.join("a.gguf"), dir.join("sub/Qwen3-4B-Q4_K_M.GGUF")]);
+        assert!(find_gguf(&dir.join("notes.txt")).is_err());
+
+        let m = CustomModel::from_file(&found[1]);
+        assert_eq!((m.id.as_str(), m.name.as_str()), ("custom/qwen3-4b-q4_k_m", "Qwen3-4B-Q4_K_M"));
+        let item = m.to_list_item();
+        assert_eq!((item["downloaded"].as_bool(), item["tags"][0].as_str()), (Some(true), Some(CUSTOM_TAG)));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn entries_added_by_chi_llm_read_as_custom_models() {
+        let added = serde_json::json!([{
+            "id": "custom/a", "name": "a", "path": "/m/a.gguf", "filename": "a.gguf",
+            "file_size_mb": 3, "description": "Custom model", "tags": ["custom"],
+        }]);
+        let models: Vec<CustomModel> = serde_json::from_value(added).unwrap();
+        assert_eq!(models[0].path, "/m/a.gguf");
+        assert_eq!(models[0].file_size_mb, Some(3));
+    }
+}
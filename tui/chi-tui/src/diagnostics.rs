@@ -1,16 +1,28 @@
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
 use std::time::Duration;
 
 use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::layout::Rect;
-use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Paragraph, Wrap};
 use ratatui::prelude::Frame;
 use serde_json::Value;
 
-use crate::app::App;
-use crate::theme::Theme;
-use crate::util::run_cli_json;
+use crate::app::{App, Notification, Page};
+use crate::clipboard::{copy_message, copy_text};
+use crate::controller::PageController;
+use crate::keymap::KeyAction;
+use crate::env::timeouts;
+use crate::jobs::{Jobs, Periodic};
+use crate::json_view::JsonViewModal;
+use crate::modal::{ActionModal, PromptModal};
+use crate::msg::Msg;
+use crate::page_data::{draw_page_status, PageData};
+use crate::paths::{file_in, paths};
+use crate::providers::{check_configured_providers, Reachability};
+use crate::util::{invalidate_cli_cache, run_cli_json_cancellable};
 
 #[derive(Clone, Debug)]
 pub struct DiagState {
@@ -18,11 +30,86 @@ pub struct DiagState {
     pub diagnostics: Value,
     pub model_explain: Value,
     pub saved_path: Option<String>,
+    pub updated: chrono::DateTime<chrono::Local>,
 }
 
-pub fn fetch_diagnostics(timeout: Duration) -> Result<DiagState> {
-    let diag = run_cli_json(&["diagnostics", "--json"], timeout)?;
-    let explain = run_cli_json(&["models", "current", "--explain", "--json"], timeout)?;
+/// How often watch mode re-runs diagnostics.
+pub const WATCH_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Diagnostics plus the endpoint checks, as one watch-mode refresh.
+type Refresh = (Result<DiagState, String>, Result<Vec<Reachability>, String>);
+
+/// Watch mode (`w`): diagnostics and endpoint checks re-run every
+/// `WATCH_INTERVAL` in the background, the page keeping the previous results
+/// until new ones arrive.
+#[derive(Debug)]
+pub struct DiagWatch {
+    pub runs: Periodic<Refresh>,
+    /// Why the last refresh failed, until one succeeds.
+    pub error: Option<String>,
+}
+
+impl DiagWatch {
+    pub fn new() -> Self {
+        Self { runs: Periodic::new(WATCH_INTERVAL), error: None }
+    }
+
+    /// Start a refresh when one is due and none is running.
+    fn start_due(&mut self, jobs: &Jobs) {
+        if !self.runs.is_due() { return; }
+        forget_cached_diagnostics();
+        self.runs.start(jobs, "Watching diagnostics", |ctx| {
+            let diag = fetch_diagnostics(timeouts().cli, ctx.cancel_flag()).map_err(|e| e.to_string());
+            (diag, check_configured_providers().map_err(|e| e.to_string()))
+        });
+    }
+}
+
+impl Default for DiagWatch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Advance watch mode: collect a finished refresh into the page and start the
+/// next one when due.
+pub fn tick_watch(app: &mut App) {
+    let Some(watch) = app.diag_watch.as_mut() else { return };
+    if let Some(done) = watch.runs.poll() {
+        match done {
+            Ok((diag, reach)) => {
+                watch.error = diag.as_ref().err().cloned();
+                if let Ok(diag) = diag { app.diag = PageData::Ready(diag); }
+                app.reach = reach.map_or_else(PageData::Error, PageData::Ready);
+            }
+            Err(e) => watch.error = Some(e),
+        }
+    }
+    watch.start_due(&app.jobs);
+}
+
+/// `w`: start or stop watch mode.
+pub fn toggle_watch(app: &mut App) {
+    match app.diag_watch.take() {
+        Some(mut watch) => watch.runs.stop(),
+        None => {
+            let mut watch = DiagWatch::new();
+            // The data on screen is current when it is ready; otherwise refresh now
+            if app.diag.ready().is_some() { watch.runs = watch.runs.delayed(); }
+            app.diag_watch = Some(watch);
+        }
+    }
+}
+
+/// `r` and watch mode ask the CLI again instead of reusing a cached answer.
+fn forget_cached_diagnostics() {
+    invalidate_cli_cache(&["diagnostics"]);
+    invalidate_cli_cache(&["models", "current"]);
+}
+
+pub fn fetch_diagnostics(timeout: Duration, cancel: &AtomicBool) -> Result<DiagState> {
+    let diag = run_cli_json_cancellable(&["diagnostics", "--json"], timeout, cancel)?;
+    let explain = run_cli_json_cancellable(&["models", "current", "--explain", "--json"], timeout, cancel)?;
     let mut summary = Vec::new();
     if let Some(py) = diag
         .get("python")
@@ -48,53 +135,159 @@ pub fn fetch_diagnostics(timeout: Duration) -> Result<DiagState> {
         diagnostics: diag,
         model_explain: explain,
         saved_path: None,
+        updated: chrono::Local::now(),
     })
 }
 
-pub fn export_diagnostics(d: &DiagState) -> Result<String> {
-    let obj = serde_json::json!({
-        "timestamp": chrono::Utc::now().to_rfc3339(),
-        "diagnostics": d.diagnostics,
-        "model_explain": d.model_explain,
-    });
-    let path = "chi_llm_diagnostics.json".to_string();
-    std::fs::write(&path, serde_json::to_vec_pretty(&obj)?)?;
-    Ok(path)
+/// Format of an exported diagnostics report.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ReportFormat {
+    /// The raw CLI output, for tools.
+    Json,
+    /// A readable report to paste into a GitHub issue.
+    Markdown,
+}
+
+impl ReportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ReportFormat::Json => "json",
+            ReportFormat::Markdown => "md",
+        }
+    }
+}
+
+/// Timestamped report file in the export directory, created if needed.
+pub fn default_export_path(format: ReportFormat) -> Result<PathBuf> {
+    let name = format!("diagnostics-{}.{}", chrono::Local::now().format("%Y%m%d-%H%M%S"), format.extension());
+    Ok(file_in(&paths().export_dir(), &name)?)
+}
+
+/// The report for `d`; the Markdown one also lists `reach` when given.
+pub fn render_report(d: &DiagState, format: ReportFormat, reach: Option<&[Reachability]>) -> Result<String> {
+    match format {
+        ReportFormat::Json => {
+            let obj = serde_json::json!({
+                "timestamp": chrono::Utc::now().to_rfc3339(),
+                "diagnostics": d.diagnostics,
+                "model_explain": d.model_explain,
+            });
+            Ok(serde_json::to_string_pretty(&obj)?)
+        }
+        ReportFormat::Markdown => Ok(markdown_report(d, reach)),
+    }
+}
+
+fn markdown_report(d: &DiagState, reach: Option<&[Reachability]>) -> String {
+    let mut out = String::from("## chi-llm diagnostics\n\n");
+    out.push_str(&format!("Collected {} with chi-tui {}.\n\n", d.updated.format("%Y-%m-%d %H:%M:%S %:z"), env!("CARGO_PKG_VERSION")));
+    out.push_str("### Summary\n\n");
+    for s in &d.summary { out.push_str(&format!("- {}\n", s)); }
+    if let Some(reach) = reach.filter(|r| !r.is_empty()) {
+        out.push_str("\n### Provider reachability\n\n| Provider | Type | Endpoint | Result | Latency |\n|---|---|---|---|---|\n");
+        for r in reach {
+            let result = match &r.result { Ok(s) => format!("✓ {}", s), Err(e) => format!("✗ {}", e) };
+            let latency = r.latency.map(|l| format!("{} ms", l.as_millis())).unwrap_or_default();
+            let cells = [r.name.as_str(), &r.ptype, r.endpoint.as_deref().unwrap_or("-"), &result, &latency].map(|c| c.replace('|', "\\|"));
+            out.push_str(&format!("| {} |\n", cells.join(" | ")));
+        }
+    }
+    for (title, v) in [("chi-llm diagnostics --json", &d.diagnostics), ("chi-llm models current --explain --json", &d.model_explain)] {
+        let json = serde_json::to_string_pretty(v).unwrap_or_default();
+        out.push_str(&format!("\n<details><summary><code>{}</code></summary>\n\n```json\n{}\n```\n\n</details>\n", title, json));
+    }
+    out
+}
+
+/// Write the report to `path`; `~/` is the home directory. Returns the path written.
+pub fn export_diagnostics(d: &DiagState, format: ReportFormat, reach: Option<&[Reachability]>, path: &str) -> Result<String> {
+    let path = match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    };
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) { std::fs::create_dir_all(dir)?; }
+    std::fs::write(&path, render_report(d, format, reach)?)?;
+    Ok(path.display().to_string())
+}
+
+/// `e`: pick a format, then confirm or edit the output path.
+fn open_export(app: &mut App) {
+    let prompt_for = |format: ReportFormat| move |app: &mut App| {
+        let mut prompt = PromptModal::new(format!("Save {} report to", format.extension()), move |app, path| {
+            let Some(diag) = app.diag.ready() else { return };
+            match export_diagnostics(diag, format, app.reach.ready().map(Vec::as_slice), &path) {
+                Ok(path) => {
+                    let _ = app.notify_tx.send(Notification::new(format!("Saved report to {path}"), true, Page::Diagnostics));
+                    if let Some(diag) = app.diag.ready_mut() { diag.saved_path = Some(path); }
+                }
+                Err(e) => app.report_error(format!("Export failed: {e}")),
+            }
+        });
+        match default_export_path(format) {
+            Ok(path) => prompt.buffer = path.display().to_string(),
+            Err(e) => app.report_error(format!("Export failed: {e}")),
+        }
+        app.modals.push(Box::new(prompt));
+    };
+    let modal = ActionModal::new("Export diagnostics", "Markdown is readable and fits a GitHub issue; JSON is the raw CLI output.")
+        .action('m', "Markdown report", prompt_for(ReportFormat::Markdown))
+        .action('j', "JSON", prompt_for(ReportFormat::Json));
+    app.modals.push(Box::new(modal));
+}
+
+/// `✓ name (type) endpoint: HTTP 200, 12 ms`, or why it is not reachable.
+fn reach_line(r: &Reachability, app: &App) -> Line<'static> {
+    let at = r.endpoint.as_ref().map(|e| format!(" {}", e)).unwrap_or_default();
+    let ms = r.latency.map(|l| format!(", {} ms", l.as_millis())).unwrap_or_default();
+    match &r.result {
+        Ok(detail) if r.endpoint.is_none() => Line::from(Span::styled(format!("· {} ({}): {}", r.name, r.ptype, detail), app.theme.hint())),
+        Ok(detail) => Line::from(Span::styled(format!("✓ {} ({}){}: {}{}", r.name, r.ptype, at, detail, ms), app.theme.success())),
+        Err(e) => Line::from(Span::styled(format!("✗ {} ({}){}: {}{}", r.name, r.ptype, at, e, ms), app.theme.error())),
+    }
 }
 
 pub fn draw_diagnostics(f: &mut Frame, area: Rect, app: &App) {
+    let Some(diag) = app.diag.ready() else {
+        draw_page_status(f, area, app, "Diagnostics", &app.diag);
+        return;
+    };
     let mut lines: Vec<Line> = Vec::new();
-    if let Some(err) = &app.last_error {
-        lines.push(Line::from(Span::styled(
-            err.clone(),
-            Style::default().fg(Color::Red),
-        )));
+    lines.push(Line::from(Span::styled(
+        "Diagnostics summary:",
+        app.theme.title(),
+    )));
+    for s in &diag.summary {
+        lines.push(Line::from(s.as_str()));
     }
-    if let Some(diag) = &app.diag {
+    let mut updated = format!("Last updated: {}", diag.updated.format("%H:%M:%S"));
+    if let Some(watch) = &app.diag_watch {
+        updated.push_str(&format!(" • watching every {}s ('w' stops)", WATCH_INTERVAL.as_secs()));
+        if watch.runs.is_running() { updated.push_str(" • refreshing…"); }
+    }
+    lines.push(Line::from(Span::styled(updated, app.theme.hint())));
+    if let Some(e) = app.diag_watch.as_ref().and_then(|w| w.error.as_ref()) {
+        lines.push(Line::from(Span::styled(format!("Refresh failed: {}", e), app.theme.error())));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled("Provider reachability:", app.theme.title())));
+    match &app.reach {
+        PageData::Ready(results) if results.is_empty() => lines.push(Line::from(Span::styled("No providers in chi.tmp.json.", app.theme.hint()))),
+        PageData::Ready(results) => lines.extend(results.iter().map(|r| reach_line(r, app))),
+        PageData::Error(e) => lines.push(Line::from(Span::styled(format!("Error: {}", e), app.theme.error()))),
+        PageData::Loading(_) | PageData::NotLoaded => lines.push(Line::from(Span::styled("Checking endpoints…", app.theme.hint()))),
+    }
+    if let Some(path) = &diag.saved_path {
         lines.push(Line::from(Span::styled(
-            "Diagnostics summary:",
-            Style::default()
-                .fg(app.theme.primary)
-                .add_modifier(Modifier::BOLD),
+            format!("Exported: {}", path),
+            app.theme.hint(),
         )));
-        for s in &diag.summary {
-            lines.push(Line::from(s.as_str()));
-        }
-        if let Some(path) = &diag.saved_path {
-            lines.push(Line::from(Span::styled(
-                format!("Exported: {}", path),
-                Style::default().fg(app.theme.secondary),
-            )));
-        }
-    } else {
-        lines.push(Line::from("Loading diagnostics..."));
     }
     let p = Paragraph::new(lines)
-        .style(Style::default().bg(app.theme.bg).fg(app.theme.fg))
+        .style(app.theme.base())
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .border_style(Style::default().fg(app.theme.frame))
+                .border_style(app.theme.border())
                 .title("Diagnostics"),
         )
         .alignment(ratatui::layout::Alignment::Left)
@@ -102,3 +295,93 @@ pub fn draw_diagnostics(f: &mut Frame, area: Rect, app: &App) {
     f.render_widget(p, area);
 }
 
+pub struct DiagnosticsPage;
+
+impl PageController for DiagnosticsPage {
+    fn handle_key(&self, app: &mut App, key: KeyEvent) -> Option<Msg> {
+        match key.code {
+            KeyCode::Char('e') | KeyCode::Char('E') => {
+                if app.diag.ready().is_some() { open_export(app); }
+            }
+            KeyCode::Char('r') | KeyCode::Char('R') => {
+                forget_cached_diagnostics();
+                app.diag.load(&app.jobs, "Running diagnostics", |ctx| fetch_diagnostics(timeouts().cli, ctx.cancel_flag()));
+                app.reach.load(&app.jobs, "Checking provider endpoints", |_| check_configured_providers());
+            }
+            KeyCode::Char('w') | KeyCode::Char('W') => toggle_watch(app),
+            KeyCode::Char('v') | KeyCode::Char('V') => {
+                let Some(diag) = app.diag.ready() else { return Some(Msg::Handled) };
+                let raw = serde_json::json!({"diagnostics": diag.diagnostics, "model_explain": diag.model_explain});
+                app.modals.push(Box::new(JsonViewModal::new("Diagnostics: raw JSON", raw)));
+            }
+            KeyCode::Char('y') | KeyCode::Char('Y') => {
+                let Some(diag) = app.diag.ready() else { return Some(Msg::Handled) };
+                let copied = render_report(diag, ReportFormat::Json, app.reach.ready().map(Vec::as_slice)).and_then(|json| copy_text(&json));
+                let (msg, ok) = copy_message("diagnostics as JSON", copied);
+                let _ = app.notify_tx.send(Notification::new(msg, ok, Page::Diagnostics));
+            }
+            _ => return None,
+        }
+        Some(Msg::Handled)
+    }
+
+    fn tick(&self, app: &mut App) {
+        app.diag.ensure(&app.jobs, "Running diagnostics", |ctx| fetch_diagnostics(timeouts().cli, ctx.cancel_flag()));
+        app.reach.ensure(&app.jobs, "Checking provider endpoints", |_| check_configured_providers());
+        tick_watch(app);
+    }
+
+    fn draw(&self, f: &mut Frame, area: Rect, app: &App) {
+        draw_diagnostics(f, area, app);
+    }
+
+    fn actions(&self) -> &'static [KeyAction] {
+        const ACTIONS: &[KeyAction] = &[
+            KeyAction::new("Esc", "back"),
+            KeyAction::new("q", "quit"),
+            KeyAction::new("e", "export").when(|app| app.diag.ready().is_some()),
+            KeyAction::new("v", "raw JSON").when(|app| app.diag.ready().is_some()),
+            KeyAction::new("y", "copy JSON").when(|app| app.diag.ready().is_some()),
+            KeyAction::new("r", "refresh").when(|app| !app.diag.is_loading()),
+            KeyAction::new("w", "watch").when(|app| app.diag_watch.is_none()),
+            KeyAction::new("w", "stop watching").when(|app| app.diag_watch.is_some()),
+            KeyAction::new("?", "help"),
+        ];
+        ACTIONS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn markdown_report_has_summary_reachability_and_raw_output() {
+        let d = DiagState {
+            summary: vec!["python: 3.12".to_string()],
+            diagnostics: serde_json::json!({"python": {"version": "3.12"}}),
+            model_explain: serde_json::json!({"current_model": "qwen3"}),
+            saved_path: None,
+            updated: chrono::Local::now(),
+        };
+        let reach = [Reachability {
+            id: "p1".to_string(),
+            name: "home|lab".to_string(),
+            ptype: "ollama".to_string(),
+            endpoint: Some("http://127.0.0.1:11434".to_string()),
+            result: Ok("HTTP 200".to_string()),
+            latency: Some(Duration::from_millis(12)),
+        }];
+        let md = render_report(&d, ReportFormat::Markdown, Some(&reach)).unwrap();
+        assert!(md.starts_with("## chi-llm diagnostics\n"));
+        assert!(md.contains("- python: 3.12\n"));
+        assert!(md.contains("| home\\|lab | ollama | http://127.0.0.1:11434 | ✓ HTTP 200 | 12 ms |\n"), "{}", md);
+        assert!(md.contains("```json\n{\n  \"current_model\": \"qwen3\"\n}\n```"));
+
+        let path = std::env::temp_dir().join(format!("chi-tui-diag-{}/report.json", std::process::id()));
+        let written = export_diagnostics(&d, ReportFormat::Json, None, &path.display().to_string()).unwrap();
+        let v: Value = serde_json::from_str(&std::fs::read_to_string(&written).unwrap()).unwrap();
+        assert_eq!(v["model_explain"]["current_model"], "qwen3");
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
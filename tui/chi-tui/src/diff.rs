@@ -0,0 +1,131 @@
+//! Field-level differences between two JSON configs, e.g. the default provider
+//! in `chi.tmp.json` (as Build would write it) and the written `.chi_llm.json`.
+//! Objects are compared key by key and lists of objects (`provider_profiles`)
+//! entry by entry; anything else is compared as a whole value.
+
+use serde_json::Value;
+
+use crate::interp::env_refs;
+use crate::keychain::keyring_account;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Change {
+    Added(Value),
+    Removed(Value),
+    Changed { old: Value, new: Value },
+}
+
+/// One differing field; `path` is dotted, with `[i]` for list entries
+/// (`provider_profiles[1].model`).
+#[derive(Clone, Debug, PartialEq)]
+pub struct FieldChange {
+    pub path: String,
+    pub change: Change,
+}
+
+impl FieldChange {
+    /// The last key of `path`, without list indices.
+    pub fn key(&self) -> &str {
+        let last = self.path.rsplit('.').next().unwrap_or(&self.path);
+        last.split('[').next().unwrap_or(last)
+    }
+
+    /// `+ path: new`, `- path: old` or `~ path: old → new`. Values of keys
+    /// `is_secret` accepts are masked unless they are references.
+    pub fn line(&self, is_secret: impl Fn(&str) -> bool) -> String {
+        let secret = is_secret(self.key());
+        let show = |v: &Value| show_value(v, secret, &is_secret);
+        match &self.change {
+            Change::Added(v) => format!("+ {}: {}", self.path, show(v)),
+            Change::Removed(v) => format!("- {}: {}", self.path, show(v)),
+            Change::Changed { old, new } => format!("~ {}: {} → {}", self.path, show(old), show(new)),
+        }
+    }
+}
+
+/// `v` for display; a whole added or removed block masks its secret keys too.
+fn show_value(v: &Value, secret: bool, is_secret: &impl Fn(&str) -> bool) -> String {
+    match v {
+        Value::String(s) if secret && env_refs(s).is_empty() && keyring_account(s).is_none() => "••••••".to_string(),
+        Value::String(s) => s.clone(),
+        Value::Object(obj) => {
+            let masked: serde_json::Map<String, Value> = obj.iter().map(|(k, v)| {
+                let v = match v {
+                    Value::String(_) if is_secret(k) => Value::String(show_value(v, true, is_secret)),
+                    _ => v.clone(),
+                };
+                (k.clone(), v)
+            }).collect();
+            Value::Object(masked).to_string()
+        }
+        other => other.to_string(),
+    }
+}
+
+/// What changes going from `old` to `new`.
+pub fn diff_values(old: &Value, new: &Value) -> Vec<FieldChange> {
+    let mut out = Vec::new();
+    diff_at("", old, new, &mut out);
+    out
+}
+
+fn diff_at(path: &str, old: &Value, new: &Value, out: &mut Vec<FieldChange>) {
+    let join = |key: &str| if path.is_empty() { key.to_string() } else { format!("{}.{}", path, key) };
+    match (old, new) {
+        (Value::Object(a), Value::Object(b)) => {
+            for (k, va) in a {
+                match b.get(k) {
+                    Some(vb) => diff_at(&join(k), va, vb, out),
+                    None => out.push(FieldChange { path: join(k), change: Change::Removed(va.clone()) }),
+                }
+            }
+            for (k, vb) in b.iter().filter(|(k, _)| !a.contains_key(*k)) {
+                out.push(FieldChange { path: join(k), change: Change::Added(vb.clone()) });
+            }
+        }
+        (Value::Array(a), Value::Array(b)) if a.iter().chain(b).all(Value::is_object) => {
+            for i in 0..a.len().max(b.len()) {
+                let at = format!("{}[{}]", path, i);
+                match (a.get(i), b.get(i)) {
+                    (Some(va), Some(vb)) => diff_at(&at, va, vb, out),
+                    (Some(va), None) => out.push(FieldChange { path: at, change: Change::Removed(va.clone()) }),
+                    (None, Some(vb)) => out.push(FieldChange { path: at, change: Change::Added(vb.clone()) }),
+                    (None, None) => {}
+                }
+            }
+        }
+        _ if old != new => out.push(FieldChange { path: path.to_string(), change: Change::Changed { old: old.clone(), new: new.clone() } }),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn nested_fields_and_profiles_are_compared_one_by_one() {
+        let old = json!({
+            "provider": {"type": "openai", "model": "gpt-4o", "api_key": "sk-old", "timeout": 30},
+            "provider_profiles": [{"name": "a", "tags": ["chat"]}],
+        });
+        let new = json!({
+            "provider": {"type": "openai", "model": "gpt-4o-mini", "api_key": "sk-new", "base_url": "https://x"},
+            "provider_profiles": [{"name": "a", "tags": ["chat", "code"]}, {"name": "b", "api_key": "sk-b"}],
+        });
+        let is_secret = |k: &str| k == "api_key";
+        let lines: Vec<String> = diff_values(&old, &new).iter().map(|c| c.line(is_secret)).collect();
+        assert_eq!(lines, [
+            "~ provider.api_key: •••••• → ••••••",
+            "~ provider.model: gpt-4o → gpt-4o-mini",
+            "- provider.timeout: 30",
+            "+ provider.base_url: https://x",
+            "~ provider_profiles[0].tags: [\"chat\"] → [\"chat\",\"code\"]",
+            "+ provider_profiles[1]: {\"api_key\":\"••••••\",\"name\":\"b\"}",
+        ]);
+        assert!(diff_values(&new, &new).is_empty());
+        let refs = diff_values(&json!({"api_key": "${KEY}"}), &json!({"api_key": "keyring:p1/api_key"}));
+        assert_eq!(refs[0].line(is_secret), "~ api_key: ${KEY} → keyring:p1/api_key");
+    }
+}
@@ -0,0 +1,160 @@
+//! Startup overrides read from `CHI_TUI_*` environment variables, so dotfiles and
+//! CI can configure the UI without flags.
+//!
+//! - `CHI_TUI_THEME`: `dark` | `light`
+//! - `CHI_TUI_PAGE`: start page, e.g. `configure`, `diagnostics`, `models`
+//! - `CHI_TUI_NO_ANIM`: any value except `0`/`false` disables the animation
+//! - `CHI_TUI_A11Y`: any value except `0`/`false` enables accessibility mode
+//! - `CHI_TUI_TIMEOUT_CLI`, `CHI_TUI_TIMEOUT_TEST`, `CHI_TUI_TIMEOUT_E2E`: seconds for
+//!   data-loading CLI calls, connection tests and end-to-end tests
+//! - `CHI_TUI_CLI_LOG`: file to append every CLI call to, one JSON object per line
+//!
+//! These win over the values saved in `settings.toml`.
+
+use std::env;
+use std::sync::RwLock;
+use std::time::Duration;
+
+use crate::app::{App, Notification, Page};
+use crate::theme::Theme;
+
+/// Timeouts for `chi-llm` invocations.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Timeouts {
+    /// Schema, model list and diagnostics calls.
+    pub cli: Duration,
+    /// Connection tests and model discovery.
+    pub test: Duration,
+    /// End-to-end `generate` tests.
+    pub e2e: Duration,
+}
+
+impl Default for Timeouts {
+    fn default() -> Self {
+        Self { cli: Duration::from_secs(5), test: Duration::from_secs(5), e2e: Duration::from_secs(90) }
+    }
+}
+
+static TIMEOUTS: RwLock<Option<Timeouts>> = RwLock::new(None);
+
+/// Process-wide timeouts: `settings.toml` values (see `set_timeouts`) or the
+/// defaults, with the environment taking precedence.
+pub fn timeouts() -> Timeouts {
+    if let Some(t) = *TIMEOUTS.read().unwrap_or_else(|e| e.into_inner()) { return t; }
+    let t = with_env(Timeouts::default());
+    *TIMEOUTS.write().unwrap_or_else(|e| e.into_inner()) = Some(t);
+    t
+}
+
+/// Use `base` for every timeout not set through `CHI_TUI_TIMEOUT_*`.
+pub fn set_timeouts(base: Timeouts) {
+    *TIMEOUTS.write().unwrap_or_else(|e| e.into_inner()) = Some(with_env(base));
+}
+
+fn with_env(base: Timeouts) -> Timeouts {
+    let read = |name: &str, default: Duration| env::var(name).ok().and_then(|v| parse_secs(&v)).unwrap_or(default);
+    Timeouts {
+        cli: read("CHI_TUI_TIMEOUT_CLI", base.cli),
+        test: read("CHI_TUI_TIMEOUT_TEST", base.test),
+        e2e: read("CHI_TUI_TIMEOUT_E2E", base.e2e),
+    }
+}
+
+/// Positive number of seconds (fractions allowed).
+pub fn parse_secs(v: &str) -> Option<Duration> {
+    let secs: f64 = v.trim().parse().ok()?;
+    (secs.is_finite() && secs > 0.0).then(|| Duration::from_secs_f64(secs))
+}
+
+/// Page for a `CHI_TUI_PAGE` value.
+pub fn parse_page(v: &str) -> Option<Page> {
+    let page = match v.trim().to_ascii_lowercase().replace('_', "-").as_str() {
+        "welcome" => Page::Welcome,
+        "readme" | "docs" => Page::Readme,
+        "configure" | "providers" => Page::Configure,
+        "select-default" | "default" => Page::SelectDefault,
+        "model-browser" | "models" => Page::ModelBrowser,
+        "diagnostics" | "diag" => Page::Diagnostics,
+        "routing" | "tags" => Page::Routing,
+        "build" => Page::Build,
+        "settings" => Page::Settings,
+        "whats-new" | "changelog" => Page::WhatsNew,
+        "benchmarks" | "bench" => Page::Benchmarks,
+        "cli-log" | "logs" => Page::CliLog,
+        _ => return None,
+    };
+    Some(page)
+}
+
+/// The name `parse_page` reads back for `page`.
+pub fn page_slug(page: Page) -> &'static str {
+    match page {
+        Page::Welcome => "welcome",
+        Page::Readme => "docs",
+        Page::Configure => "configure",
+        Page::SelectDefault => "select-default",
+        Page::ModelBrowser => "models",
+        Page::Diagnostics => "diagnostics",
+        Page::Routing => "routing",
+        Page::Build => "build",
+        Page::Settings => "settings",
+        Page::WhatsNew => "whats-new",
+        Page::Benchmarks => "benchmarks",
+        Page::CliLog => "cli-log",
+    }
+}
+
+fn parse_flag(v: &str) -> bool {
+    !matches!(v.trim().to_ascii_lowercase().as_str(), "0" | "false" | "no" | "off")
+}
+
+/// Apply theme, start page, animation and accessibility overrides to a fresh `App`. Invalid
+/// values are reported through a toast rather than aborting startup.
+pub fn apply_env_overrides(app: &mut App) {
+    let mut invalid: Vec<String> = Vec::new();
+    if let Ok(v) = env::var("CHI_TUI_THEME") {
+        match v.trim().to_ascii_lowercase().as_str() {
+            "dark" => app.theme = Theme::synthwave_dark(),
+            "light" => app.theme = Theme::synthwave_light(),
+            _ => invalid.push(format!("CHI_TUI_THEME={v}")),
+        }
+    }
+    if let Ok(v) = env::var("CHI_TUI_PAGE") {
+        match parse_page(&v) {
+            Some(page) => app.page = page,
+            None => invalid.push(format!("CHI_TUI_PAGE={v}")),
+        }
+    }
+    if let Ok(v) = env::var("CHI_TUI_NO_ANIM") {
+        app.anim = !parse_flag(&v);
+    }
+    if let Ok(v) = env::var("CHI_TUI_A11Y") {
+        app.a11y = parse_flag(&v);
+    }
+    for name in ["CHI_TUI_TIMEOUT_CLI", "CHI_TUI_TIMEOUT_TEST", "CHI_TUI_TIMEOUT_E2E"] {
+        if let Ok(v) = env::var(name) {
+            if parse_secs(&v).is_none() { invalid.push(format!("{name}={v}")); }
+        }
+    }
+    if !invalid.is_empty() {
+        let _ = app.notify_tx.send(Notification::new(format!("Ignored invalid env: {}", invalid.join(", ")), false, app.page));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_pages_secs_and_flags() {
+        assert_eq!(parse_page("Select_Default"), Some(Page::SelectDefault));
+        assert_eq!(parse_page("models"), Some(Page::ModelBrowser));
+        assert_eq!(parse_page("nope"), None);
+        assert_eq!(parse_page(page_slug(Page::CliLog)), Some(Page::CliLog));
+        assert_eq!(parse_secs("2.5"), Some(Duration::from_millis(2500)));
+        assert_eq!(parse_secs("0"), None);
+        assert_eq!(parse_secs("abc"), None);
+        assert!(parse_flag("1") && parse_flag("yes"));
+        assert!(!parse_flag("false") && !parse_flag("0"));
+    }
+}
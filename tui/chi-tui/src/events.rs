@@ -0,0 +1,56 @@
+//! In-process event bus for cross-page effects. Pages queue an `AppEvent` with
+//! `App::emit`; `App::process_events` applies the queue once per loop iteration,
+//! so the effect is visible on the next frame regardless of which page is active.
+
+use crate::app::{App, Notification, Page};
+use crate::custom_models::MODEL_PATH;
+use crate::providers::{is_local_type, load_providers_state};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AppEvent {
+    /// A model was chosen in the Model Browser: set it on the selected provider.
+    ModelPicked(String),
+    /// A custom GGUF file was chosen: set it as `model_path` on the selected
+    /// (local) provider.
+    ModelFilePicked(String),
+    /// Switch to a page.
+    Navigate(Page),
+}
+
+/// Apply one event. Returns the event when it has to wait (e.g. for provider data
+/// still loading) so it is retried on the next iteration.
+pub(crate) fn handle_event(app: &mut App, event: AppEvent) -> Option<AppEvent> {
+    match event {
+        AppEvent::ModelPicked(model_id) => {
+            if app.providers.is_error() {
+                app.report_error(format!("Could not apply model {model_id}: providers failed to load"));
+                return None;
+            }
+            let Some(st) = app.providers.ready_mut() else {
+                app.providers.ensure(&app.jobs, "Loading providers", |_| load_providers_state());
+                return Some(AppEvent::ModelPicked(model_id));
+            };
+            st.apply_model_to_selected(&model_id);
+        }
+        AppEvent::ModelFilePicked(path) => {
+            if app.providers.is_error() {
+                app.report_error(format!("Could not apply {path}: providers failed to load"));
+                return None;
+            }
+            let Some(st) = app.providers.ready_mut() else {
+                app.providers.ensure(&app.jobs, "Loading providers", |_| load_providers_state());
+                return Some(AppEvent::ModelFilePicked(path));
+            };
+            match st.entries.get(st.selected) {
+                Some(entry) if is_local_type(&entry.ptype) => st.apply_to_selected(MODEL_PATH, &path),
+                Some(entry) => {
+                    let msg = format!("{} is a {} provider; select a local one to use {}", entry.name, entry.ptype, path);
+                    let _ = app.notify_tx.send(Notification::new(msg, false, Page::ModelBrowser));
+                }
+                None => {}
+            }
+        }
+        AppEvent::Navigate(page) => app.page = page,
+    }
+    None
+}
@@ -0,0 +1,356 @@
+//! Filesystem browser popup for path fields (a local provider's `model_path` and
+//! other `path` fields) and the Model Browser's `a`. Directories are listed
+//! first, then files, optionally only `.gguf` ones (`g`); dot files show with
+//! `.`. `/` types a path instead, for places the list does not reach or for
+//! `${VAR}` references, which are kept as typed.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::layout::Rect;
+use ratatui::prelude::Frame;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+
+use crate::app::App;
+use crate::interp::env_refs;
+use crate::modal::{Modal, ModalResult};
+use crate::text::{grapheme_byte, grapheme_len, single_line};
+use crate::util::centered_rect;
+
+/// Rows PageUp/PageDown move.
+const PAGE: usize = 10;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DirEntry {
+    pub name: String,
+    pub is_dir: bool,
+    /// File size in bytes; `None` for directories.
+    pub size: Option<u64>,
+}
+
+impl DirEntry {
+    fn label(&self) -> String {
+        match self.size {
+            Some(bytes) => format!("{}  {}", self.name, size_label(bytes)),
+            None => format!("{}/", self.name),
+        }
+    }
+}
+
+fn is_gguf(name: &str) -> bool {
+    Path::new(name).extension().is_some_and(|e| e.eq_ignore_ascii_case("gguf"))
+}
+
+/// Entries of `dir`, directories first, each group sorted by name ignoring case.
+/// Dot files are left out unless `hidden`; with `gguf_only`, so are files other
+/// than `.gguf`. Entries that cannot be read are skipped.
+pub fn list_dir(dir: &Path, hidden: bool, gguf_only: bool) -> Result<Vec<DirEntry>> {
+    let mut entries = Vec::new();
+    for e in fs::read_dir(dir)?.flatten() {
+        let name = e.file_name().to_string_lossy().into_owned();
+        if !hidden && name.starts_with('.') { continue; }
+        // Follows symlinks, so a linked models directory opens like a real one
+        let Ok(meta) = fs::metadata(e.path()) else { continue };
+        if meta.is_dir() {
+            entries.push(DirEntry { name, is_dir: true, size: None });
+        } else if !gguf_only || is_gguf(&name) {
+            entries.push(DirEntry { name, is_dir: false, size: Some(meta.len()) });
+        }
+    }
+    entries.sort_by_cached_key(|e| (!e.is_dir, e.name.to_lowercase()));
+    Ok(entries)
+}
+
+/// `path` with a leading `~/` (or a lone `~`) replaced by the home directory.
+pub fn expand_home(path: &str) -> PathBuf {
+    match (path, dirs::home_dir()) {
+        ("~", Some(home)) => home,
+        (p, Some(home)) if p.starts_with("~/") => home.join(&p[2..]),
+        (p, _) => PathBuf::from(p),
+    }
+}
+
+/// `3.3 GB`, `512 MB`, `4 KB` or `12 B`.
+fn size_label(bytes: u64) -> String {
+    match bytes {
+        b if b >= 1_000_000_000 => format!("{:.1} GB", b as f64 / 1e9),
+        b if b >= 1_000_000 => format!("{} MB", b / 1_000_000),
+        b if b >= 1_000 => format!("{} KB", b / 1_000),
+        b => format!("{} B", b),
+    }
+}
+
+type PickFn = Box<dyn FnOnce(&mut App, String)>;
+
+/// Browse directories and choose a file; `on_pick` receives its path. With
+/// `pick_dirs`, `s` chooses the directory being shown instead.
+pub struct FilePickerModal {
+    pub title: String,
+    pub dir: PathBuf,
+    /// Listing of `dir`; a `..` row comes first when `dir` has a parent.
+    pub entries: Vec<DirEntry>,
+    pub selected: usize,
+    hidden: bool,
+    gguf_only: bool,
+    pick_dirs: bool,
+    /// `Some` while a path is typed after `/` (or pasted).
+    typed: Option<String>,
+    error: Option<String>,
+    on_pick: Option<PickFn>,
+}
+
+impl FilePickerModal {
+    /// Picker opened at `current`: the directory of a file path (with the file
+    /// selected), a directory itself, else the home directory.
+    pub fn new(title: impl Into<String>, current: &str, on_pick: impl FnOnce(&mut App, String) + 'static) -> Self {
+        let path = expand_home(current.trim());
+        let start = if current.trim().is_empty() || !env_refs(current).is_empty() {
+            None
+        } else if path.is_dir() {
+            Some((path, None))
+        } else {
+            path.parent().filter(|p| p.is_dir()).map(|p| (p.to_path_buf(), path.file_name().map(|n| n.to_string_lossy().into_owned())))
+        };
+        let (dir, select) = start.unwrap_or_else(|| (dirs::home_dir().or_else(|| std::env::current_dir().ok()).unwrap_or_else(|| PathBuf::from("/")), None));
+        let mut picker = Self {
+            title: title.into(),
+            dir,
+            entries: Vec::new(),
+            selected: 0,
+            hidden: false,
+            gguf_only: false,
+            pick_dirs: false,
+            typed: None,
+            error: None,
+            on_pick: Some(Box::new(on_pick)),
+        };
+        picker.read(select.as_deref());
+        picker
+    }
+
+    /// List only `.gguf` files (and directories) until `g` is pressed.
+    pub fn gguf_only(mut self) -> Self {
+        self.gguf_only = true;
+        let name = self.selected_name();
+        self.read(name.as_deref());
+        self
+    }
+
+    /// Let `s` choose the directory being shown.
+    pub fn pick_dirs(mut self) -> Self {
+        self.pick_dirs = true;
+        self
+    }
+
+    fn has_parent(&self) -> bool {
+        self.dir.parent().is_some()
+    }
+
+    /// Name of the entry under the cursor; `..` for the parent row.
+    pub fn selected_name(&self) -> Option<String> {
+        if self.has_parent() && self.selected == 0 { return Some("..".to_string()); }
+        self.entries.get(self.selected - usize::from(self.has_parent())).map(|e| e.name.clone())
+    }
+
+    fn rows(&self) -> usize {
+        self.entries.len() + usize::from(self.has_parent())
+    }
+
+    /// Re-read `dir`, putting the cursor on `select` when it is listed.
+    fn read(&mut self, select: Option<&str>) {
+        self.error = None;
+        self.entries = list_dir(&self.dir, self.hidden, self.gguf_only).unwrap_or_else(|e| {
+            self.error = Some(format!("Cannot read {}: {}", self.dir.display(), e));
+            Vec::new()
+        });
+        let offset = usize::from(self.has_parent());
+        self.selected = select.and_then(|s| self.entries.iter().position(|e| e.name == s)).map_or(0, |i| i + offset);
+    }
+
+    fn open(&mut self, dir: PathBuf) {
+        self.dir = dir;
+        self.read(None);
+    }
+
+    fn up(&mut self) {
+        let Some(parent) = self.dir.parent().map(Path::to_path_buf) else { return };
+        let came_from = self.dir.file_name().map(|n| n.to_string_lossy().into_owned());
+        self.dir = parent;
+        self.read(came_from.as_deref());
+    }
+
+    fn pick(&mut self, app: &mut App, path: String) -> ModalResult {
+        if let Some(on_pick) = self.on_pick.take() { on_pick(app, path); }
+        ModalResult::Close
+    }
+
+    /// Enter on the selected row: go up, open a directory or choose a file.
+    fn activate(&mut self, app: &mut App) -> ModalResult {
+        let Some(name) = self.selected_name() else { return ModalResult::Keep };
+        if name == ".." {
+            self.up();
+            return ModalResult::Keep;
+        }
+        let path = self.dir.join(&name);
+        if path.is_dir() {
+            self.open(path);
+            ModalResult::Keep
+        } else {
+            self.pick(app, path.display().to_string())
+        }
+    }
+
+    /// Enter on a typed path: open a directory, choose a file, or keep a path
+    /// with `${VAR}` references as it is.
+    fn go_to(&mut self, app: &mut App, typed: String) -> ModalResult {
+        if !env_refs(&typed).is_empty() { return self.pick(app, typed); }
+        let path = expand_home(&typed);
+        let path = if path.is_relative() { self.dir.join(path) } else { path };
+        if path.is_dir() {
+            self.open(path);
+            ModalResult::Keep
+        } else if path.is_file() {
+            self.pick(app, path.display().to_string())
+        } else {
+            self.typed = Some(typed);
+            self.error = Some(format!("{} does not exist", path.display()));
+            ModalResult::Keep
+        }
+    }
+
+    fn flags(&self) -> String {
+        let mut flags = Vec::new();
+        if self.gguf_only { flags.push("GGUF only"); }
+        if self.hidden { flags.push("hidden shown"); }
+        if flags.is_empty() { String::new() } else { format!(" ({})", flags.join(", ")) }
+    }
+}
+
+impl Modal for FilePickerModal {
+    fn label(&self) -> String {
+        self.title.clone()
+    }
+
+    fn handle_key(&mut self, app: &mut App, key: KeyEvent) -> ModalResult {
+        if let Some(typed) = &mut self.typed {
+            match key.code {
+                KeyCode::Enter if !typed.trim().is_empty() => {
+                    let typed = self.typed.take().unwrap_or_default();
+                    return self.go_to(app, typed.trim().to_string());
+                }
+                // Backspace on an empty path goes back to the list
+                KeyCode::Backspace if typed.is_empty() => self.typed = None,
+                KeyCode::Backspace => typed.truncate(grapheme_byte(typed, grapheme_len(typed).saturating_sub(1))),
+                KeyCode::Char(c) => typed.push(c),
+                _ => {}
+            }
+            return ModalResult::Keep;
+        }
+        let last = self.rows().saturating_sub(1);
+        match key.code {
+            KeyCode::Up => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down => self.selected = (self.selected + 1).min(last),
+            KeyCode::PageUp => self.selected = self.selected.saturating_sub(PAGE),
+            KeyCode::PageDown => self.selected = (self.selected + PAGE).min(last),
+            KeyCode::Home => self.selected = 0,
+            KeyCode::End => self.selected = last,
+            KeyCode::Enter => return self.activate(app),
+            KeyCode::Right => {
+                let dir = self.selected_name().filter(|n| n != "..").map(|n| self.dir.join(n)).filter(|p| p.is_dir());
+                if let Some(dir) = dir { self.open(dir); }
+            }
+            KeyCode::Backspace | KeyCode::Left => self.up(),
+            KeyCode::Char('.') => {
+                self.hidden = !self.hidden;
+                let name = self.selected_name();
+                self.read(name.as_deref());
+            }
+            KeyCode::Char('g') | KeyCode::Char('G') => {
+                self.gguf_only = !self.gguf_only;
+                let name = self.selected_name();
+                self.read(name.as_deref());
+            }
+            KeyCode::Char('~') => {
+                if let Some(home) = dirs::home_dir() { self.open(home); }
+            }
+            KeyCode::Char('/') => {
+                let mut start = self.dir.display().to_string();
+                if !start.ends_with(std::path::MAIN_SEPARATOR) { start.push(std::path::MAIN_SEPARATOR); }
+                self.typed = Some(start);
+            }
+            KeyCode::Char('s') | KeyCode::Char('S') if self.pick_dirs => {
+                let dir = self.dir.display().to_string();
+                return self.pick(app, dir);
+            }
+            _ => {}
+        }
+        ModalResult::Keep
+    }
+
+    /// A pasted path lands in the `/` input, to be confirmed with Enter.
+    fn handle_paste(&mut self, _app: &mut App, text: &str) -> bool {
+        self.typed = Some(single_line(text).trim().to_string());
+        true
+    }
+
+    fn draw(&self, f: &mut Frame, area: Rect, app: &App) {
+        let area_pop = centered_rect(70, 70, area);
+        // Borders, the path line and two status rows
+        let visible = (area_pop.height as usize).saturating_sub(5).max(1);
+        let mut lines = vec![match &self.typed {
+            Some(typed) => Line::from(vec![Span::styled("Go to: ", app.theme.title()), Span::raw(format!("{}▌", typed))]),
+            None => Line::from(Span::styled(self.dir.display().to_string(), app.theme.title())),
+        }];
+        let parent = self.has_parent().then(|| "../".to_string());
+        let labels: Vec<String> = parent.into_iter().chain(self.entries.iter().map(DirEntry::label)).collect();
+        let start = (self.selected + 1).saturating_sub(visible);
+        lines.extend(labels.iter().enumerate().skip(start).take(visible).map(|(i, label)| {
+            Line::from(Span::styled(label.clone(), app.theme.row(self.typed.is_none() && i == self.selected)))
+        }));
+        if self.entries.is_empty() && self.error.is_none() {
+            lines.push(Line::from(Span::styled(if self.gguf_only { "(no .gguf files here; g shows all files)" } else { "(empty)" }, app.theme.hint())));
+        }
+        lines.resize(visible + 1, Line::from(""));
+        lines.push(match &self.error {
+            Some(e) => Line::from(Span::styled(e.clone(), app.theme.error())),
+            None => Line::from(""),
+        });
+        let mut hint = if self.typed.is_some() {
+            "Enter: open or choose • Backspace on empty: back to the list • Esc: cancel".to_string()
+        } else {
+            "Enter: open/choose • Backspace: up • / type a path • ~ home • . hidden • g GGUF only".to_string()
+        };
+        if self.pick_dirs && self.typed.is_none() { hint.push_str(" • s choose this directory"); }
+        lines.push(Line::from(Span::styled(hint, app.theme.hint())));
+        let p = Paragraph::new(lines)
+            .style(app.theme.base())
+            .block(Block::default().borders(Borders::ALL).border_style(app.theme.focused_border()).title(format!("{}{}", self.title, self.flags())));
+        f.render_widget(Clear, area_pop);
+        f.render_widget(p, area_pop);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn listing_puts_directories_first_and_filters() {
+        let dir = std::env::temp_dir().join(format!("chi-tui-picker-{}", std::process::id()));
+        fs::create_dir_all(dir.join("models")).unwrap();
+        fs::create_dir_all(dir.join(".cache")).unwrap();
+        for f in ["b.GGUF", "a.gguf", "notes.txt", ".secret.gguf"] { fs::write(dir.join(f), b"x").unwrap(); }
+        let names = |hidden, gguf| list_dir(&dir, hidden, gguf).unwrap().into_iter().map(|e| e.name).collect::<Vec<_>>();
+        assert_eq!(names(false, false), ["models", "a.gguf", "b.GGUF", "notes.txt"]);
+        assert_eq!(names(false, true), ["models", "a.gguf", "b.GGUF"]);
+        assert_eq!(names(true, true), [".cache", "models", ".secret.gguf", "a.gguf", "b.GGUF"]);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(size_label(3_300_000_000), "3.3 GB");
+        assert_eq!(size_label(512_000_000), "512 MB");
+        assert_eq!(DirEntry { name: "m".to_string(), is_dir: true, size: None }.label(), "m/");
+    }
+}
@@ -0,0 +1,94 @@
+use crossterm::event::{KeyCode, KeyEvent};
+
+/// Ordered panes of a page that can hold focus. Tab moves forward, Shift+Tab
+/// backward, wrapping at both ends; pages draw the focused pane with
+/// `Theme::focused_border`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FocusRing<F: Copy + PartialEq> {
+    items: Vec<F>,
+    current: usize,
+}
+
+impl<F: Copy + PartialEq> FocusRing<F> {
+    /// Ring over `items` with the first one focused; an empty ring focuses nothing.
+    pub fn new(items: &[F]) -> Self {
+        Self { items: items.to_vec(), current: 0 }
+    }
+
+    pub fn current(&self) -> Option<F> {
+        self.items.get(self.current).copied()
+    }
+
+    pub fn is(&self, item: F) -> bool {
+        self.current() == Some(item)
+    }
+
+    /// Focus `item` if it is part of the ring.
+    pub fn focus(&mut self, item: F) {
+        if let Some(i) = self.items.iter().position(|x| *x == item) {
+            self.current = i;
+        }
+    }
+
+    pub fn next(&mut self) {
+        if self.items.is_empty() { return; }
+        self.current = (self.current + 1) % self.items.len();
+    }
+
+    pub fn prev(&mut self) {
+        if self.items.is_empty() { return; }
+        self.current = (self.current + self.items.len() - 1) % self.items.len();
+    }
+
+    /// Replace the set of panes (e.g. when one is hidden), keeping focus on the
+    /// current pane if it is still present.
+    pub fn set_items(&mut self, items: &[F]) {
+        let cur = self.current();
+        self.items = items.to_vec();
+        self.current = self.items.iter().position(|x| Some(*x) == cur).unwrap_or(0);
+    }
+
+    /// Cycle on Tab/Shift+Tab. Returns true if focus moved.
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        if self.items.len() < 2 { return false; }
+        match key.code {
+            KeyCode::Tab => self.next(),
+            KeyCode::BackTab => self.prev(),
+            _ => return false,
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+
+    #[test]
+    fn cycles_and_keeps_focus_across_set_items() {
+        let mut ring = FocusRing::new(&['a', 'b', 'c']);
+        ring.prev();
+        assert_eq!(ring.current(), Some('c'));
+        ring.next();
+        ring.next();
+        assert!(ring.is('b'));
+        ring.set_items(&['b', 'c']);
+        assert!(ring.is('b'));
+        ring.set_items(&['c']);
+        assert!(ring.is('c'));
+        assert!(!ring.handle_key(KeyEvent::new(KeyCode::Tab, KeyModifiers::NONE)));
+    }
+
+    #[test]
+    fn an_empty_ring_focuses_nothing() {
+        let mut ring: FocusRing<char> = FocusRing::new(&[]);
+        ring.next();
+        ring.prev();
+        assert_eq!(ring.current(), None);
+        ring.set_items(&['a']);
+        assert!(ring.is('a'));
+        ring.set_items(&[]);
+        assert!(!ring.is('a'));
+    }
+}
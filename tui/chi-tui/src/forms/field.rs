@@ -0,0 +1,231 @@
+use serde_json::Value;
+
+use crate::interp::env_refs;
+use crate::text::{grapheme_byte, grapheme_len};
+
+/// Declarative description of one form field.
+#[derive(Clone, Debug)]
+pub struct FieldSchema {
+    pub name: String,
+    pub ftype: String, // "string" | "int" | "float" | "url" | "port" | "secret" | "path" (file browser) | "bool" (toggle) | "text" (multi-line) | "map" (`Name: value` lines) | ...
+    pub required: bool,
+    pub default: Option<String>,
+    pub help: Option<String>,
+    pub options: Option<Vec<String>>, // optional enum-like options for dropdowns
+}
+
+/// A field's schema plus its edit buffer. `cursor` counts grapheme clusters
+/// (what the user sees as one character, e.g. `é` or a flag emoji), not bytes;
+/// `saved` is the buffer as last loaded or saved, for per-field change tracking.
+#[derive(Clone, Debug)]
+pub struct FormField {
+    pub schema: FieldSchema,
+    pub buffer: String,
+    pub cursor: usize,
+    pub saved: String,
+    /// Secret kept in memory only; the page decides what is written instead.
+    pub session_only: bool,
+}
+
+impl FormField {
+    /// Field initialised from a config value, falling back to the schema default.
+    pub fn from_value(schema: &FieldSchema, value: Option<&Value>) -> Self {
+        let mut buffer = match value {
+            Some(Value::Object(map)) if schema.ftype == "map" => {
+                map.iter().map(|(k, v)| format!("{}: {}", k, v.as_str().map(String::from).unwrap_or_else(|| v.to_string()))).collect::<Vec<_>>().join("\n")
+            }
+            Some(Value::String(s)) => s.clone(),
+            Some(Value::Null) | None => String::new(),
+            Some(other) => other.to_string(),
+        };
+        if buffer.is_empty() { if let Some(d) = &schema.default { buffer = d.clone(); } }
+        Self { schema: schema.clone(), saved: buffer.clone(), buffer, cursor: 0, session_only: false }
+    }
+
+    /// True when the buffer differs from the saved value.
+    pub fn is_changed(&self) -> bool {
+        self.buffer != self.saved
+    }
+
+    /// True for `bool` fields, flipped with Enter/Space instead of typed.
+    pub fn is_toggle(&self) -> bool {
+        self.schema.ftype == "bool"
+    }
+
+    /// A `bool` field's state; empty or unrecognised buffers count as off.
+    pub fn is_on(&self) -> bool {
+        self.buffer.trim().eq_ignore_ascii_case("true")
+    }
+
+    /// True for `path` fields and fields named `*_path` (e.g. `model_path`),
+    /// chosen in the file browser.
+    pub fn is_path(&self) -> bool {
+        self.schema.ftype == "path" || self.schema.name.ends_with("_path")
+    }
+
+    /// True for `text` fields, edited in place over several lines.
+    pub fn is_multiline(&self) -> bool {
+        self.schema.ftype == "text"
+    }
+
+    /// `saved → current` for the help line; secrets, maps and multi-line text
+    /// are not spelled out.
+    pub fn change_preview(&self) -> String {
+        let show = |s: &str| if s.is_empty() { "(empty)".to_string() } else { s.to_string() };
+        match self.schema.ftype.as_str() {
+            "secret" => "secret changed".to_string(),
+            "map" => format!("{} → {} entries", self.saved.lines().count(), self.buffer.lines().count()),
+            "text" => format!("{} → {} lines", self.saved.lines().count(), self.buffer.lines().count()),
+            _ => format!("{} → {}", show(&self.saved), show(&self.buffer)),
+        }
+    }
+
+    /// Value for a config object; `int`, `port` and `float` fields become numbers
+    /// when they parse, `bool` fields always booleans (unset is off) and `map`
+    /// fields objects.
+    pub fn value(&self) -> Value {
+        if self.is_toggle() { return Value::Bool(self.is_on()); }
+        if self.schema.ftype == "map" {
+            return parse_map_lines(&self.buffer).map(Value::Object).unwrap_or_else(|_| Value::String(self.buffer.clone()));
+        }
+        let number = match self.schema.ftype.as_str() {
+            "int" | "port" => self.buffer.parse::<i64>().ok().map(Value::from),
+            "float" => self.buffer.parse::<f64>().ok().and_then(serde_json::Number::from_f64).map(Value::Number),
+            _ => None,
+        };
+        number.unwrap_or_else(|| Value::String(self.buffer.clone()))
+    }
+
+    /// Why the buffer is not a valid value of the field's type, e.g. `must be a
+    /// whole number`. An `int` field named `port` is checked as a port. Empty
+    /// values and `${VAR}` references (checked once resolved) pass.
+    pub fn type_error(&self) -> Option<String> {
+        let v = self.buffer.trim();
+        if v.is_empty() || !env_refs(v).is_empty() { return None; }
+        let ftype = if self.schema.ftype == "int" && self.schema.name == "port" { "port" } else { self.schema.ftype.as_str() };
+        let error = match ftype {
+            "int" => v.parse::<i64>().is_err().then_some("must be a whole number"),
+            "float" => (!v.parse::<f64>().is_ok_and(f64::is_finite)).then_some("must be a number"),
+            "bool" => v.to_ascii_lowercase().parse::<bool>().is_err().then_some("must be true or false"),
+            "port" => match v.parse::<i64>() {
+                Ok(n) if (1..=65535).contains(&n) => None,
+                Ok(_) => Some("must be 1..=65535"),
+                Err(_) => Some("must be a whole number"),
+            },
+            "url" => {
+                let rest = v.strip_prefix("http://").or_else(|| v.strip_prefix("https://")).unwrap_or("");
+                let host = rest.split(['/', '?', '#']).next().unwrap_or("");
+                (host.is_empty() || host.starts_with(':') || v.contains(char::is_whitespace))
+                    .then_some("must be an http:// or https:// URL")
+            }
+            _ => None,
+        };
+        error.map(str::to_string)
+    }
+
+    pub fn is_missing(&self) -> bool {
+        self.schema.required && self.buffer.trim().is_empty()
+    }
+
+    /// Length of the buffer in grapheme clusters.
+    pub fn len(&self) -> usize {
+        grapheme_len(&self.buffer)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buffer.is_empty()
+    }
+
+    /// Byte offset of grapheme `idx` (the buffer end when past it).
+    pub fn byte_idx(&self, idx: usize) -> usize {
+        grapheme_byte(&self.buffer, idx)
+    }
+
+    pub fn insert(&mut self, c: char) {
+        self.insert_str(c.encode_utf8(&mut [0; 4]));
+    }
+
+    /// Insert text at the cursor in one go (e.g. a bracketed paste). Line breaks
+    /// are kept only in `map` and `text` fields; other control characters are
+    /// dropped and tabs become spaces. Returns true if the buffer changed.
+    pub fn insert_str(&mut self, text: &str) -> bool {
+        let multiline = self.schema.ftype == "map" || self.is_multiline();
+        let text: String = text.replace("\r\n", "\n").chars()
+            .filter_map(|c| match c {
+                '\n' if multiline => Some('\n'),
+                '\t' => Some(' '),
+                c if c.is_control() => None,
+                c => Some(c),
+            })
+            .collect();
+        if text.is_empty() { return false; }
+        let idx = self.byte_idx(self.cursor);
+        self.buffer.insert_str(idx, &text);
+        // A combining mark joins the cluster before it, so count rather than add
+        self.cursor = grapheme_len(&self.buffer[..idx + text.len()]);
+        true
+    }
+
+    /// Delete the grapheme before the cursor. Returns true if the buffer changed.
+    pub fn backspace(&mut self) -> bool {
+        if self.cursor == 0 { return false; }
+        let (a, b) = (self.byte_idx(self.cursor - 1), self.byte_idx(self.cursor));
+        self.buffer.replace_range(a..b, "");
+        self.cursor -= 1;
+        true
+    }
+
+    /// Delete the grapheme under the cursor. Returns true if the buffer changed.
+    pub fn delete(&mut self) -> bool {
+        if self.cursor >= self.len() { return false; }
+        let (a, b) = (self.byte_idx(self.cursor), self.byte_idx(self.cursor + 1));
+        self.buffer.replace_range(a..b, "");
+        true
+    }
+
+    pub fn move_left(&mut self) { self.cursor = self.cursor.saturating_sub(1); }
+    pub fn move_right(&mut self) { self.cursor = (self.cursor + 1).min(self.len()); }
+    pub fn home(&mut self) { self.cursor = 0; }
+    pub fn end(&mut self) { self.cursor = self.len(); }
+
+    /// Cursor as line and grapheme column of a multi-line buffer.
+    pub fn line_col(&self) -> (usize, usize) {
+        let before = &self.buffer[..self.byte_idx(self.cursor)];
+        let line = before.matches('\n').count();
+        (line, grapheme_len(before.rsplit('\n').next().unwrap_or_default()))
+    }
+
+    /// Move the cursor to the line above or below, keeping its column where
+    /// that line is long enough. Returns false on the first or last line.
+    pub fn move_line(&mut self, down: bool) -> bool {
+        let (line, col) = self.line_col();
+        let lines: Vec<&str> = self.buffer.split('\n').collect();
+        let Some(target) = (if down { Some(line + 1) } else { line.checked_sub(1) }).filter(|t| *t < lines.len()) else { return false };
+        let start: usize = lines[..target].iter().map(|l| grapheme_len(l) + 1).sum();
+        self.cursor = start + col.min(grapheme_len(lines[target]));
+        true
+    }
+
+    /// Start (or end) of the cursor's line.
+    pub fn line_home(&mut self) { self.cursor -= self.line_col().1; }
+    pub fn line_end(&mut self) {
+        let (line, col) = self.line_col();
+        let len = self.buffer.split('\n').nth(line).map(grapheme_len).unwrap_or_default();
+        self.cursor += len - col;
+    }
+}
+
+/// Parse `Name: value` lines (blank lines skipped) into an object. Names must be
+/// non-empty and free of whitespace, as in HTTP headers.
+pub fn parse_map_lines(text: &str) -> Result<serde_json::Map<String, Value>, String> {
+    let mut map = serde_json::Map::new();
+    for (i, line) in text.lines().enumerate().filter(|(_, l)| !l.trim().is_empty()) {
+        let (name, value) = line.split_once(':').ok_or_else(|| format!("line {}: expected `Name: value`", i + 1))?;
+        let name = name.trim();
+        if name.is_empty() || name.contains(char::is_whitespace) {
+            return Err(format!("line {}: invalid name '{}'", i + 1, name));
+        }
+        map.insert(name.to_string(), Value::String(value.trim().to_string()));
+    }
+    Ok(map)
+}
@@ -0,0 +1,10 @@
+//! Schema-driven forms: declare `FieldSchema`s, wrap them in a `FormState`, route
+//! keys through `FormState::handle_key` and render with `draw_form`.
+
+mod field;
+mod state;
+mod view;
+
+pub use field::{parse_map_lines, FieldSchema, FormField};
+pub use state::{hash_fields, FormEvent, FormLocator, FormState};
+pub use view::draw_form;
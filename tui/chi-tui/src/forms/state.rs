@@ -0,0 +1,587 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde_json::Value;
+
+use crate::app::App;
+use crate::custom_models::MODEL_PATH;
+use crate::file_picker::FilePickerModal;
+use crate::modal::{DropdownModal, Modal, TextEditorModal};
+use crate::undo::UndoStack;
+
+use super::field::{parse_map_lines, FormField};
+
+/// Finds a form inside the app state, e.g. the open provider form.
+pub type FormLocator = fn(&mut App) -> Option<&mut FormState>;
+
+/// Result of routing a key through `FormState::handle_key`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FormEvent {
+    /// Not a form key; let the page or global keymap handle it.
+    Ignored,
+    /// Navigation or editing was applied.
+    Handled,
+    /// Enter on a lead row.
+    Lead(usize),
+    /// Enter on a field that is not being edited. Call `activate_field` for the
+    /// default behaviour (options dropdown or edit mode).
+    Field(usize),
+    /// Enter on a button (index into `buttons`).
+    Button(usize),
+    /// Esc outside of edit mode: the page should leave the form.
+    Exit,
+}
+
+/// Rows are laid out as `lead` (page-specific rows such as a type selector),
+/// then `fields`, then one row of `buttons`. `selected` indexes that sequence.
+#[derive(Clone, Debug)]
+pub struct FormState {
+    pub lead: Vec<String>,
+    pub fields: Vec<FormField>,
+    pub buttons: Vec<&'static str>,
+    pub selected: usize,
+    pub editing: bool,
+    pub message: Option<String>,
+    pub scroll: usize,
+    pub initial_hash: String,
+    /// Hash of the values last confirmed by the page (e.g. a successful connection test).
+    pub verified_hash: Option<String>,
+    /// Field values before each edit (Ctrl+Z / Ctrl+Y).
+    pub history: UndoStack<Vec<String>>,
+    /// Field being typed into; one undo step covers the whole run of keys.
+    typing: Option<usize>,
+}
+
+impl FormState {
+    pub fn new(lead: Vec<String>, fields: Vec<FormField>, buttons: Vec<&'static str>) -> Self {
+        let initial_hash = hash_fields(&fields);
+        Self {
+            lead, fields, buttons, selected: 0, editing: false, message: None, scroll: 0, initial_hash, verified_hash: None,
+            history: UndoStack::default(), typing: None,
+        }
+    }
+
+    fn first_button(&self) -> usize { self.lead.len() + self.fields.len() }
+
+    /// Index into `fields` of the selected row, if it is a field.
+    pub fn field_index(&self) -> Option<usize> {
+        let i = self.selected.checked_sub(self.lead.len())?;
+        (i < self.fields.len()).then_some(i)
+    }
+
+    /// Index into `buttons` of the selected row, if it is a button.
+    pub fn button_index(&self) -> Option<usize> {
+        let i = self.selected.checked_sub(self.first_button())?;
+        (i < self.buttons.len()).then_some(i)
+    }
+
+    pub fn is_button_selected(&self, button: usize) -> bool {
+        self.button_index() == Some(button)
+    }
+
+    /// Select row `row` (lead rows, fields, then buttons), leaving edit mode when
+    /// the row changes. Out-of-range rows are ignored.
+    pub fn select_row(&mut self, row: usize) {
+        if row >= self.first_button() + self.buttons.len() || row == self.selected { return; }
+        self.selected = row;
+        self.editing = false;
+    }
+
+    /// Select the first field (or the last lead row when there are no fields).
+    pub fn select_first_field(&mut self) {
+        self.selected = if self.fields.is_empty() { self.lead.len().saturating_sub(1) } else { self.lead.len() };
+    }
+
+    pub fn selected_field(&self) -> Option<&FormField> {
+        self.fields.get(self.field_index()?)
+    }
+
+    fn selected_field_mut(&mut self) -> Option<&mut FormField> {
+        let i = self.field_index()?;
+        self.fields.get_mut(i)
+    }
+
+    pub fn hash(&self) -> String {
+        hash_fields(&self.fields)
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.hash() != self.initial_hash
+    }
+
+    pub fn is_verified(&self) -> bool {
+        self.verified_hash.as_ref().is_some_and(|h| *h == self.hash())
+    }
+
+    /// Saving is allowed for untouched values or values the page has verified.
+    pub fn can_save(&self) -> bool {
+        !self.is_dirty() || self.is_verified()
+    }
+
+    /// Names of required fields that are empty.
+    pub fn missing_required(&self) -> Vec<String> {
+        self.fields.iter().filter(|f| f.is_missing()).map(|f| f.schema.name.clone()).collect()
+    }
+
+    /// `name: problem` for each field whose value does not fit its type.
+    pub fn type_errors(&self) -> Vec<String> {
+        self.fields.iter().filter_map(|f| f.type_error().map(|e| format!("{}: {}", f.schema.name, e))).collect()
+    }
+
+    /// Treat the current values as the new saved baseline.
+    pub fn mark_saved(&mut self) {
+        for ff in &mut self.fields { ff.saved = ff.buffer.clone(); }
+        self.initial_hash = self.hash();
+        self.verified_hash = Some(self.initial_hash.clone());
+    }
+
+    /// Field values as a config object.
+    pub fn to_config(&self) -> serde_json::Map<String, Value> {
+        self.fields.iter().map(|f| (f.schema.name.clone(), f.value())).collect()
+    }
+
+    /// Put field `fi` back to its saved value.
+    pub fn revert_field(&mut self, fi: usize) {
+        let Some(ff) = self.fields.get(fi).filter(|ff| ff.is_changed()) else { return };
+        let (name, saved) = (ff.schema.name.clone(), ff.saved.clone());
+        self.set_field(fi, saved);
+        self.message = Some(format!("{} reverted", name));
+    }
+
+    /// Set a field's buffer (e.g. from a dropdown) and leave edit mode.
+    pub fn set_field(&mut self, fi: usize, value: String) {
+        let before = self.values();
+        let Some(ff) = self.fields.get_mut(fi) else { return };
+        if ff.buffer != value { self.history.record(before); }
+        self.typing = None;
+        let ff = &mut self.fields[fi];
+        ff.buffer = value;
+        ff.cursor = ff.len();
+        self.editing = false;
+        self.message = Some(format!("{} set", ff.schema.name));
+        self.edited();
+    }
+
+    /// Dropdown modal that writes the chosen item into field `fi` of the form
+    /// found by `form_of` (the form lives in page state, so it is looked up again
+    /// when the choice is applied). Recent choices are remembered per field name.
+    pub fn field_dropdown(&self, fi: usize, title: String, items: Vec<String>, form_of: FormLocator) -> DropdownModal {
+        let current = self.fields.get(fi).map(|ff| ff.buffer.as_str()).unwrap_or("");
+        let key = self.fields.get(fi).map(|ff| ff.schema.name.clone()).unwrap_or_default();
+        DropdownModal::new(title, items, current, move |app, value| {
+            if let Some(form) = form_of(app) { form.set_field(fi, value); }
+        }).remember(key)
+    }
+
+    /// Flip `bool` field `fi` between `true` and `false`.
+    pub fn toggle_field(&mut self, fi: usize) {
+        let Some(ff) = self.fields.get(fi).filter(|ff| ff.is_toggle()) else { return };
+        let value = if ff.is_on() { "false" } else { "true" };
+        self.set_field(fi, value.to_string());
+    }
+
+    /// Default Enter behaviour on a field: a dropdown of its options (for the caller
+    /// to push onto `App::modals`), a flip for `bool` fields, else toggle edit mode.
+    pub fn activate_field(&mut self, fi: usize, form_of: FormLocator) -> Option<DropdownModal> {
+        let ff = self.fields.get(fi)?;
+        if ff.is_toggle() {
+            self.toggle_field(fi);
+            return None;
+        }
+        match ff.schema.options.clone() {
+            Some(opts) => Some(self.field_dropdown(fi, format!("Select {}", ff.schema.name), opts, form_of)),
+            None => { self.editing = !self.editing; None }
+        }
+    }
+
+    /// Modal for Enter on field `fi`: a text editor for `map` fields, the file
+    /// browser for path fields, otherwise `activate_field`'s dropdown (or edit mode).
+    pub fn field_modal(&mut self, fi: usize, form_of: FormLocator) -> Option<Box<dyn Modal>> {
+        let ff = self.fields.get(fi)?;
+        if ff.is_path() && ff.schema.options.is_none() {
+            let picker = FilePickerModal::new(format!("Choose {}", ff.schema.name), &ff.buffer, move |app, path| {
+                if let Some(form) = form_of(app) { form.set_field(fi, path); }
+            });
+            // Local providers load GGUF files; other paths may be anything
+            return Some(Box::new(if ff.schema.name == MODEL_PATH { picker.gguf_only() } else { picker }));
+        }
+        if ff.schema.ftype != "map" {
+            return self.activate_field(fi, form_of).map(|dd| Box::new(dd) as Box<dyn Modal>);
+        }
+        let title = format!("{} (one `Name: value` per line)", ff.schema.name);
+        Some(Box::new(TextEditorModal::new(title, &ff.buffer, move |app, text| {
+            let map = parse_map_lines(text)?;
+            let normalized: Vec<String> = map.iter().map(|(k, v)| format!("{}: {}", k, v.as_str().unwrap_or_default())).collect();
+            if let Some(form) = form_of(app) { form.set_field(fi, normalized.join("\n")); }
+            Ok(())
+        })))
+    }
+
+    /// True while the form wants every key (text entry).
+    pub fn captures_input(&self) -> bool {
+        self.editing
+    }
+
+    fn editing_multiline(&self) -> bool {
+        self.editing && self.selected_field().is_some_and(FormField::is_multiline)
+    }
+
+    /// Insert pasted text into the selected field in one edit. A plain text field
+    /// that is not being edited enters edit mode with the cursor at the end; fields
+    /// with options, toggles and `map` fields (edited in their own popup) ignore
+    /// pastes.
+    /// Returns true when the paste was taken.
+    pub fn paste(&mut self, text: &str) -> bool {
+        let editing = self.editing;
+        let before = self.values();
+        let Some(ff) = self.selected_field_mut() else { return false };
+        if !editing {
+            if ff.schema.options.is_some() || ff.schema.ftype == "map" || ff.is_toggle() { return false; }
+            ff.end();
+        }
+        if ff.insert_str(text) {
+            self.history.record(before);
+            self.typing = None;
+            self.edited();
+        }
+        self.editing = true;
+        true
+    }
+
+    fn edited(&mut self) {
+        self.verified_hash = None;
+    }
+
+    fn values(&self) -> Vec<String> {
+        self.fields.iter().map(|ff| ff.buffer.clone()).collect()
+    }
+
+    /// Edit of field `fi` by a typed key: `change` applies it and returns
+    /// whether anything changed. Keys typed into the same field in a row are
+    /// undone together.
+    fn type_into(&mut self, fi: usize, change: impl FnOnce(&mut FormField) -> bool) {
+        let before = self.values();
+        if !self.fields.get_mut(fi).is_some_and(change) { return; }
+        if self.typing != Some(fi) { self.history.record(before); }
+        self.typing = Some(fi);
+        self.edited();
+    }
+
+    fn restore(&mut self, values: Vec<String>) {
+        for (ff, value) in self.fields.iter_mut().zip(values) {
+            ff.buffer = value;
+            ff.cursor = ff.len();
+        }
+        self.typing = None;
+        self.editing = false;
+        self.edited();
+    }
+
+    /// Go back to the values before the last edit. Returns false when there is none.
+    pub fn undo(&mut self) -> bool {
+        let Some(prev) = self.history.undo(self.values()) else { return false };
+        self.restore(prev);
+        self.message = Some("Undone (Ctrl+Y redoes)".to_string());
+        true
+    }
+
+    /// Re-apply the last undone edit. Returns false when there is none.
+    pub fn redo(&mut self) -> bool {
+        let Some(next) = self.history.redo(self.values()) else { return false };
+        self.restore(next);
+        self.message = Some("Redone".to_string());
+        true
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> FormEvent {
+        let first_button = self.first_button();
+        if key.modifiers.contains(KeyModifiers::CONTROL) {
+            match key.code {
+                KeyCode::Char('z') => if !self.undo() { self.message = Some("Nothing to undo".to_string()); },
+                KeyCode::Char('y') => if !self.redo() { self.message = Some("Nothing to redo".to_string()); },
+                _ => return FormEvent::Ignored,
+            }
+            return FormEvent::Handled;
+        }
+        // Any other key than typing starts a new undo step
+        if !matches!(key.code, KeyCode::Char(_) | KeyCode::Backspace | KeyCode::Delete) { self.typing = None; }
+        match key.code {
+            KeyCode::Esc => {
+                if !self.editing { return FormEvent::Exit; }
+                self.editing = false;
+            }
+            // Multi-line text: Alt+Enter breaks the line, Up/Down/Home/End move within it
+            KeyCode::Enter if key.modifiers.contains(KeyModifiers::ALT) && self.editing_multiline() => {
+                if let Some(fi) = self.field_index() { self.type_into(fi, |ff| ff.insert_str("\n")); }
+            }
+            KeyCode::Up | KeyCode::Down | KeyCode::Home | KeyCode::End if self.editing_multiline() => {
+                if let Some(ff) = self.selected_field_mut() {
+                    match key.code {
+                        KeyCode::Up => { ff.move_line(false); }
+                        KeyCode::Down => { ff.move_line(true); }
+                        KeyCode::Home => ff.line_home(),
+                        _ => ff.line_end(),
+                    }
+                }
+            }
+            // Up/Down move between rows; the button row counts as one row.
+            KeyCode::Up => {
+                if self.selected >= first_button {
+                    self.selected = first_button.saturating_sub(1);
+                } else if self.selected > 0 {
+                    self.selected -= 1;
+                }
+            }
+            KeyCode::Down => {
+                if self.selected < first_button && !self.buttons.is_empty() { self.selected += 1; }
+            }
+            KeyCode::Enter => {
+                if let Some(b) = self.button_index() { return FormEvent::Button(b); }
+                if let Some(fi) = self.field_index() {
+                    if self.editing { self.editing = false; } else { return FormEvent::Field(fi); }
+                } else {
+                    return FormEvent::Lead(self.selected);
+                }
+            }
+            // Left/Right: switch buttons within the button row; move the cursor while editing.
+            KeyCode::Left => {
+                if self.selected > first_button {
+                    self.selected -= 1;
+                } else if self.editing {
+                    if let Some(ff) = self.selected_field_mut() { ff.move_left(); }
+                }
+            }
+            KeyCode::Right => {
+                if self.selected >= first_button && self.selected + 1 < first_button + self.buttons.len() {
+                    self.selected += 1;
+                } else if self.editing {
+                    if let Some(ff) = self.selected_field_mut() { ff.move_right(); }
+                }
+            }
+            KeyCode::Home if self.editing => { if let Some(ff) = self.selected_field_mut() { ff.home(); } }
+            KeyCode::End if self.editing => { if let Some(ff) = self.selected_field_mut() { ff.end(); } }
+            KeyCode::Backspace if self.editing => {
+                if let Some(fi) = self.field_index() { self.type_into(fi, FormField::backspace); }
+            }
+            KeyCode::Delete if self.editing => {
+                if let Some(fi) = self.field_index() { self.type_into(fi, FormField::delete); }
+            }
+            KeyCode::Char(c) if self.editing => {
+                if let Some(fi) = self.field_index() { self.type_into(fi, |ff| { ff.insert(c); true }); }
+            }
+            KeyCode::Char(' ') if !self.editing && self.selected_field().is_some_and(FormField::is_toggle) => {
+                if let Some(fi) = self.field_index() { self.toggle_field(fi); }
+            }
+            // Revert the selected changed field (its preview is on the help line)
+            KeyCode::Char('r') if self.selected_field().is_some_and(|ff| ff.is_changed()) => {
+                if let Some(fi) = self.field_index() { self.revert_field(fi); }
+            }
+            _ => return FormEvent::Ignored,
+        }
+        FormEvent::Handled
+    }
+}
+
+/// Stable hash of field names and buffers, used for dirty/verified tracking.
+pub fn hash_fields(fields: &[FormField]) -> String {
+    let mut s = String::new();
+    for f in fields.iter() {
+        s.push_str(&f.schema.name);
+        s.push('=');
+        s.push_str(&f.buffer);
+        s.push('\u{1F}');
+    }
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::forms::FieldSchema;
+    use crossterm::event::KeyModifiers;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn field(name: &str, ftype: &str, options: Option<Vec<String>>) -> FormField {
+        let schema = FieldSchema { name: name.to_string(), ftype: ftype.to_string(), required: true, default: None, help: None, options };
+        FormField::from_value(&schema, None)
+    }
+
+    #[test]
+    fn edit_navigate_and_save_gating() {
+        let mut form = FormState::new(vec!["lead".to_string()], vec![field("port", "int", None)], vec!["Test", "Save"]);
+        assert_eq!(form.handle_key(key(KeyCode::Enter)), FormEvent::Lead(0));
+        form.handle_key(key(KeyCode::Down));
+        assert_eq!(form.handle_key(key(KeyCode::Enter)), FormEvent::Field(0));
+        assert!(form.activate_field(0, |_| None).is_none());
+        for c in "80x".chars() { form.handle_key(key(KeyCode::Char(c))); }
+        form.handle_key(key(KeyCode::Backspace));
+        form.handle_key(key(KeyCode::Home));
+        form.handle_key(key(KeyCode::Char('1')));
+        assert_eq!(form.fields[0].buffer, "180");
+        assert!(form.missing_required().is_empty());
+        assert!(!form.can_save());
+        form.verified_hash = Some(form.hash());
+        assert!(form.can_save());
+        assert_eq!(form.to_config()["port"], serde_json::json!(180));
+
+        form.handle_key(key(KeyCode::Esc));
+        form.handle_key(key(KeyCode::Down));
+        form.handle_key(key(KeyCode::Right));
+        assert_eq!(form.handle_key(key(KeyCode::Enter)), FormEvent::Button(1));
+        assert_eq!(form.handle_key(key(KeyCode::Esc)), FormEvent::Exit);
+    }
+
+    #[test]
+    fn typed_fields_are_validated_and_stored_as_numbers() {
+        let fields = vec![field("port", "int", None), field("temperature", "float", None), field("base_url", "url", None), field("retries", "int", None)];
+        let mut form = FormState::new(Vec::new(), fields, vec!["Save"]);
+        for (ff, v) in form.fields.iter_mut().zip(["8o80", "warm", "localhost:8080", "${RETRIES}"]) { ff.buffer = v.to_string(); }
+        assert_eq!(form.type_errors(), [
+            "port: must be a whole number",
+            "temperature: must be a number",
+            "base_url: must be an http:// or https:// URL",
+        ]);
+        form.fields[0].buffer = "70000".to_string();
+        assert_eq!(form.fields[0].type_error().as_deref(), Some("must be 1..=65535"));
+        for (ff, v) in form.fields.iter_mut().zip(["8080", "0.7", "https://api.example.com/v1", "3"]) { ff.buffer = v.to_string(); }
+        assert!(form.type_errors().is_empty());
+        assert_eq!(Value::Object(form.to_config()), serde_json::json!({
+            "port": 8080, "temperature": 0.7, "base_url": "https://api.example.com/v1", "retries": 3,
+        }));
+    }
+
+    #[test]
+    fn options_field_opens_dropdown_instead_of_editing() {
+        let opts = Some(vec!["a".to_string(), "b".to_string()]);
+        let mut form = FormState::new(Vec::new(), vec![field("mode", "string", opts)], vec!["Save"]);
+        let dd = form.activate_field(0, |_| None).expect("options field opens a dropdown");
+        assert_eq!(dd.items, ["a", "b"]);
+        assert!(!form.captures_input());
+        form.set_field(0, "b".to_string());
+        assert_eq!(form.fields[0].buffer, "b");
+    }
+
+    #[test]
+    fn changed_field_previews_and_reverts_alone() {
+        let mut form = FormState::new(Vec::new(), vec![field("host", "string", None), field("port", "int", None)], vec!["Save"]);
+        form.set_field(0, "h1".to_string());
+        form.mark_saved();
+        assert!(!form.fields[0].is_changed());
+        form.set_field(0, "h12".to_string());
+        form.set_field(1, "9".to_string());
+        assert_eq!(form.fields[0].change_preview(), "h1 → h12");
+        assert_eq!(form.fields[1].change_preview(), "(empty) → 9");
+        form.handle_key(key(KeyCode::Char('r')));
+        assert_eq!(form.fields[0].buffer, "h1");
+        assert_eq!(form.fields[1].buffer, "9");
+        assert_eq!(form.message.as_deref(), Some("host reverted"));
+    }
+
+    #[test]
+    fn paste_and_cursor_work_on_graphemes() {
+        let mut form = FormState::new(Vec::new(), vec![field("api_key", "secret", None), field("mode", "string", Some(vec!["a".into()]))], vec!["Save"]);
+        assert!(form.paste("sk-\u{1F1F5}\u{1F1F1}x\r\n"));
+        assert!(form.captures_input());
+        assert_eq!(form.fields[0].buffer, "sk-\u{1F1F5}\u{1F1F1}x");
+        assert_eq!(form.fields[0].cursor, 5);
+        form.handle_key(key(KeyCode::Left));
+        form.handle_key(key(KeyCode::Backspace));
+        assert_eq!(form.fields[0].buffer, "sk-x");
+        form.handle_key(key(KeyCode::Char('e')));
+        form.handle_key(key(KeyCode::Char('\u{301}')));
+        assert_eq!(form.fields[0].cursor, 4);
+        form.handle_key(key(KeyCode::Backspace));
+        assert_eq!(form.fields[0].buffer, "sk-x");
+        form.handle_key(key(KeyCode::Esc));
+        form.handle_key(key(KeyCode::Down));
+        assert!(!form.paste("b"));
+        assert_eq!(form.fields[1].buffer, "");
+    }
+
+    #[test]
+    fn map_field_round_trips_name_value_lines() {
+        let schema = FieldSchema { name: "headers".to_string(), ftype: "map".to_string(), required: false, default: None, help: None, options: None };
+        let ff = FormField::from_value(&schema, Some(&serde_json::json!({"X-Api-Key": "k", "X-Tenant": "t1"})));
+        assert_eq!(ff.buffer, "X-Api-Key: k\nX-Tenant: t1");
+        assert_eq!(ff.value(), serde_json::json!({"X-Api-Key": "k", "X-Tenant": "t1"}));
+        assert!(parse_map_lines("no colon").is_err());
+        assert!(parse_map_lines("bad name: v").is_err());
+        assert!(parse_map_lines("\nA: b:c\n").unwrap()["A"] == "b:c");
+    }
+
+    #[test]
+    fn undo_restores_a_cleared_field_in_one_step() {
+        let mut form = FormState::new(Vec::new(), vec![field("base_url", "string", None), field("mode", "string", None)], vec!["Save"]);
+        form.set_field(0, "http://long".to_string());
+        form.editing = true;
+        for _ in 0..11 { form.handle_key(key(KeyCode::Backspace)); }
+        for c in "x".chars() { form.handle_key(key(KeyCode::Char(c))); }
+        assert_eq!(form.fields[0].buffer, "x");
+        form.handle_key(key(KeyCode::Esc));
+        form.set_field(1, "fast".to_string());
+
+        let ctrl = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL);
+        assert_eq!(form.handle_key(ctrl('z')), FormEvent::Handled);
+        assert_eq!((form.fields[0].buffer.as_str(), form.fields[1].buffer.as_str()), ("x", ""));
+        form.handle_key(ctrl('z'));
+        assert_eq!(form.fields[0].buffer, "http://long", "the whole typing run is one step");
+        form.handle_key(ctrl('z'));
+        assert_eq!(form.fields[0].buffer, "");
+        form.handle_key(ctrl('z'));
+        assert_eq!(form.message.as_deref(), Some("Nothing to undo"));
+        form.handle_key(ctrl('y'));
+        form.handle_key(ctrl('y'));
+        assert_eq!(form.fields[0].buffer, "x");
+    }
+
+    #[test]
+    fn text_field_edits_across_lines() {
+        let mut form = FormState::new(Vec::new(), vec![field("system_prompt", "text", None)], vec!["Save"]);
+        assert_eq!(form.handle_key(key(KeyCode::Enter)), FormEvent::Field(0));
+        assert!(form.activate_field(0, |_| None).is_none());
+        for c in "Be brief".chars() { form.handle_key(key(KeyCode::Char(c))); }
+        form.handle_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::ALT));
+        assert!(form.paste("Use\r\nGerman"));
+        assert_eq!(form.fields[0].buffer, "Be brief\nUse\nGerman");
+
+        // Up keeps the column where the line allows it and stays in the field
+        form.handle_key(key(KeyCode::Up));
+        assert_eq!(form.fields[0].line_col(), (1, 3));
+        form.handle_key(key(KeyCode::Up));
+        form.handle_key(key(KeyCode::Up));
+        assert_eq!(form.fields[0].line_col(), (0, 3));
+        form.handle_key(key(KeyCode::End));
+        form.handle_key(key(KeyCode::Char('!')));
+        form.handle_key(key(KeyCode::Down));
+        form.handle_key(key(KeyCode::Home));
+        form.handle_key(key(KeyCode::Delete));
+        assert_eq!(form.fields[0].buffer, "Be brief!\nse\nGerman");
+        assert_eq!(form.selected, 0);
+
+        form.handle_key(key(KeyCode::Enter));
+        assert!(!form.editing);
+        assert_eq!(form.to_config()["system_prompt"], serde_json::json!("Be brief!\nse\nGerman"));
+    }
+
+    #[test]
+    fn bool_field_toggles_and_saves_a_boolean() {
+        let schema = FieldSchema { name: "stream".to_string(), ftype: "bool".to_string(), required: false, default: None, help: None, options: None };
+        let mut form = FormState::new(Vec::new(), vec![FormField::from_value(&schema, Some(&serde_json::json!(true)))], vec!["Save"]);
+        assert!(form.fields[0].is_on());
+        assert_eq!(form.handle_key(key(KeyCode::Enter)), FormEvent::Field(0));
+        assert!(form.activate_field(0, |_| None).is_none());
+        assert!(!form.editing, "toggles are never typed into");
+        assert_eq!(form.to_config()["stream"], serde_json::json!(false));
+        form.handle_key(key(KeyCode::Char(' ')));
+        assert_eq!(form.to_config()["stream"], serde_json::json!(true));
+        assert!(!form.paste("no"));
+
+        form.fields[0].buffer = "maybe".to_string();
+        assert_eq!(form.fields[0].type_error().as_deref(), Some("must be true or false"));
+
+        // No value and no default: saved as false, never as ""
+        let unset = FormState::new(Vec::new(), vec![FormField::from_value(&schema, None)], vec!["Save"]);
+        assert_eq!(unset.to_config()["stream"], serde_json::json!(false));
+    }
+}
@@ -0,0 +1,164 @@
+use ratatui::layout::{Constraint, Direction, Layout, Margin, Rect};
+use ratatui::prelude::Frame;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph, Wrap};
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::app::App;
+use crate::interp::{env_refs, env_value, interpolate};
+use crate::keychain::keyring_account;
+use crate::mouse::HitTarget;
+use crate::text;
+
+use super::field::FormField;
+use super::state::FormState;
+
+/// Text rows of a multi-line (`text`) field's box.
+const TEXT_ROWS: usize = 4;
+
+/// Rows a field takes, borders included.
+fn field_height(ff: &FormField) -> usize {
+    if ff.is_multiline() { TEXT_ROWS + 2 } else { 3 }
+}
+
+/// `text` cut into rows of at most `cols` columns; line breaks start a new row.
+fn wrap_rows(text: &str, cols: usize) -> Vec<String> {
+    let cols = cols.max(1);
+    let mut rows = Vec::new();
+    for line in text.split('\n') {
+        let mut row = String::new();
+        for g in line.graphemes(true) {
+            if text::width(&row) + text::width(g) > cols { rows.push(std::mem::take(&mut row)); }
+            row.push_str(g);
+        }
+        rows.push(row);
+    }
+    rows
+}
+
+/// Render `form` into `area`: lead rows, a scrolling window of fields, the
+/// message/help line and the button row (titled `title`). Buttons listed in
+/// `disabled` are dimmed. Rows and buttons are registered as
+/// `HitTarget::FormRow` for mouse clicks.
+pub fn draw_form(f: &mut Frame, area: Rect, app: &App, form: &FormState, focused: bool, title: &str, disabled: &[usize]) {
+    let fields = &form.fields;
+    let lead_h = 3 * form.lead.len();
+    let reserve = lead_h + 1 + 3;
+    let room = (area.height as usize).saturating_sub(reserve);
+    let mut start = 0usize;
+    let mut end = fields.len();
+    if fields.iter().map(field_height).sum::<usize>() > room {
+        // Fields take 3 rows, multi-line ones more: keep the selected one in view
+        let sel = form.field_index().unwrap_or(if form.selected < form.lead.len() { 0 } else { fields.len().saturating_sub(1) });
+        start = form.scroll.min(sel);
+        while start < sel && fields[start..=sel].iter().map(field_height).sum::<usize>() > room { start += 1; }
+        end = start;
+        let mut used = 0;
+        while end < fields.len() && used + field_height(&fields[end]) <= room {
+            used += field_height(&fields[end]);
+            end += 1;
+        }
+    }
+    let visible = &fields[start..end];
+    let mut cons: Vec<Constraint> = Vec::new();
+    cons.extend(std::iter::repeat_n(Constraint::Length(3), form.lead.len()));
+    cons.extend(visible.iter().map(|ff| Constraint::Length(field_height(ff) as u16)));
+    cons.push(Constraint::Length(1));
+    cons.push(Constraint::Length(3));
+    let chunks = Layout::default().direction(Direction::Vertical).constraints(cons).split(area);
+    let mut hits = app.hits.borrow_mut();
+
+    for (i, label) in form.lead.iter().enumerate() {
+        let style = app.theme.row(focused && form.selected == i);
+        let p = Paragraph::new(label.as_str()).style(app.theme.base()).block(Block::default().borders(Borders::ALL).border_style(style));
+        f.render_widget(p, chunks[i]);
+        hits.add(chunks[i], HitTarget::FormRow(i));
+    }
+    for (i_vis, ff) in visible.iter().enumerate() {
+        let i = start + i_vis;
+        // `${VAR}` and keychain references are shown as they are, with whether each variable is set
+        let refs = env_refs(&ff.buffer);
+        let only_refs = !refs.is_empty() && interpolate(&ff.buffer, |_| Some(String::new())).is_ok_and(|rest| rest.trim().is_empty());
+        let in_keychain = keyring_account(&ff.buffer).is_some();
+        let secret = ff.schema.ftype == "secret" && !only_refs && !in_keychain;
+        let mut display = if secret && !ff.buffer.is_empty() { "••••••".to_string() } else { ff.buffer.clone() };
+        // Maps (e.g. headers) may carry credentials: show names only
+        if ff.schema.ftype == "map" {
+            let names: Vec<&str> = ff.buffer.lines().filter_map(|l| l.split_once(':')).map(|(k, _)| k.trim()).collect();
+            display = if names.is_empty() { "(none; Enter to edit)".to_string() } else { format!("{} (Enter to edit)", names.join(", ")) };
+        }
+        if ff.is_toggle() && ff.type_error().is_none() {
+            display = if ff.is_on() { "[x] On".to_string() } else { "[ ] Off".to_string() };
+        }
+        let is_selected = focused && form.field_index() == Some(i);
+        if is_selected && form.editing {
+            let pos = ff.cursor.min(ff.len());
+            if secret { display = "•".repeat(ff.len()); }
+            // One bullet per grapheme, so the secret cursor lands at pos bullets in
+            let idx = if secret { "•".len() * pos } else { ff.byte_idx(pos) };
+            display.insert(idx, '▌');
+        }
+        let mut bstyle = app.theme.border();
+        if ff.is_missing() || ff.type_error().is_some() || refs.iter().any(|n| env_value(n).is_none()) { bstyle = app.theme.error(); }
+        if is_selected { bstyle = app.theme.selected_row(); }
+        let mut title_txt = if ff.schema.required { format!("* {}", ff.schema.name) } else { ff.schema.name.clone() };
+        // Trailing `*`: changed since load/save
+        if ff.is_changed() { title_txt.push('*'); }
+        if ff.session_only { title_txt.push_str(" (session only)"); }
+        if in_keychain { title_txt.push_str(" (keychain)"); }
+        for name in &refs {
+            title_txt.push_str(&format!(" • ${} {}", name, if env_value(name).is_some() { "set" } else { "not set" }));
+        }
+        let mut block = Block::default().borders(Borders::ALL).border_style(bstyle).title(title_txt);
+        // Type problems (e.g. a port that is not a number) sit under the field as it is typed
+        if let Some(e) = ff.type_error() { block = block.title_bottom(Span::styled(format!(" {} ", e), app.theme.error())); }
+        let chunk = chunks[form.lead.len() + i_vis];
+        let p = if ff.is_multiline() {
+            // Wrapped by hand so the rows around the cursor can be shown
+            let rows = wrap_rows(&display, chunk.width.saturating_sub(2) as usize);
+            let cursor_row = rows.iter().position(|r| r.contains('▌')).unwrap_or(0);
+            let top = (cursor_row + 1).saturating_sub(TEXT_ROWS);
+            let hidden = rows.len().saturating_sub(top + TEXT_ROWS);
+            if hidden > 0 { block = block.title_bottom(Span::styled(format!(" +{} more ", hidden), app.theme.hint())); }
+            let lines: Vec<Line> = rows.into_iter().skip(top).take(TEXT_ROWS).map(Line::from).collect();
+            Paragraph::new(lines).style(app.theme.base()).block(block)
+        } else {
+            Paragraph::new(display).style(app.theme.base()).block(block).wrap(Wrap { trim: false })
+        };
+        f.render_widget(p, chunk);
+        hits.add(chunk, HitTarget::FormRow(form.lead.len() + i));
+    }
+
+    // Status message, or the selected field's change preview / help text when there is none
+    let help = form.selected_field().and_then(|ff| {
+        if ff.is_changed() && !form.editing { return Some(format!("saved → now: {} • r revert", ff.change_preview())); }
+        if ff.is_multiline() && form.editing { return Some("Alt+Enter new line • Up/Down move between lines • Enter done".to_string()); }
+        if ff.is_toggle() && ff.schema.help.is_none() { return Some("Enter/Space toggles".to_string()); }
+        if ff.is_path() && ff.schema.help.is_none() { return Some("Enter browses for a file • Ctrl+V pastes a path".to_string()); }
+        ff.schema.help.clone()
+    });
+    let mut msg = form.message.clone().or(help).unwrap_or_default();
+    if fields.len() > end { msg = format!("{}  ↓ more…", msg); }
+    if start > 0 { msg = format!("↑ more…  {}", msg); }
+    let row = form.lead.len() + visible.len();
+    let p = Paragraph::new(msg).style(app.theme.base().patch(app.theme.hint())).block(Block::default());
+    f.render_widget(p, chunks[row]);
+
+    let spans: Vec<Span> = form.buttons.iter().enumerate().map(|(i, b)| {
+        let style = if disabled.contains(&i) { app.theme.disabled() } else { app.theme.row(focused && form.is_button_selected(i)) };
+        Span::styled(format!("[ {} ]  ", b), style)
+    }).collect();
+    let p = Paragraph::new(Line::from(spans))
+        .style(app.theme.base())
+        .block(Block::default().borders(Borders::ALL).border_style(app.theme.border()).title(title.to_string()));
+    f.render_widget(p, chunks[row + 1]);
+    // Buttons sit inside the border, each `[ label ]` followed by two spaces
+    let inner = chunks[row + 1].inner(&Margin { horizontal: 1, vertical: 1 });
+    let mut x = inner.x;
+    for (i, b) in form.buttons.iter().enumerate() {
+        let w = text::width(&format!("[ {} ]", b)) as u16;
+        let rect = Rect { x, y: inner.y, width: w.min((inner.x + inner.width).saturating_sub(x)), height: inner.height.min(1) };
+        hits.add(rect, HitTarget::FormRow(form.lead.len() + fields.len() + i));
+        x = x.saturating_add(w + 2);
+    }
+}
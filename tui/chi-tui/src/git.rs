@@ -0,0 +1,77 @@
+//! Git status of config files written into the project (`.chi_llm.json`), via the
+//! `git` CLI in the working directory.
+
+use std::time::Duration;
+
+use anyhow::Result;
+
+use crate::util::run_program_text;
+
+const GIT_TIMEOUT: Duration = Duration::from_secs(5);
+
+fn git(args: &[&str]) -> Result<String> {
+    run_program_text("git", args, &[], GIT_TIMEOUT)
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FileStatus {
+    /// Not inside a git work tree (or git is not installed).
+    NotARepo,
+    Untracked,
+    Ignored,
+    Tracked { modified: bool },
+}
+
+impl FileStatus {
+    pub fn label(&self) -> &'static str {
+        match self {
+            FileStatus::NotARepo => "not a git repository",
+            FileStatus::Untracked => "untracked",
+            FileStatus::Ignored => "ignored by .gitignore",
+            FileStatus::Tracked { modified: false } => "tracked, unchanged",
+            FileStatus::Tracked { modified: true } => "tracked, modified",
+        }
+    }
+
+    /// Whether a "commit config change" makes sense.
+    pub fn can_commit(&self) -> bool {
+        matches!(self, FileStatus::Untracked | FileStatus::Tracked { modified: true })
+    }
+}
+
+pub fn file_status(path: &str) -> FileStatus {
+    if git(&["rev-parse", "--is-inside-work-tree"]).is_err() { return FileStatus::NotARepo; }
+    // Both commands exit non-zero for "no", which run_program_text reports as Err
+    if git(&["check-ignore", "-q", "--", path]).is_ok() { return FileStatus::Ignored; }
+    if git(&["ls-files", "--error-unmatch", "--", path]).is_err() { return FileStatus::Untracked; }
+    FileStatus::Tracked { modified: git(&["diff", "--quiet", "HEAD", "--", path]).is_err() }
+}
+
+/// Diff of `path` against HEAD; an untracked file is shown as all additions.
+pub fn file_diff(path: &str, status: &FileStatus) -> String {
+    match status {
+        FileStatus::Tracked { .. } => git(&["diff", "HEAD", "--", path]).unwrap_or_default(),
+        FileStatus::Untracked => std::fs::read_to_string(path)
+            .map(|t| t.lines().map(|l| format!("+{}", l)).collect::<Vec<_>>().join("\n"))
+            .unwrap_or_default(),
+        _ => String::new(),
+    }
+}
+
+/// Append `entry` to `.gitignore` in the working directory (created if missing).
+pub fn add_to_gitignore(entry: &str) -> Result<()> {
+    let mut text = std::fs::read_to_string(".gitignore").unwrap_or_default();
+    if text.lines().any(|l| l.trim() == entry) { return Ok(()); }
+    if !text.is_empty() && !text.ends_with('\n') { text.push('\n'); }
+    text.push_str(entry);
+    text.push('\n');
+    std::fs::write(".gitignore", text)?;
+    Ok(())
+}
+
+/// Commit only `path` with `message`; returns the new short commit hash.
+pub fn commit_file(path: &str, message: &str) -> Result<String> {
+    git(&["add", "--", path])?;
+    git(&["commit", "-m", message, "--", path])?;
+    git(&["rev-parse", "--short", "HEAD"])
+}
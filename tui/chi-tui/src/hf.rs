@@ -0,0 +1,251 @@
+//! Hugging Face hub search for GGUF models (Model Browser `h`): repos matching
+//! a term, the quantization variants in one, and a download into chi-llm's
+//! model directory, after which the file is listed as a custom model. Requests
+//! go through `curl`, like the release-notes fetch; download progress streams
+//! to the log panel.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Sender;
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+
+use crate::app::{App, Notification, Page};
+use crate::custom_models::{add_custom_models, load_custom_models, CustomModel};
+use crate::env::timeouts;
+use crate::log::{stream_output, LogLine};
+use crate::modal::{ConfirmModal, DropdownModal, PromptModal};
+use crate::util::{invalidate_cli_cache, percent_encode, run_program_text_cancellable};
+
+pub const HF_URL: &str = "https://huggingface.co";
+/// Repos listed per search.
+const SEARCH_LIMIT: usize = 30;
+
+/// A model repo from a hub search.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HfRepo {
+    pub id: String,
+    pub downloads: u64,
+    pub likes: u64,
+}
+
+impl HfRepo {
+    pub fn label(&self) -> String {
+        format!("{}  (↓ {} • ♥ {})", self.id, self.downloads, self.likes)
+    }
+}
+
+/// A `.gguf` file in a repo.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HfFile {
+    pub filename: String,
+    pub size: Option<u64>,
+    pub quant: Option<String>,
+}
+
+impl HfFile {
+    pub fn label(&self) -> String {
+        let size = self.size.map(|s| format!("  {:.1} GB", s as f64 / 1e9)).unwrap_or_default();
+        format!("{:<8} {}{}", self.quant.as_deref().unwrap_or("?"), self.filename, size)
+    }
+}
+
+/// Result of one step of the search-pick-download flow.
+#[derive(Debug)]
+pub enum HfStep {
+    Repos(String, Result<Vec<HfRepo>, String>),
+    Files(String, Result<Vec<HfFile>, String>),
+    Downloaded(String, Result<CustomModel, String>),
+}
+
+pub fn search_url(term: &str) -> String {
+    format!("{}/api/models?search={}&filter=gguf&sort=downloads&direction=-1&limit={}", HF_URL, percent_encode(term.trim()), SEARCH_LIMIT)
+}
+
+/// Repo details with file sizes.
+pub fn repo_url(repo: &str) -> String {
+    format!("{}/api/models/{}?blobs=true", HF_URL, repo)
+}
+
+pub fn download_url(repo: &str, filename: &str) -> String {
+    format!("{}/{}/resolve/main/{}", HF_URL, repo, filename)
+}
+
+/// Where chi-llm keeps downloaded model files.
+pub fn model_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|h| h.join(".cache").join("chi_llm"))
+}
+
+pub fn parse_repos(v: &Value) -> Vec<HfRepo> {
+    v.as_array().map(Vec::as_slice).unwrap_or_default().iter().filter_map(|r| {
+        Some(HfRepo {
+            id: r.get("id").or_else(|| r.get("modelId"))?.as_str()?.to_string(),
+            downloads: r.get("downloads").and_then(Value::as_u64).unwrap_or(0),
+            likes: r.get("likes").and_then(Value::as_u64).unwrap_or(0),
+        })
+    }).collect()
+}
+
+/// The `.gguf` files of a repo, smallest first.
+pub fn parse_files(v: &Value) -> Vec<HfFile> {
+    let siblings = v.get("siblings").and_then(Value::as_array).map(Vec::as_slice).unwrap_or_default();
+    let mut files: Vec<HfFile> = siblings.iter().filter_map(|s| {
+        let filename = s.get("rfilename")?.as_str()?;
+        if !filename.to_lowercase().ends_with(".gguf") { return None; }
+        Some(HfFile { filename: filename.to_string(), size: s.get("size").and_then(Value::as_u64), quant: quant_of(filename) })
+    }).collect();
+    files.sort_by_key(|f| f.size.unwrap_or(u64::MAX));
+    files
+}
+
+/// Quantization named in a GGUF file name, e.g. `Q4_K_M`, `IQ3_XS` or `F16`.
+pub fn quant_of(filename: &str) -> Option<String> {
+    let name = filename.rsplit('/').next().unwrap_or(filename);
+    let stem = name.get(..name.len().saturating_sub(5)).unwrap_or(name);
+    stem.split(['-', '.']).rev().find(|part| {
+        let p = part.to_ascii_uppercase();
+        let digit_after = |prefix: &str| p.strip_prefix(prefix).is_some_and(|r| r.starts_with(|c: char| c.is_ascii_digit()));
+        digit_after("Q") || digit_after("IQ") || matches!(p.as_str(), "F16" | "BF16" | "F32")
+    }).map(str::to_ascii_uppercase)
+}
+
+fn curl_json(url: &str, cancel: &AtomicBool) -> Result<Value> {
+    let text = run_program_text_cancellable("curl", &["-fsSL", url], &[], timeouts().cli, cancel)?;
+    Ok(serde_json::from_str(&text)?)
+}
+
+/// Download `filename` of `repo` into `dir` (via a `.part` file, so an
+/// interrupted download never looks complete) and register it. Cancelling
+/// kills `curl`.
+fn download(repo: &str, filename: &str, dir: &Path, log: &Sender<LogLine>, cancel: &AtomicBool) -> Result<CustomModel> {
+    let name = filename.rsplit('/').next().unwrap_or(filename);
+    let dest = dir.join(name);
+    if !dest.exists() {
+        fs::create_dir_all(dir)?;
+        let part = dir.join(format!("{}.part", name));
+        let source = format!("download {}", name);
+        let mut child = Command::new("curl")
+            .args(["-fL", "--progress-bar", "-o"]).arg(&part).arg(download_url(repo, filename))
+            .stdin(Stdio::null()).stdout(Stdio::piped()).stderr(Stdio::piped())
+            .spawn().map_err(|e| anyhow!("cannot run curl: {}", e))?;
+        let (out, err) = (child.stdout.take(), child.stderr.take());
+        let streaming = { let log = log.clone(); thread::spawn(move || stream_output(out, err, &source, &log)) };
+        let status = loop {
+            if cancel.load(Ordering::Relaxed) { let _ = child.kill(); }
+            match child.try_wait() {
+                Ok(None) => thread::sleep(Duration::from_millis(100)),
+                done => break done.ok().flatten(),
+            }
+        };
+        let _ = streaming.join();
+        if !status.is_some_and(|s| s.success()) {
+            let _ = fs::remove_file(&part);
+            return Err(anyhow!("download of {} failed or was cancelled (see log)", name));
+        }
+        fs::rename(&part, &dest)?;
+        // chi-llm may now report a catalog model as downloaded
+        invalidate_cli_cache(&["models", "list"]);
+    }
+    let added = add_custom_models(&dest)?;
+    let path = fs::canonicalize(&dest).unwrap_or(dest).display().to_string();
+    added.into_iter().next()
+        .or_else(|| load_custom_models().into_iter().find(|m| m.path == path))
+        // Already in chi-llm's catalog (CHI_LLM_MODELS_YAML), under the same id
+        .or_else(|| Path::new(&path).is_file().then(|| CustomModel::from_file(Path::new(&path))))
+        .ok_or_else(|| anyhow!("{} was downloaded but could not be registered", path))
+}
+
+/// `h`: ask for a search term, then search in the background.
+pub fn open_hf_search(app: &mut App) {
+    if app.hf_job.is_some() {
+        let _ = app.notify_tx.send(Notification::new("A Hugging Face search or download is still running", false, Page::ModelBrowser));
+        return;
+    }
+    app.modals.push(Box::new(PromptModal::new("Search Hugging Face for GGUF models", |app, term| {
+        app.hf_job = Some(app.jobs.spawn(format!("Searching Hugging Face for {}", term), move |ctx| {
+            let repos = curl_json(&search_url(&term), ctx.cancel_flag()).map(|v| parse_repos(&v)).map_err(|e| e.to_string());
+            HfStep::Repos(term, repos)
+        }));
+    })));
+}
+
+/// Collect a finished step and offer the next one: repos → files → download.
+pub fn poll_hf(app: &mut App) {
+    let Some(done) = app.hf_job.as_ref().and_then(|job| job.poll()) else { return };
+    app.hf_job = None;
+    let notify = |app: &App, msg: String, ok: bool| { let _ = app.notify_tx.send(Notification::new(msg, ok, Page::ModelBrowser)); };
+    match done {
+        Err(e) => notify(app, format!("Hugging Face: {}", e), false),
+        Ok(HfStep::Repos(term, Err(e))) => notify(app, format!("Hugging Face search for {} failed: {}", term, e), false),
+        Ok(HfStep::Repos(term, Ok(repos))) if repos.is_empty() => notify(app, format!("No GGUF repos on Hugging Face match {}", term), false),
+        Ok(HfStep::Repos(term, Ok(repos))) => {
+            let labels: Vec<String> = repos.iter().map(HfRepo::label).collect();
+            let modal = DropdownModal::new(format!("Hugging Face: {}", term), labels.clone(), "", move |app, label| {
+                let Some(repo) = labels.iter().position(|l| *l == label).map(|i| repos[i].id.clone()) else { return };
+                app.hf_job = Some(app.jobs.spawn(format!("Listing {}", repo), move |ctx| {
+                    let files = curl_json(&repo_url(&repo), ctx.cancel_flag()).map(|v| parse_files(&v)).map_err(|e| e.to_string());
+                    HfStep::Files(repo, files)
+                }));
+            });
+            app.modals.push(Box::new(modal));
+        }
+        Ok(HfStep::Files(repo, Err(e))) => notify(app, format!("Could not list {}: {}", repo, e), false),
+        Ok(HfStep::Files(repo, Ok(files))) if files.is_empty() => notify(app, format!("{} has no .gguf files", repo), false),
+        Ok(HfStep::Files(repo, Ok(files))) => {
+            let labels: Vec<String> = files.iter().map(HfFile::label).collect();
+            let modal = DropdownModal::new(format!("{}: quantization", repo), labels.clone(), "", move |app, label| {
+                let Some(file) = labels.iter().position(|l| *l == label).map(|i| files[i].clone()) else { return };
+                let Some(dir) = model_dir() else { return notify(app, "No home directory to download into".to_string(), false) };
+                let msg = format!("Download {} into {}? Progress shows in the log panel.", file.label().trim(), dir.display());
+                app.modals.push(Box::new(ConfirmModal::new("Download model", msg, move |app| {
+                    let log = app.log_tx.clone();
+                    app.show_log = true;
+                    app.hf_job = Some(app.jobs.spawn(format!("Downloading {}", file.filename), move |ctx| {
+                        let done = download(&repo, &file.filename, &dir, &log, ctx.cancel_flag()).map_err(|e| e.to_string());
+                        HfStep::Downloaded(file.filename, done)
+                    }));
+                })));
+            });
+            app.modals.push(Box::new(modal));
+        }
+        Ok(HfStep::Downloaded(file, Err(e))) => notify(app, format!("{}: {}", file, e), false),
+        Ok(HfStep::Downloaded(_, Ok(model))) => {
+            if let Some(m) = app.model.ready_mut() {
+                if !m.entries.iter().any(|e| e.id == model.id) { m.add_items(&[model.to_list_item()]); }
+            }
+            notify(app, format!("Downloaded {}; it is listed as {}", model.name, model.id), true);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn hub_replies_become_repos_and_quantized_files() {
+        assert_eq!(search_url("qwen 2.5"), "https://huggingface.co/api/models?search=qwen%202.5&filter=gguf&sort=downloads&direction=-1&limit=30");
+        let repos = parse_repos(&json!([{"id": "bartowski/Qwen2.5-7B-Instruct-GGUF", "downloads": 1200, "likes": 7}, {"nope": 1}]));
+        assert_eq!(repos.len(), 1);
+        assert_eq!(repos[0].label(), "bartowski/Qwen2.5-7B-Instruct-GGUF  (↓ 1200 • ♥ 7)");
+
+        let files = parse_files(&json!({"siblings": [
+            {"rfilename": "README.md", "size": 10},
+            {"rfilename": "Qwen2.5-7B-Instruct-Q8_0.gguf", "size": 8_100_000_000u64},
+            {"rfilename": "Qwen2.5-7B-Instruct-IQ3_XS.gguf", "size": 3_300_000_000u64},
+        ]}));
+        let quants: Vec<_> = files.iter().map(|f| f.quant.as_deref()).collect();
+        assert_eq!(quants, [Some("IQ3_XS"), Some("Q8_0")]);
+        assert_eq!(files[0].label(), "IQ3_XS   Qwen2.5-7B-Instruct-IQ3_XS.gguf  3.3 GB");
+        assert_eq!(quant_of("sub/model.f16.gguf").as_deref(), Some("F16"));
+        assert_eq!(quant_of("llama-3-8b-q4_k_m.gguf").as_deref(), Some("Q4_K_M"));
+        assert_eq!(quant_of("qwen3-4b.gguf"), None);
+        assert_eq!(download_url("a/b", "x.gguf"), "https://huggingface.co/a/b/resolve/main/x.gguf");
+    }
+}
@@ -0,0 +1,176 @@
+//! Minimal syntax highlighting for fenced code blocks in rendered markdown and
+//! config previews. Covers what chi-llm docs and configs mostly contain (Python,
+//! JSON, YAML, shell) with a per-line tokenizer: keywords, strings, numbers,
+//! comments and JSON/YAML keys / shell variables. Multi-line strings are not
+//! tracked; other languages stay plain.
+
+use ratatui::text::{Line, Span};
+
+use crate::theme::Theme;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Lang {
+    Python,
+    Json,
+    Yaml,
+    Shell,
+    Plain,
+}
+
+impl Lang {
+    /// Language for a fence info string such as `py` or `bash`.
+    pub fn from_fence(info: &str) -> Self {
+        match info.split_whitespace().next().unwrap_or("").to_ascii_lowercase().as_str() {
+            "python" | "py" | "python3" => Lang::Python,
+            "json" | "jsonc" => Lang::Json,
+            "yaml" | "yml" => Lang::Yaml,
+            "bash" | "sh" | "shell" | "zsh" | "console" => Lang::Shell,
+            _ => Lang::Plain,
+        }
+    }
+
+    fn keywords(self) -> &'static [&'static str] {
+        match self {
+            Lang::Python => &[
+                "and", "as", "assert", "async", "await", "break", "class", "continue", "def", "del", "elif", "else", "except",
+                "False", "finally", "for", "from", "if", "import", "in", "is", "lambda", "None", "not", "or", "pass", "raise",
+                "return", "True", "try", "while", "with", "yield",
+            ],
+            Lang::Json | Lang::Yaml => &["true", "false", "null"],
+            Lang::Shell => &[
+                "case", "cd", "do", "done", "echo", "elif", "else", "esac", "export", "fi", "for", "function", "if", "in",
+                "then", "while",
+            ],
+            Lang::Plain => &[],
+        }
+    }
+
+    fn comment_char(self) -> Option<char> {
+        matches!(self, Lang::Python | Lang::Yaml | Lang::Shell).then_some('#')
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Token {
+    Plain,
+    Keyword,
+    String,
+    Number,
+    Comment,
+    /// JSON/YAML keys and shell variables.
+    Name,
+}
+
+/// Split one line of code into `(token, text)` runs.
+pub fn tokenize(lang: Lang, line: &str) -> Vec<(Token, String)> {
+    let chars: Vec<char> = line.chars().collect();
+    let mut out: Vec<(Token, String)> = Vec::new();
+    let mut push = |tok: Token, s: String| match out.last_mut() {
+        Some((t, prev)) if *t == tok => prev.push_str(&s),
+        _ => out.push((tok, s)),
+    };
+    if lang == Lang::Plain {
+        push(Token::Plain, line.to_string());
+        return out;
+    }
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let prev_is_word = i > 0 && (chars[i - 1].is_alphanumeric() || chars[i - 1] == '_');
+        let start = i;
+        if Some(c) == lang.comment_char() && (lang == Lang::Python || i == 0 || chars[i - 1].is_whitespace()) {
+            push(Token::Comment, chars[i..].iter().collect());
+            break;
+        } else if c == '"' || c == '\'' {
+            i += 1;
+            while i < chars.len() && chars[i] != c {
+                if chars[i] == '\\' { i += 1; }
+                i += 1;
+            }
+            i = (i + 1).min(chars.len());
+            let rest: String = chars[i..].iter().collect();
+            let tok = if matches!(lang, Lang::Json | Lang::Yaml) && rest.trim_start().starts_with(':') { Token::Name } else { Token::String };
+            push(tok, chars[start..i].iter().collect());
+            continue;
+        } else if c == '$' && lang == Lang::Shell && i + 1 < chars.len() {
+            i += 1;
+            if chars[i] == '{' {
+                while i < chars.len() && chars[i] != '}' { i += 1; }
+                i = (i + 1).min(chars.len());
+            } else {
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') { i += 1; }
+            }
+            push(Token::Name, chars[start..i].iter().collect());
+            continue;
+        } else if c.is_ascii_digit() && !prev_is_word {
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '.' || chars[i] == '_') { i += 1; }
+            push(Token::Number, chars[start..i].iter().collect());
+            continue;
+        } else if (c.is_alphabetic() || c == '_') && !prev_is_word {
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') { i += 1; }
+            let word: String = chars[start..i].iter().collect();
+            let is_key = lang == Lang::Yaml && chars.get(i) == Some(&':');
+            let tok = if is_key { Token::Name } else if lang.keywords().contains(&word.as_str()) { Token::Keyword } else { Token::Plain };
+            push(tok, word);
+            continue;
+        }
+        push(Token::Plain, c.to_string());
+        i += 1;
+    }
+    out
+}
+
+/// Styled line for one line of code in `lang`.
+pub fn highlight_line(lang: Lang, line: &str, theme: &Theme) -> Line<'static> {
+    Line::from(tokenize(lang, line).into_iter().map(|(tok, s)| Span::styled(s, theme.syntax(tok))).collect::<Vec<_>>())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn runs(expected: &[(Token, &str)]) -> Vec<(Token, String)> {
+        expected.iter().map(|(t, s)| (*t, s.to_string())).collect()
+    }
+
+    #[test]
+    fn python_json_and_shell_tokens() {
+        assert_eq!(
+            tokenize(Lang::Python, "def f(x=1): return 'a#b'  # hi"),
+            runs(&[
+                (Token::Keyword, "def"), (Token::Plain, " f(x="), (Token::Number, "1"), (Token::Plain, "): "),
+                (Token::Keyword, "return"), (Token::Plain, " "), (Token::String, "'a#b'"), (Token::Plain, "  "),
+                (Token::Comment, "# hi"),
+            ])
+        );
+        assert_eq!(
+            tokenize(Lang::Json, r#"{"port": 8080, "ok": true}"#),
+            runs(&[
+                (Token::Plain, "{"), (Token::Name, "\"port\""), (Token::Plain, ": "), (Token::Number, "8080"),
+                (Token::Plain, ", "), (Token::Name, "\"ok\""), (Token::Plain, ": "), (Token::Keyword, "true"), (Token::Plain, "}"),
+            ])
+        );
+        assert_eq!(
+            tokenize(Lang::Shell, "export KEY=$HOME/x#y # c"),
+            runs(&[
+                (Token::Keyword, "export"), (Token::Plain, " KEY="), (Token::Name, "$HOME"), (Token::Plain, "/x#y "),
+                (Token::Comment, "# c"),
+            ])
+        );
+        assert_eq!(
+            tokenize(Lang::Yaml, "  port: 8080 # local"),
+            runs(&[
+                (Token::Plain, "  "), (Token::Name, "port"), (Token::Plain, ": "), (Token::Number, "8080"), (Token::Plain, " "),
+                (Token::Comment, "# local"),
+            ])
+        );
+        assert_eq!(Lang::from_fence("py title=x"), Lang::Python);
+    }
+
+    #[test]
+    fn fenced_lines_are_tagged_and_kept_out_of_the_toc() {
+        let rm = crate::readme::parse_markdown("# Setup\n```bash\n# not a heading\n```\ntext");
+        assert_eq!(rm.toc.len(), 1);
+        assert_eq!(rm.code, [None, None, Some(Lang::Shell), None, None]);
+    }
+}
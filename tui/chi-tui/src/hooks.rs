@@ -0,0 +1,90 @@
+//! Shell hooks from `settings.toml`, run after a config write or a successful
+//! provider test:
+//!
+//! ```toml
+//! [hooks]
+//! after_write = ["direnv allow"]
+//! after_test = ["notify-send chi-tui \"$CHI_TUI_PROVIDER_ID passed\""]
+//! ```
+//!
+//! Each hook runs through the platform shell as a background job with
+//! `CHI_TUI_EVENT` (`write` | `test`) plus `CHI_TUI_FILE` or `CHI_TUI_PROVIDER_ID`
+//! set. Output is streamed to the log panel and a toast reports the exit status.
+
+use std::process::{Command, Stdio};
+use std::sync::mpsc::Sender;
+
+use serde::Deserialize;
+
+use crate::app::{Notification, Page};
+use crate::jobs::Jobs;
+use crate::log::{stream_child_output, LogLine};
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct HookSettings {
+    #[serde(default)]
+    pub after_write: Vec<String>,
+    #[serde(default)]
+    pub after_test: Vec<String>,
+}
+
+/// What triggered a hook run.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HookEvent {
+    /// A config file was written.
+    Write { path: String },
+    /// A provider test passed.
+    Test { provider_id: String },
+}
+
+impl HookEvent {
+    fn env(&self) -> Vec<(&'static str, String)> {
+        match self {
+            HookEvent::Write { path } => vec![("CHI_TUI_EVENT", "write".to_string()), ("CHI_TUI_FILE", path.clone())],
+            HookEvent::Test { provider_id } => vec![("CHI_TUI_EVENT", "test".to_string()), ("CHI_TUI_PROVIDER_ID", provider_id.clone())],
+        }
+    }
+}
+
+impl HookSettings {
+    pub fn for_event(&self, event: &HookEvent) -> &[String] {
+        match event {
+            HookEvent::Write { .. } => &self.after_write,
+            HookEvent::Test { .. } => &self.after_test,
+        }
+    }
+}
+
+fn shell(cmd: &str) -> Command {
+    let mut c = if cfg!(windows) { Command::new("cmd") } else { Command::new("sh") };
+    c.arg(if cfg!(windows) { "/C" } else { "-c" }).arg(cmd);
+    c
+}
+
+/// Start the hooks configured for `event`, one job each; the outcome arrives
+/// as a notification.
+pub fn run_hooks(hooks: &HookSettings, event: HookEvent, jobs: &Jobs, log: Sender<LogLine>, notify: Sender<Notification>, origin: Page) {
+    for cmd in hooks.for_event(&event) {
+        let (cmd, env, log, notify) = (cmd.clone(), event.env(), log.clone(), notify.clone());
+        jobs.spawn(format!("Hook: {}", cmd), move |_| {
+            let source = format!("hook:{}", cmd);
+            let mut command = shell(&cmd);
+            command.envs(env).stdin(Stdio::null()).stdout(Stdio::piped()).stderr(Stdio::piped());
+            let mut child = match command.spawn() {
+                Ok(c) => c,
+                Err(e) => {
+                    let _ = notify.send(Notification::new(format!("Hook failed to start: {}: {}", cmd, e), false, origin));
+                    return;
+                }
+            };
+            stream_child_output(&mut child, &source, &log);
+            let (msg, ok) = match child.wait() {
+                Ok(status) if status.success() => (format!("Hook ok: {}", cmd), true),
+                Ok(status) => (format!("Hook {}: {}", status, cmd), false),
+                Err(e) => (format!("Hook error: {}: {}", cmd, e), false),
+            };
+            let _ = log.send(LogLine::new(source, msg.clone(), !ok));
+            let _ = notify.send(Notification::new(msg, ok, origin));
+        });
+    }
+}
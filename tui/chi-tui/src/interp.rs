@@ -0,0 +1,86 @@
+//! `${VAR}` references in config values. They are written to `chi.tmp.json`
+//! and `.chi_llm.json` as they are; only tests, model discovery and prompts
+//! see the environment's values.
+
+use serde_json::Value;
+
+/// Names referenced as `${NAME}` in `s`, in order, without repeats. `NAME` is
+/// letters, digits and `_`, not starting with a digit.
+pub fn env_refs(s: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    let mut rest = s;
+    while let Some(start) = rest.find("${") {
+        let after = &rest[start + 2..];
+        let Some(end) = after.find('}') else { break };
+        let name = &after[..end];
+        if is_var_name(name) && !names.iter().any(|n| n == name) { names.push(name.to_string()); }
+        rest = &after[end + 1..];
+    }
+    names
+}
+
+fn is_var_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_') && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// `s` with every `${NAME}` replaced by `lookup(NAME)`; `Err` lists the names
+/// `lookup` has no value for.
+pub fn interpolate(s: &str, lookup: impl Fn(&str) -> Option<String>) -> Result<String, Vec<String>> {
+    let mut out = s.to_string();
+    let mut missing = Vec::new();
+    for name in env_refs(s) {
+        match lookup(&name) {
+            Some(value) => out = out.replace(&format!("${{{}}}", name), &value),
+            None => missing.push(name),
+        }
+    }
+    if missing.is_empty() { Ok(out) } else { Err(missing) }
+}
+
+/// Value of `name` in the process environment (unset and empty count as missing).
+pub fn env_value(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|v| !v.is_empty())
+}
+
+/// Resolve every string in `config` (including map values) from the environment.
+/// `Err` names the unset variables.
+pub fn resolve_config(config: &Value) -> Result<Value, Vec<String>> {
+    let mut missing: Vec<String> = Vec::new();
+    let mut out = config.clone();
+    resolve_value(&mut out, &mut missing);
+    if missing.is_empty() { Ok(out) } else { Err(missing) }
+}
+
+fn resolve_value(v: &mut Value, missing: &mut Vec<String>) {
+    match v {
+        Value::String(s) => match interpolate(s, env_value) {
+            Ok(resolved) => *s = resolved,
+            Err(names) => missing.extend(names.into_iter().filter(|n| !missing.contains(n)).collect::<Vec<_>>()),
+        },
+        Value::Object(obj) => obj.values_mut().for_each(|v| resolve_value(v, missing)),
+        Value::Array(arr) => arr.iter_mut().for_each(|v| resolve_value(v, missing)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn references_are_found_and_resolved() {
+        assert_eq!(env_refs("Bearer ${TOKEN} ${TOKEN}-${ORG_1} ${1X} ${open"), ["TOKEN", "ORG_1"]);
+        let lookup = |n: &str| (n == "TOKEN").then(|| "t0k".to_string());
+        assert_eq!(interpolate("Bearer ${TOKEN}", lookup), Ok("Bearer t0k".to_string()));
+        assert_eq!(interpolate("${TOKEN}/${NOPE}", lookup), Err(vec!["NOPE".to_string()]));
+        assert_eq!(interpolate("plain $HOME", lookup), Ok("plain $HOME".to_string()));
+
+        std::env::set_var("CHI_TUI_INTERP_TEST", "sk-1");
+        let config = serde_json::json!({"api_key": "${CHI_TUI_INTERP_TEST}", "port": 80, "headers": {"X": "${CHI_TUI_INTERP_TEST}"}});
+        let resolved = resolve_config(&config).unwrap();
+        assert_eq!((resolved["api_key"].as_str(), resolved["headers"]["X"].as_str()), (Some("sk-1"), Some("sk-1")));
+        let missing = resolve_config(&serde_json::json!({"a": "${CHI_TUI_INTERP_UNSET}", "b": "${CHI_TUI_INTERP_UNSET}"}));
+        assert_eq!(missing, Err(vec!["CHI_TUI_INTERP_UNSET".to_string()]));
+    }
+}
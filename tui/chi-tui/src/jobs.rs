@@ -0,0 +1,293 @@
+//! Background jobs. Long-running work (CLI calls, page loads) runs on a worker
+//! thread started by `Jobs::spawn`, which gives it an id, a progress line, a
+//! cancel flag and a typed `Job` handle for the result. `Jobs` is cheap to clone
+//! (like `App::notify_tx`), so page state can start jobs without the whole app;
+//! the header shows a spinner with the running jobs.
+
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+pub type JobId = u64;
+
+/// Handed to a job's closure: report progress and check for cancellation.
+#[derive(Clone, Debug, Default)]
+pub struct JobCtx {
+    progress: Arc<Mutex<String>>,
+    cancel: Arc<AtomicBool>,
+}
+
+impl JobCtx {
+    /// Replace the job's progress line (shown next to its label).
+    pub fn progress(&self, text: impl Into<String>) {
+        if let Ok(mut p) = self.progress.lock() { *p = text.into(); }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.load(Ordering::Relaxed)
+    }
+
+    /// The flag to pass to cancellable calls such as `run_program_text_cancellable`.
+    pub fn cancel_flag(&self) -> &AtomicBool {
+        &self.cancel
+    }
+}
+
+struct Running {
+    id: JobId,
+    label: String,
+    started: Instant,
+    ctx: JobCtx,
+}
+
+#[derive(Default)]
+struct JobTable {
+    next_id: JobId,
+    running: Vec<Running>,
+}
+
+/// Snapshot of a running job for display.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JobInfo {
+    pub id: JobId,
+    pub label: String,
+    pub progress: String,
+    pub elapsed: Duration,
+    pub cancelled: bool,
+}
+
+impl JobInfo {
+    /// `Testing ollama (3s)`, with the progress line when there is one.
+    pub fn describe(&self) -> String {
+        let mut s = self.label.clone();
+        if !self.progress.is_empty() { s.push_str(&format!(": {}", self.progress)); }
+        if self.cancelled { s.push_str(" (stopping)"); } else { s.push_str(&format!(" ({}s)", self.elapsed.as_secs())); }
+        s
+    }
+}
+
+/// Registry of running jobs, shared between the UI and worker threads.
+#[derive(Clone, Default)]
+pub struct Jobs {
+    table: Arc<Mutex<JobTable>>,
+}
+
+impl Jobs {
+    /// Run `work` on a worker thread. The job is listed until `work` returns
+    /// (or panics); its result arrives on the returned handle.
+    pub fn spawn<T, F>(&self, label: impl Into<String>, work: F) -> Job<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&JobCtx) -> T + Send + 'static,
+    {
+        let ctx = JobCtx::default();
+        let id = {
+            let mut table = self.lock();
+            table.next_id += 1;
+            let id = table.next_id;
+            table.running.push(Running { id, label: label.into(), started: Instant::now(), ctx: ctx.clone() });
+            id
+        };
+        let (tx, rx) = mpsc::channel();
+        let (worker_ctx, done) = (ctx.clone(), Unlist { jobs: self.clone(), id });
+        thread::spawn(move || {
+            let _done = done;
+            let _ = tx.send(work(&worker_ctx));
+        });
+        Job { id, rx, ctx }
+    }
+
+    /// Running jobs, oldest first.
+    pub fn running(&self) -> Vec<JobInfo> {
+        self.lock().running.iter().map(|r| JobInfo {
+            id: r.id,
+            label: r.label.clone(),
+            progress: r.ctx.progress.lock().map(|p| p.clone()).unwrap_or_default(),
+            elapsed: r.started.elapsed(),
+            cancelled: r.ctx.is_cancelled(),
+        }).collect()
+    }
+
+    pub fn is_busy(&self) -> bool {
+        !self.lock().running.is_empty()
+    }
+
+    /// Ask job `id` to stop. False when it is not running.
+    pub fn cancel(&self, id: JobId) -> bool {
+        let table = self.lock();
+        let Some(r) = table.running.iter().find(|r| r.id == id) else { return false };
+        r.ctx.cancel.store(true, Ordering::Relaxed);
+        true
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, JobTable> {
+        // A worker panicking while listed cannot leave the table half-updated
+        self.table.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Removes a job from the table when its worker ends, including by panic.
+struct Unlist {
+    jobs: Jobs,
+    id: JobId,
+}
+
+impl Drop for Unlist {
+    fn drop(&mut self) {
+        self.jobs.lock().running.retain(|r| r.id != self.id);
+    }
+}
+
+/// Handle to one job's result, kept by whoever started it.
+pub struct Job<T> {
+    pub id: JobId,
+    rx: Receiver<T>,
+    ctx: JobCtx,
+}
+
+impl<T> Job<T> {
+    /// The result once the job has finished; `Err` if the worker died without one.
+    pub fn poll(&self) -> Option<Result<T, String>> {
+        match self.rx.try_recv() {
+            Ok(v) => Some(Ok(v)),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(Err("job stopped unexpectedly".to_string())),
+        }
+    }
+
+    /// Ask the job to stop; it still delivers a result (usually an error).
+    pub fn cancel(&self) {
+        self.ctx.cancel.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.ctx.is_cancelled()
+    }
+
+    /// The job's cancel flag, e.g. for a popup that stops the job when closed.
+    pub fn cancel_handle(&self) -> Arc<AtomicBool> {
+        self.ctx.cancel.clone()
+    }
+}
+
+impl<T> fmt::Debug for Job<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Job").field("id", &self.id).finish()
+    }
+}
+
+/// A job re-run every `interval` (Diagnostics watch mode, the health
+/// monitor): the first run is due at once, each next one `interval` after the
+/// previous finished.
+pub struct Periodic<T> {
+    pub interval: Duration,
+    next: Instant,
+    job: Option<Job<T>>,
+}
+
+impl<T: Send + 'static> Periodic<T> {
+    pub fn new(interval: Duration) -> Self {
+        Self { interval, next: Instant::now(), job: None }
+    }
+
+    /// Wait a full interval before the first run.
+    pub fn delayed(mut self) -> Self {
+        self.next = Instant::now() + self.interval;
+        self
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.job.is_some()
+    }
+
+    /// Whether a run should start now: its time has come and none is running.
+    pub fn is_due(&self) -> bool {
+        self.job.is_none() && Instant::now() >= self.next
+    }
+
+    /// Run `work` as this schedule's job.
+    pub fn start<F>(&mut self, jobs: &Jobs, label: &str, work: F)
+    where
+        F: FnOnce(&JobCtx) -> T + Send + 'static,
+    {
+        self.job = Some(jobs.spawn(label, work));
+    }
+
+    /// The result of a finished run; the next one is due an interval from now.
+    pub fn poll(&mut self) -> Option<Result<T, String>> {
+        let done = self.job.as_ref()?.poll()?;
+        self.job = None;
+        self.next = Instant::now() + self.interval;
+        Some(done)
+    }
+
+    /// Cancel a run in flight.
+    pub fn stop(&mut self) {
+        if let Some(job) = self.job.take() { job.cancel(); }
+    }
+}
+
+impl<T> fmt::Debug for Periodic<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Periodic").field("interval", &self.interval).field("job", &self.job).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wait<T>(job: &Job<T>) -> Result<T, String> {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        loop {
+            if let Some(r) = job.poll() { return r; }
+            assert!(Instant::now() < deadline, "job did not finish");
+            thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    #[test]
+    fn jobs_are_listed_until_done_and_can_be_cancelled() {
+        let jobs = Jobs::default();
+        let job = jobs.spawn("Waiting", |ctx| {
+            ctx.progress("step 1");
+            while !ctx.is_cancelled() { thread::sleep(Duration::from_millis(5)); }
+            "stopped"
+        });
+        let info = loop {
+            let running = jobs.running();
+            if running.first().is_some_and(|j| !j.progress.is_empty()) { break running[0].clone(); }
+            thread::sleep(Duration::from_millis(5));
+        };
+        assert_eq!((info.id, info.label.as_str(), info.progress.as_str()), (job.id, "Waiting", "step 1"));
+        assert!(jobs.cancel(job.id));
+        assert_eq!(wait(&job), Ok("stopped"));
+        // The worker unlists itself right after sending
+        while jobs.is_busy() { thread::sleep(Duration::from_millis(5)); }
+        assert!(!jobs.cancel(job.id));
+
+        let panicked: Job<()> = jobs.spawn("Panics", |_| panic!("boom"));
+        assert!(wait(&panicked).is_err());
+    }
+
+    #[test]
+    fn periodic_runs_are_spaced_by_the_interval() {
+        let jobs = Jobs::default();
+        let mut runs = Periodic::new(Duration::from_secs(60));
+        assert!(runs.is_due());
+        runs.start(&jobs, "Counting", |_| 1);
+        assert!(runs.is_running() && !runs.is_due());
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let done = loop {
+            if let Some(r) = runs.poll() { break r; }
+            assert!(Instant::now() < deadline, "job did not finish");
+            thread::sleep(Duration::from_millis(5));
+        };
+        assert_eq!(done, Ok(1));
+        assert!(!runs.is_running() && !runs.is_due(), "the next run waits an interval");
+        assert!(!Periodic::<()>::new(Duration::from_secs(60)).delayed().is_due());
+    }
+}
@@ -0,0 +1,201 @@
+//! Foldable, syntax-colored JSON viewer, e.g. the raw `diagnostics` and
+//! `model_explain` output on the Diagnostics page. Objects and arrays fold on
+//! Enter/Space (←/→ collapse/expand); below the first two levels they start
+//! folded so a large document opens as an outline.
+
+use std::collections::HashSet;
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::layout::Rect;
+use ratatui::prelude::Frame;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+use serde_json::Value;
+
+use crate::app::App;
+use crate::highlight::{highlight_line, Lang};
+use crate::modal::{Modal, ModalResult};
+use crate::util::centered_rect;
+
+/// Containers deeper than this start folded.
+const OPEN_DEPTH: usize = 2;
+
+/// One rendered line. `path` is set on the opening line of an object or
+/// array, which is what folds.
+#[derive(Clone, Debug, PartialEq)]
+pub struct JsonLine {
+    pub depth: usize,
+    pub text: String,
+    pub path: Option<String>,
+    pub folded: bool,
+}
+
+/// Lines of `value` with the containers at `folded` paths collapsed. Paths
+/// are `/`-joined keys and indices (`/diagnostics/python`); the root is `""`.
+pub fn json_lines(value: &Value, folded: &HashSet<String>) -> Vec<JsonLine> {
+    let mut out = Vec::new();
+    push_value(&mut out, value, None, String::new(), 0, true, folded);
+    out
+}
+
+fn push_value(out: &mut Vec<JsonLine>, v: &Value, key: Option<&str>, path: String, depth: usize, last: bool, folded: &HashSet<String>) {
+    let label = key.map(|k| format!("{}: ", Value::String(k.to_string()))).unwrap_or_default();
+    let comma = if last { "" } else { "," };
+    let (open, close, children): (&str, &str, Vec<(Option<&str>, &Value)>) = match v {
+        Value::Object(map) if !map.is_empty() => ("{", "}", map.iter().map(|(k, v)| (Some(k.as_str()), v)).collect()),
+        Value::Array(items) if !items.is_empty() => ("[", "]", items.iter().map(|v| (None, v)).collect()),
+        _ => {
+            out.push(JsonLine { depth, text: format!("{}{}{}", label, v, comma), path: None, folded: false });
+            return;
+        }
+    };
+    if folded.contains(&path) {
+        let noun = if open == "{" { "keys" } else { "items" };
+        let text = format!("{}{}…{}{}  ({} {})", label, open, close, comma, children.len(), noun);
+        out.push(JsonLine { depth, text, path: Some(path), folded: true });
+        return;
+    }
+    out.push(JsonLine { depth, text: format!("{}{}", label, open), path: Some(path.clone()), folded: false });
+    let n = children.len();
+    for (i, (k, child)) in children.into_iter().enumerate() {
+        let child_path = format!("{}/{}", path, k.map(str::to_string).unwrap_or_else(|| i.to_string()));
+        push_value(out, child, k, child_path, depth + 1, i + 1 == n, folded);
+    }
+    out.push(JsonLine { depth, text: format!("{}{}", close, comma), path: None, folded: false });
+}
+
+/// Paths of the containers in `value` deeper than `depth`.
+fn paths_below(value: &Value, path: &str, depth: usize, at: usize, out: &mut HashSet<String>) {
+    let children: Vec<(String, &Value)> = match value {
+        Value::Object(map) if !map.is_empty() => map.iter().map(|(k, v)| (k.clone(), v)).collect(),
+        Value::Array(items) if !items.is_empty() => items.iter().enumerate().map(|(i, v)| (i.to_string(), v)).collect(),
+        _ => return,
+    };
+    if at >= depth { out.insert(path.to_string()); }
+    for (k, child) in children {
+        paths_below(child, &format!("{}/{}", path, k), depth, at + 1, out);
+    }
+}
+
+pub struct JsonViewModal {
+    pub title: String,
+    value: Value,
+    folded: HashSet<String>,
+    lines: Vec<JsonLine>,
+    cursor: usize,
+}
+
+impl JsonViewModal {
+    pub fn new(title: impl Into<String>, value: Value) -> Self {
+        let mut folded = HashSet::new();
+        paths_below(&value, "", OPEN_DEPTH, 0, &mut folded);
+        let lines = json_lines(&value, &folded);
+        Self { title: title.into(), value, folded, lines, cursor: 0 }
+    }
+
+    /// Fold (`Some(true)`), unfold (`Some(false)`) or toggle the container
+    /// under the cursor.
+    fn fold(&mut self, fold: Option<bool>) {
+        let Some(path) = self.lines.get(self.cursor).and_then(|l| l.path.clone()) else { return };
+        let now = fold.unwrap_or(!self.folded.contains(&path));
+        if now { self.folded.insert(path.clone()); } else { self.folded.remove(&path); }
+        self.lines = json_lines(&self.value, &self.folded);
+        // Keep the cursor on the line it was on
+        self.cursor = self.lines.iter().position(|l| l.path.as_deref() == Some(path.as_str())).unwrap_or(0);
+    }
+
+    fn set_all(&mut self, folded: bool) {
+        self.folded.clear();
+        if folded { paths_below(&self.value, "", 1, 0, &mut self.folded); }
+        self.lines = json_lines(&self.value, &self.folded);
+        self.cursor = 0;
+    }
+}
+
+impl Modal for JsonViewModal {
+    fn label(&self) -> String {
+        self.title.clone()
+    }
+
+    fn handle_key(&mut self, _app: &mut App, key: KeyEvent) -> ModalResult {
+        let last = self.lines.len().saturating_sub(1);
+        match key.code {
+            KeyCode::Up => self.cursor = self.cursor.saturating_sub(1),
+            KeyCode::Down => self.cursor = (self.cursor + 1).min(last),
+            KeyCode::PageUp => self.cursor = self.cursor.saturating_sub(10),
+            KeyCode::PageDown => self.cursor = (self.cursor + 10).min(last),
+            KeyCode::Home => self.cursor = 0,
+            KeyCode::End => self.cursor = last,
+            KeyCode::Enter | KeyCode::Char(' ') => self.fold(None),
+            KeyCode::Left => self.fold(Some(true)),
+            KeyCode::Right => self.fold(Some(false)),
+            KeyCode::Char('E') => self.set_all(false),
+            KeyCode::Char('C') => self.set_all(true),
+            KeyCode::Char('v') | KeyCode::Char('q') => return ModalResult::Close,
+            _ => {}
+        }
+        ModalResult::Keep
+    }
+
+    fn draw(&self, f: &mut Frame, area: Rect, app: &App) {
+        let area_pop = centered_rect(85, 85, area);
+        let visible = (area_pop.height as usize).saturating_sub(3).max(1);
+        let top = (self.cursor + 1).saturating_sub(visible);
+        let mut lines: Vec<Line> = self.lines.iter().enumerate().skip(top).take(visible).map(|(i, l)| {
+            let marker = match (&l.path, l.folded) {
+                (Some(_), true) => "▸ ",
+                (Some(_), false) => "▾ ",
+                (None, _) => "  ",
+            };
+            let cursor = if i == self.cursor { "›" } else { " " };
+            let mut spans = vec![
+                Span::styled(cursor, app.theme.selected_row()),
+                Span::styled(format!("{}{}", "  ".repeat(l.depth), marker), app.theme.hint()),
+            ];
+            spans.extend(highlight_line(Lang::Json, &l.text, &app.theme).spans);
+            Line::from(spans)
+        }).collect();
+        lines.resize(visible, Line::from(""));
+        lines.push(Line::from(Span::styled(
+            format!("line {}/{} • ↑/↓ PgUp/PgDn move • Enter/Space fold • ←/→ collapse/expand • E/C expand/collapse all • v/Esc close", self.cursor + 1, self.lines.len()),
+            app.theme.hint(),
+        )));
+        let p = Paragraph::new(lines)
+            .style(app.theme.base())
+            .block(Block::default().borders(Borders::ALL).border_style(app.theme.focused_border()).title(self.title.clone()));
+        f.render_widget(Clear, area_pop);
+        f.render_widget(p, area_pop);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn containers_fold_into_one_line() {
+        let v = json!({"python": {"version": "3.12", "path": "/usr/bin/python3"}, "gpus": [], "ok": true});
+        let text = |folded: &HashSet<String>| json_lines(&v, folded).into_iter().map(|l| format!("{}{}", "  ".repeat(l.depth), l.text)).collect::<Vec<_>>();
+        assert_eq!(text(&HashSet::new()), [
+            "{",
+            "  \"gpus\": [],",
+            "  \"ok\": true,",
+            "  \"python\": {",
+            "    \"path\": \"/usr/bin/python3\",",
+            "    \"version\": \"3.12\"",
+            "  }",
+            "}",
+        ]);
+        let folded: HashSet<String> = ["/python".to_string()].into();
+        assert_eq!(text(&folded)[3], "  \"python\": {…}  (2 keys)");
+
+        let mut view = JsonViewModal::new("raw", json!({"a": {"b": {"c": 1}}}));
+        assert_eq!(view.lines.iter().filter(|l| l.folded).map(|l| l.path.clone().unwrap()).collect::<Vec<_>>(), ["/a/b"]);
+        view.cursor = 2;
+        view.fold(None);
+        assert_eq!((view.lines.len(), view.cursor), (7, 2));
+        view.set_all(true);
+        assert_eq!(view.lines.len(), 3);
+    }
+}
@@ -0,0 +1,85 @@
+//! Secrets kept in the system keychain (Secret Service, macOS Keychain,
+//! Windows Credential Manager). The config only holds `keyring:<account>`;
+//! tests, model discovery and prompts look the value up under the `chi-llm`
+//! service.
+
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+
+/// Keychain service name the accounts are stored under.
+pub const SERVICE: &str = "chi-llm";
+pub const PREFIX: &str = "keyring:";
+
+/// The account named by a `keyring:<account>` value.
+pub fn keyring_account(value: &str) -> Option<&str> {
+    value.trim().strip_prefix(PREFIX).filter(|a| !a.is_empty())
+}
+
+pub fn keyring_ref(account: &str) -> String {
+    format!("{}{}", PREFIX, account)
+}
+
+/// Account for config key `key` of provider `id`, e.g. `work-openai/api_key`.
+pub fn account_for(id: &str, key: &str) -> String {
+    format!("{}/{}", id, key)
+}
+
+fn entry(account: &str) -> Result<keyring::Entry> {
+    keyring::Entry::new(SERVICE, account).map_err(|e| anyhow!("keychain: {}", e))
+}
+
+/// Store `secret` under `account`, replacing what was there.
+pub fn store(account: &str, secret: &str) -> Result<()> {
+    entry(account)?.set_password(secret).map_err(|e| anyhow!("keychain: {}", e))
+}
+
+/// The secret stored under `account`.
+pub fn load(account: &str) -> Result<String> {
+    match entry(account)?.get_password() {
+        Ok(secret) => Ok(secret),
+        Err(keyring::Error::NoEntry) => Err(anyhow!("no keychain entry {}/{}", SERVICE, account)),
+        Err(e) => Err(anyhow!("keychain: {}", e)),
+    }
+}
+
+/// Replace every top-level `keyring:` value in `config` by `lookup(account)`.
+/// `Err` lists the accounts `lookup` has no value for.
+pub fn resolve_with(config: &Value, lookup: impl Fn(&str) -> Option<String>) -> Result<Value, Vec<String>> {
+    let mut out = config.clone();
+    let mut missing = Vec::new();
+    if let Some(obj) = out.as_object_mut() {
+        for v in obj.values_mut() {
+            let Some(account) = v.as_str().and_then(keyring_account).map(str::to_string) else { continue };
+            match lookup(&account) {
+                Some(secret) => *v = Value::String(secret),
+                None => missing.push(account),
+            }
+        }
+    }
+    if missing.is_empty() { Ok(out) } else { Err(missing) }
+}
+
+/// `resolve_with` against the system keychain.
+pub fn resolve_keychain(config: &Value) -> Result<Value, Vec<String>> {
+    resolve_with(config, |account| load(account).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn references_name_an_account_and_resolve_through_the_lookup() {
+        assert_eq!(keyring_account(" keyring:p1/api_key"), Some("p1/api_key"));
+        assert_eq!(keyring_account("keyring:"), None);
+        assert_eq!(keyring_account("sk-keyring:x"), None);
+        assert_eq!(keyring_ref(&account_for("p1", "api_key")), "keyring:p1/api_key");
+
+        let lookup = |a: &str| (a == "p1/api_key").then(|| "sk-1".to_string());
+        let config = serde_json::json!({"api_key": "keyring:p1/api_key", "model": "gpt-4o", "port": 80});
+        let resolved = resolve_with(&config, lookup).unwrap();
+        assert_eq!((resolved["api_key"].as_str(), resolved["model"].as_str()), (Some("sk-1"), Some("gpt-4o")));
+        let missing = resolve_with(&serde_json::json!({"token": "keyring:p2/token"}), lookup);
+        assert_eq!(missing, Err(vec!["p2/token".to_string()]));
+    }
+}
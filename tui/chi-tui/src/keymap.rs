@@ -0,0 +1,97 @@
+//! Key hints of each page, with when they apply. Pages list them through
+//! `PageController::actions`; the footer shows the ones valid right now and the
+//! help overlay lists all of the active page's. The list describes keys, it
+//! does not bind them: each page's `handle_key` does, so a new key goes in both.
+
+use crate::app::App;
+
+#[derive(Copy, Clone)]
+pub struct KeyAction {
+    pub keys: &'static str,
+    pub label: &'static str,
+    /// When the action is valid; `None` means always.
+    pub when: Option<fn(&App) -> bool>,
+}
+
+impl KeyAction {
+    pub const fn new(keys: &'static str, label: &'static str) -> Self {
+        Self { keys, label, when: None }
+    }
+
+    pub const fn when(self, when: fn(&App) -> bool) -> Self {
+        Self { when: Some(when), ..self }
+    }
+
+    pub fn available(&self, app: &App) -> bool {
+        self.when.is_none_or(|when| when(app))
+    }
+
+    pub fn hint(&self) -> String {
+        format!("{} {}", self.keys, self.label)
+    }
+}
+
+fn test_running(app: &App) -> bool {
+    app.providers.ready().is_some_and(|st| st.test_job.is_some())
+}
+
+fn has_log(app: &App) -> bool {
+    !app.log.is_empty()
+}
+
+/// Global keys, shown by pages without their own actions.
+pub const GLOBAL_ACTIONS: &[KeyAction] = &[
+    KeyAction::new("Esc", "back"),
+    KeyAction::new("q", "quit"),
+    KeyAction::new("1-7/b/s/w", "sections"),
+    KeyAction::new("?", "help"),
+];
+
+/// Global keys that only matter in some states; every footer ends with them.
+pub const CONTEXT_ACTIONS: &[KeyAction] = &[
+    KeyAction::new("Ctrl+X", "cancel test").when(test_running),
+    KeyAction::new("l", "log").when(has_log),
+];
+
+/// Footer line: the valid `actions`, then the valid context actions.
+pub fn footer_hints(actions: &[KeyAction], app: &App) -> String {
+    actions.iter().chain(CONTEXT_ACTIONS)
+        .filter(|a| a.available(app))
+        .map(KeyAction::hint)
+        .collect::<Vec<_>>()
+        .join(" • ")
+}
+
+/// Help overlay line: every action of the page, valid or not.
+pub fn help_line(actions: &[KeyAction]) -> String {
+    actions.iter().map(KeyAction::hint).collect::<Vec<_>>().join(" • ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn never(_: &App) -> bool {
+        false
+    }
+
+    #[test]
+    fn footer_shows_only_valid_actions() {
+        let mut app = App::new();
+        let actions = [KeyAction::new("s", "save").when(never), KeyAction::new("r", "reload")];
+        assert_eq!(footer_hints(&actions, &app), "r reload");
+        assert_eq!(help_line(&actions), "s save • r reload");
+        app.log.push_back(crate::log::LogLine::new("ollama", "pulling", false));
+        assert_eq!(footer_hints(&actions, &app), "r reload • l log");
+    }
+
+    #[test]
+    fn sections_hint_names_every_page_key() {
+        use crate::msg::PAGE_KEYS;
+        let keys: Vec<char> = PAGE_KEYS.iter().map(|(k, _)| *k).collect();
+        let last_digit = keys.iter().rfind(|k| k.is_ascii_digit()).unwrap();
+        let letters: Vec<String> = keys.iter().filter(|k| !k.is_ascii_digit()).map(char::to_string).collect();
+        let hint = GLOBAL_ACTIONS.iter().find(|a| a.label == "sections").unwrap().keys;
+        assert_eq!(hint, format!("1-{}/{}", last_digit, letters.join("/")));
+    }
+}
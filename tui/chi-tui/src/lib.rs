@@ -0,0 +1,105 @@
+//! Terminal configurator for chi-llm. The binary is a thin wrapper around `run_app`;
+//! the app state, pages and widgets are public so they can be embedded or tested.
+
+use std::io::Write;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use crossterm::event::{self, Event};
+use ratatui::backend::Backend;
+use ratatui::Terminal;
+
+pub mod a11y;
+pub mod theme;
+pub mod util;
+pub mod app;
+pub mod backend;
+pub mod batch;
+pub mod bench;
+pub mod bundle;
+pub mod diagnostics;
+pub mod diff;
+pub mod readme;
+pub mod models;
+pub mod providers;
+pub mod pyenv;
+pub mod build;
+pub mod changelog;
+pub mod cli;
+pub mod cli_log;
+pub mod clipboard;
+pub mod compat;
+pub mod page_data;
+pub mod paths;
+pub mod controller;
+pub mod custom_models;
+pub mod env;
+pub mod file_picker;
+pub mod events;
+pub mod focus;
+pub mod forms;
+pub mod git;
+pub mod hf;
+pub mod highlight;
+pub mod hooks;
+pub mod interp;
+pub mod jobs;
+pub mod json_view;
+pub mod keychain;
+pub mod keymap;
+pub mod log;
+pub mod macros;
+pub mod modal;
+pub mod mouse;
+pub mod msg;
+pub mod model_meta;
+pub mod recent;
+pub mod recovery;
+pub mod welcome;
+pub mod session;
+pub mod settings;
+pub mod text;
+pub mod toast;
+pub mod tutorial;
+pub mod ui;
+pub mod undo;
+
+pub use app::{App, Page};
+
+/// Event poll interval while idle (see `App::is_idle`); input still wakes the
+/// loop at once.
+const IDLE_POLL: Duration = Duration::from_secs(2);
+
+/// Run the event loop on `terminal` until the user quits. Terminal setup and
+/// teardown (raw mode, alternate screen) are left to the caller.
+pub fn run_app<B: Backend>(terminal: &mut Terminal<B>, mut app: App) -> Result<()> {
+    app.start_health_check();
+    loop {
+        let tick_rate = app.settings.tick_rate();
+        app.poll_background();
+        // Idle: the animation stops and the loop wakes every IDLE_POLL
+        let idle = app.is_idle();
+        if !idle && app.last_tick.elapsed() >= tick_rate {
+            app.tick = app.tick.wrapping_add(1);
+            app.last_tick = Instant::now();
+        }
+        terminal.draw(|f| ui::ui(f, &app))?;
+        if app.announcer.take_bell() {
+            let mut out = std::io::stdout();
+            let _ = out.write_all(b"\x07").and_then(|_| out.flush());
+        }
+        if event::poll(if idle { IDLE_POLL } else { tick_rate })? {
+            app.last_input = Instant::now();
+            match event::read()? {
+                Event::Key(key) if app.compat.accepts(&key) => app.handle_key(key),
+                Event::Paste(text) => app.handle_paste(&text),
+                Event::Mouse(ev) => app.handle_mouse(ev),
+                _ => {}
+            }
+        }
+        if app.should_quit { break; }
+    }
+    readme::remember_docs(&mut app);
+    session::save_session(&app);
+    Ok(())
+}
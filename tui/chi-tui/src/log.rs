@@ -0,0 +1,112 @@
+//! In-app log panel (`l` toggles it). Background work such as hooks sends lines
+//! through `App::log_tx`; `App::poll_log` keeps the newest `LOG_CAPACITY` lines.
+
+use std::io::{BufReader, Read};
+use std::process::{Child, ChildStderr, ChildStdout};
+use std::sync::mpsc::Sender;
+use std::thread;
+use std::time::Instant;
+
+use ratatui::layout::Rect;
+use ratatui::prelude::Frame;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph};
+
+use crate::app::App;
+
+pub const LOG_CAPACITY: usize = 500;
+
+#[derive(Clone, Debug)]
+pub struct LogLine {
+    /// Short origin label, e.g. `hook:direnv allow`.
+    pub source: String,
+    pub text: String,
+    pub is_err: bool,
+    pub at: Instant,
+}
+
+impl LogLine {
+    pub fn new(source: impl Into<String>, text: impl Into<String>, is_err: bool) -> Self {
+        Self { source: source.into(), text: text.into(), is_err, at: Instant::now() }
+    }
+}
+
+/// Newest lines at the bottom, clipped to the panel height.
+pub fn draw_log_panel(f: &mut Frame, area: Rect, app: &App) {
+    let visible = area.height.saturating_sub(2) as usize;
+    let start = app.log.len().saturating_sub(visible);
+    let lines: Vec<Line> = app.log.iter().skip(start).map(|l| {
+        let style = if l.is_err { app.theme.error() } else { app.theme.text() };
+        Line::from(vec![
+            Span::styled(format!("[{}] ", l.source), app.theme.hint()),
+            Span::styled(l.text.clone(), style),
+        ])
+    }).collect();
+    let p = Paragraph::new(lines)
+        .style(app.theme.base())
+        .block(Block::default().borders(Borders::TOP).border_style(app.theme.border()).title("Log (l hides)"));
+    f.render_widget(p, area);
+}
+
+/// Forward a child's stdout and stderr (as errors) to the log until both close.
+pub fn stream_child_output(child: &mut Child, source: &str, log: &Sender<LogLine>) {
+    stream_output(child.stdout.take(), child.stderr.take(), source, log);
+}
+
+/// `stream_child_output` for pipes already taken from the child, so another
+/// thread can wait on (or kill) it meanwhile.
+pub fn stream_output(stdout: Option<ChildStdout>, stderr: Option<ChildStderr>, source: &str, log: &Sender<LogLine>) {
+    let stderr = stderr.map(|s| {
+        let (source, log) = (source.to_string(), log.clone());
+        thread::spawn(move || forward_lines(s, &source, true, &log))
+    });
+    if let Some(out) = stdout {
+        forward_lines(out, source, false, log);
+    }
+    if let Some(h) = stderr { let _ = h.join(); }
+}
+
+/// Split on `\r` as well as `\n` so redrawn progress bars arrive as they update;
+/// ANSI escapes, blank lines and repeats of the previous line are dropped.
+fn forward_lines(r: impl Read, source: &str, is_err: bool, log: &Sender<LogLine>) {
+    let mut last = String::new();
+    let mut send = |raw: &[u8]| {
+        let line = strip_ansi(&String::from_utf8_lossy(raw));
+        if line.trim().is_empty() || line == last { return; }
+        let _ = log.send(LogLine::new(source, line.clone(), is_err));
+        last = line;
+    };
+    let mut buf = Vec::new();
+    for b in BufReader::new(r).bytes().map_while(Result::ok) {
+        if b == b'\n' || b == b'\r' { send(&buf); buf.clear(); } else { buf.push(b); }
+    }
+    send(&buf);
+}
+
+/// Remove CSI escape sequences such as colours and cursor movement.
+pub fn strip_ansi(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' { out.push(c); continue; }
+        if chars.next() == Some('[') {
+            for c in chars.by_ref() { if ('@'..='~').contains(&c) { break; } }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn progress_output_becomes_clean_lines() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let raw = "pulling 10%\r\x1b[2Kpulling 10%\rpulling 55%\r\n\x1b[32msuccess\x1b[0m\n";
+        forward_lines(raw.as_bytes(), "pull", false, &tx);
+        drop(tx);
+        let lines: Vec<String> = rx.iter().map(|l| l.text).collect();
+        assert_eq!(lines, ["pulling 10%", "pulling 55%", "success"]);
+    }
+}
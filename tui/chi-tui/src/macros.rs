@@ -0,0 +1,137 @@
+//! Keyboard macros: `Ctrl+R` starts/stops recording, stopping asks for a name and
+//! saves the keys to `macros.json` in the config dir; `Ctrl+P` picks a saved macro
+//! and replays it. Replay feeds one key per loop iteration and waits while page
+//! data or provider tests are still running, so recorded flows that cross pages
+//! behave like they did when typed.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::fs;
+
+use anyhow::{anyhow, Result};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::paths::{file_in, paths};
+
+pub type MacroBook = BTreeMap<String, Vec<String>>;
+
+#[derive(Debug, Default)]
+pub struct MacroState {
+    /// Keys captured so far while recording.
+    pub recording: Option<Vec<KeyEvent>>,
+    /// Keys waiting to be replayed.
+    pub replay: VecDeque<KeyEvent>,
+}
+
+impl MacroState {
+    pub fn is_recording(&self) -> bool { self.recording.is_some() }
+    pub fn is_replaying(&self) -> bool { !self.replay.is_empty() }
+
+    pub fn record(&mut self, key: KeyEvent) {
+        if let Some(keys) = &mut self.recording { keys.push(key); }
+    }
+}
+
+/// Saved macros; an unreadable file counts as empty.
+pub fn load_macros() -> MacroBook {
+    fs::read_to_string(paths().config_dir.join("macros.json"))
+        .ok()
+        .and_then(|t| serde_json::from_str(&t).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_macro(name: &str, keys: &[KeyEvent]) -> Result<()> {
+    let mut book = load_macros();
+    book.insert(name.to_string(), keys.iter().map(key_to_string).collect());
+    let path = file_in(&paths().config_dir, "macros.json")?;
+    fs::write(path, serde_json::to_vec_pretty(&book)?)?;
+    Ok(())
+}
+
+/// Keys of a saved macro.
+pub fn macro_keys(book: &MacroBook, name: &str) -> Result<Vec<KeyEvent>> {
+    let keys = book.get(name).ok_or_else(|| anyhow!("no macro named '{}'", name))?;
+    keys.iter().map(|k| key_from_string(k)).collect()
+}
+
+/// Readable key notation used in `macros.json`, e.g. `a`, `Enter`, `Ctrl+s`, `Shift+Tab`.
+pub fn key_to_string(key: &KeyEvent) -> String {
+    let base = match key.code {
+        KeyCode::Char(' ') => "Space".to_string(),
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::F(n) => format!("F{}", n),
+        KeyCode::BackTab => "BackTab".to_string(),
+        other => format!("{:?}", other),
+    };
+    let mut prefix = String::new();
+    if key.modifiers.contains(KeyModifiers::CONTROL) { prefix.push_str("Ctrl+"); }
+    if key.modifiers.contains(KeyModifiers::ALT) { prefix.push_str("Alt+"); }
+    // Shift is implied by the character for printable keys
+    if key.modifiers.contains(KeyModifiers::SHIFT) && !matches!(key.code, KeyCode::Char(_) | KeyCode::BackTab) {
+        prefix.push_str("Shift+");
+    }
+    prefix + &base
+}
+
+pub fn key_from_string(s: &str) -> Result<KeyEvent> {
+    let mut mods = KeyModifiers::NONE;
+    let mut rest = s;
+    // A lone "+" is the plus key, not a modifier separator
+    while let Some((m, tail)) = rest.split_once('+').filter(|(_, t)| !t.is_empty()) {
+        match m {
+            "Ctrl" => mods |= KeyModifiers::CONTROL,
+            "Alt" => mods |= KeyModifiers::ALT,
+            "Shift" => mods |= KeyModifiers::SHIFT,
+            _ => break,
+        }
+        rest = tail;
+    }
+    let code = match rest {
+        "Space" => KeyCode::Char(' '),
+        "Enter" => KeyCode::Enter,
+        "Esc" => KeyCode::Esc,
+        "Tab" => KeyCode::Tab,
+        "BackTab" => KeyCode::BackTab,
+        "Backspace" => KeyCode::Backspace,
+        "Delete" => KeyCode::Delete,
+        "Up" => KeyCode::Up,
+        "Down" => KeyCode::Down,
+        "Left" => KeyCode::Left,
+        "Right" => KeyCode::Right,
+        "Home" => KeyCode::Home,
+        "End" => KeyCode::End,
+        "PageUp" => KeyCode::PageUp,
+        "PageDown" => KeyCode::PageDown,
+        f if f.len() > 1 && f.starts_with('F') && f[1..].parse::<u8>().is_ok() => KeyCode::F(f[1..].parse().unwrap_or(1)),
+        c if c.chars().count() == 1 => KeyCode::Char(c.chars().next().unwrap_or(' ')),
+        other => return Err(anyhow!("unknown key '{}' in macro", other)),
+    };
+    if code == KeyCode::BackTab { mods |= KeyModifiers::SHIFT; }
+    Ok(KeyEvent::new(code, mods))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn key_notation_round_trips() {
+        let keys = [
+            KeyEvent::new(KeyCode::Char('a'), KeyModifiers::NONE),
+            KeyEvent::new(KeyCode::Char('T'), KeyModifiers::SHIFT),
+            KeyEvent::new(KeyCode::Char('+'), KeyModifiers::NONE),
+            KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL),
+            KeyEvent::new(KeyCode::BackTab, KeyModifiers::SHIFT),
+            KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE),
+            KeyEvent::new(KeyCode::F(5), KeyModifiers::NONE),
+            KeyEvent::new(KeyCode::Char(' '), KeyModifiers::NONE),
+        ];
+        for k in keys {
+            let s = key_to_string(&k);
+            let back = key_from_string(&s).unwrap();
+            assert_eq!(back.code, k.code, "{s}");
+            assert_eq!(back.modifiers.contains(KeyModifiers::CONTROL), k.modifiers.contains(KeyModifiers::CONTROL));
+        }
+        assert_eq!(key_to_string(&keys[3]), "Ctrl+s");
+        assert!(key_from_string("Hyper+x").is_err());
+    }
+}
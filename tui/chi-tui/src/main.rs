@@ -1,70 +1,25 @@
-use std::io::{self, Stdout};
-use std::time::Duration;
+use std::io;
+use std::path::PathBuf;
 
 use anyhow::Result;
 use clap::Parser;
-use crossterm::event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyModifiers};
+use crossterm::event::{DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture};
 use crossterm::execute;
 use crossterm::terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen};
 use ratatui::backend::CrosstermBackend;
-use ratatui::layout::{Alignment, Constraint, Direction, Layout, Rect};
-use ratatui::style::{Color, Modifier, Style};
-use ratatui::text::{Line, Span};
-use ratatui::widgets::{Block, Borders, Clear, List, ListItem, Paragraph, Wrap};
 use ratatui::Terminal;
-use ratatui::prelude::Frame;
-use serde_json::Value;
 
-mod theme;
-mod util;
-mod app;
-mod diagnostics;
-mod readme;
-mod models;
-mod providers;
-mod build;
-
-use app::{App, Page, WELCOME_ITEMS};
-use build::{BuildState, BuildTarget, draw_build_config, write_active_config};
-use diagnostics::{draw_diagnostics, export_diagnostics, fetch_diagnostics};
-use models::{fetch_models, draw_model_browser};
-use providers::{ProvidersState, FormState, DropdownState, load_providers_state, draw_providers_catalog, probe_provider, load_providers_scratch, save_default_provider, draw_select_default};
-use readme::{load_readme, draw_readme};
-use util::{ensure_chi_llm, centered_rect, neon_gradient_line};
-
-fn ensure_form_for_selected(st: &mut ProvidersState) {
-    if st.selected >= st.entries.len() { st.form = None; return; }
-    let entry = &st.entries[st.selected];
-    let mut ff = Vec::new();
-    if let Some(sfields) = st.schema_map.get(&entry.ptype) {
-        for sc in sfields.iter() {
-            let mut value = String::new();
-            if let Some(cfg) = entry.config.as_object() {
-                if let Some(v) = cfg.get(&sc.name) {
-                    value = match v { Value::String(s) => s.clone(), other => other.to_string() };
-                }
-            }
-            if value.is_empty() { if let Some(d) = &sc.default { value = d.clone(); } }
-            ff.push(providers::FormField { schema: providers::FieldSchema { name: sc.name.clone(), ftype: sc.ftype.clone(), required: sc.required, default: sc.default.clone(), help: sc.help.clone(), options: sc.options.clone() }, buffer: value, cursor: 0 });
-        }
-    }
-    let init_hash = providers::compute_form_hash(&ff);
-    st.form = Some(FormState { fields: ff, selected: 0, editing: false, message: None, scroll: 0, initial_hash: init_hash, last_test_ok_hash: None });
-}
-
-fn focus_form_field(st: &mut ProvidersState, field_name: &str) {
-    if st.selected >= st.entries.len() { return; }
-    ensure_form_for_selected(st);
-    if let Some(form) = &mut st.form {
-        if let Some(idx) = form.fields.iter().position(|f| f.schema.name == field_name) {
-            form.selected = idx;
-            form.editing = true;
-            st.focus_right = true;
-        } else {
-            st.focus_right = true;
-        }
-    }
-}
+use chi_tui::build::BuildTarget;
+use chi_tui::changelog::take_upgrade;
+use chi_tui::cli::{apply_providers_file, run_command, Command};
+use chi_tui::compat::{Compat, CompatMode};
+use chi_tui::env::apply_env_overrides;
+use chi_tui::paths::paths;
+use chi_tui::recovery::check_startup;
+use chi_tui::session::{resume, Session};
+use chi_tui::settings::{apply_settings, load_settings};
+use chi_tui::util::{ensure_chi_llm, resolve_chi_llm, set_chi_llm_command};
+use chi_tui::{run_app, App, Page};
 
 #[derive(Parser, Debug)]
 #[command(name = "chi-tui")] 
@@ -73,31 +28,108 @@ struct Args {
     /// Do not use alternate screen buffer
     #[arg(long = "no-alt")]
     no_alt: bool,
+
+    /// Windows terminal workarounds (default: auto-detect, or CHI_TUI_COMPAT)
+    #[arg(long, value_enum)]
+    compat: Option<CompatMode>,
+
+    /// Screen-reader-friendly mode: linear text, no borders, announced changes
+    /// (or CHI_TUI_A11Y)
+    #[arg(long)]
+    a11y: bool,
+
+    /// Validate a providers file (chi.tmp.json format) and write its default
+    /// provider to the --target config, without starting the UI
+    #[arg(long, value_name = "PROVIDERS_JSON")]
+    apply: Option<PathBuf>,
+
+    /// Config written by --apply
+    #[arg(long, value_enum, default_value_t = BuildTarget::Project, requires = "apply")]
+    target: BuildTarget,
+
+    /// Start on the welcome page instead of where the last session left off
+    #[arg(long)]
+    no_resume: bool,
+
+    /// The chi-llm executable, or a Python interpreter to run `-m chi_llm`
+    /// with (default: settings.toml [chi_llm] bin, then PATH, then python -m chi_llm)
+    #[arg(long, value_name = "PATH")]
+    chi_llm_bin: Option<String>,
+
+    /// Run a non-interactive command instead of the UI
+    #[command(subcommand)]
+    command: Option<Command>,
 }
 
 fn main() -> Result<()> {
     let args = Args::parse();
+    let settings = load_settings();
+    if let Some(bin) = &args.chi_llm_bin {
+        set_chi_llm_command(resolve_chi_llm(Some(bin))?);
+    } else if let Some(bin) = settings.as_ref().ok().and_then(|s| s.chi_llm.bin.clone()) {
+        // A saved command that stopped working falls back to the search
+        match resolve_chi_llm(Some(&bin)) {
+            Ok(cmd) => set_chi_llm_command(cmd),
+            Err(e) => eprintln!("Ignoring settings.toml [chi_llm] bin: {:#}", e),
+        }
+    }
+    if let Some(path) = &args.apply {
+        report(apply_providers_file(path, args.target));
+        return Ok(());
+    }
+    if let Some(cmd) = args.command {
+        report(run_command(cmd));
+        return Ok(());
+    }
     ensure_chi_llm()?;
+    let compat = Compat::detect(args.compat.or_else(CompatMode::from_env).unwrap_or_default());
+    let use_alt = !args.no_alt && compat.alt_screen;
 
     // Terminal setup
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    if !args.no_alt {
-        execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+    if use_alt {
+        execute!(stdout, EnterAlternateScreen, EnableMouseCapture, EnableBracketedPaste)?;
     } else {
-        execute!(stdout, EnableMouseCapture)?;
+        execute!(stdout, EnableMouseCapture, EnableBracketedPaste)?;
     }
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
-    let res = run_app(&mut terminal, App::new(!args.no_alt));
+    let mut app = App::new();
+    app.compat = compat;
+    let mut settings_error = None;
+    match settings {
+        Ok(settings) => {
+            app.settings = settings;
+            app.settings_path = Some(paths().settings_file());
+            apply_settings(&mut app);
+        }
+        Err(e) => settings_error = Some(e),
+    }
+    // Corrupt settings or chi.tmp.json: ask before anything overwrites them
+    check_startup(&mut app, settings_error);
+    // Show the changelog once after an upgrade, instead of the resumed page
+    // but not instead of a requested start page
+    let upgraded = take_upgrade();
+    if !args.no_resume {
+        if let Some(mut session) = Session::load() {
+            if upgraded { session.page = None; }
+            resume(&mut app, session);
+        }
+    }
+    apply_env_overrides(&mut app);
+    if upgraded && app.page == Page::Welcome { app.page = Page::WhatsNew; }
+    if args.a11y { app.a11y = true; }
+    if app.a11y { app.anim = false; }
+    let res = run_app(&mut terminal, app);
 
     // Restore terminal
     disable_raw_mode()?;
     let mut stdout = io::stdout();
-    if !args.no_alt {
-        execute!(stdout, LeaveAlternateScreen, DisableMouseCapture)?;
+    if use_alt {
+        execute!(stdout, LeaveAlternateScreen, DisableMouseCapture, DisableBracketedPaste)?;
     } else {
-        execute!(stdout, DisableMouseCapture)?;
+        execute!(stdout, DisableMouseCapture, DisableBracketedPaste)?;
     }
     terminal.show_cursor()?;
 
@@ -108,607 +140,13 @@ fn main() -> Result<()> {
     Ok(())
 }
 
-fn run_app(terminal: &mut Terminal<CrosstermBackend<Stdout>>, mut app: App) -> Result<()> {
-    let tick_rate = Duration::from_millis(100);
-    loop {
-        terminal.draw(|f| ui(f, &app))?;
-        if event::poll(tick_rate)? {
-            if let Event::Key(key) = event::read()? {
-                // Diagnostics page extra keys
-                if app.page == Page::Diagnostics {
-                    match key.code {
-                        KeyCode::Char('e') | KeyCode::Char('E') => {
-                            if let Some(diag) = &app.diag {
-                                match export_diagnostics(diag) {
-                                    Ok(path) => {
-                                        if let Some(d) = &mut app.diag.clone() {
-                                            let mut d2 = d.clone();
-                                            d2.saved_path = Some(path);
-                                            app.diag = Some(d2);
-                                        }
-                                    }
-                                    Err(e) => app.last_error = Some(format!("Export failed: {e}")),
-                                }
-                            }
-                            continue;
-                        }
-                        KeyCode::Char('r') | KeyCode::Char('R') => {
-                            match fetch_diagnostics(Duration::from_secs(5)) {
-                                Ok(d) => app.diag = Some(d),
-                                Err(e) => app.last_error = Some(format!("Diagnostics failed: {e}")),
-                            }
-                            continue;
-                        }
-                        _ => {}
-                    }
-                }
-                handle_key(&mut app, key);
-            }
-        }
-        if app.should_quit { break; }
-    }
-    Ok(())
-}
-
-fn handle_key(app: &mut App, key: KeyEvent) {
-    // Ctrl+C / q always quits
-    if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) { app.should_quit = true; return; }
-    match key.code {
-        KeyCode::Char('q') => { app.should_quit = true; }
-        KeyCode::Char('?') => { app.show_help = !app.show_help; }
-        KeyCode::Char('t') => { app.theme.toggle(); }
-        KeyCode::Char('a') => { app.anim = !app.anim; }
-        KeyCode::Char('1') => app.page = Page::Readme,
-        KeyCode::Char('2') => app.page = Page::Configure,
-        KeyCode::Char('3') => app.page = Page::SelectDefault,
-        KeyCode::Char('4') => {
-            app.page = Page::Diagnostics;
-            if app.diag.is_none() {
-                match fetch_diagnostics(Duration::from_secs(5)) {
-                    Ok(d) => app.diag = Some(d),
-                    Err(e) => app.last_error = Some(format!("Diagnostics failed: {e}")),
-                }
-            }
-        }
-        KeyCode::Char('b') | KeyCode::Char('B') => app.page = Page::Build,
-        KeyCode::Char('s') | KeyCode::Char('S') => app.page = Page::Settings,
-        KeyCode::Esc => {
-            if app.show_help { app.show_help = false; }
-            else if app.page != Page::Welcome { app.page = Page::Welcome; }
-            else { app.should_quit = true; }
-        }
-        _ => {}
-    }
-
-    // Welcome-specific navigation
-    if app.page == Page::Welcome {
-        match key.code {
-            KeyCode::Up => { if app.menu_idx > 0 { app.menu_idx -= 1; } },
-            KeyCode::Down => { if app.menu_idx < WELCOME_ITEMS.len() - 1 { app.menu_idx += 1; } },
-            KeyCode::Enter => {
-                app.page = WELCOME_ITEMS[app.menu_idx].1;
-                if app.page == Page::Diagnostics && app.diag.is_none() {
-                    match fetch_diagnostics(Duration::from_secs(5)) {
-                        Ok(d) => app.diag = Some(d),
-                        Err(e) => app.last_error = Some(format!("Diagnostics failed: {e}")),
-                    }
-                }
-            }
-            _ => {}
-        }
-    }
-
-    // README keys
-    if app.page == Page::Readme {
-        if app.readme.is_none() {
-            app.readme = Some(load_readme());
-        }
-        if let Some(rm) = &mut app.readme {
-            // When TOC visible, allow Tab to switch focus and Up/Down to navigate TOC
-            match key.code {
-                KeyCode::Char('h') | KeyCode::Char('H') => {
-                    rm.show_toc = !rm.show_toc;
-                    if !rm.show_toc { rm.focus_toc = false; }
-                }
-                KeyCode::Tab => {
-                    if rm.show_toc { rm.focus_toc = !rm.focus_toc; }
-                }
-                KeyCode::BackTab => {
-                    if rm.show_toc { rm.focus_toc = !rm.focus_toc; }
-                }
-                KeyCode::Up => {
-                    if rm.show_toc && rm.focus_toc {
-                        if rm.toc_selected > 0 { rm.toc_selected -= 1; }
-                    } else {
-                        rm.scroll_up(1);
-                    }
-                }
-                KeyCode::Down => {
-                    if rm.show_toc && rm.focus_toc {
-                        if rm.toc_selected + 1 < rm.toc.len() { rm.toc_selected += 1; }
-                    } else {
-                        rm.scroll_down(1);
-                    }
-                }
-                KeyCode::PageUp => rm.scroll_up(8),
-                KeyCode::PageDown => rm.scroll_down(8),
-                KeyCode::Enter => {
-                    if rm.show_toc && rm.focus_toc {
-                        if let Some(entry) = rm.toc.get(rm.toc_selected) {
-                            rm.scroll = entry.line;
-                            rm.focus_toc = false; // jump to content focus
-                        }
-                    }
-                }
-                _ => {}
-            }
-        }
-    }
-
-    // Model Browser keys
-    if app.page == Page::ModelBrowser {
-        if app.model.is_none() {
-            match fetch_models(Duration::from_secs(5)) {
-                Ok(m) => app.model = Some(m),
-                Err(e) => app.last_error = Some(format!("Models failed: {e}")),
-            }
-        }
-        if let Some(m) = &mut app.model {
-            match key.code {
-                KeyCode::Up => m.move_up(),
-                KeyCode::Down => m.move_down(),
-                KeyCode::Char('r') | KeyCode::Char('R') => m.toggle_downloaded_only(),
-                KeyCode::Char('f') | KeyCode::Char('F') => m.cycle_tag(),
-                KeyCode::Char('i') | KeyCode::Char('I') => m.show_info = !m.show_info,
-                KeyCode::Enter => {
-                    if let Some(cur) = m.current_entry() { app.selected_model_id = Some(cur.id.clone()); }
-                    app.page = Page::Configure; // return to configure with selected model id
-                }
-                _ => {}
-            }
-        }
-    }
-
-    // Select Default provider keys
-    if app.page == Page::SelectDefault {
-        if app.defaultp.is_none() {
-            match load_providers_scratch() {
-                Ok(s) => app.defaultp = Some(s),
-                Err(e) => app.last_error = Some(format!("Load providers failed: {e}")),
-            }
-        }
-        if let Some(s) = &mut app.defaultp {
-            match key.code {
-                KeyCode::Up => { if !s.providers.is_empty() && s.selected > 0 { s.selected -= 1; } },
-                KeyCode::Down => { if !s.providers.is_empty() && s.selected + 1 < s.providers.len() { s.selected += 1; } },
-                KeyCode::Enter | KeyCode::Char('s') | KeyCode::Char('S') => {
-                    if let Some(p) = s.providers.get(s.selected) {
-                        s.current_default_id = Some(p.id.clone());
-                        if let Err(e) = save_default_provider(&p.id) {
-                            app.last_error = Some(format!("Save default failed: {e}"));
-                        }
-                    }
-                }
-                _ => {}
-            }
-        }
-    }
-
-    // Configure Providers keys
-    if app.page == Page::Configure {
-        if app.providers.is_none() {
-            app.providers = Some(match load_providers_state() {
-                Ok(s) => s,
-                Err(e) => { app.last_error = Some(format!("Load providers failed: {e}")); ProvidersState::empty() }
-            });
-        }
-        if let Some(st) = &mut app.providers {
-            // Dropdown handling (e.g., type selector)
-            if let Some(dd) = &mut st.dropdown {
-                match key.code {
-                    KeyCode::Up => { if dd.selected > 0 { dd.selected -= 1; } }
-                    KeyCode::Down => { if dd.selected + 1 < dd.items.len() { dd.selected += 1; } }
-                    KeyCode::Enter => {
-                        if dd.selected < dd.items.len() {
-                            let chosen = dd.items[dd.selected].clone();
-                            match dd.target_field {
-                                None => {
-                                    if st.selected < st.entries.len() {
-                                        st.entries[st.selected].ptype = chosen.clone();
-                                        ensure_form_for_selected(st);
-                                        if let Some(form) = &mut st.form {
-                                            form.selected = 1.min(form.fields.len());
-                                            form.editing = false;
-                                            form.message = Some("Type changed".to_string());
-                                        }
-                                    }
-                                }
-                                Some(fi) => {
-                                    if let Some(form) = &mut st.form {
-                                        if fi < form.fields.len() {
-                                            form.fields[fi].buffer = chosen.clone();
-                                            form.editing = false;
-                                            form.message = Some(format!("{} set", form.fields[fi].schema.name));
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                        st.dropdown = None;
-        			return;
-                    }
-                    KeyCode::Esc => { st.dropdown = None; return; }
-                    _ => { return; }
-                }
-                return;
-            }
-            // Pane focus shortcuts: Tab cycles focus between panes; Shift+Tab cycles backward
-            match key.code {
-                KeyCode::Tab => {
-                    if !st.focus_right {
-                        if st.is_add_row() { st.add_default(); }
-                        if st.selected < st.entries.len() { ensure_form_for_selected(st); }
-                        st.focus_right = true;
-                    } else {
-                        st.focus_right = false;
-                    }
-                },
-                KeyCode::BackTab => {
-                    if st.focus_right {
-                        st.focus_right = false;
-                    } else {
-                        if st.is_add_row() { st.add_default(); }
-                        if st.selected < st.entries.len() { ensure_form_for_selected(st); }
-                        st.focus_right = true;
-                    }
-                },
-                _ => {}
-            }
-            if st.focus_right {
-                // Right pane: inline form
-                if st.form.is_none() && st.selected < st.entries.len() { ensure_form_for_selected(st); }
-                if let Some(form) = &mut st.form {
-                    match key.code {
-                        KeyCode::Esc => { if form.editing { form.editing = false; } else { st.focus_right = false; } }
-                        // Up/Down navigate between form groups. Treat [Test|Save|Cancel] as one group.
-                        KeyCode::Up => {
-                            let fields_len = form.fields.len();
-                            let test_idx = fields_len + 1;
-                            let save_idx = fields_len + 2;
-                            let cancel_idx = fields_len + 3;
-                            if form.selected == test_idx || form.selected == save_idx || form.selected == cancel_idx {
-                                // Jump to last field (or Type if no fields)
-                                form.selected = if fields_len > 0 { fields_len } else { 0 };
-                            } else if form.selected > 0 {
-                                form.selected -= 1;
-                            }
-                        }
-                        KeyCode::Down => {
-                            let fields_len = form.fields.len();
-                            let test_idx = fields_len + 1;
-                            let save_idx = fields_len + 2;
-                            let cancel_idx = fields_len + 3;
-                            let total = fields_len + 4;
-                            if form.selected == test_idx || form.selected == save_idx || form.selected == cancel_idx {
-                                // Already in the last group; stay within group on Down
-                            } else if form.selected + 1 < total {
-                                form.selected += 1;
-                            }
-                        }
-                        KeyCode::Enter => {
-                            // If on Type row: open dropdown
-                            if form.selected == 0 {
-                                let current = st.entries.get(st.selected).map(|e| e.ptype.clone()).unwrap_or_default();
-                                let idx = st.schema_types.iter().position(|t| *t == current).unwrap_or(0);
-                                st.dropdown = Some(DropdownState { items: st.schema_types.clone(), selected: idx, title: "Select Provider Type".to_string(), target_field: None });
-                                return;
-                            }
-                            // If on Test/Save/Cancel buttons, act; else toggle edit
-                            let test_idx = form.fields.len() + 1;
-                            let save_idx = form.fields.len() + 2;
-                            let cancel_idx = form.fields.len() + 3;
-                            let total = form.fields.len() + 4;
-                            if form.selected == test_idx {
-                                // Run test: use CLI where applicable
-                                let mut status = String::new();
-                                let mut ptype_cur = String::new();
-                                if st.selected < st.entries.len() {
-                                    let entry = &st.entries[st.selected];
-                                    ptype_cur = entry.ptype.clone();
-                                    match probe_provider(entry) {
-                                        Ok(msg) => { status = msg; },
-                                        Err(e) => { status = format!("Error: {}", e); },
-                                    }
-                                }
-                                let cur_hash = providers::compute_form_hash(&form.fields);
-                                let low = status.to_lowercase();
-                                if (ptype_cur == "lmstudio" || ptype_cur == "ollama" || ptype_cur == "openai") && !low.starts_with("error") && !low.contains("http ") {
-                                    form.last_test_ok_hash = Some(cur_hash);
-                                } else {
-                                    form.last_test_ok_hash = None;
-                                }
-                                form.message = Some(status);
-                            } else if form.selected == save_idx {
-                                let mut missing: Vec<String> = Vec::new();
-                                for ff in &form.fields { if ff.schema.required && ff.buffer.trim().is_empty() { missing.push(ff.schema.name.clone()); } }
-                                if !missing.is_empty() {
-                                    form.message = Some(format!("Missing required: {}", missing.join(", ")));
-                                } else {
-                                    // Enforce: if dirty and not tested ok, prevent save
-                                    let cur_hash = providers::compute_form_hash(&form.fields);
-                                    let dirty = cur_hash != form.initial_hash;
-                                    let tested_ok = form.last_test_ok_hash.as_ref().map_or(false, |h| *h == cur_hash);
-                                    if dirty && !tested_ok {
-                                        form.message = Some("Run Test connection first".to_string());
-                                        return;
-                                    }
-                                    if st.selected < st.entries.len() {
-                                        if let Some(obj) = st.entries[st.selected].config.as_object_mut() {
-                                            for ff in &form.fields {
-                                                let key2 = ff.schema.name.clone();
-                                                if ff.schema.ftype == "int" {
-                                                    if let Ok(n) = ff.buffer.parse::<i64>() { obj.insert(key2, Value::Number(n.into())); } else { obj.insert(key2, Value::String(ff.buffer.clone())); }
-                                                } else {
-                                                    obj.insert(key2, Value::String(ff.buffer.clone()));
-                                                }
-                                            }
-                                        }
-                                    }
-                                    form.message = Some("Saved".to_string());
-                                    // Update baseline hash after save
-                                    form.initial_hash = cur_hash;
-                                    form.last_test_ok_hash = Some(form.initial_hash.clone());
-                                }
-                            } else if form.selected == cancel_idx { // Cancel
-                                form.editing = false;
-                                st.focus_right = false;
-                            } else {
-                                // If field has options, open dropdown, else toggle edit
-                                let fi = form.selected - 1; // map to fields index
-                                if let Some(ff) = form.fields.get(fi) {
-                                    // Special-case: dynamic model list for lmstudio/ollama using CLI
-                                    let ptype = st.entries.get(st.selected).map(|e| e.ptype.clone()).unwrap_or_default();
-                                    if ff.schema.name == "model" && (ptype == "lmstudio" || ptype == "ollama") {
-                                        // Use CLI discover-models
-                                        let host = form.fields.iter().find(|f| f.schema.name == "host").map(|f| f.buffer.clone()).unwrap_or_else(|| "localhost".to_string());
-                                        let port = form.fields.iter().find(|f| f.schema.name == "port").map(|f| f.buffer.clone()).unwrap_or_default();
-                                        let mut args = vec!["providers", "discover-models", "--type", &ptype, "--host", &host, "--json"];
-                                        if !port.is_empty() { args.push("--port"); args.push(&port); }
-                                        match util::run_cli_json(&args, Duration::from_secs(5)) {
-                                            Ok(v) => {
-                                                let mut items: Vec<String> = Vec::new();
-                                                if let Some(arr) = v.get("models").and_then(|x| x.as_array()) {
-                                                    for it in arr { if let Some(id) = it.get("id").and_then(|x| x.as_str()) { items.push(id.to_string()); } }
-                                                }
-                                                if items.is_empty() {
-                                                    form.message = Some(format!("No models discovered for {}", ptype));
-                                                } else {
-                                                    let sel = items.iter().position(|x| *x == ff.buffer).unwrap_or(0);
-                                                    st.dropdown = Some(DropdownState { items, selected: sel, title: format!("Select model ({}):", ptype), target_field: Some(fi) });
-                                                    return;
-                                                }
-                                            }
-                                            Err(e) => { form.message = Some(format!("Discover failed: {}", e)); }
-                                        }
-                                    } else if let Some(opts) = &ff.schema.options {
-                                        let mut items = opts.clone();
-                                        let current_val = ff.buffer.clone();
-                                        let mut sel = 0usize;
-                                        if let Some(i) = items.iter().position(|x| *x == current_val) { sel = i; }
-                                        st.dropdown = Some(DropdownState { items, selected: sel, title: format!("Select {}", ff.schema.name), target_field: Some(fi) });
-                                        return;
-                                    }
-                                }
-                                form.editing = !form.editing;
-                            }
-                        }
-                        // Left/Right: within button group, switch between Test/Save/Cancel. In fields, move cursor when editing.
-                        KeyCode::Left => {
-                            let fields_len = form.fields.len();
-                            let test_idx = fields_len + 1;
-                            let save_idx = fields_len + 2;
-                            let cancel_idx = fields_len + 3;
-                            if form.selected > test_idx {
-                                form.selected -= 1;
-                            } else if form.editing {
-                                if let Some(ff) = form.fields.get_mut(form.selected) {
-                                    if ff.cursor > 0 { ff.cursor -= 1; }
-                                }
-                            }
-                        }
-                        KeyCode::Right => {
-                            let fields_len = form.fields.len();
-                            let test_idx = fields_len + 1;
-                            let save_idx = fields_len + 2;
-                            let cancel_idx = fields_len + 3;
-                            if form.selected >= test_idx && form.selected < cancel_idx {
-                                form.selected += 1;
-                            } else if form.editing {
-                                if let Some(ff) = form.fields.get_mut(form.selected) {
-                                    if ff.cursor < ff.buffer.chars().count() { ff.cursor += 1; }
-                                }
-                            }
-                        }
-                        KeyCode::Home => { if form.editing { if let Some(ff) = form.fields.get_mut(form.selected) { ff.cursor = 0; } } }
-                        KeyCode::End => { if form.editing { if let Some(ff) = form.fields.get_mut(form.selected) { ff.cursor = ff.buffer.chars().count(); } } }
-                        KeyCode::Backspace => { if form.editing { if let Some(ff) = form.fields.get_mut(form.selected) { if ff.cursor > 0 { let mut s = ff.buffer.clone(); let idx = s.char_indices().nth(ff.cursor-1).map(|(i, _)| i).unwrap_or(0); let idx2 = s.char_indices().nth(ff.cursor).map(|(i, _)| i).unwrap_or(s.len()); s.replace_range(idx..idx2, ""); ff.buffer = s; ff.cursor -= 1; form.last_test_ok_hash = None; } } } }
-                        KeyCode::Delete => { if form.editing { if let Some(ff) = form.fields.get_mut(form.selected) { let len = ff.buffer.chars().count(); if ff.cursor < len { let mut s = ff.buffer.clone(); let idx = s.char_indices().nth(ff.cursor).map(|(i, _)| i).unwrap_or(s.len()); let idx2 = s.char_indices().nth(ff.cursor+1).map(|(i, _)| i).unwrap_or(s.len()); s.replace_range(idx..idx2, ""); ff.buffer = s; form.last_test_ok_hash = None; } } } }
-                        KeyCode::Tab => { let total = form.fields.len() + 4; form.selected = (form.selected + 1) % total; }
-                        KeyCode::BackTab => { let total = form.fields.len() + 4; form.selected = if form.selected == 0 { total - 1 } else { form.selected - 1 }; }
-                        _ => {}
-                    }
-                    if let KeyCode::Char(c) = key.code {
-                        if form.editing {
-                            if let Some(ff) = form.fields.get_mut(form.selected) {
-                                let mut s = ff.buffer.clone();
-                                let idx = s.char_indices().nth(ff.cursor).map(|(i, _)| i).unwrap_or(s.len());
-                                s.insert(idx, c);
-                                ff.buffer = s;
-                                ff.cursor += 1;
-                                form.last_test_ok_hash = None;
-                            }
-                        }
-                    }
-                }
-                return;
-            }
-
-            // Left pane: list navigation and actions
-            match key.code {
-                KeyCode::Up => { if st.selected > 0 { st.selected -= 1; st.form = None; } },
-                KeyCode::Down => { if st.selected + 1 < st.len_with_add() { st.selected += 1; st.form = None; } },
-                KeyCode::Enter => {
-                    if st.is_add_row() {
-                        st.add_default();
-                        ensure_form_for_selected(st);
-                        st.focus_right = true;
-                    } else {
-                        ensure_form_for_selected(st);
-                        st.focus_right = true;
-                    }
-                }
-                KeyCode::Char('a') | KeyCode::Char('A') => { st.add_default(); ensure_form_for_selected(st); st.focus_right = true; }
-                KeyCode::Char('d') | KeyCode::Char('D') => { st.delete_selected(); st.form = None; }
-                KeyCode::Char('m') | KeyCode::Char('M') => { app.page = Page::ModelBrowser; }
-                KeyCode::Char('t') | KeyCode::Char('T') => {
-                    if st.selected < st.entries.len() {
-                        match probe_provider(&st.entries[st.selected]) {
-                            Ok(msg) => st.test_status = Some(msg),
-                            Err(e) => st.test_status = Some(format!("Error: {}", e)),
-                        }
-                    }
-                }
-                // Save from left pane
-                KeyCode::Char('s') | KeyCode::Char('S') => { if let Err(e) = st.save() { app.last_error = Some(format!("Save failed: {e}")); } }
-                _ => {}
-            }
-            // If a model was picked in model browser, apply to selected provider
-            if let Some(model_id) = app.selected_model_id.take() {
-                st.apply_model_to_selected(&model_id);
-            }
-        }
-    }
-
-    // Build/Write Configuration keys
-    if app.page == Page::Build {
-        if app.build.is_none() {
-            app.build = Some(BuildState::default());
-        }
-        if let Some(st) = &mut app.build {
-            match key.code {
-                KeyCode::Char('g') | KeyCode::Char('G') => { st.toggle_target(); }
-                KeyCode::Enter => {
-                    match write_active_config(st.target) {
-                        Ok(path) => st.status = Some(format!("Written: {}", path)),
-                        Err(e) => st.status = Some(format!("Error: {}", e)),
-                    }
-                }
-                _ => {}
-            }
+/// Print a non-interactive result as JSON; failures exit with code 1.
+fn report(res: Result<serde_json::Value>) {
+    match res {
+        Ok(out) => println!("{}", out),
+        Err(e) => {
+            println!("{}", serde_json::json!({ "ok": false, "error": e.to_string() }));
+            std::process::exit(1);
         }
     }
 }
-
-fn ui(f: &mut Frame, app: &App) {
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(6), // header with animation space
-            Constraint::Min(3),
-            Constraint::Length(1), // footer
-        ]).split(f.size());
-
-    draw_header(f, chunks[0], app);
-    match app.page {
-        Page::Welcome => draw_welcome(f, chunks[1], app),
-        Page::Readme => draw_readme(f, chunks[1], app),
-        Page::Configure => draw_providers_catalog(f, chunks[1], app),
-        Page::SelectDefault => draw_select_default(f, chunks[1], app),
-        Page::ModelBrowser => draw_model_browser(f, chunks[1], app),
-        Page::Diagnostics => draw_diagnostics(f, chunks[1], app),
-        Page::Build => draw_build_config(f, chunks[1], app),
-        Page::Settings => draw_stub(f, chunks[1], app, "Settings (stub) — t/a toggles"),
-    }
-    draw_footer(f, chunks[2], app);
-
-    if app.show_help { draw_help_overlay(f, app); }
-}
-
-fn draw_header(f: &mut Frame, area: Rect, app: &App) {
-    let title = neon_gradient_line(" chi_llm — micro‑LLM • TUI vNext ", &app.theme);
-    let sub = Line::from(vec![
-        Span::styled("  retro/synthwave • arrows + enter • ? help ", Style::default().fg(app.theme.secondary)),
-    ]);
-    let block = Block::default()
-        .borders(Borders::BOTTOM)
-        .border_style(Style::default().fg(app.theme.frame))
-        .title(Span::styled("CHI_TUI", Style::default().fg(app.theme.primary).add_modifier(Modifier::BOLD)))
-        .title_alignment(Alignment::Center);
-    let v = vec![title, sub];
-    let p = Paragraph::new(v)
-        .style(Style::default().bg(app.theme.bg).fg(app.theme.fg))
-        .block(block)
-        .alignment(Alignment::Center)
-        .wrap(Wrap { trim: true });
-    f.render_widget(p, area);
-}
-
-fn draw_footer(f: &mut Frame, area: Rect, app: &App) {
-    let msg_text = match app.page {
-        Page::Diagnostics => "Esc: back • q: quit • e: export • r: refresh • ?: help",
-        Page::Readme => "Up/Down scroll • PgUp/PgDn • h TOC • Tab switch TOC/Content • Enter jump • Esc back",
-        Page::ModelBrowser => "Up/Down select • Enter choose • r downloaded-only • f tag filter • i info • Esc back",
-        Page::Configure => "Tab/Shift+Tab switch • ↑/↓ field • Enter edit/Test/Save/Cancel • ←/→/Home/End • Del/Backspace • Esc back",
-        Page::Build => "g toggle target • Enter write • Esc back",
-        Page::SelectDefault => "Up/Down select • Enter set default • Esc back",
-        _ => "Esc: back • q: quit • 1/2/3/4/b/s: sections • ?: help",
-    };
-    let msg = Line::from(Span::styled(msg_text, Style::default().fg(app.theme.secondary)));
-    let p = Paragraph::new(msg)
-        .style(Style::default().bg(app.theme.bg).fg(app.theme.fg))
-        .block(Block::default())
-        .alignment(Alignment::Center);
-    f.render_widget(p, area);
-}
-
-fn draw_welcome(f: &mut Frame, area: Rect, app: &App) {
-    let items: Vec<ListItem> = WELCOME_ITEMS.iter().enumerate().map(|(i, (label, _))| {
-        let style = if i == app.menu_idx { Style::default().fg(app.theme.selected).add_modifier(Modifier::BOLD) } else { Style::default().fg(app.theme.fg) };
-        ListItem::new(Line::from(Span::styled(format!("{} {}", if i == app.menu_idx {"›"} else {" "}, label), style)))
-    }).collect();
-    let list = List::new(items)
-        .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(app.theme.frame)).title("Welcome"))
-        .highlight_style(Style::default().fg(app.theme.selected));
-    f.render_widget(list, area);
-}
-
-fn draw_stub(f: &mut Frame, area: Rect, app: &App, text: &str) {
-    let p = Paragraph::new(text)
-        .style(Style::default().bg(app.theme.bg).fg(app.theme.fg))
-        .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(app.theme.frame)))
-        .alignment(Alignment::Center)
-        .wrap(Wrap { trim: true });
-    f.render_widget(p, area);
-}
-
-fn draw_help_overlay(f: &mut Frame, app: &App) {
-    let area = centered_rect(70, 60, f.size());
-    let lines = vec![
-        Line::from(Span::styled("Global keys:", Style::default().fg(app.theme.primary).add_modifier(Modifier::BOLD))),
-        Line::from("Up/Down: navigate • Enter: select • Esc: back • q/Ctrl+C: quit"),
-        Line::from("1: README • 2: Configure • 3: Select Default • 4: Diagnostics • b: Build • s: Settings"),
-        Line::from("?: help overlay • t: theme • a: animation"),
-        Line::from("Diagnostics: e export • r refresh"),
-        Line::from("Model Browser: r downloaded-only • f cycle tag • i info"),
-        Line::from("Configure: Tab/Shift+Tab • ↑/↓ field • Enter edit/Test/Save/Cancel • ←/→/Home/End • Del/Backspace"),
-        Line::from("README: Up/Down/PgUp/PgDn scroll • h TOC • Tab switch TOC/Content • Enter jump"),
-        Line::from("Build: g toggle Project/Global • Enter write"),
-        Line::from("Welcome: Up/Down + Enter to open a section"),
-        Line::from("—").style(Style::default().fg(app.theme.frame)),
-        Line::from("This is a scaffold. Pages will be implemented in tasks 003–009."),
-    ];
-    let block = Block::default().title("Help").borders(Borders::ALL).border_style(Style::default().fg(app.theme.frame));
-    let content = Paragraph::new(lines).style(Style::default().bg(app.theme.bg).fg(app.theme.fg)).alignment(Alignment::Left).wrap(Wrap { trim: true }).block(block);
-    f.render_widget(Clear, area);
-    f.render_widget(content, area);
-}
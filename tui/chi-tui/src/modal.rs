@@ -0,0 +1,585 @@
+use std::collections::HashSet;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::layout::Rect;
+use ratatui::prelude::Frame;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, List, ListItem, Paragraph, Wrap};
+
+use crate::a11y::page_name;
+use crate::app::App;
+use crate::controller::controller;
+use crate::keymap::help_line;
+use crate::text::{grapheme_byte, grapheme_len, single_line};
+use crate::util::centered_rect;
+
+/// Whether a modal stays open after handling a key.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ModalResult {
+    Keep,
+    Close,
+}
+
+/// An overlay on `App::modals`. Only the topmost modal receives keys; Esc always
+/// closes it before it sees the key. Modals are drawn bottom to top over the page.
+pub trait Modal {
+    fn handle_key(&mut self, app: &mut App, key: KeyEvent) -> ModalResult;
+    fn draw(&self, f: &mut Frame, area: Rect, app: &App);
+
+    /// Name announced in accessibility mode when the modal opens or closes.
+    fn label(&self) -> String {
+        "Popup".to_string()
+    }
+
+    /// Insert a bracketed paste in one go; false for popups without a text input.
+    fn handle_paste(&mut self, _app: &mut App, _text: &str) -> bool {
+        false
+    }
+}
+
+type SelectFn = Box<dyn FnOnce(&mut App, String)>;
+type ConfirmFn = Box<dyn FnOnce(&mut App)>;
+
+/// Pick one item from a list; `on_select` receives the chosen item. With
+/// `remember`, recently chosen items (`App::recent`) are listed again in a
+/// "Recent" group above the full list. With `mark`, a subset (e.g. downloaded
+/// models) is sorted first, badged, and `r` toggles showing only that subset.
+pub struct DropdownModal {
+    pub title: String,
+    pub items: Vec<String>,
+    /// Row under the cursor; rows are the recent group followed by the shown items.
+    selected: usize,
+    /// Item to put the cursor on once the rows are known (before the first key).
+    preselect: Option<String>,
+    recent_key: Option<String>,
+    badge: String,
+    marked: HashSet<String>,
+    marked_only: bool,
+    on_select: Option<SelectFn>,
+}
+
+impl DropdownModal {
+    /// Dropdown preselecting `current` when it is one of `items`.
+    pub fn new(title: impl Into<String>, items: Vec<String>, current: &str, on_select: impl FnOnce(&mut App, String) + 'static) -> Self {
+        Self {
+            title: title.into(),
+            items,
+            selected: 0,
+            preselect: Some(current.to_string()),
+            recent_key: None,
+            badge: String::new(),
+            marked: HashSet::new(),
+            marked_only: false,
+            on_select: Some(Box::new(on_select)),
+        }
+    }
+
+    /// Show and record recent choices under `key` in `App::recent`.
+    pub fn remember(mut self, key: impl Into<String>) -> Self {
+        self.recent_key = Some(key.into());
+        self
+    }
+
+    /// List `marked` items first (keeping their order) with a `[badge]` suffix.
+    pub fn mark(mut self, badge: impl Into<String>, marked: HashSet<String>) -> Self {
+        self.items.sort_by_key(|it| !marked.contains(it));
+        self.badge = badge.into();
+        self.marked = marked;
+        self
+    }
+
+    /// Items after the `r` filter.
+    fn shown(&self) -> Vec<&str> {
+        self.items.iter().filter(|it| !self.marked_only || self.marked.contains(*it)).map(String::as_str).collect()
+    }
+
+    /// Recent choices that are still shown, most recent first.
+    fn recent<'a>(&'a self, app: &'a App, shown: &[&str]) -> Vec<&'a str> {
+        let Some(key) = &self.recent_key else { return Vec::new() };
+        app.recent.get(key).iter().map(String::as_str).filter(|v| shown.contains(v)).collect()
+    }
+
+    /// Cursor row; the preselected item is found in the recent group first.
+    fn cursor(&self, recent: &[&str], shown: &[&str]) -> usize {
+        let Some(current) = &self.preselect else { return self.selected };
+        recent.iter().position(|v| v == current)
+            .or_else(|| shown.iter().position(|v| v == current).map(|i| recent.len() + i))
+            .unwrap_or(0)
+    }
+}
+
+impl Modal for DropdownModal {
+    fn label(&self) -> String {
+        self.title.clone()
+    }
+
+    fn handle_key(&mut self, app: &mut App, key: KeyEvent) -> ModalResult {
+        let shown = self.shown();
+        let recent = self.recent(app, &shown);
+        let cursor = self.cursor(&recent, &shown);
+        let rows: Vec<String> = recent.iter().chain(shown.iter()).map(|s| s.to_string()).collect();
+        self.selected = cursor;
+        self.preselect = None;
+        match key.code {
+            KeyCode::Up if self.selected > 0 => self.selected -= 1,
+            KeyCode::Down if self.selected + 1 < rows.len() => self.selected += 1,
+            KeyCode::Char('r') if !self.marked.is_empty() => {
+                // Keep the cursor on the same item when it is still shown
+                self.marked_only = !self.marked_only;
+                self.preselect = rows.get(self.selected).cloned();
+            }
+            KeyCode::Enter => {
+                if let (Some(item), Some(on_select)) = (rows.get(self.selected).cloned(), self.on_select.take()) {
+                    if let Some(key) = &self.recent_key {
+                        app.recent.record(key, &item);
+                        if let Err(e) = app.recent.save() { app.report_error(format!("Saving recent choices failed: {e}")); }
+                    }
+                    on_select(app, item);
+                }
+                return ModalResult::Close;
+            }
+            _ => {}
+        }
+        ModalResult::Keep
+    }
+
+    fn draw(&self, f: &mut Frame, area: Rect, app: &App) {
+        let area_pop = centered_rect(50, 60, area);
+        let shown = self.shown();
+        let recent = self.recent(app, &shown);
+        let cursor = self.cursor(&recent, &shown);
+        let row = |i: usize, it: &str| {
+            let mut label = it.to_string();
+            if self.marked.contains(it) { label.push_str(&format!("  [{}]", self.badge)); }
+            ListItem::new(Line::from(Span::styled(label, app.theme.row(i == cursor))))
+        };
+        let mut items: Vec<ListItem> = Vec::new();
+        if !recent.is_empty() {
+            items.push(ListItem::new(Line::from(Span::styled("Recent", app.theme.hint()))));
+            items.extend(recent.iter().enumerate().map(|(i, it)| row(i, it)));
+            items.push(ListItem::new(Line::from(Span::styled("All", app.theme.hint()))));
+        }
+        items.extend(shown.iter().enumerate().map(|(i, it)| row(recent.len() + i, it)));
+        let mut title = self.title.clone();
+        if !self.marked.is_empty() {
+            let hint = if self.marked_only { format!(" • {} only (r: all)", self.badge) } else { format!(" • r: {} only", self.badge) };
+            title.push_str(&hint);
+        }
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL).border_style(app.theme.focused_border()).title(title))
+            .highlight_style(app.theme.highlight());
+        f.render_widget(Clear, area_pop);
+        f.render_widget(list, area_pop);
+    }
+}
+
+/// Yes/no question; `on_confirm` runs on `y` or Enter.
+pub struct ConfirmModal {
+    pub title: String,
+    pub message: String,
+    on_confirm: Option<ConfirmFn>,
+}
+
+impl ConfirmModal {
+    pub fn new(title: impl Into<String>, message: impl Into<String>, on_confirm: impl FnOnce(&mut App) + 'static) -> Self {
+        Self { title: title.into(), message: message.into(), on_confirm: Some(Box::new(on_confirm)) }
+    }
+}
+
+impl Modal for ConfirmModal {
+    fn label(&self) -> String {
+        self.title.clone()
+    }
+
+    fn handle_key(&mut self, app: &mut App, key: KeyEvent) -> ModalResult {
+        match key.code {
+            KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter => {
+                if let Some(on_confirm) = self.on_confirm.take() { on_confirm(app); }
+                ModalResult::Close
+            }
+            KeyCode::Char('n') | KeyCode::Char('N') => ModalResult::Close,
+            _ => ModalResult::Keep,
+        }
+    }
+
+    fn draw(&self, f: &mut Frame, area: Rect, app: &App) {
+        let area_pop = centered_rect(50, 25, area);
+        let lines = vec![
+            Line::from(self.message.as_str()),
+            Line::from(""),
+            Line::from(Span::styled("y/Enter: yes • n/Esc: no", app.theme.hint())),
+        ];
+        let p = Paragraph::new(lines)
+            .style(app.theme.base())
+            .block(Block::default().borders(Borders::ALL).border_style(app.theme.focused_border()).title(self.title.clone()))
+            .wrap(Wrap { trim: true });
+        f.render_widget(Clear, area_pop);
+        f.render_widget(p, area_pop);
+    }
+}
+
+/// A message with one-key actions, e.g. `g` add to .gitignore / `w` write anyway.
+/// Each action runs its callback and closes the modal.
+pub struct ActionModal {
+    pub title: String,
+    pub message: String,
+    actions: Vec<(char, String, Option<ConfirmFn>)>,
+}
+
+impl ActionModal {
+    pub fn new(title: impl Into<String>, message: impl Into<String>) -> Self {
+        Self { title: title.into(), message: message.into(), actions: Vec::new() }
+    }
+
+    pub fn action(mut self, key: char, label: impl Into<String>, run: impl FnOnce(&mut App) + 'static) -> Self {
+        self.actions.push((key, label.into(), Some(Box::new(run))));
+        self
+    }
+}
+
+impl Modal for ActionModal {
+    fn label(&self) -> String {
+        self.title.clone()
+    }
+
+    fn handle_key(&mut self, app: &mut App, key: KeyEvent) -> ModalResult {
+        let KeyCode::Char(c) = key.code else { return ModalResult::Keep };
+        let Some((_, _, run)) = self.actions.iter_mut().find(|(k, _, _)| k.eq_ignore_ascii_case(&c)) else {
+            return ModalResult::Keep;
+        };
+        if let Some(run) = run.take() { run(app); }
+        ModalResult::Close
+    }
+
+    fn draw(&self, f: &mut Frame, area: Rect, app: &App) {
+        let area_pop = centered_rect(60, 35, area);
+        let mut lines: Vec<Line> = self.message.lines().map(|l| Line::from(l.to_string())).collect();
+        lines.push(Line::from(""));
+        for (k, label, _) in &self.actions {
+            lines.push(Line::from(vec![Span::styled(format!("{}: ", k), app.theme.title()), Span::raw(label.clone())]));
+        }
+        lines.push(Line::from(Span::styled("Esc: cancel", app.theme.hint())));
+        let p = Paragraph::new(lines)
+            .style(app.theme.base())
+            .block(Block::default().borders(Borders::ALL).border_style(app.theme.focused_border()).title(self.title.clone()))
+            .wrap(Wrap { trim: true });
+        f.render_widget(Clear, area_pop);
+        f.render_widget(p, area_pop);
+    }
+}
+
+type SubmitFn = Box<dyn FnOnce(&mut App, String)>;
+
+/// Single-line text input; `on_submit` receives the trimmed text on Enter.
+pub struct PromptModal {
+    pub title: String,
+    pub buffer: String,
+    allow_empty: bool,
+    on_submit: Option<SubmitFn>,
+}
+
+impl PromptModal {
+    pub fn new(title: impl Into<String>, on_submit: impl FnOnce(&mut App, String) + 'static) -> Self {
+        Self { title: title.into(), buffer: String::new(), allow_empty: false, on_submit: Some(Box::new(on_submit)) }
+    }
+
+    /// Let Enter submit a blank buffer as `""`.
+    pub fn allow_empty(mut self) -> Self {
+        self.allow_empty = true;
+        self
+    }
+}
+
+impl Modal for PromptModal {
+    fn label(&self) -> String {
+        self.title.clone()
+    }
+
+    fn handle_key(&mut self, app: &mut App, key: KeyEvent) -> ModalResult {
+        match key.code {
+            KeyCode::Enter if self.allow_empty || !self.buffer.trim().is_empty() => {
+                if let Some(on_submit) = self.on_submit.take() { on_submit(app, self.buffer.trim().to_string()); }
+                return ModalResult::Close;
+            }
+            KeyCode::Backspace => { self.buffer.truncate(grapheme_byte(&self.buffer, grapheme_len(&self.buffer).saturating_sub(1))); }
+            KeyCode::Char(c) => self.buffer.push(c),
+            _ => {}
+        }
+        ModalResult::Keep
+    }
+
+    fn handle_paste(&mut self, _app: &mut App, text: &str) -> bool {
+        self.buffer.push_str(&single_line(text));
+        true
+    }
+
+    fn draw(&self, f: &mut Frame, area: Rect, app: &App) {
+        let area_pop = centered_rect(50, 20, area);
+        let lines = vec![
+            Line::from(format!("{}▌", self.buffer)),
+            Line::from(""),
+            Line::from(Span::styled("Enter: ok • Esc: cancel", app.theme.hint())),
+        ];
+        let p = Paragraph::new(lines)
+            .style(app.theme.base())
+            .block(Block::default().borders(Borders::ALL).border_style(app.theme.focused_border()).title(self.title.clone()));
+        f.render_widget(Clear, area_pop);
+        f.render_widget(p, area_pop);
+    }
+}
+
+type SaveFn = Box<dyn FnMut(&mut App, &str) -> Result<(), String>>;
+
+/// Multi-line text editor. Ctrl+S passes the text to `on_save`; an error keeps
+/// the editor open and is shown under the text.
+pub struct TextEditorModal {
+    pub title: String,
+    pub lines: Vec<String>,
+    /// Cursor line and grapheme column.
+    pub row: usize,
+    pub col: usize,
+    pub error: Option<String>,
+    on_save: SaveFn,
+}
+
+impl TextEditorModal {
+    pub fn new(title: impl Into<String>, text: &str, on_save: impl FnMut(&mut App, &str) -> Result<(), String> + 'static) -> Self {
+        let mut lines: Vec<String> = text.lines().map(|l| l.to_string()).collect();
+        if lines.is_empty() { lines.push(String::new()); }
+        Self { title: title.into(), lines, row: 0, col: 0, error: None, on_save: Box::new(on_save) }
+    }
+
+    pub fn text(&self) -> String {
+        self.lines.join("\n")
+    }
+
+    fn line_len(&self) -> usize {
+        grapheme_len(&self.lines[self.row])
+    }
+
+    /// Insert `text` at the cursor, line breaks included; the cursor ends up
+    /// after it.
+    pub fn insert_text(&mut self, text: &str) {
+        let text = text.replace("\r\n", "\n").replace('\r', "\n");
+        let idx = self.byte_idx(self.col);
+        let rest = self.lines[self.row].split_off(idx);
+        let mut pasted = text.split('\n');
+        if let Some(first) = pasted.next() { self.lines[self.row].push_str(first); }
+        for line in pasted {
+            self.row += 1;
+            self.lines.insert(self.row, line.to_string());
+        }
+        self.col = self.line_len();
+        self.lines[self.row].push_str(&rest);
+    }
+
+    fn byte_idx(&self, col: usize) -> usize {
+        grapheme_byte(&self.lines[self.row], col)
+    }
+}
+
+impl Modal for TextEditorModal {
+    fn label(&self) -> String {
+        self.title.clone()
+    }
+
+    fn handle_key(&mut self, app: &mut App, key: KeyEvent) -> ModalResult {
+        if key.modifiers.contains(KeyModifiers::CONTROL) {
+            if key.code == KeyCode::Char('s') {
+                let text = self.text();
+                return match (self.on_save)(app, &text) {
+                    Ok(()) => ModalResult::Close,
+                    Err(e) => { self.error = Some(e); ModalResult::Keep }
+                };
+            }
+            return ModalResult::Keep;
+        }
+        match key.code {
+            KeyCode::Up if self.row > 0 => { self.row -= 1; self.col = self.col.min(self.line_len()); }
+            KeyCode::Down if self.row + 1 < self.lines.len() => { self.row += 1; self.col = self.col.min(self.line_len()); }
+            KeyCode::Left if self.col > 0 => self.col -= 1,
+            KeyCode::Left if self.row > 0 => { self.row -= 1; self.col = self.line_len(); }
+            KeyCode::Right if self.col < self.line_len() => self.col += 1,
+            KeyCode::Right if self.row + 1 < self.lines.len() => { self.row += 1; self.col = 0; }
+            KeyCode::Home => self.col = 0,
+            KeyCode::End => self.col = self.line_len(),
+            KeyCode::Enter => {
+                let idx = self.byte_idx(self.col);
+                let rest = self.lines[self.row].split_off(idx);
+                self.lines.insert(self.row + 1, rest);
+                self.row += 1;
+                self.col = 0;
+            }
+            KeyCode::Backspace if self.col > 0 => {
+                let (a, b) = (self.byte_idx(self.col - 1), self.byte_idx(self.col));
+                self.lines[self.row].replace_range(a..b, "");
+                self.col -= 1;
+            }
+            KeyCode::Backspace if self.row > 0 => {
+                let line = self.lines.remove(self.row);
+                self.row -= 1;
+                self.col = self.line_len();
+                self.lines[self.row].push_str(&line);
+            }
+            KeyCode::Delete if self.col < self.line_len() => {
+                let (a, b) = (self.byte_idx(self.col), self.byte_idx(self.col + 1));
+                self.lines[self.row].replace_range(a..b, "");
+            }
+            KeyCode::Delete if self.row + 1 < self.lines.len() => {
+                let next = self.lines.remove(self.row + 1);
+                self.lines[self.row].push_str(&next);
+            }
+            KeyCode::Tab => {
+                let idx = self.byte_idx(self.col);
+                self.lines[self.row].insert_str(idx, "  ");
+                self.col += 2;
+            }
+            KeyCode::Char(c) => {
+                let idx = self.byte_idx(self.col);
+                self.lines[self.row].insert(idx, c);
+                // A combining mark joins the grapheme before it
+                self.col = grapheme_len(&self.lines[self.row][..idx + c.len_utf8()]);
+            }
+            _ => {}
+        }
+        ModalResult::Keep
+    }
+
+    fn handle_paste(&mut self, _app: &mut App, text: &str) -> bool {
+        self.insert_text(text);
+        true
+    }
+
+    fn draw(&self, f: &mut Frame, area: Rect, app: &App) {
+        let area_pop = centered_rect(80, 80, area);
+        // Keep the cursor line visible: text rows are the popup minus borders and 2 status rows
+        let visible = (area_pop.height as usize).saturating_sub(4).max(1);
+        let start = (self.row + 1).saturating_sub(visible);
+        let mut lines: Vec<Line> = self.lines.iter().enumerate().skip(start).take(visible).map(|(i, l)| {
+            if i != self.row { return Line::from(l.clone()); }
+            let mut l = l.clone();
+            l.insert(self.byte_idx(self.col), '▌');
+            Line::from(l)
+        }).collect();
+        lines.resize(visible, Line::from(""));
+        lines.push(match &self.error {
+            Some(e) => Line::from(Span::styled(e.clone(), app.theme.error())),
+            None => Line::from(""),
+        });
+        lines.push(Line::from(Span::styled("Ctrl+S: validate and save • Esc: discard", app.theme.hint())));
+        let p = Paragraph::new(lines)
+            .style(app.theme.base())
+            .block(Block::default().borders(Borders::ALL).border_style(app.theme.focused_border()).title(self.title.clone()));
+        f.render_widget(Clear, area_pop);
+        f.render_widget(p, area_pop);
+    }
+}
+
+/// Read-only, scrollable text (e.g. a config diff): ↑/↓, PgUp/PgDn, Home/End
+/// scroll; Enter or Esc closes. With `on_enter`, Enter confirms an action the
+/// text previews.
+pub struct TextViewModal {
+    pub title: String,
+    pub lines: Vec<Line<'static>>,
+    pub scroll: usize,
+    on_enter: Option<(String, ConfirmFn)>,
+}
+
+impl TextViewModal {
+    pub fn new(title: impl Into<String>, lines: Vec<Line<'static>>) -> Self {
+        Self { title: title.into(), lines, scroll: 0, on_enter: None }
+    }
+
+    /// Run `run` on Enter; `label` says what it does in the hint line.
+    pub fn on_enter(mut self, label: impl Into<String>, run: impl FnOnce(&mut App) + 'static) -> Self {
+        self.on_enter = Some((label.into(), Box::new(run)));
+        self
+    }
+}
+
+impl Modal for TextViewModal {
+    fn label(&self) -> String {
+        self.title.clone()
+    }
+
+    fn handle_key(&mut self, app: &mut App, key: KeyEvent) -> ModalResult {
+        let last = self.lines.len().saturating_sub(1);
+        match key.code {
+            KeyCode::Enter => {
+                if let Some((_, run)) = self.on_enter.take() { run(app); }
+                return ModalResult::Close;
+            }
+            KeyCode::Up => self.scroll = self.scroll.saturating_sub(1),
+            KeyCode::Down => self.scroll = (self.scroll + 1).min(last),
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(10),
+            KeyCode::PageDown => self.scroll = (self.scroll + 10).min(last),
+            KeyCode::Home => self.scroll = 0,
+            KeyCode::End => self.scroll = last,
+            _ => {}
+        }
+        ModalResult::Keep
+    }
+
+    fn draw(&self, f: &mut Frame, area: Rect, app: &App) {
+        let area_pop = centered_rect(80, 80, area);
+        let visible = (area_pop.height as usize).saturating_sub(3).max(1);
+        let mut lines: Vec<Line> = self.lines.iter().skip(self.scroll).take(visible).cloned().collect();
+        lines.resize(visible, Line::from(""));
+        let more = if self.scroll + visible < self.lines.len() { " • ↓ more" } else { "" };
+        let keys = match &self.on_enter {
+            Some((label, _)) => format!("Enter: {} • Esc: cancel", label),
+            None => "Enter/Esc: close".to_string(),
+        };
+        lines.push(Line::from(Span::styled(format!("↑/↓ PgUp/PgDn scroll • {}{}", keys, more), app.theme.hint())));
+        let p = Paragraph::new(lines)
+            .style(app.theme.base())
+            .block(Block::default().borders(Borders::ALL).border_style(app.theme.focused_border()).title(self.title.clone()));
+        f.render_widget(Clear, area_pop);
+        f.render_widget(p, area_pop);
+    }
+}
+
+/// Key reference; `?` toggles it.
+pub struct HelpModal;
+
+impl Modal for HelpModal {
+    fn label(&self) -> String {
+        "Help".to_string()
+    }
+
+    fn handle_key(&mut self, _app: &mut App, key: KeyEvent) -> ModalResult {
+        if key.code == KeyCode::Char('?') { ModalResult::Close } else { ModalResult::Keep }
+    }
+
+    fn draw(&self, f: &mut Frame, area: Rect, app: &App) {
+        let area = centered_rect(70, 60, area);
+        let lines = vec![
+            Line::from(Span::styled(format!("{} page:", page_name(app.page)), app.theme.title())),
+            Line::from(help_line(controller(app.page).actions())),
+            Line::from(Span::styled("Global keys:", app.theme.title())),
+            Line::from("Up/Down: navigate • Enter: select • Esc: back • q/Ctrl+C: quit"),
+            Line::from("1: Docs • 2: Configure • 3: Select Default • 4: Diagnostics • 5: Routing • 6: Benchmarks • 7: CLI Log • b: Build • s: Settings • w: What's New"),
+            Line::from("?: help overlay • t: theme • a: animation • l: log panel • Y: copy the log • Ctrl+V: paste the clipboard into the focused field • Ctrl+G: open result of last background task • Ctrl+K: dismiss the newest toast (or click it) • Ctrl+D: switch the default provider (w also rewrites the active config) • Ctrl+X: stop a running provider test"),
+            Line::from("Macros: Ctrl+R start/stop recording • Ctrl+P replay a saved macro (any key stops a replay)"),
+            Line::from("Diagnostics: e export (Markdown or JSON, path editable) • v raw JSON (Enter folds) • y copy JSON • r refresh (also re-checks every provider endpoint) • w watch: refresh every 10s until w again"),
+            Line::from("Model Browser: / search id and name (Enter keeps the filter, Esc clears it) • r downloaded-only • f cycle tag • i info • a browse for a GGUF file (s adds a whole directory) • x remove a custom model • h search Hugging Face, pick a quantization and download it • Enter on a custom model sets model_path"),
+            Line::from("Configure: Tab/Shift+Tab • ↑/↓ field • Enter edit/Test/Save/Cancel • ←/→/Home/End • Del/Backspace"),
+            Line::from("Configure form: Enter on Name/ID/Type edits them • in multi-line text fields Alt+Enter adds a line and Up/Down move between lines • Enter/Space flips On/Off toggles • Enter on a path field (e.g. model_path) opens a file browser: Backspace up, . hidden files, g GGUF only, / type a path • fields changed since the last save end in * and the help line shows saved → now; r reverts the selected one; o on a secret keeps it in memory only; k moves it to the system keychain • Ctrl+Z/Ctrl+Y undo/redo field edits"),
+            Line::from("Configure list: a add • A add from a preset (Ollama, LM Studio, OpenAI, Anthropic, …) • n new from selected (asks name/endpoint/model) • c clone • d delete • t test • T end-to-end test • g run a prompt (streamed) • v .chi_llm.json preview pane • s save • m model browser • x enable/disable • y copy JSON • u copy URI • p paste JSON/URI • e edit config as JSON • P ollama pull • $ monthly budget • i import an existing chi-llm config • Ctrl+Z/Ctrl+Y undo/redo adding and deleting providers"),
+            Line::from("Select Default: Enter/s set the default • r check every provider again (done on opening and every 30s while shown)"),
+            Line::from("Settings: k tick rate • o timeouts • p end-to-end test prompt • m background health monitor (dots on Configure and Select Default) • c chi-llm executable or Python interpreter • e pick a Python environment (venv, uv, conda) that has chi_llm (t/a changes and these are saved to settings.toml) • x export bundle • i import bundle"),
+            Line::from("Benchmarks: r run a fixed prompt against every enabled provider (restarts a running one) • n runs per provider • x stop"),
+            Line::from("CLI Log: Up/Down select a call (arguments, duration, exit code, output; secret flags masked) • c clear"),
+            Line::from("Routing: ↑/↓ provider • ←/→ tag • Space toggle • a add tag • d delete tag • s save • r reload"),
+            Line::from("Docs: Up/Down/PgUp/PgDn scroll • f files (README.md and docs/*.md; Enter opens) • h TOC • Tab switch pane • Enter jump • l links: open in the browser, #anchors jump to the heading"),
+            Line::from("Build: g toggle Project/Global • f JSON/YAML project file • d diff against the written config • p write to several project directories • e ${ENV} secret references • y copy export lines • j export JSON Schemas • Enter preview, Enter again writes • c commit the project config (git repos)"),
+            Line::from("Welcome: Up/Down + Enter to open a section • Tutorial walks through setup (Ctrl+N skip step • Ctrl+T end)"),
+            Line::from("Popups: Esc closes the topmost one"),
+            Line::from("Mouse: click a provider to select it, a form field to select it (again to open it), a button to press it • wheel scrolls Docs, What's New and the model list"),
+        ];
+        let block = Block::default().title("Help").borders(Borders::ALL).border_style(app.theme.border());
+        let content = Paragraph::new(lines).style(app.theme.base()).wrap(Wrap { trim: true }).block(block);
+        f.render_widget(Clear, area);
+        f.render_widget(content, area);
+    }
+}
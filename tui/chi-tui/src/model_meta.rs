@@ -0,0 +1,138 @@
+//! Freshness metadata for catalog models, shown in the Model Browser info pane
+//! to help decide which multi-GB files to delete. The download date is the model
+//! file's modification time when chi-llm reports its `path`, else when chi-tui
+//! first saw it downloaded. "Last used" is updated when a provider using the
+//! model passes a test or Build writes it. Stored in `model_meta.json` in the
+//! state dir.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::time::SystemTime;
+
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::models::ModelEntry;
+use crate::paths::{file_in, paths};
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModelMeta {
+    /// RFC 3339 time chi-tui first listed the model as downloaded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub first_seen_downloaded: Option<String>,
+    /// RFC 3339 time of the last test or build using the model.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_used: Option<String>,
+    /// Provider (or build target) behind `last_used`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_used_by: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ModelMetaStore(BTreeMap<String, ModelMeta>);
+
+impl ModelMetaStore {
+    /// Read `model_meta.json`; a missing or unreadable file gives an empty store.
+    pub fn load() -> Self {
+        fs::read_to_string(paths().state_dir.join("model_meta.json"))
+            .ok()
+            .and_then(|t| serde_json::from_str(&t).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = file_in(&paths().state_dir, "model_meta.json")?;
+        fs::write(path, serde_json::to_vec_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn get(&self, id: &str) -> Option<&ModelMeta> {
+        self.0.get(id)
+    }
+
+    /// Stamp models seen downloaded for the first time with `now`. Returns true
+    /// when anything changed.
+    pub fn note_downloaded<'a>(&mut self, ids: impl IntoIterator<Item = &'a str>, now: &str) -> bool {
+        let mut changed = false;
+        for id in ids {
+            let meta = self.0.entry(id.to_string()).or_default();
+            if meta.first_seen_downloaded.is_none() {
+                meta.first_seen_downloaded = Some(now.to_string());
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    pub fn note_used(&mut self, id: &str, by: &str, now: &str) {
+        let meta = self.0.entry(id.to_string()).or_default();
+        meta.last_used = Some(now.to_string());
+        meta.last_used_by = Some(by.to_string());
+    }
+}
+
+pub fn now_rfc3339() -> String {
+    Local::now().to_rfc3339()
+}
+
+/// When `entry` was downloaded: its file's mtime, else the first-seen stamp.
+pub fn downloaded_at(entry: &ModelEntry, meta: Option<&ModelMeta>) -> Option<DateTime<Local>> {
+    let mtime = entry.raw.get("path").and_then(|p| p.as_str())
+        .and_then(|p| fs::metadata(p).ok())
+        .and_then(|m| m.modified().ok())
+        .map(DateTime::<Local>::from);
+    mtime.or_else(|| parse_time(meta?.first_seen_downloaded.as_deref()?))
+}
+
+pub fn parse_time(s: &str) -> Option<DateTime<Local>> {
+    DateTime::parse_from_rfc3339(s).ok().map(|t| t.with_timezone(&Local))
+}
+
+/// `2025-08-21 (12 days ago)`.
+pub fn describe_age(t: DateTime<Local>, now: DateTime<Local>) -> String {
+    let days = (now - t).num_days();
+    let ago = match days {
+        d if d <= 0 => "today".to_string(),
+        1 => "yesterday".to_string(),
+        d => format!("{} days ago", d),
+    };
+    format!("{} ({})", t.format("%Y-%m-%d"), ago)
+}
+
+/// Info pane lines for `entry`: downloaded date and last use.
+pub fn freshness_lines(entry: &ModelEntry, meta: Option<&ModelMeta>) -> Vec<String> {
+    let now = DateTime::<Local>::from(SystemTime::now());
+    let mut lines = Vec::new();
+    if entry.downloaded {
+        let at = downloaded_at(entry, meta).map(|t| describe_age(t, now)).unwrap_or_else(|| "unknown".to_string());
+        lines.push(format!("downloaded: {}", at));
+    }
+    let last = meta.and_then(|m| Some((parse_time(m.last_used.as_deref()?)?, m.last_used_by.clone())));
+    lines.push(match last {
+        Some((t, Some(by))) => format!("last used: {} by {}", describe_age(t, now), by),
+        Some((t, None)) => format!("last used: {}", describe_age(t, now)),
+        None => "last used: never (by chi-tui)".to_string(),
+    });
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_download_stamp_is_kept_and_use_is_described() {
+        let mut store = ModelMetaStore::default();
+        assert!(store.note_downloaded(["phi"], "2025-08-01T10:00:00+00:00"));
+        assert!(!store.note_downloaded(["phi"], "2025-09-01T10:00:00+00:00"));
+        store.note_used("phi", "local-dev", "2025-08-03T10:00:00+00:00");
+        let meta = store.get("phi").unwrap();
+        assert_eq!(meta.first_seen_downloaded.as_deref(), Some("2025-08-01T10:00:00+00:00"));
+
+        let t = parse_time("2025-08-01T10:00:00+00:00").unwrap();
+        assert_eq!(describe_age(t, t + chrono::Duration::days(12)), format!("{} (12 days ago)", t.format("%Y-%m-%d")));
+        assert!(describe_age(t, t).ends_with("(today)"));
+    }
+}
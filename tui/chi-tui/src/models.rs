@@ -1,15 +1,28 @@
+use std::sync::atomic::AtomicBool;
 use std::time::Duration;
 
 use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
 use ratatui::prelude::Frame;
-use ratatui::style::{Modifier, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, List, ListItem, Paragraph, Wrap};
 use serde_json::Value;
 
-use crate::app::App;
-use crate::util::run_cli_json;
+use crate::app::{App, Notification, Page};
+use crate::controller::PageController;
+use crate::custom_models::{add_custom_models, load_custom_models, remove_custom_model, CustomModel};
+use crate::keymap::KeyAction;
+use crate::events::AppEvent;
+use crate::env::timeouts;
+use crate::file_picker::FilePickerModal;
+use crate::hf::open_hf_search;
+use crate::model_meta::{freshness_lines, now_rfc3339};
+use crate::mouse::{Scroll, SCROLL_LINES};
+use crate::msg::Msg;
+use crate::page_data::draw_page_status;
+use crate::text::{self, single_line};
+use crate::util::run_cli_json_cancellable;
 
 #[derive(Clone, Debug)]
 pub struct ModelEntry {
@@ -33,11 +46,38 @@ pub struct ModelBrowser {
     pub tag_filter: Option<String>,
     pub show_info: bool,
     pub all_tags: Vec<String>,
+    /// Text typed after `/`; filters on top of the downloaded/tag filters.
+    pub search: String,
+    /// Keys go to `search` (until Enter or Esc).
+    pub searching: bool,
+}
+
+/// How well `query` matches `e`: 0 for a substring of the id or name (either
+/// case), higher the more spread out its letters are, `None` when they do not
+/// appear in order.
+pub fn search_score(query: &str, e: &ModelEntry) -> Option<usize> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() { return Some(0); }
+    [&e.id, &e.name].iter().filter_map(|text| {
+        let text = text.to_lowercase();
+        if text.contains(&query) { return Some(0); }
+        // Fuzzy: the query's characters in order; score is the span they cover
+        let mut chars = text.char_indices();
+        let mut first = None;
+        let mut end = 0;
+        for q in query.chars() {
+            let (i, c) = chars.by_ref().find(|(_, c)| *c == q)?;
+            first.get_or_insert(i);
+            end = i + c.len_utf8();
+        }
+        Some(1 + end - first.unwrap_or(0) - query.len())
+    }).min()
 }
 
 impl ModelBrowser {
     pub fn compute_filtered(&mut self) {
         self.filtered.clear();
+        let mut scored = Vec::new();
         for (i, e) in self.entries.iter().enumerate() {
             if self.downloaded_only && !e.downloaded {
                 continue;
@@ -47,8 +87,13 @@ impl ModelBrowser {
                     continue;
                 }
             }
-            self.filtered.push(i);
+            if let Some(score) = search_score(&self.search, e) {
+                scored.push((score, i));
+            }
         }
+        // Stable: equally good matches keep list order
+        scored.sort_by_key(|&(score, _)| score);
+        self.filtered.extend(scored.into_iter().map(|(_, i)| i));
         if self.filtered.is_empty() {
             self.selected = 0;
         } else if self.selected >= self.filtered.len() {
@@ -93,81 +138,105 @@ impl ModelBrowser {
         }
         self.compute_filtered();
     }
+    /// A key while `searching`; false for keys search leaves to the page.
+    pub fn search_key(&mut self, key: KeyEvent) -> bool {
+        match key.code {
+            KeyCode::Char(c) => self.search.push(c),
+            KeyCode::Backspace => { self.search.pop(); }
+            KeyCode::Enter => { self.searching = false; return true; }
+            KeyCode::Esc => {
+                self.searching = false;
+                self.search.clear();
+            }
+            _ => return false,
+        }
+        self.selected = 0;
+        self.compute_filtered();
+        true
+    }
+    /// Append list items (catalog or custom models) and refresh tags and filter.
+    pub fn add_items<'a>(&mut self, items: impl IntoIterator<Item = &'a Value>) {
+        self.entries.extend(items.into_iter().map(parse_model_entry));
+        let tags: std::collections::BTreeSet<&String> = self.entries.iter().flat_map(|e| &e.tags).collect();
+        self.all_tags = tags.into_iter().cloned().collect();
+        self.compute_filtered();
+    }
     pub fn current_entry(&self) -> Option<&ModelEntry> {
         self.filtered.get(self.selected).map(|&i| &self.entries[i])
     }
 }
 
-pub fn fetch_models(timeout: Duration) -> Result<ModelBrowser> {
-    let arr = run_cli_json(&["models", "list", "--json"], timeout)?;
-    let mut entries: Vec<ModelEntry> = Vec::new();
-    let mut tagset: std::collections::BTreeSet<String> =
-        std::collections::BTreeSet::new();
-    if let Some(list) = arr.as_array() {
-        for v in list {
-            let id = v.get("id").and_then(|x| x.as_str()).unwrap_or("").to_string();
-            let name = v
-                .get("name")
-                .and_then(|x| x.as_str())
-                .unwrap_or(&id)
-                .to_string();
-            let size = v
-                .get("size")
-                .and_then(|x| x.as_str())
-                .map(|s| s.to_string());
-            let file_size_mb = v.get("file_size_mb").and_then(|x| x.as_u64());
-            let context_window = v.get("context_window").and_then(|x| x.as_u64());
-            let tags: Vec<String> = v
-                .get("tags")
-                .and_then(|x| x.as_array())
-                .map(|a| {
-                    a.iter()
-                        .filter_map(|t| t.as_str().map(|s| s.to_string()))
-                        .collect()
-                })
-                .unwrap_or_default();
-            for t in &tags {
-                tagset.insert(t.clone());
-            }
-            let downloaded = v
-                .get("downloaded")
-                .and_then(|x| x.as_bool())
-                .unwrap_or(false);
-            let current = v
-                .get("current")
-                .and_then(|x| x.as_bool())
-                .unwrap_or(false);
-            entries.push(ModelEntry {
-                id,
-                name,
-                size,
-                file_size_mb,
-                context_window,
-                tags,
-                downloaded,
-                current,
-                raw: v.clone(),
-            });
-        }
+/// A `models list --json` item.
+pub fn parse_model_entry(v: &Value) -> ModelEntry {
+    let id = v.get("id").and_then(|x| x.as_str()).unwrap_or("").to_string();
+    let name = v
+        .get("name")
+        .and_then(|x| x.as_str())
+        .unwrap_or(&id)
+        .to_string();
+    let size = v
+        .get("size")
+        .and_then(|x| x.as_str())
+        .map(|s| s.to_string());
+    let file_size_mb = v.get("file_size_mb").and_then(|x| x.as_u64());
+    let context_window = v.get("context_window").and_then(|x| x.as_u64());
+    let tags: Vec<String> = v
+        .get("tags")
+        .and_then(|x| x.as_array())
+        .map(|a| {
+            a.iter()
+                .filter_map(|t| t.as_str().map(|s| s.to_string()))
+                .collect()
+        })
+        .unwrap_or_default();
+    let downloaded = v
+        .get("downloaded")
+        .and_then(|x| x.as_bool())
+        .unwrap_or(false);
+    let current = v
+        .get("current")
+        .and_then(|x| x.as_bool())
+        .unwrap_or(false);
+    ModelEntry {
+        id,
+        name,
+        size,
+        file_size_mb,
+        context_window,
+        tags,
+        downloaded,
+        current,
+        raw: v.clone(),
     }
-    let all_tags = tagset.into_iter().collect();
+}
+
+/// The chi-llm catalog followed by the custom GGUF models.
+pub fn fetch_models(timeout: Duration, cancel: &AtomicBool) -> Result<ModelBrowser> {
+    let arr = run_cli_json_cancellable(&["models", "list", "--json"], timeout, cancel)?;
     let mut mb = ModelBrowser {
-        entries,
+        entries: Vec::new(),
         filtered: Vec::new(),
         selected: 0,
         downloaded_only: false,
         tag_filter: None,
         show_info: false,
-        all_tags,
+        all_tags: Vec::new(),
+        search: String::new(),
+        searching: false,
     };
-    mb.compute_filtered();
+    let custom: Vec<Value> = load_custom_models().iter().map(CustomModel::to_list_item).collect();
+    mb.add_items(arr.as_array().map(Vec::as_slice).unwrap_or_default().iter().chain(&custom));
     Ok(mb)
 }
 
 pub fn draw_model_browser(f: &mut Frame, area: Rect, app: &App) {
+    let Some(mb) = app.model.ready() else {
+        draw_page_status(f, area, app, "Models", &app.model);
+        return;
+    };
     let mut upper = area;
     let mut lower = area;
-    let show_info = app.model.as_ref().map(|m| m.show_info).unwrap_or(false);
+    let show_info = mb.show_info;
     if show_info {
         let chunks = Layout::default()
             .direction(Direction::Vertical)
@@ -177,83 +246,76 @@ pub fn draw_model_browser(f: &mut Frame, area: Rect, app: &App) {
         lower = chunks[1];
     }
     let mut items: Vec<ListItem> = Vec::new();
-    if let Some(mb) = &app.model {
-        for (pos, &idx) in mb.filtered.iter().enumerate() {
-            let e = &mb.entries[idx];
-            let mut label = format!("{} {}", if pos == mb.selected { '›' } else { ' ' }, e.name);
-            if e.current {
-                label.push_str("  [current]");
-            }
-            if e.downloaded {
-                label.push_str("  [downloaded]");
-            }
-            if let Some(ref tag) = mb.tag_filter {
-                label.push_str(&format!("  [tag:{}]", tag));
-            }
-            let style = if pos == mb.selected {
-                Style::default()
-                    .fg(app.theme.selected)
-                    .add_modifier(Modifier::BOLD)
-            } else {
-                Style::default().fg(app.theme.fg)
-            };
-            items.push(ListItem::new(Line::from(Span::styled(label, style))));
+    for (pos, &idx) in mb.filtered.iter().enumerate() {
+        let e = &mb.entries[idx];
+        let mut label = format!("{} {}", if pos == mb.selected { '›' } else { ' ' }, text::truncate(&e.name, text::NAME_MAX));
+        if e.current {
+            label.push_str("  [current]");
         }
-    } else {
-        items.push(ListItem::new("Loading models..."));
-    }
-    let title = if let Some(mb) = &app.model {
-        let mut t = String::from("Models");
-        if mb.downloaded_only {
-            t.push_str(" • downloaded-only");
+        if e.raw.get("custom").is_some() {
+            label.push_str("  [custom]");
+        } else if e.downloaded {
+            label.push_str("  [downloaded]");
         }
-        if let Some(tag) = &mb.tag_filter {
-            t.push_str(&format!(" • tag:{}", tag));
+        if let Some(ref tag) = mb.tag_filter {
+            label.push_str(&format!("  [tag:{}]", tag));
         }
-        t
-    } else {
-        String::from("Models")
-    };
+        let style = if pos == mb.selected {
+            app.theme.selected_row()
+        } else {
+            app.theme.text()
+        };
+        items.push(ListItem::new(Line::from(Span::styled(label, style))));
+    }
+    let mut title = String::from("Models");
+    if mb.downloaded_only {
+        title.push_str(" • downloaded-only");
+    }
+    if let Some(tag) = &mb.tag_filter {
+        title.push_str(&format!(" • tag:{}", tag));
+    }
+    if mb.searching || !mb.search.is_empty() {
+        title.push_str(&format!(" • /{}{} ({} match(es))", mb.search, if mb.searching { "▏" } else { "" }, mb.filtered.len()));
+    }
     let list = List::new(items)
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .border_style(Style::default().fg(app.theme.frame))
+                .border_style(app.theme.border())
                 .title(title),
         )
-        .highlight_style(Style::default().fg(app.theme.selected));
+        .highlight_style(app.theme.highlight());
     f.render_widget(list, upper);
 
     if show_info {
         let mut lines: Vec<Line> = Vec::new();
-        if let Some(mb) = &app.model {
-            if let Some(e) = mb.current_entry() {
-                lines.push(Line::from(Span::styled(
-                    format!("{} ({})", e.name, e.id),
-                    Style::default()
-                        .fg(app.theme.primary)
-                        .add_modifier(Modifier::BOLD),
-                )));
-                if let Some(s) = &e.size {
-                    lines.push(Line::from(format!("size: {}", s)));
-                }
-                if let Some(fs) = e.file_size_mb {
-                    lines.push(Line::from(format!("file_size_mb: {}", fs)));
-                }
-                if let Some(ctx) = e.context_window {
-                    lines.push(Line::from(format!("context_window: {}", ctx)));
-                }
-                if !e.tags.is_empty() {
-                    lines.push(Line::from(format!("tags: {}", e.tags.join(", "))));
-                }
+        if let Some(e) = mb.current_entry() {
+            lines.push(Line::from(Span::styled(
+                format!("{} ({})", e.name, e.id),
+                app.theme.title(),
+            )));
+            if let Some(s) = &e.size {
+                lines.push(Line::from(format!("size: {}", s)));
+            }
+            if let Some(fs) = e.file_size_mb {
+                lines.push(Line::from(format!("file_size_mb: {}", fs)));
+            }
+            if let Some(ctx) = e.context_window {
+                lines.push(Line::from(format!("context_window: {}", ctx)));
+            }
+            if !e.tags.is_empty() {
+                lines.push(Line::from(format!("tags: {}", e.tags.join(", "))));
+            }
+            for l in freshness_lines(e, app.model_meta.get(&e.id)) {
+                lines.push(Line::from(l));
             }
         }
         let p = Paragraph::new(lines)
-            .style(Style::default().bg(app.theme.bg).fg(app.theme.fg))
+            .style(app.theme.base())
             .block(
                 Block::default()
                     .borders(Borders::ALL)
-                    .border_style(Style::default().fg(app.theme.frame))
+                    .border_style(app.theme.border())
                     .title("Info"),
             )
             .alignment(ratatui::layout::Alignment::Left)
@@ -262,3 +324,186 @@ pub fn draw_model_browser(f: &mut Frame, area: Rect, app: &App) {
     }
 }
 
+/// `a`: register a GGUF file, or every one under a directory.
+fn open_add_custom(app: &mut App) {
+    let picker = FilePickerModal::new("Add custom model: a .gguf file, or s to scan a directory", "", |app, path| {
+        let path = std::path::PathBuf::from(&path);
+        let (msg, ok) = match add_custom_models(&path) {
+            Ok(added) if added.is_empty() => ("Those models are already in the list".to_string(), true),
+            Ok(added) => {
+                if let Some(m) = app.model.ready_mut() {
+                    let items: Vec<Value> = added.iter().map(CustomModel::to_list_item).collect();
+                    m.add_items(&items);
+                    if let Some(pos) = m.filtered.iter().position(|&i| m.entries[i].id == added[0].id) { m.selected = pos; }
+                }
+                (format!("Added {} custom model(s); Enter uses one as model_path of the selected provider", added.len()), true)
+            }
+            Err(e) => (format!("Could not add: {}", e), false),
+        };
+        let _ = app.notify_tx.send(Notification::new(msg, ok, Page::ModelBrowser));
+    }).gguf_only().pick_dirs();
+    app.modals.push(Box::new(picker));
+}
+
+pub struct ModelBrowserPage;
+
+impl PageController for ModelBrowserPage {
+    fn handle_key(&self, app: &mut App, key: KeyEvent) -> Option<Msg> {
+        if matches!(key.code, KeyCode::Char('r') | KeyCode::Char('R')) && app.model.is_error() {
+            app.model.load(&app.jobs, "Listing models", |ctx| fetch_models(timeouts().cli, ctx.cancel_flag()));
+            return Some(Msg::Handled);
+        }
+        let m = app.model.ready_mut()?;
+        if m.searching && m.search_key(key) { return Some(Msg::Handled); }
+        match key.code {
+            KeyCode::Up => m.move_up(),
+            KeyCode::Char('/') => m.searching = true,
+            // Esc clears a search before it leaves the page
+            KeyCode::Esc if !m.search.is_empty() => {
+                m.search.clear();
+                m.compute_filtered();
+            }
+            KeyCode::Down => m.move_down(),
+            KeyCode::Char('r') | KeyCode::Char('R') => m.toggle_downloaded_only(),
+            KeyCode::Char('f') | KeyCode::Char('F') => m.cycle_tag(),
+            KeyCode::Char('i') | KeyCode::Char('I') => m.show_info = !m.show_info,
+            KeyCode::Enter => {
+                match m.current_entry() {
+                    Some(cur) if cur.raw.get("custom").is_some() => {
+                        let path = cur.raw.get("path").and_then(|p| p.as_str()).unwrap_or_default().to_string();
+                        app.emit(AppEvent::ModelFilePicked(path));
+                    }
+                    Some(cur) => { let id = cur.id.clone(); app.emit(AppEvent::ModelPicked(id)); }
+                    None => {}
+                }
+                return Some(Msg::Goto(Page::Configure));
+            }
+            KeyCode::Char('a') | KeyCode::Char('A') => open_add_custom(app),
+            KeyCode::Char('h') | KeyCode::Char('H') => open_hf_search(app),
+            KeyCode::Char('x') | KeyCode::Char('X') => {
+                let Some(id) = m.current_entry().filter(|e| e.raw.get("custom").is_some()).map(|e| e.id.clone()) else { return Some(Msg::Handled) };
+                let (msg, ok) = match remove_custom_model(&id) {
+                    Ok(_) => {
+                        m.entries.retain(|e| e.id != id);
+                        m.compute_filtered();
+                        (format!("Removed {} from the list (the file is kept)", id), true)
+                    }
+                    Err(e) => (format!("Could not remove {}: {}", id, e), false),
+                };
+                let _ = app.notify_tx.send(Notification::new(msg, ok, Page::ModelBrowser));
+            }
+            _ => return None,
+        }
+        Some(Msg::Handled)
+    }
+
+    fn captures_input(&self, app: &App) -> bool {
+        app.model.ready().is_some_and(|m| m.searching)
+    }
+
+    fn handle_paste(&self, app: &mut App, text: &str) -> bool {
+        let Some(m) = app.model.ready_mut().filter(|m| m.searching) else { return false };
+        m.search.push_str(&single_line(text));
+        m.selected = 0;
+        m.compute_filtered();
+        true
+    }
+
+    fn handle_scroll(&self, app: &mut App, dir: Scroll) -> bool {
+        let Some(m) = app.model.ready_mut() else { return false };
+        for _ in 0..SCROLL_LINES {
+            match dir {
+                Scroll::Up => m.move_up(),
+                Scroll::Down => m.move_down(),
+            }
+        }
+        true
+    }
+
+    fn tick(&self, app: &mut App) {
+        app.model.ensure(&app.jobs, "Listing models", |ctx| fetch_models(timeouts().cli, ctx.cancel_flag()));
+        if let Some(mb) = app.model.ready() {
+            let downloaded = mb.entries.iter().filter(|e| e.downloaded).map(|e| e.id.as_str());
+            if app.model_meta.note_downloaded(downloaded, &now_rfc3339()) { let _ = app.model_meta.save(); }
+        }
+    }
+
+    fn draw(&self, f: &mut Frame, area: Rect, app: &App) {
+        draw_model_browser(f, area, app);
+    }
+
+    fn actions(&self) -> &'static [KeyAction] {
+        const ACTIONS: &[KeyAction] = &[
+            KeyAction::new("r", "retry").when(|app| app.model.is_error()),
+            KeyAction::new("Up/Down", "select").when(|app| app.model.ready().is_some()),
+            KeyAction::new("/", "search").when(|app| app.model.ready().is_some_and(|m| !m.searching)),
+            KeyAction::new("Enter", "done searching").when(|app| app.model.ready().is_some_and(|m| m.searching)),
+            KeyAction::new("Enter", "choose").when(|app| app.model.ready().is_some_and(|m| !m.searching)),
+            KeyAction::new("r", "downloaded-only").when(|app| app.model.ready().is_some()),
+            KeyAction::new("f", "tag filter").when(|app| app.model.ready().is_some()),
+            KeyAction::new("i", "info").when(|app| app.model.ready().is_some()),
+            KeyAction::new("a", "add GGUF").when(|app| app.model.ready().is_some()),
+            KeyAction::new("h", "Hugging Face").when(|app| app.model.ready().is_some() && app.hf_job.is_none()),
+            KeyAction::new("x", "remove custom").when(|app| app.model.ready().and_then(|m| m.current_entry()).is_some_and(|e| e.raw.get("custom").is_some())),
+            KeyAction::new("Esc", "back"),
+        ];
+        ACTIONS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: &str, name: &str, tags: &[&str], downloaded: bool) -> ModelEntry {
+        ModelEntry {
+            id: id.to_string(),
+            name: name.to_string(),
+            size: None,
+            file_size_mb: None,
+            context_window: None,
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            downloaded,
+            current: false,
+            raw: Value::Null,
+        }
+    }
+
+    #[test]
+    fn search_ranks_substrings_before_fuzzy_matches_within_the_filters() {
+        let mut mb = ModelBrowser {
+            entries: vec![
+                entry("qwen3-4b-instruct", "Qwen3 4B Instruct", &["chat"], true),
+                entry("phi-3-mini", "Phi 3 Mini", &["chat"], true),
+                entry("qwen2.5-coder", "Qwen2.5 Coder", &["code"], false),
+                entry("gemma-2b", "Gemma 2B", &["chat"], false),
+            ],
+            filtered: Vec::new(),
+            selected: 0,
+            downloaded_only: false,
+            tag_filter: None,
+            show_info: false,
+            all_tags: vec!["chat".to_string(), "code".to_string()],
+            search: String::new(),
+            searching: true,
+        };
+        for c in "qwn".chars() { mb.search_key(KeyEvent::from(KeyCode::Char(c))); }
+        assert_eq!(mb.filtered, [0, 2]);
+        mb.search = "coder".to_string();
+        mb.compute_filtered();
+        assert_eq!(mb.filtered, [2]);
+        mb.search = "mini".to_string();
+        mb.compute_filtered();
+        assert_eq!(mb.filtered, [1]);
+        assert_eq!(search_score("QW", &mb.entries[0]), Some(0));
+        assert!(search_score("q3i", &mb.entries[0]).unwrap() > 0);
+
+        mb.search = "qwen".to_string();
+        mb.downloaded_only = true;
+        mb.compute_filtered();
+        assert_eq!(mb.filtered, [0]);
+        mb.search_key(KeyEvent::from(KeyCode::Esc));
+        assert!(!mb.searching && mb.search.is_empty());
+        assert_eq!(mb.filtered, [0, 1]);
+    }
+}
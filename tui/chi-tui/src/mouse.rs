@@ -0,0 +1,65 @@
+//! Mouse hit testing. Drawing code records clickable areas in `App::hits` while
+//! it renders; `App::handle_mouse` hands a left click to the active page as the
+//! topmost target under the pointer (as of the last frame), and the wheel to the
+//! page's `handle_scroll`.
+
+use ratatui::layout::Rect;
+
+/// Rows per wheel notch.
+pub const SCROLL_LINES: usize = 3;
+
+/// Something that can be clicked.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum HitTarget {
+    /// A row of a list pane (Configure's `+ Add provider` row included).
+    ListRow(usize),
+    /// A form row in `FormState::selected` numbering: lead rows, fields, then buttons.
+    FormRow(usize),
+    /// A toast, newest first; a click dismisses it.
+    Toast(usize),
+}
+
+/// Clickable areas of the last frame, in drawing order.
+#[derive(Debug, Default)]
+pub struct HitMap(Vec<(Rect, HitTarget)>);
+
+impl HitMap {
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    pub fn add(&mut self, area: Rect, target: HitTarget) {
+        if area.width > 0 && area.height > 0 { self.0.push((area, target)); }
+    }
+
+    /// The target at a terminal cell; later (topmost) areas win.
+    pub fn at(&self, column: u16, row: u16) -> Option<HitTarget> {
+        self.0.iter().rev()
+            .find(|(r, _)| column >= r.x && column < r.x + r.width && row >= r.y && row < r.y + r.height)
+            .map(|(_, t)| *t)
+    }
+}
+
+/// Whether the wheel moved up or down.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Scroll {
+    Up,
+    Down,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn topmost_area_wins() {
+        let mut hits = HitMap::default();
+        hits.add(Rect::new(0, 0, 10, 5), HitTarget::ListRow(0));
+        hits.add(Rect::new(2, 1, 3, 1), HitTarget::FormRow(4));
+        hits.add(Rect::new(0, 9, 0, 1), HitTarget::ListRow(9));
+        assert_eq!(hits.at(3, 1), Some(HitTarget::FormRow(4)));
+        assert_eq!(hits.at(9, 4), Some(HitTarget::ListRow(0)));
+        assert_eq!(hits.at(10, 0), None);
+        assert_eq!(hits.at(0, 9), None);
+    }
+}
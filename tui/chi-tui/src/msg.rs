@@ -0,0 +1,125 @@
+//! App-level messages. `App::dispatch_key` turns a key into a `Msg` with the
+//! pure functions here and `App::update` carries it out, so which key does
+//! what is testable without a terminal or an `App`. Page controllers handle
+//! their own keys and return a `Msg` for anything app-level; only keys they
+//! leave unhandled become global messages.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::app::Page;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Msg {
+    /// The page took the key and updated its own state; nothing left to do.
+    Handled,
+    Quit,
+    /// Esc: the welcome screen, or quit from there.
+    Back,
+    Goto(Page),
+    ToggleTheme,
+    ToggleAnimation,
+    ToggleLog,
+    /// Copy the log panel's lines to the clipboard.
+    CopyLog,
+    /// Ctrl+V: paste the clipboard like a terminal paste.
+    PasteClipboard,
+    Help,
+    /// Ctrl+G: the page behind the newest toast.
+    JumpToToast,
+    /// Ctrl+K: close the newest toast.
+    DismissToast,
+    /// Ctrl+D: switch the default provider from any page.
+    QuickDefault,
+    /// Ctrl+X: stop a running provider test.
+    CancelTest,
+    ToggleRecording,
+    OpenMacros,
+    TutorialStart,
+    TutorialSkip,
+    TutorialEnd,
+}
+
+/// Page shortcuts, in the order the help lists them.
+pub const PAGE_KEYS: &[(char, Page)] = &[
+    ('1', Page::Readme),
+    ('2', Page::Configure),
+    ('3', Page::SelectDefault),
+    ('4', Page::Diagnostics),
+    ('5', Page::Routing),
+    ('6', Page::Benchmarks),
+    ('7', Page::CliLog),
+    ('b', Page::Build),
+    ('s', Page::Settings),
+    ('w', Page::WhatsNew),
+];
+
+/// Ctrl chords for macros, handled before recording (so they are not recorded).
+pub fn macro_msg(key: KeyEvent) -> Option<Msg> {
+    if !key.modifiers.contains(KeyModifiers::CONTROL) { return None; }
+    match key.code {
+        KeyCode::Char('r') => Some(Msg::ToggleRecording),
+        KeyCode::Char('p') => Some(Msg::OpenMacros),
+        _ => None,
+    }
+}
+
+/// Ctrl chords that work everywhere, before popups and pages see the key.
+/// The tutorial ones only while `tutorial` runs.
+pub fn chord_msg(key: KeyEvent, tutorial: bool) -> Option<Msg> {
+    if !key.modifiers.contains(KeyModifiers::CONTROL) { return None; }
+    match key.code {
+        KeyCode::Char('c') => Some(Msg::Quit),
+        KeyCode::Char('g') => Some(Msg::JumpToToast),
+        KeyCode::Char('d') => Some(Msg::QuickDefault),
+        KeyCode::Char('k') => Some(Msg::DismissToast),
+        KeyCode::Char('x') => Some(Msg::CancelTest),
+        KeyCode::Char('v') => Some(Msg::PasteClipboard),
+        KeyCode::Char('n') if tutorial => Some(Msg::TutorialSkip),
+        KeyCode::Char('t') if tutorial => Some(Msg::TutorialEnd),
+        _ => None,
+    }
+}
+
+/// Global keys for a key the active page did not handle.
+pub fn global_msg(key: KeyEvent) -> Option<Msg> {
+    match key.code {
+        KeyCode::Char('q') => Some(Msg::Quit),
+        KeyCode::Char('?') => Some(Msg::Help),
+        KeyCode::Char('t') => Some(Msg::ToggleTheme),
+        KeyCode::Char('a') => Some(Msg::ToggleAnimation),
+        KeyCode::Char('l') => Some(Msg::ToggleLog),
+        KeyCode::Char('Y') => Some(Msg::CopyLog),
+        KeyCode::Esc => Some(Msg::Back),
+        KeyCode::Char(c) => PAGE_KEYS.iter().find(|(k, _)| *k == c.to_ascii_lowercase()).map(|(_, page)| Msg::Goto(*page)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn keys_map_to_messages() {
+        let plain = |c| global_msg(key(KeyCode::Char(c), KeyModifiers::NONE));
+        assert_eq!(plain('7'), Some(Msg::Goto(Page::CliLog)));
+        assert_eq!(plain('S'), Some(Msg::Goto(Page::Settings)));
+        assert_eq!(plain('t'), Some(Msg::ToggleTheme));
+        assert_eq!(plain('Y'), Some(Msg::CopyLog));
+        assert_eq!(plain('z'), None);
+        assert_eq!(global_msg(key(KeyCode::Esc, KeyModifiers::NONE)), Some(Msg::Back));
+
+        let ctrl = |c, tutorial| chord_msg(key(KeyCode::Char(c), KeyModifiers::CONTROL), tutorial);
+        assert_eq!(ctrl('c', false), Some(Msg::Quit));
+        assert_eq!(ctrl('k', false), Some(Msg::DismissToast));
+        assert_eq!(ctrl('d', false), Some(Msg::QuickDefault));
+        assert_eq!(ctrl('n', false), None);
+        assert_eq!(ctrl('n', true), Some(Msg::TutorialSkip));
+        assert_eq!(chord_msg(key(KeyCode::Char('c'), KeyModifiers::NONE), false), None);
+        assert_eq!(macro_msg(key(KeyCode::Char('r'), KeyModifiers::CONTROL)), Some(Msg::ToggleRecording));
+    }
+}
@@ -0,0 +1,171 @@
+use anyhow::Result;
+use ratatui::layout::{Alignment, Rect};
+use ratatui::prelude::Frame;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph, Wrap};
+
+use crate::app::App;
+use crate::jobs::{Job, JobCtx, Jobs};
+
+const SPINNER: &[&str] = &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
+/// Lifecycle of data backing a page. Loads run as background jobs and are
+/// collected by `poll` from the main loop.
+#[derive(Debug, Default)]
+pub enum PageData<T> {
+    #[default]
+    NotLoaded,
+    Loading(Job<Result<T, String>>),
+    Ready(T),
+    Error(String),
+}
+
+impl<T: Send + 'static> PageData<T> {
+    /// Start (or restart) loading with `load` as a job labelled `label`. A load
+    /// still running is cancelled.
+    pub fn load<F>(&mut self, jobs: &Jobs, label: &str, load: F)
+    where
+        F: FnOnce(&JobCtx) -> Result<T> + Send + 'static,
+    {
+        if let PageData::Loading(job) = self { job.cancel(); }
+        *self = PageData::Loading(jobs.spawn(label, move |ctx| load(ctx).map_err(|e| e.to_string())));
+    }
+
+    /// Start loading only if nothing has been loaded or requested yet.
+    pub fn ensure<F>(&mut self, jobs: &Jobs, label: &str, load: F)
+    where
+        F: FnOnce(&JobCtx) -> Result<T> + Send + 'static,
+    {
+        if matches!(self, PageData::NotLoaded) {
+            self.load(jobs, label, load);
+        }
+    }
+}
+
+impl<T> PageData<T> {
+    /// Move a finished load into `Ready`/`Error`.
+    pub fn poll(&mut self) {
+        let PageData::Loading(job) = self else { return };
+        *self = match job.poll() {
+            Some(Ok(Ok(v))) => PageData::Ready(v),
+            Some(Ok(Err(e)) | Err(e)) => PageData::Error(e),
+            None => return,
+        };
+    }
+
+    pub fn ready(&self) -> Option<&T> {
+        match self {
+            PageData::Ready(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn ready_mut(&mut self) -> Option<&mut T> {
+        match self {
+            PageData::Ready(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        matches!(self, PageData::Error(_))
+    }
+
+    pub fn is_loading(&self) -> bool {
+        matches!(self, PageData::Loading(_))
+    }
+}
+
+/// Spinner frame for the current UI tick.
+pub fn spinner(app: &App) -> &'static str {
+    SPINNER[(app.tick as usize) % SPINNER.len()]
+}
+
+/// Render the non-ready states of a page consistently: spinner while loading,
+/// error with a retry hint, or a placeholder before loading starts.
+pub fn draw_page_status<T>(f: &mut Frame, area: Rect, app: &App, title: &str, data: &PageData<T>) {
+    let line = match data {
+        PageData::Ready(_) => return,
+        PageData::NotLoaded => Line::from(Span::styled("Not loaded", app.theme.hint())),
+        PageData::Loading(_) => Line::from(Span::styled(
+            format!("{} Loading…", spinner(app)),
+            app.theme.hint(),
+        )),
+        PageData::Error(e) => Line::from(Span::styled(
+            format!("Error: {} — press r to retry", e),
+            app.theme.error(),
+        )),
+    };
+    let p = Paragraph::new(line)
+        .style(app.theme.base())
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(app.theme.border())
+                .title(title.to_string()),
+        )
+        .alignment(Alignment::Left)
+        .wrap(Wrap { trim: true });
+    f.render_widget(p, area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+    use std::time::{Duration, Instant};
+
+    /// Poll until the load has finished.
+    fn settle<T>(data: &mut PageData<T>) {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while data.is_loading() {
+            assert!(Instant::now() < deadline, "load never finished");
+            std::thread::sleep(Duration::from_millis(5));
+            data.poll();
+        }
+    }
+
+    #[test]
+    fn loads_end_ready_or_with_the_error() {
+        let jobs = Jobs::default();
+        let mut data: PageData<u32> = PageData::NotLoaded;
+        data.poll();
+        assert!(matches!(data, PageData::NotLoaded));
+
+        data.load(&jobs, "answer", |_| Ok(42));
+        assert!(data.is_loading());
+        settle(&mut data);
+        assert_eq!(data.ready(), Some(&42));
+        *data.ready_mut().unwrap() += 1;
+        assert_eq!(data.ready(), Some(&43));
+
+        data.load(&jobs, "broken", |_| Err(anyhow!("chi-llm not found")));
+        settle(&mut data);
+        assert!(data.is_error() && data.ready().is_none());
+        assert!(matches!(&data, PageData::Error(e) if e == "chi-llm not found"));
+    }
+
+    #[test]
+    fn ensure_loads_once_and_load_cancels_the_running_one() {
+        let jobs = Jobs::default();
+        let mut data: PageData<u32> = PageData::NotLoaded;
+        data.ensure(&jobs, "first", |_| Ok(1));
+        data.ensure(&jobs, "second", |_| Ok(2));
+        settle(&mut data);
+        assert_eq!(data.ready(), Some(&1));
+        data.ensure(&jobs, "third", |_| Ok(3));
+        assert_eq!(data.ready(), Some(&1), "ready data is kept");
+
+        // A reload cancels the load still running
+        data.load(&jobs, "slow", |ctx| {
+            while !ctx.is_cancelled() { std::thread::sleep(Duration::from_millis(5)); }
+            Err(anyhow!("cancelled"))
+        });
+        let PageData::Loading(slow) = &data else { panic!("not loading") };
+        let flag = slow.cancel_handle();
+        data.load(&jobs, "fast", |_| Ok(4));
+        assert!(flag.load(std::sync::atomic::Ordering::Relaxed));
+        settle(&mut data);
+        assert_eq!(data.ready(), Some(&4));
+    }
+}
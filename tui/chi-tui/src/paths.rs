@@ -0,0 +1,112 @@
+//! Locations of files owned by chi-tui. Everything goes through XDG base
+//! directories (or the platform equivalents from `dirs` on macOS/Windows) under a
+//! `chi-tui` subdirectory; nothing app-owned is written to the working directory.
+//!
+//! The provider scratch file stays project-local (`./chi.tmp.json`) because it
+//! belongs with the project's `.chi_llm.json`, not with the user.
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+const APP_DIR: &str = "chi-tui";
+
+/// Provider scratch catalog edited by Configure / Select Default, relative to CWD.
+pub const SCRATCH_FILE: &str = "chi.tmp.json";
+/// Project config written by Build.
+pub const PROJECT_CONFIG: &str = ".chi_llm.json";
+/// Project config in YAML, for teams that standardise on it.
+pub const PROJECT_CONFIG_YAML: &str = ".chi_llm.yaml";
+/// Project config files in the order chi-llm looks for them; the first found wins.
+pub const PROJECT_CONFIG_LOOKUP: &[&str] = &[PROJECT_CONFIG_YAML, ".chi_llm.yml", PROJECT_CONFIG];
+
+#[derive(Clone, Debug)]
+pub struct AppPaths {
+    /// `$XDG_CONFIG_HOME/chi-tui`: settings.
+    pub config_dir: PathBuf,
+    /// `$XDG_CACHE_HOME/chi-tui`: cached CLI output, safe to delete.
+    pub cache_dir: PathBuf,
+    /// `$XDG_STATE_HOME/chi-tui`: logs and session state.
+    pub state_dir: PathBuf,
+    /// `$XDG_DATA_HOME/chi-tui`: backups and exports.
+    pub data_dir: PathBuf,
+}
+
+impl AppPaths {
+    /// Resolve from the environment; falls back to `./.chi-tui` when no home is known.
+    /// Unit tests get a temporary directory instead.
+    pub fn resolve() -> Self {
+        if cfg!(test) {
+            return Self::under(&std::env::temp_dir().join(format!("chi-tui-unit-{}", std::process::id())));
+        }
+        let fallback = || PathBuf::from(".chi-tui");
+        let under = |base: Option<PathBuf>| base.map(|b| b.join(APP_DIR)).unwrap_or_else(fallback);
+        Self {
+            config_dir: under(dirs::config_dir()),
+            cache_dir: under(dirs::cache_dir()),
+            // Only Linux has a state dir; elsewhere keep state next to local data
+            state_dir: under(dirs::state_dir().or_else(dirs::data_local_dir)),
+            data_dir: under(dirs::data_dir()),
+        }
+    }
+
+    /// All four directories under `root`, for a self-contained setup such as tests.
+    pub fn under(root: &Path) -> Self {
+        Self {
+            config_dir: root.join("config"),
+            cache_dir: root.join("cache"),
+            state_dir: root.join("state"),
+            data_dir: root.join("data"),
+        }
+    }
+
+    pub fn settings_file(&self) -> PathBuf { self.config_dir.join("settings.toml") }
+    /// Provider type definitions (`*.toml`), see `providers::plugins`.
+    pub fn plugin_dir(&self) -> PathBuf { self.config_dir.join("providers.d") }
+    pub fn log_dir(&self) -> PathBuf { self.state_dir.join("logs") }
+    pub fn usage_file(&self) -> PathBuf { self.state_dir.join("usage.json") }
+    pub fn backup_dir(&self) -> PathBuf { self.data_dir.join("backups") }
+    pub fn export_dir(&self) -> PathBuf { self.data_dir.join("exports") }
+
+    /// Global chi-llm config written by Build (owned by chi-llm, not chi-tui).
+    pub fn global_config(&self) -> Option<PathBuf> {
+        dirs::home_dir().map(|h| h.join(".cache").join("chi_llm").join("model_config.json"))
+    }
+
+    /// Labelled locations for the Settings page.
+    pub fn locations(&self) -> Vec<(&'static str, String)> {
+        let show = |p: &Path| p.display().to_string();
+        vec![
+            ("Settings", show(&self.settings_file())),
+            ("Provider plugins", show(&self.plugin_dir())),
+            ("Cache", show(&self.cache_dir)),
+            ("Logs", show(&self.log_dir())),
+            // usage.json, recent.json, latency.json, model_meta.json, custom_models.json, last_version
+            ("State", show(&self.state_dir)),
+            ("Backups", show(&self.backup_dir())),
+            ("Exports", show(&self.export_dir())),
+            ("Provider scratch", format!("./{}", SCRATCH_FILE)),
+            ("Project config", format!("./{}", PROJECT_CONFIG)),
+            ("Global config", self.global_config().map(|p| show(&p)).unwrap_or_else(|| "(no home dir)".to_string())),
+        ]
+    }
+}
+
+static PATHS: OnceLock<AppPaths> = OnceLock::new();
+
+/// Process-wide paths, resolved on first use.
+pub fn paths() -> &'static AppPaths {
+    PATHS.get_or_init(AppPaths::resolve)
+}
+
+/// Use `p` instead of the resolved paths, e.g. a temporary directory in tests.
+/// Only the first call before any `paths()` takes effect; false otherwise.
+pub fn set_paths(p: AppPaths) -> bool {
+    PATHS.set(p).is_ok()
+}
+
+/// Create `dir` (and parents) and return `dir/name`.
+pub fn file_in(dir: &Path, name: &str) -> io::Result<PathBuf> {
+    std::fs::create_dir_all(dir)?;
+    Ok(dir.join(name))
+}
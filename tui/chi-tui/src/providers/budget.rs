@@ -0,0 +1,188 @@
+//! Monthly budgets and estimated spend for cloud providers, kept in `usage.json`
+//! under the state directory:
+//!
+//! ```json
+//! {"budgets": {"p1": 20.0},
+//!  "usage": {"p1": {"2026-10": {"input_tokens": 1200, "output_tokens": 300}}}}
+//! ```
+//!
+//! This is TUI-only bookkeeping: chi-llm itself does not count tokens, so the
+//! counters hold the TUI's own end-to-end tests and benchmarks (plus whatever
+//! other tools choose to add to the same file), not all traffic through the
+//! provider. Spend is estimated from the price table in `pricing.toml`, which
+//! `settings.toml` `[pricing]` can override.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::sync::OnceLock;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::paths::paths;
+
+use super::local::is_local_type;
+
+/// USD per million tokens.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Deserialize)]
+pub struct Price {
+    pub input: f64,
+    pub output: f64,
+}
+
+/// Built-in prices from `pricing.toml`, by model-id prefix.
+fn builtin_prices() -> &'static BTreeMap<String, Price> {
+    static PRICES: OnceLock<BTreeMap<String, Price>> = OnceLock::new();
+    PRICES.get_or_init(|| toml::from_str(include_str!("../../pricing.toml")).expect("pricing.toml is valid"))
+}
+
+/// Price for `model`: the longest prefix match in `overrides` or `pricing.toml`.
+pub fn price_for(model: &str, overrides: &BTreeMap<String, Price>) -> Option<Price> {
+    let builtin = builtin_prices().iter().map(|(m, p)| (m.as_str(), *p));
+    let custom = overrides.iter().map(|(m, p)| (m.as_str(), *p));
+    // Overrides come last so they win ties
+    builtin.chain(custom)
+        .filter(|(prefix, _)| model.starts_with(prefix))
+        .max_by_key(|(prefix, _)| prefix.len())
+        .map(|(_, p)| p)
+}
+
+/// Providers billed per token (not local models or local servers).
+pub fn is_cloud_type(ptype: &str) -> bool {
+    !is_local_type(ptype) && !matches!(ptype, "lmstudio" | "ollama")
+}
+
+/// Rough token count for text (about four characters per token).
+pub fn estimate_tokens(text: &str) -> u64 {
+    (text.chars().count() as u64).div_ceil(4)
+}
+
+pub fn current_month() -> String {
+    chrono::Local::now().format("%Y-%m").to_string()
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UsageCounters {
+    #[serde(default)]
+    pub input_tokens: u64,
+    #[serde(default)]
+    pub output_tokens: u64,
+}
+
+impl UsageCounters {
+    pub fn cost(&self, price: Price) -> f64 {
+        (self.input_tokens as f64 * price.input + self.output_tokens as f64 * price.output) / 1_000_000.0
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct UsageBook {
+    /// Monthly budget in USD by provider id.
+    #[serde(default)]
+    pub budgets: BTreeMap<String, f64>,
+    /// Provider id -> `YYYY-MM` -> counters.
+    #[serde(default)]
+    pub usage: BTreeMap<String, BTreeMap<String, UsageCounters>>,
+}
+
+impl UsageBook {
+    /// Read `usage.json`; a missing or unreadable file gives an empty book.
+    pub fn load() -> Self {
+        fs::read_to_string(paths().usage_file()).ok().and_then(|t| serde_json::from_str(&t).ok()).unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = paths().usage_file();
+        if let Some(dir) = path.parent() { fs::create_dir_all(dir)?; }
+        fs::write(path, serde_json::to_vec_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn month(&self, id: &str, month: &str) -> UsageCounters {
+        self.usage.get(id).and_then(|m| m.get(month)).copied().unwrap_or_default()
+    }
+
+    pub fn record(&mut self, id: &str, month: &str, input_tokens: u64, output_tokens: u64) {
+        let c = self.usage.entry(id.to_string()).or_default().entry(month.to_string()).or_default();
+        c.input_tokens += input_tokens;
+        c.output_tokens += output_tokens;
+    }
+
+    /// Move the budget and counters of a provider whose id changed.
+    pub fn rename(&mut self, old: &str, new: &str) {
+        if let Some(b) = self.budgets.remove(old) { self.budgets.insert(new.to_string(), b); }
+        if let Some(u) = self.usage.remove(old) { self.usage.insert(new.to_string(), u); }
+    }
+
+    /// Set (or with `None`, remove) the monthly budget of `id`.
+    pub fn set_budget(&mut self, id: &str, usd: Option<f64>) {
+        match usd {
+            Some(v) => { self.budgets.insert(id.to_string(), v); }
+            None => { self.budgets.remove(id); }
+        }
+    }
+}
+
+/// Spend against a budget for one month.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct BudgetStatus {
+    pub spent: f64,
+    pub budget: f64,
+    /// False when the model has no known price (spend shows as 0).
+    pub priced: bool,
+}
+
+impl BudgetStatus {
+    pub fn ratio(&self) -> f64 {
+        if self.budget > 0.0 { self.spent / self.budget } else { 0.0 }
+    }
+
+    /// At or above 80% of the budget.
+    pub fn is_warning(&self) -> bool {
+        self.ratio() >= 0.8
+    }
+
+    /// `$1.20/$20.00 ▓▓░░░░░░░░ 6%`
+    pub fn bar(&self, width: usize) -> String {
+        let filled = ((self.ratio().min(1.0) * width as f64).round() as usize).min(width);
+        let price = if self.priced { "" } else { " (no price for model)" };
+        format!("${:.2}/${:.2} {}{} {:.0}%{}", self.spent, self.budget, "▓".repeat(filled), "░".repeat(width - filled), self.ratio() * 100.0, price)
+    }
+}
+
+/// Budget status of provider `id` running `model` this month, if it has a budget.
+pub fn budget_status(book: &UsageBook, id: &str, model: &str, overrides: &BTreeMap<String, Price>) -> Option<BudgetStatus> {
+    let budget = *book.budgets.get(id)?;
+    let price = price_for(model, overrides);
+    let spent = price.map(|p| book.month(id, &current_month()).cost(p)).unwrap_or(0.0);
+    Some(BudgetStatus { spent, budget, priced: price.is_some() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spend_is_estimated_against_budget() {
+        let mut overrides = BTreeMap::new();
+        assert_eq!(price_for("gpt-4o-mini-2024-07-18", &overrides), Some(Price { input: 0.15, output: 0.60 }));
+        assert_eq!(price_for("gpt-4o", &overrides).map(|p| p.input), Some(2.50));
+        assert_eq!(price_for("mystery", &overrides), None);
+        overrides.insert("gpt-4o".to_string(), Price { input: 1.0, output: 2.0 });
+        assert_eq!(price_for("gpt-4o", &overrides).map(|p| p.input), Some(1.0));
+
+        let mut book = UsageBook::default();
+        book.set_budget("p1", Some(10.0));
+        let month = current_month();
+        book.record("p1", &month, 2_000_000, 1_000_000);
+        let st = budget_status(&book, "p1", "gpt-4o", &overrides).unwrap();
+        assert_eq!(st.spent, 4.0);
+        assert!(!st.is_warning());
+        book.record("p1", &month, 0, 2_000_000);
+        let st = budget_status(&book, "p1", "gpt-4o", &overrides).unwrap();
+        assert!(st.is_warning());
+        assert!(st.bar(10).starts_with("$8.00/$10.00 ▓▓▓▓▓▓▓▓░░ 80%"));
+        assert!(budget_status(&book, "p2", "gpt-4o", &overrides).is_none());
+        assert!(builtin_prices().contains_key("claude-sonnet-4"));
+    }
+}
@@ -0,0 +1,494 @@
+use std::sync::mpsc::Sender;
+
+use anyhow::anyhow;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::layout::Rect;
+use ratatui::prelude::Frame;
+
+use crate::app::{App, Notification, Page};
+use crate::clipboard::{copy_message, copy_text, paste_text};
+use crate::controller::PageController;
+use crate::forms::{FormEvent, FormState};
+use crate::jobs::Jobs;
+use crate::keychain::{self, account_for, keyring_account, keyring_ref};
+use crate::keymap::KeyAction;
+use crate::modal::{ConfirmModal, DropdownModal, Modal, PromptModal, TextEditorModal};
+use crate::mouse::HitTarget;
+use crate::msg::Msg;
+use crate::tutorial::{self, Milestone};
+
+use super::probe::TestMode;
+use super::budget::is_cloud_type;
+use super::local::tuning_errors;
+use super::network::network_errors;
+use super::generate::{Generation, GenerationModal};
+use super::fork::ForkModal;
+use super::import::offer_import;
+use super::ports::warn_about_ports;
+use super::presets::{available_presets, preset_by_label};
+use super::pull::spawn_ollama_pull;
+use super::state::{load_providers_state, parse_config_json, parse_provider_json, ProvidersPane, ProvidersState, BUTTON_SAVE, BUTTON_TEST, LEAD_ID, LEAD_NAME};
+use super::view::draw_providers_catalog;
+
+pub struct ConfigurePage;
+
+impl PageController for ConfigurePage {
+    fn handle_key(&self, app: &mut App, key: KeyEvent) -> Option<Msg> {
+        if matches!(key.code, KeyCode::Char('r') | KeyCode::Char('R')) && app.providers.is_error() {
+            app.providers.load(&app.jobs, "Loading providers", |_| load_providers_state());
+            return Some(Msg::Handled);
+        }
+        let notify = app.notify_tx.clone();
+        let st = app.providers.ready_mut()?;
+        // Tab / Shift+Tab cycle focus between list and form (unless the form holds input)
+        if !st.form.as_ref().is_some_and(|f| f.captures_input()) && st.focus.handle_key(key) {
+            if st.form_focused() { st.focus_form(); }
+            return Some(Msg::Handled);
+        }
+        // Toggle the JSON preview pane from either pane (unless typing in a field)
+        if key.code == KeyCode::Char('v') && !st.form.as_ref().is_some_and(|f| f.captures_input()) {
+            st.show_json = !st.show_json;
+            return Some(Msg::Handled);
+        }
+        if st.form_focused() {
+            return handle_form_key(st, &mut app.modals, key, notify, &app.jobs).then_some(Msg::Handled);
+        }
+
+        // Left pane: list navigation and actions
+        if key.modifiers.contains(KeyModifiers::CONTROL) {
+            let (done, msg) = match key.code {
+                KeyCode::Char('z') if st.undo_list() => (true, "Undid the last add/delete; s to save"),
+                KeyCode::Char('z') => (false, "Nothing to undo"),
+                KeyCode::Char('y') if st.redo_list() => (true, "Redid the add/delete; s to save"),
+                KeyCode::Char('y') => (false, "Nothing to redo"),
+                _ => return None,
+            };
+            let _ = notify.send(Notification::new(msg, done, Page::Configure));
+            return Some(Msg::Handled);
+        }
+        match key.code {
+            KeyCode::Up if st.selected > 0 => { st.selected -= 1; st.form = None; },
+            KeyCode::Down if st.selected + 1 < st.len_with_add() => { st.selected += 1; st.form = None; },
+            KeyCode::Enter => st.focus_form(),
+            KeyCode::Char('a') => { st.add_default(); st.focus_form(); }
+            // Common setups with the fields filled in
+            KeyCode::Char('A') => {
+                let items = available_presets(&st.schema_types).iter().map(|p| p.label()).collect();
+                app.modals.push(Box::new(DropdownModal::new("Add from preset", items, "", |app, label| {
+                    let Some(preset) = preset_by_label(&label) else { return };
+                    let Some(st) = app.providers.ready_mut() else { return };
+                    let name = st.add_preset(preset).name.clone();
+                    st.focus_form();
+                    let _ = app.notify_tx.send(Notification::new(format!("Added {}; check the fields, then s to save", name), true, Page::Configure));
+                }).remember("preset")));
+            }
+            // New provider pre-filled from the selected one; a mini-form asks for what differs
+            KeyCode::Char('n') | KeyCode::Char('N') if st.selected < st.entries.len() => {
+                if let Some(m) = ForkModal::for_selected(app) { app.modals.push(Box::new(m)); }
+            }
+            // Exact copy with a fresh id, e.g. for another endpoint of the same kind
+            KeyCode::Char('c') | KeyCode::Char('C') if st.selected < st.entries.len() => {
+                if let Some(copy) = st.clone_selected() {
+                    let _ = notify.send(Notification::new(format!("Cloned as {}; edit it, then s to save", copy.name), true, Page::Configure));
+                }
+                st.focus_form();
+            }
+            KeyCode::Char('d') | KeyCode::Char('D') if st.selected < st.entries.len() => {
+                let name = st.entries[st.selected].name.clone();
+                app.modals.push(Box::new(ConfirmModal::new("Delete provider", format!("Delete provider '{}'?", name), |app| {
+                    if let Some(st) = app.providers.ready_mut() { st.delete_selected(); st.form = None; }
+                })));
+            }
+            KeyCode::Char('m') | KeyCode::Char('M') => return Some(Msg::Goto(Page::ModelBrowser)),
+            KeyCode::Char('i') | KeyCode::Char('I') => {
+                if !offer_import(app) {
+                    let _ = notify.send(Notification::new("No .chi_llm.json/.yaml or global config to import", false, Page::Configure));
+                }
+            }
+            // Maintenance mode: keep the config but take the provider out of use
+            KeyCode::Char('x') | KeyCode::Char('X') if st.selected < st.entries.len() => {
+                let entry = &mut st.entries[st.selected];
+                entry.disabled = !entry.disabled;
+                let state = if entry.disabled { "disabled" } else { "enabled" };
+                let _ = notify.send(Notification::new(format!("{} {}; s to save", entry.name, state), true, Page::Configure));
+            }
+            // Share providers as JSON through the clipboard (secrets stay local)
+            KeyCode::Char('y') => {
+                let Some(json) = st.selected_shareable() else { return Some(Msg::Handled) };
+                let text = serde_json::to_string_pretty(&json).unwrap_or_default();
+                let (msg, ok) = copy_message(&format!("{} as JSON (secrets omitted)", st.entries[st.selected].name), copy_text(&text));
+                let _ = notify.send(Notification::new(msg, ok, Page::Configure));
+            }
+            KeyCode::Char('u') => {
+                let Some(uri) = st.selected_uri() else { return Some(Msg::Handled) };
+                let (msg, ok) = copy_message(&format!("{} as URI (secrets omitted)", st.entries[st.selected].name), copy_text(&uri));
+                let _ = notify.send(Notification::new(msg, ok, Page::Configure));
+            }
+            KeyCode::Char('p') => {
+                let (msg, ok) = match paste_text().and_then(|t| parse_provider_json(&t)) {
+                    Ok(entries) => (format!("Pasted {} provider(s); s to save", st.add_entries(entries)), true),
+                    Err(e) => (format!("Paste failed: {}", e), false),
+                };
+                let _ = notify.send(Notification::new(msg, ok, Page::Configure));
+            }
+            // Advanced edit: the whole config as JSON, including keys the form does not show
+            KeyCode::Char('e') | KeyCode::Char('E') if st.selected < st.entries.len() => {
+                let Some(entry) = st.selected_with_form() else { return Some(Msg::Handled) };
+                let text = serde_json::to_string_pretty(&entry.config).unwrap_or_default();
+                let ptype = entry.ptype.clone();
+                app.modals.push(Box::new(TextEditorModal::new(format!("Edit {} config (JSON)", entry.name), &text, move |app, text| {
+                    let config = parse_config_json(text, &ptype).map_err(|e| e.to_string())?;
+                    if let Some(st) = app.providers.ready_mut() { st.set_selected_config(config); }
+                    let _ = app.notify_tx.send(Notification::new("Config updated; s to save", true, Page::Configure));
+                    Ok(())
+                })));
+            }
+            // Pull a model into the selected ollama server; progress goes to the log panel
+            KeyCode::Char('P') if st.entries.get(st.selected).is_some_and(|e| e.ptype == "ollama") => {
+                if st.pull_job.is_some() {
+                    let _ = notify.send(Notification::new("A pull is already running", false, Page::Configure));
+                    return Some(Msg::Handled);
+                }
+                let current = st.selected_with_form().and_then(|e| e.config.get("model").and_then(|m| m.as_str()).map(str::to_string));
+                let mut prompt = PromptModal::new("ollama pull (model name)", |app, model| {
+                    let (log, notify) = (app.log_tx.clone(), app.notify_tx.clone());
+                    let Some(st) = app.providers.ready_mut() else { return };
+                    let Some(entry) = st.selected_with_form() else { return };
+                    st.pull_job = Some(spawn_ollama_pull(&entry, model, log, notify, &app.jobs));
+                    app.show_log = true;
+                });
+                prompt.buffer = current.unwrap_or_default();
+                app.modals.push(Box::new(prompt));
+            }
+            // Monthly budget for cloud providers; spend shows as a bar in the list
+            KeyCode::Char('$') if st.entries.get(st.selected).is_some_and(|e| is_cloud_type(&e.ptype)) => {
+                let entry = &st.entries[st.selected];
+                let id = entry.id.clone();
+                let mut prompt = PromptModal::new(format!("Monthly budget in USD for {} (counts calls made from this TUI only; 0 removes)", entry.name), move |app, text| {
+                    match text.trim_start_matches('$').parse::<f64>() {
+                        Ok(v) if v.is_finite() && v >= 0.0 => {
+                            app.usage.set_budget(&id, (v > 0.0).then_some(v));
+                            if let Err(e) = app.usage.save() { app.report_error(format!("Saving budget failed: {e}")); }
+                        }
+                        _ => { let _ = app.notify_tx.send(Notification::new(format!("Not a budget: {}", text), false, Page::Configure)); }
+                    }
+                });
+                prompt.buffer = app.usage.budgets.get(&entry.id).map(|b| b.to_string()).unwrap_or_default();
+                app.modals.push(Box::new(prompt));
+            }
+            // Run an ad-hoc prompt; the reply streams into a popup
+            KeyCode::Char('g') if st.selected < st.entries.len() => {
+                let name = st.entries[st.selected].name.clone();
+                app.modals.push(Box::new(PromptModal::new(format!("Prompt for {}", name), |app, prompt| {
+                    let Some(entry) = app.providers.ready().and_then(|st| st.selected_with_form()) else { return };
+                    if let Some(old) = &app.generation { old.cancel(); }
+                    match Generation::start(&entry, prompt, &app.jobs) {
+                        Ok(g) => {
+                            app.modals.push(Box::new(GenerationModal::new(&g)));
+                            app.generation = Some(g);
+                        }
+                        Err(e) => { let _ = app.notify_tx.send(Notification::new(format!("Cannot run prompt: {}", e), false, Page::Configure)); }
+                    }
+                })));
+            }
+            KeyCode::Char('t') => { st.start_test(None, None, notify, &app.jobs); }
+            KeyCode::Char('T') => { st.start_test(Some(TestMode::E2e), None, notify, &app.jobs); }
+            // Save from left pane
+            KeyCode::Char('s') | KeyCode::Char('S') => match st.save() {
+                // The Routing page reloads the saved providers on its next visit
+                Ok(()) => {
+                    app.routing = None;
+                    let _ = notify.send(Notification::new("Saved providers to chi.tmp.json", true, Page::Configure));
+                    warn_about_ports(st.entries.clone(), notify, &app.jobs);
+                    let renamed = std::mem::take(&mut st.renamed_ids);
+                    if !renamed.is_empty() {
+                        for (old, new) in &renamed {
+                            app.latency.rename(old, new);
+                            app.usage.rename(old, new);
+                        }
+                        let _ = app.latency.save();
+                        let _ = app.usage.save();
+                        app.defaultp = None;
+                    }
+                    tutorial::reached(app, Milestone::ProvidersSaved);
+                }
+                Err(e) => app.report_error(format!("Save failed: {e}")),
+            },
+            _ => return None,
+        }
+        Some(Msg::Handled)
+    }
+
+    fn handle_click(&self, app: &mut App, target: HitTarget) -> bool {
+        let notify = app.notify_tx.clone();
+        let Some(st) = app.providers.ready_mut() else { return false };
+        match target {
+            HitTarget::ListRow(i) => {
+                if i >= st.len_with_add() { return false; }
+                if i != st.selected { st.selected = i; st.form = None; }
+                // Show the clicked provider's form right away, so its buttons can be clicked too
+                if st.form.is_none() { st.ensure_form(); }
+                if let Some(form) = &mut st.form { form.editing = false; }
+                st.focus.focus(ProvidersPane::List);
+            }
+            // A click selects the row; clicking a selected row or a button acts like Enter
+            HitTarget::FormRow(row) => {
+                let Some(form) = &mut st.form else { return false };
+                let was_selected = st.focus.is(ProvidersPane::Form) && form.selected == row;
+                form.select_row(row);
+                st.focus.focus(ProvidersPane::Form);
+                let act = form.button_index().is_some() || (was_selected && !form.editing);
+                if act {
+                    handle_form_key(st, &mut app.modals, KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE), notify, &app.jobs);
+                }
+            }
+            HitTarget::Toast(_) => return false,
+        }
+        true
+    }
+
+    fn tick(&self, app: &mut App) {
+        app.providers.ensure(&app.jobs, "Loading providers", |_| load_providers_state());
+        // Empty catalog: offer the existing chi-llm config once per load
+        if let Some(st) = app.providers.ready_mut().filter(|st| !st.import_offered) {
+            st.import_offered = true;
+            if st.entries.is_empty() { offer_import(app); }
+        }
+        let Some(st) = app.providers.ready_mut() else { return };
+        // A finished pull selects the model and refreshes the dropdown
+        if let Some(pull) = st.poll_pull().filter(|p| p.ok) {
+            if st.entries.get(st.selected).is_some_and(|e| e.id == pull.entry_id) {
+                if st.form.is_none() { st.ensure_form(); }
+                let fi = st.form.as_ref().and_then(|f| f.fields.iter().position(|ff| ff.schema.name == "model"));
+                if let (Some(fi), Some(form)) = (fi, st.form.as_mut()) {
+                    form.set_field(fi, pull.model);
+                    st.start_model_discovery(fi, &app.jobs);
+                }
+            }
+        }
+        // Offer discovered models once the background lookup finishes
+        let Some(outcome) = st.poll_discovery() else { return };
+        if st.entries.get(st.selected).map(|e| e.id.as_str()) != Some(outcome.entry_id.as_str()) { return; }
+        let Some(form) = &mut st.form else { return };
+        let fi = outcome.field;
+        match outcome.result {
+            Ok(found) if found.ids.is_empty() => form.message = Some(format!("No models discovered for {}", outcome.ptype)),
+            Ok(found) => {
+                form.message = None;
+                let dd = form.field_dropdown(fi, format!("Select model ({}):", outcome.ptype), found.ids, provider_form);
+                app.modals.push(Box::new(dd.mark("downloaded", found.downloaded)));
+            }
+            Err(e) => {
+                form.message = Some(format!("Discover failed: {}", e));
+                if let Some(dd) = form.activate_field(fi, provider_form) { app.modals.push(Box::new(dd)); }
+            }
+        }
+    }
+
+    fn draw(&self, f: &mut Frame, area: Rect, app: &App) {
+        draw_providers_catalog(f, area, app);
+    }
+
+    fn handle_paste(&self, app: &mut App, text: &str) -> bool {
+        let Some(st) = app.providers.ready_mut() else { return false };
+        if !st.form_focused() { return false; }
+        if st.form.is_none() && st.selected < st.entries.len() { st.ensure_form(); }
+        st.form.as_mut().is_some_and(|form| form.paste(text))
+    }
+
+    fn actions(&self) -> &'static [KeyAction] {
+        const ACTIONS: &[KeyAction] = &[
+            KeyAction::new("r", "retry").when(|app| app.providers.is_error()),
+            KeyAction::new("Tab/Shift+Tab", "switch").when(|app| app.providers.ready().is_some()),
+            KeyAction::new("↑/↓", "select").when(list_focused),
+            KeyAction::new("↑/↓", "field").when(form_focused),
+            KeyAction::new("Enter", "edit/Test/Cancel").when(form_focused),
+            KeyAction::new("[ Save ]", "keep edits").when(|app| form_focused(app) && with_form(app, |f| f.is_dirty())),
+            KeyAction::new("r", "revert field").when(|app| form_focused(app) && with_form(app, |f| f.selected_field().is_some_and(|ff| ff.is_changed()))),
+            KeyAction::new("k", "keychain").when(|app| form_focused(app) && with_form(app, |f| f.selected_field().is_some_and(|ff| ff.schema.ftype == "secret"))),
+            KeyAction::new("o", "session-only secret").when(|app| form_focused(app) && with_form(app, |f| f.selected_field().is_some_and(|ff| ff.schema.ftype == "secret"))),
+            KeyAction::new("Ctrl+Z/Y", "undo/redo").when(|app| form_focused(app) && with_form(app, |f| f.history.can_undo() || f.history.can_redo())),
+            KeyAction::new("Ctrl+Z/Y", "undo/redo").when(|app| list_focused(app) && app.providers.ready().is_some_and(|st| st.list_history.can_undo() || st.list_history.can_redo())),
+            KeyAction::new("a", "add").when(list_focused),
+            KeyAction::new("A", "add from preset").when(list_focused),
+            KeyAction::new("t", "test").when(|app| entry_selected(app) && app.providers.ready().is_some_and(|st| st.test_job.is_none())),
+            KeyAction::new("T", "e2e test").when(|app| entry_selected(app) && app.providers.ready().is_some_and(|st| st.test_job.is_none())),
+            KeyAction::new("s", "save").when(list_focused),
+            KeyAction::new("g", "prompt").when(entry_selected),
+            KeyAction::new("v", "JSON preview").when(|app| app.providers.ready().is_some()),
+            KeyAction::new("n", "new from selected").when(entry_selected),
+            KeyAction::new("c", "clone").when(entry_selected),
+            KeyAction::new("d", "delete").when(entry_selected),
+            KeyAction::new("x", "enable/disable").when(entry_selected),
+            KeyAction::new("P", "ollama pull").when(|app| entry_selected(app) && app.providers.ready().is_some_and(|st| st.pull_job.is_none() && selected_type(st) == Some("ollama"))),
+            KeyAction::new("$", "budget").when(|app| entry_selected(app) && app.providers.ready().and_then(selected_type).is_some_and(is_cloud_type)),
+            KeyAction::new("e", "edit JSON").when(entry_selected),
+            KeyAction::new("y/u", "copy JSON/URI").when(entry_selected),
+            KeyAction::new("p", "paste").when(list_focused),
+            KeyAction::new("m", "models").when(list_focused),
+            KeyAction::new("i", "import config").when(list_focused),
+            KeyAction::new("Esc", "back"),
+        ];
+        ACTIONS
+    }
+
+    fn captures_input(&self, app: &App) -> bool {
+        app.providers.ready().is_some_and(|st| st.form_focused() && st.form.as_ref().is_some_and(|f| f.captures_input()))
+    }
+}
+
+fn list_focused(app: &App) -> bool {
+    app.providers.ready().is_some_and(|st| !st.form_focused())
+}
+
+fn form_focused(app: &App) -> bool {
+    app.providers.ready().is_some_and(|st| st.form_focused())
+}
+
+/// A provider (not the add row) is selected on the list.
+fn entry_selected(app: &App) -> bool {
+    list_focused(app) && app.providers.ready().is_some_and(|st| !st.is_add_row())
+}
+
+fn selected_type(st: &ProvidersState) -> Option<&str> {
+    st.entries.get(st.selected).map(|e| e.ptype.as_str())
+}
+
+fn with_form(app: &App, f: impl Fn(&FormState) -> bool) -> bool {
+    app.providers.ready().and_then(|st| st.form.as_ref()).is_some_and(f)
+}
+
+fn provider_form(app: &mut App) -> Option<&mut FormState> {
+    app.providers.ready_mut()?.form.as_mut()
+}
+
+/// Right pane: inline form. Dropdowns go onto `modals`. Returns true if the key was consumed.
+fn handle_form_key(st: &mut ProvidersState, modals: &mut Vec<Box<dyn Modal>>, key: KeyEvent, notify: Sender<Notification>, jobs: &Jobs) -> bool {
+    if st.form.is_none() && st.selected < st.entries.len() { st.ensure_form(); }
+    let Some(form) = &mut st.form else { return false };
+    match form.handle_key(key) {
+        // o: keep the selected secret in memory only (or persist it again)
+        FormEvent::Ignored if key.code == KeyCode::Char('o') => {
+            let Some(fi) = form.field_index() else { return false };
+            if form.fields[fi].schema.ftype != "secret" { return false; }
+            let Some(entry) = st.entries.get_mut(st.selected) else { return false };
+            let on = entry.toggle_session_secret(&form.fields[fi].schema.name);
+            form.fields[fi].session_only = on;
+            form.message = Some(if on { "Session only: chi.tmp.json gets a ${VAR} reference instead" } else { "Secret will be written to chi.tmp.json" }.to_string());
+        }
+        // k: move the selected secret into the system keychain (or back into the config)
+        FormEvent::Ignored if key.code == KeyCode::Char('k') => {
+            let Some(fi) = form.field_index() else { return false };
+            if form.fields[fi].schema.ftype != "secret" { return false; }
+            let Some(entry) = st.entries.get_mut(st.selected) else { return false };
+            let (name, value) = (form.fields[fi].schema.name.clone(), form.fields[fi].buffer.trim().to_string());
+            let verified = form.is_verified();
+            let result = match keyring_account(&value) {
+                Some(account) => keychain::load(account).map(|secret| (secret, format!("{} taken back from the keychain", name))),
+                None if value.is_empty() || value.starts_with("${") => Err(anyhow!("Type the secret first")),
+                None => {
+                    let account = account_for(&entry.id, &name);
+                    keychain::store(&account, &value).map(|()| {
+                        // The config only holds the reference now, so it need not be session-only
+                        if entry.session_secrets.contains(&name) { entry.toggle_session_secret(&name); }
+                        (keyring_ref(&account), format!("Stored in the keychain; the config gets {} (chi-llm reads it with its keyring extra)", keyring_ref(&account)))
+                    })
+                }
+            };
+            match result {
+                Ok((buffer, msg)) => {
+                    form.set_field(fi, buffer);
+                    form.fields[fi].session_only = entry.session_secrets.contains(&name);
+                    // Same secret for the CLI either way, so a passed test still counts
+                    if verified { form.verified_hash = Some(form.hash()); }
+                    form.message = Some(msg);
+                }
+                Err(e) => form.message = Some(e.to_string()),
+            }
+        }
+        FormEvent::Ignored => return false,
+        FormEvent::Handled => {}
+        FormEvent::Exit => st.focus.focus(ProvidersPane::List),
+        FormEvent::Lead(LEAD_NAME) => {
+            let Some(entry) = st.entries.get(st.selected) else { return true };
+            let mut prompt = PromptModal::new("Provider name", |app, name| {
+                let Some(st) = app.providers.ready_mut() else { return };
+                let msg = match st.rename_selected(&name) { Ok(()) => "Renamed; s to save".to_string(), Err(e) => e };
+                if let Some(form) = &mut st.form { form.message = Some(msg); }
+            });
+            prompt.buffer = entry.name.clone();
+            modals.push(Box::new(prompt));
+        }
+        FormEvent::Lead(LEAD_ID) => {
+            let Some(entry) = st.entries.get(st.selected) else { return true };
+            let mut prompt = PromptModal::new("Provider id (letters, digits, - _ .)", |app, id| {
+                let Some(st) = app.providers.ready_mut() else { return };
+                let msg = match st.set_selected_id(&id) { Ok(()) => "Id changed; s to save".to_string(), Err(e) => e };
+                if let Some(form) = &mut st.form { form.message = Some(msg); }
+            });
+            prompt.buffer = entry.id.clone();
+            modals.push(Box::new(prompt));
+        }
+        // The last lead row: the provider type selector
+        FormEvent::Lead(_) => {
+            let current = st.entries.get(st.selected).map(|e| e.ptype.clone()).unwrap_or_default();
+            modals.push(Box::new(DropdownModal::new("Select Provider Type", st.schema_types.clone(), &current, |app, ptype| {
+                let Some(st) = app.providers.ready_mut() else { return };
+                if st.selected >= st.entries.len() { return; }
+                st.entries[st.selected].ptype = ptype;
+                st.ensure_form();
+                if let Some(form) = &mut st.form {
+                    form.select_first_field();
+                    form.message = Some("Type changed".to_string());
+                }
+            }).remember("provider type")));
+        }
+        FormEvent::Field(fi) => {
+            // Special-case: dynamic model list via CLI discovery, using current form values.
+            // Discovery runs in the background; `tick` opens the dropdown when it finishes
+            let is_model = form.fields.get(fi).is_some_and(|ff| ff.schema.name == "model");
+            if !(is_model && st.start_model_discovery(fi, jobs)) {
+                let Some(form) = &mut st.form else { return true };
+                if let Some(m) = form.field_modal(fi, provider_form) { modals.push(m); }
+            }
+        }
+        FormEvent::Button(BUTTON_TEST) => {
+            // Run test in the background; the result arrives via poll_background
+            if st.test_in_progress {
+                form.message = Some("Test already running…".to_string());
+            } else {
+                form.message = Some("Testing… (Ctrl+X stops)".to_string());
+                form.verified_hash = None;
+                let hash = form.hash();
+                st.start_test(None, Some(hash), notify, jobs);
+            }
+        }
+        FormEvent::Button(BUTTON_SAVE) => {
+            let missing = form.missing_required();
+            let mut invalid = form.type_errors();
+            invalid.extend(tuning_errors(&form.fields));
+            invalid.extend(network_errors(&form.fields));
+            if !missing.is_empty() {
+                form.message = Some(format!("Missing required: {}", missing.join(", ")));
+            } else if !invalid.is_empty() {
+                form.message = Some(invalid.join("; "));
+            } else if !form.can_save() {
+                // Changed values must pass a test before they are saved
+                form.message = Some("Run Test connection first".to_string());
+            } else {
+                if let Some(obj) = st.entries.get_mut(st.selected).and_then(|e| e.config.as_object_mut()) {
+                    obj.extend(form.to_config());
+                }
+                form.message = Some("Saved".to_string());
+                form.mark_saved();
+                if let Some(entry) = st.entries.get(st.selected) { warn_about_ports(vec![entry.clone()], notify, jobs); }
+            }
+        }
+        // Cancel
+        FormEvent::Button(_) => {
+            form.editing = false;
+            st.focus.focus(ProvidersPane::List);
+        }
+    }
+    true
+}
@@ -0,0 +1,78 @@
+//! "New from selected" (`n` on the Configure list): a small form asking only for
+//! what usually differs between near-identical providers (name, endpoint,
+//! model). The copy keeps everything else from the selected entry, including
+//! secrets, and opens in the full form for a test.
+
+use crossterm::event::KeyEvent;
+use ratatui::layout::Rect;
+use ratatui::prelude::Frame;
+use ratatui::widgets::Clear;
+
+use crate::app::{App, Notification, Page};
+use crate::forms::{draw_form, FormEvent, FormState};
+use crate::modal::{Modal, ModalResult};
+use crate::util::centered_rect;
+
+const BUTTON_CREATE: usize = 0;
+
+pub struct ForkModal {
+    source: String,
+    form: FormState,
+}
+
+impl ForkModal {
+    /// Mini-form for the selected provider of `app`; `None` when none is selected.
+    pub fn for_selected(app: &App) -> Option<Self> {
+        let st = app.providers.ready()?;
+        let entry = st.entries.get(st.selected)?;
+        let mut form = FormState::new(Vec::new(), st.fork_fields(), vec!["Create", "Cancel"]);
+        form.message = Some("Enter edits a field; the rest is copied".to_string());
+        Some(Self { source: entry.name.clone(), form })
+    }
+
+    fn create(&mut self, app: &mut App) -> ModalResult {
+        let missing = self.form.missing_required();
+        if !missing.is_empty() {
+            self.form.message = Some(format!("Missing required: {}", missing.join(", ")));
+            return ModalResult::Keep;
+        }
+        let invalid = self.form.type_errors();
+        if !invalid.is_empty() {
+            self.form.message = Some(invalid.join("; "));
+            return ModalResult::Keep;
+        }
+        let mut values = self.form.to_config();
+        let name = values.remove("name").and_then(|v| v.as_str().map(|s| s.trim().to_string())).unwrap_or_default();
+        let Some(st) = app.providers.ready_mut() else { return ModalResult::Close };
+        let Some(entry) = st.fork_selected(name, values) else { return ModalResult::Close };
+        let msg = format!("Created {} from {}; test it, then s to save", entry.name, self.source);
+        st.focus_form();
+        let _ = app.notify_tx.send(Notification::new(msg, true, Page::Configure));
+        ModalResult::Close
+    }
+}
+
+impl Modal for ForkModal {
+    fn label(&self) -> String {
+        format!("New from {}", self.source)
+    }
+
+    fn handle_key(&mut self, app: &mut App, key: KeyEvent) -> ModalResult {
+        match self.form.handle_key(key) {
+            FormEvent::Field(_) => self.form.editing = true,
+            FormEvent::Button(BUTTON_CREATE) => return self.create(app),
+            FormEvent::Button(_) | FormEvent::Exit => return ModalResult::Close,
+            _ => {}
+        }
+        ModalResult::Keep
+    }
+
+    fn draw(&self, f: &mut Frame, area: Rect, app: &App) {
+        // Field rows, the message line and the button row
+        let h = (3 * self.form.fields.len() + 4) as u16;
+        let area = centered_rect(60, 100, area);
+        let area = Rect { y: area.y + area.height.saturating_sub(h) / 2, height: h.min(area.height), ..area };
+        f.render_widget(Clear, area);
+        draw_form(f, area, app, &self.form, true, &format!("New from {}", self.source), &[]);
+    }
+}
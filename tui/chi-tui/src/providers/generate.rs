@@ -0,0 +1,140 @@
+//! Ad-hoc prompts against a provider (`g` on the Configure list). Runs
+//! `chi-llm generate --stream` and shows the reply in a popup as tokens arrive;
+//! closing the popup stops the run.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::layout::Rect;
+use ratatui::prelude::Frame;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, Wrap};
+
+use crate::app::App;
+use crate::jobs::{Job, Jobs};
+use crate::modal::{Modal, ModalResult};
+use crate::page_data::spinner;
+use crate::util::centered_rect;
+
+use super::probe::{build_generate_args, PROMPT_MAX_TOKENS};
+use super::state::ProviderScratchEntry;
+
+/// A prompt run; output grows as `poll` picks up chunks.
+pub struct Generation {
+    pub entry_id: String,
+    pub provider: String,
+    pub prompt: String,
+    pub output: String,
+    /// Set when the run ends: `Ok`, or the error text.
+    pub result: Option<Result<(), String>>,
+    pub started: Instant,
+    pub elapsed: Option<Duration>,
+    chunks: Receiver<String>,
+    job: Job<Result<(), String>>,
+}
+
+impl Generation {
+    /// Start `prompt` against `entry` as a job.
+    pub fn start(entry: &ProviderScratchEntry, prompt: String, jobs: &Jobs) -> Result<Self, String> {
+        let cmd = build_generate_args(entry, &prompt, PROMPT_MAX_TOKENS)?;
+        let (tx, chunks) = mpsc::channel();
+        let job = jobs.spawn(format!("Prompting {}", entry.name), move |ctx| {
+            cmd.stream(ctx.cancel_flag(), |s| { let _ = tx.send(s.to_string()); }).map_err(|e| e.to_string())
+        });
+        Ok(Self {
+            entry_id: entry.id.clone(),
+            provider: entry.name.clone(),
+            prompt,
+            output: String::new(),
+            result: None,
+            started: Instant::now(),
+            elapsed: None,
+            chunks,
+            job,
+        })
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.result.is_none()
+    }
+
+    /// Append arrived output; true when the run finished during this call.
+    pub fn poll(&mut self) -> bool {
+        if !self.is_running() { return false; }
+        // The result is sent after the last chunk, so check for it first
+        let done = self.job.poll();
+        while let Ok(s) = self.chunks.try_recv() { self.output.push_str(&s); }
+        let Some(res) = done else { return false };
+        self.result = Some(res.and_then(|r| r));
+        self.elapsed = Some(self.started.elapsed());
+        true
+    }
+
+    /// Stop a running prompt (kills the CLI).
+    pub fn cancel(&self) {
+        self.job.cancel();
+    }
+
+    pub fn was_cancelled(&self) -> bool {
+        self.job.is_cancelled()
+    }
+}
+
+/// Popup following `App::generation`. Dropping it (Esc, or Enter once done)
+/// cancels the run if it is still going.
+pub struct GenerationModal {
+    cancel: Arc<AtomicBool>,
+}
+
+impl GenerationModal {
+    pub fn new(generation: &Generation) -> Self {
+        Self { cancel: generation.job.cancel_handle() }
+    }
+}
+
+impl Drop for GenerationModal {
+    fn drop(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+impl Modal for GenerationModal {
+    fn label(&self) -> String {
+        "Prompt output".to_string()
+    }
+
+    fn handle_key(&mut self, app: &mut App, key: KeyEvent) -> ModalResult {
+        let running = app.generation.as_ref().is_some_and(|g| g.is_running());
+        if key.code == KeyCode::Enter && !running { ModalResult::Close } else { ModalResult::Keep }
+    }
+
+    fn draw(&self, f: &mut Frame, area: Rect, app: &App) {
+        let area = centered_rect(80, 70, area);
+        let Some(g) = &app.generation else { return };
+        let (status, hint) = match (&g.result, g.elapsed) {
+            (None, _) => (format!("{} generating…", spinner(app)), "Esc: stop"),
+            (Some(Ok(())), Some(t)) => (format!("done in {:.1}s", t.as_secs_f64()), "Enter/Esc: close"),
+            (Some(Ok(())), None) => ("done".to_string(), "Enter/Esc: close"),
+            (Some(Err(_)), _) => ("failed".to_string(), "Enter/Esc: close"),
+        };
+        let mut lines = vec![Line::from(Span::styled(format!("> {}", g.prompt), app.theme.hint())), Line::from("")];
+        lines.extend(g.output.lines().map(|l| Line::from(l.to_string())));
+        if let Some(Err(e)) = &g.result { lines.push(Line::from(Span::styled(e.clone(), app.theme.error()))); }
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(hint, app.theme.hint())));
+        // Follow the tail: estimate wrapped rows and scroll the last ones into view
+        let width = area.width.saturating_sub(2).max(1) as usize;
+        let rows: usize = lines.iter().map(|l| l.width().max(1).div_ceil(width)).sum();
+        let scroll = rows.saturating_sub(area.height.saturating_sub(2) as usize) as u16;
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(app.theme.focused_border())
+            .title(format!("Prompt → {} ({})", g.provider, status));
+        let p = Paragraph::new(lines).style(app.theme.base()).wrap(Wrap { trim: false }).scroll((scroll, 0)).block(block);
+        f.render_widget(Clear, area);
+        f.render_widget(p, area);
+    }
+}
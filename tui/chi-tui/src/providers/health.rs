@@ -0,0 +1,63 @@
+//! Startup check of the default provider from `chi.tmp.json`, shown as a dot in
+//! the header. Only the quick connection test is used, so starting chi-tui never
+//! loads a local model or spends cloud tokens; other types show as unchecked.
+
+use crate::jobs::{Job, Jobs};
+
+use super::probe::{build_test_args, probe_provider, TestMode};
+use super::select_default::load_providers_scratch;
+use super::state::load_scratch_entries;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HealthStatus {
+    Checking,
+    Ok,
+    Failed,
+    /// The type has no quick test.
+    Unchecked,
+}
+
+/// Latest health of the default provider.
+#[derive(Clone, Debug)]
+pub struct DefaultHealth {
+    pub name: String,
+    pub status: HealthStatus,
+    pub detail: String,
+}
+
+impl DefaultHealth {
+    /// Header text, e.g. `● ollama: ok`.
+    pub fn label(&self) -> String {
+        let state = match self.status {
+            HealthStatus::Checking => "checking…",
+            HealthStatus::Ok => "ok",
+            HealthStatus::Failed => "failing",
+            HealthStatus::Unchecked => "not checked",
+        };
+        format!("● {}: {}", self.name, state)
+    }
+}
+
+/// Start checking the default provider. Returns the initial state and the
+/// job delivering the result, or `None` when no default provider is set.
+pub fn spawn_default_health_check(jobs: &Jobs) -> Option<(DefaultHealth, Option<Job<DefaultHealth>>)> {
+    let default_id = load_providers_scratch().ok()?.current_default_id?;
+    let entry = load_scratch_entries().ok()?.into_iter().find(|e| e.id == default_id)?;
+    let name = entry.name.clone();
+    if entry.disabled {
+        return Some((DefaultHealth { name, status: HealthStatus::Unchecked, detail: "disabled".to_string() }, None));
+    }
+    if let Err(reason) = build_test_args(&entry, TestMode::Connection) {
+        return Some((DefaultHealth { name, status: HealthStatus::Unchecked, detail: reason }, None));
+    }
+    let checking = DefaultHealth { name, status: HealthStatus::Checking, detail: String::new() };
+    let mut done = checking.clone();
+    let job = jobs.spawn(format!("Checking {}", done.name), move |ctx| {
+        (done.status, done.detail) = match probe_provider(&entry, TestMode::Connection, ctx.cancel_flag()) {
+            Ok(msg) => (HealthStatus::Ok, msg),
+            Err(e) => (HealthStatus::Failed, e.to_string()),
+        };
+        done
+    });
+    Some((checking, Some(job)))
+}
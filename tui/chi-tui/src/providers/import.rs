@@ -0,0 +1,154 @@
+//! Import of an existing chi-llm config (`.chi_llm.json`/`.yaml` in the project,
+//! or the global config) into the scratch catalog, so a working setup can be
+//! edited instead of recreated. Offered when Configure opens with no providers,
+//! and on `i`. The provider block becomes the default provider;
+//! `provider_profiles` become tagged providers.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+
+use crate::app::{App, Notification, Page};
+use crate::modal::ActionModal;
+use crate::paths::{paths, PROJECT_CONFIG_LOOKUP};
+
+use super::select_default::save_default_provider;
+use super::state::ProviderScratchEntry;
+
+/// Keys of a profile that describe the profile rather than the provider.
+const PROFILE_KEYS: &[&str] = &["name", "tags", "priority"];
+
+/// The project config chi-llm would read (first of its lookup order) and the
+/// global config, whichever exist.
+pub fn import_sources() -> Vec<PathBuf> {
+    let project = PROJECT_CONFIG_LOOKUP.iter().map(PathBuf::from).find(|p| p.is_file());
+    project.into_iter().chain(paths().global_config().filter(|p| p.is_file())).collect()
+}
+
+/// A config file as JSON; `.yaml`/`.yml` files are read as YAML.
+pub fn read_config_file(path: &Path) -> Result<Value> {
+    let text = fs::read_to_string(path).map_err(|e| anyhow!("{}: {}", path.display(), e))?;
+    let yaml = path.extension().is_some_and(|e| e == "yaml" || e == "yml");
+    let v: Value = if yaml {
+        serde_yaml::from_str(&text).map_err(|e| anyhow!("{}: {}", path.display(), e))?
+    } else {
+        serde_json::from_str(&text).map_err(|e| anyhow!("{}: {}", path.display(), e))?
+    };
+    Ok(v)
+}
+
+/// Scratch entries for a chi-llm config: the `provider` block first, then each
+/// `provider_profiles` entry with its tags. The profile Build writes for the
+/// default provider is folded into the first entry. Ids are left empty for the
+/// catalog to assign.
+pub fn config_entries(config: &Value) -> Vec<ProviderScratchEntry> {
+    let mut out: Vec<ProviderScratchEntry> = Vec::new();
+    let provider = config.get("provider").and_then(Value::as_object).filter(|p| p.contains_key("type"));
+    if let Some(block) = provider {
+        out.push(entry_from_block(block.clone(), None, Vec::new()));
+    }
+    let profiles = config.get("provider_profiles").and_then(Value::as_array).map(Vec::as_slice).unwrap_or_default();
+    for p in profiles.iter().filter_map(Value::as_object) {
+        let name = p.get("name").and_then(Value::as_str).map(str::to_string);
+        let tags: Vec<String> = p.get("tags").and_then(Value::as_array)
+            .map(|a| a.iter().filter_map(|t| t.as_str().map(str::to_string)).collect())
+            .unwrap_or_default();
+        let block: serde_json::Map<String, Value> = p.iter().filter(|(k, _)| !PROFILE_KEYS.contains(&k.as_str())).map(|(k, v)| (k.clone(), v.clone())).collect();
+        if !block.contains_key("type") { continue; }
+        if provider.is_some_and(|d| *d == block) {
+            if let Some(name) = name { out[0].name = name; }
+            out[0].tags = tags;
+            continue;
+        }
+        out.push(entry_from_block(block, name, tags));
+    }
+    out
+}
+
+fn entry_from_block(block: serde_json::Map<String, Value>, name: Option<String>, tags: Vec<String>) -> ProviderScratchEntry {
+    let ptype = block.get("type").and_then(Value::as_str).unwrap_or_default().to_string();
+    ProviderScratchEntry {
+        id: String::new(),
+        name: name.unwrap_or_else(|| ptype.clone()),
+        ptype,
+        tags,
+        disabled: false,
+        session_secrets: Vec::new(),
+        config: Value::Object(block),
+    }
+}
+
+/// Dialog offering each of `import_sources`; false when there are none.
+pub fn offer_import(app: &mut App) -> bool {
+    let sources = import_sources();
+    if sources.is_empty() { return false; }
+    let empty = app.providers.ready().is_some_and(|st| st.entries.is_empty());
+    let msg = if empty {
+        "chi.tmp.json has no providers yet. Import the current chi-llm config to edit it here?"
+    } else {
+        "Add the providers of a chi-llm config to chi.tmp.json?"
+    };
+    let mut modal = ActionModal::new("Import existing config", msg);
+    for (i, path) in sources.into_iter().enumerate() {
+        let key = char::from_digit(i as u32 + 1, 10).unwrap_or('?');
+        modal = modal.action(key, format!("import {}", path.display()), move |app| import_into(app, &path));
+    }
+    app.modals.push(Box::new(modal));
+    true
+}
+
+/// Add the entries of the config at `path` and save them. Into an empty
+/// catalog, the first one also becomes the default provider.
+fn import_into(app: &mut App, path: &Path) {
+    let Some(st) = app.providers.ready_mut() else { return };
+    let result = read_config_file(path).and_then(|config| {
+        let entries = config_entries(&config);
+        if entries.is_empty() { return Err(anyhow!("{} has no provider block", path.display())); }
+        let first = st.entries.len();
+        let n = st.add_entries(entries);
+        st.selected = first;
+        st.form = None;
+        st.save()?;
+        if first == 0 { save_default_provider(&st.entries[0].id)?; }
+        Ok((n, first == 0))
+    });
+    let (msg, ok) = match result {
+        Ok((n, true)) => (format!("Imported {} provider(s) from {}; the first is the default", n, path.display()), true),
+        Ok((n, false)) => (format!("Imported {} provider(s) from {}", n, path.display()), true),
+        Err(e) => (format!("Import failed: {}", e), false),
+    };
+    app.defaultp = None;
+    app.routing = None;
+    let _ = app.notify_tx.send(Notification::new(msg, ok, Page::Configure));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn provider_and_profiles_become_entries() {
+        let config = serde_json::json!({
+            "provider": {"type": "ollama", "host": "127.0.0.1", "port": 11434, "model": "llama3.2"},
+            "provider_profiles": [
+                {"name": "home", "type": "ollama", "host": "127.0.0.1", "port": 11434, "model": "llama3.2", "tags": ["chat"], "priority": 10},
+                {"name": "cloud", "type": "openai", "api_key": "${OPENAI_API_KEY}", "model": "gpt-4o-mini", "tags": ["code"]},
+            ],
+        });
+        let entries = config_entries(&config);
+        assert_eq!(entries.len(), 2);
+        assert_eq!((entries[0].name.as_str(), entries[0].ptype.as_str(), entries[0].tags.clone()), ("home", "ollama", vec!["chat".to_string()]));
+        assert_eq!(entries[0].config["port"], 11434);
+        assert_eq!((entries[1].name.as_str(), entries[1].ptype.as_str()), ("cloud", "openai"));
+        assert!(entries[1].config.get("tags").is_none());
+        assert!(config_entries(&serde_json::json!({"model": {"temperature": 0.7}})).is_empty());
+
+        let tmp = std::env::temp_dir().join(format!("chi-tui-import-{}.yaml", std::process::id()));
+        fs::write(&tmp, "provider:\n  type: lmstudio\n  port: 1234\n").unwrap();
+        let entries = config_entries(&read_config_file(&tmp).unwrap());
+        assert_eq!((entries[0].ptype.as_str(), entries[0].config["port"].as_i64()), ("lmstudio", Some(1234)));
+        fs::remove_file(&tmp).unwrap();
+    }
+}
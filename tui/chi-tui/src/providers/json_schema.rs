@@ -0,0 +1,196 @@
+//! JSON Schema (draft 2020-12) documents for `chi.tmp.json` and `.chi_llm.json`
+//! as chi-tui reads and writes them, so editors and CI can validate the files.
+//! Per-type config properties come from the provider schema (CLI plus plugins,
+//! with the fields the form adds); the rest mirrors `ProviderScratchEntry` and
+//! `ConfigBlocks`.
+//!
+//! The scratch schema is lenient where the form is: non-string fields may hold
+//! a string (an empty or unparsed value), and required fields may be missing
+//! until the provider is saved from the form. The config schema is what Build
+//! writes: empty values dropped, required fields present.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use serde_json::{json, Map, Value};
+
+use crate::build::provider_block;
+use crate::forms::FieldSchema;
+use crate::paths::{PROJECT_CONFIG, SCRATCH_FILE};
+
+const DRAFT: &str = "https://json-schema.org/draft/2020-12/schema";
+/// File names written by `write_json_schemas`.
+pub const SCRATCH_SCHEMA_FILE: &str = "chi.tmp.schema.json";
+pub const CONFIG_SCHEMA_FILE: &str = "chi_llm.schema.json";
+
+/// Schema of one config value. `lenient` also accepts a string for typed fields.
+fn value_schema(sc: &FieldSchema, lenient: bool) -> Value {
+    let base = match sc.ftype.as_str() {
+        "int" | "port" => "integer",
+        "float" => "number",
+        "map" => "object",
+        "bool" => "boolean",
+        _ => "string",
+    };
+    let mut s = Map::new();
+    s.insert("type".into(), if lenient && base != "string" { json!([base, "string"]) } else { json!(base) });
+    if base == "object" { s.insert("additionalProperties".into(), json!({"type": "string"})); }
+    let mut description = sc.help.clone().unwrap_or_default();
+    if sc.ftype == "secret" {
+        if !description.is_empty() { description.push(' '); }
+        description.push_str("Secret; may be a ${VAR} reference.");
+    }
+    if !description.is_empty() { s.insert("description".into(), json!(description)); }
+    if let Some(d) = &sc.default {
+        let typed = match base {
+            "integer" => d.parse::<i64>().map(Value::from).unwrap_or(json!(d)),
+            "number" => d.parse::<f64>().map(Value::from).unwrap_or(json!(d)),
+            _ => json!(d),
+        };
+        s.insert("default".into(), typed);
+    }
+    if let Some(options) = sc.options.as_ref().filter(|o| !o.is_empty()) {
+        s.insert("examples".into(), json!(options));
+    }
+    Value::Object(s)
+}
+
+/// Object schema for a config with `fields`; extra keys stay allowed (see `e`
+/// on the provider list).
+fn config_object(fields: &[FieldSchema], lenient: bool) -> Value {
+    let props: Map<String, Value> = fields.iter().map(|sc| (sc.name.clone(), value_schema(sc, lenient))).collect();
+    let mut s = json!({"type": "object", "properties": props});
+    if !lenient {
+        let required: Vec<&str> = fields.iter().filter(|sc| sc.required).map(|sc| sc.name.as_str()).collect();
+        if !required.is_empty() { s["required"] = json!(required); }
+    }
+    s
+}
+
+/// `if type is T then <key> matches $defs/T`, one per type.
+fn per_type(types: &[String], key: Option<&str>) -> Vec<Value> {
+    types.iter().map(|t| {
+        let target = json!({"$ref": format!("#/$defs/{}", t)});
+        let then = match key { Some(k) => json!({"properties": {k: target}}), None => target };
+        json!({"if": {"properties": {"type": {"const": t}}, "required": ["type"]}, "then": then})
+    }).collect()
+}
+
+/// Schema of `chi.tmp.json`.
+pub fn scratch_json_schema(types: &[String], schema_map: &HashMap<String, Vec<FieldSchema>>) -> Value {
+    let mut defs: Map<String, Value> = types.iter()
+        .map(|t| (t.clone(), config_object(schema_map.get(t).map(Vec::as_slice).unwrap_or_default(), true)))
+        .collect();
+    let mut entry = json!({
+        "type": "object",
+        "required": ["id", "type"],
+        "properties": {
+            "id": {"type": "string", "pattern": "^[A-Za-z0-9._-]+$"},
+            "name": {"type": "string", "description": "Display name"},
+            "type": {"type": "string", "enum": types},
+            "tags": {"description": "Routing tags; Build writes tagged providers as provider_profiles", "type": "array", "items": {"type": "string"}},
+            "disabled": {"type": "boolean", "description": "Maintenance mode: kept but not used"},
+            "session_only": {"description": "Secret keys stored as ${VAR} references", "type": "array", "items": {"type": "string"}},
+            "config": {"type": "object"},
+        },
+    });
+    if !types.is_empty() { entry["allOf"] = json!(per_type(types, Some("config"))); }
+    defs.insert("provider".into(), entry);
+    json!({
+        "$schema": DRAFT,
+        "title": format!("chi-tui provider scratch file ({})", SCRATCH_FILE),
+        "type": "object",
+        "properties": {
+            "default_provider_id": {"type": "string", "description": "id of the provider Build writes"},
+            "providers": {"type": "array", "items": {"$ref": "#/$defs/provider"}},
+        },
+        "$defs": defs,
+    })
+}
+
+/// Schema of `.chi_llm.json` as Build writes it.
+pub fn config_json_schema(types: &[String], schema_map: &HashMap<String, Vec<FieldSchema>>) -> Value {
+    // Build writes UI-only variants (local-zeroconfig, ...) as their chi-llm type
+    let mut written: BTreeMap<String, Vec<FieldSchema>> = BTreeMap::new();
+    for t in types {
+        let out = provider_block(t, &Value::Null)["type"].as_str().unwrap_or(t).to_string();
+        let fields = written.entry(out).or_default();
+        for sc in schema_map.get(t).into_iter().flatten() {
+            if !fields.iter().any(|f| f.name == sc.name) { fields.push(sc.clone()); }
+        }
+    }
+    let names: Vec<String> = written.keys().cloned().collect();
+    let mut defs: Map<String, Value> = written.iter().map(|(t, f)| (t.clone(), config_object(f, false))).collect();
+    let mut provider = json!({"type": "object", "required": ["type"], "properties": {"type": {"type": "string", "enum": names}}});
+    if !names.is_empty() { provider["allOf"] = json!(per_type(&names, None)); }
+    defs.insert("provider".into(), provider);
+    json!({
+        "$schema": DRAFT,
+        "title": format!("chi-llm project config ({}) as written by chi-tui", PROJECT_CONFIG),
+        "type": "object",
+        "required": ["provider"],
+        "properties": {
+            "provider": {"$ref": "#/$defs/provider"},
+            "provider_profiles": {
+                "description": "Providers for chi-llm's tag router",
+                "type": "array",
+                "items": {
+                    "allOf": [{"$ref": "#/$defs/provider"}],
+                    "required": ["name", "tags"],
+                    "properties": {
+                        "name": {"type": "string"},
+                        "tags": {"type": "array", "items": {"type": "string"}},
+                        "priority": {"type": "integer", "description": "Lower is tried first; the default provider gets 10"},
+                    },
+                },
+            },
+        },
+        "$defs": defs,
+    })
+}
+
+/// Write both schemas into `dir`; returns the paths.
+pub fn write_json_schemas(dir: &Path, types: &[String], schema_map: &HashMap<String, Vec<FieldSchema>>) -> Result<Vec<PathBuf>> {
+    fs::create_dir_all(dir)?;
+    let docs = [(SCRATCH_SCHEMA_FILE, scratch_json_schema(types, schema_map)), (CONFIG_SCHEMA_FILE, config_json_schema(types, schema_map))];
+    let mut paths = Vec::new();
+    for (name, doc) in docs {
+        let path = dir.join(name);
+        fs::write(&path, serde_json::to_vec_pretty(&doc)?)?;
+        paths.push(path);
+    }
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(name: &str, ftype: &str, required: bool) -> FieldSchema {
+        FieldSchema { name: name.into(), ftype: ftype.into(), required, default: None, help: None, options: None }
+    }
+
+    #[test]
+    fn schemas_follow_the_provider_types() {
+        let types = vec!["local-custom".to_string(), "openai".to_string()];
+        let schema_map = HashMap::from([
+            ("local-custom".to_string(), vec![field("model", "string", true), field("threads", "int", false)]),
+            ("openai".to_string(), vec![field("api_key", "secret", true), field("headers", "map", false)]),
+        ]);
+        let scratch = scratch_json_schema(&types, &schema_map);
+        assert_eq!(scratch["$defs"]["provider"]["properties"]["type"]["enum"], json!(["local-custom", "openai"]));
+        let local = &scratch["$defs"]["local-custom"];
+        assert_eq!(local["properties"]["threads"]["type"], json!(["integer", "string"]));
+        assert!(local.get("required").is_none());
+        assert_eq!(scratch["$defs"]["provider"]["allOf"][1]["then"]["properties"]["config"]["$ref"], "#/$defs/openai");
+
+        let config = config_json_schema(&types, &schema_map);
+        assert_eq!(config["$defs"]["provider"]["properties"]["type"]["enum"], json!(["local", "openai"]));
+        assert_eq!(config["$defs"]["local"]["properties"]["threads"]["type"], "integer");
+        assert_eq!(config["$defs"]["openai"]["required"], json!(["api_key"]));
+        assert_eq!(config["$defs"]["openai"]["properties"]["headers"]["additionalProperties"], json!({"type": "string"}));
+        assert!(config["$defs"]["openai"]["properties"]["api_key"]["description"].as_str().unwrap().contains("${VAR}"));
+    }
+}
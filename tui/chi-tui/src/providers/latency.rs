@@ -0,0 +1,71 @@
+//! Duration of each provider's last successful test, shown as a badge (e.g.
+//! `1.2s`) in the Configure and Select Default lists. Stored in `latency.json`
+//! in the state dir, keyed by provider id.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::time::Duration;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::paths::{file_in, paths};
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct LatencyBook(BTreeMap<String, LastTest>);
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LastTest {
+    pub millis: u64,
+    /// True for end-to-end tests (a generate call, slower than listing models).
+    #[serde(default)]
+    pub e2e: bool,
+}
+
+impl LatencyBook {
+    /// Read `latency.json`; a missing or unreadable file gives an empty book.
+    pub fn load() -> Self {
+        fs::read_to_string(paths().state_dir.join("latency.json"))
+            .ok()
+            .and_then(|t| serde_json::from_str(&t).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = file_in(&paths().state_dir, "latency.json")?;
+        fs::write(path, serde_json::to_vec_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn record(&mut self, id: &str, elapsed: Duration, e2e: bool) {
+        self.0.insert(id.to_string(), LastTest { millis: elapsed.as_millis() as u64, e2e });
+    }
+
+    /// Move the record of a provider whose id changed.
+    pub fn rename(&mut self, old: &str, new: &str) {
+        if let Some(last) = self.0.remove(old) { self.0.insert(new.to_string(), last); }
+    }
+
+    /// Badge for provider `id`, e.g. `1.2s` or `e2e 3.4s`.
+    pub fn badge(&self, id: &str) -> Option<String> {
+        let last = self.0.get(id)?;
+        let time = if last.millis < 1000 { format!("{}ms", last.millis) } else { format!("{:.1}s", last.millis as f64 / 1000.0) };
+        Some(if last.e2e { format!("e2e {}", time) } else { time })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn badges_use_ms_below_a_second() {
+        let mut book = LatencyBook::default();
+        book.record("a", Duration::from_millis(850), false);
+        book.record("b", Duration::from_millis(1234), true);
+        assert_eq!(book.badge("a").as_deref(), Some("850ms"));
+        assert_eq!(book.badge("b").as_deref(), Some("e2e 1.2s"));
+        assert_eq!(book.badge("c"), None);
+    }
+}
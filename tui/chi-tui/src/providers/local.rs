@@ -0,0 +1,79 @@
+//! Performance overrides for local (llama.cpp) providers: GPU offload, threads
+//! and context window, the ones chi-llm's local provider reads. Added to local
+//! forms when the CLI schema does not declare them; empty values keep chi-llm's
+//! defaults and are not written.
+
+use crate::forms::{FieldSchema, FormField};
+use crate::interp::env_refs;
+
+/// Provider types that run models in-process.
+pub fn is_local_type(ptype: &str) -> bool {
+    matches!(ptype, "local" | "local-zeroconfig" | "local-custom")
+}
+
+/// Name, inclusive range and help for each override; the ranges are the values
+/// chi-llm accepts (it ignores anything else and keeps its default). Threads
+/// go up to this machine's CPU count.
+fn tuning() -> [(&'static str, i64, i64, &'static str); 3] {
+    let cpus = std::thread::available_parallelism().map_or(1, |n| n.get() as i64);
+    [
+        ("n_gpu_layers", 0, 1000, "Layers offloaded to the GPU: 0 CPU only (empty: the model's default)"),
+        ("n_threads", 1, cpus, "CPU threads for inference (empty: up to 4)"),
+        ("context_window", 1, 1_048_576, "Context window in tokens; must not exceed what the model supports (empty: default)"),
+    ]
+}
+
+/// Append the overrides missing from `fields`.
+pub fn add_tuning_fields(fields: &mut Vec<FieldSchema>) {
+    for (name, _, _, help) in tuning() {
+        if fields.iter().any(|f| f.name == name) { continue; }
+        fields.push(FieldSchema {
+            name: name.to_string(),
+            ftype: "int".to_string(),
+            required: false,
+            default: None,
+            help: Some(help.to_string()),
+            options: None,
+        });
+    }
+}
+
+/// Problems with the overrides in `fields`, e.g. `n_gpu_layers: must be 0..=1000`.
+/// Values that are not numbers at all are left to `FormField::type_error`.
+pub fn tuning_errors(fields: &[FormField]) -> Vec<String> {
+    let tuning = tuning();
+    let mut errors = Vec::new();
+    for ff in fields {
+        let Some((name, min, max, _)) = tuning.iter().find(|t| t.0 == ff.schema.name) else { continue };
+        let v = ff.buffer.trim();
+        if v.is_empty() || !env_refs(v).is_empty() { continue; }
+        if v.parse::<i64>().is_ok_and(|n| !(*min..=*max).contains(&n)) {
+            errors.push(format!("{}: must be {}..={}", name, min, max));
+        }
+    }
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tuning_fields_are_added_once_and_range_checked() {
+        let mut schema = Vec::new();
+        add_tuning_fields(&mut schema);
+        add_tuning_fields(&mut schema);
+        assert_eq!(schema.iter().map(|s| s.name.as_str()).collect::<Vec<_>>(), ["n_gpu_layers", "n_threads", "context_window"]);
+        let mut fields: Vec<FormField> = schema.iter().map(|s| FormField::from_value(s, None)).collect();
+        assert!(tuning_errors(&fields).is_empty());
+        // chi-llm ignores a negative layer count, so -1 is not offered as "all"
+        fields[0].buffer = "-1".to_string();
+        fields[1].buffer = "0".to_string();
+        fields[2].buffer = "4k".to_string();
+        let cpus = std::thread::available_parallelism().unwrap().get();
+        assert_eq!(tuning_errors(&fields), ["n_gpu_layers: must be 0..=1000".to_string(), format!("n_threads: must be 1..={}", cpus)]);
+        fields[1].buffer = cpus.to_string();
+        assert_eq!(tuning_errors(&fields).len(), 1);
+        assert_eq!(fields[2].type_error().as_deref(), Some("must be a whole number"));
+    }
+}
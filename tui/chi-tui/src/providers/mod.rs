@@ -1,14 +1,60 @@
 mod state;
+mod budget;
+mod health;
+mod latency;
 mod select_default;
+mod routing;
 mod view;
+mod probe;
+mod local;
+mod network;
+mod plugins;
+mod pull;
+mod ports;
+mod reach;
+mod monitor;
+mod generate;
+mod fork;
+mod presets;
+mod quick_default;
+mod share;
+mod import;
+mod status;
+mod json_schema;
+mod controller;
 
 pub use state::{
-    ProvidersState, ProviderScratchEntry, FieldSchema, FormField, FormState, DropdownState,
-    load_providers_state, compute_form_hash,
+    ProvidersState, ProvidersPane, ProviderScratchEntry, load_providers_state, load_scratch_entries,
+    parse_config_json, parse_provider_json, is_secret_key,
+    FORM_BUTTONS, FORK_KEYS, BUTTON_TEST, BUTTON_SAVE, BUTTON_CANCEL,
 };
-pub use select_default::{
-    DefaultProviderState, load_providers_scratch, save_default_provider, draw_select_default,
+pub use select_default::{DefaultProviderState, ProviderEntry, SelectDefaultPage, load_providers_scratch, save_default_provider};
+pub use probe::{
+    build_discovery_args, build_generate_args, build_test_args, discover_models, e2e_prompt, probe_provider, probe_streaming, probe_with_reply,
+    set_e2e_prompt, DiscoveredModels, ProviderTestOutcome, TestCommand, TestMode,
 };
-pub use view::{
-    draw_providers_catalog, probe_provider,
+pub use plugins::{load_plugins, parse_plugin, plugin_for, plugins, CommandTemplate, PluginDef, PluginField, PluginRegistry};
+pub use budget::{
+    budget_status, current_month, estimate_tokens, is_cloud_type, price_for, BudgetStatus, Price, UsageBook, UsageCounters,
 };
+pub use routing::{load_routing, save_routing_tags, RoutingPage, RoutingState, SUGGESTED_TAGS};
+pub use status::{ActiveConfig, ActiveStatus, ConfigSource, ProviderSummary};
+pub use health::{spawn_default_health_check, DefaultHealth, HealthStatus};
+pub use latency::{LastTest, LatencyBook};
+pub use local::{add_tuning_fields, is_local_type, tuning_errors};
+pub use network::{
+    add_headers_field, add_network_fields, header_lines, is_openai_compatible, network_env, network_errors, CA_CERT_PATH, HEADERS,
+    PROXY_URL,
+};
+pub use generate::{Generation, GenerationModal};
+pub use fork::ForkModal;
+pub use quick_default::{open_quick_default, QuickDefaultModal, RewriteTarget};
+pub use presets::{available_presets, preset_by_label, Preset, PRESETS};
+pub use pull::{ollama_host, spawn_ollama_pull, PullOutcome};
+pub use ports::{check_local_port, classify, probe_port, service_for, warn_about_ports, LocalService, PortProbe};
+pub use monitor::{dot_for, health_dot, monitor_title, tick_monitor, HealthDot, HealthMonitor, MONITOR_INTERVAL};
+pub use reach::{check_configured_providers, check_endpoint, check_reachability, endpoint_for, parse_url, Endpoint, Reachability};
+pub use import::{config_entries, import_sources, offer_import, read_config_file};
+pub use share::{decode_provider_uri, encode_provider_uri, URI_PREFIX};
+pub use json_schema::{config_json_schema, scratch_json_schema, write_json_schemas, CONFIG_SCHEMA_FILE, SCRATCH_SCHEMA_FILE};
+pub use controller::ConfigurePage;
@@ -0,0 +1,135 @@
+//! Opt-in background health monitor (`m` on Settings, `[monitor]` in
+//! `settings.toml`): every interval the reachability check of `reach` runs for
+//! all providers, and Configure and Select Default show a green, yellow or red
+//! dot per entry with the time of the last check.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use chrono::{DateTime, Local};
+use ratatui::text::Span;
+
+use crate::app::App;
+use crate::jobs::Periodic;
+
+use super::reach::{check_configured_providers, Reachability};
+
+/// Check interval unless `[monitor] interval_secs` sets another.
+pub const MONITOR_INTERVAL: Duration = Duration::from_secs(60);
+/// Answers slower than this show yellow.
+const SLOW: Duration = Duration::from_millis(1500);
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum HealthDot {
+    /// Answered in time.
+    Green,
+    /// Answered, but slowly or with an HTTP error status.
+    Yellow,
+    /// Nothing answered.
+    Red,
+}
+
+/// Dot for one result; `None` for types without a network endpoint.
+pub fn dot_for(r: &Reachability) -> Option<HealthDot> {
+    match &r.result {
+        Err(_) => Some(HealthDot::Red),
+        Ok(_) if r.endpoint.is_none() => None,
+        Ok(detail) => {
+            let http_error = detail.strip_prefix("HTTP ").and_then(|s| s.parse::<u16>().ok()).is_some_and(|code| code >= 400);
+            let slow = r.latency.is_some_and(|l| l > SLOW);
+            Some(if http_error || slow { HealthDot::Yellow } else { HealthDot::Green })
+        }
+    }
+}
+
+pub struct HealthMonitor {
+    pub runs: Periodic<Result<Vec<Reachability>, String>>,
+    /// Latest dot per provider id.
+    pub dots: HashMap<String, HealthDot>,
+    pub checked: Option<DateTime<Local>>,
+    pub error: Option<String>,
+}
+
+impl HealthMonitor {
+    /// A monitor whose first check starts at once.
+    pub fn new(interval: Duration) -> Self {
+        Self { runs: Periodic::new(interval), dots: HashMap::new(), checked: None, error: None }
+    }
+
+    /// Store a finished check.
+    pub fn record(&mut self, results: &[Reachability]) {
+        self.dots = results.iter().filter_map(|r| dot_for(r).map(|d| (r.id.clone(), d))).collect();
+        self.checked = Some(Local::now());
+        self.error = None;
+    }
+
+    /// Stop a check in flight (the monitor is being turned off).
+    pub fn stop(&mut self) {
+        self.runs.stop();
+    }
+}
+
+/// Collect a finished check and start the next when due; runs on every page.
+pub fn tick_monitor(app: &mut App) {
+    let Some(monitor) = app.monitor.as_mut() else { return };
+    if let Some(done) = monitor.runs.poll() {
+        match done.and_then(|r| r) {
+            Ok(results) => monitor.record(&results),
+            Err(e) => monitor.error = Some(e),
+        }
+    }
+    if monitor.runs.is_due() {
+        monitor.runs.start(&app.jobs, "Checking provider health", |_| check_configured_providers().map_err(|e| e.to_string()));
+    }
+}
+
+/// `● ` in the color of provider `id`'s last check, when the monitor has one.
+pub fn health_dot(app: &App, id: &str) -> Option<Span<'static>> {
+    Some(dot_span(app, *app.monitor.as_ref()?.dots.get(id)?))
+}
+
+/// `● ` in the color of `dot`.
+pub fn dot_span(app: &App, dot: HealthDot) -> Span<'static> {
+    let style = match dot {
+        HealthDot::Green => app.theme.success(),
+        HealthDot::Yellow => app.theme.warning(),
+        HealthDot::Red => app.theme.error(),
+    };
+    Span::styled("● ", style)
+}
+
+/// `title` with the monitor's last check time, e.g. `Configure Providers • health 12:03:41`.
+pub fn monitor_title(app: &App, title: &str) -> String {
+    let Some(monitor) = &app.monitor else { return title.to_string() };
+    match (&monitor.checked, &monitor.error) {
+        (_, Some(_)) => format!("{} • health check failed", title),
+        (Some(at), None) => format!("{} • health {}", title, at.format("%H:%M:%S")),
+        (None, None) => format!("{} • checking health…", title),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reach(result: Result<&str, &str>, endpoint: bool, ms: u64) -> Reachability {
+        Reachability {
+            id: "p".to_string(),
+            name: "p".to_string(),
+            ptype: "ollama".to_string(),
+            endpoint: endpoint.then(|| "http://127.0.0.1:11434".to_string()),
+            result: result.map(String::from).map_err(String::from),
+            latency: endpoint.then(|| Duration::from_millis(ms)),
+        }
+    }
+
+    #[test]
+    fn dots_follow_the_reachability_result() {
+        assert_eq!(dot_for(&reach(Ok("HTTP 200"), true, 20)), Some(HealthDot::Green));
+        assert_eq!(dot_for(&reach(Ok("TCP open (HTTPS not probed)"), true, 20)), Some(HealthDot::Green));
+        assert_eq!(dot_for(&reach(Ok("HTTP 401"), true, 20)), Some(HealthDot::Yellow));
+        assert_eq!(dot_for(&reach(Ok("HTTP 200"), true, 4000)), Some(HealthDot::Yellow));
+        assert_eq!(dot_for(&reach(Err("nothing is listening"), true, 1)), Some(HealthDot::Red));
+        assert_eq!(dot_for(&reach(Ok("no network endpoint"), false, 0)), None);
+    }
+}
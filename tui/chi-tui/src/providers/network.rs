@@ -0,0 +1,119 @@
+//! Per-provider HTTP proxy and custom CA bundle, for networks with TLS
+//! interception. Offered on HTTP-based provider forms; tests pass them to
+//! chi-llm through the standard proxy/CA environment variables and Build writes
+//! them into the provider config, where chi-llm's HTTP providers read them.
+
+use std::path::Path;
+
+use crate::forms::{FieldSchema, FormField};
+use crate::interp::env_refs;
+
+use super::probe::cfg_str;
+use super::state::ProviderScratchEntry;
+
+pub const PROXY_URL: &str = "proxy_url";
+pub const CA_CERT_PATH: &str = "ca_cert_path";
+/// Extra HTTP headers (`map` field) for OpenAI-compatible gateways.
+pub const HEADERS: &str = "headers";
+
+/// Provider types chi-llm talks to over HTTP itself.
+pub fn is_http_type(ptype: &str) -> bool {
+    matches!(ptype, "lmstudio" | "ollama" | "openai" | "anthropic")
+}
+
+/// OpenAI and OpenAI-compatible provider types.
+pub fn is_openai_compatible(ptype: &str) -> bool {
+    ptype.contains("openai")
+}
+
+/// Append the `headers` field when missing from `fields`.
+pub fn add_headers_field(fields: &mut Vec<FieldSchema>) {
+    if fields.iter().any(|f| f.name == HEADERS) { return; }
+    fields.push(FieldSchema {
+        name: HEADERS.to_string(),
+        ftype: "map".to_string(),
+        required: false,
+        default: None,
+        help: Some("Extra HTTP headers, e.g. X-Api-Key or tenant headers required by a gateway".to_string()),
+        options: None,
+    });
+}
+
+/// `Name: value` strings for the entry's extra headers.
+pub fn header_lines(entry: &ProviderScratchEntry) -> Vec<String> {
+    let Some(map) = entry.config.get(HEADERS).and_then(|h| h.as_object()) else { return Vec::new() };
+    map.iter().filter_map(|(k, v)| v.as_str().map(|v| format!("{}: {}", k, v))).collect()
+}
+
+/// Append `proxy_url` and `ca_cert_path` when missing from `fields`.
+pub fn add_network_fields(fields: &mut Vec<FieldSchema>) {
+    let extra = [
+        (PROXY_URL, "HTTP(S) proxy, e.g. http://proxy.corp:3128 (empty: none)"),
+        (CA_CERT_PATH, "PEM bundle trusted for TLS, e.g. your corporate root CA (empty: system default)"),
+    ];
+    for (name, help) in extra {
+        if fields.iter().any(|f| f.name == name) { continue; }
+        fields.push(FieldSchema {
+            name: name.to_string(),
+            ftype: "string".to_string(),
+            required: false,
+            default: None,
+            help: Some(help.to_string()),
+            options: None,
+        });
+    }
+}
+
+/// Problems with the proxy URL scheme or CA file in `fields`.
+pub fn network_errors(fields: &[FormField]) -> Vec<String> {
+    let mut errors = Vec::new();
+    for ff in fields {
+        let v = ff.buffer.trim();
+        // `${VAR}` references are checked once resolved, by the test
+        if v.is_empty() || !env_refs(v).is_empty() { continue; }
+        match ff.schema.name.as_str() {
+            PROXY_URL if !["http://", "https://", "socks5://", "socks5h://"].iter().any(|s| v.starts_with(s)) => {
+                errors.push(format!("{}: must start with http://, https:// or socks5://", PROXY_URL));
+            }
+            CA_CERT_PATH if !Path::new(v).is_file() => errors.push(format!("{}: no such file", CA_CERT_PATH)),
+            _ => {}
+        }
+    }
+    errors
+}
+
+/// Environment for a `chi-llm` test process honouring the entry's proxy and CA.
+pub fn network_env(entry: &ProviderScratchEntry) -> Vec<(String, String)> {
+    let mut env = Vec::new();
+    if let Some(proxy) = cfg_str(entry, PROXY_URL) {
+        for var in ["HTTP_PROXY", "HTTPS_PROXY", "ALL_PROXY"] { env.push((var.to_string(), proxy.clone())); }
+    }
+    if let Some(ca) = cfg_str(entry, CA_CERT_PATH) {
+        for var in ["SSL_CERT_FILE", "REQUESTS_CA_BUNDLE"] { env.push((var.to_string(), ca.clone())); }
+    }
+    env
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn proxy_and_ca_are_validated_and_exported() {
+        let mut schema = Vec::new();
+        add_network_fields(&mut schema);
+        let mut fields: Vec<FormField> = schema.iter().map(|s| FormField::from_value(s, None)).collect();
+        assert!(network_errors(&fields).is_empty());
+        fields[0].buffer = "proxy.corp:3128".to_string();
+        fields[1].buffer = "/nonexistent/ca.pem".to_string();
+        assert_eq!(network_errors(&fields).len(), 2);
+
+        let entry = ProviderScratchEntry {
+            id: "p1".into(), name: "o".into(), ptype: "openai".into(), tags: Vec::new(), disabled: false, session_secrets: Vec::new(),
+            config: serde_json::json!({"proxy_url": "http://proxy:3128", "ca_cert_path": "/etc/ca.pem"}),
+        };
+        let env = network_env(&entry);
+        assert!(env.contains(&("HTTPS_PROXY".to_string(), "http://proxy:3128".to_string())));
+        assert!(env.contains(&("SSL_CERT_FILE".to_string(), "/etc/ca.pem".to_string())));
+    }
+}
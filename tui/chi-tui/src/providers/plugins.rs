@@ -0,0 +1,289 @@
+//! External provider types from `<config dir>/providers.d/*.toml`, for backends
+//! that neither chi-tui nor chi-llm know yet. A plugin declares its fields and
+//! optional test/discovery command templates:
+//!
+//! ```toml
+//! type = "my-backend"
+//! description = "Experimental inference server"
+//!
+//! [[fields]]
+//! name = "base_url"
+//! required = true
+//! default = "http://127.0.0.1:9000"
+//!
+//! [[fields]]
+//! name = "api_key"
+//! type = "secret"
+//!
+//! [[fields]]
+//! name = "system_prompt"
+//! type = "text"                   # multi-line; also int, float, bool, path, url, port, map
+//!
+//! [discover]                      # connection test / model dropdown
+//! program = "curl"
+//! args = ["-sf", "{base_url}/models"]
+//!
+//! [test]                          # end-to-end test (defaults to chi-llm)
+//! args = ["generate", "--max-tokens", "16", "--", "{prompt}"]
+//! env = { CHI_LLM_CONFIG = "{config_json}" }
+//! ```
+//!
+//! Placeholders: any config field, plus `{type}`, `{name}`, `{prompt}` and
+//! `{config_json}` (the provider block as written to `.chi_llm.json`).
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::build::provider_block;
+use crate::forms::FieldSchema;
+use crate::paths::paths;
+
+use super::probe::{e2e_prompt, TestCommand, TestMode};
+use super::state::{is_secret_key, ProviderScratchEntry};
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct PluginDef {
+    #[serde(rename = "type")]
+    pub ptype: String,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub fields: Vec<PluginField>,
+    pub test: Option<CommandTemplate>,
+    pub discover: Option<CommandTemplate>,
+    #[serde(skip)]
+    pub source: PathBuf,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct PluginField {
+    pub name: String,
+    #[serde(rename = "type", default = "default_ftype")]
+    pub ftype: String,
+    #[serde(default)]
+    pub required: bool,
+    pub default: Option<toml::Value>,
+    pub help: Option<String>,
+    pub options: Option<Vec<String>>,
+}
+
+fn default_ftype() -> String { "string".to_string() }
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct CommandTemplate {
+    #[serde(default = "default_program")]
+    pub program: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+}
+
+fn default_program() -> String { "chi-llm".to_string() }
+
+impl PluginField {
+    pub fn schema(&self) -> FieldSchema {
+        let default = self.default.as_ref().map(|d| match d {
+            toml::Value::String(s) => s.clone(),
+            other => other.to_string(),
+        });
+        FieldSchema {
+            name: self.name.clone(),
+            ftype: self.ftype.clone(),
+            required: self.required,
+            default,
+            help: self.help.clone(),
+            options: self.options.clone(),
+        }
+    }
+}
+
+impl PluginDef {
+    pub fn schema(&self) -> Vec<FieldSchema> {
+        self.fields.iter().map(PluginField::schema).collect()
+    }
+
+    fn is_secret(&self, key: &str) -> bool {
+        match self.fields.iter().find(|f| f.name == key) {
+            Some(f) => f.ftype == "secret",
+            None => is_secret_key(key),
+        }
+    }
+
+    /// Render the template for `mode` with `entry`'s values.
+    pub fn test_command(&self, entry: &ProviderScratchEntry, mode: TestMode) -> Result<TestCommand, String> {
+        let template = match mode {
+            TestMode::Connection => self.discover.as_ref().ok_or_else(|| format!("{}: plugin has no [discover]; use end-to-end test", self.ptype))?,
+            TestMode::E2e => self.test.as_ref().ok_or_else(|| format!("{}: plugin has no [test] command", self.ptype))?,
+        };
+        let mut vars: BTreeMap<String, String> = BTreeMap::new();
+        let mut secrets = Vec::new();
+        // Schema defaults first, then the entry's own values
+        for f in &self.fields {
+            if let Some(d) = f.schema().default { vars.insert(f.name.clone(), d); }
+        }
+        if let Some(obj) = entry.config.as_object() {
+            for (k, v) in obj {
+                let s = match v {
+                    Value::String(s) => s.clone(),
+                    Value::Null => continue,
+                    other => other.to_string(),
+                };
+                if self.is_secret(k) { secrets.push(s.clone()); }
+                vars.insert(k.clone(), s);
+            }
+        }
+        vars.insert("type".to_string(), entry.ptype.clone());
+        vars.insert("name".to_string(), entry.name.clone());
+        vars.insert("prompt".to_string(), e2e_prompt());
+        let block = provider_block(&entry.ptype, &entry.config);
+        vars.insert("config_json".to_string(), serde_json::json!({ "provider": block }).to_string());
+
+        let args = template.args.iter().map(|a| render(a, &vars)).collect::<Result<Vec<_>, _>>()?;
+        let env = template.env.iter().map(|(k, v)| Ok((k.clone(), render(v, &vars)?))).collect::<Result<Vec<_>, String>>()?;
+        Ok(TestCommand { program: template.program.clone(), args, env, secrets })
+    }
+}
+
+/// Replace `{name}` placeholders; unknown names are an error so a typo does not
+/// silently produce a broken command.
+fn render(template: &str, vars: &BTreeMap<String, String>) -> Result<String, String> {
+    let mut out = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let Some(len) = rest[start..].find('}') else { break };
+        let key = &rest[start + 1..start + len];
+        let value = vars.get(key).ok_or_else(|| format!("missing value for {{{}}}", key))?;
+        out.push_str(value);
+        rest = &rest[start + len + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// Plugins found on disk plus per-file load errors.
+#[derive(Debug, Default)]
+pub struct PluginRegistry {
+    pub dir: PathBuf,
+    pub plugins: Vec<PluginDef>,
+    pub errors: Vec<String>,
+}
+
+/// Parse one plugin file's contents.
+pub fn parse_plugin(text: &str, source: &Path) -> Result<PluginDef, String> {
+    let mut def: PluginDef = toml::from_str(text).map_err(|e| format!("{}: {}", source.display(), e))?;
+    if def.ptype.trim().is_empty() { return Err(format!("{}: empty type", source.display())); }
+    def.source = source.to_path_buf();
+    Ok(def)
+}
+
+/// Load every `*.toml` in `dir`, in file name order. A missing directory is not an error.
+pub fn load_plugins(dir: &Path) -> PluginRegistry {
+    let mut reg = PluginRegistry { dir: dir.to_path_buf(), ..Default::default() };
+    let Ok(read) = fs::read_dir(dir) else { return reg };
+    let mut files: Vec<PathBuf> = read.filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|x| x == "toml"))
+        .collect();
+    files.sort();
+    for path in files {
+        match fs::read_to_string(&path).map_err(|e| format!("{}: {}", path.display(), e)).and_then(|t| parse_plugin(&t, &path)) {
+            Ok(def) => reg.plugins.push(def),
+            Err(e) => reg.errors.push(e),
+        }
+    }
+    reg
+}
+
+/// Process-wide registry, loaded from `providers.d` on first use.
+pub fn plugins() -> &'static PluginRegistry {
+    static REGISTRY: OnceLock<PluginRegistry> = OnceLock::new();
+    REGISTRY.get_or_init(|| load_plugins(&paths().plugin_dir()))
+}
+
+pub fn plugin_for(ptype: &str) -> Option<&'static PluginDef> {
+    plugins().plugins.iter().find(|p| p.ptype == ptype)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicBool;
+
+    use crate::cli_log::cli_calls;
+
+    use super::*;
+
+    const PLUGIN: &str = r#"
+type = "mybackend"
+
+[[fields]]
+name = "base_url"
+required = true
+default = "http://127.0.0.1:9000"
+
+[[fields]]
+name = "api_key"
+type = "secret"
+
+[discover]
+program = "curl"
+args = ["-sf", "-H", "Authorization: Bearer {api_key}", "{base_url}/models"]
+"#;
+
+    #[test]
+    fn parses_and_renders_templates() {
+        let def = parse_plugin(PLUGIN, Path::new("x.toml")).unwrap();
+        assert_eq!(def.schema()[0].default.as_deref(), Some("http://127.0.0.1:9000"));
+        let entry = ProviderScratchEntry {
+            id: "p1".to_string(),
+            name: "mine".to_string(),
+            ptype: "mybackend".to_string(),
+            tags: Vec::new(),
+            disabled: false,
+            session_secrets: Vec::new(),
+            config: serde_json::json!({"api_key": "s3cret"}),
+        };
+        let cmd = def.test_command(&entry, TestMode::Connection).unwrap();
+        assert_eq!(cmd.program, "curl");
+        assert_eq!(cmd.args[3], "http://127.0.0.1:9000/models");
+        assert!(!cmd.redacted().contains("s3cret"));
+        assert!(def.test_command(&entry, TestMode::E2e).is_err());
+
+        let bad = PLUGIN.replace("{base_url}/models", "{nope}");
+        let def = parse_plugin(&bad, Path::new("x.toml")).unwrap();
+        assert!(def.test_command(&entry, TestMode::Connection).unwrap_err().contains("{nope}"));
+        assert!(parse_plugin("type = 3", Path::new("bad.toml")).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn plugin_secrets_are_masked_in_the_cli_log_and_errors() {
+        let toml = PLUGIN.to_string() + r#"
+[test]
+program = "sh"
+args = ["-c", "echo \"$0 $1\" >&2; exit 37", "{api_key}", "{config_json}"]
+"#;
+        let def = parse_plugin(&toml, Path::new("x.toml")).unwrap();
+        let entry = ProviderScratchEntry {
+            id: "p1".to_string(),
+            name: "mine".to_string(),
+            ptype: "mybackend".to_string(),
+            tags: Vec::new(),
+            disabled: false,
+            session_secrets: Vec::new(),
+            config: serde_json::json!({"api_key": "s3cret"}),
+        };
+        let cmd = def.test_command(&entry, TestMode::E2e).unwrap();
+        assert!(cmd.args[3].contains("s3cret"), "config_json carries the key");
+        let err = cmd.stream(&AtomicBool::new(false), |_| {}).unwrap_err().to_string();
+        assert!(!err.contains("s3cret"), "{err}");
+
+        let call = cli_calls().into_iter().find(|c| c.args.iter().any(|a| a.contains("exit 37"))).unwrap();
+        assert_eq!(call.args[2], "••••••");
+        assert!(!call.command_line().contains("s3cret") && !call.output.contains("s3cret"), "{call:?}");
+    }
+}
@@ -0,0 +1,177 @@
+//! Port checks for local model servers (Ollama, LM Studio, llama.cpp's
+//! `llama-server`). Before a test calls the CLI, the configured host:port is
+//! probed directly, so a stopped server or another program on the port gets a
+//! message like "port open but not Ollama" instead of a CLI timeout. Saving runs
+//! the same check in the background and warns without blocking the save.
+
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::sync::mpsc::Sender;
+use std::time::Duration;
+
+use crate::app::{Notification, Page};
+use crate::jobs::Jobs;
+
+use super::probe::cfg_str;
+use super::state::ProviderScratchEntry;
+
+/// Connect and read timeout for one port check.
+pub(super) const CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+/// Bytes of the reply read before giving up on the rest.
+const MAX_REPLY: u64 = 64 * 1024;
+
+/// A local server type and how to recognise it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct LocalService {
+    pub name: &'static str,
+    pub default_port: u16,
+    /// Path requested to identify the server.
+    pub path: &'static str,
+    /// Text the reply body contains when it is this server.
+    pub marker: &'static str,
+}
+
+/// The server behind provider type `ptype`, if it is one we can check.
+pub fn service_for(ptype: &str) -> Option<LocalService> {
+    let svc = match ptype {
+        "ollama" => LocalService { name: "Ollama", default_port: 11434, path: "/api/version", marker: "\"version\"" },
+        "lmstudio" => LocalService { name: "LM Studio", default_port: 1234, path: "/v1/models", marker: "\"data\"" },
+        "llama-server" => LocalService { name: "llama-server", default_port: 8080, path: "/health", marker: "\"status\"" },
+        _ => return None,
+    };
+    Some(svc)
+}
+
+/// What answered on a port.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PortProbe {
+    /// Connection refused or timed out.
+    Closed,
+    /// Accepted the connection but sent no HTTP reply.
+    NotHttp,
+    /// HTTP status and body.
+    Http(u16, String),
+}
+
+/// Check `entry`'s server. `None` for types without a local server, otherwise
+/// `Ok` or a user-facing reason the CLI call would fail.
+pub fn check_local_port(entry: &ProviderScratchEntry) -> Option<Result<(), String>> {
+    let svc = service_for(&entry.ptype)?;
+    let host = cfg_str(entry, "host").unwrap_or_else(|| "127.0.0.1".to_string());
+    let host = host.trim_start_matches("http://").trim_start_matches("https://").trim_end_matches('/').to_string();
+    let port = match cfg_str(entry, "port") {
+        Some(p) => match p.parse::<u16>() {
+            Ok(n) => n,
+            Err(_) => return Some(Err(format!("invalid port: {}", p))),
+        },
+        None => svc.default_port,
+    };
+    let Some(addr) = (host.as_str(), port).to_socket_addrs().ok().and_then(|mut a| a.next()) else {
+        return Some(Err(format!("cannot resolve host {}", host)));
+    };
+    Some(classify(svc, &format!("{}:{}", host, port), &probe_port(addr, &host, svc.path)))
+}
+
+/// After a save: check the servers of the saved (enabled) `entries` in one job
+/// and send a warning for each that a test would fail on.
+pub fn warn_about_ports(entries: Vec<ProviderScratchEntry>, notify: Sender<Notification>, jobs: &Jobs) {
+    let entries: Vec<_> = entries.into_iter().filter(|e| !e.disabled && service_for(&e.ptype).is_some()).collect();
+    if entries.is_empty() { return; }
+    jobs.spawn("Checking local server ports", move |_| {
+        for e in &entries {
+            if let Some(Err(why)) = check_local_port(e) {
+                let name = if e.name.is_empty() { &e.id } else { &e.name };
+                let _ = notify.send(Notification::new(format!("Saved {}, but {}", name, why), false, Page::Configure));
+            }
+        }
+    });
+}
+
+/// `GET path` on `addr` over plain HTTP.
+pub fn probe_port(addr: SocketAddr, host: &str, path: &str) -> PortProbe {
+    let Ok(mut stream) = TcpStream::connect_timeout(&addr, CHECK_TIMEOUT) else { return PortProbe::Closed };
+    let _ = stream.set_read_timeout(Some(CHECK_TIMEOUT));
+    let _ = stream.set_write_timeout(Some(CHECK_TIMEOUT));
+    let request = format!("GET {} HTTP/1.0\r\nHost: {}:{}\r\nConnection: close\r\n\r\n", path, host, addr.port());
+    if stream.write_all(request.as_bytes()).is_err() { return PortProbe::NotHttp; }
+    let mut reply = Vec::new();
+    // A timeout after part of the reply still leaves what arrived
+    let _ = stream.take(MAX_REPLY).read_to_end(&mut reply);
+    parse_reply(&reply).map_or(PortProbe::NotHttp, |(status, body)| PortProbe::Http(status, body))
+}
+
+/// Status code and body of a raw HTTP reply.
+fn parse_reply(reply: &[u8]) -> Option<(u16, String)> {
+    let text = String::from_utf8_lossy(reply);
+    let status_line = text.lines().next()?;
+    let mut parts = status_line.split_whitespace();
+    if !parts.next()?.starts_with("HTTP/") { return None; }
+    let status = parts.next()?.parse().ok()?;
+    let body = text.split_once("\r\n\r\n").map(|(_, b)| b.to_string()).unwrap_or_default();
+    Some((status, body))
+}
+
+/// `Ok` when `probe` looks like `svc`, otherwise what is wrong with `addr`.
+pub fn classify(svc: LocalService, addr: &str, probe: &PortProbe) -> Result<(), String> {
+    match probe {
+        PortProbe::Closed => Err(format!("nothing is listening on {}; is {} running?", addr, svc.name)),
+        PortProbe::NotHttp => Err(format!("port {} is open but not {} (no HTTP reply)", addr, svc.name)),
+        // llama-server answers 503 while it loads the model
+        PortProbe::Http(status, body) if body.contains(svc.marker) && (*status == 200 || *status == 503) => Ok(()),
+        PortProbe::Http(status, _) => Err(format!("port {} is open but not {} (HTTP {} on {})", addr, svc.name, status, svc.path)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+    use std::thread;
+
+    fn serve_once(reply: &'static str) -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        thread::spawn(move || {
+            let (mut s, _) = listener.accept().unwrap();
+            let mut buf = [0u8; 512];
+            let _ = s.read(&mut buf);
+            let _ = s.write_all(reply.as_bytes());
+        });
+        addr
+    }
+
+    #[test]
+    fn port_replies_are_told_apart() {
+        let ollama = service_for("ollama").unwrap();
+        let addr = serve_once("HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\r\n{\"version\":\"0.3.0\"}");
+        assert_eq!(classify(ollama, "a", &probe_port(addr, "127.0.0.1", ollama.path)), Ok(()));
+
+        let addr = serve_once("HTTP/1.1 404 Not Found\r\n\r\nnot here");
+        let probe = probe_port(addr, "127.0.0.1", ollama.path);
+        assert_eq!(probe, PortProbe::Http(404, "not here".to_string()));
+        assert_eq!(classify(ollama, "127.0.0.1:1", &probe).unwrap_err(), "port 127.0.0.1:1 is open but not Ollama (HTTP 404 on /api/version)");
+
+        let addr = serve_once("SSH-2.0-OpenSSH\r\n");
+        assert_eq!(probe_port(addr, "127.0.0.1", ollama.path), PortProbe::NotHttp);
+
+        let closed = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+        assert_eq!(probe_port(closed, "127.0.0.1", ollama.path), PortProbe::Closed);
+        assert!(classify(ollama, "x", &PortProbe::Closed).unwrap_err().contains("is Ollama running?"));
+        assert!(service_for("openai").is_none());
+    }
+
+    #[test]
+    fn saving_warns_about_a_port_taken_by_another_server() {
+        let addr = serve_once("HTTP/1.1 404 Not Found\r\n\r\nnot here");
+        let ollama = ProviderScratchEntry::from_json(&serde_json::json!({"id": "p1", "name": "laptop", "type": "ollama",
+            "config": {"type": "ollama", "host": "127.0.0.1", "port": addr.port().to_string()}}));
+        let cloud = ProviderScratchEntry::from_json(&serde_json::json!({"id": "p2", "type": "openai", "config": {"type": "openai"}}));
+        let (tx, rx) = std::sync::mpsc::channel();
+        warn_about_ports(vec![ollama, cloud], tx, &Jobs::default());
+        let n = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert!(!n.ok);
+        assert!(n.message.starts_with("Saved laptop, but port 127.0.0.1:"), "{}", n.message);
+        // One warning; the cloud provider has no server to check
+        assert!(rx.recv_timeout(Duration::from_secs(5)).is_err());
+    }
+}
@@ -0,0 +1,76 @@
+//! Built-in provider presets for Configure's `A`: common setups such as a local
+//! Ollama or OpenAI's gpt-4o-mini, added as a new provider with the fields
+//! filled in. API keys are `${VAR}` references to the usual variables, which
+//! chi-llm expands when it loads the config, so a preset never holds a secret.
+//! Only types chi-llm implements have presets.
+
+use serde_json::{json, Map, Value};
+
+use crate::forms::FieldSchema;
+
+pub struct Preset {
+    pub name: &'static str,
+    pub ptype: &'static str,
+    config: fn() -> Value,
+}
+
+impl Preset {
+    /// Dropdown row: `name (type)`.
+    pub fn label(&self) -> String {
+        format!("{} ({})", self.name, self.ptype)
+    }
+
+    /// The preset's config, limited to the fields `schema` declares when it is
+    /// known, so chi-llm versions without a field do not get it.
+    pub fn config(&self, schema: Option<&[FieldSchema]>) -> Map<String, Value> {
+        let Value::Object(mut config) = (self.config)() else { return Map::new() };
+        if let Some(schema) = schema.filter(|s| !s.is_empty()) {
+            config.retain(|k, _| schema.iter().any(|sc| sc.name == *k));
+        }
+        config.insert("type".to_string(), Value::String(self.ptype.to_string()));
+        config
+    }
+}
+
+pub const PRESETS: &[Preset] = &[
+    Preset { name: "Ollama on localhost", ptype: "ollama", config: || json!({"host": "127.0.0.1", "port": 11434, "model": "llama3.2"}) },
+    Preset { name: "LM Studio default", ptype: "lmstudio", config: || json!({"host": "127.0.0.1", "port": 1234}) },
+    Preset { name: "OpenAI gpt-4o-mini", ptype: "openai", config: || json!({"model": "gpt-4o-mini", "api_key": "${OPENAI_API_KEY}"}) },
+    Preset {
+        name: "Anthropic Claude 3.5 Haiku",
+        ptype: "anthropic",
+        config: || json!({"model": "claude-3-5-haiku-latest", "api_key": "${ANTHROPIC_API_KEY}"}),
+    },
+];
+
+/// Presets whose type the installed chi-llm offers; all of them when the type
+/// list is unknown.
+pub fn available_presets(types: &[String]) -> Vec<&'static Preset> {
+    PRESETS.iter().filter(|p| types.is_empty() || types.iter().any(|t| t == p.ptype)).collect()
+}
+
+/// The preset whose `label` is `label`.
+pub fn preset_by_label(label: &str) -> Option<&'static Preset> {
+    PRESETS.iter().find(|p| p.label() == label)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presets_follow_the_installed_types_and_schema() {
+        let types = vec!["ollama".to_string(), "openai".to_string()];
+        let names: Vec<&str> = available_presets(&types).iter().map(|p| p.name).collect();
+        assert_eq!(names, ["Ollama on localhost", "OpenAI gpt-4o-mini"]);
+        assert_eq!(available_presets(&[]).len(), PRESETS.len());
+        // chi-llm lists these types but does not implement them yet
+        assert!(PRESETS.iter().all(|p| !["groq", "gemini"].contains(&p.ptype)));
+
+        let ollama = preset_by_label("Ollama on localhost (ollama)").unwrap();
+        let host = FieldSchema { name: "host".to_string(), ftype: "string".to_string(), required: false, default: None, help: None, options: None };
+        assert_eq!(Value::Object(ollama.config(Some(&[host]))), json!({"type": "ollama", "host": "127.0.0.1"}));
+        assert_eq!(ollama.config(None)["port"], 11434);
+        assert!(PRESETS.iter().all(|p| p.config(None).get("api_key").and_then(Value::as_str).is_none_or(|k| k.starts_with("${"))));
+    }
+}
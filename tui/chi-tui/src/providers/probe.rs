@@ -0,0 +1,500 @@
+use std::collections::HashSet;
+use std::sync::atomic::AtomicBool;
+use std::sync::mpsc::Sender;
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+
+use crate::app::{Notification, Page};
+use crate::build::provider_block;
+use crate::env::timeouts;
+use crate::interp::resolve_config;
+use crate::keychain::resolve_keychain;
+use crate::jobs::{Job, Jobs};
+use crate::util::{run_secret_program_text, stream_program, Pipe};
+
+use super::budget::estimate_tokens;
+use super::local::is_local_type;
+use super::network::{header_lines, network_env, PROXY_URL};
+use super::plugins::plugin_for;
+use super::ports::check_local_port;
+use super::state::ProviderScratchEntry;
+
+/// Prompt used by end-to-end tests unless `settings.toml` sets another.
+pub const E2E_PROMPT: &str = "Reply with the single word: ok";
+/// `--max-tokens` for end-to-end tests.
+pub const E2E_MAX_TOKENS: &str = "16";
+/// `--max-tokens` for prompts run from the Configure page.
+pub const PROMPT_MAX_TOKENS: &str = "512";
+
+static TEST_PROMPT: RwLock<Option<String>> = RwLock::new(None);
+
+/// Prompt end-to-end tests send: `[test] prompt` from `settings.toml` (see
+/// `set_e2e_prompt`), else `E2E_PROMPT`.
+pub fn e2e_prompt() -> String {
+    TEST_PROMPT.read().unwrap_or_else(|e| e.into_inner()).clone().unwrap_or_else(|| E2E_PROMPT.to_string())
+}
+
+/// Use `prompt` for end-to-end tests from now on; `None` or a blank one
+/// restores `E2E_PROMPT`.
+pub fn set_e2e_prompt(prompt: Option<String>) {
+    *TEST_PROMPT.write().unwrap_or_else(|e| e.into_inner()) = prompt.filter(|p| !p.trim().is_empty());
+}
+
+/// How a provider test exercises the backend.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TestMode {
+    /// Reachability check: list models via `providers discover-models`.
+    Connection,
+    /// Short `generate` call with the entry injected through `CHI_LLM_CONFIG`.
+    E2e,
+}
+
+impl TestMode {
+    /// Connection check where the CLI can discover models, end-to-end otherwise.
+    pub fn default_for(ptype: &str) -> Self {
+        if let Some(plugin) = plugin_for(ptype) {
+            return if plugin.discover.is_some() { TestMode::Connection } else { TestMode::E2e };
+        }
+        match ptype {
+            "lmstudio" | "ollama" | "openai" => TestMode::Connection,
+            _ => TestMode::E2e,
+        }
+    }
+
+    fn timeout(self) -> Duration {
+        match self {
+            TestMode::Connection => timeouts().test,
+            TestMode::E2e => timeouts().e2e,
+        }
+    }
+}
+
+/// A test invocation: program (`chi-llm` unless a plugin says otherwise), its
+/// arguments and extra environment variables.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TestCommand {
+    pub program: String,
+    pub args: Vec<String>,
+    pub env: Vec<(String, String)>,
+    /// Secret values that appear in `args`; masked by `redacted`.
+    pub secrets: Vec<String>,
+}
+
+impl TestCommand {
+    pub fn chi_llm(args: Vec<String>, env: Vec<(String, String)>) -> Self {
+        Self { program: "chi-llm".to_string(), args, env, secrets: Vec::new() }
+    }
+
+    /// Shell-like rendering for logs, with secret values masked.
+    pub fn redacted(&self) -> String {
+        let mut out: Vec<String> = self.env.iter().map(|(k, _)| format!("{}=…", k)).collect();
+        out.push(self.program.clone());
+        for a in &self.args {
+            let masked = self.secrets.iter().filter(|s| !s.is_empty()).fold(a.clone(), |acc, s| acc.replace(s.as_str(), "••••••"));
+            out.push(masked);
+        }
+        out.join(" ")
+    }
+
+    /// Run to completion; setting `cancel` kills the program.
+    fn run(&self, timeout: Duration, cancel: &AtomicBool) -> Result<String> {
+        let argv: Vec<&str> = self.args.iter().map(String::as_str).collect();
+        run_secret_program_text(&self.program, &argv, &self.env, &self.secrets, timeout, cancel)
+    }
+
+    /// Run with stdout passed to `on_chunk` as it arrives, until done or `cancel` is set.
+    pub fn stream(&self, cancel: &AtomicBool, mut on_chunk: impl FnMut(&str)) -> Result<()> {
+        self.stream_all(None, cancel, |pipe, chunk| if pipe == Pipe::Stdout { on_chunk(chunk) })
+    }
+
+    /// Run with stdout and stderr passed to `on_chunk` as they arrive, until
+    /// done, `cancel` is set or `timeout` passes.
+    pub fn stream_all(&self, timeout: Option<Duration>, cancel: &AtomicBool, on_chunk: impl FnMut(Pipe, &str)) -> Result<()> {
+        let argv: Vec<&str> = self.args.iter().map(String::as_str).collect();
+        stream_program(&self.program, &argv, &self.env, &self.secrets, timeout, cancel, on_chunk)
+    }
+}
+
+/// `entry` with its `${VAR}` references replaced by the environment's values
+/// and `keyring:` references by the keychain's, for a CLI call; the stored
+/// config keeps the references.
+pub fn resolved_entry(entry: &ProviderScratchEntry) -> Result<ProviderScratchEntry, String> {
+    let config = resolve_config(&entry.config)
+        .map_err(|unset| format!("{}: environment variable {} is not set", entry.ptype, unset.join(", ")))?;
+    let config = resolve_keychain(&config)
+        .map_err(|missing| format!("{}: no keychain entry for {}", entry.ptype, missing.join(", ")))?;
+    Ok(ProviderScratchEntry { config, ..entry.clone() })
+}
+
+/// Config value as a string; numbers (e.g. ports saved as int) are stringified.
+pub(super) fn cfg_str(entry: &ProviderScratchEntry, key: &str) -> Option<String> {
+    match entry.config.get(key)? {
+        Value::String(s) if !s.trim().is_empty() => Some(s.trim().to_string()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// Build the CLI invocation that tests `entry` in the given mode. `Err` carries a
+/// user-facing reason when the entry cannot be tested that way.
+pub fn build_test_args(entry: &ProviderScratchEntry, mode: TestMode) -> Result<TestCommand, String> {
+    let entry = &resolved_entry(entry)?;
+    let ptype = entry.ptype.as_str();
+    if let Some(plugin) = plugin_for(ptype) {
+        return plugin.test_command(entry, mode);
+    }
+    let mut args: Vec<String> = Vec::new();
+    let mut env = network_env(entry);
+    let mut secrets: Vec<String> = Vec::new();
+    // Proxy URLs may embed credentials
+    if let Some(proxy) = cfg_str(entry, PROXY_URL) { secrets.push(proxy); }
+    match mode {
+        TestMode::Connection => {
+            args.extend(["providers", "discover-models", "--type", ptype].map(String::from));
+            match ptype {
+                "lmstudio" | "ollama" => {
+                    let default_port = if ptype == "lmstudio" { "1234" } else { "11434" };
+                    let host = cfg_str(entry, "host").unwrap_or_else(|| "127.0.0.1".to_string());
+                    let port = cfg_str(entry, "port").unwrap_or_else(|| default_port.to_string());
+                    args.extend(["--host".to_string(), host, "--port".to_string(), port]);
+                }
+                "openai" => {
+                    let api_key = cfg_str(entry, "api_key").ok_or_else(|| "openai: missing api_key".to_string())?;
+                    let base = cfg_str(entry, "base_url").unwrap_or_else(|| "https://api.openai.com".to_string());
+                    secrets.push(api_key.clone());
+                    args.extend(["--base-url".to_string(), base, "--api-key".to_string(), api_key]);
+                    if let Some(org) = cfg_str(entry, "org_id") {
+                        args.extend(["--org-id".to_string(), org]);
+                    }
+                    for h in header_lines(entry) {
+                        secrets.extend(h.split_once(':').map(|(_, v)| v.trim().to_string()));
+                        args.extend(["--header".to_string(), h]);
+                    }
+                }
+                _ => return Err(format!("{}: no connection test; use end-to-end test", ptype)),
+            }
+            args.push("--json".to_string());
+        }
+        TestMode::E2e => {
+            if ptype.is_empty() {
+                return Err("provider type missing".to_string());
+            }
+            // `--` keeps a prompt starting with `-` from reading as an option
+            args.extend(["generate", "--max-tokens", E2E_MAX_TOKENS, "--"].map(String::from));
+            args.push(e2e_prompt());
+            let cfg = serde_json::json!({ "provider": provider_block(ptype, &entry.config) });
+            env.push(("CHI_LLM_CONFIG".to_string(), cfg.to_string()));
+        }
+    }
+    Ok(TestCommand { secrets, ..TestCommand::chi_llm(args, env) })
+}
+
+/// `chi-llm generate --stream` for an ad-hoc prompt, set up like an end-to-end
+/// test. Plugin types have no generate command.
+pub fn build_generate_args(entry: &ProviderScratchEntry, prompt: &str, max_tokens: &str) -> Result<TestCommand, String> {
+    if plugin_for(&entry.ptype).is_some() {
+        return Err(format!("{}: plugin providers cannot run prompts", entry.ptype));
+    }
+    let mut cmd = build_test_args(entry, TestMode::E2e)?;
+    cmd.args = ["generate", "--max-tokens", max_tokens, "--stream", "--", prompt].map(String::from).to_vec();
+    Ok(cmd)
+}
+
+/// Command listing the models offered for `entry`'s model field: chi-llm's own
+/// catalog for local types, otherwise the connection test's `discover-models`.
+pub fn build_discovery_args(entry: &ProviderScratchEntry) -> Result<TestCommand, String> {
+    if is_local_type(&entry.ptype) && plugin_for(&entry.ptype).is_none() {
+        return Ok(TestCommand::chi_llm(["models", "list", "--json"].map(String::from).to_vec(), Vec::new()));
+    }
+    build_test_args(entry, TestMode::Connection)
+}
+
+/// Model ids from a discovery run, plus those reported as `"downloaded": true`.
+#[derive(Clone, Debug, Default)]
+pub struct DiscoveredModels {
+    pub ids: Vec<String>,
+    pub downloaded: HashSet<String>,
+}
+
+/// Run a discovery command built by `build_discovery_args` and return model ids.
+/// Output is `{"models": [{"id": ..}]}` JSON or a bare array of such objects
+/// (`models list`); plugins may also print one id per line.
+pub fn discover_models(cmd: &TestCommand, cancel: &AtomicBool) -> Result<DiscoveredModels> {
+    parse_discovered(cmd, &cmd.run(TestMode::Connection.timeout(), cancel)?)
+}
+
+/// Models in the output of a discovery command: chi-llm's JSON, or one id per
+/// line from a plugin's program.
+fn parse_discovered(cmd: &TestCommand, text: &str) -> Result<DiscoveredModels> {
+    let Ok(v) = serde_json::from_str::<Value>(text) else {
+        if cmd.program == "chi-llm" { return Err(anyhow!("unexpected discovery output: {}", text.chars().take(80).collect::<String>())); }
+        let ids = text.lines().map(str::trim).filter(|l| !l.is_empty()).map(String::from).collect();
+        return Ok(DiscoveredModels { ids, downloaded: HashSet::new() });
+    };
+    if let Some(err) = v.get("error").and_then(|e| e.as_str()) {
+        return Err(anyhow!("{}", err));
+    }
+    let mut found = DiscoveredModels::default();
+    if let Some(arr) = v.get("models").or(Some(&v)).and_then(|x| x.as_array()) {
+        for it in arr {
+            let Some(id) = it.get("id").and_then(|x| x.as_str()).or_else(|| it.as_str()) else { continue };
+            if it.get("downloaded").and_then(|x| x.as_bool()) == Some(true) { found.downloaded.insert(id.to_string()); }
+            found.ids.push(id.to_string());
+        }
+    }
+    Ok(found)
+}
+
+/// Test `entry` through the CLI and return a short status line. Local servers
+/// get a port check first (see `ports`). Setting `cancel` stops the CLI call.
+pub fn probe_provider(entry: &ProviderScratchEntry, mode: TestMode, cancel: &AtomicBool) -> Result<String> {
+    probe_with_reply(entry, mode, cancel).map(|(status, _)| status)
+}
+
+/// `probe_provider`, plus the whole reply of an end-to-end test.
+pub fn probe_with_reply(entry: &ProviderScratchEntry, mode: TestMode, cancel: &AtomicBool) -> Result<(String, Option<String>)> {
+    let cmd = build_test_args(entry, mode).map_err(|e| anyhow!(e))?;
+    check_port(entry)?;
+    probe_result(entry, mode, &cmd, cmd.run(mode.timeout(), cancel)?)
+}
+
+/// `probe_with_reply` with `cmd` from `build_test_args`, passing the command's
+/// stdout and stderr to `on_output` as they arrive (`chi-tui test`).
+pub fn probe_streaming(
+    entry: &ProviderScratchEntry,
+    mode: TestMode,
+    cmd: &TestCommand,
+    cancel: &AtomicBool,
+    mut on_output: impl FnMut(Pipe, &str),
+) -> Result<(String, Option<String>)> {
+    check_port(entry)?;
+    let mut out = String::new();
+    cmd.stream_all(Some(mode.timeout()), cancel, |pipe, chunk| {
+        if pipe == Pipe::Stdout { out.push_str(chunk); }
+        on_output(pipe, chunk);
+    })?;
+    probe_result(entry, mode, cmd, out.trim().to_string())
+}
+
+fn check_port(entry: &ProviderScratchEntry) -> Result<()> {
+    match check_local_port(&resolved_entry(entry).map_err(|e| anyhow!(e))?) {
+        Some(Err(e)) => Err(anyhow!(e)),
+        _ => Ok(()),
+    }
+}
+
+/// Status line (and end-to-end reply) from a test command's stdout.
+fn probe_result(entry: &ProviderScratchEntry, mode: TestMode, cmd: &TestCommand, out: String) -> Result<(String, Option<String>)> {
+    match mode {
+        TestMode::Connection => {
+            let models = parse_discovered(cmd, &out)?;
+            Ok((format!("{}: {} models", entry.ptype, models.ids.len()), None))
+        }
+        TestMode::E2e => {
+            let short: String = out.chars().take(60).collect();
+            Ok((format!("{}: replied \"{}\"", entry.ptype, short), Some(out)))
+        }
+    }
+}
+
+/// Result of a provider test that ran as a background job.
+#[derive(Clone, Debug)]
+pub struct ProviderTestOutcome {
+    pub entry_id: String,
+    /// Form hash captured when the test started (None for list-level tests).
+    pub form_hash: Option<String>,
+    pub ok: bool,
+    pub status: String,
+    /// Estimated (input, output) tokens billed by a passed end-to-end test.
+    pub tokens: Option<(u64, u64)>,
+    pub mode: TestMode,
+    /// Wall time of the CLI call.
+    pub elapsed: Duration,
+    /// Whole reply of a passed end-to-end test, for the log panel.
+    pub reply: Option<String>,
+}
+
+/// Run `probe_provider` as a job. The outcome is delivered on the returned handle
+/// and a completion toast is sent through the global notification channel.
+pub fn spawn_provider_test(
+    entry: ProviderScratchEntry,
+    mode: TestMode,
+    form_hash: Option<String>,
+    notify: Sender<Notification>,
+    jobs: &Jobs,
+) -> Job<ProviderTestOutcome> {
+    let label = format!("Testing {}", entry.name);
+    jobs.spawn(label, move |ctx| {
+        let started = Instant::now();
+        if mode == TestMode::E2e { ctx.progress("end-to-end"); }
+        let (ok, status, reply) = match probe_with_reply(&entry, mode, ctx.cancel_flag()) {
+            Ok((msg, reply)) => (true, msg, reply),
+            Err(e) => (false, format!("Error: {}", e), None),
+        };
+        let message = format!("Test {}: {}", entry.name, status);
+        // The reply is capped by --max-tokens, so count the cap as output
+        let tokens = (ok && mode == TestMode::E2e).then(|| (estimate_tokens(&e2e_prompt()), E2E_MAX_TOKENS.parse().unwrap_or(0)));
+        let elapsed = started.elapsed();
+        // A cancelled test was already reported by whoever cancelled it
+        if !ctx.is_cancelled() { let _ = notify.send(Notification::new(message, ok, Page::Configure)); }
+        ProviderTestOutcome { entry_id: entry.id.clone(), form_hash, ok, status, tokens, mode, elapsed, reply }
+    })
+}
+
+/// Model ids discovered for one form field by a background job.
+#[derive(Clone, Debug)]
+pub struct DiscoveryOutcome {
+    pub entry_id: String,
+    pub ptype: String,
+    /// Index of the form field that requested the list.
+    pub field: usize,
+    pub result: Result<DiscoveredModels, String>,
+}
+
+/// Run `discover_models` as a job; the outcome arrives on the returned handle.
+pub fn spawn_model_discovery(cmd: TestCommand, entry: &ProviderScratchEntry, field: usize, jobs: &Jobs) -> Job<DiscoveryOutcome> {
+    let (entry_id, ptype) = (entry.id.clone(), entry.ptype.clone());
+    jobs.spawn(format!("Discovering {} models", ptype), move |ctx| {
+        let result = discover_models(&cmd, ctx.cancel_flag()).map_err(|e| e.to_string());
+        DiscoveryOutcome { entry_id, ptype, field, result }
+    })
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use super::*;
+
+    /// Provider `p1` of type `ptype`, named after it.
+    pub(in crate::providers) fn entry(ptype: &str, config: Value) -> ProviderScratchEntry {
+        ProviderScratchEntry {
+            id: "p1".to_string(),
+            name: ptype.to_string(),
+            ptype: ptype.to_string(),
+            tags: Vec::new(),
+            disabled: false,
+            session_secrets: Vec::new(),
+            config,
+        }
+    }
+
+    fn args(cmd: &TestCommand) -> Vec<&str> {
+        cmd.args.iter().map(String::as_str).collect()
+    }
+
+    #[test]
+    fn local_types_list_the_model_catalog() {
+        let cmd = build_discovery_args(&entry("local", serde_json::json!({}))).unwrap();
+        assert_eq!(args(&cmd), ["models", "list", "--json"]);
+        let cmd = build_discovery_args(&entry("ollama", serde_json::json!({}))).unwrap();
+        assert_eq!(args(&cmd)[..2], ["providers", "discover-models"]);
+    }
+
+    #[test]
+    fn lmstudio_uses_defaults() {
+        let cmd = build_test_args(&entry("lmstudio", serde_json::json!({})), TestMode::Connection).unwrap();
+        assert_eq!(
+            args(&cmd),
+            ["providers", "discover-models", "--type", "lmstudio", "--host", "127.0.0.1", "--port", "1234", "--json"]
+        );
+        assert!(cmd.env.is_empty());
+    }
+
+    #[test]
+    fn ollama_accepts_numeric_and_string_port() {
+        let numeric = entry("ollama", serde_json::json!({"host": "gpu-box", "port": 11500}));
+        let cmd = build_test_args(&numeric, TestMode::Connection).unwrap();
+        assert_eq!(
+            args(&cmd),
+            ["providers", "discover-models", "--type", "ollama", "--host", "gpu-box", "--port", "11500", "--json"]
+        );
+        let string = entry("ollama", serde_json::json!({"port": "11500"}));
+        let cmd = build_test_args(&string, TestMode::Connection).unwrap();
+        assert!(args(&cmd).windows(2).any(|w| w == ["--port", "11500"]));
+        let default = build_test_args(&entry("ollama", serde_json::json!({})), TestMode::Connection).unwrap();
+        assert!(args(&default).windows(2).any(|w| w == ["--port", "11434"]));
+    }
+
+    #[test]
+    fn openai_requires_api_key_and_passes_org() {
+        let missing = build_test_args(&entry("openai", serde_json::json!({})), TestMode::Connection);
+        assert_eq!(missing.unwrap_err(), "openai: missing api_key");
+        let cfg = serde_json::json!({"api_key": "sk-1", "org_id": "org-9"});
+        let cmd = build_test_args(&entry("openai", cfg), TestMode::Connection).unwrap();
+        assert_eq!(
+            args(&cmd),
+            [
+                "providers", "discover-models", "--type", "openai", "--base-url", "https://api.openai.com",
+                "--api-key", "sk-1", "--org-id", "org-9", "--json"
+            ]
+        );
+        let cfg = serde_json::json!({"api_key": "sk-1", "headers": {"X-Tenant": "t1"}});
+        let cmd = build_test_args(&entry("openai", cfg), TestMode::Connection).unwrap();
+        assert!(args(&cmd).windows(2).any(|w| w == ["--header", "X-Tenant: t1"]));
+        assert!(cmd.redacted().contains("X-Tenant: ••••••"));
+    }
+
+    #[test]
+    fn env_references_are_resolved_for_the_call_only() {
+        let e = entry("openai", serde_json::json!({"api_key": "${CHI_TUI_PROBE_KEY}"}));
+        let unset = build_test_args(&e, TestMode::Connection).unwrap_err();
+        assert_eq!(unset, "openai: environment variable CHI_TUI_PROBE_KEY is not set");
+        std::env::set_var("CHI_TUI_PROBE_KEY", "sk-env");
+        let cmd = build_test_args(&e, TestMode::Connection).unwrap();
+        assert!(args(&cmd).contains(&"sk-env"));
+        assert!(!cmd.redacted().contains("sk-env"));
+        assert_eq!(e.config["api_key"], "${CHI_TUI_PROBE_KEY}");
+        let e2e = build_test_args(&e, TestMode::E2e).unwrap();
+        assert!(e2e.env.iter().any(|(k, v)| k == "CHI_LLM_CONFIG" && v.contains("sk-env")));
+    }
+
+    #[test]
+    fn non_discoverable_types_have_no_connection_test() {
+        for ptype in ["local", "local-zeroconfig", "local-custom", "anthropic", "claude-cli", "openai-cli", "groq", "gemini"] {
+            let res = build_test_args(&entry(ptype, serde_json::json!({})), TestMode::Connection);
+            assert!(res.is_err(), "{ptype} should not have a connection test");
+            assert_eq!(TestMode::default_for(ptype), TestMode::E2e);
+        }
+        for ptype in ["lmstudio", "ollama", "openai"] {
+            assert_eq!(TestMode::default_for(ptype), TestMode::Connection);
+        }
+    }
+
+    #[test]
+    fn e2e_injects_canonical_provider_config() {
+        let cfg = serde_json::json!({"type": "local-custom", "model_path": "/m.gguf", "model": ""});
+        let cmd = build_test_args(&entry("local-custom", cfg), TestMode::E2e).unwrap();
+        assert_eq!(args(&cmd), ["generate", "--max-tokens", "16", "--", E2E_PROMPT]);
+        assert_eq!(cmd.env.len(), 1);
+        assert_eq!(cmd.env[0].0, "CHI_LLM_CONFIG");
+        let injected: Value = serde_json::from_str(&cmd.env[0].1).unwrap();
+        assert_eq!(injected, serde_json::json!({"provider": {"type": "local", "model_path": "/m.gguf"}}));
+    }
+
+    #[test]
+    fn prompts_stream_with_the_e2e_setup() {
+        let cmd = build_generate_args(&entry("ollama", serde_json::json!({"model": "llama3"})), "hi there", PROMPT_MAX_TOKENS).unwrap();
+        assert_eq!(args(&cmd), ["generate", "--max-tokens", PROMPT_MAX_TOKENS, "--stream", "--", "hi there"]);
+        assert_eq!(cmd.env[0].0, "CHI_LLM_CONFIG");
+        let dashed = build_generate_args(&entry("ollama", serde_json::json!({"model": "llama3"})), "-v please", PROMPT_MAX_TOKENS).unwrap();
+        assert_eq!(args(&dashed).last(), Some(&"-v please"));
+    }
+
+    #[test]
+    fn e2e_requires_type() {
+        assert!(build_test_args(&entry("", serde_json::json!({})), TestMode::E2e).is_err());
+    }
+
+    #[test]
+    fn redacted_masks_api_key_and_env() {
+        let e = entry("openai", serde_json::json!({"api_key": "sk-secret", "model": "gpt"}));
+        let text = build_test_args(&e, TestMode::Connection).unwrap().redacted();
+        assert!(!text.contains("sk-secret"));
+        assert!(text.contains("--api-key ••••••"));
+        let text = build_test_args(&e, TestMode::E2e).unwrap().redacted();
+        assert!(text.starts_with("CHI_LLM_CONFIG=… chi-llm generate"));
+        assert!(!text.contains("sk-secret"));
+    }
+}
@@ -0,0 +1,115 @@
+//! `ollama pull` for ollama providers. Progress streams to the log panel; the
+//! Configure page polls the outcome and refreshes the model dropdown.
+
+use std::process::{Command, Stdio};
+use std::sync::mpsc::Sender;
+use std::thread;
+use std::time::Duration;
+
+use crate::app::{Notification, Page};
+use crate::jobs::{Job, Jobs};
+use crate::log::{stream_output, LogLine};
+
+use super::probe::cfg_str;
+use super::state::ProviderScratchEntry;
+
+#[derive(Clone, Debug)]
+pub struct PullOutcome {
+    pub entry_id: String,
+    pub model: String,
+    pub ok: bool,
+}
+
+/// `OLLAMA_HOST` for the provider's host/port, when either is configured.
+pub fn ollama_host(entry: &ProviderScratchEntry) -> Option<String> {
+    let (host, port) = (cfg_str(entry, "host"), cfg_str(entry, "port"));
+    if host.is_none() && port.is_none() { return None; }
+    Some(format!("{}:{}", host.as_deref().unwrap_or("127.0.0.1"), port.as_deref().unwrap_or("11434")))
+}
+
+/// Run `ollama pull <model>` against `entry`'s server as a job; cancelling it
+/// kills `ollama`.
+pub fn spawn_ollama_pull(entry: &ProviderScratchEntry, model: String, log: Sender<LogLine>, notify: Sender<Notification>, jobs: &Jobs) -> Job<PullOutcome> {
+    spawn_pull("ollama", entry, model, log, notify, jobs)
+}
+
+/// `spawn_ollama_pull` with `program` in place of `ollama`.
+fn spawn_pull(program: &str, entry: &ProviderScratchEntry, model: String, log: Sender<LogLine>, notify: Sender<Notification>, jobs: &Jobs) -> Job<PullOutcome> {
+    let (program, entry_id, host) = (program.to_string(), entry.id.clone(), ollama_host(entry));
+    jobs.spawn(format!("Pulling {}", model), move |ctx| {
+        let source = format!("ollama pull {}", model);
+        let mut cmd = Command::new(&program);
+        cmd.args(["pull", &model]).stdin(Stdio::null()).stdout(Stdio::piped()).stderr(Stdio::piped());
+        if let Some(h) = host { cmd.env("OLLAMA_HOST", h); }
+        let ok = match cmd.spawn() {
+            Ok(mut child) => {
+                let (out, err) = (child.stdout.take(), child.stderr.take());
+                let (source, log) = (source.clone(), log.clone());
+                let streaming = thread::spawn(move || stream_output(out, err, &source, &log));
+                let status = loop {
+                    if ctx.is_cancelled() { let _ = child.kill(); }
+                    match child.try_wait() {
+                        Ok(None) => thread::sleep(Duration::from_millis(100)),
+                        done => break done.ok().flatten(),
+                    }
+                };
+                let _ = streaming.join();
+                !ctx.is_cancelled() && status.is_some_and(|s| s.success())
+            }
+            Err(e) => {
+                let _ = log.send(LogLine::new(source.as_str(), format!("failed to start ollama: {}", e), true));
+                false
+            }
+        };
+        let msg = match (ok, ctx.is_cancelled()) {
+            (true, _) => format!("Pulled {}", model),
+            (false, true) => format!("Pull of {} cancelled", model),
+            (false, false) => format!("Pull of {} failed (see log)", model),
+        };
+        let _ = notify.send(Notification::new(msg, ok, Page::Configure));
+        PullOutcome { entry_id, model, ok }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ollama_host_from_config() {
+        let entry = |config| ProviderScratchEntry { id: "p1".into(), name: "o".into(), ptype: "ollama".into(), tags: Vec::new(), disabled: false, session_secrets: Vec::new(), config };
+        assert_eq!(ollama_host(&entry(serde_json::json!({}))), None);
+        assert_eq!(ollama_host(&entry(serde_json::json!({"port": 11500}))).as_deref(), Some("127.0.0.1:11500"));
+        assert_eq!(ollama_host(&entry(serde_json::json!({"host": "gpu-box"}))).as_deref(), Some("gpu-box:11434"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn pull_streams_progress_and_reports_the_outcome() {
+        use std::os::unix::fs::PermissionsExt;
+
+        // Stands in for `ollama`: redrawn progress, then success unless the model is missing
+        let fake = std::env::temp_dir().join(format!("chi-tui-fake-ollama-{}", std::process::id()));
+        std::fs::write(&fake, "#!/bin/sh\necho \"host $OLLAMA_HOST\"\nprintf 'pulling 10%%\\rpulling 100%%\\r\\n'\n\
+            [ \"$2\" = missing ] && { echo 'file does not exist' >&2; exit 1; }\necho success\n").unwrap();
+        std::fs::set_permissions(&fake, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let entry = ProviderScratchEntry::from_json(&serde_json::json!({"id": "p1", "type": "ollama", "config": {"type": "ollama", "port": 11500}}));
+        let (jobs, (log_tx, log), (notify_tx, notify)) = (Jobs::default(), std::sync::mpsc::channel(), std::sync::mpsc::channel());
+        let wait = |job: Job<PullOutcome>| loop {
+            if let Some(done) = job.poll() { break done.unwrap(); }
+            thread::sleep(Duration::from_millis(10));
+        };
+
+        let pulled = wait(spawn_pull(fake.to_str().unwrap(), &entry, "qwen2.5".into(), log_tx.clone(), notify_tx.clone(), &jobs));
+        assert!(pulled.ok && pulled.model == "qwen2.5" && pulled.entry_id == "p1");
+        let lines: Vec<String> = log.try_iter().map(|l| l.text).collect();
+        assert_eq!(lines, ["host 127.0.0.1:11500", "pulling 10%", "pulling 100%", "success"]);
+        assert_eq!(notify.try_recv().map(|n| (n.message, n.ok)), Ok(("Pulled qwen2.5".to_string(), true)));
+
+        let failed = wait(spawn_pull(fake.to_str().unwrap(), &entry, "missing".into(), log_tx, notify_tx, &jobs));
+        assert!(!failed.ok);
+        assert!(log.try_iter().any(|l| l.is_err && l.text == "file does not exist"));
+        assert_eq!(notify.try_recv().map(|n| n.message), Ok("Pull of missing failed (see log)".to_string()));
+        std::fs::remove_file(&fake).unwrap();
+    }
+}
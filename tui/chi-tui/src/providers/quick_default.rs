@@ -0,0 +1,179 @@
+//! Ctrl+D from any page: a compact list of the configured providers. Enter makes
+//! the selected one the default; `w` also rewrites the config chi-llm reads (the
+//! project file when there is none yet), so switching takes one step instead of
+//! Select Default followed by Build.
+
+use std::path::Path;
+
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::layout::Rect;
+use ratatui::prelude::Frame;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+
+use crate::app::{App, Notification};
+use crate::build::{write_with_checks, BuildState, BuildTarget, ConfigFormat};
+use crate::modal::{Modal, ModalResult};
+use crate::paths::PROJECT_CONFIG;
+use crate::text;
+use crate::tutorial::{self, Milestone};
+use crate::util::centered_rect;
+
+use super::select_default::{load_providers_scratch, save_default_provider};
+use super::state::load_scratch_entries;
+use super::status::{ActiveConfig, ConfigSource, ProviderSummary};
+
+struct QuickRow {
+    id: String,
+    name: String,
+    /// `type/model`.
+    summary: String,
+    disabled: bool,
+}
+
+/// Where `w` writes: target, format and the path to show.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RewriteTarget {
+    pub target: BuildTarget,
+    pub format: ConfigFormat,
+    pub path: String,
+}
+
+impl RewriteTarget {
+    /// The config chi-llm reads now, else a new project `.chi_llm.json`.
+    pub fn for_active(active: Option<&ActiveConfig>) -> Self {
+        let Some(active) = active else {
+            return Self { target: BuildTarget::Project, format: ConfigFormat::Json, path: PROJECT_CONFIG.to_string() };
+        };
+        let yaml = active.path.extension().is_some_and(|e| e == "yaml" || e == "yml");
+        let (target, format) = match active.source {
+            ConfigSource::Global => (BuildTarget::Global, ConfigFormat::Json),
+            ConfigSource::Project if yaml => (BuildTarget::Project, ConfigFormat::Yaml),
+            ConfigSource::Project => (BuildTarget::Project, ConfigFormat::Json),
+        };
+        Self { target, format, path: active.path.display().to_string() }
+    }
+}
+
+pub struct QuickDefaultModal {
+    rows: Vec<QuickRow>,
+    selected: usize,
+    default_id: Option<String>,
+    rewrite: RewriteTarget,
+}
+
+impl QuickDefaultModal {
+    /// Providers from `chi.tmp.json`, with the cursor on the current default.
+    pub fn load(active: Option<&ActiveConfig>) -> Result<Self> {
+        let default_id = load_providers_scratch()?.current_default_id;
+        let rows: Vec<QuickRow> = load_scratch_entries()?.into_iter().filter(|e| !e.id.is_empty()).map(|e| QuickRow {
+            summary: ProviderSummary::from_config(e.name.clone(), e.ptype.clone(), &e.config).describe(),
+            id: e.id,
+            name: e.name,
+            disabled: e.disabled,
+        }).collect();
+        let selected = rows.iter().position(|r| Some(&r.id) == default_id.as_ref()).unwrap_or(0);
+        Ok(Self { rows, selected, default_id, rewrite: RewriteTarget::for_active(active) })
+    }
+
+    /// Make the selected provider the default and, with `rewrite`, write it to
+    /// the active config.
+    fn choose(&mut self, app: &mut App, rewrite: bool) -> ModalResult {
+        let Some(row) = self.rows.get(self.selected) else { return ModalResult::Keep };
+        if row.disabled {
+            let msg = format!("{} is disabled; enable it in Configure (x) first", row.name);
+            let _ = app.notify_tx.send(Notification::new(msg, false, app.page));
+            return ModalResult::Keep;
+        }
+        if let Err(e) = save_default_provider(&row.id) {
+            app.report_error(format!("Save default failed: {e}"));
+            return ModalResult::Close;
+        }
+        // Select Default shows the new default without a reload
+        if let Some(s) = &mut app.defaultp { s.current_default_id = Some(row.id.clone()); }
+        tutorial::reached(app, Milestone::DefaultSelected);
+        if !rewrite {
+            let msg = format!("Saved {} as the default (Build writes it to the config)", row.name);
+            let _ = app.notify_tx.send(Notification::new(msg, true, app.page));
+            return ModalResult::Close;
+        }
+        // Build's own target and format stay as they are
+        let env_refs = app.build.as_ref().is_some_and(|b| b.env_refs);
+        let spec = BuildState { target: self.rewrite.target, format: self.rewrite.format, env_refs, ..Default::default() };
+        match write_with_checks(app, spec) {
+            Ok(Some(status)) => { let _ = app.notify_tx.send(Notification::new(format!("{} is the default; {}", row.name, status), true, app.page)); }
+            // Build's question about plaintext secrets is open
+            Ok(None) => {}
+            Err(e) => app.report_error(format!("{} is the default, but writing failed: {}", row.name, e)),
+        }
+        ModalResult::Close
+    }
+}
+
+/// Open the popup, or say why it cannot be.
+pub fn open_quick_default(app: &mut App) {
+    match QuickDefaultModal::load(app.active.config.as_ref()) {
+        Ok(m) => app.modals.push(Box::new(m)),
+        Err(e) => app.report_error(format!("Load providers failed: {e}")),
+    }
+}
+
+impl Modal for QuickDefaultModal {
+    fn label(&self) -> String {
+        "Switch default provider".to_string()
+    }
+
+    fn handle_key(&mut self, app: &mut App, key: KeyEvent) -> ModalResult {
+        match key.code {
+            KeyCode::Up => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down => self.selected = (self.selected + 1).min(self.rows.len().saturating_sub(1)),
+            KeyCode::Enter => return self.choose(app, false),
+            KeyCode::Char('w') | KeyCode::Char('W') => return self.choose(app, true),
+            _ => {}
+        }
+        ModalResult::Keep
+    }
+
+    fn draw(&self, f: &mut Frame, area: Rect, app: &App) {
+        let height = (self.rows.len().max(1) + 4).min(area.height as usize) as u16;
+        let mut area_pop = centered_rect(60, 100, area);
+        area_pop.y += area_pop.height.saturating_sub(height) / 2;
+        area_pop.height = height;
+        let visible = (height as usize).saturating_sub(4).max(1);
+        let start = (self.selected + 1).saturating_sub(visible);
+        let mut lines: Vec<Line> = self.rows.iter().enumerate().skip(start).take(visible).map(|(i, r)| {
+            let mut label = format!("{} {}  {}", if i == self.selected { '›' } else { ' ' }, text::truncate(&r.name, text::NAME_MAX), r.summary);
+            if self.default_id.as_ref() == Some(&r.id) { label.push_str("  [default]"); }
+            if r.disabled { label.push_str("  [disabled]"); }
+            let style = if r.disabled { app.theme.disabled() } else { app.theme.row(i == self.selected) };
+            Line::from(Span::styled(label, style))
+        }).collect();
+        if self.rows.is_empty() { lines.push(Line::from("No providers in chi.tmp.json; add some in Configure (2).")); }
+        lines.push(Line::from(""));
+        let file = Path::new(&self.rewrite.path).file_name().map_or(self.rewrite.path.clone(), |n| n.to_string_lossy().into_owned());
+        lines.push(Line::from(Span::styled(format!("Enter: set default • w: set and write {} • Esc: cancel", file), app.theme.hint())));
+        let p = Paragraph::new(lines)
+            .style(app.theme.base())
+            .block(Block::default().borders(Borders::ALL).border_style(app.theme.focused_border()).title("Switch default provider"));
+        f.render_widget(Clear, area_pop);
+        f.render_widget(p, area_pop);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    #[test]
+    fn rewrite_goes_to_the_config_chi_llm_reads() {
+        let active = |source, path: &str| ActiveConfig { source, path: PathBuf::from(path), provider: None };
+        assert_eq!(RewriteTarget::for_active(None).path, PROJECT_CONFIG);
+        let yaml = RewriteTarget::for_active(Some(&active(ConfigSource::Project, ".chi_llm.yaml")));
+        assert_eq!((yaml.target, yaml.format), (BuildTarget::Project, ConfigFormat::Yaml));
+        let global = RewriteTarget::for_active(Some(&active(ConfigSource::Global, "/home/u/.chi_llm/config.json")));
+        assert_eq!((global.target, global.format), (BuildTarget::Global, ConfigFormat::Json));
+    }
+}
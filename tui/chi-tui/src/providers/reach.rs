@@ -0,0 +1,218 @@
+//! Reachability of every configured provider for the Diagnostics page. Each
+//! endpoint (`base_url`, else `host`/`port` with the local server defaults, else
+//! the cloud API's public URL) is contacted directly, all providers at once,
+//! and the latency and HTTP status reported. HTTPS endpoints are only
+//! connected to: there is no TLS client here, and an open port is what tells
+//! a down backend from a wrong key.
+
+use std::net::{TcpStream, ToSocketAddrs};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+
+use crate::interp::resolve_config;
+
+use super::ports::{probe_port, service_for, PortProbe, CHECK_TIMEOUT};
+use super::probe::cfg_str;
+use super::state::{load_scratch_entries, ProviderScratchEntry};
+
+/// Where a provider is served.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Endpoint {
+    pub https: bool,
+    pub host: String,
+    pub port: u16,
+    /// Path requested over plain HTTP.
+    pub path: String,
+}
+
+impl Endpoint {
+    pub fn display(&self) -> String {
+        format!("{}://{}:{}", if self.https { "https" } else { "http" }, self.host, self.port)
+    }
+}
+
+/// Outcome of checking one provider.
+#[derive(Clone, Debug)]
+pub struct Reachability {
+    pub id: String,
+    pub name: String,
+    pub ptype: String,
+    /// `None` for types without a network endpoint (in-process or CLI-backed).
+    pub endpoint: Option<String>,
+    /// What answered (`HTTP 200`, `TCP open`) or why nothing did.
+    pub result: Result<String, String>,
+    pub latency: Option<Duration>,
+}
+
+/// Public API of cloud types whose `base_url` is optional.
+fn cloud_base_url(ptype: &str) -> Option<&'static str> {
+    Some(match ptype {
+        "openai" => "https://api.openai.com",
+        "anthropic" => "https://api.anthropic.com",
+        "groq" => "https://api.groq.com",
+        "gemini" => "https://generativelanguage.googleapis.com",
+        _ => return None,
+    })
+}
+
+/// `http(s)://host[:port][/path]` as an endpoint.
+pub fn parse_url(url: &str) -> Result<Endpoint, String> {
+    let (https, rest) = match (url.strip_prefix("https://"), url.strip_prefix("http://")) {
+        (Some(rest), _) => (true, rest),
+        (None, Some(rest)) => (false, rest),
+        _ => return Err(format!("not an http(s) URL: {}", url)),
+    };
+    let (authority, path) = match rest.find(['/', '?', '#']) {
+        Some(i) if rest[i..].starts_with('/') => (&rest[..i], rest[i..].to_string()),
+        Some(i) => (&rest[..i], format!("/{}", &rest[i..])),
+        None => (rest, "/".to_string()),
+    };
+    let default_port = if https { 443 } else { 80 };
+    let (host, port) = match authority.rsplit_once(':') {
+        Some((host, port)) if !authority.ends_with(']') => {
+            (host, port.parse::<u16>().map_err(|_| format!("invalid port in {}", url))?)
+        }
+        _ => (authority, default_port),
+    };
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    if host.is_empty() { return Err(format!("no host in {}", url)); }
+    Ok(Endpoint { https, host: host.to_string(), port, path })
+}
+
+/// The endpoint of `entry` (with `${ENV}` references resolved); `None` for
+/// types without one.
+pub fn endpoint_for(entry: &ProviderScratchEntry) -> Option<Result<Endpoint, String>> {
+    let config = match resolve_config(&entry.config) {
+        Ok(config) => config,
+        Err(unset) => return Some(Err(format!("environment variable {} is not set", unset.join(", ")))),
+    };
+    let entry = &ProviderScratchEntry { config, ..entry.clone() };
+    if let Some(url) = cfg_str(entry, "base_url") {
+        return Some(parse_url(&url));
+    }
+    let svc = service_for(&entry.ptype);
+    let host = cfg_str(entry, "host");
+    if svc.is_none() && host.is_none() {
+        return cloud_base_url(&entry.ptype).map(parse_url);
+    }
+    let host = host.unwrap_or_else(|| "127.0.0.1".to_string());
+    let host = host.trim_start_matches("http://").trim_start_matches("https://").trim_end_matches('/').to_string();
+    let port = match cfg_str(entry, "port") {
+        Some(p) => match p.parse::<u16>() {
+            Ok(n) => n,
+            Err(_) => return Some(Err(format!("invalid port: {}", p))),
+        },
+        None => svc.map_or(80, |s| s.default_port),
+    };
+    let path = svc.map_or("/", |s| s.path).to_string();
+    Some(Ok(Endpoint { https: false, host, port, path }))
+}
+
+/// Contact `ep`: a plain-HTTP GET, or a TCP connect for HTTPS.
+pub fn check_endpoint(ep: &Endpoint) -> (Result<String, String>, Duration) {
+    let start = Instant::now();
+    let Some(addr) = (ep.host.as_str(), ep.port).to_socket_addrs().ok().and_then(|mut a| a.next()) else {
+        return (Err(format!("cannot resolve host {}", ep.host)), start.elapsed());
+    };
+    let result = if ep.https {
+        match TcpStream::connect_timeout(&addr, CHECK_TIMEOUT) {
+            Ok(_) => Ok("TCP open (HTTPS not probed)".to_string()),
+            Err(e) => Err(e.to_string()),
+        }
+    } else {
+        match probe_port(addr, &ep.host, &ep.path) {
+            PortProbe::Closed => Err("nothing is listening".to_string()),
+            PortProbe::NotHttp => Err("port open, no HTTP reply".to_string()),
+            PortProbe::Http(status, _) => Ok(format!("HTTP {}", status)),
+        }
+    };
+    (result, start.elapsed())
+}
+
+/// Check every enabled entry, concurrently, keeping list order.
+pub fn check_reachability(entries: &[ProviderScratchEntry]) -> Vec<Reachability> {
+    thread::scope(|s| {
+        let checks: Vec<_> = entries.iter().filter(|e| !e.disabled).map(|entry| {
+            s.spawn(move || {
+                let mut out = Reachability {
+                    id: entry.id.clone(),
+                    name: entry.name.clone(),
+                    ptype: entry.ptype.clone(),
+                    endpoint: None,
+                    result: Ok("no network endpoint".to_string()),
+                    latency: None,
+                };
+                match endpoint_for(entry) {
+                    None => {}
+                    Some(Err(e)) => out.result = Err(e),
+                    Some(Ok(ep)) => {
+                        let (result, latency) = check_endpoint(&ep);
+                        out.endpoint = Some(ep.display());
+                        out.result = result;
+                        out.latency = Some(latency);
+                    }
+                }
+                out
+            })
+        }).collect();
+        checks.into_iter().filter_map(|c| c.join().ok()).collect()
+    })
+}
+
+/// `check_reachability` for the providers in `chi.tmp.json`.
+pub fn check_configured_providers() -> Result<Vec<Reachability>> {
+    Ok(check_reachability(&load_scratch_entries()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+
+    use crate::providers::probe::tests::entry;
+
+    #[test]
+    fn endpoints_come_from_base_url_host_port_or_the_cloud_default() {
+        let ep = parse_url("https://api.example.com/v1?x=1").unwrap();
+        assert_eq!((ep.https, ep.host.as_str(), ep.port, ep.path.as_str()), (true, "api.example.com", 443, "/v1?x=1"));
+        assert_eq!(parse_url("http://[::1]:8080").unwrap().host, "::1");
+        assert!(parse_url("ftp://x").is_err() && parse_url("http://h:99999").is_err());
+
+        let ollama = endpoint_for(&entry("ollama", serde_json::json!({}))).unwrap().unwrap();
+        assert_eq!((ollama.display(), ollama.path.as_str()), ("http://127.0.0.1:11434".to_string(), "/api/version"));
+        let openai = endpoint_for(&entry("openai", serde_json::json!({"api_key": "sk"}))).unwrap().unwrap();
+        assert_eq!(openai.display(), "https://api.openai.com:443");
+        assert!(endpoint_for(&entry("local", serde_json::json!({}))).is_none());
+    }
+
+    #[test]
+    fn providers_are_checked_together_in_list_order() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        thread::spawn(move || {
+            let (mut s, _) = listener.accept().unwrap();
+            let mut buf = [0u8; 512];
+            let _ = s.read(&mut buf);
+            let _ = s.write_all(b"HTTP/1.1 200 OK\r\n\r\n{\"version\":\"0.3.0\"}");
+        });
+        let closed = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+        let mut off = entry("ollama", serde_json::json!({}));
+        off.disabled = true;
+        let entries = [
+            entry("ollama", serde_json::json!({"port": port})),
+            off,
+            entry("lmstudio", serde_json::json!({"host": "127.0.0.1", "port": closed})),
+            entry("local", serde_json::json!({})),
+        ];
+        let results = check_reachability(&entries);
+        let names: Vec<&str> = results.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, ["ollama", "lmstudio", "local"]);
+        assert_eq!(results[0].result, Ok("HTTP 200".to_string()));
+        assert!(results[0].latency.is_some());
+        assert_eq!(results[1].result, Err("nothing is listening".to_string()));
+        assert_eq!((results[2].endpoint.as_ref(), results[2].latency), (None, None));
+    }
+}
@@ -0,0 +1,265 @@
+//! Routing page: a matrix of providers × routing tags (e.g. `chat`, `code`,
+//! `cheap`). Tags are stored on the providers in `chi.tmp.json`; Build writes
+//! tagged providers as chi-llm `provider_profiles`, so setting `llm.tags =
+//! ["code"]` in chi-llm picks a provider by task.
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::layout::Rect;
+use ratatui::prelude::Frame;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph};
+use serde_json::Value;
+
+use crate::app::{App, Notification, Page};
+use crate::controller::PageController;
+use crate::keymap::KeyAction;
+use crate::modal::PromptModal;
+use crate::msg::Msg;
+use crate::paths::SCRATCH_FILE;
+use crate::recovery::{write_keeping_backup, FileKind};
+use crate::text;
+
+use super::select_default::{load_providers_scratch, ProviderEntry};
+
+/// Columns offered when no provider has tags yet.
+pub const SUGGESTED_TAGS: [&str; 3] = ["chat", "code", "cheap"];
+
+#[derive(Clone, Debug, Default)]
+pub struct RoutingState {
+    pub providers: Vec<ProviderEntry>,
+    /// Matrix columns: every tag in use, in first-seen order, plus added ones.
+    pub tags: Vec<String>,
+    pub row: usize,
+    pub col: usize,
+    /// Unsaved changes.
+    pub dirty: bool,
+}
+
+impl RoutingState {
+    pub fn new(providers: Vec<ProviderEntry>) -> Self {
+        let mut tags: Vec<String> = Vec::new();
+        for t in providers.iter().flat_map(|p| &p.tags) {
+            if !tags.contains(t) { tags.push(t.clone()); }
+        }
+        if tags.is_empty() { tags = SUGGESTED_TAGS.iter().map(|t| t.to_string()).collect(); }
+        Self { providers, tags, ..Default::default() }
+    }
+
+    pub fn assigned(&self, row: usize, col: usize) -> bool {
+        match (self.providers.get(row), self.tags.get(col)) {
+            (Some(p), Some(tag)) => p.tags.contains(tag),
+            _ => false,
+        }
+    }
+
+    /// Assign or unassign the selected tag for the selected provider.
+    pub fn toggle(&mut self) {
+        let Some(tag) = self.tags.get(self.col).cloned() else { return };
+        let Some(p) = self.providers.get_mut(self.row) else { return };
+        match p.tags.iter().position(|t| *t == tag) {
+            Some(i) => { p.tags.remove(i); }
+            None => p.tags.push(tag),
+        }
+        self.dirty = true;
+    }
+
+    /// Add a column; whitespace and commas become `-`. False for empty or duplicate tags.
+    pub fn add_tag(&mut self, tag: &str) -> bool {
+        let tag: String = tag.trim().chars().map(|c| if c.is_whitespace() || c == ',' { '-' } else { c }).collect();
+        if tag.is_empty() || self.tags.contains(&tag) { return false; }
+        self.tags.push(tag);
+        self.col = self.tags.len() - 1;
+        true
+    }
+
+    /// Drop the selected column and unassign it from every provider.
+    pub fn remove_tag(&mut self) {
+        if self.col >= self.tags.len() { return; }
+        let tag = self.tags.remove(self.col);
+        for p in &mut self.providers { p.tags.retain(|t| *t != tag); }
+        self.col = self.col.min(self.tags.len().saturating_sub(1));
+        self.dirty = true;
+    }
+
+    /// Enabled providers per tag, in list order (the order chi-llm tries them in).
+    pub fn routes(&self) -> Vec<(&str, Vec<&str>)> {
+        self.tags.iter()
+            .map(|tag| {
+                let names = self.providers.iter().filter(|p| !p.disabled && p.tags.contains(tag)).map(|p| p.name.as_str()).collect();
+                (tag.as_str(), names)
+            })
+            .collect()
+    }
+}
+
+pub fn load_routing() -> Result<RoutingState> {
+    Ok(RoutingState::new(load_providers_scratch()?.providers))
+}
+
+/// Write each provider's tags into `chi.tmp.json`, leaving everything else as is.
+pub fn save_routing_tags(providers: &[ProviderEntry]) -> Result<()> {
+    let text = fs::read_to_string(SCRATCH_FILE)?;
+    let mut root: Value = serde_json::from_str(&text)?;
+    if let Some(arr) = root.get_mut("providers").and_then(|p| p.as_array_mut()) {
+        for entry in arr.iter_mut() {
+            let id = entry.get("id").and_then(|x| x.as_str()).unwrap_or("");
+            let Some(p) = providers.iter().find(|p| p.id == id) else { continue };
+            if let Some(obj) = entry.as_object_mut() { obj.insert("tags".to_string(), serde_json::json!(p.tags)); }
+        }
+    }
+    write_keeping_backup(FileKind::Scratch, Path::new(SCRATCH_FILE), &serde_json::to_vec_pretty(&root)?)
+}
+
+fn save(app: &mut App) {
+    let Some(st) = &mut app.routing else { return };
+    let (msg, ok) = match save_routing_tags(&st.providers) {
+        Ok(()) => {
+            st.dirty = false;
+            ("Routing tags saved; Build writes them as provider_profiles".to_string(), true)
+        }
+        Err(e) => (format!("Saving routing tags failed: {}", e), false),
+    };
+    if ok {
+        // Keep the other provider views in step so their saves don't undo the tags
+        if let Some(ps) = app.providers.ready_mut() {
+            for e in &mut ps.entries {
+                if let Some(p) = st.providers.iter().find(|p| p.id == e.id) { e.tags = p.tags.clone(); }
+            }
+        }
+        app.defaultp = None;
+    }
+    let _ = app.notify_tx.send(Notification::new(msg, ok, Page::Routing));
+}
+
+pub fn draw_routing(f: &mut Frame, area: Rect, app: &App) {
+    let Some(st) = &app.routing else {
+        f.render_widget(Paragraph::new("Loading providers..."), area);
+        return;
+    };
+    let mut lines: Vec<Line> = Vec::new();
+    if st.providers.is_empty() {
+        lines.push(Line::from("No providers found in chi.tmp.json → Configure first."));
+    } else {
+        let name_w = st.providers.iter().map(|p| text::width(&p.name)).max().unwrap_or(0).clamp(8, 24) + 2;
+        let widths: Vec<usize> = st.tags.iter().map(|t| text::width(t).max(3) + 2).collect();
+        let mut header = vec![Span::raw(" ".repeat(name_w))];
+        for (ci, (tag, w)) in st.tags.iter().zip(&widths).enumerate() {
+            let style = if ci == st.col { app.theme.title() } else { app.theme.hint() };
+            header.push(Span::styled(text::pad(tag, *w), style));
+        }
+        lines.push(Line::from(header));
+        for (ri, p) in st.providers.iter().enumerate() {
+            let row_style = if p.disabled { app.theme.disabled() } else { app.theme.row(ri == st.row) };
+            let mut spans = vec![Span::styled(format!("{} {}", if ri == st.row { '›' } else { ' ' }, text::pad(&p.name, name_w - 2)), row_style)];
+            for (ci, w) in widths.iter().enumerate() {
+                let cell = if st.assigned(ri, ci) { "[x]" } else { "[ ]" };
+                let style = if ri == st.row && ci == st.col { app.theme.highlight() } else { app.theme.text() };
+                spans.push(Span::styled(cell, style));
+                spans.push(Span::raw(" ".repeat(w - 3)));
+            }
+            lines.push(Line::from(spans));
+        }
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled("Routes (tried in list order):", app.theme.title())));
+        for (tag, names) in st.routes() {
+            let to = if names.is_empty() { "(none)".to_string() } else { names.join(", ") };
+            lines.push(Line::from(vec![Span::styled(format!("{} → ", tag), app.theme.accent()), Span::raw(to)]));
+        }
+    }
+    if st.tags.is_empty() {
+        lines.push(Line::from(Span::styled("No tags; a adds one.", app.theme.hint())));
+    }
+    let title = if st.dirty { "Routing (tag → provider) *" } else { "Routing (tag → provider)" };
+    let block = Block::default().borders(Borders::ALL).border_style(app.theme.border()).title(title);
+    f.render_widget(Paragraph::new(lines).style(app.theme.base()).block(block), area);
+}
+
+pub struct RoutingPage;
+
+impl PageController for RoutingPage {
+    fn handle_key(&self, app: &mut App, key: KeyEvent) -> Option<Msg> {
+        let Some(st) = &mut app.routing else { return None };
+        match key.code {
+            KeyCode::Up if st.row > 0 => st.row -= 1,
+            KeyCode::Down if st.row + 1 < st.providers.len() => st.row += 1,
+            KeyCode::Left if st.col > 0 => st.col -= 1,
+            KeyCode::Right if st.col + 1 < st.tags.len() => st.col += 1,
+            KeyCode::Char(' ') | KeyCode::Enter => st.toggle(),
+            KeyCode::Char('a') | KeyCode::Char('A') => {
+                app.modals.push(Box::new(PromptModal::new("New routing tag", |app, tag| {
+                    let Some(st) = &mut app.routing else { return };
+                    if !st.add_tag(&tag) {
+                        let _ = app.notify_tx.send(Notification::new(format!("Tag already listed: {}", tag), false, Page::Routing));
+                    }
+                })));
+            }
+            KeyCode::Char('d') | KeyCode::Char('D') => st.remove_tag(),
+            KeyCode::Char('s') | KeyCode::Char('S') => save(app),
+            // Reload from chi.tmp.json, dropping unsaved changes
+            KeyCode::Char('r') | KeyCode::Char('R') => app.routing = None,
+            _ => return None,
+        }
+        Some(Msg::Handled)
+    }
+
+    fn tick(&self, app: &mut App) {
+        if app.routing.is_some() { return; }
+        match load_routing() {
+            Ok(st) => app.routing = Some(st),
+            Err(e) => {
+                app.report_error(format!("Load providers failed: {e}"));
+                app.routing = Some(RoutingState::new(Vec::new()));
+            }
+        }
+    }
+
+    fn draw(&self, f: &mut Frame, area: Rect, app: &App) {
+        draw_routing(f, area, app);
+    }
+
+    fn actions(&self) -> &'static [KeyAction] {
+        const ACTIONS: &[KeyAction] = &[
+            KeyAction::new("↑/↓", "provider"),
+            KeyAction::new("←/→", "tag"),
+            KeyAction::new("Space", "toggle"),
+            KeyAction::new("a", "add tag"),
+            KeyAction::new("d", "delete tag"),
+            KeyAction::new("s", "save").when(|app| app.routing.as_ref().is_some_and(|st| st.dirty)),
+            KeyAction::new("r", "reload"),
+            KeyAction::new("Esc", "back"),
+        ];
+        ACTIONS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: &str, tags: &[&str]) -> ProviderEntry {
+        ProviderEntry { id: id.into(), name: id.into(), ptype: "openai".into(), tags: tags.iter().map(|t| t.to_string()).collect(), disabled: false, model: None }
+    }
+
+    #[test]
+    fn matrix_toggles_adds_and_removes_tags() {
+        assert_eq!(RoutingState::new(vec![entry("a", &[])]).tags, SUGGESTED_TAGS);
+        let mut st = RoutingState::new(vec![entry("a", &["code"]), entry("b", &["chat", "code"])]);
+        assert_eq!(st.tags, ["code", "chat"]);
+        st.row = 1;
+        st.toggle();
+        assert_eq!(st.providers[1].tags, ["chat"]);
+        assert!(st.add_tag(" very cheap "));
+        assert!(!st.add_tag("code"));
+        st.toggle();
+        assert_eq!(st.routes(), [("code", vec!["a"]), ("chat", vec!["b"]), ("very-cheap", vec!["b"])]);
+        st.col = 1;
+        st.remove_tag();
+        assert_eq!(st.tags, ["code", "very-cheap"]);
+        assert_eq!(st.providers[1].tags, ["very-cheap"]);
+        assert!(st.dirty);
+    }
+}
@@ -1,20 +1,60 @@
+//! Select Default: the providers of `chi.tmp.json` with their configured model
+//! and, checked in the background when the page opens (and again every
+//! `RECHECK` while it stays open, or on `r`), whether each endpoint answers.
+
 use std::fs;
+use std::path::Path;
+use std::time::{Duration, Instant};
 
 use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::layout::Rect;
 use ratatui::prelude::Frame;
-use ratatui::style::{Modifier, Style};
 use ratatui::text::{Line, Span};
-use ratatui::widgets::{Block, Borders, List, ListItem, Paragraph};
+use ratatui::widgets::{Block, Borders, List, ListItem};
 use serde_json::Value;
 
-use crate::app::App;
+use crate::app::{App, Notification, Page};
+use crate::controller::PageController;
+use crate::jobs::Jobs;
+use crate::keymap::KeyAction;
+use crate::msg::Msg;
+use crate::page_data::{spinner, PageData};
+use crate::paths::SCRATCH_FILE;
+use crate::recovery::{read_json_root, write_keeping_backup, FileKind};
+use crate::text;
+use crate::tutorial::{self, Milestone};
 
-#[derive(Clone, Debug)]
+use super::monitor::{dot_for, dot_span, health_dot, monitor_title};
+use super::reach::{check_configured_providers, Reachability};
+use super::status::configured_model;
+
+/// How old the reachability results may get while the page is shown.
+const RECHECK: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Default)]
 pub struct DefaultProviderState {
     pub providers: Vec<ProviderEntry>,
     pub selected: usize,
     pub current_default_id: Option<String>,
+    /// Reachability of the enabled providers, from `check_configured_providers`.
+    pub health: PageData<Vec<Reachability>>,
+    /// When `health` was last requested.
+    pub checked_at: Option<Instant>,
+}
+
+impl DefaultProviderState {
+    /// Check every provider again unless a check is running.
+    pub fn start_health_check(&mut self, jobs: &Jobs) {
+        if self.health.is_loading() { return; }
+        self.checked_at = Some(Instant::now());
+        self.health.load(jobs, "Checking providers", |_| check_configured_providers());
+    }
+
+    /// Result for provider `id`, once the check has finished.
+    pub fn health_of(&self, id: &str) -> Option<&Reachability> {
+        self.health.ready()?.iter().find(|r| r.id == id)
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -23,10 +63,13 @@ pub struct ProviderEntry {
     pub name: String,
     pub ptype: String,
     pub tags: Vec<String>,
+    pub disabled: bool,
+    /// `model`, or the file name of a local `model_path`.
+    pub model: Option<String>,
 }
 
 pub fn load_providers_scratch() -> Result<DefaultProviderState> {
-    let path = "chi.tmp.json";
+    let path = SCRATCH_FILE;
     let text = fs::read_to_string(path).unwrap_or_else(|_| "{}".to_string());
     let v: Value = serde_json::from_str(&text)?;
     let mut providers: Vec<ProviderEntry> = Vec::new();
@@ -38,45 +81,128 @@ pub fn load_providers_scratch() -> Result<DefaultProviderState> {
             let tags: Vec<String> = p.get("tags").and_then(|x| x.as_array()).map(|a| {
                 a.iter().filter_map(|t| t.as_str().map(|s| s.to_string())).collect()
             }).unwrap_or_default();
-            if !id.is_empty() { providers.push(ProviderEntry { id, name, ptype, tags }); }
+            let disabled = p.get("disabled").and_then(|x| x.as_bool()).unwrap_or(false);
+            let model = p.get("config").and_then(configured_model);
+            if !id.is_empty() { providers.push(ProviderEntry { id, name, ptype, tags, disabled, model }); }
         }
     }
     let current_default_id = v.get("default_provider_id").and_then(|x| x.as_str()).map(|s| s.to_string());
-    Ok(DefaultProviderState { providers, selected: 0, current_default_id })
+    Ok(DefaultProviderState { providers, selected: 0, current_default_id, ..DefaultProviderState::default() })
 }
 
 pub fn save_default_provider(id: &str) -> Result<()> {
-    let path = "chi.tmp.json";
-    let mut root: Value = if let Ok(text) = fs::read_to_string(path) {
-        serde_json::from_str(&text).unwrap_or_else(|_| Value::Object(Default::default()))
-    } else {
-        Value::Object(Default::default())
-    };
-    if !root.is_object() { root = Value::Object(Default::default()); }
+    let path = Path::new(SCRATCH_FILE);
+    let mut root = read_json_root(path)?;
     if let Some(obj) = root.as_object_mut() {
         obj.insert("default_provider_id".to_string(), Value::String(id.to_string()));
     }
-    fs::write(path, serde_json::to_vec_pretty(&root)?)?;
-    Ok(())
+    write_keeping_backup(FileKind::Scratch, path, &serde_json::to_vec_pretty(&root)?)
+}
+
+/// `✓ HTTP 200, 12 ms` or why the provider did not answer, in its dot's style.
+fn health_note(r: &Reachability, app: &App) -> Span<'static> {
+    let ms = r.latency.map(|l| format!(", {} ms", l.as_millis())).unwrap_or_default();
+    match &r.result {
+        Ok(detail) if r.endpoint.is_none() => Span::styled(format!("  {}", detail), app.theme.hint()),
+        Ok(detail) => Span::styled(format!("  ✓ {}{}", detail, ms), app.theme.success()),
+        Err(e) => Span::styled(format!("  ✗ {}", e), app.theme.error()),
+    }
 }
 
 pub fn draw_select_default(f: &mut Frame, area: Rect, app: &App) {
     let mut items: Vec<ListItem> = Vec::new();
+    let mut title = monitor_title(app, "Select Default Provider");
     if let Some(st) = &app.defaultp {
+        match &st.health {
+            PageData::Loading(_) => title.push_str(&format!(" • {} checking providers", spinner(app))),
+            PageData::Error(e) => title.push_str(&format!(" • check failed: {}", e)),
+            _ => {}
+        }
         for (i, p) in st.providers.iter().enumerate() {
-            let mut label = format!("{} {} [{}]", if i == st.selected { '›' } else { ' ' }, p.name, p.ptype);
+            let kind = match &p.model {
+                Some(m) => format!("{}/{}", p.ptype, m),
+                None => p.ptype.clone(),
+            };
+            let mut label = format!("{} {} [{}]", if i == st.selected { '›' } else { ' ' }, text::truncate(&p.name, text::NAME_MAX), kind);
             if let Some(cur) = &st.current_default_id { if cur == &p.id { label.push_str("  [default]"); } }
             if !p.tags.is_empty() { label.push_str(&format!("  [{}]", p.tags.join(","))); }
-            let style = if i == st.selected { Style::default().fg(app.theme.selected).add_modifier(Modifier::BOLD) } else { Style::default().fg(app.theme.fg) };
-            items.push(ListItem::new(Line::from(Span::styled(label, style))))
+            if p.disabled { label.push_str("  [disabled]"); }
+            let style = if p.disabled { app.theme.disabled() } else { app.theme.row(i == st.selected) };
+            // This page's own check, else the background monitor's
+            let health = st.health_of(&p.id);
+            let dot = health.and_then(dot_for).map(|d| dot_span(app, d)).or_else(|| health_dot(app, &p.id));
+            let mut spans = vec![dot.unwrap_or_else(|| Span::raw("  "))];
+            spans.push(Span::styled(label, style));
+            if let Some(badge) = app.latency.badge(&p.id) { spans.push(Span::styled(format!("  {}", badge), app.theme.hint())); }
+            if let Some(r) = health { spans.push(health_note(r, app)); }
+            items.push(ListItem::new(Line::from(spans)))
         }
         if st.providers.is_empty() { items.push(ListItem::new("No providers found in chi.tmp.json → Configure first.")); }
     } else {
         items.push(ListItem::new("Loading providers..."));
     }
     let list = List::new(items)
-        .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(app.theme.frame)).title("Select Default Provider"))
-        .highlight_style(Style::default().fg(app.theme.selected));
+        .block(Block::default().borders(Borders::ALL).border_style(app.theme.border()).title(title))
+        .highlight_style(app.theme.highlight());
     f.render_widget(list, area);
 }
 
+pub struct SelectDefaultPage;
+
+impl PageController for SelectDefaultPage {
+    fn handle_key(&self, app: &mut App, key: KeyEvent) -> Option<Msg> {
+        let Some(s) = &mut app.defaultp else { return None };
+        match key.code {
+            KeyCode::Up if !s.providers.is_empty() && s.selected > 0 => { s.selected -= 1; },
+            KeyCode::Down if !s.providers.is_empty() && s.selected + 1 < s.providers.len() => { s.selected += 1; },
+            KeyCode::Enter | KeyCode::Char('s') | KeyCode::Char('S') => {
+                if let Some(p) = s.providers.get(s.selected).filter(|p| p.disabled) {
+                    let msg = format!("{} is disabled; enable it in Configure (x) first", p.name);
+                    let _ = app.notify_tx.send(Notification::new(msg, false, Page::SelectDefault));
+                } else if let Some(p) = s.providers.get(s.selected) {
+                    s.current_default_id = Some(p.id.clone());
+                    match save_default_provider(&p.id) {
+                        Ok(()) => {
+                            let _ = app.notify_tx.send(Notification::new(format!("Saved {} as the default", p.name), true, Page::SelectDefault));
+                            tutorial::reached(app, Milestone::DefaultSelected);
+                        }
+                        Err(e) => app.report_error(format!("Save default failed: {e}")),
+                    }
+                }
+            }
+            KeyCode::Char('r') | KeyCode::Char('R') => s.start_health_check(&app.jobs),
+            _ => return None,
+        }
+        Some(Msg::Handled)
+    }
+
+    fn tick(&self, app: &mut App) {
+        if app.defaultp.is_none() {
+            match load_providers_scratch() {
+                Ok(s) => app.defaultp = Some(s),
+                Err(e) => {
+                    app.report_error(format!("Load providers failed: {e}"));
+                    app.defaultp = Some(DefaultProviderState::default());
+                }
+            }
+        }
+        let Some(s) = &mut app.defaultp else { return };
+        s.health.poll();
+        // Checked on opening, and kept fresh while the page is shown
+        if !s.providers.is_empty() && s.checked_at.is_none_or(|at| at.elapsed() >= RECHECK) { s.start_health_check(&app.jobs); }
+    }
+
+    fn draw(&self, f: &mut Frame, area: Rect, app: &App) {
+        draw_select_default(f, area, app);
+    }
+
+    fn actions(&self) -> &'static [KeyAction] {
+        const ACTIONS: &[KeyAction] = &[
+            KeyAction::new("Up/Down", "select"),
+            KeyAction::new("Enter", "set default"),
+            KeyAction::new("r", "re-check").when(|app| app.defaultp.as_ref().is_some_and(|s| !s.health.is_loading())),
+            KeyAction::new("Esc", "back"),
+        ];
+        ACTIONS
+    }
+}
@@ -0,0 +1,113 @@
+//! Compact `chi-llm://provider?...` URIs for sharing a provider entry in chat.
+//! Query keys are `type`, `name`, `tags` (comma-separated) and the config fields;
+//! secrets are left out by the caller-supplied predicate.
+
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+
+use crate::util::percent_encode;
+
+use super::state::ProviderScratchEntry;
+
+pub const URI_PREFIX: &str = "chi-llm://provider?";
+
+/// Encode `entry`, skipping config keys for which `is_secret` returns true.
+pub fn encode_provider_uri(entry: &ProviderScratchEntry, is_secret: impl Fn(&str) -> bool) -> String {
+    let mut pairs: Vec<(String, String)> = vec![("type".to_string(), entry.ptype.clone())];
+    if entry.name != entry.ptype { pairs.push(("name".to_string(), entry.name.clone())); }
+    if !entry.tags.is_empty() { pairs.push(("tags".to_string(), entry.tags.join(","))); }
+    if let Some(obj) = entry.config.as_object() {
+        for (k, v) in obj {
+            if matches!(k.as_str(), "type" | "name" | "tags") || is_secret(k) { continue; }
+            let v = match v {
+                Value::Null => continue,
+                Value::String(s) if s.is_empty() => continue,
+                Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            pairs.push((k.clone(), v));
+        }
+    }
+    let query: Vec<String> = pairs.iter().map(|(k, v)| format!("{}={}", percent_encode(k), percent_encode(v))).collect();
+    format!("{}{}", URI_PREFIX, query.join("&"))
+}
+
+/// Decode a URI made by `encode_provider_uri`. The id is left empty for the
+/// catalog to assign; config values come back as strings.
+pub fn decode_provider_uri(uri: &str) -> Result<ProviderScratchEntry> {
+    let query = uri.trim().strip_prefix(URI_PREFIX).ok_or_else(|| anyhow!("not a {} URI", URI_PREFIX))?;
+    let mut ptype = None;
+    let mut name = None;
+    let mut tags = Vec::new();
+    let mut config = serde_json::Map::new();
+    for pair in query.split('&').filter(|p| !p.is_empty()) {
+        let (k, v) = pair.split_once('=').unwrap_or((pair, ""));
+        let (k, v) = (percent_decode(k)?, percent_decode(v)?);
+        match k.as_str() {
+            "type" => ptype = Some(v),
+            "name" => name = Some(v),
+            "tags" => tags = v.split(',').filter(|t| !t.is_empty()).map(String::from).collect(),
+            _ => { config.insert(k, Value::String(v)); }
+        }
+    }
+    let ptype = ptype.filter(|t| !t.is_empty()).ok_or_else(|| anyhow!("URI has no provider type"))?;
+    config.insert("type".to_string(), Value::String(ptype.clone()));
+    Ok(ProviderScratchEntry {
+        id: String::new(),
+        name: name.unwrap_or_else(|| ptype.clone()),
+        ptype,
+        tags,
+        disabled: false,
+        session_secrets: Vec::new(),
+        config: Value::Object(config),
+    })
+}
+
+fn percent_decode(s: &str) -> Result<String> {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' => {
+                let hex = s.get(i + 1..i + 3).ok_or_else(|| anyhow!("truncated escape in URI"))?;
+                out.push(u8::from_str_radix(hex, 16).map_err(|_| anyhow!("bad escape %{} in URI", hex))?);
+                i += 3;
+            }
+            b'+' => { out.push(b' '); i += 1; }
+            b => { out.push(b); i += 1; }
+        }
+    }
+    String::from_utf8(out).map_err(|_| anyhow!("URI is not valid UTF-8"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_without_secrets() {
+        let entry = ProviderScratchEntry {
+            id: "p3".to_string(),
+            name: "work gpt".to_string(),
+            ptype: "openai".to_string(),
+            tags: vec!["cloud".to_string(), "fast".to_string()],
+            disabled: false,
+            session_secrets: Vec::new(),
+            config: serde_json::json!({"type": "openai", "model": "gpt-4o", "api_key": "sk-x", "base_url": "https://a.b/v1?x=1&y", "port": 8080}),
+        };
+        let uri = encode_provider_uri(&entry, |k| k == "api_key");
+        assert!(uri.starts_with(URI_PREFIX));
+        assert!(!uri.contains("sk-x"));
+        let back = decode_provider_uri(&uri).unwrap();
+        assert_eq!((back.name.as_str(), back.ptype.as_str()), ("work gpt", "openai"));
+        assert_eq!(back.tags, ["cloud", "fast"]);
+        assert_eq!(back.config["base_url"], "https://a.b/v1?x=1&y");
+        assert_eq!(back.config["port"], "8080");
+        assert!(back.config.get("api_key").is_none());
+        assert!(back.id.is_empty());
+
+        assert!(decode_provider_uri("chi-llm://provider?model=x").is_err());
+        assert!(decode_provider_uri("https://example.com").is_err());
+    }
+}
@@ -1,22 +1,197 @@
 use std::collections::HashMap;
 use std::fs;
-use std::time::Duration;
+use std::path::Path;
+use std::sync::mpsc::Sender;
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use serde_json::Value;
 
+use crate::app::Notification;
+use crate::build::{env_var_name, provider_block, routing_profiles};
+use crate::env::timeouts;
+use crate::focus::FocusRing;
+use crate::forms::{FieldSchema, FormField, FormState};
+use crate::jobs::{Job, Jobs};
+use crate::keychain::keyring_account;
+use crate::paths::SCRATCH_FILE;
+use crate::recovery::{read_json_root, write_keeping_backup, FileKind};
+use crate::undo::UndoStack;
 use crate::util::run_cli_json;
 
+use super::share::{decode_provider_uri, encode_provider_uri, URI_PREFIX};
+use super::local::{add_tuning_fields, is_local_type};
+use super::network::{add_headers_field, add_network_fields, is_http_type, is_openai_compatible, HEADERS};
+use super::plugins::plugins;
+use super::presets::Preset;
+use super::probe::{build_discovery_args, spawn_model_discovery, spawn_provider_test, DiscoveryOutcome, ProviderTestOutcome, TestMode};
+use super::pull::PullOutcome;
+
 #[derive(Clone, Debug)]
 pub struct ProviderScratchEntry {
     pub id: String,
     pub name: String,
     pub ptype: String,
     pub tags: Vec<String>,
+    /// Maintenance mode: kept in `chi.tmp.json` but greyed out, left out of
+    /// routing profiles and never made the default.
+    pub disabled: bool,
+    /// Secret config keys kept in memory only: `chi.tmp.json` gets a `${VAR}`
+    /// reference instead of the value (for shared machines).
+    pub session_secrets: Vec<String>,
     pub config: Value,
 }
 
-#[derive(Clone, Debug)]
+impl ProviderScratchEntry {
+    /// Entry as stored in `chi.tmp.json`.
+    pub fn to_json(&self) -> Value {
+        let mut v = serde_json::json!({
+            "id": self.id,
+            "name": self.name,
+            "type": self.ptype,
+            "tags": self.tags,
+            "config": self.stored_config(),
+        });
+        if self.disabled { v["disabled"] = Value::Bool(true); }
+        if !self.session_secrets.is_empty() { v["session_only"] = serde_json::json!(self.session_secrets); }
+        v
+    }
+
+    /// Config as written to disk: session-only secrets become `${VAR}` references.
+    pub fn stored_config(&self) -> Value {
+        let mut config = self.config.clone();
+        if let Some(obj) = config.as_object_mut() {
+            for k in &self.session_secrets {
+                if obj.contains_key(k) { obj.insert(k.clone(), Value::String(format!("${{{}}}", env_var_name(&self.ptype, k)))); }
+            }
+        }
+        config
+    }
+
+    /// Mark config key `key` session-only, or persist it again. Returns the new state.
+    pub fn toggle_session_secret(&mut self, key: &str) -> bool {
+        match self.session_secrets.iter().position(|k| k == key) {
+            Some(i) => { self.session_secrets.remove(i); false }
+            None => { self.session_secrets.push(key.to_string()); true }
+        }
+    }
+
+    /// Parse a `chi.tmp.json` entry; the name falls back to the id.
+    pub fn from_json(p: &Value) -> Self {
+        let id = p.get("id").and_then(|x| x.as_str()).unwrap_or("").to_string();
+        let name = p.get("name").and_then(|x| x.as_str()).unwrap_or(&id).to_string();
+        let ptype = p.get("type").and_then(|x| x.as_str()).unwrap_or("").to_string();
+        let tags: Vec<String> = p.get("tags").and_then(|x| x.as_array()).map(|a| {
+            a.iter().filter_map(|t| t.as_str().map(|s| s.to_string())).collect()
+        }).unwrap_or_default();
+        let disabled = p.get("disabled").and_then(|x| x.as_bool()).unwrap_or(false);
+        let session_secrets: Vec<String> = p.get("session_only").and_then(|x| x.as_array()).map(|a| {
+            a.iter().filter_map(|t| t.as_str().map(|s| s.to_string())).collect()
+        }).unwrap_or_default();
+        let mut config = p.get("config").cloned().unwrap_or_else(|| serde_json::json!({"type": ptype}));
+        // Session-only secrets come from the environment when set, else start empty
+        if let Some(obj) = config.as_object_mut() {
+            for k in &session_secrets {
+                let from_env = std::env::var(env_var_name(&ptype, k)).unwrap_or_default();
+                if obj.contains_key(k) { obj.insert(k.clone(), Value::String(from_env)); }
+            }
+        }
+        Self { id, name, ptype, tags, disabled, session_secrets, config }
+    }
+}
+
+/// Heuristic for config keys holding credentials, used when no schema says so.
+/// Matches whole words (`api_key`, `auth.token`) and words ending in one
+/// (`apikey`, `accessToken`), but not `max_tokens` or `keep_alive`.
+pub fn is_secret_key(key: &str) -> bool {
+    let k = key.to_ascii_lowercase();
+    k.split(|c: char| !c.is_ascii_alphanumeric())
+        .any(|word| word == "key" || word == "apikey" || ["token", "secret", "password", "passwd"].iter().any(|s| word.ends_with(s)))
+}
+
+/// Validate raw JSON typed for a provider's config: it must be an object, and a
+/// `type` key (added when missing) must match `ptype`.
+pub fn parse_config_json(text: &str, ptype: &str) -> Result<Value> {
+    let mut v: Value = serde_json::from_str(text).map_err(|e| anyhow!("invalid JSON: {}", e))?;
+    let obj = v.as_object_mut().ok_or_else(|| anyhow!("config must be a JSON object"))?;
+    match obj.get("type") {
+        None => { obj.insert("type".to_string(), Value::String(ptype.to_string())); }
+        Some(t) if t.as_str() == Some(ptype) => {}
+        Some(_) => return Err(anyhow!("\"type\" must stay \"{}\"; change it with the type selector", ptype)),
+    }
+    Ok(v)
+}
+
+/// Provider entries found in pasted text: a `chi-llm://provider?...` URI, a
+/// `chi.tmp.json` entry, a list of them, a whole scratch file (`{"providers": [...]}`)
+/// or a `.chi_llm.json` provider block.
+pub fn parse_provider_json(text: &str) -> Result<Vec<ProviderScratchEntry>> {
+    if text.trim().starts_with(URI_PREFIX) { return Ok(vec![decode_provider_uri(text)?]); }
+    let v: Value = serde_json::from_str(text.trim()).map_err(|e| anyhow!("not valid JSON: {}", e))?;
+    let items: Vec<&Value> = match (v.as_array(), v.get("providers").and_then(|p| p.as_array())) {
+        (Some(arr), _) | (None, Some(arr)) => arr.iter().collect(),
+        (None, None) => vec![&v],
+    };
+    let mut out = Vec::new();
+    for item in items {
+        if item.get("config").is_some() {
+            out.push(ProviderScratchEntry::from_json(item));
+            continue;
+        }
+        // Bare provider block: {"provider": {"type": ..}} or {"type": .., "model": ..}
+        let block = item.get("provider").unwrap_or(item);
+        let Some(ptype) = block.get("type").and_then(|t| t.as_str()) else {
+            return Err(anyhow!("no provider type in pasted JSON"));
+        };
+        out.push(ProviderScratchEntry {
+            id: String::new(),
+            name: ptype.to_string(),
+            ptype: ptype.to_string(),
+            tags: Vec::new(),
+            disabled: false,
+            session_secrets: Vec::new(),
+            config: block.clone(),
+        });
+    }
+    if out.is_empty() { return Err(anyhow!("no providers in pasted JSON")); }
+    Ok(out)
+}
+
+/// Config keys that usually tell near-identical providers apart; "new from
+/// selected" asks for those the type has.
+pub const FORK_KEYS: [&str; 4] = ["base_url", "host", "port", "model"];
+
+/// Lead rows of the provider form, above the type's fields.
+pub const LEAD_NAME: usize = 0;
+pub const LEAD_ID: usize = 1;
+
+/// Lead row texts for `entry`: name, id, then the type selector.
+pub fn lead_rows(entry: &ProviderScratchEntry) -> Vec<String> {
+    vec![
+        format!("Name: {}  (Enter to rename)", entry.name),
+        format!("ID: {}  (Enter to change)", entry.id),
+        format!("Type: {}  (Enter to change)", entry.ptype),
+    ]
+}
+
+/// Provider ids: letters, digits, `-`, `_` and `.`.
+pub fn valid_id(id: &str) -> bool {
+    !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+/// Buttons under the provider form, addressed by the `BUTTON_*` indices.
+pub const FORM_BUTTONS: [&str; 3] = ["Test", "Save", "Cancel"];
+pub const BUTTON_TEST: usize = 0;
+pub const BUTTON_SAVE: usize = 1;
+pub const BUTTON_CANCEL: usize = 2;
+
+/// Panes of the Configure page: provider list (left) and inline form (right).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ProvidersPane {
+    List,
+    Form,
+}
+
+#[derive(Debug)]
 pub struct ProvidersState {
     pub entries: Vec<ProviderScratchEntry>,
     pub selected: usize,
@@ -24,23 +199,26 @@ pub struct ProvidersState {
     pub schema_map: HashMap<String, Vec<FieldSchema>>, // type -> fields
     pub test_status: Option<String>,
     pub form: Option<FormState>,
-    pub focus_right: bool,
-    pub dropdown: Option<DropdownState>,
+    pub focus: FocusRing<ProvidersPane>,
+    pub test_in_progress: bool,
+    pub test_job: Option<Job<ProviderTestOutcome>>,
+    /// Pending model discovery for the form's model dropdown.
+    pub discover_job: Option<Job<DiscoveryOutcome>>,
+    /// Running `ollama pull`.
+    pub pull_job: Option<Job<PullOutcome>>,
+    /// Show the JSON preview pane next to the form (`v`).
+    pub show_json: bool,
+    /// Id changes (old, new) since the last save, applied to the default
+    /// provider and per-provider history when saving.
+    pub renamed_ids: Vec<(String, String)>,
+    /// Entries and selection before each add or delete (Ctrl+Z / Ctrl+Y on the list).
+    pub list_history: UndoStack<(Vec<ProviderScratchEntry>, usize)>,
+    /// Importing an existing config was offered for this load (empty catalog).
+    pub import_offered: bool,
 }
 
 impl ProvidersState {
-    pub fn empty() -> Self {
-        Self {
-            entries: Vec::new(),
-            selected: 0,
-            schema_types: Vec::new(),
-            schema_map: HashMap::new(),
-            test_status: None,
-            form: None,
-            focus_right: false,
-            dropdown: None,
-        }
-    }
+    pub fn form_focused(&self) -> bool { self.focus.is(ProvidersPane::Form) }
     pub fn len_with_add(&self) -> usize { self.entries.len() + 1 }
     pub fn is_add_row(&self) -> bool { self.selected >= self.entries.len() }
     pub fn add_default(&mut self) {
@@ -50,62 +228,340 @@ impl ProvidersState {
         } else if let Some(idx) = self.schema_types.iter().position(|t| t == "local") {
             self.schema_types.get(idx).cloned().unwrap_or_else(|| "local".to_string())
         } else {
-            self.schema_types.get(0).cloned().unwrap_or_else(|| "local".to_string())
+            self.schema_types.first().cloned().unwrap_or_else(|| "local".to_string())
         };
-        let id = format!("p{}", self.entries.len() + 1);
-        let name = format!("{}", &ptype);
-        let cfg = serde_json::json!({"type": ptype});
+        self.add_provider(&ptype);
+    }
+    /// Append a provider of `ptype` with a fresh id and select it.
+    pub fn add_provider(&mut self, ptype: &str) -> &mut ProviderScratchEntry {
+        self.checkpoint();
+        let id = self.next_id();
         self.entries.push(ProviderScratchEntry {
             id,
-            name,
-            ptype: cfg.get("type").and_then(|x| x.as_str()).unwrap_or("").to_string(),
+            name: ptype.to_string(),
+            ptype: ptype.to_string(),
             tags: Vec::new(),
-            config: cfg,
+            disabled: false,
+            session_secrets: Vec::new(),
+            config: serde_json::json!({"type": ptype}),
         });
-        self.selected = self.entries.len().saturating_sub(1);
+        self.selected = self.entries.len() - 1;
+        &mut self.entries[self.selected]
+    }
+    /// Append a provider filled in from `preset` under a free name and select it.
+    pub fn add_preset(&mut self, preset: &Preset) -> &mut ProviderScratchEntry {
+        let name = self.free_name(preset.name);
+        let config = preset.config(self.schema_map.get(preset.ptype).map(Vec::as_slice));
+        let entry = self.add_provider(preset.ptype);
+        entry.name = name;
+        entry.config = Value::Object(config);
+        entry
+    }
+    /// Fields a "new from selected" copy is asked for: its name plus whichever
+    /// of the endpoint/model fields its type has, filled with the selected values.
+    pub fn fork_fields(&self) -> Vec<FormField> {
+        let Some(entry) = self.entries.get(self.selected) else { return Vec::new() };
+        let name = FieldSchema { name: "name".to_string(), ftype: "string".to_string(), required: true, default: None, help: Some("Display name of the copy".to_string()), options: None };
+        let mut fields = vec![FormField::from_value(&name, Some(&Value::String(self.copy_name(&entry.name))))];
+        let schema = self.schema_map.get(&entry.ptype).map(Vec::as_slice).unwrap_or_default();
+        for key in FORK_KEYS {
+            let Some(sc) = schema.iter().find(|sc| sc.name == key) else { continue };
+            fields.push(FormField::from_value(sc, entry.config.get(key)));
+        }
+        fields
+    }
+    /// Append a copy of the selected entry (fresh id, same type, config, tags and
+    /// session-only secrets) with `name` and `overrides` applied, and select it.
+    pub fn fork_selected(&mut self, name: String, overrides: serde_json::Map<String, Value>) -> Option<&mut ProviderScratchEntry> {
+        let mut copy = self.entries.get(self.selected)?.clone();
+        self.checkpoint();
+        copy.id = self.next_id();
+        copy.name = name;
+        copy.disabled = false;
+        if let Some(obj) = copy.config.as_object_mut() { obj.extend(overrides); }
+        self.entries.push(copy);
+        self.selected = self.entries.len() - 1;
+        self.form = None;
+        self.entries.last_mut()
+    }
+    /// Duplicate the selected entry unchanged (see `fork_selected`) under a free copy name.
+    pub fn clone_selected(&mut self) -> Option<&mut ProviderScratchEntry> {
+        let name = self.copy_name(&self.entries.get(self.selected)?.name);
+        self.fork_selected(name, serde_json::Map::new())
+    }
+    /// `<name> copy`, or `<name> copy 2`, ... when that name is taken.
+    pub fn copy_name(&self, name: &str) -> String {
+        self.free_name(&format!("{} copy", name))
+    }
+    /// `name`, or `name 2`, `name 3`, ... when it is taken.
+    pub fn free_name(&self, name: &str) -> String {
+        let taken = |n: &str| self.entries.iter().any(|e| e.name == n);
+        let mut free = name.to_string();
+        let mut n = 2;
+        while taken(&free) {
+            free = format!("{} {}", name, n);
+            n += 1;
+        }
+        free
+    }
+    /// Rename the selected entry; the name is for display only.
+    pub fn rename_selected(&mut self, name: &str) -> Result<(), String> {
+        let name = name.trim();
+        if name.is_empty() { return Err("Name cannot be empty".to_string()); }
+        let entry = self.entries.get_mut(self.selected).ok_or("No provider selected")?;
+        entry.name = name.to_string();
+        self.refresh_lead_rows();
+        Ok(())
+    }
+    /// Change the selected entry's id. It must be unique and use letters, digits, `-`, `_` or `.`.
+    pub fn set_selected_id(&mut self, id: &str) -> Result<(), String> {
+        let id = id.trim();
+        if !valid_id(id) { return Err(format!("Invalid id '{}': use letters, digits, -, _ or .", id)); }
+        if self.entries.iter().enumerate().any(|(i, e)| i != self.selected && e.id == id) {
+            return Err(format!("Id '{}' is already used", id));
+        }
+        let entry = self.entries.get_mut(self.selected).ok_or("No provider selected")?;
+        if entry.id != id {
+            self.renamed_ids.push((std::mem::replace(&mut entry.id, id.to_string()), id.to_string()));
+        }
+        self.refresh_lead_rows();
+        Ok(())
+    }
+    /// Update the form's name/id/type rows without touching unsaved field edits.
+    fn refresh_lead_rows(&mut self) {
+        if let (Some(form), Some(entry)) = (&mut self.form, self.entries.get(self.selected)) { form.lead = lead_rows(entry); }
     }
     pub fn delete_selected(&mut self) {
         if self.selected < self.entries.len() {
+            self.checkpoint();
             self.entries.remove(self.selected);
             if self.selected > 0 { self.selected -= 1; }
         }
     }
     pub fn apply_model_to_selected(&mut self, model_id: &str) {
+        self.apply_to_selected("model", model_id);
+    }
+    /// Set config `key` of the selected provider, keeping an open form in sync.
+    pub fn apply_to_selected(&mut self, key: &str, value: &str) {
         if self.selected < self.entries.len() {
             if let Some(obj) = self.entries[self.selected].config.as_object_mut() {
-                obj.insert("model".to_string(), Value::String(model_id.to_string()));
+                obj.insert(key.to_string(), Value::String(value.to_string()));
+            }
+        }
+        if let Some(form) = &mut self.form {
+            if let Some(fi) = form.fields.iter().position(|ff| ff.schema.name == key) {
+                form.set_field(fi, value.to_string());
             }
         }
     }
-    pub fn save(&self) -> Result<()> {
-        let path = "chi.tmp.json";
-        let mut root: Value = if let Ok(text) = fs::read_to_string(path) {
-            serde_json::from_str(&text).unwrap_or_else(|_| serde_json::json!({}))
-        } else {
-            serde_json::json!({})
+    /// True if `key` is a secret for `ptype` per the schema (or by name when the
+    /// schema does not know the field).
+    pub fn is_secret(&self, ptype: &str, key: &str) -> bool {
+        match self.schema_map.get(ptype).and_then(|fields| fields.iter().find(|f| f.name == key)) {
+            // Gateway headers usually carry credentials
+            Some(f) => f.ftype == "secret" || f.name == HEADERS,
+            None => is_secret_key(key),
+        }
+    }
+    /// What the selected provider, with unsaved form edits, contributes to
+    /// `.chi_llm.json`: its `provider` block and, when tagged, its routing
+    /// profile. Secret values are masked.
+    pub fn selected_preview(&self) -> Option<Value> {
+        let entry = self.selected_with_form()?;
+        let mask = |block: &mut serde_json::Map<String, Value>| {
+            for (k, v) in block.iter_mut() {
+                if !self.is_secret(&entry.ptype, k) { continue; }
+                match v {
+                    Value::String(s) if !s.starts_with("${") && keyring_account(s).is_none() => *s = "••••••".to_string(),
+                    Value::Object(m) => m.values_mut().for_each(|x| *x = Value::String("••••••".to_string())),
+                    _ => {}
+                }
+            }
         };
-        let mut providers: Vec<Value> = Vec::new();
-        for e in &self.entries {
-            providers.push(serde_json::json!({
-                "id": e.id,
-                "name": e.name,
-                "type": e.ptype,
-                "tags": e.tags,
-                "config": e.config,
-            }));
-        }
-        if !root.is_object() { root = serde_json::json!({}); }
+        let mut provider = provider_block(&entry.ptype, &entry.stored_config());
+        mask(&mut provider);
+        let mut out = serde_json::json!({ "provider": provider });
+        let scratch = serde_json::json!({ "providers": [entry.to_json()] });
+        if let Some(mut profile) = routing_profiles(&scratch, "").into_iter().next() {
+            mask(&mut profile);
+            out["provider_profiles"] = serde_json::json!([profile]);
+        }
+        Some(out)
+    }
+    /// Replace the selected entry's config (e.g. from the raw JSON editor) and
+    /// rebuild its form.
+    pub fn set_selected_config(&mut self, config: Value) {
+        let Some(entry) = self.entries.get_mut(self.selected) else { return };
+        entry.config = config;
+        self.ensure_form();
+    }
+    /// Selected entry as JSON with secret config values removed, for sharing.
+    pub fn selected_shareable(&self) -> Option<Value> {
+        let mut entry = self.entries.get(self.selected)?.clone();
+        if let Some(obj) = entry.config.as_object_mut() {
+            obj.retain(|k, _| !self.is_secret(&entry.ptype, k));
+        }
+        Some(entry.to_json())
+    }
+    /// Selected entry as a shareable `chi-llm://provider?...` URI (no secrets).
+    pub fn selected_uri(&self) -> Option<String> {
+        let entry = self.entries.get(self.selected)?;
+        Some(encode_provider_uri(entry, |k| self.is_secret(&entry.ptype, k)))
+    }
+    /// Append pasted entries, giving them fresh ids when theirs are missing or taken
+    /// and typing config values per the schema (e.g. `int` ports from a URI).
+    /// Selects the last one and returns how many were added.
+    pub fn add_entries(&mut self, pasted: Vec<ProviderScratchEntry>) -> usize {
+        let n = pasted.len();
+        if n > 0 { self.checkpoint(); }
+        for mut e in pasted {
+            if e.id.is_empty() || self.entries.iter().any(|x| x.id == e.id) {
+                e.id = self.next_id();
+            }
+            if let (Some(fields), Some(obj)) = (self.schema_map.get(&e.ptype), e.config.as_object_mut()) {
+                for sc in fields {
+                    if let Some(v) = obj.get_mut(&sc.name).filter(|v| v.is_string()) { *v = FormField::from_value(sc, Some(v)).value(); }
+                }
+            }
+            self.entries.push(e);
+        }
+        if n > 0 { self.selected = self.entries.len() - 1; self.form = None; }
+        n
+    }
+    /// Remember the list before an add or delete.
+    fn checkpoint(&mut self) {
+        self.list_history.record((self.entries.clone(), self.selected));
+    }
+    /// Undo the last add or delete (unsaved form edits of the selected entry are dropped).
+    pub fn undo_list(&mut self) -> bool {
+        let Some(prev) = self.list_history.undo((self.entries.clone(), self.selected)) else { return false };
+        (self.entries, self.selected) = prev;
+        self.form = None;
+        true
+    }
+    pub fn redo_list(&mut self) -> bool {
+        let Some(next) = self.list_history.redo((self.entries.clone(), self.selected)) else { return false };
+        (self.entries, self.selected) = next;
+        self.form = None;
+        true
+    }
+    fn next_id(&self) -> String {
+        let mut n = self.entries.len() + 1;
+        while self.entries.iter().any(|e| e.id == format!("p{}", n)) { n += 1; }
+        format!("p{}", n)
+    }
+    /// Rebuild the form for the selected entry from its type schema and saved config.
+    pub fn ensure_form(&mut self) {
+        if self.selected >= self.entries.len() { self.form = None; return; }
+        let entry = &self.entries[self.selected];
+        let fields: Vec<FormField> = self.schema_map.get(&entry.ptype)
+            .map(|sfields| sfields.iter().map(|sc| {
+                let mut ff = FormField::from_value(sc, entry.config.get(&sc.name));
+                ff.session_only = entry.session_secrets.contains(&sc.name);
+                ff
+            }).collect())
+            .unwrap_or_default();
+        self.form = Some(FormState::new(lead_rows(entry), fields, FORM_BUTTONS.to_vec()));
+    }
+    /// Move focus to the form pane, creating an entry first when the "add" row is selected.
+    pub fn focus_form(&mut self) {
+        if self.is_add_row() { self.add_default(); }
+        self.ensure_form();
+        self.focus.focus(ProvidersPane::Form);
+    }
+    /// Selected entry with the open form's (possibly unsaved) values applied.
+    pub fn selected_with_form(&self) -> Option<ProviderScratchEntry> {
+        let mut entry = self.entries.get(self.selected)?.clone();
+        if let (Some(form), Some(obj)) = (&self.form, entry.config.as_object_mut()) {
+            obj.extend(form.to_config());
+        }
+        Some(entry)
+    }
+    /// Start a background test of the selected entry (using unsaved form values when a
+    /// form hash is given). `mode` defaults per provider type. Returns false if nothing started.
+    pub fn start_test(&mut self, mode: Option<TestMode>, form_hash: Option<String>, notify: Sender<Notification>, jobs: &Jobs) -> bool {
+        if self.test_in_progress { return false; }
+        let entry = if form_hash.is_some() { self.selected_with_form() } else { self.entries.get(self.selected).cloned() };
+        let Some(entry) = entry else { return false };
+        let mode = mode.unwrap_or_else(|| TestMode::default_for(&entry.ptype));
+        self.test_job = Some(spawn_provider_test(entry, mode, form_hash, notify, jobs));
+        self.test_in_progress = true;
+        self.test_status = Some("Testing… (Ctrl+X stops)".to_string());
+        true
+    }
+    /// Stop the running test: its CLI call is killed and its result dropped.
+    /// Returns false when no test is running.
+    pub fn cancel_test(&mut self) -> bool {
+        let Some(job) = self.test_job.take() else { return false };
+        job.cancel();
+        self.test_in_progress = false;
+        self.test_status = Some("Test cancelled".to_string());
+        if let Some(form) = &mut self.form { form.message = Some("Test cancelled".to_string()); }
+        true
+    }
+    /// Apply a finished background test (if any) to the list status and the open form.
+    /// Returns the outcome so the caller can react to it (e.g. run hooks).
+    pub fn poll_test(&mut self) -> Option<ProviderTestOutcome> {
+        let outcome = self.test_job.as_ref()?.poll()?;
+        self.test_job = None;
+        self.test_in_progress = false;
+        let outcome = match outcome {
+            Ok(o) => o,
+            Err(e) => { self.test_status = Some(format!("Error: {}", e)); return None; }
+        };
+        self.test_status = Some(outcome.status.clone());
+        let same_entry = self.entries.get(self.selected).is_some_and(|e| e.id == outcome.entry_id);
+        if let (true, Some(form), Some(hash)) = (same_entry, &mut self.form, &outcome.form_hash) {
+            form.verified_hash = if outcome.ok { Some(hash.clone()) } else { None };
+            form.message = Some(outcome.status.clone());
+        }
+        Some(outcome)
+    }
+    /// Discover models for form field `fi` in the background, using the form's
+    /// current values. Returns false when the type has no discovery command.
+    pub fn start_model_discovery(&mut self, fi: usize, jobs: &Jobs) -> bool {
+        let Some(cmd) = self.selected_with_form().and_then(|e| build_discovery_args(&e).ok()) else { return false };
+        let (Some(form), Some(entry)) = (self.form.as_mut(), self.entries.get(self.selected)) else { return false };
+        if self.discover_job.is_some() {
+            form.message = Some("Model discovery already running…".to_string());
+        } else {
+            form.message = Some(format!("Discovering {} models…", entry.ptype));
+            self.discover_job = Some(spawn_model_discovery(cmd, entry, fi, jobs));
+        }
+        true
+    }
+    /// Take a finished ollama pull, if any.
+    pub fn poll_pull(&mut self) -> Option<PullOutcome> {
+        let outcome = self.pull_job.as_ref()?.poll()?;
+        self.pull_job = None;
+        outcome.ok()
+    }
+    /// Take a finished model discovery, if any.
+    pub fn poll_discovery(&mut self) -> Option<DiscoveryOutcome> {
+        let outcome = self.discover_job.as_ref()?.poll()?;
+        self.discover_job = None;
+        outcome.ok()
+    }
+    pub fn save(&self) -> Result<()> {
+        let path = Path::new(SCRATCH_FILE);
+        let mut root = read_json_root(path)?;
+        let providers: Vec<Value> = self.entries.iter().map(ProviderScratchEntry::to_json).collect();
         if let Some(obj) = root.as_object_mut() {
             obj.insert("providers".to_string(), Value::Array(providers));
+            // Keep the default pointing at a provider whose id changed
+            for (old, new) in &self.renamed_ids {
+                if obj.get("default_provider_id").and_then(Value::as_str) == Some(old) {
+                    obj.insert("default_provider_id".to_string(), Value::String(new.clone()));
+                }
+            }
         }
-        fs::write(path, serde_json::to_vec_pretty(&root)?)?;
-        Ok(())
+        write_keeping_backup(FileKind::Scratch, path, &serde_json::to_vec_pretty(&root)?)
     }
 }
 
 pub fn load_providers_state() -> Result<ProvidersState> {
     // Load schema types and fields
-    let schema = run_cli_json(&["providers", "schema", "--json"], Duration::from_secs(5))?;
+    let schema = run_cli_json(&["providers", "schema", "--json"], timeouts().cli)?;
     let mut types: Vec<String> = Vec::new();
     let mut schema_map: HashMap<String, Vec<FieldSchema>> = HashMap::new();
     if let Some(arr) = schema.get("providers").and_then(|v| v.as_array()) {
@@ -117,9 +573,14 @@ pub fn load_providers_state() -> Result<ProvidersState> {
                     for f in farr {
                         let name = f.get("name").and_then(|v| v.as_str()).unwrap_or("").to_string();
                         if name.is_empty() { continue; }
-                        let ftype = f.get("type").and_then(|v| v.as_str()).unwrap_or("string").to_string();
+                        // JSON Schema spellings of a toggle and a number
+                        let ftype = match f.get("type").and_then(|v| v.as_str()).unwrap_or("string") {
+                            "boolean" => "bool".to_string(),
+                            "number" => "float".to_string(),
+                            t => t.to_string(),
+                        };
                         let required = f.get("required").and_then(|v| v.as_bool()).unwrap_or(false);
-                        let default = if let Some(d) = f.get("default") { Some(d.to_string().trim_matches('"').to_string()) } else { None };
+                        let default = f.get("default").map(|d| d.to_string().trim_matches('"').to_string());
                         let help = f.get("help").and_then(|v| v.as_str()).map(|s| s.to_string());
                         // Collect enum-like options for dropdowns from common keys
                         let mut opts: Vec<String> = Vec::new();
@@ -140,75 +601,108 @@ pub fn load_providers_state() -> Result<ProvidersState> {
             }
         }
     }
-    types.sort();
-    // Load scratch file
-    let path = "chi.tmp.json";
-    let text = fs::read_to_string(path).unwrap_or_else(|_| "{}".to_string());
-    let v: Value = serde_json::from_str(&text)?;
-    let mut entries: Vec<ProviderScratchEntry> = Vec::new();
-    if let Some(arr) = v.get("providers").and_then(|x| x.as_array()) {
-        for p in arr {
-            let id = p.get("id").and_then(|x| x.as_str()).unwrap_or("").to_string();
-            let name = p.get("name").and_then(|x| x.as_str()).unwrap_or(&id).to_string();
-            let ptype = p.get("type").and_then(|x| x.as_str()).unwrap_or("").to_string();
-            let tags: Vec<String> = p.get("tags").and_then(|x| x.as_array()).map(|a| {
-                a.iter().filter_map(|t| t.as_str().map(|s| s.to_string())).collect()
-            }).unwrap_or_default();
-            let config = p.get("config").cloned().unwrap_or_else(|| serde_json::json!({"type": ptype}));
-            entries.push(ProviderScratchEntry { id, name, ptype, tags, config });
-        }
+    for (ptype, fields) in schema_map.iter_mut() {
+        if is_local_type(ptype) { add_tuning_fields(fields); } else if is_http_type(ptype) { add_network_fields(fields); }
+        if is_openai_compatible(ptype) { add_headers_field(fields); }
+    }
+    // Plugin types extend (or override) what the CLI reports
+    for plugin in &plugins().plugins {
+        if !types.contains(&plugin.ptype) { types.push(plugin.ptype.clone()); }
+        schema_map.insert(plugin.ptype.clone(), plugin.schema());
     }
+    types.sort();
     Ok(ProvidersState {
-        entries,
+        entries: load_scratch_entries()?,
         selected: 0,
         schema_types: types,
         schema_map,
         test_status: None,
         form: None,
-        focus_right: false,
-        dropdown: None,
+        focus: FocusRing::new(&[ProvidersPane::List, ProvidersPane::Form]),
+        test_in_progress: false,
+        test_job: None,
+        discover_job: None,
+        pull_job: None,
+        show_json: false,
+        renamed_ids: Vec::new(),
+        list_history: UndoStack::default(),
+        import_offered: false,
     })
 }
 
-#[derive(Clone, Debug)]
-pub struct FieldSchema {
-    pub name: String,
-    pub ftype: String,
-    pub required: bool,
-    pub default: Option<String>,
-    pub help: Option<String>,
-    pub options: Option<Vec<String>>, // optional enum-like options for dropdowns
+/// Provider entries from the `chi.tmp.json` scratch file (empty when it is missing).
+pub fn load_scratch_entries() -> Result<Vec<ProviderScratchEntry>> {
+    let path = SCRATCH_FILE;
+    let text = fs::read_to_string(path).unwrap_or_else(|_| "{}".to_string());
+    let v: Value = serde_json::from_str(&text)?;
+    let mut entries: Vec<ProviderScratchEntry> = Vec::new();
+    if let Some(arr) = v.get("providers").and_then(|x| x.as_array()) {
+        entries.extend(arr.iter().map(ProviderScratchEntry::from_json));
+    }
+    Ok(entries)
 }
 
-#[derive(Clone, Debug)]
-pub struct FormField { pub schema: FieldSchema, pub buffer: String, pub cursor: usize }
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-#[derive(Clone, Debug)]
-pub struct FormState {
-    pub fields: Vec<FormField>,
-    pub selected: usize, // 0: Type, 1..=fields: fields, fields+1: Save, fields+2: Cancel
-    pub editing: bool,
-    pub message: Option<String>,
-    pub scroll: usize,
-    pub initial_hash: String,
-    pub last_test_ok_hash: Option<String>,
-}
+    #[test]
+    fn parses_pasted_entries_and_blocks() {
+        let entry = r#"{"id":"p1","name":"work","type":"openai","config":{"model":"gpt"}}"#;
+        let parsed = parse_provider_json(entry).unwrap();
+        assert_eq!((parsed[0].id.as_str(), parsed[0].name.as_str()), ("p1", "work"));
+
+        let block = r#"{"provider":{"type":"ollama","model":"llama3"}}"#;
+        let parsed = parse_provider_json(block).unwrap();
+        assert_eq!(parsed[0].ptype, "ollama");
+        assert!(parsed[0].id.is_empty());
+        assert_eq!(parsed[0].config["model"], "llama3");
 
-pub fn compute_form_hash(fields: &Vec<FormField>) -> String {
-    let mut s = String::new();
-    for f in fields.iter() {
-        s.push_str(&f.schema.name);
-        s.push('=');
-        s.push_str(&f.buffer);
-        s.push('\u{1F}');
+        assert!(parse_provider_json(r#"{"model":"x"}"#).is_err());
+        assert!(parse_provider_json("not json").is_err());
     }
-    s
-}
 
-#[derive(Clone, Debug)]
-pub struct DropdownState {
-    pub items: Vec<String>,
-    pub selected: usize,
-    pub title: String,
-    pub target_field: Option<usize>, // None => provider type; Some(i) => form field index
+    #[test]
+    fn disabled_flag_is_stored_only_when_set() {
+        let mut e = ProviderScratchEntry::from_json(&serde_json::json!({"id": "p1", "type": "openai", "disabled": true}));
+        assert!(e.disabled);
+        assert_eq!(e.to_json()["disabled"], true);
+        e.disabled = false;
+        assert!(e.to_json().get("disabled").is_none());
+    }
+
+    #[test]
+    fn session_secrets_are_stored_as_env_refs() {
+        let mut e = ProviderScratchEntry::from_json(&serde_json::json!({"id": "p1", "type": "session-test", "config": {"type": "session-test", "api_key": "sk-1"}}));
+        assert!(e.toggle_session_secret("api_key"));
+        let stored = e.to_json();
+        assert_eq!(stored["config"]["api_key"], "${SESSION_TEST_API_KEY}");
+        assert_eq!(stored["session_only"], serde_json::json!(["api_key"]));
+        assert_eq!(e.config["api_key"], "sk-1");
+        // Not in the environment: the value starts empty but stays session-only
+        let back = ProviderScratchEntry::from_json(&stored);
+        assert_eq!((back.config["api_key"].as_str(), back.session_secrets.len()), (Some(""), 1));
+        assert!(!e.toggle_session_secret("api_key"));
+        assert!(e.to_json().get("session_only").is_none());
+    }
+
+    #[test]
+    fn secret_keys_are_matched_by_word() {
+        for key in ["api_key", "apiKey", "x-api-key", "cheap.api_key", "token", "accessToken", "client_secret", "password"] {
+            assert!(is_secret_key(key), "{}", key);
+        }
+        for key in ["max_tokens", "output_tokens", "keep_alive", "monkey", "model"] {
+            assert!(!is_secret_key(key), "{}", key);
+        }
+    }
+
+    #[test]
+    fn raw_config_json_is_validated() {
+        let v = parse_config_json(r#"{"model": "m", "extra": {"a": 1}}"#, "openai").unwrap();
+        assert_eq!(v["type"], "openai");
+        assert_eq!(v["extra"]["a"], 1);
+        assert!(parse_config_json(r#"{"type": "ollama"}"#, "openai").is_err());
+        assert!(parse_config_json("[1]", "openai").is_err());
+        assert!(parse_config_json("{", "openai").is_err());
+    }
 }
@@ -0,0 +1,155 @@
+//! Status strip under the header: the default provider in `chi.tmp.json` and
+//! what chi-llm will actually use, i.e. the provider block of the config it
+//! reads first (project, then global). Re-read when one of those files
+//! changes, checked once a second.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+use serde_json::Value;
+
+use crate::build::provider_block;
+use crate::paths::{paths, PROJECT_CONFIG_LOOKUP, SCRATCH_FILE};
+
+use super::import::{import_sources, read_config_file};
+use super::select_default::load_providers_scratch;
+use super::state::load_scratch_entries;
+
+const CHECK_EVERY: Duration = Duration::from_secs(1);
+
+/// A provider as the strip shows it.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ProviderSummary {
+    pub name: String,
+    pub ptype: String,
+    pub model: Option<String>,
+}
+
+/// The model a provider config names: `model`, else the file name of
+/// `model_path` (local providers name a file; its name is enough to recognise it).
+pub fn configured_model(config: &Value) -> Option<String> {
+    let text = |key: &str| config.get(key).and_then(Value::as_str).filter(|m| !m.is_empty());
+    text("model").map(str::to_string).or_else(|| {
+        text("model_path").map(|p| Path::new(p).file_name().map_or(p.into(), |n| n.to_string_lossy().into_owned()))
+    })
+}
+
+impl ProviderSummary {
+    pub fn from_config(name: String, ptype: String, config: &Value) -> Self {
+        Self { name, ptype, model: configured_model(config) }
+    }
+
+    /// `type/model`, or just the type.
+    pub fn describe(&self) -> String {
+        match &self.model {
+            Some(m) => format!("{}/{}", self.ptype, m),
+            None => self.ptype.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConfigSource {
+    Project,
+    Global,
+}
+
+/// The config chi-llm reads and its provider block.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ActiveConfig {
+    pub source: ConfigSource,
+    pub path: PathBuf,
+    /// `None` when the file has no provider block or cannot be read.
+    pub provider: Option<ProviderSummary>,
+}
+
+#[derive(Debug, Default)]
+pub struct ActiveStatus {
+    /// Default provider of the scratch catalog.
+    pub default: Option<ProviderSummary>,
+    pub config: Option<ActiveConfig>,
+    stamps: Vec<Option<SystemTime>>,
+    next_check: Option<Instant>,
+    generation: u64,
+}
+
+impl ActiveStatus {
+    /// Re-read the files if one of them changed since the last read.
+    pub fn refresh(&mut self) {
+        let now = Instant::now();
+        if self.next_check.is_some_and(|at| now < at) { return; }
+        self.next_check = Some(now + CHECK_EVERY);
+        let stamps = watched_files().iter().map(|p| fs::metadata(p).and_then(|m| m.modified()).ok()).collect::<Vec<_>>();
+        if stamps == self.stamps { return; }
+        self.stamps = stamps;
+        self.generation += 1;
+        self.default = read_default();
+        self.config = read_active_config();
+    }
+
+    /// Bumped whenever `chi.tmp.json` or a config file changes, for views
+    /// that cache something computed from them.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Whether the config chi-llm reads uses another provider than the
+    /// scratch default; Build brings them back in line.
+    pub fn out_of_date(&self) -> bool {
+        let (Some(default), Some(config)) = (&self.default, &self.config) else { return false };
+        config.provider.as_ref().is_none_or(|a| default.ptype != a.ptype || default.model != a.model)
+    }
+}
+
+fn watched_files() -> Vec<PathBuf> {
+    let mut files = vec![PathBuf::from(SCRATCH_FILE)];
+    files.extend(PROJECT_CONFIG_LOOKUP.iter().map(PathBuf::from));
+    files.extend(paths().global_config());
+    files
+}
+
+fn read_default() -> Option<ProviderSummary> {
+    let id = load_providers_scratch().ok()?.current_default_id?;
+    let entry = load_scratch_entries().ok()?.into_iter().find(|e| e.id == id)?;
+    // As Build would write it, so UI-only local variants compare equal to `local`
+    let block = provider_block(&entry.ptype, &entry.config);
+    let ptype = block.get("type").and_then(Value::as_str).unwrap_or_default().to_string();
+    Some(ProviderSummary::from_config(entry.name, ptype, &Value::Object(block)))
+}
+
+fn read_active_config() -> Option<ActiveConfig> {
+    let path = import_sources().into_iter().next()?;
+    let source = if paths().global_config().as_ref() == Some(&path) { ConfigSource::Global } else { ConfigSource::Project };
+    let provider = read_config_file(&path).ok().and_then(|config| {
+        let block = config.get("provider")?;
+        let ptype = block.get("type").and_then(Value::as_str)?.to_string();
+        Some(ProviderSummary::from_config(ptype.clone(), ptype, block))
+    });
+    Some(ActiveConfig { source, path, provider })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(ptype: &str, model: Option<&str>) -> ProviderSummary {
+        ProviderSummary { name: ptype.to_string(), ptype: ptype.to_string(), model: model.map(str::to_string) }
+    }
+
+    #[test]
+    fn out_of_date_compares_type_and_model() {
+        let config = |provider| Some(ActiveConfig { source: ConfigSource::Project, path: PathBuf::from(".chi_llm.json"), provider });
+        let mut st = ActiveStatus { default: Some(summary("ollama", Some("llama3.2"))), ..ActiveStatus::default() };
+        assert!(!st.out_of_date(), "nothing built yet is not a mismatch");
+        st.config = config(Some(summary("ollama", Some("llama3.2"))));
+        assert!(!st.out_of_date());
+        st.config = config(Some(summary("ollama", Some("qwen2.5"))));
+        assert!(st.out_of_date());
+        st.config = config(None);
+        assert!(st.out_of_date());
+
+        let local = ProviderSummary::from_config("mine".to_string(), "local".to_string(), &serde_json::json!({ "model_path": "/m/phi.gguf" }));
+        assert_eq!(local.describe(), "local/phi.gguf");
+    }
+}
@@ -1,200 +1,108 @@
-use std::time::Duration;
-
-use anyhow::Result;
-use ratatui::layout::{Rect, Layout, Direction, Constraint};
+use ratatui::layout::{Rect, Layout, Direction, Constraint, Margin};
 use ratatui::prelude::Frame;
-use ratatui::style::{Modifier, Style};
+use ratatui::style::Modifier;
 use ratatui::text::{Line, Span};
-use ratatui::widgets::{Block, Borders, Clear, List, ListItem, Paragraph, Wrap};
-use crate::util::run_cli_json;
-use super::state::compute_form_hash;
-use serde_json::Value;
+use ratatui::widgets::{Block, Borders, List, ListItem, Paragraph};
 
 use crate::app::App;
-use crate::util::centered_rect;
+use crate::mouse::HitTarget;
+use crate::page_data::{draw_page_status, spinner};
+
+use crate::forms::draw_form;
+use crate::highlight::{highlight_line, Lang};
+use crate::text;
 
-use super::{ProvidersState, FormField};
+use super::budget::{budget_status, is_cloud_type};
+use super::monitor::{health_dot, monitor_title};
+use super::state::BUTTON_SAVE;
 
 pub fn draw_providers_catalog(f: &mut Frame, area: Rect, app: &App) {
+    let Some(st) = app.providers.ready() else {
+        draw_page_status(f, area, app, "Configure Providers", &app.providers);
+        return;
+    };
+    // `v` adds a third pane with the JSON the selected provider is written as
+    let widths = if st.show_json { [30, 40, 30] } else { [45, 55, 0] };
     let cols = Layout::default()
         .direction(Direction::Horizontal)
-        .constraints([Constraint::Percentage(45), Constraint::Percentage(55)]).split(area);
+        .constraints(widths.map(Constraint::Percentage)).split(area);
 
     // Left list
     let mut items: Vec<ListItem> = Vec::new();
-    if let Some(st) = &app.providers {
-        for (i, e) in st.entries.iter().enumerate() {
-            let mut label = format!("{} {} [{}]", if i == st.selected { '›' } else { ' ' }, e.name, e.ptype);
-            if let Some(model) = e.config.get("model").and_then(|v| v.as_str()) { label.push_str(&format!("  [model:{}]", model)); }
-            if !e.tags.is_empty() { label.push_str(&format!("  [{}]", e.tags.join(","))); }
-            let mut style = if i == st.selected { Style::default().fg(app.theme.selected).add_modifier(Modifier::BOLD) } else { Style::default().fg(app.theme.fg) };
-            if !st.focus_right && i == st.selected { style = style.add_modifier(Modifier::UNDERLINED); }
-            items.push(ListItem::new(Line::from(Span::styled(label, style))));
+    for (i, e) in st.entries.iter().enumerate() {
+        let mut label = format!("{} {} [{}]", if i == st.selected { '›' } else { ' ' }, text::truncate(&e.name, text::NAME_MAX), e.ptype);
+        if let Some(model) = e.config.get("model").and_then(|v| v.as_str()) { label.push_str(&format!("  [model:{}]", model)); }
+        if !e.tags.is_empty() { label.push_str(&format!("  [{}]", e.tags.join(","))); }
+        if e.disabled { label.push_str("  [disabled]"); }
+        let mut style = if e.disabled { app.theme.disabled() } else { app.theme.row(i == st.selected) };
+        let model = e.config.get("model").and_then(|v| v.as_str()).unwrap_or("");
+        if let Some(b) = budget_status(&app.usage, &e.id, model, &app.settings.pricing).filter(|_| is_cloud_type(&e.ptype)) {
+            // Only calls made from the TUI are counted
+            label.push_str(&format!("  TUI spend {}", b.bar(10)));
+            if b.ratio() >= 1.0 { style = app.theme.error(); } else if b.is_warning() { style = app.theme.warning(); }
         }
-        let mut add_style = if st.is_add_row() { Style::default().fg(app.theme.selected).add_modifier(Modifier::BOLD) } else { Style::default().fg(app.theme.accent) };
-        if !st.focus_right && st.is_add_row() { add_style = add_style.add_modifier(Modifier::UNDERLINED); }
-        items.push(ListItem::new(Line::from(Span::styled("+ Add provider", add_style))));
-        if let Some(status) = &st.test_status {
-            items.push(ListItem::new(Line::from(Span::styled(format!("Status: {}", status), Style::default().fg(app.theme.secondary)))));
-        }
-    } else {
-        items.push(ListItem::new("Loading providers..."));
+        if !st.form_focused() && i == st.selected { style = style.add_modifier(Modifier::UNDERLINED); }
+        let mut spans = Vec::new();
+        if app.monitor.is_some() { spans.push(health_dot(app, &e.id).unwrap_or_else(|| Span::raw("  "))); }
+        spans.push(Span::styled(label, style));
+        if let Some(badge) = app.latency.badge(&e.id) { spans.push(Span::styled(format!("  {}", badge), app.theme.hint())); }
+        items.push(ListItem::new(Line::from(spans)));
     }
-    // Highlight left pane when it has focus (focus_right == false)
-    let left_border = if let Some(st) = &app.providers { if !st.focus_right { app.theme.selected } else { app.theme.frame } } else { app.theme.frame };
+    let mut add_style = if st.is_add_row() { app.theme.selected_row() } else { app.theme.accent() };
+    if !st.form_focused() && st.is_add_row() { add_style = add_style.add_modifier(Modifier::UNDERLINED); }
+    items.push(ListItem::new(Line::from(Span::styled("+ Add provider (A: from a preset)", add_style))));
+    if let Some(status) = &st.test_status {
+        let status = if st.test_in_progress { format!("{} {}", spinner(app), status) } else { status.clone() };
+        items.push(ListItem::new(Line::from(Span::styled(format!("Status: {}", status), app.theme.hint()))));
+    }
+    // Highlight the pane that has focus
+    let left_border = app.theme.border_for(!st.form_focused());
     let list = List::new(items)
-        .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(left_border)).title("Configure Providers"))
-        .highlight_style(Style::default().fg(app.theme.selected));
+        .block(Block::default().borders(Borders::ALL).border_style(left_border).title(monitor_title(app, "Configure Providers")))
+        .highlight_style(app.theme.highlight());
     f.render_widget(list, cols[0]);
+    // Entry rows and the add row are clickable; the list does not scroll
+    let inner = cols[0].inner(&Margin { horizontal: 1, vertical: 1 });
+    let mut hits = app.hits.borrow_mut();
+    for i in 0..st.len_with_add().min(inner.height as usize) {
+        hits.add(Rect { y: inner.y + i as u16, height: 1, ..inner }, HitTarget::ListRow(i));
+    }
+    drop(hits);
 
     // Right form panel
     let right = cols[1];
     let mut title = "Provider Details".to_string();
-    if let Some(st) = &app.providers {
-        if st.selected < st.entries.len() {
-            let entry = &st.entries[st.selected];
-            title = format!("Provider Details — {}", entry.ptype);
-            let fields: Vec<FormField> = if let Some(form) = &st.form { form.fields.clone() } else { Vec::new() };
-            if fields.is_empty() {
-                let p = Paragraph::new("Tab to open form").style(Style::default().bg(app.theme.bg).fg(app.theme.secondary)).block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(app.theme.frame)).title(title));
+    if st.selected < st.entries.len() {
+        let entry = &st.entries[st.selected];
+        title = format!("Provider Details — {}", entry.ptype);
+        match &st.form {
+            Some(form) => {
+                // Save stays disabled until changed values pass a test
+                let disabled: &[usize] = if form.can_save() { &[] } else { &[BUTTON_SAVE] };
+                draw_form(f, right, app, form, st.form_focused(), &title, disabled);
+            }
+            None => {
+                let p = Paragraph::new("Tab to open form").style(app.theme.base().patch(app.theme.hint())).block(Block::default().borders(Borders::ALL).border_style(app.theme.border()).title(title));
                 f.render_widget(p, right);
-            } else {
-                // layout with type row, fields (scroll), message, buttons
-                let total_height = right.height as usize;
-                let reserve = 3 + 1 + 3;
-                let per_field = 3usize;
-                let max_fields_visible = if total_height > reserve { (total_height - reserve) / per_field } else { 0 };
-                let mut start = 0usize; let mut end = fields.len();
-                if let Some(form) = &st.form { if fields.len() > max_fields_visible {
-                    let sel = form.selected.saturating_sub(1);
-                    let mut scroll = form.scroll;
-                    if sel < scroll { scroll = sel; }
-                    if sel >= scroll + max_fields_visible { scroll = sel + 1 - max_fields_visible; }
-                    start = scroll.min(fields.len().saturating_sub(max_fields_visible));
-                    end = (start + max_fields_visible).min(fields.len());
-                } }
-                let visible = &fields[start..end];
-                let mut cons: Vec<Constraint> = Vec::new();
-                cons.push(Constraint::Length(3));
-                cons.extend(std::iter::repeat(Constraint::Length(3)).take(visible.len()));
-                cons.push(Constraint::Length(1));
-                cons.push(Constraint::Length(3));
-                let chunks = Layout::default().direction(Direction::Vertical).constraints(cons).split(right);
-                if let Some(form) = &st.form {
-                    let sel = form.selected;
-                    let style = if st.focus_right && sel == 0 { Style::default().fg(app.theme.selected).add_modifier(Modifier::BOLD) } else { Style::default().fg(app.theme.fg) };
-                    let p = Paragraph::new(format!("Type: {}  (Enter to change)", entry.ptype)).style(Style::default().bg(app.theme.bg).fg(app.theme.fg)).block(Block::default().borders(Borders::ALL).border_style(style));
-                    f.render_widget(p, chunks[0]);
-                }
-                for (i_vis, ff) in visible.iter().enumerate() {
-                    let i = start + i_vis;
-                    let mut display = if ff.schema.ftype == "secret" && !ff.buffer.is_empty() { "••••••".to_string() } else { ff.buffer.clone() };
-                    let is_selected = st.focus_right && st.form.as_ref().map(|f| f.selected).unwrap_or(0) == i + 1;
-                    let is_editing = st.form.as_ref().map(|f| f.editing).unwrap_or(false);
-                    if is_selected && is_editing {
-                        let pos = ff.cursor.min(ff.buffer.chars().count());
-                        if ff.schema.ftype == "secret" { display = ff.buffer.chars().map(|_| '•').collect(); }
-                        let (byte_idx, _) = display.char_indices().nth(pos).unwrap_or((display.len(), ' '));
-                        display.insert(byte_idx, '▌');
-                    }
-                    let mut bstyle = Style::default().fg(app.theme.frame);
-                    if ff.schema.required && ff.buffer.trim().is_empty() { bstyle = Style::default().fg(ratatui::style::Color::Red); }
-                    if is_selected { bstyle = Style::default().fg(app.theme.selected).add_modifier(Modifier::BOLD); }
-                    let title_txt = if ff.schema.required { format!("* {}", ff.schema.name) } else { ff.schema.name.clone() };
-                    let block = Block::default().borders(Borders::ALL).border_style(bstyle).title(title_txt);
-                    let p = Paragraph::new(display).style(Style::default().bg(app.theme.bg).fg(app.theme.fg)).block(block).wrap(Wrap { trim: false });
-                    f.render_widget(p, chunks[1 + i_vis]);
-                }
-                if let Some(form) = &st.form {
-                    let mut msg = form.message.clone().unwrap_or_default();
-                    if fields.len() > end { msg = format!("{}  ↓ more…", msg); }
-                    if start > 0 { msg = format!("↑ more…  {}", msg); }
-                    let p = Paragraph::new(msg).style(Style::default().bg(app.theme.bg).fg(app.theme.secondary)).block(Block::default());
-                    f.render_widget(p, chunks[1 + visible.len()]);
-                    let buttons_area = chunks[1 + visible.len() + 1];
-                    let sel = form.selected;
-                    let test_idx = fields.len() + 1;
-                    let save_idx = fields.len() + 2;
-                    let cancel_idx = fields.len() + 3;
-                    // Compute save enabled: disabled if dirty and not tested ok for current values
-                    let cur_hash = crate::providers::compute_form_hash(&form.fields);
-                    let dirty = cur_hash != form.initial_hash;
-                    let tested_ok = form.last_test_ok_hash.as_ref().map_or(false, |h| *h == cur_hash);
-                    let save_enabled = !(dirty && !tested_ok);
-                    let test_style = if sel == test_idx { Style::default().fg(app.theme.selected).add_modifier(Modifier::BOLD) } else { Style::default().fg(app.theme.fg) };
-                    let mut save_style = if sel == save_idx { Style::default().fg(app.theme.selected).add_modifier(Modifier::BOLD) } else { Style::default().fg(app.theme.fg) };
-                    if !save_enabled { save_style = Style::default().fg(app.theme.secondary).add_modifier(Modifier::DIM); }
-                    let cancel_style = if sel == cancel_idx { Style::default().fg(app.theme.selected).add_modifier(Modifier::BOLD) } else { Style::default().fg(app.theme.fg) };
-                    let btns = vec![Line::from(vec![Span::styled("[ Test ]  ", test_style), Span::styled("[ Save ]  ", save_style), Span::styled("[ Cancel ]", cancel_style)])];
-                    let p = Paragraph::new(btns).style(Style::default().bg(app.theme.bg).fg(app.theme.fg)).block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(app.theme.frame)).title(title)).alignment(ratatui::layout::Alignment::Left);
-                    f.render_widget(p, buttons_area);
-                }
             }
-        } else {
-            let p = Paragraph::new("Add a provider to edit details.").style(Style::default().bg(app.theme.bg).fg(app.theme.fg)).block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(app.theme.frame)).title(title));
-            f.render_widget(p, right);
         }
     } else {
-        let p = Paragraph::new("Loading...").style(Style::default().bg(app.theme.bg).fg(app.theme.fg)).block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(app.theme.frame)).title(title));
+        let p = Paragraph::new("Add a provider to edit details.").style(app.theme.base()).block(Block::default().borders(Borders::ALL).border_style(app.theme.border()).title(title));
         f.render_widget(p, right);
     }
 
     // Draw an outer border around the right pane to indicate active focus
-    if let Some(st) = &app.providers {
-        let right_border = if st.focus_right { app.theme.selected } else { app.theme.frame };
-        let outer = Block::default().borders(Borders::ALL).border_style(Style::default().fg(right_border));
-        f.render_widget(outer, right);
-    }
+    let right_border = app.theme.border_for(st.form_focused());
+    let outer = Block::default().borders(Borders::ALL).border_style(right_border);
+    f.render_widget(outer, right);
 
-    // Overlay dropdown
-    if let Some(st) = &app.providers {
-        if let Some(dd) = &st.dropdown {
-            let area_pop = centered_rect(50, 60, area);
-            let mut items: Vec<ListItem> = Vec::new();
-            for (i, it) in dd.items.iter().enumerate() {
-                let style = if i == dd.selected { Style::default().fg(app.theme.selected).add_modifier(Modifier::BOLD) } else { Style::default().fg(app.theme.fg) };
-                items.push(ListItem::new(Line::from(Span::styled(it.clone(), style))));
-            }
-            let list = List::new(items)
-                .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(app.theme.frame)).title(dd.title.clone()))
-                .highlight_style(Style::default().fg(app.theme.selected));
-            f.render_widget(Clear, area_pop);
-            f.render_widget(list, area_pop);
-        }
+    if st.show_json {
+        let lines: Vec<Line> = match st.selected_preview() {
+            Some(v) => serde_json::to_string_pretty(&v).unwrap_or_default().lines().map(|l| highlight_line(Lang::Json, l, &app.theme)).collect(),
+            None => vec![Line::from(Span::styled("Select a provider", app.theme.hint()))],
+        };
+        let block = Block::default().borders(Borders::ALL).border_style(app.theme.border()).title(".chi_llm.json preview");
+        f.render_widget(Paragraph::new(lines).style(app.theme.base()).block(block), cols[2]);
     }
-}
 
-pub fn probe_provider(entry: &super::state::ProviderScratchEntry) -> Result<String> {
-    let ptype = entry.ptype.as_str();
-    if ptype == "local" { return Ok("local: no network test".to_string()); }
-    match ptype {
-        "lmstudio" => {
-            let host = entry.config.get("host").and_then(|v| v.as_str()).unwrap_or("127.0.0.1");
-            let port = entry.config.get("port").and_then(|v| v.as_u64()).unwrap_or(1234);
-            let args = ["providers", "discover-models", "--type", "lmstudio", "--host", host, "--port", &port.to_string(), "--json"];
-            let v = run_cli_json(&args, Duration::from_secs(5))?;
-            let count = v.get("models").and_then(|d| d.as_array()).map(|a| a.len()).unwrap_or(0);
-            Ok(format!("lmstudio: {} models", count))
-        }
-        "ollama" => {
-            let host = entry.config.get("host").and_then(|v| v.as_str()).unwrap_or("127.0.0.1");
-            let port = entry.config.get("port").and_then(|v| v.as_u64()).unwrap_or(11434);
-            let args = ["providers", "discover-models", "--type", "ollama", "--host", host, "--port", &port.to_string(), "--json"];
-            let v = run_cli_json(&args, Duration::from_secs(5))?;
-            let count = v.get("models").and_then(|d| d.as_array()).map(|a| a.len()).unwrap_or(0);
-            Ok(format!("ollama: {} models", count))
-        }
-        "openai" => {
-            let base = entry.config.get("base_url").and_then(|v| v.as_str()).unwrap_or("https://api.openai.com");
-            let api_key = entry.config.get("api_key").and_then(|v| v.as_str()).unwrap_or("");
-            let org = entry.config.get("org_id").and_then(|v| v.as_str()).unwrap_or("");
-            if api_key.is_empty() { return Ok("openai: missing api_key".to_string()); }
-            let mut args: Vec<&str> = vec!["providers", "discover-models", "--type", "openai", "--base-url", base, "--api-key", api_key, "--json"];
-            if !org.is_empty() { args.push("--org-id"); args.push(org); }
-            let v = run_cli_json(&args, Duration::from_secs(5))?;
-            let count = v.get("models").and_then(|d| d.as_array()).map(|a| a.len()).unwrap_or(0);
-            Ok(format!("openai: {} models", count))
-        }
-        _ => Ok(format!("{}: no test implemented", ptype)),
-    }
 }
@@ -0,0 +1,149 @@
+//! Python environments that have the `chi_llm` package: the active virtualenv
+//! or conda environment, project virtualenvs (`.venv`, `venv`), uv tool
+//! installs, conda environments and the system interpreters. Settings `e`
+//! lists them and makes the picked interpreter run every `chi-llm` call
+//! (as `python -m chi_llm`), so the TUI talks to the intended installation.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
+
+use anyhow::Result;
+
+use crate::env::timeouts;
+use crate::util::run_program_text_cancellable;
+
+/// What Settings runs once the environment is picked (`python -m chi_llm`),
+/// so an environment is only offered when that works.
+const PROBE: [&str; 3] = ["-m", "chi_llm", "--version"];
+/// Directory names checked for a project virtualenv.
+const PROJECT_VENVS: [&str; 3] = [".venv", "venv", "env"];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EnvKind {
+    /// `$VIRTUAL_ENV`.
+    Active,
+    Venv,
+    /// A virtualenv created by uv (project `.venv` or `uv tool install`).
+    Uv,
+    Conda,
+    /// `python3`/`python` from PATH.
+    System,
+}
+
+impl EnvKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            EnvKind::Active => "active",
+            EnvKind::Venv => "venv",
+            EnvKind::Uv => "uv",
+            EnvKind::Conda => "conda",
+            EnvKind::System => "system",
+        }
+    }
+}
+
+/// An interpreter that can run `python -m chi_llm`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PyEnv {
+    pub kind: EnvKind,
+    pub python: PathBuf,
+    pub python_version: String,
+    pub chi_llm_version: String,
+}
+
+impl PyEnv {
+    /// One dropdown row: `uv  /repo/.venv/bin/python  (Python 3.12.1, chi_llm 0.5.0)`.
+    pub fn label(&self) -> String {
+        format!("{:<7}{}  (Python {}, chi_llm {})", self.kind.label(), self.python.display(), self.python_version, self.chi_llm_version)
+    }
+}
+
+/// The interpreter inside an environment directory.
+fn interpreter(root: &Path) -> PathBuf {
+    if cfg!(windows) {
+        // conda puts python.exe at the root, virtualenvs under Scripts
+        let scripts = root.join("Scripts").join("python.exe");
+        if scripts.exists() { scripts } else { root.join("python.exe") }
+    } else {
+        root.join("bin").join("python")
+    }
+}
+
+/// Venv or uv, from the `uv = …` line uv writes into `pyvenv.cfg`.
+fn venv_kind(root: &Path) -> EnvKind {
+    let cfg = fs::read_to_string(root.join("pyvenv.cfg")).unwrap_or_default();
+    if cfg.lines().any(|l| l.trim_start().starts_with("uv ")) { EnvKind::Uv } else { EnvKind::Venv }
+}
+
+/// Virtualenvs in `dir` (see `PROJECT_VENVS`).
+pub fn project_envs(dir: &Path) -> Vec<(EnvKind, PathBuf)> {
+    PROJECT_VENVS.iter().map(|name| dir.join(name)).filter(|root| root.join("pyvenv.cfg").exists()).map(|root| (venv_kind(&root), interpreter(&root))).collect()
+}
+
+/// Every place an interpreter may be, most specific first; not yet probed.
+pub fn candidate_envs(cwd: &Path) -> Vec<(EnvKind, PathBuf)> {
+    let mut out = Vec::new();
+    if let Some(root) = std::env::var_os("VIRTUAL_ENV") { out.push((EnvKind::Active, interpreter(Path::new(&root)))); }
+    if let Some(root) = std::env::var_os("CONDA_PREFIX") { out.push((EnvKind::Conda, interpreter(Path::new(&root)))); }
+    out.extend(project_envs(cwd));
+    let uv_tools = std::env::var_os("UV_TOOL_DIR").map(PathBuf::from).or_else(|| dirs::data_dir().map(|d| d.join("uv").join("tools")));
+    if let Some(Ok(entries)) = uv_tools.map(fs::read_dir) {
+        let mut tools: Vec<PathBuf> = entries.flatten().map(|e| e.path()).collect();
+        tools.sort();
+        out.extend(tools.iter().map(|root| (EnvKind::Uv, interpreter(root))));
+    }
+    // conda records every environment it creates here
+    if let Some(list) = dirs::home_dir().and_then(|h| fs::read_to_string(h.join(".conda").join("environments.txt")).ok()) {
+        out.extend(list.lines().map(str::trim).filter(|l| !l.is_empty()).map(|root| (EnvKind::Conda, interpreter(Path::new(root)))));
+    }
+    out.retain(|(_, python)| python.exists());
+    out.extend(["python3", "python"].map(|p| (EnvKind::System, PathBuf::from(p))));
+    // The same interpreter found twice keeps its first (most specific) kind
+    let mut seen = HashSet::new();
+    out.retain(|(_, python)| seen.insert(fs::canonicalize(python).unwrap_or_else(|_| python.clone())));
+    out
+}
+
+/// The version in `--version` output: `Python 3.12.1`, `chi_llm 2.1.0`.
+fn version_of(out: &str) -> String {
+    out.split_whitespace().nth(1).unwrap_or("?").to_string()
+}
+
+/// The candidates from `candidate_envs` that run `python -m chi_llm --version`.
+pub fn find_python_envs(cwd: &Path, cancel: &AtomicBool) -> Result<Vec<PyEnv>> {
+    let mut found = Vec::new();
+    for (kind, python) in candidate_envs(cwd) {
+        let program = python.to_string_lossy();
+        let Ok(chi_llm) = run_program_text_cancellable(&program, &PROBE, &[], timeouts().test, cancel) else { continue };
+        let python_version = run_program_text_cancellable(&program, &["--version"], &[], timeouts().test, cancel).map_or_else(|_| "?".to_string(), |v| version_of(&v));
+        found.push(PyEnv { kind, python, python_version, chi_llm_version: version_of(&chi_llm) });
+    }
+    Ok(found)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn project_venvs_are_told_apart_by_their_pyvenv_cfg() {
+        let dir = std::env::temp_dir().join(format!("chi-tui-pyenv-{}", std::process::id()));
+        fs::create_dir_all(dir.join(".venv")).unwrap();
+        fs::write(dir.join(".venv").join("pyvenv.cfg"), "home = /usr/bin\nuv = 0.4.18\n").unwrap();
+        fs::create_dir_all(dir.join("venv")).unwrap();
+        fs::write(dir.join("venv").join("pyvenv.cfg"), "home = /usr/bin\nversion = 3.12.1\n").unwrap();
+        fs::create_dir_all(dir.join("env")).unwrap();
+        let envs = project_envs(&dir);
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(envs, [(EnvKind::Uv, interpreter(&dir.join(".venv"))), (EnvKind::Venv, interpreter(&dir.join("venv")))]);
+    }
+
+    #[test]
+    fn versions_come_from_the_version_output() {
+        assert_eq!(version_of("chi_llm 2.1.0\n"), "2.1.0");
+        assert_eq!(version_of("Python 3.12.1"), "3.12.1");
+        assert_eq!(version_of(""), "?");
+    }
+}
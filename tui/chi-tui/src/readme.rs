@@ -1,11 +1,25 @@
-use anyhow::Result;
+use std::ops::Range;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::layout::{Alignment, Constraint, Direction, Layout, Rect};
 use ratatui::prelude::Frame;
-use ratatui::style::{Modifier, Style};
+use ratatui::style::Modifier;
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, List, ListItem, Paragraph, Wrap};
 
-use crate::app::App;
+use crate::app::{App, Notification, Page};
+use crate::controller::PageController;
+use crate::focus::FocusRing;
+use crate::highlight::{highlight_line, Lang};
+use crate::keymap::KeyAction;
+use crate::modal::DropdownModal;
+use crate::mouse::{Scroll, SCROLL_LINES};
+use crate::msg::Msg;
+use crate::page_data::draw_page_status;
+use crate::settings::{persist, DocsSettings};
+use crate::util::open_url;
 
 #[derive(Clone, Debug)]
 pub struct TocEntry {
@@ -14,13 +28,48 @@ pub struct TocEntry {
     pub line: usize,
 }
 
+/// A link outside code blocks: `[text](url)`, `<url>` or a bare
+/// `http(s)://` URL. `span` is its byte range in line `line`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Link {
+    pub text: String,
+    pub url: String,
+    pub line: usize,
+    pub span: Range<usize>,
+}
+
+impl Link {
+    pub fn label(&self) -> String {
+        if self.text == self.url { self.url.clone() } else { format!("{} — {}", self.text, self.url) }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ReadmePane {
+    Files,
+    Toc,
+    Content,
+}
+
+/// How deep `docs/` is scanned for markdown files.
+const DOCS_DEPTH: usize = 3;
+
 #[derive(Clone, Debug)]
 pub struct ReadmeState {
+    /// File the lines came from (empty for text not read from a file).
+    pub path: String,
     pub lines: Vec<String>,
+    /// Language of each line inside a fenced code block (`None` elsewhere).
+    pub code: Vec<Option<Lang>>,
     pub toc: Vec<TocEntry>,
+    pub links: Vec<Link>,
     pub show_toc: bool,
+    /// Documents the Docs page can switch between (`find_docs`).
+    pub files: Vec<String>,
+    pub show_files: bool,
+    pub file_selected: usize,
     pub scroll: usize,
-    pub focus_toc: bool,
+    pub focus: FocusRing<ReadmePane>,
     pub toc_selected: usize,
 }
 
@@ -31,14 +80,124 @@ impl ReadmeState {
     pub fn scroll_down(&mut self, n: usize) {
         self.scroll = self.scroll.saturating_add(n);
     }
+    /// True when the visible TOC pane has focus.
+    pub fn toc_focused(&self) -> bool {
+        self.show_toc && self.focus.is(ReadmePane::Toc)
+    }
+    /// True when the visible file list has focus.
+    pub fn files_focused(&self) -> bool {
+        self.show_files && self.focus.is(ReadmePane::Files)
+    }
+    fn update_panes(&mut self) {
+        let panes: Vec<ReadmePane> = [(self.show_files, ReadmePane::Files), (self.show_toc, ReadmePane::Toc), (true, ReadmePane::Content)]
+            .into_iter().filter(|(shown, _)| *shown).map(|(_, pane)| pane).collect();
+        self.focus.set_items(&panes);
+    }
+    pub fn toggle_toc(&mut self) {
+        self.show_toc = !self.show_toc;
+        self.update_panes();
+    }
+    pub fn toggle_files(&mut self) {
+        if self.files.is_empty() { return; }
+        self.show_files = !self.show_files;
+        self.update_panes();
+    }
+
+    /// Where the reader is, as saved in `settings.toml`.
+    pub fn position(&self) -> DocsSettings {
+        DocsSettings {
+            remember: None,
+            path: (!self.path.is_empty()).then(|| self.path.clone()),
+            scroll: Some(self.scroll.min(self.lines.len().saturating_sub(1))),
+            toc_selected: Some(self.toc_selected),
+            show_toc: Some(self.show_toc),
+            show_files: Some(self.show_files),
+        }
+    }
+
+    /// Go back to a saved position. A document no longer listed is ignored,
+    /// and the scroll is clamped to the document's length.
+    pub fn restore(&mut self, saved: &DocsSettings) {
+        if let Some(path) = saved.path.as_deref().filter(|p| *p != self.path && self.files.iter().any(|f| f == p)) {
+            self.open(path);
+        }
+        if saved.show_files.is_some_and(|on| on != self.show_files) { self.toggle_files(); }
+        if saved.show_toc.is_some_and(|on| on != self.show_toc) { self.toggle_toc(); }
+        self.scroll = saved.scroll.unwrap_or(0).min(self.lines.len().saturating_sub(1));
+        self.toc_selected = saved.toc_selected.unwrap_or(0).min(self.toc.len().saturating_sub(1));
+    }
+
+    /// Show the document at `path` instead, keeping the file list and panes.
+    pub fn open(&mut self, path: &str) {
+        let doc = load_doc(path);
+        let file_selected = self.files.iter().position(|f| f == path).unwrap_or(self.file_selected);
+        *self = ReadmeState {
+            files: std::mem::take(&mut self.files),
+            show_files: self.show_files,
+            file_selected,
+            show_toc: self.show_toc,
+            focus: self.focus.clone(),
+            ..doc
+        };
+    }
 }
 
+/// Markdown files for the Docs page: `README.md`, then those under `docs/`
+/// (`DOCS_DEPTH` levels), sorted. Paths are relative to `root`.
+pub fn find_docs(root: &Path) -> Vec<String> {
+    let is_md = |p: &Path| p.extension().is_some_and(|e| e.eq_ignore_ascii_case("md"));
+    let mut found = Vec::new();
+    let mut dirs = vec![(root.join("docs"), 0)];
+    while let Some((dir, depth)) = dirs.pop() {
+        let Ok(read) = fs::read_dir(&dir) else { continue };
+        for p in read.flatten().map(|e| e.path()) {
+            if p.is_dir() && depth + 1 < DOCS_DEPTH { dirs.push((p, depth + 1)); }
+            else if p.is_file() && is_md(&p) { found.push(p); }
+        }
+    }
+    found.sort();
+    let readme = root.join("README.md");
+    let files = readme.is_file().then_some(readme).into_iter().chain(found);
+    files.map(|p| p.strip_prefix(root).map(PathBuf::from).unwrap_or(p).display().to_string()).collect()
+}
+
+/// One markdown file, or a placeholder saying it could not be read.
+pub fn load_doc(path: &str) -> ReadmeState {
+    let content = fs::read_to_string(path)
+        .unwrap_or_else(|_| format!("# {} not found\n\nPlace a README.md or a docs/ directory in the current directory.", path));
+    ReadmeState { path: path.to_string(), ..parse_markdown(&content) }
+}
+
+/// The Docs page: the first of `find_docs` (normally README.md), with the
+/// file list shown when there is more than one.
 pub fn load_readme() -> ReadmeState {
-    let content = std::fs::read_to_string("README.md")
-        .unwrap_or_else(|_| "# README not found\n\nPlace a README.md in the current directory.".to_string());
+    let files = find_docs(Path::new("."));
+    let mut rm = load_doc(files.first().map_or("README.md", String::as_str));
+    rm.show_files = files.len() > 1;
+    rm.files = files;
+    rm.update_panes();
+    rm.focus.focus(ReadmePane::Content);
+    rm
+}
+
+/// Split markdown into display lines and a `#`/`##`/`###` table of contents.
+/// Lines in fenced code blocks are tagged with the fence's language instead.
+pub fn parse_markdown(content: &str) -> ReadmeState {
     let mut lines = Vec::new();
+    let mut code = Vec::new();
     let mut toc = Vec::new();
+    let mut links = Vec::new();
+    let mut fence: Option<Lang> = None;
     for (idx, raw) in content.lines().enumerate() {
+        lines.push(raw.to_string());
+        if let Some(info) = raw.trim_start().strip_prefix("```") {
+            fence = if fence.is_some() { None } else { Some(Lang::from_fence(info)) };
+            code.push(None);
+            continue;
+        }
+        code.push(fence);
+        if fence.is_some() { continue; }
+        links.extend(find_links(raw, idx));
         let mut level = 0u8;
         let mut title = raw.to_string();
         if let Some(stripped) = raw.strip_prefix("### ") {
@@ -58,97 +217,393 @@ pub fn load_readme() -> ReadmeState {
                 line: idx,
             });
         }
-        lines.push(raw.to_string());
     }
     ReadmeState {
         lines,
+        code,
         toc,
+        links,
+        path: String::new(),
+        files: Vec::new(),
+        show_files: false,
+        file_selected: 0,
         show_toc: false,
         scroll: 0,
-        focus_toc: false,
+        focus: FocusRing::new(&[ReadmePane::Content]),
         toc_selected: 0,
     }
 }
 
+fn is_web_url(s: &str) -> bool {
+    s.starts_with("http://") || s.starts_with("https://")
+}
+
+/// The links in one markdown line, in order.
+pub fn find_links(raw: &str, line: usize) -> Vec<Link> {
+    let mut links = Vec::new();
+    let mut push = |text: &str, url: &str, span: Range<usize>| {
+        links.push(Link { text: text.to_string(), url: url.to_string(), line, span });
+    };
+    let mut i = 0;
+    while i < raw.len() {
+        let rest = &raw[i..];
+        if let Some(after) = rest.strip_prefix('[') {
+            // `[text](url "title")`
+            if let Some((text, tail)) = after.split_once("](") {
+                if let Some(end) = tail.find(')') {
+                    let url = tail[..end].split_whitespace().next().unwrap_or("");
+                    let len = 1 + text.len() + 2 + end + 1;
+                    if !url.is_empty() && !text.contains('[') {
+                        push(text, url, i..i + len);
+                        i += len;
+                        continue;
+                    }
+                }
+            }
+        } else if let Some(after) = rest.strip_prefix('<') {
+            if let Some(url) = after.split_once('>').map(|(url, _)| url).filter(|u| is_web_url(u)) {
+                push(url, url, i..i + url.len() + 2);
+                i += url.len() + 2;
+                continue;
+            }
+        } else if is_web_url(rest) {
+            let end = rest.find(|c: char| c.is_whitespace() || matches!(c, ')' | '>' | '"' | '`' | '\'')).unwrap_or(rest.len());
+            let url = rest[..end].trim_end_matches(['.', ',', ';', ':']);
+            push(url, url, i..i + url.len());
+            i += url.len();
+            continue;
+        }
+        i += rest.chars().next().map_or(1, char::len_utf8);
+    }
+    links
+}
+
+/// GitHub's anchor for a heading: lowercase, punctuation dropped, spaces as `-`.
+pub fn heading_anchor(title: &str) -> String {
+    title.trim().to_lowercase().chars()
+        .filter(|c| c.is_alphanumeric() || matches!(c, ' ' | '-' | '_'))
+        .map(|c| if c == ' ' { '-' } else { c })
+        .collect()
+}
+
+/// Follow `url` from a markdown page: `#anchor` scrolls `rm` to the heading,
+/// a web URL or an existing relative file opens in the system's browser.
+pub fn follow_link(rm: &mut ReadmeState, url: &str) -> Result<String, String> {
+    if let Some(anchor) = url.strip_prefix('#') {
+        let entry = rm.toc.iter().position(|e| heading_anchor(&e.title) == anchor)
+            .ok_or_else(|| format!("no heading #{}", anchor))?;
+        rm.scroll = rm.toc[entry].line;
+        rm.toc_selected = entry;
+        return Ok(format!("Jumped to {}", rm.toc[entry].title));
+    }
+    if !is_web_url(url) {
+        // Relative to the document the link is in
+        let file = url.split('#').next().unwrap_or(url);
+        let file = Path::new(&rm.path).parent().map_or_else(|| PathBuf::from(file), |dir| dir.join(file));
+        let file = file.display().to_string();
+        if !Path::new(&file).exists() { return Err(format!("{} not found", file)); }
+        if !rm.files.is_empty() && file.to_lowercase().ends_with(".md") {
+            rm.open(&file);
+            return Ok(format!("Showing {}", file));
+        }
+        open_url(&file).map_err(|e| e.to_string())?;
+        return Ok(format!("Opened {}", file));
+    }
+    open_url(url).map_err(|e| e.to_string())?;
+    Ok(format!("Opened {}", url))
+}
+
+/// `l` on a markdown page: pick one of its links to follow. `state` finds the
+/// page's markdown again when the pick is made.
+pub fn open_link_list(app: &mut App, page: Page, state: fn(&mut App) -> Option<&mut ReadmeState>) {
+    let Some(links) = state(app).map(|rm| rm.links.clone()) else { return };
+    if links.is_empty() {
+        let _ = app.notify_tx.send(Notification::new("This page has no links", false, page));
+        return;
+    }
+    let labels: Vec<String> = links.iter().map(Link::label).collect();
+    let title = format!("Links ({})", links.len());
+    app.modals.push(Box::new(DropdownModal::new(title, labels.clone(), "", move |app, label| {
+        let Some(link) = labels.iter().position(|l| *l == label).map(|i| &links[i]) else { return };
+        let Some(rm) = state(app) else { return };
+        let (msg, ok) = match follow_link(rm, &link.url) {
+            Ok(msg) => (msg, true),
+            Err(e) => (format!("Cannot open {}: {}", link.url, e), false),
+        };
+        let _ = app.notify_tx.send(Notification::new(msg, ok, page));
+    })));
+}
+
+/// `raw` with its links underlined.
+fn link_line<'a>(raw: &'a str, links: &[&Link], app: &App) -> Line<'a> {
+    let mut spans = Vec::new();
+    let mut at = 0;
+    for link in links {
+        spans.push(Span::raw(&raw[at..link.span.start]));
+        spans.push(Span::styled(&raw[link.span.clone()], app.theme.accent().add_modifier(Modifier::UNDERLINED)));
+        at = link.span.end;
+    }
+    spans.push(Span::raw(&raw[at..]));
+    Line::from(spans)
+}
+
+/// `load_readme` at the position saved in `settings.toml`.
+fn load_docs_at(saved: &DocsSettings) -> ReadmeState {
+    let mut rm = load_readme();
+    rm.restore(saved);
+    rm
+}
+
+/// Save the Docs page's position to `settings.toml` when it changed and
+/// `[docs] remember` is on; called while another page is active and on quit.
+pub fn remember_docs(app: &mut App) {
+    if !app.settings.docs.remembers() { return; }
+    let Some(mut pos) = app.readme.ready().map(ReadmeState::position) else { return };
+    pos.remember = Some(true);
+    if pos == app.settings.docs { return; }
+    app.settings.docs = pos;
+    persist(app);
+}
+
 pub fn draw_readme(f: &mut Frame, area: Rect, app: &App) {
-    // Ensure loaded
-    let mut rm = app.readme.clone().unwrap_or_else(load_readme);
+    let Some(rm) = app.readme.ready() else {
+        draw_page_status(f, area, app, "Docs", &app.readme);
+        return;
+    };
+    let title = if rm.path.is_empty() { "Docs".to_string() } else { format!("Docs: {}", rm.path) };
+    draw_markdown(f, area, app, rm, &title);
+}
+
+/// Render parsed markdown (`parse_markdown`) with its optional TOC pane.
+pub fn draw_markdown(f: &mut Frame, area: Rect, app: &App, rm: &ReadmeState, title: &str) {
     let show_toc = rm.show_toc;
-    let chunks = if show_toc {
-        Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([Constraint::Percentage(25), Constraint::Percentage(75)])
-            .split(area)
-    } else {
-        Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([Constraint::Percentage(100)])
-            .split(area)
+    let show_files = rm.show_files && !rm.files.is_empty();
+    let side = [show_files, show_toc].iter().filter(|s| **s).count();
+    let widths: &[u16] = match side {
+        0 => &[100],
+        1 => &[25, 75],
+        _ => &[20, 20, 60],
     };
+    let constraints: Vec<Constraint> = widths.iter().map(|w| Constraint::Percentage(*w)).collect();
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(constraints)
+        .split(area);
 
+    let mut next = 0;
+    if show_files {
+        let items = rm.files.iter().map(|f| (0, if *f == rm.path { format!("• {}", f) } else { format!("  {}", f) }));
+        draw_side_list(f, chunks[next], app, "Files", items, rm.file_selected, rm.files_focused());
+        next += 1;
+    }
     if show_toc {
-        let mut toc_items: Vec<ListItem> = Vec::new();
-        for (i, e) in rm.toc.iter().enumerate() {
-            let indent = match e.level {
-                1 => "",
-                2 => "  ",
-                _ => "    ",
-            };
-            let style = if rm.focus_toc && i == rm.toc_selected {
-                Style::default().fg(app.theme.selected).add_modifier(Modifier::BOLD)
-            } else { Style::default().fg(app.theme.fg) };
-            toc_items.push(ListItem::new(Line::from(Span::styled(format!("{}- {}", indent, e.title), style))));
-        }
-        let left_border = if rm.focus_toc { app.theme.selected } else { app.theme.frame };
-        let list = List::new(toc_items).block(
-            Block::default()
-                .borders(Borders::ALL)
-                .border_style(Style::default().fg(left_border))
-                .title("TOC"),
-        );
-        f.render_widget(list, chunks[0]);
+        let items = rm.toc.iter().map(|e| (e.level.saturating_sub(1).min(2) as usize, format!("- {}", e.title)));
+        draw_side_list(f, chunks[next], app, "TOC", items, rm.toc_selected, rm.toc_focused());
+        next += 1;
     }
 
     // Render content with simple styling for headings
     let mut vlines: Vec<Line> = Vec::new();
     let start = rm.scroll.min(rm.lines.len());
     let max_rows = area.height.saturating_sub(2) as usize; // rough, accounting for borders
-    for raw in rm.lines.iter().skip(start).take(max_rows) {
-        if let Some(s) = raw.strip_prefix("# ") {
+    for (idx, (raw, code)) in rm.lines.iter().zip(&rm.code).enumerate().skip(start).take(max_rows) {
+        let links: Vec<&Link> = rm.links.iter().filter(|l| l.line == idx).collect();
+        if let Some(lang) = code {
+            vlines.push(highlight_line(*lang, raw, &app.theme));
+        } else if raw.trim_start().starts_with("```") {
+            vlines.push(Line::from(Span::styled(raw.clone(), app.theme.disabled())));
+        } else if let Some(s) = raw.strip_prefix("# ") {
             vlines.push(Line::from(Span::styled(
                 s.to_string(),
-                Style::default()
-                    .fg(app.theme.primary)
-                    .add_modifier(Modifier::BOLD),
+                app.theme.title(),
             )))
         } else if let Some(s) = raw.strip_prefix("## ") {
             vlines.push(Line::from(Span::styled(
                 s.to_string(),
-                Style::default()
-                    .fg(app.theme.accent)
-                    .add_modifier(Modifier::BOLD),
+                app.theme.subtitle(),
             )))
         } else if let Some(s) = raw.strip_prefix("### ") {
             vlines.push(Line::from(Span::styled(
                 s.to_string(),
-                Style::default().fg(app.theme.secondary),
+                app.theme.hint(),
             )))
+        } else if !links.is_empty() {
+            vlines.push(link_line(raw, &links, app));
         } else {
             vlines.push(Line::from(raw.as_str()));
         }
     }
-    let right_border = if show_toc && !rm.focus_toc { app.theme.selected } else { app.theme.frame };
+    let right_border = app.theme.border_for(side > 0 && rm.focus.is(ReadmePane::Content));
     let p = Paragraph::new(vlines)
-        .style(Style::default().bg(app.theme.bg).fg(app.theme.fg))
+        .style(app.theme.base())
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .border_style(Style::default().fg(right_border))
-                .title("README"),
+                .border_style(right_border)
+                .title(title.to_string()),
         )
         .alignment(Alignment::Left)
-        .wrap(Wrap { trim: true });
-    f.render_widget(p, chunks[if show_toc { 1 } else { 0 }]);
+        // No trimming: code blocks keep their indentation
+        .wrap(Wrap { trim: false });
+    f.render_widget(p, chunks[next]);
+}
+
+/// A side pane of a markdown page (TOC, file list): `(indent level, text)`
+/// items, the selected one highlighted while the pane has focus.
+fn draw_side_list(f: &mut Frame, area: Rect, app: &App, title: &str, items: impl Iterator<Item = (usize, String)>, selected: usize, focused: bool) {
+    let items: Vec<ListItem> = items.enumerate().map(|(i, (indent, text))| {
+        let style = if focused && i == selected { app.theme.selected_row() } else { app.theme.text() };
+        ListItem::new(Line::from(Span::styled(format!("{}{}", "  ".repeat(indent), text), style)))
+    }).collect();
+    let list = List::new(items).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(app.theme.border_for(focused))
+            .title(title.to_string()),
+    );
+    f.render_widget(list, area);
+}
 
-    // Note: caller updates app.readme via key handler (holds &mut App there)
+pub struct ReadmePage;
+
+impl PageController for ReadmePage {
+    fn handle_key(&self, app: &mut App, key: KeyEvent) -> Option<Msg> {
+        if matches!(key.code, KeyCode::Char('r') | KeyCode::Char('R')) && app.readme.is_error() {
+            let saved = app.settings.docs.saved();
+            app.readme.load(&app.jobs, "Loading docs", move |_| Ok(load_docs_at(&saved)));
+            return Some(Msg::Handled);
+        }
+        if matches!(key.code, KeyCode::Char('l') | KeyCode::Char('L')) && app.readme.ready().is_some() {
+            open_link_list(app, Page::Readme, |app| app.readme.ready_mut());
+            return Some(Msg::Handled);
+        }
+        let rm = app.readme.ready_mut()?;
+        handle_markdown_key(rm, key).then_some(Msg::Handled)
+    }
+
+    fn handle_scroll(&self, app: &mut App, dir: Scroll) -> bool {
+        app.readme.ready_mut().is_some_and(|rm| scroll_markdown(rm, dir))
+    }
+
+    fn tick(&self, app: &mut App) {
+        let saved = app.settings.docs.saved();
+        app.readme.ensure(&app.jobs, "Loading docs", move |_| Ok(load_docs_at(&saved)));
+    }
+
+    fn draw(&self, f: &mut Frame, area: Rect, app: &App) {
+        draw_readme(f, area, app);
+    }
+
+    fn actions(&self) -> &'static [KeyAction] {
+        const ACTIONS: &[KeyAction] = &[
+            KeyAction::new("r", "retry").when(|app| app.readme.is_error()),
+            KeyAction::new("Up/Down", "scroll"),
+            KeyAction::new("PgUp/PgDn", "page"),
+            KeyAction::new("f", "files").when(|app| app.readme.ready().is_some_and(|rm| !rm.files.is_empty())),
+            KeyAction::new("h", "TOC"),
+            KeyAction::new("l", "links").when(|app| app.readme.ready().is_some_and(|rm| !rm.links.is_empty())),
+            KeyAction::new("Tab", "switch pane").when(|app| app.readme.ready().is_some_and(|rm| rm.show_toc || rm.show_files)),
+            KeyAction::new("Enter", "open").when(|app| app.readme.ready().is_some_and(|rm| rm.files_focused())),
+            KeyAction::new("Enter", "jump").when(|app| app.readme.ready().is_some_and(|rm| rm.toc_focused())),
+            KeyAction::new("Esc", "back"),
+        ];
+        ACTIONS
+    }
+}
+
+/// Mouse wheel on a markdown page: scrolls the content, whichever pane has focus.
+pub fn scroll_markdown(rm: &mut ReadmeState, dir: Scroll) -> bool {
+    match dir {
+        Scroll::Up => rm.scroll_up(SCROLL_LINES),
+        Scroll::Down => rm.scroll_down(SCROLL_LINES),
+    }
+    true
+}
+
+/// Scrolling and TOC keys shared by markdown pages; true when the key was used.
+pub fn handle_markdown_key(rm: &mut ReadmeState, key: KeyEvent) -> bool {
+    // When TOC visible, allow Tab to switch focus and Up/Down to navigate TOC
+    match key.code {
+        KeyCode::Char('h') | KeyCode::Char('H') => rm.toggle_toc(),
+        KeyCode::Char('f') | KeyCode::Char('F') if !rm.files.is_empty() => rm.toggle_files(),
+        KeyCode::Tab | KeyCode::BackTab => return rm.focus.handle_key(key),
+        KeyCode::Up => {
+            if rm.files_focused() {
+                rm.file_selected = rm.file_selected.saturating_sub(1);
+            } else if rm.toc_focused() {
+                if rm.toc_selected > 0 { rm.toc_selected -= 1; }
+            } else {
+                rm.scroll_up(1);
+            }
+        }
+        KeyCode::Down => {
+            if rm.files_focused() {
+                if rm.file_selected + 1 < rm.files.len() { rm.file_selected += 1; }
+            } else if rm.toc_focused() {
+                if rm.toc_selected + 1 < rm.toc.len() { rm.toc_selected += 1; }
+            } else {
+                rm.scroll_down(1);
+            }
+        }
+        KeyCode::PageUp => rm.scroll_up(8),
+        KeyCode::PageDown => rm.scroll_down(8),
+        KeyCode::Enter if rm.files_focused() => {
+            if let Some(path) = rm.files.get(rm.file_selected).cloned() {
+                rm.open(&path);
+                rm.focus.focus(ReadmePane::Content);
+            }
+        }
+        KeyCode::Enter if rm.toc_focused() => {
+            if let Some(entry) = rm.toc.get(rm.toc_selected) {
+                rm.scroll = entry.line;
+                rm.focus.focus(ReadmePane::Content); // jump to content focus
+            }
+        }
+        _ => return false,
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn links_are_found_outside_code_blocks_and_anchors_jump() {
+        let line = "See [the guide](docs/guide.md \"Guide\"), <https://a.example/x> or https://b.example/y.";
+        let links = find_links(line, 0);
+        let found: Vec<(&str, &str)> = links.iter().map(|l| (l.text.as_str(), l.url.as_str())).collect();
+        assert_eq!(found, [("the guide", "docs/guide.md"), ("https://a.example/x", "https://a.example/x"), ("https://b.example/y", "https://b.example/y")]);
+        assert_eq!(&line[links[1].span.clone()], "<https://a.example/x>");
+        assert_eq!(links[0].label(), "the guide — docs/guide.md");
+
+        let mut rm = parse_markdown("# Top\n[setup](#quick-setup)\n```\nhttps://not.a.link\n```\n## Quick setup!\ntext");
+        assert_eq!(rm.links.len(), 1);
+        assert_eq!(follow_link(&mut rm, "#quick-setup"), Ok("Jumped to Quick setup!".to_string()));
+        assert_eq!((rm.scroll, rm.toc_selected), (5, 1));
+        assert!(follow_link(&mut rm, "#nope").is_err());
+        assert!(follow_link(&mut rm, "missing/file.md").is_err());
+    }
+
+    #[test]
+    fn docs_lists_readme_then_docs_and_relative_links_open_in_place() {
+        let root = std::env::temp_dir().join(format!("chi-tui-docs-{}", std::process::id()));
+        fs::create_dir_all(root.join("docs/guides")).unwrap();
+        fs::write(root.join("README.md"), "# Readme").unwrap();
+        fs::write(root.join("docs/CLI.md"), "# CLI\n[config](guides/config.md)").unwrap();
+        fs::write(root.join("docs/guides/config.md"), "# Config").unwrap();
+        fs::write(root.join("docs/notes.txt"), "x").unwrap();
+        assert_eq!(find_docs(&root), ["README.md", "docs/CLI.md", "docs/guides/config.md"]);
+
+        let cli = root.join("docs/CLI.md").display().to_string();
+        let mut rm = load_doc(&cli);
+        rm.files = vec![cli.clone(), root.join("docs/guides/config.md").display().to_string()];
+        rm.toggle_files();
+        assert!(rm.focus.is(ReadmePane::Content));
+        rm.focus.next();
+        assert!(rm.files_focused());
+        assert!(follow_link(&mut rm, "guides/config.md").unwrap().starts_with("Showing"));
+        assert_eq!((rm.lines[0].as_str(), rm.file_selected, rm.show_files), ("# Config", 1, true));
+        fs::remove_dir_all(&root).unwrap();
+    }
 }
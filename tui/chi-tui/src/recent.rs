@@ -0,0 +1,61 @@
+//! Recently chosen dropdown values, most recent first, keyed by what the dropdown
+//! picks (`provider type`, or a field name such as `model` or `host`). Dropdowns
+//! opened with `DropdownModal::remember` list them in a "Recent" group at the top
+//! and record each choice. Stored in `recent.json` in the state dir.
+
+use std::collections::BTreeMap;
+use std::fs;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::paths::{file_in, paths};
+
+/// Values kept per dropdown.
+pub const MAX_RECENT: usize = 5;
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct RecentPicks(BTreeMap<String, Vec<String>>);
+
+impl RecentPicks {
+    /// Read `recent.json`; a missing or unreadable file gives no history.
+    pub fn load() -> Self {
+        fs::read_to_string(paths().state_dir.join("recent.json"))
+            .ok()
+            .and_then(|t| serde_json::from_str(&t).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = file_in(&paths().state_dir, "recent.json")?;
+        fs::write(path, serde_json::to_vec_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Recent values for `key`, most recent first.
+    pub fn get(&self, key: &str) -> &[String] {
+        self.0.get(key).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// Move `value` to the front of `key`'s list, dropping the oldest beyond `MAX_RECENT`.
+    pub fn record(&mut self, key: &str, value: &str) {
+        let list = self.0.entry(key.to_string()).or_default();
+        list.retain(|v| v != value);
+        list.insert(0, value.to_string());
+        list.truncate(MAX_RECENT);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn picks_are_deduplicated_and_capped() {
+        let mut recent = RecentPicks::default();
+        for v in ["a", "b", "c", "d", "e", "f", "b"] { recent.record("model", v); }
+        assert_eq!(recent.get("model"), ["b", "f", "e", "d", "c"]);
+        assert!(recent.get("host").is_empty());
+    }
+}
@@ -0,0 +1,204 @@
+//! Corrupt `chi.tmp.json` or `settings.toml`: instead of falling back to an
+//! empty file that the next save would write over, startup shows a dialog to
+//! edit the file, restore its backup or start fresh. The corrupt file is kept
+//! as `<name>.broken` in the last two cases.
+//!
+//! The backup is `<name>.bak` next to the file: the previous version, copied
+//! before each save as long as it still parses.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+use serde_json::Value;
+
+use crate::app::{App, Notification};
+use crate::modal::{ActionModal, TextEditorModal};
+use crate::page_data::PageData;
+use crate::paths::{paths, SCRATCH_FILE};
+use crate::settings::{apply_settings, load_settings, UserSettings};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FileKind {
+    /// `chi.tmp.json` in the project directory.
+    Scratch,
+    /// `settings.toml` in the config directory.
+    Settings,
+}
+
+impl FileKind {
+    pub fn path(self) -> PathBuf {
+        match self {
+            FileKind::Scratch => PathBuf::from(SCRATCH_FILE),
+            FileKind::Settings => paths().settings_file(),
+        }
+    }
+
+    /// Why `text` is not a usable file of this kind.
+    pub fn check(self, text: &str) -> Result<(), String> {
+        match self {
+            FileKind::Scratch => serde_json::from_str::<Value>(text).map(drop).map_err(|e| e.to_string()),
+            FileKind::Settings => toml::from_str::<UserSettings>(text).map(drop).map_err(|e| e.to_string()),
+        }
+    }
+}
+
+pub fn backup_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".bak");
+    PathBuf::from(name)
+}
+
+/// `<path>.broken`, or `.broken.2`, `.broken.3`, ... when taken.
+pub fn broken_path(path: &Path) -> PathBuf {
+    let mut n = 1;
+    loop {
+        let mut name = path.as_os_str().to_owned();
+        name.push(if n == 1 { ".broken".to_string() } else { format!(".broken.{}", n) });
+        let candidate = PathBuf::from(name);
+        if !candidate.exists() { return candidate; }
+        n += 1;
+    }
+}
+
+/// Parse error of the file at `path`; `None` when it is fine or missing.
+pub fn check_file(kind: FileKind, path: &Path) -> Option<String> {
+    let text = fs::read_to_string(path).ok()?;
+    kind.check(&text).err().map(|e| format!("{}: {}", path.display(), e))
+}
+
+/// The scratch JSON at `path`: `{}` when missing, an error when it does not
+/// parse (so a save never replaces a file it could not read).
+pub fn read_json_root(path: &Path) -> Result<Value> {
+    let Ok(text) = fs::read_to_string(path) else { return Ok(Value::Object(Default::default())) };
+    let root: Value = serde_json::from_str(&text)
+        .map_err(|e| anyhow!("{} is corrupt ({}); restart chi-tui to recover it", path.display(), e))?;
+    Ok(if root.is_object() { root } else { Value::Object(Default::default()) })
+}
+
+/// Write `contents` to `path`, first copying the current file to `<path>.bak`
+/// if it is still valid.
+pub fn write_keeping_backup(kind: FileKind, path: &Path, contents: &[u8]) -> Result<()> {
+    if fs::read_to_string(path).is_ok_and(|text| kind.check(&text).is_ok()) {
+        fs::copy(path, backup_path(path)).with_context(|| format!("backing up {}", path.display()))?;
+    }
+    fs::write(path, contents).with_context(|| path.display().to_string())
+}
+
+/// Move the corrupt file aside and copy its backup in. Returns where the
+/// corrupt file went.
+pub fn restore_backup(kind: FileKind, path: &Path) -> Result<PathBuf> {
+    let backup = backup_path(path);
+    let text = fs::read_to_string(&backup).with_context(|| format!("no backup at {}", backup.display()))?;
+    kind.check(&text).map_err(|e| anyhow!("{} is not valid either: {}", backup.display(), e))?;
+    let broken = start_fresh(path)?;
+    fs::write(path, text).with_context(|| path.display().to_string())?;
+    Ok(broken)
+}
+
+/// Move the corrupt file aside; the app then runs as if it never existed.
+pub fn start_fresh(path: &Path) -> Result<PathBuf> {
+    let broken = broken_path(path);
+    fs::rename(path, &broken).with_context(|| format!("moving {} to {}", path.display(), broken.display()))?;
+    Ok(broken)
+}
+
+/// Startup: offer recovery for each corrupt file. `settings_error` is what
+/// `load_settings` reported, if anything.
+pub fn check_startup(app: &mut App, settings_error: Option<String>) {
+    if let Some(e) = settings_error { offer_recovery(app, FileKind::Settings, e); }
+    if let Some(e) = check_file(FileKind::Scratch, &FileKind::Scratch.path()) { offer_recovery(app, FileKind::Scratch, e); }
+}
+
+/// Recovery dialog for `kind`, whose file failed to parse with `error`.
+pub fn offer_recovery(app: &mut App, kind: FileKind, error: String) {
+    let path = kind.path();
+    let has_backup = backup_path(&path).exists();
+    let mut msg = format!("{}\n\nNothing is written to it until this is resolved.", error);
+    if !has_backup { msg.push_str(" There is no backup to restore."); }
+    let (p1, p2, p3) = (path.clone(), path.clone(), path.clone());
+    let mut modal = ActionModal::new(format!("Corrupt {}", path.display()), msg)
+        .action('o', "open and fix it", move |app| open_editor(app, kind, &p1));
+    if has_backup {
+        modal = modal.action('r', "restore the backup (corrupt file kept as .broken)", move |app| {
+            finish(app, kind, restore_backup(kind, &p2).map(|b| format!("Restored {} from its backup; the corrupt file is {}", p2.display(), b.display())));
+        });
+    }
+    modal = modal.action('f', "start fresh (corrupt file kept as .broken)", move |app| {
+        finish(app, kind, start_fresh(&p3).map(|b| format!("Started fresh; the corrupt file is {}", b.display())));
+    });
+    app.modals.push(Box::new(modal));
+}
+
+fn open_editor(app: &mut App, kind: FileKind, path: &Path) {
+    let text = fs::read_to_string(path).unwrap_or_default();
+    let path = path.to_path_buf();
+    app.modals.push(Box::new(TextEditorModal::new(format!("Fix {}", path.display()), &text, move |app, text| {
+        kind.check(text)?;
+        fs::write(&path, text).map_err(|e| e.to_string())?;
+        finish(app, kind, Ok(format!("Fixed {}", path.display())));
+        Ok(())
+    })));
+}
+
+/// Reload what was read from the recovered file, or report why recovery failed.
+fn finish(app: &mut App, kind: FileKind, result: Result<String>) {
+    let (msg, ok) = match result {
+        Ok(msg) => (msg, true),
+        Err(e) => (format!("Recovery failed: {:#}", e), false),
+    };
+    if ok {
+        match kind {
+            FileKind::Scratch => {
+                app.providers = PageData::NotLoaded;
+                app.defaultp = None;
+                app.routing = None;
+            }
+            FileKind::Settings => {
+                if let Ok(settings) = load_settings() {
+                    app.settings = settings;
+                    app.settings_path = Some(kind.path());
+                    apply_settings(app);
+                }
+            }
+        }
+    }
+    let _ = app.notify_tx.send(Notification::new(msg, ok, app.page));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn corrupt_files_are_kept_and_the_backup_restored() {
+        let tmp = std::env::temp_dir().join(format!("chi-tui-recovery-{}", std::process::id()));
+        fs::create_dir_all(&tmp).unwrap();
+        let path = tmp.join("chi.tmp.json");
+        let kind = FileKind::Scratch;
+
+        write_keeping_backup(kind, &path, b"{\"providers\": []}").unwrap();
+        assert!(!backup_path(&path).exists(), "nothing to back up yet");
+        write_keeping_backup(kind, &path, b"{\"default_provider_id\": \"p1\"}").unwrap();
+        assert_eq!(fs::read_to_string(backup_path(&path)).unwrap(), "{\"providers\": []}");
+
+        fs::write(&path, "{\"providers\": [").unwrap();
+        assert!(check_file(kind, &path).is_some());
+        assert!(read_json_root(&path).is_err(), "a save must not replace what it cannot read");
+        // A corrupt file is never copied over the good backup
+        write_keeping_backup(kind, &path, b"{\"providers\": [").unwrap();
+        assert_eq!(fs::read_to_string(backup_path(&path)).unwrap(), "{\"providers\": []}");
+
+        let broken = restore_backup(kind, &path).unwrap();
+        assert_eq!(broken, tmp.join("chi.tmp.json.broken"));
+        assert_eq!(fs::read_to_string(&broken).unwrap(), "{\"providers\": [");
+        assert!(check_file(kind, &path).is_none());
+
+        fs::write(&path, "not json").unwrap();
+        assert_eq!(start_fresh(&path).unwrap(), tmp.join("chi.tmp.json.broken.2"));
+        assert!(!path.exists());
+        assert_eq!(read_json_root(&path).unwrap(), serde_json::json!({}));
+        assert!(FileKind::Settings.check("[ui\n").is_err());
+        fs::remove_dir_all(&tmp).unwrap();
+    }
+}
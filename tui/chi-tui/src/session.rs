@@ -0,0 +1,111 @@
+//! Where the user was when chi-tui last exited: page, welcome menu row,
+//! selected provider, Model Browser filters and selection, What's new scroll
+//! and CLI Log row. Saved to `session.json` in the state dir on quit and
+//! restored at the next start (unless `--no-resume`). Docs keeps its own
+//! position in `settings.toml` (see `readme::remember_docs`).
+//!
+//! Pages whose data loads in the background get their part once it is ready
+//! (`apply_resume`).
+
+use std::fs;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::app::{App, WELCOME_ITEMS};
+use crate::env::{page_slug, parse_page};
+use crate::paths::{file_in, paths};
+
+/// Model Browser filters and the selected model.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModelView {
+    pub search: String,
+    pub downloaded_only: bool,
+    pub tag_filter: Option<String>,
+    pub selected_id: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Session {
+    /// `CHI_TUI_PAGE`-style name, e.g. `configure`.
+    pub page: Option<String>,
+    pub menu_idx: usize,
+    pub provider_id: Option<String>,
+    pub models: Option<ModelView>,
+    pub whats_new_scroll: Option<usize>,
+    pub cli_log_selected: usize,
+}
+
+impl Session {
+    /// Read `session.json`; `None` when there is none or it cannot be read.
+    pub fn load() -> Option<Self> {
+        fs::read_to_string(paths().state_dir.join("session.json")).ok().and_then(|t| serde_json::from_str(&t).ok())
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = file_in(&paths().state_dir, "session.json")?;
+        fs::write(path, serde_json::to_vec_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// The session as it stands. Parts of pages never opened this time keep
+    /// the values still waiting in `app.resume`.
+    pub fn capture(app: &App) -> Self {
+        let provider_id = app.providers.ready().map(|st| st.entries.get(st.selected).map(|e| e.id.clone()));
+        let models = app.model.ready().map(|m| ModelView {
+            search: m.search.clone(),
+            downloaded_only: m.downloaded_only,
+            tag_filter: m.tag_filter.clone(),
+            selected_id: m.current_entry().map(|e| e.id.clone()),
+        });
+        Self {
+            page: Some(page_slug(app.page).to_string()),
+            menu_idx: app.menu_idx,
+            provider_id: provider_id.unwrap_or_else(|| app.resume.provider_id.clone()),
+            models: models.or_else(|| app.resume.models.clone()),
+            whats_new_scroll: app.changelog.ready().map(|rm| rm.scroll).or(app.resume.whats_new_scroll),
+            cli_log_selected: app.cli_log_selected,
+        }
+    }
+}
+
+/// Start where `session` left off; page data loaded later picks up the rest
+/// through `apply_resume`.
+pub fn resume(app: &mut App, session: Session) {
+    if let Some(page) = session.page.as_deref().and_then(parse_page) { app.page = page; }
+    // A stale or hand-edited file may name a row that no longer exists
+    app.menu_idx = session.menu_idx.min(WELCOME_ITEMS.len() - 1);
+    app.cli_log_selected = session.cli_log_selected;
+    app.resume = session;
+}
+
+/// Hand the waiting parts of a resumed session to pages whose data is ready.
+pub fn apply_resume(app: &mut App) {
+    if app.resume.provider_id.is_some() {
+        if let Some(st) = app.providers.ready_mut() {
+            let id = app.resume.provider_id.take();
+            if let Some(i) = st.entries.iter().position(|e| Some(&e.id) == id.as_ref()) { st.selected = i; }
+        }
+    }
+    if app.resume.models.is_some() {
+        if let Some(m) = app.model.ready_mut() {
+            let view = app.resume.models.take().unwrap_or_default();
+            m.search = view.search;
+            m.downloaded_only = view.downloaded_only;
+            m.tag_filter = view.tag_filter.filter(|t| m.all_tags.contains(t));
+            m.compute_filtered();
+            if let Some(i) = m.filtered.iter().position(|&i| Some(&m.entries[i].id) == view.selected_id.as_ref()) { m.selected = i; }
+        }
+    }
+    if app.resume.whats_new_scroll.is_some() {
+        if let Some(rm) = app.changelog.ready_mut() { rm.scroll = app.resume.whats_new_scroll.take().unwrap_or_default(); }
+    }
+}
+
+/// Save the session on quit; only for an app with a settings file (not tests
+/// or embedders), and a failure is not worth stopping the exit for.
+pub fn save_session(app: &App) {
+    if app.settings_path.is_none() { return; }
+    let _ = Session::capture(app).save();
+}
@@ -0,0 +1,524 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::ops::RangeInclusive;
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::layout::Rect;
+use ratatui::prelude::Frame;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph, Wrap};
+use serde::Deserialize;
+use toml_edit::{DocumentMut, Item, Table};
+
+use crate::app::{App, Notification, Page};
+use crate::backend::{backend_status, set_backend_enabled};
+use crate::bundle::{export_to_file, export_with_secrets, open_import};
+use crate::controller::PageController;
+use crate::keymap::KeyAction;
+use crate::env::{parse_secs, set_timeouts, timeouts, Timeouts};
+use crate::hooks::HookSettings;
+use crate::modal::{DropdownModal, PromptModal};
+use crate::msg::Msg;
+use crate::page_data::PageData;
+use crate::paths::paths;
+use crate::pyenv::{find_python_envs, PyEnv};
+use crate::providers::{e2e_prompt, plugins, set_e2e_prompt, HealthMonitor, Price, MONITOR_INTERVAL};
+use crate::recovery::{write_keeping_backup, FileKind};
+use crate::text;
+use crate::util::{chi_llm_command, invalidate_cli_cache, resolve_chi_llm, set_chi_llm_command};
+use crate::theme::{Theme, ThemeMode};
+
+/// Contents of `settings.toml`; missing sections fall back to defaults.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct UserSettings {
+    #[serde(default)]
+    pub hooks: HookSettings,
+    /// Model-id prefix -> USD per million tokens, overriding the built-in prices.
+    #[serde(default)]
+    pub pricing: BTreeMap<String, Price>,
+    /// Seconds without input before the UI slows down (0: never).
+    #[serde(default)]
+    pub idle_after_secs: Option<u64>,
+    /// Display choices made in the app; rewritten when they change.
+    #[serde(default)]
+    pub ui: UiSettings,
+    /// `chi-llm` timeouts in seconds, set on the Settings page.
+    #[serde(default)]
+    pub timeouts: TimeoutSettings,
+    /// Where the Docs page was left; rewritten when it is left again.
+    #[serde(default)]
+    pub docs: DocsSettings,
+    /// End-to-end test setup, set on the Settings page.
+    #[serde(default)]
+    pub test: TestSettings,
+    /// Background provider health checks, turned on and off on the Settings page.
+    #[serde(default)]
+    pub monitor: MonitorSettings,
+    /// How to start `chi-llm`, set on the Settings page.
+    #[serde(default)]
+    pub chi_llm: ChiLlmSettings,
+}
+
+/// `[ui]` in `settings.toml`.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+pub struct UiSettings {
+    /// `dark` or `light`.
+    pub theme: Option<String>,
+    pub animation: Option<bool>,
+    /// Redraw interval in milliseconds.
+    pub tick_ms: Option<u64>,
+}
+
+/// `[timeouts]` in `settings.toml`; unset ones use the built-in defaults.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+pub struct TimeoutSettings {
+    pub cli: Option<f64>,
+    pub test: Option<f64>,
+    pub e2e: Option<f64>,
+}
+
+/// `[test]` in `settings.toml`.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+pub struct TestSettings {
+    /// Prompt end-to-end tests send instead of the built-in one.
+    pub prompt: Option<String>,
+}
+
+/// `[chi_llm]` in `settings.toml`.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+pub struct ChiLlmSettings {
+    /// The `chi-llm` executable, or a Python interpreter to run `-m chi_llm`
+    /// with; `--chi-llm-bin` overrides it.
+    pub bin: Option<String>,
+    /// Keep a `chi-llm serve --stdio` session for data calls (default on).
+    pub backend: Option<bool>,
+}
+
+/// `[monitor]` in `settings.toml`.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+pub struct MonitorSettings {
+    pub enabled: Option<bool>,
+    /// Seconds between checks.
+    pub interval_secs: Option<u64>,
+}
+
+impl MonitorSettings {
+    pub fn interval(&self) -> Duration {
+        self.interval_secs.filter(|s| *s > 0).map_or(MONITOR_INTERVAL, Duration::from_secs)
+    }
+}
+
+/// `[docs]` in `settings.toml`.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+pub struct DocsSettings {
+    /// Save the position below and restore it on the next start; off unless set.
+    pub remember: Option<bool>,
+    /// Open document, as listed on the Docs page.
+    pub path: Option<String>,
+    pub scroll: Option<usize>,
+    pub toc_selected: Option<usize>,
+    pub show_toc: Option<bool>,
+    pub show_files: Option<bool>,
+}
+
+impl DocsSettings {
+    pub fn remembers(&self) -> bool {
+        self.remember == Some(true)
+    }
+
+    /// The position to open the Docs page at: the saved one when remembering, else the top.
+    pub fn saved(&self) -> DocsSettings {
+        if self.remembers() { self.clone() } else { DocsSettings::default() }
+    }
+}
+
+/// Default for `idle_after_secs`.
+pub const IDLE_AFTER_DEFAULT: Duration = Duration::from_secs(60);
+/// Default for `ui.tick_ms`.
+pub const TICK_MS_DEFAULT: u64 = 100;
+/// Accepted range for `ui.tick_ms`.
+pub const TICK_MS_RANGE: RangeInclusive<u64> = 20..=1000;
+
+impl UserSettings {
+    /// How long without input before the UI goes idle; `None` when disabled.
+    pub fn idle_after(&self) -> Option<Duration> {
+        match self.idle_after_secs {
+            None => Some(IDLE_AFTER_DEFAULT),
+            Some(0) => None,
+            Some(s) => Some(Duration::from_secs(s)),
+        }
+    }
+
+    /// Redraw interval while not idle, clamped to `TICK_MS_RANGE`.
+    pub fn tick_rate(&self) -> Duration {
+        let ms = self.ui.tick_ms.unwrap_or(TICK_MS_DEFAULT);
+        Duration::from_millis(ms.clamp(*TICK_MS_RANGE.start(), *TICK_MS_RANGE.end()))
+    }
+
+    /// Timeouts from `[timeouts]`, before `CHI_TUI_TIMEOUT_*` overrides.
+    pub fn timeouts(&self) -> Timeouts {
+        let d = Timeouts::default();
+        let secs = |v: Option<f64>, default: Duration| v.and_then(|s| parse_secs(&s.to_string())).unwrap_or(default);
+        Timeouts { cli: secs(self.timeouts.cli, d.cli), test: secs(self.timeouts.test, d.test), e2e: secs(self.timeouts.e2e, d.e2e) }
+    }
+}
+
+/// Read `settings.toml`. A missing file gives the defaults; a malformed one is an error.
+pub fn load_settings() -> Result<UserSettings, String> {
+    let path = paths().settings_file();
+    match fs::read_to_string(&path) {
+        Ok(text) => toml::from_str(&text).map_err(|e| format!("{}: {}", path.display(), e)),
+        Err(_) => Ok(UserSettings::default()),
+    }
+}
+
+/// Apply the saved theme, animation and timeouts to a fresh `App`; environment
+/// overrides are applied afterwards and win.
+pub fn apply_settings(app: &mut App) {
+    match app.settings.ui.theme.as_deref() {
+        Some("light") => app.theme = Theme::synthwave_light(),
+        Some("dark") => app.theme = Theme::synthwave_dark(),
+        _ => {}
+    }
+    if let Some(anim) = app.settings.ui.animation { app.anim = anim; }
+    set_timeouts(app.settings.timeouts());
+    set_e2e_prompt(app.settings.test.prompt.clone());
+    set_backend_enabled(app.settings.chi_llm.backend != Some(false));
+    if app.settings.monitor.enabled == Some(true) { app.monitor = Some(HealthMonitor::new(app.settings.monitor.interval())); }
+}
+
+/// Write `[ui]`, `[timeouts]`, `[docs]`, `[test]`, `[monitor]` and `[chi_llm]` into the settings file at `path`. Everything
+/// else in the file (hooks, pricing, comments) is kept as it was.
+pub fn save_ui_settings(path: &Path, settings: &UserSettings) -> Result<()> {
+    let text = fs::read_to_string(path).unwrap_or_default();
+    let mut doc: DocumentMut = text.parse().with_context(|| format!("{} is not valid TOML", path.display()))?;
+    let ui = &settings.ui;
+    let t = &settings.timeouts;
+    let d = &settings.docs;
+    let values = [
+        ("ui", "theme", ui.theme.clone().map(toml_edit::value)),
+        ("ui", "animation", ui.animation.map(toml_edit::value)),
+        ("ui", "tick_ms", ui.tick_ms.map(|v| toml_edit::value(v as i64))),
+        ("timeouts", "cli", t.cli.map(toml_edit::value)),
+        ("timeouts", "test", t.test.map(toml_edit::value)),
+        ("timeouts", "e2e", t.e2e.map(toml_edit::value)),
+        ("docs", "remember", d.remember.map(toml_edit::value)),
+        ("docs", "path", d.path.clone().map(toml_edit::value)),
+        ("docs", "scroll", d.scroll.map(|v| toml_edit::value(v as i64))),
+        ("docs", "toc_selected", d.toc_selected.map(|v| toml_edit::value(v as i64))),
+        ("docs", "show_toc", d.show_toc.map(toml_edit::value)),
+        ("docs", "show_files", d.show_files.map(toml_edit::value)),
+        ("test", "prompt", settings.test.prompt.clone().map(toml_edit::value)),
+        ("monitor", "enabled", settings.monitor.enabled.map(toml_edit::value)),
+        ("chi_llm", "bin", settings.chi_llm.bin.clone().map(toml_edit::value)),
+    ];
+    for (table, key, value) in values {
+        let Some(value) = value else { continue };
+        let entry = doc.entry(table).or_insert_with(|| Item::Table(Table::new()));
+        let Some(table) = entry.as_table_like_mut() else { bail!("{}: [{}] is not a table", path.display(), table) };
+        table.insert(key, value);
+    }
+    // A cleared test prompt goes back to the built-in one
+    if settings.test.prompt.is_none() {
+        if let Some(test) = doc.get_mut("test").and_then(Item::as_table_like_mut) { test.remove("prompt"); }
+    }
+    // Without `remember` no position is kept in the file
+    if !d.remembers() {
+        if let Some(docs) = doc.get_mut("docs").and_then(Item::as_table_like_mut) {
+            for key in ["path", "scroll", "toc_selected", "show_toc", "show_files"] { docs.remove(key); }
+        }
+    }
+    if settings.chi_llm.bin.is_none() {
+        if let Some(chi_llm) = doc.get_mut("chi_llm").and_then(Item::as_table_like_mut) { chi_llm.remove("bin"); }
+    }
+    if let Some(dir) = path.parent() { fs::create_dir_all(dir)?; }
+    write_keeping_backup(FileKind::Settings, path, doc.to_string().as_bytes())
+}
+
+/// Record the current theme and animation in `app.settings` and save them.
+pub fn save_display(app: &mut App) {
+    let theme = match app.theme.mode { ThemeMode::Dark => "dark", ThemeMode::Light => "light" };
+    app.settings.ui.theme = Some(theme.to_string());
+    app.settings.ui.animation = Some(app.anim);
+    persist(app);
+}
+
+/// Save `[ui]`/`[timeouts]`/`[docs]`/`[test]`/`[monitor]`/`[chi_llm]` when `app` has a settings file; failures become a toast.
+pub(crate) fn persist(app: &mut App) {
+    let Some(path) = app.settings_path.clone() else { return };
+    if let Err(e) = save_ui_settings(&path, &app.settings) {
+        let _ = app.notify_tx.send(Notification::new(format!("Saving settings failed: {:#}", e), false, Page::Settings));
+    }
+}
+
+fn prompt_tick_rate(app: &mut App) {
+    let title = format!("Tick rate in ms, {}-{} (now {})", TICK_MS_RANGE.start(), TICK_MS_RANGE.end(), app.settings.tick_rate().as_millis());
+    app.modals.push(Box::new(PromptModal::new(title, |app, text| {
+        match text.parse::<u64>() {
+            Ok(ms) if TICK_MS_RANGE.contains(&ms) => {
+                app.settings.ui.tick_ms = Some(ms);
+                persist(app);
+            }
+            _ => { let _ = app.notify_tx.send(Notification::new(format!("Invalid tick rate: {}", text), false, Page::Settings)); }
+        }
+    })));
+}
+
+/// `m`: start or stop the background health monitor and remember the choice.
+pub fn toggle_monitor(app: &mut App) {
+    match app.monitor.take() {
+        Some(mut monitor) => monitor.stop(),
+        None => app.monitor = Some(HealthMonitor::new(app.settings.monitor.interval())),
+    }
+    app.settings.monitor.enabled = Some(app.monitor.is_some());
+    persist(app);
+}
+
+/// `d`: save the Docs position across restarts, or stop and drop the saved one.
+pub fn toggle_remember_docs(app: &mut App) {
+    let remember = !app.settings.docs.remembers();
+    app.settings.docs = DocsSettings { remember: Some(remember), ..DocsSettings::default() };
+    persist(app);
+}
+
+fn prompt_test_prompt(app: &mut App) {
+    let mut modal = PromptModal::new("End-to-end test prompt (blank or \"default\" restores the built-in one)", |app, text| {
+        app.settings.test.prompt = Some(text).filter(|t| !t.trim().is_empty() && t != "default");
+        set_e2e_prompt(app.settings.test.prompt.clone());
+        persist(app);
+    }).allow_empty();
+    modal.buffer = e2e_prompt();
+    app.modals.push(Box::new(modal));
+}
+
+/// `c`: the `chi-llm` executable or Python interpreter; it must answer
+/// `--version` before it is used and saved.
+fn prompt_chi_llm_bin(app: &mut App) {
+    let mut modal = PromptModal::new("chi-llm executable or Python interpreter (\"default\" searches PATH)", |app, text| {
+        use_chi_llm_bin(app, Some(text.trim().to_string()).filter(|t| t != "default"));
+    });
+    modal.buffer = app.settings.chi_llm.bin.clone().unwrap_or_else(|| chi_llm_command().program);
+    app.modals.push(Box::new(modal));
+}
+
+/// Run every `chi-llm` call through `bin` (or the PATH search for `None`) once
+/// it answers `--version`, and save it.
+fn use_chi_llm_bin(app: &mut App, bin: Option<String>) {
+    match resolve_chi_llm(bin.as_deref()) {
+        Ok(cmd) => {
+            let msg = format!("Using {}", cmd.display());
+            set_chi_llm_command(cmd);
+            invalidate_cli_cache(&[]);
+            app.settings.chi_llm.bin = bin;
+            persist(app);
+            let _ = app.notify_tx.send(Notification::new(msg, true, Page::Settings));
+        }
+        Err(e) => { let _ = app.notify_tx.send(Notification::new(format!("{:#}", e), false, Page::Settings)); }
+    }
+}
+
+/// `e`: look for Python environments with chi_llm; `poll_pyenvs` offers them.
+fn find_envs(app: &mut App) {
+    let cwd = std::env::current_dir().unwrap_or_default();
+    app.pyenvs.load(&app.jobs, "Finding Python environments", move |ctx| find_python_envs(&cwd, ctx.cancel_flag()));
+}
+
+/// Collect a finished environment search and, on Settings, open the picker.
+pub fn poll_pyenvs(app: &mut App) {
+    if !app.pyenvs.is_loading() { return; }
+    app.pyenvs.poll();
+    match &app.pyenvs {
+        PageData::Ready(envs) if envs.is_empty() => {
+            let _ = app.notify_tx.send(Notification::new("No Python environment has chi_llm installed", false, Page::Settings));
+        }
+        PageData::Ready(envs) if app.page == Page::Settings => {
+            let items: Vec<String> = envs.iter().map(PyEnv::label).collect();
+            let current = envs.iter().find(|e| is_current(e)).map(PyEnv::label).unwrap_or_default();
+            app.modals.push(Box::new(DropdownModal::new("Python environment for chi-llm calls", items, &current, |app, label| {
+                let python = app.pyenvs.ready().and_then(|envs| envs.iter().find(|e| e.label() == label)).map(|e| e.python.to_string_lossy().into_owned());
+                if let Some(python) = python { use_chi_llm_bin(app, Some(python)); }
+            })));
+        }
+        PageData::Error(e) => {
+            let _ = app.notify_tx.send(Notification::new(format!("Finding Python environments failed: {}", e), false, Page::Settings));
+        }
+        _ => {}
+    }
+}
+
+/// Whether `env` is the interpreter `chi-llm` calls go through.
+fn is_current(env: &PyEnv) -> bool {
+    let cmd = chi_llm_command();
+    !cmd.prefix.is_empty() && Path::new(&cmd.program) == env.python
+}
+
+fn prompt_timeouts(app: &mut App) {
+    let t = app.settings.timeouts();
+    let title = format!("Timeouts in seconds: CLI test e2e (now {} {} {})", t.cli.as_secs_f64(), t.test.as_secs_f64(), t.e2e.as_secs_f64());
+    app.modals.push(Box::new(PromptModal::new(title, |app, text| {
+        let secs: Vec<f64> = text.split_whitespace().filter_map(|v| parse_secs(v).map(|d| d.as_secs_f64())).collect();
+        let [cli, test, e2e] = secs[..] else {
+            let _ = app.notify_tx.send(Notification::new(format!("Expected three positive numbers: {}", text), false, Page::Settings));
+            return;
+        };
+        app.settings.timeouts = TimeoutSettings { cli: Some(cli), test: Some(test), e2e: Some(e2e) };
+        set_timeouts(app.settings.timeouts());
+        persist(app);
+    })));
+}
+
+pub struct SettingsPage;
+
+impl PageController for SettingsPage {
+    fn handle_key(&self, app: &mut App, key: KeyEvent) -> Option<Msg> {
+        match key.code {
+            KeyCode::Char('k') | KeyCode::Char('K') => prompt_tick_rate(app),
+            KeyCode::Char('o') | KeyCode::Char('O') => prompt_timeouts(app),
+            KeyCode::Char('p') | KeyCode::Char('P') => prompt_test_prompt(app),
+            KeyCode::Char('m') | KeyCode::Char('M') => toggle_monitor(app),
+            KeyCode::Char('d') | KeyCode::Char('D') => toggle_remember_docs(app),
+            KeyCode::Char('c') | KeyCode::Char('C') => prompt_chi_llm_bin(app),
+            KeyCode::Char('e') | KeyCode::Char('E') => find_envs(app),
+            KeyCode::Char('x') => export_to_file(app),
+            KeyCode::Char('X') => export_with_secrets(app),
+            KeyCode::Char('i') | KeyCode::Char('I') => open_import(app),
+            _ => return None,
+        }
+        Some(Msg::Handled)
+    }
+
+    fn draw(&self, f: &mut Frame, area: Rect, app: &App) {
+        let on_off = |b: bool| if b { "on" } else { "off" };
+        let theme = match app.theme.mode { ThemeMode::Dark => "dark", ThemeMode::Light => "light" };
+        let idle = match app.settings.idle_after() {
+            Some(d) => format!("after {}s without input  (settings.toml idle_after_secs; 0 disables)", d.as_secs()),
+            None => "off  (settings.toml idle_after_secs)".to_string(),
+        };
+        let t = timeouts();
+        let saved = if app.settings_path.is_some() { "saved to settings.toml" } else { "this session only" };
+        let mut lines = vec![
+            Line::from(vec![Span::styled("Display", app.theme.title()), Span::styled(format!("  ({})", saved), app.theme.hint())]),
+            Line::from(format!("Theme: {}  (t toggles)", theme)),
+            Line::from(format!("Animation: {}  (a toggles)", on_off(app.anim))),
+            Line::from(format!("Tick rate: {} ms  (k changes)", app.settings.tick_rate().as_millis())),
+            Line::from(format!("Idle slow-down: {}", idle)),
+            Line::from(format!(
+                "Timeouts: CLI {}s • test {}s • end-to-end {}s  (o changes; CHI_TUI_TIMEOUT_* override)",
+                t.cli.as_secs_f64(), t.test.as_secs_f64(), t.e2e.as_secs_f64(),
+            )),
+            Line::from(format!(
+                "End-to-end test prompt: \"{}\"{}  (p changes; the reply shows in the log panel)",
+                e2e_prompt(), if app.settings.test.prompt.is_none() { " (default)" } else { "" },
+            )),
+            Line::from(format!(
+                "Provider health monitor: {}  (m toggles; every {}s, settings.toml [monitor] interval_secs)",
+                on_off(app.monitor.is_some()), app.settings.monitor.interval().as_secs(),
+            )),
+            Line::from(format!("Remember Docs position across restarts: {}  (d toggles)", on_off(app.settings.docs.remembers()))),
+            Line::from(format!("chi-llm command: {}  (c changes; --chi-llm-bin overrides)", chi_llm_command().display())),
+            Line::from(format!("chi-llm serve session: {}  (settings.toml [chi_llm] backend = false turns it off)", backend_status())),
+            Line::from(format!("Accessibility mode: {}  (--a11y or CHI_TUI_A11Y)", on_off(app.a11y))),
+            Line::from(""),
+        ];
+        lines.push(Line::from(Span::styled("Python environments with chi_llm", app.theme.title())));
+        match &app.pyenvs {
+            PageData::NotLoaded => lines.push(Line::from(Span::styled("e finds them and picks the one chi-llm calls run in", app.theme.hint()))),
+            PageData::Loading(_) => lines.push(Line::from(Span::styled("searching…", app.theme.hint()))),
+            PageData::Error(e) => lines.push(Line::from(Span::styled(e.clone(), app.theme.error()))),
+            PageData::Ready(envs) if envs.is_empty() => lines.push(Line::from("none found  (e searches again)")),
+            PageData::Ready(envs) => lines.extend(envs.iter().map(|env| {
+                if is_current(env) { Line::from(Span::styled(format!("✓ {}", env.label()), app.theme.success())) } else { Line::from(format!("  {}", env.label())) }
+            })),
+        }
+        lines.push(Line::from(""));
+        let reg = plugins();
+        let names: Vec<&str> = reg.plugins.iter().map(|p| p.ptype.as_str()).collect();
+        lines.push(Line::from(Span::styled("Provider plugins", app.theme.title())));
+        lines.push(Line::from(if names.is_empty() { "none loaded".to_string() } else { format!("{} loaded: {}", names.len(), names.join(", ")) }));
+        for e in &reg.errors {
+            lines.push(Line::from(Span::styled(e.clone(), app.theme.error())));
+        }
+        lines.push(Line::from(""));
+        let hooks = &app.settings.hooks;
+        lines.push(Line::from(Span::styled("Hooks", app.theme.title())));
+        lines.push(Line::from(format!("after_write: {} • after_test: {}  (settings.toml [hooks])", hooks.after_write.len(), hooks.after_test.len())));
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled("File locations", app.theme.title())));
+        let locations = paths().locations();
+        let width = locations.iter().map(|(label, _)| text::width(label)).max().unwrap_or(0);
+        for (label, path) in locations {
+            lines.push(Line::from(vec![
+                Span::styled(format!("{}  ", text::pad(label, width)), app.theme.subtitle()),
+                Span::styled(path, app.theme.text()),
+            ]));
+        }
+        let p = Paragraph::new(lines)
+            .style(app.theme.base())
+            .block(Block::default().borders(Borders::ALL).border_style(app.theme.border()).title("Settings"))
+            .wrap(Wrap { trim: false });
+        f.render_widget(p, area);
+    }
+
+    fn actions(&self) -> &'static [KeyAction] {
+        const ACTIONS: &[KeyAction] = &[
+            KeyAction::new("t", "theme"),
+            KeyAction::new("a", "animation"),
+            KeyAction::new("k", "tick rate"),
+            KeyAction::new("o", "timeouts"),
+            KeyAction::new("p", "test prompt"),
+            KeyAction::new("m", "health monitor"),
+            KeyAction::new("d", "remember docs position"),
+            KeyAction::new("c", "chi-llm command"),
+            KeyAction::new("e", "python environments"),
+            KeyAction::new("x", "export bundle"),
+            KeyAction::new("X", "export with secrets"),
+            KeyAction::new("i", "import bundle"),
+            KeyAction::new("Esc", "back"),
+        ];
+        ACTIONS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saving_keeps_the_rest_of_the_file() {
+        let path = std::env::temp_dir().join(format!("chi-tui-settings-{}.toml", std::process::id()));
+        fs::write(&path, "# my settings\nidle_after_secs = 30\n\n[hooks]\nafter_write = [\"make\"]\n\n[ui]\ntheme = \"dark\" # keep\n").unwrap();
+        let mut s: UserSettings = toml::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        s.ui = UiSettings { theme: Some("light".into()), animation: Some(false), tick_ms: Some(250) };
+        s.timeouts.e2e = Some(120.0);
+        s.test.prompt = Some("Odpowiedz po polsku: ok".into());
+        s.docs = DocsSettings { remember: Some(true), path: Some("docs/CLI.md".into()), scroll: Some(40), toc_selected: Some(2), show_toc: Some(true), show_files: None };
+        save_ui_settings(&path, &s).unwrap();
+
+        let text = fs::read_to_string(&path).unwrap();
+        assert!(text.starts_with("# my settings\nidle_after_secs = 30\n"));
+        assert!(text.contains("after_write = [\"make\"]"));
+        let back: UserSettings = toml::from_str(&text).unwrap();
+        assert_eq!(back.ui, s.ui);
+        assert_eq!(back.docs, s.docs);
+        assert_eq!(back.test.prompt.as_deref(), Some("Odpowiedz po polsku: ok"));
+        s.test.prompt = None;
+        save_ui_settings(&path, &s).unwrap();
+        assert!(!fs::read_to_string(&path).unwrap().contains("Odpowiedz"));
+        // Turning `remember` off drops the saved position
+        s.docs = DocsSettings { remember: Some(false), ..DocsSettings::default() };
+        save_ui_settings(&path, &s).unwrap();
+        let cleared: UserSettings = toml::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(cleared.docs, s.docs);
+        assert_eq!(back.tick_rate(), Duration::from_millis(250));
+        assert_eq!(back.timeouts().e2e, Duration::from_secs(120));
+        assert_eq!(back.timeouts().cli, Timeouts::default().cli);
+        assert_eq!(back.hooks.after_write.len(), 1);
+
+        fs::write(&path, "ui = 3\n").unwrap();
+        assert!(save_ui_settings(&path, &s).is_err());
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,79 @@
+//! Display-width text helpers. Terminal cells are not chars: CJK characters and
+//! most emoji take two columns, combining marks none, and a flag or family emoji
+//! is several chars in one grapheme. Padding, truncation and cursor positions
+//! go through here so such names neither break alignment nor get cut in half.
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// Columns a list label's name may take before it is cut with `…`.
+pub const NAME_MAX: usize = 32;
+
+/// Columns `s` takes in the terminal.
+pub fn width(s: &str) -> usize {
+    UnicodeWidthStr::width(s)
+}
+
+/// `s` cut to at most `max` columns, ending in `…` when shortened. Cuts fall
+/// between graphemes.
+pub fn truncate(s: &str, max: usize) -> String {
+    if width(s) <= max { return s.to_string(); }
+    let mut out = String::new();
+    let mut used = 0;
+    for g in s.graphemes(true) {
+        let w = width(g);
+        if used + w + 1 > max { break; }
+        out.push_str(g);
+        used += w;
+    }
+    if max > 0 { out.push('…'); }
+    out
+}
+
+/// `s` truncated and padded with spaces to exactly `cols` columns.
+pub fn pad(s: &str, cols: usize) -> String {
+    let mut out = truncate(s, cols);
+    let w = width(&out);
+    out.extend(std::iter::repeat_n(' ', cols.saturating_sub(w)));
+    out
+}
+
+/// Number of graphemes in `s` (cursor positions in a line editor).
+pub fn grapheme_len(s: &str) -> usize {
+    s.graphemes(true).count()
+}
+
+/// Byte offset of grapheme `idx` in `s`, or `s.len()` past the end; always a
+/// char boundary, so it is safe to insert or slice at.
+pub fn grapheme_byte(s: &str, idx: usize) -> usize {
+    s.grapheme_indices(true).nth(idx).map(|(i, _)| i).unwrap_or(s.len())
+}
+
+/// Pasted text for a one-line input: line breaks and other control
+/// characters dropped, tabs as spaces.
+pub fn single_line(text: &str) -> String {
+    text.chars().filter_map(|c| match c {
+        '\t' => Some(' '),
+        c if c.is_control() => None,
+        c => Some(c),
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wide_and_combined_text_is_measured_and_cut_by_columns() {
+        assert_eq!(width("qwen-中文"), 9);
+        assert_eq!(width("e\u{301}"), 1);
+        assert_eq!(truncate("中文模型名", 7), "中文模…");
+        assert_eq!(truncate("ok", 7), "ok");
+        assert_eq!(pad("中文", 6), "中文  ");
+        assert_eq!(pad("中文模型", 5), "中文…");
+        assert_eq!(pad("中文模型", 4), "中… ");
+        let flag = "\u{1F1F5}\u{1F1F1}";
+        assert_eq!(grapheme_len(&format!("a{}b", flag)), 3);
+        assert_eq!(grapheme_byte(&format!("a{}b", flag), 2), 1 + flag.len());
+    }
+}
@@ -1,4 +1,6 @@
-use ratatui::style::Color;
+use ratatui::style::{Color, Modifier, Style};
+
+use crate::highlight::Token;
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum ThemeMode {
@@ -6,6 +8,9 @@ pub enum ThemeMode {
     Dark,
 }
 
+/// Palette plus the semantic styles widgets should use. Pages ask for a role
+/// (`title`, `hint`, `selected_row`, ...) rather than a raw color so every theme
+/// renders consistently.
 #[derive(Clone, Debug)]
 pub struct Theme {
     pub mode: ThemeMode,
@@ -16,6 +21,9 @@ pub struct Theme {
     pub accent: Color,
     pub frame: Color,
     pub selected: Color,
+    pub error: Color,
+    pub warning: Color,
+    pub success: Color,
 }
 
 impl Theme {
@@ -29,14 +37,122 @@ impl Theme {
             accent: Color::Rgb(64, 160, 255),
             frame: Color::Rgb(120, 80, 200),
             selected: Color::Rgb(255, 120, 0),
+            error: Color::Red,
+            warning: Color::Yellow,
+            success: Color::Rgb(0, 220, 120),
+        }
+    }
+
+    pub fn synthwave_light() -> Self {
+        Self {
+            mode: ThemeMode::Light,
+            bg: Color::Rgb(246, 242, 250),
+            fg: Color::Rgb(30, 24, 48),
+            primary: Color::Rgb(196, 0, 118),
+            secondary: Color::Rgb(0, 120, 140),
+            accent: Color::Rgb(30, 96, 200),
+            frame: Color::Rgb(120, 80, 200),
+            selected: Color::Rgb(204, 84, 0),
+            error: Color::Rgb(190, 20, 20),
+            warning: Color::Rgb(170, 110, 0),
+            success: Color::Rgb(0, 140, 70),
         }
     }
 
     pub fn toggle(&mut self) {
-        self.mode = match self.mode {
-            ThemeMode::Dark => ThemeMode::Light,
-            ThemeMode::Light => ThemeMode::Dark,
+        *self = match self.mode {
+            ThemeMode::Dark => Self::synthwave_light(),
+            ThemeMode::Light => Self::synthwave_dark(),
         };
     }
-}
 
+    /// Page background with body text.
+    pub fn base(&self) -> Style {
+        Style::default().bg(self.bg).fg(self.fg)
+    }
+
+    /// Plain body text (no background), e.g. unselected list rows.
+    pub fn text(&self) -> Style {
+        Style::default().fg(self.fg)
+    }
+
+    /// Section headings.
+    pub fn title(&self) -> Style {
+        Style::default().fg(self.primary).add_modifier(Modifier::BOLD)
+    }
+
+    /// Sub-headings.
+    pub fn subtitle(&self) -> Style {
+        Style::default().fg(self.accent).add_modifier(Modifier::BOLD)
+    }
+
+    /// Secondary information: key hints, status lines, metadata.
+    pub fn hint(&self) -> Style {
+        Style::default().fg(self.secondary)
+    }
+
+    /// Emphasised but non-selected items (e.g. the "add" row).
+    pub fn accent(&self) -> Style {
+        Style::default().fg(self.accent)
+    }
+
+    pub fn error(&self) -> Style {
+        Style::default().fg(self.error)
+    }
+
+    /// Approaching a limit (e.g. a budget), not yet an error.
+    pub fn warning(&self) -> Style {
+        Style::default().fg(self.warning).add_modifier(Modifier::BOLD)
+    }
+
+    /// A check that passed (e.g. the provider health dot).
+    pub fn success(&self) -> Style {
+        Style::default().fg(self.success)
+    }
+
+    /// Code block tokens (see `highlight`).
+    pub fn syntax(&self, token: Token) -> Style {
+        match token {
+            Token::Plain => self.text(),
+            Token::Keyword => Style::default().fg(self.primary).add_modifier(Modifier::BOLD),
+            Token::String => Style::default().fg(self.secondary),
+            Token::Number => Style::default().fg(self.selected),
+            Token::Comment => Style::default().fg(self.frame).add_modifier(Modifier::ITALIC),
+            Token::Name => Style::default().fg(self.accent),
+        }
+    }
+
+    /// Inactive controls.
+    pub fn disabled(&self) -> Style {
+        Style::default().fg(self.secondary).add_modifier(Modifier::DIM)
+    }
+
+    /// The row or control under the cursor.
+    pub fn selected_row(&self) -> Style {
+        Style::default().fg(self.selected).add_modifier(Modifier::BOLD)
+    }
+
+    /// `selected_row` when `selected`, plain text otherwise.
+    pub fn row(&self, selected: bool) -> Style {
+        if selected { self.selected_row() } else { self.text() }
+    }
+
+    /// Highlight style passed to ratatui lists.
+    pub fn highlight(&self) -> Style {
+        Style::default().fg(self.selected)
+    }
+
+    pub fn border(&self) -> Style {
+        Style::default().fg(self.frame)
+    }
+
+    /// Border of the pane or widget that currently has focus.
+    pub fn focused_border(&self) -> Style {
+        Style::default().fg(self.selected)
+    }
+
+    /// `focused_border` when `focused`, the regular border otherwise.
+    pub fn border_for(&self, focused: bool) -> Style {
+        if focused { self.focused_border() } else { self.border() }
+    }
+}
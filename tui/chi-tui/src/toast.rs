@@ -0,0 +1,104 @@
+//! Toast queue. Background tasks and pages send `Notification`s through
+//! `App::notify_tx`; `App::poll_notifications` moves them here, and the newest
+//! few are drawn stacked in the bottom-right corner until they expire, Ctrl+K
+//! dismisses the newest one or a click dismisses the one under the pointer.
+//! Failures stay up longer than successes.
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use crate::app::Notification;
+
+/// How long a success toast stays visible.
+pub const TOAST_TTL: Duration = Duration::from_secs(6);
+/// How long a failure toast stays visible.
+pub const ERROR_TOAST_TTL: Duration = Duration::from_secs(12);
+/// Toasts kept at once; older ones are dropped when more arrive.
+pub const MAX_TOASTS: usize = 4;
+
+#[derive(Debug, Default)]
+pub struct Toasts {
+    /// Oldest first.
+    queue: VecDeque<Notification>,
+}
+
+impl Toasts {
+    /// Add a toast. The same message again only restarts its timer.
+    pub fn push(&mut self, n: Notification) {
+        self.queue.retain(|t| t.message != n.message || t.ok != n.ok);
+        self.queue.push_back(n);
+        while self.queue.len() > MAX_TOASTS { self.queue.pop_front(); }
+    }
+
+    /// Drop toasts whose time is up.
+    pub fn expire(&mut self) {
+        self.queue.retain(|t| t.created.elapsed() < ttl(t));
+    }
+
+    /// The newest toast.
+    pub fn latest(&self) -> Option<&Notification> {
+        self.queue.back()
+    }
+
+    /// Newest first.
+    pub fn iter(&self) -> impl Iterator<Item = &Notification> {
+        self.queue.iter().rev()
+    }
+
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /// Remove the `i`th toast, newest first (as drawn).
+    pub fn dismiss(&mut self, i: usize) -> Option<Notification> {
+        let idx = self.queue.len().checked_sub(i + 1)?;
+        self.queue.remove(idx)
+    }
+
+    /// Remove and return the newest toast.
+    pub fn dismiss_latest(&mut self) -> Option<Notification> {
+        self.queue.pop_back()
+    }
+}
+
+fn ttl(t: &Notification) -> Duration {
+    if t.ok { TOAST_TTL } else { ERROR_TOAST_TTL }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use super::*;
+    use crate::app::Page;
+
+    #[test]
+    fn queue_keeps_newest_and_expires_by_kind() {
+        let mut toasts = Toasts::default();
+        for i in 0..MAX_TOASTS + 2 { toasts.push(Notification::new(format!("saved {i}"), true, Page::Configure)); }
+        assert_eq!(toasts.len(), MAX_TOASTS);
+        assert_eq!(toasts.latest().map(|t| t.message.as_str()), Some("saved 5"));
+
+        // A repeat moves to the front instead of stacking
+        toasts.push(Notification::new("saved 3", true, Page::Configure));
+        assert_eq!(toasts.len(), MAX_TOASTS);
+        assert_eq!(toasts.iter().map(|t| t.message.as_str()).collect::<Vec<_>>(), ["saved 3", "saved 5", "saved 4", "saved 2"]);
+        assert_eq!(toasts.dismiss(1).map(|t| t.message), Some("saved 5".to_string()));
+        assert!(toasts.dismiss(7).is_none());
+
+        let old = Instant::now() - TOAST_TTL;
+        let mut done = Notification::new("done", true, Page::Build);
+        done.created = old;
+        let mut failed = Notification::new("failed", false, Page::Build);
+        failed.created = old;
+        let mut toasts = Toasts::default();
+        toasts.push(done);
+        toasts.push(failed);
+        toasts.expire();
+        assert_eq!(toasts.iter().map(|t| t.message.as_str()).collect::<Vec<_>>(), ["failed"]);
+    }
+}
@@ -0,0 +1,165 @@
+//! Guided tutorial, started from Welcome: adding a provider, testing and saving
+//! it, making it the default and writing the config, on the real pages. Each
+//! step opens its page and shows what to press in a panel over it; the step
+//! completes when the app sees it done (a passed test, a saved file, ...).
+//! `Ctrl+N` skips a step and `Ctrl+T` ends the tutorial.
+
+use ratatui::layout::Rect;
+use ratatui::prelude::Frame;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, Wrap};
+
+use crate::a11y::page_name;
+use crate::app::{App, Notification, Page};
+
+/// Something the user did that a tutorial step waits for.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Milestone {
+    /// The Configure list has more providers than when the tutorial started.
+    ProviderAdded,
+    TestPassed,
+    /// `s` wrote the providers to `chi.tmp.json`.
+    ProvidersSaved,
+    DefaultSelected,
+    ConfigWritten,
+}
+
+pub struct TutorialStep {
+    pub title: &'static str,
+    pub page: Page,
+    /// What to press, one line each.
+    pub howto: &'static [&'static str],
+    pub done_when: Milestone,
+}
+
+pub const STEPS: [TutorialStep; 5] = [
+    TutorialStep {
+        title: "Add a provider",
+        page: Page::Configure,
+        howto: &[
+            "a adds a provider and opens its form.",
+            "Enter on the type row picks the kind (e.g. ollama, openai).",
+            "↑/↓ moves between fields; Enter edits one, Enter again finishes.",
+        ],
+        done_when: Milestone::ProviderAdded,
+    },
+    TutorialStep {
+        title: "Test it",
+        page: Page::Configure,
+        howto: &[
+            "Fill in what the provider needs (API key, host, model).",
+            "Select [ Test ] and press Enter, or t on the list.",
+            "The result shows under the form; fix fields and test again if it fails.",
+        ],
+        done_when: Milestone::TestPassed,
+    },
+    TutorialStep {
+        title: "Save it",
+        page: Page::Configure,
+        howto: &[
+            "[ Save ] keeps the tested values in the form's provider.",
+            "Esc or Tab goes back to the list; s there writes chi.tmp.json.",
+        ],
+        done_when: Milestone::ProvidersSaved,
+    },
+    TutorialStep {
+        title: "Make it the default",
+        page: Page::SelectDefault,
+        howto: &[
+            "↑/↓ selects your provider; Enter makes it the default.",
+            "chi-llm uses the default unless code asks for another.",
+        ],
+        done_when: Milestone::DefaultSelected,
+    },
+    TutorialStep {
+        title: "Write the config",
+        page: Page::Build,
+        howto: &[
+            "g switches between this project (.chi_llm.json) and the global config.",
+            "Enter previews the file; Enter in the preview writes it.",
+            "chi-llm in this directory then uses your provider.",
+        ],
+        done_when: Milestone::ConfigWritten,
+    },
+];
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Tutorial {
+    /// Index into `STEPS`.
+    pub step: usize,
+    /// Providers on the Configure list at the start, once they are loaded.
+    baseline: Option<usize>,
+}
+
+impl Tutorial {
+    pub fn current(&self) -> Option<&'static TutorialStep> {
+        STEPS.get(self.step)
+    }
+}
+
+/// Start from the first step.
+pub fn start(app: &mut App) {
+    let tutorial = Tutorial { step: 0, baseline: app.providers.ready().map(|st| st.entries.len()) };
+    app.tutorial = Some(tutorial);
+    app.page = STEPS[0].page;
+}
+
+/// Complete the current step if it waits for `m`.
+pub fn reached(app: &mut App, m: Milestone) {
+    if app.tutorial.as_ref().and_then(Tutorial::current).is_some_and(|s| s.done_when == m) { advance(app); }
+}
+
+/// Move to the next step (skipping counts), or finish after the last one.
+pub fn advance(app: &mut App) {
+    let Some(t) = &mut app.tutorial else { return };
+    let done = STEPS[t.step].title;
+    t.step += 1;
+    let msg = match t.current() {
+        Some(next) => {
+            app.page = next.page;
+            format!("Tutorial: {} done. Next: {}", done, next.title)
+        }
+        None => {
+            app.tutorial = None;
+            "Tutorial complete: chi-llm is configured".to_string()
+        }
+    };
+    let _ = app.notify_tx.send(Notification::new(msg, true, app.page));
+}
+
+/// Milestones the app can only see by looking: a provider added on Configure.
+pub fn tick(app: &mut App) {
+    let Some(t) = &mut app.tutorial else { return };
+    let Some(count) = app.providers.ready().map(|st| st.entries.len()) else { return };
+    let baseline = *t.baseline.get_or_insert(count);
+    if count > baseline { reached(app, Milestone::ProviderAdded); }
+}
+
+/// Panel in the top-right corner of `area` with the steps and what to do now.
+pub fn draw_tutorial(f: &mut Frame, area: Rect, app: &App, t: &Tutorial) {
+    let Some(step) = t.current() else { return };
+    let mut lines: Vec<Line> = STEPS.iter().enumerate().map(|(i, s)| {
+        let (mark, style) = match i.cmp(&t.step) {
+            std::cmp::Ordering::Less => ("✓", app.theme.success()),
+            std::cmp::Ordering::Equal => ("›", app.theme.highlight()),
+            std::cmp::Ordering::Greater => (" ", app.theme.hint()),
+        };
+        Line::from(Span::styled(format!("{} {}", mark, s.title), style))
+    }).collect();
+    lines.push(Line::from(""));
+    if app.page != step.page {
+        lines.push(Line::from(Span::styled(format!("This step is on the {} page.", page_name(step.page)), app.theme.error())));
+    }
+    lines.extend(step.howto.iter().map(|l| Line::from(Span::styled(*l, app.theme.text()))));
+    lines.push(Line::from(Span::styled("Ctrl+N: skip step • Ctrl+T: end tutorial", app.theme.hint())));
+    let width = area.width.min(54);
+    // Borders plus the wrapped text, roughly
+    let inner = width.saturating_sub(2).max(1) as usize;
+    let rows: usize = lines.iter().map(|l| l.width().max(1).div_ceil(inner)).sum();
+    let height = (rows as u16 + 2).min(area.height);
+    let rect = Rect { x: area.x + area.width - width, y: area.y, width, height };
+    let title = format!("Tutorial {}/{}: {}", t.step + 1, STEPS.len(), step.title);
+    let block = Block::default().borders(Borders::ALL).border_style(app.theme.focused_border()).title(title);
+    f.render_widget(Clear, rect);
+    f.render_widget(Paragraph::new(lines).style(app.theme.base()).block(block).wrap(Wrap { trim: true }), rect);
+}
@@ -0,0 +1,151 @@
+use ratatui::layout::{Alignment, Constraint, Direction, Layout, Rect};
+use ratatui::prelude::Frame;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, Wrap};
+
+use crate::a11y::{linearize, page_name};
+use crate::app::App;
+use crate::controller::controller;
+use crate::log::draw_log_panel;
+use crate::mouse::HitTarget;
+use crate::page_data::spinner;
+use crate::providers::{ConfigSource, HealthStatus, ProviderSummary};
+use crate::tutorial::draw_tutorial;
+use crate::util::neon_gradient_line;
+
+/// Draw one frame: header, active page, footer, then the toasts and open modals.
+pub fn ui(f: &mut Frame, app: &App) {
+    app.hits.borrow_mut().clear();
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(if app.a11y { 2 } else { 6 }), // header with animation space
+            Constraint::Length(1), // status strip
+            Constraint::Min(3),
+            Constraint::Length(1), // footer
+        ]).split(f.size());
+
+    if app.a11y { draw_a11y_header(f, chunks[0], app); } else { draw_header(f, chunks[0], app); }
+    draw_status_bar(f, chunks[1], app);
+    let body = if app.show_log {
+        let parts = Layout::default().direction(Direction::Vertical)
+            .constraints([Constraint::Min(3), Constraint::Length(10)]).split(chunks[2]);
+        draw_log_panel(f, parts[1], app);
+        parts[0]
+    } else { chunks[2] };
+    controller(app.page).draw(f, body, app);
+    draw_footer(f, chunks[3], app);
+
+    if let Some(t) = &app.tutorial { draw_tutorial(f, body, app, t); }
+    if !app.toasts.is_empty() { draw_toasts(f, chunks[2], app); }
+    for modal in &app.modals { modal.draw(f, f.size(), app); }
+    if app.a11y { linearize(f.buffer_mut()); }
+}
+
+/// Plain two-line header for accessibility mode: page name, then the latest announcement.
+fn draw_a11y_header(f: &mut Frame, area: Rect, app: &App) {
+    let mut title = format!("chi-tui: {} page. Press ? for every key.", page_name(app.page));
+    if let Some(h) = &app.health { title.push_str(&format!(" Default provider {}.", h.label().trim_start_matches("● "))); }
+    if app.macros.is_recording() { title.push_str(" Recording macro."); }
+    let status = app.announcer.status.clone().unwrap_or_default();
+    let p = Paragraph::new(vec![Line::from(title), Line::from(status)]).style(app.theme.base());
+    f.render_widget(p, area);
+}
+
+/// One line: the scratch default provider and the provider of the config
+/// chi-llm reads, highlighted when they differ.
+fn draw_status_bar(f: &mut Frame, area: Rect, app: &App) {
+    let st = &app.active;
+    let mut spans = vec![Span::styled(" Default: ", app.theme.hint())];
+    match &st.default {
+        Some(d) => spans.push(Span::styled(format!("{} ({})", d.name, d.describe()), app.theme.accent())),
+        None => spans.push(Span::styled("none (3 to select)", app.theme.disabled())),
+    }
+    spans.push(Span::styled(" │ Active: ", app.theme.hint()));
+    match &st.config {
+        Some(config) => {
+            let source = match config.source { ConfigSource::Project => "project", ConfigSource::Global => "global" };
+            let provider = config.provider.as_ref().map_or_else(|| "no provider".to_string(), ProviderSummary::describe);
+            let style = if st.out_of_date() { app.theme.warning() } else { app.theme.text() };
+            spans.push(Span::styled(provider, style));
+            spans.push(Span::styled(format!(" from {} ({})", config.path.display(), source), app.theme.hint()));
+            if st.out_of_date() { spans.push(Span::styled(" • differs from the default, b to build", app.theme.warning())); }
+        }
+        None => spans.push(Span::styled("no chi-llm config (b to build)", app.theme.disabled())),
+    }
+    f.render_widget(Paragraph::new(Line::from(spans)).style(app.theme.base()), area);
+}
+
+/// Toasts stacked upwards from the bottom-right corner, newest at the bottom;
+/// the ones that do not fit wait until the ones below expire.
+fn draw_toasts(f: &mut Frame, area: Rect, app: &App) {
+    let width = area.width.min(60);
+    let mut bottom = area.y + area.height;
+    for (i, t) in app.toasts.iter().enumerate() {
+        let style = if t.ok { app.theme.hint() } else { app.theme.error() };
+        let mut lines = vec![Line::from(Span::styled(t.message.clone(), style))];
+        // Only the newest toast answers Ctrl+G
+        if i == 0 && t.origin != app.page {
+            lines.push(Line::from(Span::styled("Ctrl+G: view • Ctrl+K: dismiss", app.theme.border())));
+        }
+        let inner = width.saturating_sub(2).max(1) as usize;
+        let text_rows: usize = lines.iter().map(|l| l.width().max(1).div_ceil(inner)).sum();
+        let height = (text_rows as u16 + 2).min(6);
+        if bottom < area.y + height { break; }
+        bottom -= height;
+        let rect = Rect { x: area.x + area.width - width, y: bottom, width, height };
+        let block = Block::default().borders(Borders::ALL).border_style(style).title(if t.ok { "Done" } else { "Failed" });
+        let p = Paragraph::new(lines).style(app.theme.base()).block(block).wrap(Wrap { trim: true });
+        f.render_widget(Clear, rect);
+        f.render_widget(p, rect);
+        app.hits.borrow_mut().add(rect, HitTarget::Toast(i));
+    }
+}
+
+fn draw_header(f: &mut Frame, area: Rect, app: &App) {
+    let title = neon_gradient_line(" chi_llm — micro‑LLM • TUI vNext ", &app.theme);
+    let mut sub = Line::from(vec![
+        Span::styled("  retro/synthwave • arrows + enter • ? help ", app.theme.hint()),
+    ]);
+    if let Some(h) = &app.health {
+        let style = match h.status {
+            HealthStatus::Ok => app.theme.success(),
+            HealthStatus::Failed => app.theme.error(),
+            HealthStatus::Checking | HealthStatus::Unchecked => app.theme.disabled(),
+        };
+        sub.spans.push(Span::styled(format!(" {} ", h.label()), style));
+    }
+    // One spinner for all background jobs: the oldest one, plus a count of the rest
+    let jobs = app.jobs.running();
+    if let Some(first) = jobs.first() {
+        let more = if jobs.len() > 1 { format!(" +{}", jobs.len() - 1) } else { String::new() };
+        sub.spans.push(Span::styled(format!(" {} {}{} ", spinner(app), first.describe(), more), app.theme.accent()));
+    }
+    if app.macros.is_recording() {
+        sub.spans.push(Span::styled(" ● REC (Ctrl+R stop) ", app.theme.error()));
+    } else if app.macros.is_replaying() {
+        sub.spans.push(Span::styled(format!(" ▶ replaying ({} left) ", app.macros.replay.len()), app.theme.accent()));
+    }
+    let block = Block::default()
+        .borders(Borders::BOTTOM)
+        .border_style(app.theme.border())
+        .title(Span::styled("CHI_TUI", app.theme.title()))
+        .title_alignment(Alignment::Center);
+    let v = vec![title, sub];
+    let p = Paragraph::new(v)
+        .style(app.theme.base())
+        .block(block)
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true });
+    f.render_widget(p, area);
+}
+
+fn draw_footer(f: &mut Frame, area: Rect, app: &App) {
+    let msg_text = controller(app.page).footer(app);
+    let msg = Line::from(Span::styled(msg_text, app.theme.hint()));
+    let p = Paragraph::new(msg)
+        .style(app.theme.base())
+        .block(Block::default())
+        .alignment(Alignment::Center);
+    f.render_widget(p, area);
+}
@@ -0,0 +1,69 @@
+//! Undo/redo of snapshots (Ctrl+Z / Ctrl+Y): form field values in
+//! `FormState`, the provider list in `ProvidersState`.
+
+/// Snapshots kept per stack; older ones are dropped.
+pub const UNDO_LIMIT: usize = 100;
+
+#[derive(Clone, Debug)]
+pub struct UndoStack<T> {
+    undo: Vec<T>,
+    redo: Vec<T>,
+}
+
+impl<T> Default for UndoStack<T> {
+    fn default() -> Self {
+        Self { undo: Vec::new(), redo: Vec::new() }
+    }
+}
+
+impl<T> UndoStack<T> {
+    /// Remember `before`, the state just before a change. A new change drops
+    /// whatever could be redone.
+    pub fn record(&mut self, before: T) {
+        if self.undo.len() == UNDO_LIMIT { self.undo.remove(0); }
+        self.undo.push(before);
+        self.redo.clear();
+    }
+
+    /// The state to go back to; `current` becomes redoable.
+    pub fn undo(&mut self, current: T) -> Option<T> {
+        let prev = self.undo.pop()?;
+        self.redo.push(current);
+        Some(prev)
+    }
+
+    /// The state to go forward to; `current` becomes undoable.
+    pub fn redo(&mut self, current: T) -> Option<T> {
+        let next = self.redo.pop()?;
+        self.undo.push(current);
+        Some(next)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn undo_and_redo_walk_the_snapshots() {
+        let mut stack = UndoStack::default();
+        stack.record(1);
+        stack.record(2);
+        assert_eq!(stack.undo(3), Some(2));
+        assert_eq!(stack.undo(2), Some(1));
+        assert_eq!(stack.undo(1), None);
+        assert_eq!(stack.redo(1), Some(2));
+        stack.record(2);
+        assert!(!stack.can_redo(), "a new change drops the redo stack");
+        for i in 0..UNDO_LIMIT + 5 { stack.record(i); }
+        assert_eq!(stack.undo.len(), UNDO_LIMIT);
+    }
+}
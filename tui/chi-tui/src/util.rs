@@ -1,26 +1,167 @@
+use std::collections::BTreeMap;
 use std::io;
 use std::process::{Command, Stdio};
-use std::time::Duration;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::path::PathBuf;
+use std::sync::{Mutex, RwLock};
+use std::thread;
+use std::time::{Duration, Instant};
 
 use anyhow::{anyhow, Result};
-use crossterm::terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen};
-use crossterm::{execute, event};
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
 use ratatui::style::{Modifier, Style};
 use ratatui::text::Span;
 use ratatui::text::Line;
 use serde_json::Value;
+use unicode_segmentation::UnicodeSegmentation;
 
+use crate::backend::{reset_backend, run_in_backend};
+use crate::cli_log::{mask_values, record_call, redact_args};
 use crate::theme::Theme;
 
+/// How `chi-llm` is started: its console script, or a Python interpreter
+/// running `-m chi_llm` when only the package is installed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChiLlmCommand {
+    pub program: String,
+    /// Arguments before the CLI's own (`-m chi_llm` for an interpreter).
+    pub prefix: Vec<String>,
+}
+
+impl ChiLlmCommand {
+    /// `bin` as given; a Python interpreter (`python`, `python3.12`, a venv's
+    /// `bin/python`) runs the package with `-m chi_llm`.
+    pub fn new(bin: &str) -> Self {
+        let stem = std::path::Path::new(bin).file_stem().and_then(|s| s.to_str()).unwrap_or(bin);
+        let prefix = if stem.starts_with("python") { vec!["-m".to_string(), "chi_llm".to_string()] } else { Vec::new() };
+        Self { program: bin.to_string(), prefix }
+    }
+
+    /// `python3 -m chi_llm`, for display.
+    pub fn display(&self) -> String {
+        std::iter::once(&self.program).chain(&self.prefix).cloned().collect::<Vec<_>>().join(" ")
+    }
+
+    /// `program` and `args` to spawn when this is the command: `chi-llm`
+    /// becomes it, anything else stays as given.
+    fn spawn_line(&self, program: &str, args: &[&str]) -> (String, Vec<String>) {
+        let args = args.iter().map(|a| a.to_string());
+        if program != "chi-llm" { return (program.to_string(), args.collect()); }
+        (self.program.clone(), self.prefix.iter().cloned().chain(args).collect())
+    }
+
+    /// Run `--version`; an interpreter without the package exits non-zero.
+    fn check(&self) -> std::result::Result<(), io::Error> {
+        let out = Command::new(&self.program).args(&self.prefix).arg("--version").stdin(Stdio::null()).output()?;
+        if self.prefix.is_empty() || out.status.success() { Ok(()) } else { Err(io::Error::other(String::from_utf8_lossy(&out.stderr).trim().to_string())) }
+    }
+}
+
+impl Default for ChiLlmCommand {
+    fn default() -> Self {
+        Self::new("chi-llm")
+    }
+}
+
+static CHI_LLM: RwLock<Option<ChiLlmCommand>> = RwLock::new(None);
+
+/// The command every `chi-llm` call runs: set by `ensure_chi_llm`,
+/// `--chi-llm-bin` or the Settings page, else the console script.
+pub fn chi_llm_command() -> ChiLlmCommand {
+    CHI_LLM.read().ok().and_then(|c| c.clone()).unwrap_or_default()
+}
+
+pub fn set_chi_llm_command(cmd: ChiLlmCommand) {
+    if let Ok(mut c) = CHI_LLM.write() { *c = Some(cmd); }
+    reset_backend();
+}
+
+/// Interpreters to try `-m chi_llm` with: the active virtualenv's or conda
+/// environment's first, then `python3` and `python` from PATH.
+fn python_candidates() -> Vec<String> {
+    let in_env = |var: &str| {
+        let root = PathBuf::from(std::env::var_os(var)?);
+        Some(if cfg!(windows) { root.join("Scripts").join("python.exe") } else { root.join("bin").join("python") })
+    };
+    let mut out: Vec<String> = ["VIRTUAL_ENV", "CONDA_PREFIX"].iter().filter_map(|v| in_env(v)).filter(|p| p.exists()).map(|p| p.to_string_lossy().into_owned()).collect();
+    out.extend(["python3", "python"].map(String::from));
+    out
+}
+
+/// A working `chi-llm` command: `bin` when given, else the console script on
+/// PATH, else the first interpreter from `python_candidates` that has the
+/// package.
+pub fn resolve_chi_llm(bin: Option<&str>) -> Result<ChiLlmCommand> {
+    if let Some(bin) = bin {
+        let cmd = ChiLlmCommand::new(bin);
+        return match cmd.check() {
+            Ok(()) => Ok(cmd),
+            Err(e) => Err(anyhow!("Failed to execute '{} --version': {}", cmd.display(), e)),
+        };
+    }
+    let script = ChiLlmCommand::default();
+    match script.check() {
+        Ok(()) => return Ok(script),
+        Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(anyhow!("Failed to execute 'chi-llm --version': {e}")),
+        Err(_) => {}
+    }
+    python_candidates().iter().map(|p| ChiLlmCommand::new(p)).find(|cmd| cmd.check().is_ok()).ok_or_else(|| anyhow!(
+        "Required CLI 'chi-llm' not found in PATH, and no Python interpreter has the chi_llm package.\n\nInstall: pip install -e .[full] (inside repo) or pip install chi-llm (when published), or pass --chi-llm-bin."
+    ))
+}
+
+/// Resolve the `chi-llm` command unless `--chi-llm-bin` or the settings already
+/// chose one.
 pub fn ensure_chi_llm() -> Result<()> {
-    match Command::new("chi-llm").arg("--version").output() {
-        Ok(_) => Ok(()),
-        Err(e) if e.kind() == io::ErrorKind::NotFound => Err(anyhow!(
-            "Required CLI 'chi-llm' not found in PATH.\n\nInstall: pip install -e .[full] (inside repo) or pip install chi-llm (when published)."
-        )),
-        Err(e) => Err(anyhow!("Failed to execute 'chi-llm --version': {e}")),
+    if CHI_LLM.read().is_ok_and(|c| c.is_some()) { return Ok(()); }
+    set_chi_llm_command(resolve_chi_llm(None)?);
+    Ok(())
+}
+
+/// `program` and `args` to spawn: `chi-llm` becomes the configured command.
+fn spawn_line(program: &str, args: &[&str]) -> (String, Vec<String>) {
+    chi_llm_command().spawn_line(program, args)
+}
+
+/// `s` for a URL query or path segment: unreserved characters as they are,
+/// every other byte as `%XX`.
+pub fn percent_encode(s: &str) -> String {
+    let mut out = String::new();
+    for b in s.bytes() {
+        if b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~') {
+            out.push(b as char);
+        } else {
+            out.push_str(&format!("%{:02X}", b));
+        }
     }
+    out
+}
+
+/// Open `target`, a URL or a file, with the system's default handler
+/// (`open` on macOS, the URL protocol handler on Windows, `xdg-open`
+/// elsewhere). On Windows the target goes to `rundll32` as a single argument,
+/// never through `cmd`, so a URL from remote release notes cannot run
+/// commands with `&`.
+pub fn open_url(target: &str) -> Result<()> {
+    let (program, args): (&str, &[&str]) = if cfg!(target_os = "macos") {
+        ("open", &[])
+    } else if cfg!(windows) {
+        ("rundll32", &["url.dll,FileProtocolHandler"])
+    } else {
+        ("xdg-open", &[])
+    };
+    let mut child = Command::new(program)
+        .args(args)
+        .arg(target)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| anyhow!("cannot run {}: {}", program, e))?;
+    // Reap it; browsers usually detach anyway
+    thread::spawn(move || child.wait());
+    Ok(())
 }
 
 pub fn centered_rect(pct_x: u16, pct_y: u16, r: Rect) -> Rect {
@@ -32,26 +173,27 @@ pub fn centered_rect(pct_x: u16, pct_y: u16, r: Rect) -> Rect {
             Constraint::Percentage((100 - pct_y) / 2),
         ])
         .split(r);
-    let area = Layout::default()
+    
+    Layout::default()
         .direction(Direction::Horizontal)
         .constraints([
             Constraint::Percentage((100 - pct_x) / 2),
             Constraint::Percentage(pct_x),
             Constraint::Percentage((100 - pct_x) / 2),
         ])
-        .split(popup_layout[1])[1];
-    area
+        .split(popup_layout[1])[1]
 }
 
 pub fn neon_gradient_line(text: &str, theme: &Theme) -> Line<'static> {
     let colors = [theme.primary, theme.accent, theme.secondary, theme.frame];
+    // One colour per grapheme: combining marks and emoji sequences stay whole
     let spans: Vec<Span> = text
-        .chars()
+        .graphemes(true)
         .enumerate()
-        .map(|(i, ch)| {
+        .map(|(i, g)| {
             let c = colors[i % colors.len()];
             Span::styled(
-                ch.to_string(),
+                g.to_string(),
                 Style::default().fg(c).add_modifier(Modifier::BOLD),
             )
         })
@@ -60,33 +202,359 @@ pub fn neon_gradient_line(text: &str, theme: &Theme) -> Line<'static> {
 }
 
 pub fn run_cli_json(args: &[&str], timeout: Duration) -> Result<Value> {
+    run_cli_json_cancellable(args, timeout, &AtomicBool::new(false))
+}
+
+/// `run_cli_json` that kills the CLI once `cancel` is set.
+pub fn run_cli_json_cancellable(args: &[&str], timeout: Duration, cancel: &AtomicBool) -> Result<Value> {
+    if let Some(val) = cached_cli_json(args) { return Ok(val); }
+    let started = Instant::now();
+    let stdout = match run_in_backend(args, timeout, cancel) {
+        Some(result) => logged("chi-llm serve", args, &[], &[], started, Some(timeout), result, |out| String::from_utf8_lossy(out).into_owned())?,
+        None => run_program("chi-llm", args, &[], &[], timeout, cancel)?,
+    };
+    let val: Value = serde_json::from_slice(&stdout)?;
+    if let Some(ttl) = cli_cache_ttl(args) {
+        let key = args.iter().map(|a| a.to_string()).collect();
+        CLI_CACHE.lock().unwrap_or_else(|e| e.into_inner()).insert(key, (Instant::now() + ttl, val.clone()));
+    }
+    Ok(val)
+}
+
+/// Successful JSON answers by argument list, with when they expire. Every
+/// `chi-llm` start costs the Python interpreter's startup, so page entries
+/// reuse a recent answer instead of spawning again.
+static CLI_CACHE: Mutex<BTreeMap<Vec<String>, (Instant, Value)>> = Mutex::new(BTreeMap::new());
+
+/// How long an answer to `args` stays cached; `None` for commands that are
+/// never cached. The schema only changes with a chi-llm upgrade; diagnostics
+/// and the current model follow config edits, so they expire quickly.
+pub fn cli_cache_ttl(args: &[&str]) -> Option<Duration> {
+    match args {
+        ["providers", "schema", ..] => Some(Duration::from_secs(600)),
+        ["models", "list", ..] => Some(Duration::from_secs(60)),
+        ["diagnostics", ..] | ["models", "current", ..] => Some(Duration::from_secs(10)),
+        _ => None,
+    }
+}
+
+fn cached_cli_json(args: &[&str]) -> Option<Value> {
+    let mut cache = CLI_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    let key: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+    match cache.get(&key) {
+        Some((expires, val)) if Instant::now() < *expires => Some(val.clone()),
+        Some(_) => {
+            cache.remove(&key);
+            None
+        }
+        None => None,
+    }
+}
+
+/// Drop cached answers whose arguments start with `prefix` (all of them for
+/// an empty prefix), e.g. after a write that changes what the CLI reports.
+pub fn invalidate_cli_cache(prefix: &[&str]) {
+    CLI_CACHE.lock().unwrap_or_else(|e| e.into_inner()).retain(|args, _| {
+        !(args.len() >= prefix.len() && args.iter().zip(prefix).all(|(a, p)| a == p))
+    });
+}
+
+/// Run `chi-llm` with extra environment variables and return trimmed stdout text.
+pub fn run_cli_text(args: &[&str], env: &[(String, String)], timeout: Duration) -> Result<String> {
+    run_program_text("chi-llm", args, env, timeout)
+}
+
+/// Like `run_cli_text` for any program (e.g. a provider plugin's test command).
+pub fn run_program_text(program: &str, args: &[&str], env: &[(String, String)], timeout: Duration) -> Result<String> {
+    run_program_text_cancellable(program, args, env, timeout, &AtomicBool::new(false))
+}
+
+/// Like `run_program_text`, but setting `cancel` kills the program (checked every
+/// 100ms) and returns a `cancelled` error. Used by background jobs.
+pub fn run_program_text_cancellable(
+    program: &str,
+    args: &[&str],
+    env: &[(String, String)],
+    timeout: Duration,
+    cancel: &AtomicBool,
+) -> Result<String> {
+    run_secret_program_text(program, args, env, &[], timeout, cancel)
+}
+
+/// Like `run_program_text_cancellable` for arguments that carry `secrets`
+/// (e.g. a plugin's `{api_key}`): they are masked in the CLI log and errors.
+pub fn run_secret_program_text(
+    program: &str,
+    args: &[&str],
+    env: &[(String, String)],
+    secrets: &[String],
+    timeout: Duration,
+    cancel: &AtomicBool,
+) -> Result<String> {
+    let stdout = run_program(program, args, env, secrets, timeout, cancel)?;
+    Ok(String::from_utf8_lossy(&stdout).trim().to_string())
+}
+
+/// Why a program run failed, before it is turned into the caller's error.
+pub(crate) enum Failure {
+    Spawn(io::Error),
+    Cancelled,
+    TimedOut,
+    /// Non-zero exit, with stderr.
+    Exit(Option<i32>, String),
+    Io(io::Error),
+}
+
+/// Exit code and output of a finished run, or why it failed.
+pub(crate) type RunResult<T> = std::result::Result<(Option<i32>, T), Failure>;
+
+impl Failure {
+    fn exit_code(&self) -> Option<i32> {
+        match self { Failure::Exit(code, _) => *code, _ => None }
+    }
+
+    /// Short reason for the CLI log and stderr (if any) as its output.
+    fn summary(&self) -> (String, &str) {
+        match self {
+            Failure::Spawn(e) => (format!("cannot run: {}", e), ""),
+            Failure::Cancelled => ("cancelled".to_string(), ""),
+            Failure::TimedOut => ("timed out".to_string(), ""),
+            Failure::Exit(_, stderr) => ("failed".to_string(), stderr),
+            Failure::Io(e) => (e.to_string(), ""),
+        }
+    }
+
+    fn into_error(self, program: &str, args: &[String], timeout: Option<Duration>) -> anyhow::Error {
+        match self {
+            Failure::Spawn(e) => anyhow!("cannot run {}: {}", program, e),
+            Failure::Cancelled => anyhow!("cancelled"),
+            Failure::TimedOut => anyhow!("{} {:?} timed out after {:?}", program, args, timeout.unwrap_or_default()),
+            Failure::Exit(_, stderr) => anyhow!("{} {:?} failed: {}", program, args, stderr),
+            Failure::Io(e) => e.into(),
+        }
+    }
+}
+
+/// Record a finished run in the CLI log and convert its failure, if any.
+/// `secrets` are masked in both.
+#[allow(clippy::too_many_arguments)]
+fn logged<T>(
+    program: &str,
+    args: &[&str],
+    secrets: &[String],
+    env: &[(String, String)],
+    started: Instant,
+    timeout: Option<Duration>,
+    result: RunResult<T>,
+    output: impl FnOnce(&T) -> String,
+) -> Result<T> {
+    match result {
+        Ok((code, value)) => {
+            record_call(program, args, secrets, env, started.elapsed(), code, None, &output(&value));
+            Ok(value)
+        }
+        Err(failure) => {
+            let (reason, stderr) = failure.summary();
+            record_call(program, args, secrets, env, started.elapsed(), failure.exit_code(), Some(reason), stderr.trim());
+            let err = failure.into_error(program, &redact_args(args, secrets), timeout);
+            // stderr may echo an argument back
+            Err(if secrets.is_empty() { err } else { anyhow!(mask_values(&format!("{err:#}"), secrets)) })
+        }
+    }
+}
+
+fn run_program(program: &str, args: &[&str], env: &[(String, String)], secrets: &[String], timeout: Duration, cancel: &AtomicBool) -> Result<Vec<u8>> {
+    let (program, args) = spawn_line(program, args);
+    let (program, args) = (program.as_str(), args.iter().map(String::as_str).collect::<Vec<_>>());
+    let args = args.as_slice();
+    let started = Instant::now();
+    let result = wait_program(program, args, env, timeout, cancel);
+    logged(program, args, secrets, env, started, Some(timeout), result, |out| String::from_utf8_lossy(out).into_owned())
+}
+
+fn wait_program(
+    program: &str,
+    args: &[&str],
+    env: &[(String, String)],
+    timeout: Duration,
+    cancel: &AtomicBool,
+) -> RunResult<Vec<u8>> {
     use wait_timeout::ChildExt;
-    let mut cmd = Command::new("chi-llm");
+    let mut cmd = Command::new(program);
     cmd.args(args).stdout(Stdio::piped()).stderr(Stdio::piped());
-    let mut child = cmd.spawn()?;
-    match child.wait_timeout(timeout)? {
-        Some(status) => {
-            if !status.success() {
-                let stderr = child
-                    .stderr
-                    .take()
-                    .map(|mut s| {
-                        use std::io::Read;
-                        let mut buf = Vec::new();
-                        let _ = s.read_to_end(&mut buf);
-                        String::from_utf8_lossy(&buf).to_string()
-                    })
-                    .unwrap_or_default();
-                return Err(anyhow!("chi-llm {:?} failed: {}", args, stderr));
-            }
+    for (k, v) in env {
+        cmd.env(k, v);
+    }
+    let mut child = cmd.spawn().map_err(Failure::Spawn)?;
+    let started = Instant::now();
+    let status = loop {
+        if cancel.load(Ordering::Relaxed) {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(Failure::Cancelled);
         }
-        None => {
+        let left = timeout.saturating_sub(started.elapsed());
+        if left.is_zero() {
             let _ = child.kill();
-            return Err(anyhow!("chi-llm {:?} timed out after {:?}", args, timeout));
+            return Err(Failure::TimedOut);
         }
+        if let Some(status) = child.wait_timeout(left.min(Duration::from_millis(100))).map_err(Failure::Io)? { break status; }
+    };
+    if !status.success() {
+        let stderr = child
+            .stderr
+            .take()
+            .map(|mut s| {
+                use std::io::Read;
+                let mut buf = Vec::new();
+                let _ = s.read_to_end(&mut buf);
+                String::from_utf8_lossy(&buf).to_string()
+            })
+            .unwrap_or_default();
+        return Err(Failure::Exit(status.code(), stderr));
     }
-    let output = child.wait_with_output()?;
-    let val: Value = serde_json::from_slice(&output.stdout)?;
-    Ok(val)
+    let output = child.wait_with_output().map_err(Failure::Io)?;
+    Ok((status.code(), output.stdout))
+}
+
+/// Which output of a streamed program a chunk came from.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Pipe {
+    Stdout,
+    Stderr,
 }
 
+/// Run `program` and pass its stdout and stderr to `on_chunk` as they arrive,
+/// split on UTF-8 character boundaries. Setting `cancel` kills the child and
+/// returns an error, as does running past `timeout`; without one, streaming
+/// output is watched by the user, who can cancel. `secrets` are masked in the
+/// CLI log and errors.
+pub fn stream_program(
+    program: &str,
+    args: &[&str],
+    env: &[(String, String)],
+    secrets: &[String],
+    timeout: Option<Duration>,
+    cancel: &AtomicBool,
+    mut on_chunk: impl FnMut(Pipe, &str),
+) -> Result<()> {
+    let (program, args) = spawn_line(program, args);
+    let (program, args) = (program.as_str(), args.iter().map(String::as_str).collect::<Vec<_>>());
+    let args = args.as_slice();
+    let started = Instant::now();
+    // The start of the output, for the CLI log
+    let mut seen = String::new();
+    let result = stream_chunks(program, args, env, timeout, cancel, |pipe, chunk| {
+        if pipe == Pipe::Stdout && seen.len() < 4096 { seen.push_str(chunk); }
+        on_chunk(pipe, chunk);
+    });
+    logged(program, args, secrets, env, started, timeout, result.map(|code| (code, ())), |_| seen.clone())
+}
+
+fn stream_chunks(
+    program: &str,
+    args: &[&str],
+    env: &[(String, String)],
+    timeout: Option<Duration>,
+    cancel: &AtomicBool,
+    mut on_chunk: impl FnMut(Pipe, &str),
+) -> std::result::Result<Option<i32>, Failure> {
+    use std::io::Read;
+    let mut cmd = Command::new(program);
+    cmd.args(args).stdout(Stdio::piped()).stderr(Stdio::piped());
+    for (k, v) in env {
+        cmd.env(k, v);
+    }
+    let mut child = cmd.spawn().map_err(Failure::Spawn)?;
+    let deadline = timeout.map(|t| Instant::now() + t);
+    let (tx, rx) = mpsc::channel::<(Pipe, Vec<u8>)>();
+    let stdout: Box<dyn Read + Send> = Box::new(child.stdout.take().ok_or_else(|| Failure::Io(io::Error::other("no stdout")))?);
+    let stderr: Box<dyn Read + Send> = Box::new(child.stderr.take().ok_or_else(|| Failure::Io(io::Error::other("no stderr")))?);
+    for (pipe, mut reader) in [(Pipe::Stdout, stdout), (Pipe::Stderr, stderr)] {
+        let tx = tx.clone();
+        thread::spawn(move || {
+            let mut buf = [0u8; 256];
+            while let Ok(n) = reader.read(&mut buf) {
+                if n == 0 || tx.send((pipe, buf[..n].to_vec())).is_err() { break; }
+            }
+        });
+    }
+    drop(tx);
+    // Per pipe: bytes not yet passed on (an incomplete trailing character)
+    let mut pending: [Vec<u8>; 2] = Default::default();
+    let mut stderr_text = String::new();
+    let mut emit = |pipe: Pipe, bytes: &[u8], on_chunk: &mut dyn FnMut(Pipe, &str)| {
+        let text = String::from_utf8_lossy(bytes);
+        if pipe == Pipe::Stderr { stderr_text.push_str(&text); }
+        if !text.is_empty() { on_chunk(pipe, &text); }
+    };
+    loop {
+        if cancel.load(Ordering::Relaxed) || deadline.is_some_and(|d| Instant::now() >= d) {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(if cancel.load(Ordering::Relaxed) { Failure::Cancelled } else { Failure::TimedOut });
+        }
+        match rx.recv_timeout(Duration::from_millis(100)) {
+            Ok((pipe, bytes)) => {
+                let buf = &mut pending[pipe as usize];
+                buf.extend(bytes);
+                let valid = match std::str::from_utf8(buf) {
+                    Ok(s) => s.len(),
+                    Err(e) if e.error_len().is_none() => e.valid_up_to(),
+                    Err(_) => buf.len(),
+                };
+                let text: Vec<u8> = buf.drain(..valid).collect();
+                emit(pipe, &text, &mut on_chunk);
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+    }
+    for (pipe, rest) in [(Pipe::Stdout, &pending[0]), (Pipe::Stderr, &pending[1])] {
+        emit(pipe, rest, &mut on_chunk);
+    }
+    let status = child.wait().map_err(Failure::Io)?;
+    if !status.success() {
+        return Err(Failure::Exit(status.code(), stderr_text.trim().to_string()));
+    }
+    Ok(status.code())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interpreters_run_the_chi_llm_module() {
+        assert_eq!(ChiLlmCommand::new("/opt/venv/bin/python3.12").display(), "/opt/venv/bin/python3.12 -m chi_llm");
+        assert_eq!(ChiLlmCommand::new("/usr/local/bin/chi-llm").prefix, Vec::<String>::new());
+        let python = ChiLlmCommand::new("python3");
+        assert_eq!(python.spawn_line("chi-llm", &["models", "list"]), ("python3".to_string(), ["-m", "chi_llm", "models", "list"].map(String::from).to_vec()));
+        assert_eq!(python.spawn_line("curl", &["-s"]).0, "curl");
+        let err = resolve_chi_llm(Some("/nonexistent/bin/python3")).unwrap_err().to_string();
+        assert!(err.contains("'/nonexistent/bin/python3 -m chi_llm --version'"), "{err}");
+    }
+
+    #[test]
+    fn cli_cache_serves_fresh_answers_until_invalidated() {
+        assert_eq!(cli_cache_ttl(&["providers", "schema", "--json"]), Some(Duration::from_secs(600)));
+        assert_eq!(cli_cache_ttl(&["providers", "test", "p1"]), None);
+
+        let put = |args: &[&str], expires: Instant| {
+            let key = args.iter().map(|a| a.to_string()).collect();
+            CLI_CACHE.lock().unwrap().insert(key, (expires, Value::from(args.len())));
+        };
+        let later = Instant::now() + Duration::from_secs(60);
+        put(&["cache-test", "a"], later);
+        put(&["cache-test", "b"], later);
+        put(&["cache-test", "stale"], Instant::now());
+        assert_eq!(cached_cli_json(&["cache-test", "a"]), Some(Value::from(2)));
+        assert_eq!(cached_cli_json(&["cache-test", "stale"]), None);
+
+        invalidate_cli_cache(&["cache-test", "a"]);
+        assert_eq!(cached_cli_json(&["cache-test", "a"]), None);
+        assert!(cached_cli_json(&["cache-test", "b"]).is_some());
+        invalidate_cli_cache(&["cache-test"]);
+        assert!(cached_cli_json(&["cache-test", "b"]).is_none());
+    }
+}
@@ -0,0 +1,41 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::layout::Rect;
+use ratatui::prelude::Frame;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem};
+
+use crate::app::{App, WELCOME_ITEMS};
+use crate::controller::PageController;
+use crate::msg::Msg;
+
+pub struct WelcomePage;
+
+impl PageController for WelcomePage {
+    fn handle_key(&self, app: &mut App, key: KeyEvent) -> Option<Msg> {
+        match key.code {
+            KeyCode::Up => { app.menu_idx = app.menu_idx.saturating_sub(1); }
+            KeyCode::Down => { app.menu_idx = (app.menu_idx + 1).min(WELCOME_ITEMS.len() - 1); }
+            KeyCode::Enter => {
+                let (label, page) = WELCOME_ITEMS[app.menu_idx];
+                return Some(match label {
+                    "EXIT" => Msg::Quit,
+                    "Tutorial" => Msg::TutorialStart,
+                    _ => Msg::Goto(page),
+                });
+            }
+            _ => return None,
+        }
+        Some(Msg::Handled)
+    }
+
+    fn draw(&self, f: &mut Frame, area: Rect, app: &App) {
+        let items: Vec<ListItem> = WELCOME_ITEMS.iter().enumerate().map(|(i, (label, _))| {
+            let style = app.theme.row(i == app.menu_idx);
+            ListItem::new(Line::from(Span::styled(format!("{} {}", if i == app.menu_idx {"›"} else {" "}, label), style)))
+        }).collect();
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL).border_style(app.theme.border()).title("Welcome"))
+            .highlight_style(app.theme.highlight());
+        f.render_widget(list, area);
+    }
+}
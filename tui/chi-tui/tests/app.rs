@@ -0,0 +1,1282 @@
+use std::collections::HashMap;
+
+use chi_tui::controller::PageController;
+use chi_tui::events::AppEvent;
+use chi_tui::hooks::HookEvent;
+use chi_tui::msg::Msg;
+use chi_tui::page_data::PageData;
+use chi_tui::paths::{set_paths, AppPaths};
+use chi_tui::theme::ThemeMode;
+use chi_tui::welcome::WelcomePage;
+use chi_tui::focus::FocusRing;
+use chi_tui::forms::FieldSchema;
+use chi_tui::providers::{
+    ConfigurePage, DefaultHealth, DefaultProviderState, HealthStatus, ProviderEntry, ProviderScratchEntry, ProviderTestOutcome, ProvidersPane, ProvidersState, PullOutcome, RoutingState, TestMode,
+};
+use chi_tui::{App, Page};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::backend::TestBackend;
+use ratatui::Terminal;
+
+/// Point chi-tui's settings and state files at a temporary directory, so tests
+/// never read or write the user's own.
+fn isolate() {
+    set_paths(AppPaths::under(&std::env::temp_dir().join(format!("chi-tui-app-tests-{}", std::process::id()))));
+}
+
+fn new_app() -> App {
+    isolate();
+    App::new()
+}
+
+fn key(code: KeyCode) -> KeyEvent {
+    KeyEvent::new(code, KeyModifiers::NONE)
+}
+
+fn providers_with_one_ollama() -> ProvidersState {
+    let host = FieldSchema {
+        name: "host".to_string(),
+        ftype: "string".to_string(),
+        required: false,
+        default: None,
+        help: None,
+        options: None,
+    };
+    ProvidersState {
+        entries: vec![ProviderScratchEntry {
+            id: "p1".to_string(),
+            name: "ollama".to_string(),
+            ptype: "ollama".to_string(),
+            tags: Vec::new(),
+            disabled: false,
+            session_secrets: Vec::new(),
+            config: serde_json::json!({}),
+        }],
+        selected: 0,
+        schema_types: vec!["ollama".to_string()],
+        schema_map: HashMap::from([("ollama".to_string(), vec![host])]),
+        test_status: None,
+        form: None,
+        focus: FocusRing::new(&[ProvidersPane::List, ProvidersPane::Form]),
+        test_in_progress: false,
+        test_job: None,
+        discover_job: None,
+        pull_job: None,
+        show_json: false,
+        renamed_ids: Vec::new(),
+        list_history: Default::default(),
+        import_offered: true,
+    }
+}
+
+#[test]
+fn welcome_renders_and_exit_quits() {
+    let mut app = new_app();
+    let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+    terminal.draw(|f| chi_tui::ui::ui(f, &app)).unwrap();
+    let text: String = terminal.backend().buffer().content().iter().map(|c| c.symbol()).collect();
+    assert!(text.contains("Welcome"));
+
+    for _ in 0..20 { app.handle_key(key(KeyCode::Down)); }
+    app.handle_key(key(KeyCode::Enter));
+    assert!(app.should_quit);
+}
+
+#[test]
+fn page_keys_come_back_as_messages_for_update() {
+    let mut app = new_app();
+    app.menu_idx = chi_tui::app::WELCOME_ITEMS.len() - 1;
+    assert_eq!(WelcomePage.handle_key(&mut app, key(KeyCode::Enter)), Some(Msg::Quit));
+    assert!(!app.should_quit, "the page only asks; update quits");
+    assert_eq!(WelcomePage.handle_key(&mut app, key(KeyCode::Up)), Some(Msg::Handled));
+    assert_eq!(WelcomePage.handle_key(&mut app, key(KeyCode::Char('?'))), None);
+
+    app.page = Page::Configure;
+    app.providers = PageData::Ready(providers_with_one_ollama());
+    assert_eq!(ConfigurePage.handle_key(&mut app, key(KeyCode::Char('m'))), Some(Msg::Goto(Page::ModelBrowser)));
+    assert_eq!(app.page, Page::Configure);
+}
+
+#[test]
+fn pasted_text_lands_in_the_field_without_firing_shortcuts() {
+    let mut app = new_app();
+    app.page = Page::Configure;
+    app.providers = PageData::Ready(providers_with_one_ollama());
+
+    // On the list a paste is dropped, not replayed as `q`/`t` keys
+    app.handle_paste("qt");
+    assert!(!app.should_quit);
+    app.handle_key(key(KeyCode::Tab)); // focus form
+    for _ in 0..3 { app.handle_key(key(KeyCode::Down)); } // Name -> ID -> Type -> host
+    app.handle_paste("höst-ü.local\n");
+    app.handle_key(key(KeyCode::Left));
+    app.handle_key(key(KeyCode::Backspace));
+
+    assert!(!app.should_quit);
+    let form = app.providers.ready().unwrap().form.as_ref().unwrap();
+    assert!(form.editing);
+    assert_eq!(form.fields[0].buffer, "höst-ü.locl");
+}
+
+#[test]
+fn ctrl_x_stops_a_running_test_so_another_can_start() {
+    let mut app = new_app();
+    app.page = Page::Configure;
+    let mut st = providers_with_one_ollama();
+    st.test_job = Some(app.jobs.spawn("Testing ollama", |ctx| {
+        while !ctx.is_cancelled() { std::thread::sleep(std::time::Duration::from_millis(5)); }
+        ProviderTestOutcome {
+            entry_id: "p1".to_string(),
+            form_hash: None,
+            ok: false,
+            status: "Error: cancelled".to_string(),
+            tokens: None,
+            mode: TestMode::E2e,
+            elapsed: std::time::Duration::ZERO,
+            reply: None,
+        }
+    }));
+    st.test_in_progress = true;
+    app.providers = PageData::Ready(st);
+
+    app.handle_key(KeyEvent::new(KeyCode::Char('x'), KeyModifiers::CONTROL));
+    let st = app.providers.ready().unwrap();
+    assert!(!st.test_in_progress);
+    assert!(st.test_job.is_none());
+    assert_eq!(st.test_status.as_deref(), Some("Test cancelled"));
+    assert!(!st.entries[0].disabled, "Ctrl+X must not toggle maintenance mode");
+    // The worker sees the cancel flag and ends
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+    while app.jobs.is_busy() {
+        assert!(std::time::Instant::now() < deadline, "cancelled job kept running");
+        std::thread::sleep(std::time::Duration::from_millis(5));
+    }
+}
+
+#[test]
+fn typing_in_form_field_does_not_trigger_globals() {
+    let mut app = new_app();
+    app.page = Page::Configure;
+    app.providers = PageData::Ready(providers_with_one_ollama());
+
+    app.handle_key(key(KeyCode::Tab)); // focus form
+    for _ in 0..3 { app.handle_key(key(KeyCode::Down)); } // Name -> ID -> Type -> host
+    app.handle_key(key(KeyCode::Enter)); // start editing
+    for c in "q1s".chars() { app.handle_key(key(KeyCode::Char(c))); }
+
+    assert!(!app.should_quit);
+    assert_eq!(app.page, Page::Configure);
+    let form = app.providers.ready().unwrap().form.as_ref().unwrap();
+    assert_eq!(form.fields[0].buffer, "q1s");
+
+    app.handle_key(key(KeyCode::Esc)); // stop editing
+    app.handle_key(key(KeyCode::Esc)); // back to list
+    app.handle_key(key(KeyCode::Esc)); // back to welcome
+    assert_eq!(app.page, Page::Welcome);
+}
+
+#[test]
+fn delete_provider_asks_for_confirmation() {
+    let mut app = new_app();
+    app.page = Page::Configure;
+    app.providers = PageData::Ready(providers_with_one_ollama());
+
+    app.handle_key(key(KeyCode::Char('d')));
+    assert_eq!(app.modals.len(), 1);
+    app.handle_key(key(KeyCode::Esc)); // closes the popup, stays on the page
+    assert!(app.modals.is_empty());
+    assert_eq!(app.page, Page::Configure);
+    assert_eq!(app.providers.ready().unwrap().entries.len(), 1);
+
+    app.handle_key(key(KeyCode::Char('d')));
+    app.handle_key(key(KeyCode::Char('y')));
+    assert!(app.modals.is_empty());
+    assert!(app.providers.ready().unwrap().entries.is_empty());
+}
+
+#[test]
+fn raw_json_edit_validates_before_applying() {
+    let mut app = new_app();
+    app.page = Page::Configure;
+    app.providers = PageData::Ready(providers_with_one_ollama());
+    let ctrl_s = KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL);
+
+    app.handle_key(key(KeyCode::Char('e')));
+    assert_eq!(app.modals.len(), 1);
+    app.handle_key(key(KeyCode::End));
+    app.handle_key(key(KeyCode::Char('x'))); // "{}x"
+    app.handle_key(ctrl_s);
+    assert_eq!(app.modals.len(), 1, "invalid JSON keeps the editor open");
+
+    app.handle_key(key(KeyCode::Backspace));
+    app.handle_key(key(KeyCode::Left));
+    for c in "\"keep_alive\": 5".chars() { app.handle_key(key(KeyCode::Char(c))); }
+    app.handle_key(ctrl_s);
+    assert!(app.modals.is_empty());
+    let config = &app.providers.ready().unwrap().entries[0].config;
+    assert_eq!(config["keep_alive"], 5);
+    assert_eq!(config["type"], "ollama");
+}
+
+#[test]
+fn raw_json_edit_starts_from_unsaved_form_values() {
+    let mut app = new_app();
+    app.page = Page::Configure;
+    let mut st = providers_with_one_ollama();
+    st.ensure_form();
+    st.form.as_mut().unwrap().set_field(0, "studio.local".to_string());
+    app.providers = PageData::Ready(st);
+
+    app.handle_key(key(KeyCode::Char('e')));
+    app.handle_key(KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL));
+    assert!(app.modals.is_empty());
+    let st = app.providers.ready().unwrap();
+    assert_eq!(st.entries[0].config["host"], "studio.local");
+    assert_eq!(st.form.as_ref().unwrap().fields[0].buffer, "studio.local");
+}
+
+#[test]
+fn picked_model_is_applied_on_next_poll() {
+    let mut app = new_app();
+    app.page = Page::ModelBrowser;
+    app.providers = PageData::Ready(providers_with_one_ollama());
+
+    app.emit(AppEvent::ModelPicked("llama3".to_string()));
+    app.emit(AppEvent::Navigate(Page::Configure));
+    app.poll_background();
+
+    assert_eq!(app.page, Page::Configure);
+    assert!(app.events.is_empty());
+    let st = app.providers.ready().unwrap();
+    assert_eq!(st.entries[0].config["model"], "llama3");
+}
+
+#[test]
+fn ollama_pull_offers_the_current_model_and_selects_the_pulled_one() {
+    let mut app = new_app();
+    app.page = Page::Configure;
+    let mut st = providers_with_one_ollama();
+    st.entries[0].config = serde_json::json!({"model": "llama3"});
+    let model = FieldSchema { name: "model".to_string(), ftype: "string".to_string(), required: false, default: None, help: None, options: None };
+    st.schema_map.get_mut("ollama").unwrap().push(model);
+    app.providers = PageData::Ready(st);
+
+    app.handle_key(key(KeyCode::Char('P')));
+    assert_eq!(app.modals.len(), 1);
+    let mut terminal = Terminal::new(TestBackend::new(120, 30)).unwrap();
+    terminal.draw(|f| chi_tui::ui::ui(f, &app)).unwrap();
+    find_on_screen(&terminal, "ollama pull (model name)");
+    find_on_screen(&terminal, "llama3");
+    app.handle_key(key(KeyCode::Esc));
+
+    // While a pull runs, P does not start another
+    let (finish, finished) = std::sync::mpsc::channel::<()>();
+    let job = app.jobs.spawn("Pulling qwen2.5", move |_| {
+        let _ = finished.recv();
+        PullOutcome { entry_id: "p1".to_string(), model: "qwen2.5".to_string(), ok: true }
+    });
+    app.providers.ready_mut().unwrap().pull_job = Some(job);
+    app.handle_key(key(KeyCode::Char('P')));
+    assert!(app.modals.is_empty());
+
+    // The pulled model lands in the form
+    finish.send(()).unwrap();
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+    while app.providers.ready().unwrap().pull_job.is_some() {
+        assert!(std::time::Instant::now() < deadline, "pull never finished");
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        app.poll_background();
+    }
+    let form = app.providers.ready().unwrap().form.as_ref().expect("form opened for the pulled model");
+    let field = form.fields.iter().find(|f| f.schema.name == "model").unwrap();
+    assert_eq!(field.value(), "qwen2.5");
+}
+
+#[test]
+fn settings_lists_file_locations() {
+    let mut app = new_app();
+    app.page = Page::Settings;
+    let mut terminal = Terminal::new(TestBackend::new(120, 50)).unwrap();
+    terminal.draw(|f| chi_tui::ui::ui(f, &app)).unwrap();
+    let text: String = terminal.backend().buffer().content().iter().map(|c| c.symbol()).collect();
+    assert!(text.contains("File locations"));
+    assert!(text.contains("chi-tui"));
+    assert!(text.contains("chi.tmp.json"));
+    assert!(text.contains("Tick rate: 100 ms"));
+}
+
+#[test]
+fn a11y_mode_renders_plain_text_and_announces_popups() {
+    let mut app = new_app();
+    app.a11y = true;
+    app.page = Page::Settings;
+    app.poll_background();
+    app.handle_key(key(KeyCode::Char('?')));
+    app.poll_background();
+    let mut terminal = Terminal::new(TestBackend::new(120, 30)).unwrap();
+    terminal.draw(|f| chi_tui::ui::ui(f, &app)).unwrap();
+    let text: String = terminal.backend().buffer().content().iter().map(|c| c.symbol()).collect();
+    assert!(text.contains("Settings page"));
+    assert!(text.contains("Help opened"));
+    assert!(!text.contains('│') && !text.contains('─'));
+}
+
+#[test]
+fn a_blank_test_prompt_restores_the_built_in_one() {
+    let mut app = new_app();
+    app.settings.test.prompt = Some("Say hi".into());
+    app.page = Page::Settings;
+    app.handle_key(key(KeyCode::Char('p')));
+    for _ in 0..80 { app.handle_key(key(KeyCode::Backspace)); }
+    app.handle_key(key(KeyCode::Char(' ')));
+    app.handle_key(key(KeyCode::Enter));
+    assert!(app.modals.is_empty());
+    assert_eq!(app.settings.test.prompt, None);
+    assert_eq!(chi_tui::providers::e2e_prompt(), "Reply with the single word: ok");
+}
+
+#[test]
+fn macro_hotkeys_are_left_alone_while_typing() {
+    let mut app = new_app();
+    let ctrl_r = KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL);
+    app.page = Page::Settings;
+    app.handle_key(key(KeyCode::Char('p')));
+    app.handle_key(ctrl_r);
+    assert!(app.macros.recording.is_none(), "a prompt is open");
+
+    app.handle_key(key(KeyCode::Esc));
+    app.handle_key(ctrl_r);
+    assert!(app.macros.recording.is_some());
+}
+
+#[test]
+fn macro_replay_feeds_keys_and_any_key_stops_it() {
+    let mut app = new_app();
+    app.macros.replay = [key(KeyCode::Char('s')), key(KeyCode::Char('t')), key(KeyCode::Esc)].into();
+
+    app.poll_background();
+    app.poll_background();
+    assert_eq!(app.page, Page::Settings);
+    assert_eq!(app.theme.mode, ThemeMode::Light);
+
+    app.handle_key(key(KeyCode::Char('x')));
+    assert!(!app.macros.is_replaying());
+    app.poll_background();
+    assert_eq!(app.page, Page::Settings);
+}
+
+#[cfg(unix)]
+#[test]
+fn write_hook_output_reaches_log_panel() {
+    let mut app = new_app();
+    app.settings.hooks.after_write = vec!["echo \"wrote $CHI_TUI_FILE\"".to_string()];
+    app.run_hooks(HookEvent::Write { path: ".chi_llm.json".to_string() });
+
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+    while !app.log.iter().any(|l| l.text.starts_with("Hook ok")) && std::time::Instant::now() < deadline {
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        app.poll_log();
+    }
+    assert!(app.log.iter().any(|l| l.text == "wrote .chi_llm.json"));
+    assert!(app.log.iter().any(|l| l.text.starts_with("Hook ok")));
+}
+
+#[test]
+fn dropdown_lists_recent_choices_first() {
+    let mut app = new_app();
+    app.recent = chi_tui::recent::RecentPicks::default();
+    app.recent.record("mode", "gamma");
+    let items = ["alpha", "beta", "gamma"].map(String::from).to_vec();
+    app.modals.push(Box::new(chi_tui::modal::DropdownModal::new("Select mode", items, "", |_, _| {}).remember("mode")));
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+    terminal.draw(|f| chi_tui::ui::ui(f, &app)).unwrap();
+    let text: String = terminal.backend().buffer().content().iter().map(|c| c.symbol()).collect();
+    let at = |s: &str| text.find(s).unwrap_or_else(|| panic!("{s} not rendered"));
+    assert!(at("Recent") < at("gamma") && at("gamma") < at("All") && at("All") < at("alpha"));
+}
+
+#[test]
+fn marked_items_sort_first_and_r_filters_to_them() {
+    let mut app = new_app();
+    let items = ["phi", "qwen", "llama"].map(String::from).to_vec();
+    let downloaded = ["llama".to_string()].into_iter().collect();
+    let (picked_tx, picked) = std::sync::mpsc::channel();
+    let dd = chi_tui::modal::DropdownModal::new("Select model", items, "", move |_, v| picked_tx.send(v).unwrap()).mark("downloaded", downloaded);
+    assert_eq!(dd.items, ["llama", "phi", "qwen"]);
+    app.modals.push(Box::new(dd));
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+    terminal.draw(|f| chi_tui::ui::ui(f, &app)).unwrap();
+    let text: String = terminal.backend().buffer().content().iter().map(|c| c.symbol()).collect();
+    assert!(text.contains("llama  [downloaded]"));
+
+    app.handle_key(key(KeyCode::Char('r')));
+    app.handle_key(key(KeyCode::Down));
+    app.handle_key(key(KeyCode::Enter));
+    assert_eq!(picked.try_recv().as_deref(), Ok("llama"));
+}
+
+#[test]
+fn header_shows_default_provider_health() {
+    let mut app = new_app();
+    app.health = Some(DefaultHealth { name: "ollama".to_string(), status: HealthStatus::Ok, detail: "ollama: 3 models".to_string() });
+    let mut terminal = Terminal::new(TestBackend::new(120, 30)).unwrap();
+    terminal.draw(|f| chi_tui::ui::ui(f, &app)).unwrap();
+    let text: String = terminal.backend().buffer().content().iter().map(|c| c.symbol()).collect();
+    assert!(text.contains("● ollama: ok"));
+}
+
+#[test]
+fn provider_list_shows_last_test_latency() {
+    let mut app = new_app();
+    app.page = Page::Configure;
+    app.providers = PageData::Ready(providers_with_one_ollama());
+    app.latency = chi_tui::providers::LatencyBook::default();
+    app.latency.record("p1", std::time::Duration::from_millis(1234), false);
+    let mut terminal = Terminal::new(TestBackend::new(120, 30)).unwrap();
+    terminal.draw(|f| chi_tui::ui::ui(f, &app)).unwrap();
+    let text: String = terminal.backend().buffer().content().iter().map(|c| c.symbol()).collect();
+    assert!(text.contains("ollama [ollama]  1.2s"));
+}
+
+#[test]
+fn routing_matrix_toggles_with_space_and_lists_routes() {
+    let mut app = new_app();
+    app.page = Page::Routing;
+    let entry = |id: &str, tags: &[&str]| ProviderEntry {
+        id: id.to_string(),
+        name: id.to_string(),
+        ptype: "openai".to_string(),
+        tags: tags.iter().map(|t| t.to_string()).collect(),
+        disabled: false,
+        model: None,
+    };
+    app.routing = Some(RoutingState::new(vec![entry("gpt", &["code", "chat"]), entry("mini", &["cheap"])]));
+    app.handle_key(key(KeyCode::Down));
+    app.handle_key(key(KeyCode::Char(' ')));
+    let mut terminal = Terminal::new(TestBackend::new(100, 20)).unwrap();
+    terminal.draw(|f| chi_tui::ui::ui(f, &app)).unwrap();
+    let text: String = terminal.backend().buffer().content().iter().map(|c| c.symbol()).collect();
+    assert!(text.contains("Routing (tag → provider) *"));
+    assert!(text.contains("code → gpt, mini"));
+    assert!(text.contains("cheap → mini"));
+}
+
+#[test]
+fn wide_provider_names_keep_the_routing_columns_aligned() {
+    let mut app = new_app();
+    app.page = Page::Routing;
+    let entry = |name: &str| ProviderEntry { id: name.to_string(), name: name.to_string(), ptype: "openai".to_string(), tags: vec!["chat".to_string()], disabled: false, model: None };
+    app.routing = Some(RoutingState::new(vec![entry("gpt"), entry("通义千问"), entry("🤖 bot")]));
+    let mut terminal = Terminal::new(TestBackend::new(100, 20)).unwrap();
+    terminal.draw(|f| chi_tui::ui::ui(f, &app)).unwrap();
+    let buf = terminal.backend().buffer();
+    // Display column of the first checkbox on the row showing `name`
+    let checkbox_col = |name: &str| (0..buf.area.height).find_map(|y| {
+        let row: Vec<&str> = (0..buf.area.width).map(|x| buf.get(x, y).symbol()).collect();
+        if !row.concat().contains(name) { return None; }
+        row.iter().position(|c| *c == "[")
+    });
+    let col = checkbox_col("gpt").expect("gpt row");
+    assert_eq!(checkbox_col("通"), Some(col));
+    assert_eq!(checkbox_col("bot"), Some(col));
+}
+
+#[test]
+fn disabled_provider_cannot_become_default() {
+    let mut app = new_app();
+    app.page = Page::SelectDefault;
+    app.defaultp = Some(DefaultProviderState {
+        providers: vec![ProviderEntry { id: "p1".into(), name: "broken".into(), ptype: "openai".into(), tags: Vec::new(), disabled: true, model: None }],
+        ..DefaultProviderState::default()
+    });
+    app.handle_key(key(KeyCode::Enter));
+    assert_eq!(app.defaultp.as_ref().unwrap().current_default_id, None);
+    let mut terminal = Terminal::new(TestBackend::new(100, 20)).unwrap();
+    terminal.draw(|f| chi_tui::ui::ui(f, &app)).unwrap();
+    let text: String = terminal.backend().buffer().content().iter().map(|c| c.symbol()).collect();
+    assert!(text.contains("broken [openai]  [disabled]"));
+}
+
+#[cfg(unix)]
+#[test]
+fn streamed_output_arrives_in_chunks_and_keeps_characters_whole() {
+    isolate();
+    use std::sync::atomic::AtomicBool;
+    let cancel = AtomicBool::new(false);
+    let mut chunks: Vec<String> = Vec::new();
+    // "é" written one byte at a time must not be split into replacement chars
+    let script = "printf 'Hel'; sleep 0.2; printf '\\303'; sleep 0.2; printf '\\251!'";
+    chi_tui::util::stream_program("sh", &["-c", script], &[], &[], None, &cancel, |_, s| chunks.push(s.to_string())).unwrap();
+    assert!(chunks.len() >= 2);
+    assert_eq!(chunks.concat(), "Helé!");
+    let err = chi_tui::util::stream_program("sh", &["-c", "echo boom >&2; exit 3"], &[], &[], None, &cancel, |_, _| {}).unwrap_err();
+    assert!(err.to_string().contains("boom"));
+}
+
+#[cfg(unix)]
+#[test]
+fn streamed_programs_pass_on_stderr_and_stop_at_the_timeout() {
+    isolate();
+    use chi_tui::util::{stream_program, Pipe};
+    use std::sync::atomic::AtomicBool;
+    let cancel = AtomicBool::new(false);
+    let mut seen = Vec::new();
+    stream_program("sh", &["-c", "echo out; echo err >&2"], &[], &[], None, &cancel, |pipe, s| seen.push((pipe, s.trim().to_string()))).unwrap();
+    assert!(seen.contains(&(Pipe::Stdout, "out".to_string())), "{:?}", seen);
+    assert!(seen.contains(&(Pipe::Stderr, "err".to_string())), "{:?}", seen);
+
+    let slow = stream_program("sh", &["-c", "sleep 5"], &[], &[], Some(std::time::Duration::from_millis(200)), &cancel, |_, _| {});
+    assert!(slow.unwrap_err().to_string().contains("timed out"));
+}
+
+#[test]
+fn json_preview_pane_shows_the_written_block_with_secrets_masked() {
+    let mut app = new_app();
+    app.page = Page::Configure;
+    let mut st = providers_with_one_ollama();
+    st.entries[0].config = serde_json::json!({"host": "10.0.0.2", "api_key": "sk-x"});
+    app.providers = PageData::Ready(st);
+    app.handle_key(key(KeyCode::Char('v')));
+    let mut terminal = Terminal::new(TestBackend::new(160, 30)).unwrap();
+    terminal.draw(|f| chi_tui::ui::ui(f, &app)).unwrap();
+    let text: String = terminal.backend().buffer().content().iter().map(|c| c.symbol()).collect();
+    assert!(text.contains(".chi_llm.json preview"));
+    assert!(text.contains("\"host\": \"10.0.0.2\""));
+    assert!(!text.contains("sk-x"));
+}
+
+/// Column and row where `needle` starts on screen.
+fn find_on_screen(terminal: &Terminal<TestBackend>, needle: &str) -> (u16, u16) {
+    let buf = terminal.backend().buffer();
+    for y in 0..buf.area.height {
+        let line: String = (0..buf.area.width).map(|x| buf.get(x, y).symbol()).collect();
+        if let Some(i) = line.find(needle) { return (line[..i].chars().count() as u16, y); }
+    }
+    panic!("{needle:?} not on screen");
+}
+
+#[test]
+fn clicks_select_providers_and_press_form_buttons() {
+    isolate();
+    use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
+    let click = |(column, row): (u16, u16)| MouseEvent { kind: MouseEventKind::Down(MouseButton::Left), column, row, modifiers: KeyModifiers::NONE };
+    let mut app = new_app();
+    app.page = Page::Configure;
+    let mut st = providers_with_one_ollama();
+    st.selected = 1;
+    app.providers = PageData::Ready(st);
+    let mut terminal = Terminal::new(TestBackend::new(120, 30)).unwrap();
+    terminal.draw(|f| chi_tui::ui::ui(f, &app)).unwrap();
+
+    app.handle_mouse(click(find_on_screen(&terminal, "ollama [ollama]")));
+    let st = app.providers.ready().unwrap();
+    assert_eq!(st.selected, 0);
+    assert!(st.form.is_some() && !st.form_focused());
+
+    terminal.draw(|f| chi_tui::ui::ui(f, &app)).unwrap();
+    app.handle_mouse(click(find_on_screen(&terminal, "[ Save ]")));
+    let st = app.providers.ready().unwrap();
+    assert!(st.form_focused());
+    assert_eq!(st.form.as_ref().unwrap().message.as_deref(), Some("Saved"));
+}
+
+#[test]
+fn new_from_selected_asks_for_the_differing_fields() {
+    let mut app = new_app();
+    app.page = Page::Configure;
+    let mut st = providers_with_one_ollama();
+    st.entries[0].tags = vec!["chat".to_string()];
+    app.providers = PageData::Ready(st);
+    app.handle_key(key(KeyCode::Char('n')));
+    assert_eq!(app.modals.len(), 1);
+    // name, then host: edit the host and press Create
+    for code in [KeyCode::Down, KeyCode::Enter, KeyCode::Char('g'), KeyCode::Char('p'), KeyCode::Char('u'), KeyCode::Enter, KeyCode::Down, KeyCode::Enter] {
+        app.handle_key(key(code));
+    }
+    assert!(app.modals.is_empty());
+    let st = app.providers.ready().unwrap();
+    let copy = &st.entries[1];
+    assert_eq!((copy.name.as_str(), copy.config["host"].as_str()), ("ollama copy", Some("gpu")));
+    assert_ne!(copy.id, st.entries[0].id);
+    assert_eq!(copy.tags, ["chat"]);
+    assert_eq!(st.selected, 1);
+    assert!(st.form_focused());
+}
+
+#[test]
+fn clone_copies_the_selected_provider_under_a_free_name() {
+    let mut app = new_app();
+    app.page = Page::Configure;
+    let mut st = providers_with_one_ollama();
+    st.entries[0].config = serde_json::json!({"host": "gpu"});
+    app.providers = PageData::Ready(st);
+    app.handle_key(key(KeyCode::Char('c')));
+    let st = app.providers.ready_mut().unwrap();
+    let copy = &st.entries[1];
+    assert_eq!((copy.id.as_str(), copy.name.as_str(), copy.config["host"].as_str()), ("p2", "ollama copy", Some("gpu")));
+    assert!(st.form_focused());
+    st.focus.focus(ProvidersPane::List);
+    st.selected = 0;
+    app.handle_key(key(KeyCode::Char('c')));
+    let st = app.providers.ready().unwrap();
+    assert_eq!((st.entries[2].name.as_str(), st.selected), ("ollama copy 2", 2));
+}
+
+#[test]
+fn name_and_id_are_editable_form_rows() {
+    let mut app = new_app();
+    app.page = Page::Configure;
+    let mut st = providers_with_one_ollama();
+    st.entries[0].config = serde_json::json!({"host": "gpu"});
+    app.providers = PageData::Ready(st);
+    app.handle_key(key(KeyCode::Char('c')));
+    app.handle_key(key(KeyCode::Enter)); // Name
+    for _ in 0.."ollama copy".len() { app.handle_key(key(KeyCode::Backspace)); }
+    for c in "work".chars() { app.handle_key(key(KeyCode::Char(c))); }
+    app.handle_key(key(KeyCode::Enter));
+    app.handle_key(key(KeyCode::Down));
+    app.handle_key(key(KeyCode::Enter)); // ID, taken
+    for code in [KeyCode::Backspace, KeyCode::Char('1'), KeyCode::Enter] { app.handle_key(key(code)); }
+    let st = app.providers.ready().unwrap();
+    assert_eq!((st.entries[1].name.as_str(), st.entries[1].id.as_str()), ("work", "p2"));
+    assert_eq!(st.form.as_ref().unwrap().message.as_deref(), Some("Id 'p1' is already used"));
+
+    app.handle_key(key(KeyCode::Enter));
+    for _ in 0..2 { app.handle_key(key(KeyCode::Backspace)); }
+    for c in "work-gpu".chars() { app.handle_key(key(KeyCode::Char(c))); }
+    app.handle_key(key(KeyCode::Enter));
+    let st = app.providers.ready().unwrap();
+    assert_eq!(st.entries[1].id, "work-gpu");
+    assert_eq!(st.renamed_ids, [("p2".to_string(), "work-gpu".to_string())]);
+    assert!(st.form.as_ref().unwrap().lead[1].starts_with("ID: work-gpu"));
+}
+
+#[test]
+fn tutorial_follows_the_real_pages() {
+    let mut app = new_app();
+    app.providers = PageData::Ready(providers_with_one_ollama());
+    app.handle_key(key(KeyCode::Enter));
+    assert_eq!((app.page, app.tutorial.as_ref().map(|t| t.step)), (Page::Configure, Some(0)));
+
+    app.handle_key(key(KeyCode::Char('c')));
+    app.poll_background();
+    assert_eq!(app.tutorial.as_ref().unwrap().step, 1);
+    // Skip testing and saving
+    for _ in 0..2 { app.handle_key(KeyEvent::new(KeyCode::Char('n'), KeyModifiers::CONTROL)); }
+    assert_eq!(app.page, Page::SelectDefault);
+
+    let mut terminal = Terminal::new(TestBackend::new(120, 30)).unwrap();
+    app.page = Page::Build;
+    terminal.draw(|f| chi_tui::ui::ui(f, &app)).unwrap();
+    let text: String = terminal.backend().buffer().content().iter().map(|c| c.symbol()).collect();
+    assert!(text.contains("Tutorial 4/5: Make it the default"));
+    assert!(text.contains("This step is on the Select Default page."));
+
+    app.handle_key(KeyEvent::new(KeyCode::Char('t'), KeyModifiers::CONTROL));
+    assert!(app.tutorial.is_none());
+}
+
+#[test]
+fn ui_goes_idle_without_input_unless_work_is_running() {
+    let mut app = new_app();
+    assert!(!app.is_idle());
+    app.last_input = std::time::Instant::now().checked_sub(std::time::Duration::from_secs(120)).unwrap();
+    assert!(app.is_idle());
+    let job = app.jobs.spawn("Waiting", |ctx| while !ctx.is_cancelled() { std::thread::sleep(std::time::Duration::from_millis(5)) });
+    assert!(!app.is_idle());
+    job.cancel();
+    while app.jobs.is_busy() { std::thread::sleep(std::time::Duration::from_millis(5)); }
+    app.settings.idle_after_secs = Some(0);
+    assert!(!app.is_idle());
+}
+
+#[test]
+fn footer_shows_only_the_actions_valid_now() {
+    let mut app = new_app();
+    app.page = Page::Configure;
+    let mut st = providers_with_one_ollama();
+    st.test_job = Some(app.jobs.spawn("Testing ollama", |ctx| {
+        while !ctx.is_cancelled() { std::thread::sleep(std::time::Duration::from_millis(5)); }
+        ProviderTestOutcome {
+            entry_id: "p1".to_string(),
+            form_hash: None,
+            ok: false,
+            status: "Error: cancelled".to_string(),
+            tokens: None,
+            mode: TestMode::Connection,
+            elapsed: std::time::Duration::ZERO,
+            reply: None,
+        }
+    }));
+    app.providers = PageData::Ready(st);
+    let footer = |app: &App| chi_tui::controller::controller(app.page).footer(app);
+
+    let list = footer(&app);
+    assert!(list.contains("Ctrl+X cancel test") && list.contains("P ollama pull"), "{}", list);
+    assert!(!list.contains("t test") && !list.contains("$ budget") && !list.contains("l log"), "{}", list);
+    app.handle_key(KeyEvent::new(KeyCode::Char('x'), KeyModifiers::CONTROL));
+    assert!(footer(&app).contains("t test") && !footer(&app).contains("cancel test"));
+
+    app.handle_key(key(KeyCode::Tab)); // focus form
+    assert!(!footer(&app).contains("[ Save ]"), "clean form offers no save");
+    for _ in 0..3 { app.handle_key(key(KeyCode::Down)); } // Name -> ID -> Type -> host
+    app.handle_key(key(KeyCode::Enter));
+    app.handle_key(key(KeyCode::Char('h')));
+    app.handle_key(key(KeyCode::Enter));
+    let form = footer(&app);
+    assert!(form.contains("[ Save ] keep edits") && form.contains("r revert field") && !form.contains("a add"), "{}", form);
+}
+
+#[test]
+fn ctrl_z_undoes_provider_add_and_delete_on_the_list() {
+    let mut app = new_app();
+    app.page = Page::Configure;
+    app.providers = PageData::Ready(providers_with_one_ollama());
+    let ctrl = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL);
+    let ids = |app: &App| app.providers.ready().unwrap().entries.iter().map(|e| e.id.clone()).collect::<Vec<_>>();
+
+    app.handle_key(key(KeyCode::Char('d')));
+    app.handle_key(key(KeyCode::Char('y'))); // confirm
+    assert!(ids(&app).is_empty());
+    app.handle_key(ctrl('z'));
+    assert_eq!(ids(&app), ["p1"]);
+    app.handle_key(ctrl('y'));
+    assert!(ids(&app).is_empty());
+    app.handle_key(ctrl('z'));
+
+    app.handle_key(key(KeyCode::Char('c'))); // clone, opens the form
+    app.handle_key(key(KeyCode::Esc)); // back to the list
+    assert_eq!(ids(&app).len(), 2);
+    app.handle_key(ctrl('z'));
+    assert_eq!(ids(&app), ["p1"]);
+    assert!(app.modals.is_empty(), "Ctrl+Y must not copy JSON");
+}
+
+#[test]
+fn w_on_diagnostics_toggles_watch_mode() {
+    let mut app = new_app();
+    app.page = Page::Diagnostics;
+    app.diag = PageData::Ready(chi_tui::diagnostics::DiagState {
+        summary: vec!["python: 3.12".to_string()],
+        diagnostics: serde_json::json!({}),
+        model_explain: serde_json::json!({}),
+        saved_path: None,
+        updated: chrono::Local::now(),
+    });
+    app.reach = PageData::Ready(Vec::new());
+    let footer = |app: &App| chi_tui::controller::controller(app.page).footer(app);
+
+    app.handle_key(key(KeyCode::Char('w')));
+    assert_eq!(app.page, Page::Diagnostics, "w watches here instead of opening What's New");
+    let watch = app.diag_watch.as_ref().unwrap();
+    assert!(!watch.runs.is_running(), "fresh results wait one interval before refreshing");
+    assert!(footer(&app).contains("w stop watching"));
+    let mut terminal = Terminal::new(TestBackend::new(120, 30)).unwrap();
+    terminal.draw(|f| chi_tui::ui::ui(f, &app)).unwrap();
+    let text: String = terminal.backend().buffer().content().iter().map(|c| c.symbol()).collect();
+    assert!(text.contains("Last updated: ") && text.contains("watching every 10s"));
+    assert!(text.contains("No providers in chi.tmp.json."));
+
+    app.handle_key(key(KeyCode::Char('w')));
+    assert!(app.diag_watch.is_none());
+    assert!(footer(&app).contains("w watch"));
+}
+
+#[test]
+fn v_on_diagnostics_opens_the_raw_json_viewer() {
+    let mut app = new_app();
+    app.page = Page::Diagnostics;
+    app.diag = PageData::Ready(chi_tui::diagnostics::DiagState {
+        summary: vec!["python: 3.12".to_string()],
+        diagnostics: serde_json::json!({"python": {"version": "3.12"}}),
+        model_explain: serde_json::json!({}),
+        saved_path: None,
+        updated: chrono::Local::now(),
+    });
+    app.reach = PageData::Ready(Vec::new());
+    let mut terminal = Terminal::new(TestBackend::new(120, 30)).unwrap();
+    let mut screen = |app: &App| {
+        terminal.draw(|f| chi_tui::ui::ui(f, app)).unwrap();
+        terminal.backend().buffer().content().iter().map(|c| c.symbol()).collect::<String>()
+    };
+
+    app.handle_key(key(KeyCode::Char('v')));
+    assert_eq!(app.modals.len(), 1);
+    let text = screen(&app);
+    assert!(text.contains("Diagnostics: raw JSON") && text.contains("\"model_explain\": {}"), "{}", text);
+    assert!(!text.contains("\"version\""), "deeper levels start folded: {}", text);
+
+    // E unfolds everything, C folds it again
+    app.handle_key(key(KeyCode::Char('E')));
+    assert!(screen(&app).contains("\"version\": \"3.12\""));
+    app.handle_key(key(KeyCode::Char('C')));
+    assert!(!screen(&app).contains("\"version\""));
+}
+
+#[test]
+fn model_search_takes_every_key_until_enter() {
+    let mut app = new_app();
+    app.page = Page::ModelBrowser;
+    let model = |id: &str| chi_tui::models::ModelEntry {
+        id: id.to_string(),
+        name: id.to_string(),
+        size: None,
+        file_size_mb: None,
+        context_window: None,
+        tags: Vec::new(),
+        downloaded: false,
+        current: false,
+        raw: serde_json::Value::Null,
+    };
+    let mut mb = chi_tui::models::ModelBrowser {
+        entries: vec![model("phi-3-mini"), model("qwen3-4b")],
+        filtered: Vec::new(),
+        selected: 0,
+        downloaded_only: false,
+        tag_filter: None,
+        show_info: false,
+        all_tags: Vec::new(),
+        search: String::new(),
+        searching: false,
+    };
+    mb.compute_filtered();
+    app.model = PageData::Ready(mb);
+
+    for c in "/qwen".chars() { app.handle_key(key(KeyCode::Char(c))); }
+    assert!(!app.should_quit, "q is typed into the search");
+    app.handle_key(key(KeyCode::Enter));
+    let mb = app.model.ready().unwrap();
+    assert_eq!((mb.search.as_str(), mb.searching, mb.current_entry().unwrap().id.as_str()), ("qwen", false, "qwen3-4b"));
+    app.handle_key(key(KeyCode::Esc));
+    assert_eq!((app.page, app.model.ready().unwrap().filtered.len()), (Page::ModelBrowser, 2));
+}
+
+#[test]
+fn custom_gguf_sets_model_path_on_a_local_provider_only() {
+    let mut app = new_app();
+    app.page = Page::ModelBrowser;
+    app.providers = PageData::Ready(providers_with_one_ollama());
+    let custom = chi_tui::custom_models::CustomModel {
+        id: "custom/mine".to_string(),
+        name: "mine".to_string(),
+        path: "/models/mine.gguf".to_string(),
+        file_size_mb: None,
+    };
+    let mut mb = chi_tui::models::ModelBrowser {
+        entries: Vec::new(),
+        filtered: Vec::new(),
+        selected: 0,
+        downloaded_only: false,
+        tag_filter: None,
+        show_info: false,
+        all_tags: Vec::new(),
+        search: String::new(),
+        searching: false,
+    };
+    mb.add_items(&[custom.to_list_item()]);
+    assert_eq!(mb.all_tags, ["custom"]);
+    app.model = PageData::Ready(mb);
+
+    app.handle_key(key(KeyCode::Enter));
+    app.poll_background();
+    assert!(app.providers.ready().unwrap().entries[0].config.get("model_path").is_none(), "ollama has no model_path");
+    assert!(app.toasts.latest().is_some_and(|t| !t.ok && t.message.contains("select a local one")));
+
+    app.page = Page::ModelBrowser;
+    app.providers.ready_mut().unwrap().entries[0].ptype = "local-custom".to_string();
+    app.handle_key(key(KeyCode::Enter));
+    app.poll_background();
+    assert_eq!(app.page, Page::Configure);
+    assert_eq!(app.providers.ready().unwrap().entries[0].config["model_path"], "/models/mine.gguf");
+}
+
+#[test]
+fn docs_position_is_kept_across_pages_and_recorded_for_restarts() {
+    let mut app = new_app();
+    app.settings.docs.remember = Some(true);
+    app.page = Page::Readme;
+    while app.readme.ready().is_none() {
+        app.poll_background();
+        std::thread::sleep(std::time::Duration::from_millis(5));
+    }
+    for _ in 0..3 { app.handle_key(key(KeyCode::Down)); }
+    app.handle_key(key(KeyCode::Char('h')));
+    app.handle_key(key(KeyCode::Char('2')));
+    app.poll_background();
+    assert_eq!((app.settings.docs.scroll, app.settings.docs.show_toc), (Some(3), Some(true)));
+    assert_eq!(app.settings.docs.path.as_deref(), Some("README.md"));
+
+    app.handle_key(key(KeyCode::Char('1')));
+    assert_eq!(app.readme.ready().map(|rm| (rm.scroll, rm.show_toc)), Some((3, true)));
+
+    // A fresh start lands at the saved position
+    let mut rm = chi_tui::readme::load_readme();
+    rm.restore(&app.settings.docs);
+    assert_eq!((rm.scroll, rm.show_toc), (3, true));
+}
+
+#[test]
+fn docs_position_is_not_recorded_unless_turned_on() {
+    let mut app = new_app();
+    app.page = Page::Readme;
+    while app.readme.ready().is_none() {
+        app.poll_background();
+        std::thread::sleep(std::time::Duration::from_millis(5));
+    }
+    for _ in 0..3 { app.handle_key(key(KeyCode::Down)); }
+    app.handle_key(key(KeyCode::Char('2')));
+    app.poll_background();
+    assert_eq!(app.settings.docs, Default::default());
+
+    // `d` on Settings turns it on; the next tick away from Docs records it
+    app.page = Page::Settings;
+    app.handle_key(key(KeyCode::Char('d')));
+    app.poll_background();
+    assert!(app.settings.docs.remembers());
+    assert_eq!(app.settings.docs.scroll, Some(3));
+}
+
+#[test]
+fn benchmarks_page_lists_results_with_the_fastest_called_out() {
+    isolate();
+    use chi_tui::bench::{BenchResult, BenchRun};
+    let mut app = new_app();
+    app.handle_key(key(KeyCode::Char('6')));
+    assert_eq!(app.page, Page::Benchmarks);
+    let run = |ms| BenchRun { elapsed: std::time::Duration::from_millis(ms), first_chunk: None, tokens: 40 };
+    app.bench.results = vec![
+        BenchResult { name: "cloud".into(), ptype: "openai".into(), runs: vec![run(900), run(1100), run(1000)], ..Default::default() },
+        BenchResult { name: "laptop".into(), ptype: "ollama".into(), runs: vec![run(2500)], ..Default::default() },
+    ];
+    let mut terminal = Terminal::new(TestBackend::new(120, 20)).unwrap();
+    terminal.draw(|f| chi_tui::ui::ui(f, &app)).unwrap();
+    let text: String = terminal.backend().buffer().content().iter().map(|c| c.symbol()).collect();
+    assert!(text.contains("1.00s") && text.contains("0.90–1.10s"), "{}", text);
+    assert!(text.contains("Fastest: cloud"));
+    assert!(chi_tui::controller::controller(app.page).footer(&app).contains("r run"));
+}
+
+#[test]
+fn health_monitor_toggles_on_settings_and_dots_the_provider_lists() {
+    isolate();
+    use chi_tui::providers::{HealthMonitor, Reachability};
+    let mut app = new_app();
+    app.page = Page::Settings;
+    app.handle_key(key(KeyCode::Char('m')));
+    assert!(app.monitor.is_some());
+    assert_eq!(app.settings.monitor.enabled, Some(true));
+    app.handle_key(key(KeyCode::Char('m')));
+    assert!(app.monitor.is_none());
+    assert_eq!(app.settings.monitor.enabled, Some(false));
+
+    let mut monitor = HealthMonitor::new(std::time::Duration::from_secs(60));
+    monitor.record(&[Reachability {
+        id: "p1".to_string(),
+        name: "ollama".to_string(),
+        ptype: "ollama".to_string(),
+        endpoint: Some("http://127.0.0.1:11434".to_string()),
+        result: Err("nothing is listening".to_string()),
+        latency: Some(std::time::Duration::from_millis(1)),
+    }]);
+    app.monitor = Some(monitor);
+    app.page = Page::Configure;
+    app.providers = PageData::Ready(providers_with_one_ollama());
+    let mut terminal = Terminal::new(TestBackend::new(120, 20)).unwrap();
+    terminal.draw(|f| chi_tui::ui::ui(f, &app)).unwrap();
+    let text: String = terminal.backend().buffer().content().iter().map(|c| c.symbol()).collect();
+    assert!(text.contains("Configure Providers • health "), "{}", text);
+    assert!(text.contains("● › ollama"), "the dot leads the row: {}", text);
+}
+
+#[test]
+fn cli_log_page_shows_recorded_calls_with_secrets_masked() {
+    isolate();
+    use chi_tui::cli_log::{cli_calls, record_call};
+    let args = ["providers", "discover-models", "--api-key", "sk-live-123", "--json"];
+    record_call("chi-llm", &args, &[], &[], std::time::Duration::from_millis(1250), Some(2), Some("failed".into()), "boom: bad key");
+    let call = cli_calls().into_iter().find(|c| c.output == "boom: bad key").unwrap();
+    assert_eq!(call.command_line(), "chi-llm providers discover-models --api-key •••••• --json");
+
+    let mut app = new_app();
+    app.handle_key(key(KeyCode::Char('7')));
+    assert_eq!(app.page, Page::CliLog);
+    let mut terminal = Terminal::new(TestBackend::new(140, 40)).unwrap();
+    terminal.draw(|f| chi_tui::ui::ui(f, &app)).unwrap();
+    let text: String = terminal.backend().buffer().content().iter().map(|c| c.symbol()).collect();
+    assert!(text.contains("1.25s") && text.contains("exit 2"), "{}", text);
+    assert!(!text.contains("sk-live-123"));
+}
+
+#[test]
+fn settings_lists_python_environments_with_chi_llm() {
+    isolate();
+    use chi_tui::pyenv::{EnvKind, PyEnv};
+    let mut app = new_app();
+    app.page = Page::Settings;
+    app.pyenvs = PageData::Ready(vec![PyEnv {
+        kind: EnvKind::Uv,
+        python: "/work/.venv/bin/python".into(),
+        python_version: "3.12.1".to_string(),
+        chi_llm_version: "0.5.0".to_string(),
+    }]);
+    let mut terminal = Terminal::new(TestBackend::new(140, 50)).unwrap();
+    terminal.draw(|f| chi_tui::ui::ui(f, &app)).unwrap();
+    let text: String = terminal.backend().buffer().content().iter().map(|c| c.symbol()).collect();
+    assert!(text.contains("uv     /work/.venv/bin/python  (Python 3.12.1, chi_llm 0.5.0)"), "{}", text);
+    assert!(chi_tui::controller::controller(app.page).footer(&app).contains("e python environments"));
+}
+
+#[test]
+fn resumed_session_selects_the_provider_once_loaded() {
+    isolate();
+    use chi_tui::session::{apply_resume, resume, Session};
+
+    let mut app = new_app();
+    let saved = Session {
+        page: Some("configure".to_string()),
+        menu_idx: 2,
+        provider_id: Some("ollama-1".to_string()),
+        ..Session::default()
+    };
+    resume(&mut app, saved);
+    assert_eq!(app.page, Page::Configure);
+    assert_eq!(app.menu_idx, 2);
+
+    // Quitting before the list loads keeps the waiting provider
+    assert_eq!(Session::capture(&app).provider_id.as_deref(), Some("ollama-1"));
+
+    let mut st = providers_with_one_ollama();
+    let mut other = st.entries[0].clone();
+    other.id = "ollama-1".to_string();
+    st.entries.push(other);
+    app.providers = PageData::Ready(st);
+    apply_resume(&mut app);
+    assert_eq!(app.providers.ready().map(|st| st.selected), Some(1));
+    assert!(app.resume.provider_id.is_none());
+    assert_eq!(Session::capture(&app).page.as_deref(), Some("configure"));
+}
+
+#[test]
+fn resumed_menu_row_past_the_end_is_clamped() {
+    isolate();
+    use chi_tui::session::{resume, Session};
+
+    let mut app = new_app();
+    resume(&mut app, Session { menu_idx: 99, ..Session::default() });
+    assert_eq!(app.menu_idx, chi_tui::app::WELCOME_ITEMS.len() - 1);
+    app.handle_key(key(KeyCode::Enter));
+}
+
+#[test]
+fn errors_stack_as_toasts_and_ctrl_k_dismisses_the_newest() {
+    let mut app = new_app();
+    app.page = Page::Settings;
+    app.report_error("Export failed: disk full");
+    let _ = app.notify_tx.send(chi_tui::app::Notification::new("Saved providers to chi.tmp.json", true, Page::Configure));
+    app.poll_notifications();
+    assert_eq!(app.toasts.len(), 2);
+
+    let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+    terminal.draw(|f| chi_tui::ui::ui(f, &app)).unwrap();
+    let text: String = terminal.backend().buffer().content().iter().map(|c| c.symbol()).collect();
+    assert!(text.contains("Export failed: disk full"));
+    assert!(text.contains("Saved providers"));
+    assert!(text.contains("Ctrl+G: view"));
+
+    app.handle_key(KeyEvent::new(KeyCode::Char('k'), KeyModifiers::CONTROL));
+    assert_eq!(app.toasts.latest().map(|t| t.ok), Some(false));
+    app.handle_key(KeyEvent::new(KeyCode::Char('g'), KeyModifiers::CONTROL));
+    assert!(app.toasts.is_empty());
+    assert_eq!(app.page, Page::Settings);
+}
+
+#[test]
+fn pastes_reach_the_open_popup_in_one_piece() {
+    isolate();
+    use chi_tui::modal::{PromptModal, TextEditorModal};
+
+    let mut app = new_app();
+    let (typed_tx, typed) = std::sync::mpsc::channel();
+    app.modals.push(Box::new(PromptModal::new("API key", move |_, text| typed_tx.send(text).unwrap())));
+    app.handle_paste("sk-q\tt\r\n");
+    assert!(!app.should_quit);
+    app.handle_key(key(KeyCode::Enter));
+    assert!(app.modals.is_empty());
+    assert_eq!(typed.try_recv().as_deref(), Ok("sk-q t"));
+
+    let mut editor = TextEditorModal::new("Config", "{\n}", |_, _| Ok(()));
+    editor.col = 1;
+    editor.insert_text("\"a\": 1,\r\n\"b\": 2");
+    assert_eq!(editor.text(), "{\"a\": 1,\n\"b\": 2\n}");
+    assert_eq!((editor.row, editor.col), (1, 6));
+}
+
+#[test]
+fn file_picker_browses_into_directories_and_picks_a_gguf() {
+    isolate();
+    use chi_tui::file_picker::FilePickerModal;
+
+    let root = std::env::temp_dir().join(format!("chi-tui-pick-{}", std::process::id()));
+    std::fs::create_dir_all(root.join("qwen")).unwrap();
+    std::fs::write(root.join("qwen/qwen2.5-7b-q4.gguf"), b"gguf").unwrap();
+    std::fs::write(root.join("readme.txt"), b"x").unwrap();
+
+    let mut app = new_app();
+    let (picked_tx, picked) = std::sync::mpsc::channel();
+    let pick = move |_: &mut App, path: String| picked_tx.send(path).unwrap();
+    let picker = FilePickerModal::new("Choose model_path", &root.display().to_string(), pick.clone()).gguf_only();
+    assert_eq!(picker.entries.iter().map(|e| e.name.as_str()).collect::<Vec<_>>(), ["qwen"], "text files are filtered out");
+    app.modals.push(Box::new(picker));
+    let mut terminal = Terminal::new(TestBackend::new(120, 30)).unwrap();
+    terminal.draw(|f| chi_tui::ui::ui(f, &app)).unwrap();
+    find_on_screen(&terminal, "qwen/");
+
+    // Row 0 is `..`; the directory follows it
+    app.handle_key(key(KeyCode::Down));
+    app.handle_key(key(KeyCode::Enter));
+    app.handle_key(key(KeyCode::Down));
+    app.handle_key(key(KeyCode::Enter));
+    assert!(app.modals.is_empty());
+    assert_eq!(picked.try_recv(), Ok(root.join("qwen/qwen2.5-7b-q4.gguf").display().to_string()));
+
+    // A typed path with a variable reference is kept as typed
+    app.modals.push(Box::new(FilePickerModal::new("Choose model_path", "", pick)));
+    app.handle_key(key(KeyCode::Char('/')));
+    app.handle_paste("${MODELS}/phi.gguf");
+    app.handle_key(key(KeyCode::Enter));
+    assert_eq!(picked.try_recv().as_deref(), Ok("${MODELS}/phi.gguf"));
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn presets_add_a_filled_in_provider_under_a_free_name() {
+    let mut app = new_app();
+    app.page = Page::Configure;
+    app.providers = PageData::Ready(providers_with_one_ollama());
+    app.handle_key(key(KeyCode::Char('A')));
+    let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+    terminal.draw(|f| chi_tui::ui::ui(f, &app)).unwrap();
+    find_on_screen(&terminal, "Ollama on localhost (ollama)");
+    app.handle_key(key(KeyCode::Enter));
+
+    let st = app.providers.ready().unwrap();
+    let added = &st.entries[1];
+    assert_eq!((added.name.as_str(), st.selected), ("Ollama on localhost", 1));
+    // Only the fields the installed schema declares are filled in
+    assert_eq!(added.config, serde_json::json!({"type": "ollama", "host": "127.0.0.1"}));
+    assert_eq!(st.form.as_ref().unwrap().fields[0].buffer, "127.0.0.1");
+
+    let st = app.providers.ready_mut().unwrap();
+    let preset = chi_tui::providers::preset_by_label("Ollama on localhost (ollama)").unwrap();
+    assert_eq!(st.add_preset(preset).name, "Ollama on localhost 2");
+}
+
+#[test]
+fn ctrl_d_opens_the_default_switcher_from_any_page() {
+    let mut app = new_app();
+    app.page = Page::Benchmarks;
+    app.handle_key(KeyEvent::new(KeyCode::Char('d'), KeyModifiers::CONTROL));
+    assert_eq!(app.modals.len(), 1);
+    let mut terminal = Terminal::new(TestBackend::new(120, 30)).unwrap();
+    terminal.draw(|f| chi_tui::ui::ui(f, &app)).unwrap();
+    find_on_screen(&terminal, "Switch default provider");
+    find_on_screen(&terminal, "w: set and write .chi_llm.json");
+
+    // Nothing to choose: Enter keeps the popup, Esc closes it
+    app.handle_key(key(KeyCode::Enter));
+    assert_eq!(app.modals.len(), 1);
+    app.handle_key(key(KeyCode::Esc));
+    assert!(app.modals.is_empty());
+    assert_eq!(app.page, Page::Benchmarks);
+}
+
+#[test]
+fn select_default_shows_each_providers_model_and_reachability() {
+    isolate();
+    use chi_tui::providers::Reachability;
+
+    let mut app = new_app();
+    app.page = Page::SelectDefault;
+    let entry = |id: &str, ptype: &str, model: Option<&str>| ProviderEntry {
+        id: id.to_string(),
+        name: id.to_string(),
+        ptype: ptype.to_string(),
+        tags: Vec::new(),
+        disabled: false,
+        model: model.map(str::to_string),
+    };
+    let reach = |id: &str, result: Result<&str, &str>, ms| Reachability {
+        id: id.to_string(),
+        name: id.to_string(),
+        ptype: "ollama".to_string(),
+        endpoint: Some("http://127.0.0.1:11434".to_string()),
+        result: result.map(str::to_string).map_err(str::to_string),
+        latency: Some(std::time::Duration::from_millis(ms)),
+    };
+    app.defaultp = Some(DefaultProviderState {
+        providers: vec![entry("home", "ollama", Some("llama3.2")), entry("lab", "lmstudio", None)],
+        health: PageData::Ready(vec![reach("home", Ok("HTTP 200"), 12), reach("lab", Err("nothing is listening"), 3)]),
+        checked_at: Some(std::time::Instant::now()),
+        ..DefaultProviderState::default()
+    });
+    app.poll_background();
+    assert!(!app.defaultp.as_ref().unwrap().health.is_loading(), "fresh results are not checked again");
+    let mut terminal = Terminal::new(TestBackend::new(120, 30)).unwrap();
+    terminal.draw(|f| chi_tui::ui::ui(f, &app)).unwrap();
+    find_on_screen(&terminal, "home [ollama/llama3.2]  ✓ HTTP 200, 12 ms");
+    find_on_screen(&terminal, "lab [lmstudio]  ✗ nothing is listening");
+
+    // `r` checks again in the background
+    app.handle_key(key(KeyCode::Char('r')));
+    assert!(app.defaultp.as_ref().unwrap().health.is_loading());
+    terminal.draw(|f| chi_tui::ui::ui(f, &app)).unwrap();
+    find_on_screen(&terminal, "checking providers");
+}
+
+#[test]
+fn keychain_secret_reaches_the_built_config_as_a_reference() {
+    isolate();
+    keyring::set_default_credential_builder(keyring::mock::default_credential_builder());
+    let mut app = new_app();
+    app.page = Page::Configure;
+    let mut st = providers_with_one_ollama();
+    let api_key = FieldSchema { name: "api_key".to_string(), ftype: "secret".to_string(), required: false, default: None, help: None, options: None };
+    st.schema_map.insert("ollama".to_string(), vec![api_key]);
+    st.entries[0].config = serde_json::json!({"api_key": "sk-live"});
+    app.providers = PageData::Ready(st);
+
+    app.handle_key(key(KeyCode::Tab)); // focus form
+    for _ in 0..3 { app.handle_key(key(KeyCode::Down)); } // Name -> ID -> Type -> api_key
+    app.handle_key(key(KeyCode::Char('k')));
+
+    let entry = app.providers.ready().unwrap().selected_with_form().unwrap();
+    assert_eq!(entry.config["api_key"], "keyring:p1/api_key");
+    let scratch = serde_json::json!({"default_provider_id": "p1", "providers": [entry.to_json()]});
+    let (_, cfg) = chi_tui::build::active_provider_in(&scratch).unwrap();
+    // Build writes the reference (chi-llm looks it up), never the secret
+    assert_eq!(cfg.provider["api_key"], "keyring:p1/api_key");
+    assert!(cfg.plaintext_secrets(|_, k| k == "api_key").is_empty());
+    assert!(!cfg.to_json().to_string().contains("sk-live"));
+}
+
+#[test]
+fn build_summary_is_recomputed_when_a_setting_changes_not_every_frame() {
+    let mut app = new_app();
+    app.update(Msg::Goto(Page::Build));
+    // The second poll sees the config files' first check
+    app.poll_background();
+    app.poll_background();
+    let pending = app.build.as_mut().and_then(|b| b.pending.as_mut()).expect("computed on entry");
+    // Stand-in value: kept while nothing it depends on changes
+    pending.changes = Ok(Some(7));
+    let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+    for _ in 0..3 {
+        app.poll_background();
+        terminal.draw(|f| chi_tui::ui::ui(f, &app)).unwrap();
+    }
+    assert_eq!(app.build.as_ref().unwrap().pending.as_ref().unwrap().changes, Ok(Some(7)));
+
+    app.handle_key(key(KeyCode::Char('e')));
+    app.poll_background();
+    assert_ne!(app.build.as_ref().unwrap().pending.as_ref().unwrap().changes, Ok(Some(7)));
+}
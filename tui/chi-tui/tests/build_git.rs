@@ -0,0 +1,70 @@
+//! Build's git status, diff and commit against a real repository. The
+//! repository is the working directory, so this runs as its own test binary
+//! (its own process) and moves into a temporary directory first.
+
+use std::fs;
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+use chi_tui::build::{BuildState, BuildTarget, ConfigFormat};
+use chi_tui::git::FileStatus;
+use chi_tui::paths::{set_paths, AppPaths};
+use chi_tui::{App, Page};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+fn key(code: KeyCode) -> KeyEvent {
+    KeyEvent::new(code, KeyModifiers::NONE)
+}
+
+fn git(args: &[&str]) {
+    assert!(Command::new("git").args(args).output().unwrap().status.success(), "git {:?}", args);
+}
+
+/// Poll until the Build page's git status is `want`; git runs in jobs.
+fn wait_for_status(app: &mut App, want: FileStatus) {
+    let deadline = Instant::now() + Duration::from_secs(10);
+    loop {
+        app.poll_background();
+        if app.build.as_ref().and_then(|b| b.git.as_ref()) == Some(&want) && !app.jobs.is_busy() { return; }
+        assert!(Instant::now() < deadline, "git status stuck at {:?}", app.build.as_ref().and_then(|b| b.git.clone()));
+        std::thread::sleep(Duration::from_millis(20));
+    }
+}
+
+#[test]
+fn write_shows_the_diff_and_c_commits_in_the_background() {
+    let dir = std::env::temp_dir().join(format!("chi-tui-build-git-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    set_paths(AppPaths::under(&dir.join("xdg")));
+    std::env::set_current_dir(&dir).unwrap();
+    git(&["init", "-q"]);
+    git(&["config", "user.name", "chi-tui test"]);
+    git(&["config", "user.email", "test@example.com"]);
+    let scratch = serde_json::json!({"default_provider_id": "p1", "providers": [
+        {"id": "p1", "name": "laptop", "type": "ollama", "config": {"type": "ollama", "model": "llama3"}},
+    ]});
+    fs::write("chi.tmp.json", scratch.to_string()).unwrap();
+
+    let mut app = App::new();
+    app.page = Page::Build;
+    app.build = Some(BuildState { target: BuildTarget::Project, format: ConfigFormat::Json, ..Default::default() });
+    wait_for_status(&mut app, FileStatus::Untracked);
+
+    // Preview, then write: status and diff arrive from a job
+    app.handle_key(key(KeyCode::Enter));
+    app.handle_key(key(KeyCode::Enter));
+    assert!(app.modals.is_empty());
+    wait_for_status(&mut app, FileStatus::Untracked);
+    let diff = app.build.as_ref().and_then(|b| b.diff.clone()).unwrap_or_default();
+    assert!(diff.contains("+") && diff.contains("llama3"), "{}", diff);
+
+    // c asks, then commits off the UI thread
+    app.handle_key(key(KeyCode::Char('c')));
+    assert_eq!(app.modals.len(), 1);
+    app.handle_key(key(KeyCode::Char('y')));
+    wait_for_status(&mut app, FileStatus::Tracked { modified: false });
+    let st = app.build.as_ref().unwrap();
+    assert!(st.status.as_deref().is_some_and(|s| s.starts_with("Committed .chi_llm.json")), "{:?}", st.status);
+    assert!(st.diff.is_none());
+    fs::remove_dir_all(&dir).unwrap();
+}
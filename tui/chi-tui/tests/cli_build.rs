@@ -0,0 +1,38 @@
+//! `chi-tui build` against real files and a real `settings.toml`. It moves into
+//! a temporary directory and points the XDG directories there, so it runs as
+//! its own test binary (its own process).
+
+use std::fs;
+
+use chi_tui::build::{BuildTarget, ConfigFormat};
+use chi_tui::cli::{run_command, Command};
+
+#[test]
+fn build_checks_for_plaintext_secrets_and_waits_for_its_hooks() {
+    let dir = std::env::temp_dir().join(format!("chi-tui-cli-build-{}", std::process::id()));
+    fs::create_dir_all(dir.join("chi-tui")).unwrap();
+    std::env::set_current_dir(&dir).unwrap();
+    for var in ["XDG_CONFIG_HOME", "XDG_CACHE_HOME", "XDG_STATE_HOME", "XDG_DATA_HOME", "HOME"] {
+        std::env::set_var(var, &dir);
+    }
+    fs::write(dir.join("chi-tui").join("settings.toml"), "[hooks]\nafter_write = [\"echo \\\"$CHI_TUI_FILE\\\" > hook.out\"]\n").unwrap();
+    let scratch = serde_json::json!({"default_provider_id": "p1", "providers": [
+        {"id": "p1", "name": "cloud", "type": "openai", "config": {"type": "openai", "model": "gpt-4o-mini", "api_key": "sk-test"}},
+    ]});
+    fs::write("chi.tmp.json", scratch.to_string()).unwrap();
+
+    // Plaintext key in a file git does not ignore: the page would ask, the CLI refuses
+    let err = run_command(Command::Build { target: BuildTarget::Project, env_refs: false, format: ConfigFormat::Json }).unwrap_err();
+    assert!(err.to_string().contains("plaintext secrets"), "{}", err);
+    assert!(!dir.join(".chi_llm.json").exists());
+    assert!(!dir.join("hook.out").exists());
+
+    // With ${ENV} references it writes, and the hook has run by the time it returns
+    let out = run_command(Command::Build { target: BuildTarget::Project, env_refs: true, format: ConfigFormat::Json }).unwrap();
+    assert_eq!(out["written"], ".chi_llm.json");
+    assert_eq!(out["exports"].as_array().map(Vec::len), Some(1));
+    let written = fs::read_to_string(dir.join(".chi_llm.json")).unwrap();
+    assert!(!written.contains("sk-test"));
+    assert_eq!(fs::read_to_string(dir.join("hook.out")).unwrap().trim(), ".chi_llm.json");
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,47 @@
+//! `chi-tui set-default` against a real `chi.tmp.json`, which lives in the
+//! working directory, so this runs as its own test binary (its own process) and
+//! moves into a temporary directory first.
+
+use std::fs;
+
+use chi_tui::cli::{run_command, Command};
+use chi_tui::paths::{set_paths, AppPaths};
+
+fn scratch_default() -> String {
+    let v: serde_json::Value = serde_json::from_str(&fs::read_to_string("chi.tmp.json").unwrap()).unwrap();
+    v["default_provider_id"].as_str().unwrap_or_default().to_string()
+}
+
+#[test]
+fn set_default_switches_to_an_enabled_provider_only() {
+    let dir = std::env::temp_dir().join(format!("chi-tui-cli-set-default-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    set_paths(AppPaths::under(&dir.join("xdg")));
+    std::env::set_current_dir(&dir).unwrap();
+    let scratch = serde_json::json!({"default_provider_id": "p1", "providers": [
+        {"id": "p1", "name": "laptop", "type": "ollama", "config": {"type": "ollama", "model": "llama3"}},
+        {"id": "p2", "name": "studio", "type": "lmstudio", "config": {"type": "lmstudio", "model": "qwen"}},
+        {"id": "p3", "name": "old", "type": "ollama", "disabled": true, "config": {"type": "ollama"}},
+    ]});
+    fs::write("chi.tmp.json", scratch.to_string()).unwrap();
+
+    let out = run_command(Command::SetDefault { id: "p2".to_string() }).unwrap();
+    assert_eq!(out, serde_json::json!({"ok": true, "default_provider_id": "p2"}));
+    assert_eq!(scratch_default(), "p2");
+
+    let err = run_command(Command::SetDefault { id: "p9".to_string() }).unwrap_err();
+    assert_eq!(err.to_string(), "no provider with id 'p9' in chi.tmp.json");
+    let err = run_command(Command::SetDefault { id: "p3".to_string() }).unwrap_err();
+    assert_eq!(err.to_string(), "provider 'p3' is disabled");
+    assert_eq!(scratch_default(), "p2", "a refused id leaves the default alone");
+
+    // The binary prints one JSON object and fails with exit code 1
+    let out = std::process::Command::new(env!("CARGO_BIN_EXE_chi-tui")).args(["set-default", "p9"]).output().unwrap();
+    assert_eq!(out.status.code(), Some(1));
+    let printed: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(printed, serde_json::json!({"ok": false, "error": "no provider with id 'p9' in chi.tmp.json"}));
+    let out = std::process::Command::new(env!("CARGO_BIN_EXE_chi-tui")).args(["set-default", "p1"]).output().unwrap();
+    assert!(out.status.success());
+    assert_eq!(scratch_default(), "p1");
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,70 @@
+//! Registering a custom model while the `chi-llm serve --stdio` session is up.
+//! The stand-in `chi-llm` reads its catalog once at start, like chi-llm's
+//! `MODELS`; it and the XDG directories are process-wide, so this runs as its
+//! own test binary (its own process).
+
+#![cfg(unix)]
+
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::sync::atomic::AtomicBool;
+use std::time::Duration;
+
+use chi_tui::custom_models::add_custom_models;
+use chi_tui::models::fetch_models;
+use chi_tui::util::{set_chi_llm_command, ChiLlmCommand};
+
+/// `serve --stdio` answering `models list` from the catalog as it was at start,
+/// and `models add` by appending the file's stem to the catalog.
+fn fake_chi_llm(dir: &std::path::Path) -> String {
+    let catalog = dir.join("catalog");
+    fs::write(&catalog, "qwen3-1.7b\n").unwrap();
+    let script = format!(
+        r#"#!/bin/sh
+[ "$1" = serve ] || exit 2
+items=$(sed 's/.*/{{\\"id\\":\\"&\\"}}/' '{catalog}' | paste -sd, -)
+while read -r line; do
+  id=$(echo "$line" | sed 's/.*"id":\([0-9]*\).*/\1/')
+  case "$line" in
+    *'"version"'*) result='{{"protocol":1}}' ;;
+    *'"add"'*)
+      file=$(echo "$line" | sed 's/.*"add","\([^"]*\)".*/\1/')
+      basename "$file" .gguf >> '{catalog}'
+      result='{{"exit_code":0,"stdout":"{{\"added\":[]}}","stderr":""}}' ;;
+    *) result='{{"exit_code":0,"stdout":"['"$items"']","stderr":""}}' ;;
+  esac
+  echo '{{"jsonrpc":"2.0","id":'"$id"',"result":'"$result"'}}'
+done
+"#,
+        catalog = catalog.display()
+    );
+    let path = dir.join("chi-llm");
+    fs::write(&path, script).unwrap();
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+    path.display().to_string()
+}
+
+fn listed_ids() -> Vec<String> {
+    let mb = fetch_models(Duration::from_secs(10), &AtomicBool::new(false)).unwrap();
+    mb.entries.iter().map(|e| e.id.clone()).collect()
+}
+
+#[test]
+fn an_added_model_is_listed_by_the_running_session() {
+    let dir = std::env::temp_dir().join(format!("chi-tui-custom-session-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    for var in ["XDG_CONFIG_HOME", "XDG_CACHE_HOME", "XDG_STATE_HOME", "XDG_DATA_HOME", "HOME"] {
+        std::env::set_var(var, &dir);
+    }
+    std::env::set_var("CHI_LLM_MODELS_YAML", dir.join("models.yaml"));
+    set_chi_llm_command(ChiLlmCommand::new(&fake_chi_llm(&dir)));
+
+    // The session starts here, with the catalog as it is now
+    assert_eq!(listed_ids(), ["qwen3-1.7b"]);
+
+    let gguf = dir.join("tiny.gguf");
+    fs::write(&gguf, b"GGUF").unwrap();
+    add_custom_models(&gguf).unwrap();
+    assert_eq!(listed_ids(), ["qwen3-1.7b", "tiny"]);
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,91 @@
+//! The startup health check of the default provider, run for real: it reads
+//! `chi.tmp.json` from the working directory and calls a stand-in `chi-llm`,
+//! so this runs as its own test binary (its own process) and moves into a
+//! temporary directory first.
+
+#![cfg(unix)]
+
+use std::fs;
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::os::unix::fs::PermissionsExt;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use chi_tui::paths::{set_paths, AppPaths};
+use chi_tui::providers::HealthStatus;
+use chi_tui::util::{set_chi_llm_command, ChiLlmCommand};
+use chi_tui::App;
+use ratatui::backend::TestBackend;
+use ratatui::Terminal;
+
+/// An Ollama stand-in answering the port check.
+fn fake_ollama() -> u16 {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    thread::spawn(move || {
+        for mut s in listener.incoming().map_while(Result::ok) {
+            let mut buf = [0u8; 512];
+            let _ = s.read(&mut buf);
+            let _ = s.write_all(b"HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\r\n{\"version\":\"0.3.0\"}");
+        }
+    });
+    port
+}
+
+/// Run the check and wait for its result.
+fn check(app: &mut App) {
+    app.start_health_check();
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while app.health_job.is_some() {
+        assert!(Instant::now() < deadline, "health check never finished");
+        thread::sleep(Duration::from_millis(10));
+        app.poll_background();
+    }
+}
+
+#[test]
+fn startup_check_tests_the_default_provider() {
+    let dir = std::env::temp_dir().join(format!("chi-tui-health-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    set_paths(AppPaths::under(&dir.join("xdg")));
+    std::env::set_current_dir(&dir).unwrap();
+    let chi_llm = dir.join("chi-llm");
+    fs::write(&chi_llm, "#!/bin/sh\necho '{\"models\": [{\"id\": \"llama3\", \"downloaded\": true}, {\"id\": \"qwen\"}]}'\n").unwrap();
+    fs::set_permissions(&chi_llm, fs::Permissions::from_mode(0o755)).unwrap();
+    set_chi_llm_command(ChiLlmCommand::new(chi_llm.to_str().unwrap()));
+    let closed = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+    let scratch = |default: &str| serde_json::json!({"default_provider_id": default, "providers": [
+        {"id": "p1", "name": "laptop", "type": "ollama", "config": {"type": "ollama", "port": fake_ollama()}},
+        {"id": "p2", "name": "stopped", "type": "ollama", "config": {"type": "ollama", "port": closed}},
+        {"id": "p3", "name": "old", "type": "ollama", "disabled": true, "config": {"type": "ollama"}},
+    ]}).to_string();
+
+    // Reachable: the discovery call answers and the header says so
+    fs::write("chi.tmp.json", scratch("p1")).unwrap();
+    let mut app = App::new();
+    app.start_health_check();
+    assert_eq!(app.health.as_ref().map(|h| h.status), Some(HealthStatus::Checking));
+    check(&mut app);
+    let health = app.health.clone().unwrap();
+    assert_eq!((health.status, health.detail.as_str()), (HealthStatus::Ok, "ollama: 2 models"));
+    let mut terminal = Terminal::new(TestBackend::new(120, 30)).unwrap();
+    terminal.draw(|f| chi_tui::ui::ui(f, &app)).unwrap();
+    let text: String = terminal.backend().buffer().content().iter().map(|c| c.symbol()).collect();
+    assert!(text.contains("● laptop: ok"));
+
+    // Nothing on the port: failing, with a toast saying why
+    fs::write("chi.tmp.json", scratch("p2")).unwrap();
+    check(&mut app);
+    let health = app.health.clone().unwrap();
+    assert_eq!(health.status, HealthStatus::Failed);
+    assert!(health.detail.contains("nothing is listening"), "{}", health.detail);
+    assert!(app.toasts.latest().is_some_and(|t| !t.ok && t.message.starts_with("Default provider stopped: ")));
+
+    // Disabled: not checked at all
+    fs::write("chi.tmp.json", scratch("p3")).unwrap();
+    app.start_health_check();
+    assert!(app.health_job.is_none());
+    assert_eq!(app.health.as_ref().map(|h| (h.status, h.detail.as_str())), Some((HealthStatus::Unchecked, "disabled")));
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,57 @@
+//! Configure's offer to import the existing chi-llm config, from a real
+//! project directory and a stand-in `chi-llm`: the config and `chi.tmp.json`
+//! live in the working directory, so this runs as its own test binary (its own
+//! process) and moves into a temporary directory first.
+
+#![cfg(unix)]
+
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::time::{Duration, Instant};
+
+use chi_tui::util::{set_chi_llm_command, ChiLlmCommand};
+use chi_tui::{App, Page};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+#[test]
+fn an_empty_catalog_offers_the_project_config_once() {
+    let dir = std::env::temp_dir().join(format!("chi-tui-import-offer-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    std::env::set_current_dir(&dir).unwrap();
+    for var in ["XDG_CONFIG_HOME", "XDG_CACHE_HOME", "XDG_STATE_HOME", "XDG_DATA_HOME", "HOME"] {
+        std::env::set_var(var, &dir);
+    }
+    let chi_llm = dir.join("chi-llm");
+    fs::write(&chi_llm, "#!/bin/sh\necho '{\"providers\": [{\"type\": \"ollama\", \"fields\": [{\"name\": \"model\", \"type\": \"string\"}]}]}'\n").unwrap();
+    fs::set_permissions(&chi_llm, fs::Permissions::from_mode(0o755)).unwrap();
+    set_chi_llm_command(ChiLlmCommand::new(chi_llm.to_str().unwrap()));
+    fs::write("chi.tmp.json", r#"{"providers": []}"#).unwrap();
+    fs::write(".chi_llm.json", r#"{"provider": {"type": "ollama", "model": "llama3.2"}}"#).unwrap();
+
+    // Opening Configure loads the empty catalog and offers the config
+    let mut app = App::new();
+    app.page = Page::Configure;
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while app.modals.is_empty() {
+        assert!(Instant::now() < deadline, "no import offer");
+        std::thread::sleep(Duration::from_millis(10));
+        app.poll_background();
+    }
+    let mut terminal = ratatui::Terminal::new(ratatui::backend::TestBackend::new(120, 30)).unwrap();
+    terminal.draw(|f| chi_tui::ui::ui(f, &app)).unwrap();
+    let text: String = terminal.backend().buffer().content().iter().map(|c| c.symbol()).collect();
+    assert!(text.contains("Import existing config") && text.contains("import .chi_llm.json"), "{}", text);
+
+    // 1 imports it as the default provider
+    app.handle_key(KeyEvent::new(KeyCode::Char('1'), KeyModifiers::NONE));
+    assert!(app.modals.is_empty());
+    let scratch: serde_json::Value = serde_json::from_str(&fs::read_to_string("chi.tmp.json").unwrap()).unwrap();
+    assert_eq!(scratch["providers"][0]["config"]["model"], "llama3.2");
+    assert_eq!(scratch["default_provider_id"], scratch["providers"][0]["id"]);
+
+    // Not offered again on the next ticks
+    app.poll_background();
+    app.poll_background();
+    assert!(app.modals.is_empty());
+    fs::remove_dir_all(&dir).unwrap();
+}
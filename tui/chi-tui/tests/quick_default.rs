@@ -0,0 +1,62 @@
+//! Ctrl+D against real files. `chi.tmp.json` and `.chi_llm.json` live in the
+//! working directory, so this runs as its own test binary (its own process)
+//! and moves into a temporary directory first.
+
+use std::fs;
+
+use chi_tui::build::{BuildState, BuildTarget, ConfigFormat};
+use chi_tui::paths::{set_paths, AppPaths};
+use chi_tui::{App, Page};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+fn key(code: KeyCode) -> KeyEvent {
+    KeyEvent::new(code, KeyModifiers::NONE)
+}
+
+fn ctrl_d() -> KeyEvent {
+    KeyEvent::new(KeyCode::Char('d'), KeyModifiers::CONTROL)
+}
+
+fn scratch_default() -> String {
+    let v: serde_json::Value = serde_json::from_str(&fs::read_to_string("chi.tmp.json").unwrap()).unwrap();
+    v["default_provider_id"].as_str().unwrap_or_default().to_string()
+}
+
+#[test]
+fn enter_sets_the_default_and_w_writes_it_without_touching_build() {
+    let dir = std::env::temp_dir().join(format!("chi-tui-quick-default-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    set_paths(AppPaths::under(&dir.join("xdg")));
+    std::env::set_current_dir(&dir).unwrap();
+    let scratch = serde_json::json!({"default_provider_id": "p1", "providers": [
+        {"id": "p1", "name": "laptop", "type": "ollama", "config": {"type": "ollama", "model": "llama3"}},
+        {"id": "p2", "name": "studio", "type": "lmstudio", "config": {"type": "lmstudio", "model": "qwen"}},
+    ]});
+    fs::write("chi.tmp.json", scratch.to_string()).unwrap();
+
+    let mut app = App::new();
+    app.page = Page::Benchmarks;
+    app.build = Some(BuildState { target: BuildTarget::Global, format: ConfigFormat::Yaml, ..Default::default() });
+
+    // Enter: default only
+    app.handle_key(ctrl_d());
+    app.handle_key(key(KeyCode::Down));
+    app.handle_key(key(KeyCode::Enter));
+    assert!(app.modals.is_empty());
+    assert_eq!(scratch_default(), "p2");
+    assert!(!dir.join(".chi_llm.json").exists());
+
+    // w: default and the project config (no config yet, so .chi_llm.json)
+    app.handle_key(ctrl_d());
+    app.handle_key(key(KeyCode::Up));
+    app.handle_key(key(KeyCode::Char('w')));
+    assert_eq!(scratch_default(), "p1");
+    let written: serde_json::Value = serde_json::from_str(&fs::read_to_string(dir.join(".chi_llm.json")).unwrap()).unwrap();
+    assert_eq!(written["provider"]["type"], "ollama");
+    assert_eq!(written["provider"]["model"], "llama3");
+
+    // The Build page keeps its own choices
+    let build = app.build.as_ref().unwrap();
+    assert_eq!((build.target, build.format), (BuildTarget::Global, ConfigFormat::Yaml));
+    fs::remove_dir_all(&dir).unwrap();
+}
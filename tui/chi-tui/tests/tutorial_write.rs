@@ -0,0 +1,47 @@
+//! The tutorial's last step against a real project directory: `.chi_llm.json`
+//! lives in the working directory, so this runs as its own test binary (its
+//! own process) and moves into a temporary directory first.
+
+use std::fs;
+
+use chi_tui::paths::{set_paths, AppPaths};
+use chi_tui::tutorial::{self, Tutorial};
+use chi_tui::{App, Page};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+fn key(code: KeyCode) -> KeyEvent {
+    KeyEvent::new(code, KeyModifiers::NONE)
+}
+
+#[test]
+fn write_step_previews_then_enter_writes() {
+    let dir = std::env::temp_dir().join(format!("chi-tui-tutorial-write-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    set_paths(AppPaths::under(&dir.join("xdg")));
+    std::env::set_current_dir(&dir).unwrap();
+    let scratch = serde_json::json!({"default_provider_id": "p1", "providers": [
+        {"id": "p1", "name": "laptop", "type": "ollama", "config": {"type": "ollama", "model": "llama3"}},
+    ]});
+    fs::write("chi.tmp.json", scratch.to_string()).unwrap();
+
+    let mut app = App::new();
+    tutorial::start(&mut app);
+    while app.tutorial.as_ref().and_then(Tutorial::current).is_some_and(|s| s.title != "Write the config") {
+        tutorial::advance(&mut app);
+    }
+    assert_eq!(app.page, Page::Build);
+
+    // Enter only previews
+    app.handle_key(key(KeyCode::Enter));
+    assert_eq!(app.modals.len(), 1);
+    assert!(!dir.join(".chi_llm.json").exists());
+    assert!(app.tutorial.is_some());
+
+    // Enter in the preview writes and finishes the tutorial
+    app.handle_key(key(KeyCode::Enter));
+    assert!(app.modals.is_empty());
+    let written: serde_json::Value = serde_json::from_str(&fs::read_to_string(dir.join(".chi_llm.json")).unwrap()).unwrap();
+    assert_eq!(written["provider"]["model"], "llama3");
+    assert!(app.tutorial.is_none());
+    fs::remove_dir_all(&dir).unwrap();
+}